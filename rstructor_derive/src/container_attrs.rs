@@ -4,12 +4,19 @@ pub struct ContainerAttributes {
     /// Description of the struct or enum
     pub description: Option<String>,
 
+    /// `(locale, text)` translations of the description, from
+    /// `#[llm(description(en = "..", de = ".."))]`
+    pub description_translations: Vec<(String, String)>,
+
     /// Custom title for the schema (overrides the default type name)
     pub title: Option<String>,
 
     /// Examples of valid instances (as tokenstreams)
     pub examples: Vec<proc_macro2::TokenStream>,
 
+    /// Outputs the model must not imitate (as tokenstreams)
+    pub counter_examples: Vec<proc_macro2::TokenStream>,
+
     /// Serde rename_all case style (from serde attribute)
     pub serde_rename_all: Option<String>,
 
@@ -24,19 +31,30 @@ pub struct ContainerAttributes {
 
     /// Whether the enum is untagged
     pub serde_untagged: bool,
+
+    /// Whether `schema()` rebuilds the schema on every call
+    /// (`#[llm(no_schema_cache)]`) instead of caching it
+    pub no_schema_cache: bool,
+
+    /// Keywords merged into the root schema, from `#[llm(extra_schema = "..")]`
+    pub extra_schema: Option<String>,
 }
 
 /// Builder for constructing ContainerAttributes
 #[derive(Default)]
 pub struct ContainerAttributesBuilder {
     description: Option<String>,
+    description_translations: Vec<(String, String)>,
     title: Option<String>,
     examples: Vec<proc_macro2::TokenStream>,
+    counter_examples: Vec<proc_macro2::TokenStream>,
     serde_rename_all: Option<String>,
     validate: Option<String>,
     serde_tag: Option<String>,
     serde_content: Option<String>,
     serde_untagged: bool,
+    no_schema_cache: bool,
+    extra_schema: Option<String>,
 }
 
 impl ContainerAttributesBuilder {
@@ -45,6 +63,11 @@ impl ContainerAttributesBuilder {
         self
     }
 
+    pub fn description_translations(mut self, translations: Vec<(String, String)>) -> Self {
+        self.description_translations = translations;
+        self
+    }
+
     pub fn title(mut self, title: Option<String>) -> Self {
         self.title = title;
         self
@@ -55,6 +78,11 @@ impl ContainerAttributesBuilder {
         self
     }
 
+    pub fn counter_examples(mut self, counter_examples: Vec<proc_macro2::TokenStream>) -> Self {
+        self.counter_examples = counter_examples;
+        self
+    }
+
     pub fn serde_rename_all(mut self, rename_all: Option<String>) -> Self {
         self.serde_rename_all = rename_all;
         self
@@ -80,16 +108,30 @@ impl ContainerAttributesBuilder {
         self
     }
 
+    pub fn no_schema_cache(mut self, no_schema_cache: bool) -> Self {
+        self.no_schema_cache = no_schema_cache;
+        self
+    }
+
+    pub fn extra_schema(mut self, extra_schema: Option<String>) -> Self {
+        self.extra_schema = extra_schema;
+        self
+    }
+
     pub fn build(self) -> ContainerAttributes {
         ContainerAttributes {
             description: self.description,
+            description_translations: self.description_translations,
             title: self.title,
             examples: self.examples,
+            counter_examples: self.counter_examples,
             serde_rename_all: self.serde_rename_all,
             validate: self.validate,
             serde_tag: self.serde_tag,
             serde_content: self.serde_content,
             serde_untagged: self.serde_untagged,
+            no_schema_cache: self.no_schema_cache,
+            extra_schema: self.extra_schema,
         }
     }
 }
@@ -105,12 +147,29 @@ impl ContainerAttributes {
     #[allow(dead_code)]
     pub fn is_empty(&self) -> bool {
         self.description.is_none()
+            && self.description_translations.is_empty()
             && self.title.is_none()
             && self.examples.is_empty()
+            && self.counter_examples.is_empty()
             && self.serde_rename_all.is_none()
             && self.validate.is_none()
             && self.serde_tag.is_none()
             && self.serde_content.is_none()
             && !self.serde_untagged
+            && !self.no_schema_cache
+            && self.extra_schema.is_none()
+    }
+
+    /// Statement storing the counter-examples under `x-counter-examples`,
+    /// where prompt-based strategies read them; `None` if there are none.
+    pub fn counter_examples_setter(&self) -> Option<proc_macro2::TokenStream> {
+        if self.counter_examples.is_empty() {
+            return None;
+        }
+        let counter_examples = &self.counter_examples;
+        Some(quote::quote! {
+            schema_obj["x-counter-examples"] =
+                ::serde_json::Value::Array(vec![#(#counter_examples),*]);
+        })
     }
 }
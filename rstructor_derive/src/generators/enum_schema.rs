@@ -4,12 +4,13 @@ use syn::{DataEnum, Fields, Ident, Type};
 
 use crate::container_attrs::ContainerAttributes;
 use crate::generators::struct_schema::apply_rename_all;
-use crate::parsers::field_parser::parse_field_attributes;
+use crate::generators::type_schema::{generate_nested_type_schema, generate_type_schema};
+use crate::parsers::field_parser::{ordered_fields, parse_field_attributes};
 use crate::parsers::variant_parser::parse_variant_attributes;
 use crate::type_utils::{
-    get_array_inner_type, get_box_inner_type, get_map_types, get_option_inner_type,
-    get_schema_type_from_rust_type, get_tuple_element_types, get_type_name, is_array_type,
-    is_box_type, is_json_value_type, is_map_type, is_option_type, is_tuple_type,
+    get_box_inner_type, get_schema_type_from_rust_type, get_tuple_element_types, get_type_name,
+    is_array_type, is_box_type, is_json_value_type, is_map_type, is_option_type, is_tuple_type,
+    strip_transparent_wrappers,
 };
 
 /// Generate the schema implementation for an enum
@@ -51,22 +52,57 @@ fn generate_simple_enum_schema(
     generics: &syn::Generics,
 ) -> TokenStream {
     // Generate implementation for simple enum with serde rename support
-    let variant_values: Vec<_> = data_enum
+    let (variant_values, variant_descriptions): (Vec<_>, Vec<_>) = data_enum
         .variants
         .iter()
         .map(|v| {
             let attrs = parse_variant_attributes(v);
             let original_name = v.ident.to_string();
             // Priority: 1) variant #[serde(rename)], 2) container #[serde(rename_all)], 3) original name
-            if let Some(ref rename) = attrs.serde_rename {
+            let value = if let Some(ref rename) = attrs.serde_rename {
                 rename.clone()
             } else if let Some(ref rename_all) = container_attrs.serde_rename_all {
                 apply_rename_all(&original_name, rename_all)
             } else {
                 original_name
-            }
+            };
+            (value, attrs.description)
         })
-        .collect();
+        .unzip();
+
+    // With variant descriptions, emit a oneOf of `const` values so each label
+    // carries its meaning; otherwise a plain `enum` array
+    let values_schema =
+        if variant_descriptions.iter().any(Option::is_some) {
+            let branches = variant_values.iter().zip(&variant_descriptions).map(
+                |(value, description)| match description {
+                    Some(desc) => {
+                        quote! { ::serde_json::json!({ "const": #value, "description": #desc }) }
+                    }
+                    None => quote! { ::serde_json::json!({ "const": #value }) },
+                },
+            );
+            quote! {
+                let mut schema_obj = ::serde_json::json!({
+                    "type": "string",
+                    "oneOf": vec![#(#branches),*],
+                    "title": stringify!(#name)
+                });
+            }
+        } else {
+            quote! {
+                // Create array of enum values
+                let enum_values = vec![
+                    #(::serde_json::Value::String(#variant_values.to_string())),*
+                ];
+
+                let mut schema_obj = ::serde_json::json!({
+                    "type": "string",
+                    "enum": enum_values,
+                    "title": stringify!(#name)
+                });
+            }
+        };
 
     // Handle container attributes
     let mut container_setters = Vec::new();
@@ -96,6 +132,8 @@ fn generate_simple_enum_schema(
         });
     }
 
+    container_setters.extend(container_attrs.counter_examples_setter());
+
     // Combine all container attribute setters
     let container_setter = if !container_setters.is_empty() {
         quote! {
@@ -111,16 +149,7 @@ fn generate_simple_enum_schema(
     quote! {
         impl #impl_generics ::rstructor::schema::SchemaType for #name #ty_generics #where_clause {
             fn schema() -> ::rstructor::schema::Schema {
-                // Create array of enum values
-                let enum_values = vec![
-                    #(::serde_json::Value::String(#variant_values.to_string())),*
-                ];
-
-                let mut schema_obj = ::serde_json::json!({
-                    "type": "string",
-                    "enum": enum_values,
-                    "title": stringify!(#name)
-                });
+                #values_schema
 
                 // Add container attributes if available
                 #container_setter
@@ -226,7 +255,7 @@ fn generate_externally_tagged_enum_schema(
                     let field = fields.unnamed.first().unwrap();
 
                     // Extract field schema based on its type
-                    let field_schema = generate_field_schema(&field.ty, &None);
+                    let field_schema = generate_field_schema(field, &None);
                     let variant_name_str = variant_name.clone();
                     let description_str = description.clone();
 
@@ -255,7 +284,7 @@ fn generate_externally_tagged_enum_schema(
                     let mut field_schemas = Vec::new();
 
                     for field in fields.unnamed.iter() {
-                        let field_schema = generate_field_schema(&field.ty, &None);
+                        let field_schema = generate_field_schema(field, &None);
                         field_schemas.push(field_schema);
                     }
 
@@ -300,7 +329,7 @@ fn generate_externally_tagged_enum_schema(
                 let mut prop_setters = Vec::new();
                 let mut required_fields = Vec::new();
 
-                for field in &fields.named {
+                for field in ordered_fields(&fields.named) {
                     if let Some(field_ident) = &field.ident {
                         let original_field_name = field_ident.to_string();
                         let field_attrs = parse_field_attributes(field);
@@ -317,7 +346,7 @@ fn generate_externally_tagged_enum_schema(
                             .unwrap_or_else(|| format!("Field {}", field_name_str));
 
                         let is_optional = is_option_type(&field.ty);
-                        let field_schema = generate_field_schema(&field.ty, &Some(field_desc));
+                        let field_schema = generate_field_schema(field, &Some(field_desc));
 
                         let field_name_str_owned = field_name_str.clone();
                         prop_setters.push(quote! {
@@ -407,6 +436,8 @@ fn generate_externally_tagged_enum_schema(
         });
     }
 
+    container_setters.extend(container_attrs.counter_examples_setter());
+
     // Combine all container attribute setters
     let container_setter = if !container_setters.is_empty() {
         quote! {
@@ -446,15 +477,57 @@ fn generate_externally_tagged_enum_schema(
     }
 }
 
-/// Generate schema for a field based on its type
-fn generate_field_schema(field_type: &Type, description: &Option<String>) -> TokenStream {
-    let schema_type = get_schema_type_from_rust_type(field_type);
-    let is_optional = is_option_type(field_type);
-    let actual_type = if is_optional {
-        get_option_inner_type(field_type)
-    } else {
-        field_type
+/// Generate schema for a field, with its `#[llm(extra_schema = "..")]`
+/// keywords merged in
+fn generate_field_schema(field: &syn::Field, description: &Option<String>) -> TokenStream {
+    let schema = generate_base_field_schema(field, description);
+    let Some(extra) = parse_field_attributes(field).extra_schema else {
+        return schema;
+    };
+    quote! {
+        {
+            let mut schema = #schema;
+            if let ::serde_json::Value::Object(map) = &mut schema {
+                ::rstructor::schema::__private::merge_extra_schema(map, #extra);
+            }
+            schema
+        }
+    }
+}
+
+/// Generate schema for a field based on its type, or on its
+/// `#[llm(schema_with = "path")]` function or wire format
+fn generate_base_field_schema(field: &syn::Field, description: &Option<String>) -> TokenStream {
+    let attrs = parse_field_attributes(field);
+    // A recognized serde helper's own description beats the generic default
+    let description = match &attrs.wire {
+        Some(wire) if wire.description.is_some() && attrs.description.is_none() => &None,
+        _ => description,
     };
+    let custom_schema = match (attrs.schema_with, attrs.wire) {
+        (Some(schema_fn), _) => Some(quote! { #schema_fn().to_json() }),
+        (None, Some(wire)) => Some(wire.schema()),
+        (None, None) => None,
+    };
+    if let Some(custom_schema) = custom_schema {
+        let Some(desc) = description else {
+            return custom_schema;
+        };
+        return quote! {
+            {
+                let mut schema = #custom_schema;
+                if let ::serde_json::Value::Object(map) = &mut schema {
+                    map.insert("description".to_string(), ::serde_json::Value::String(#desc.to_string()));
+                }
+                schema
+            }
+        };
+    }
+    let field_type = &field.ty;
+
+    // Option and Box are transparent in the schema, so classify what they wrap
+    let actual_type = strip_transparent_wrappers(field_type);
+    let schema_type = get_schema_type_from_rust_type(actual_type);
 
     let desc_prop = if let Some(desc) = description {
         quote! { "description": #desc, }
@@ -471,43 +544,27 @@ fn generate_field_schema(field_type: &Type, description: &Option<String>) -> Tok
         };
     }
 
-    // Handle HashMap/BTreeMap
-    if is_map_type(actual_type)
-        && let Some((_key_ty, val_ty)) = get_map_types(actual_type)
-    {
-        let val_schema_type = get_schema_type_from_rust_type(val_ty);
-        if val_schema_type == "object" {
-            return quote! {
-                {
-                    let mut schema = ::serde_json::json!({
-                        "type": "object",
-                        #desc_prop
-                    });
-                    let value_schema = <#val_ty as ::rstructor::schema::SchemaType>::schema();
-                    if let ::serde_json::Value::Object(map) = &mut schema {
-                        map.insert("additionalProperties".to_string(), value_schema.to_json());
-                    }
-                    schema
+    // Handle collections (Vec/HashSet/HashMap/BTreeMap...), walking every
+    // level of nesting so inner items and values keep their full schema
+    if is_array_type(actual_type) || is_map_type(actual_type) {
+        let collection_schema = generate_type_schema(actual_type, None);
+        let Some(desc) = description else {
+            return collection_schema;
+        };
+        // Merge with the enum-keys hint a map may already carry
+        return quote! {
+            {
+                let mut schema = #collection_schema;
+                if let ::serde_json::Value::Object(map) = &mut schema {
+                    let merged = match map.get("description").and_then(|v| v.as_str()) {
+                        Some(existing) => format!("{}. {}", #desc, existing),
+                        None => #desc.to_string(),
+                    };
+                    map.insert("description".to_string(), ::serde_json::Value::String(merged));
                 }
-            };
-        } else {
-            return quote! {
-                ::serde_json::json!({
-                    "type": "object",
-                    #desc_prop
-                    "additionalProperties": {
-                        "type": #val_schema_type
-                    }
-                })
-            };
-        }
-    }
-
-    // Handle Box<T>
-    if is_box_type(actual_type)
-        && let Some(inner_ty) = get_box_inner_type(actual_type)
-    {
-        return generate_field_schema(inner_ty, description);
+                schema
+            }
+        };
     }
 
     // Extract type name for well-known library types
@@ -567,18 +624,7 @@ fn generate_field_schema(field_type: &Type, description: &Option<String>) -> Tok
         let element_count = element_types.len();
         let element_schemas: Vec<TokenStream> = element_types
             .iter()
-            .map(|elem_ty| {
-                let elem_schema_type = get_schema_type_from_rust_type(elem_ty);
-                if elem_schema_type == "object" {
-                    quote! {
-                        <#elem_ty as ::rstructor::schema::SchemaType>::schema().to_json()
-                    }
-                } else {
-                    quote! {
-                        ::serde_json::json!({"type": #elem_schema_type})
-                    }
-                }
-            })
+            .map(|elem_ty| generate_nested_type_schema(elem_ty, None))
             .collect();
         return quote! {
             {
@@ -596,97 +642,6 @@ fn generate_field_schema(field_type: &Type, description: &Option<String>) -> Tok
         };
     }
 
-    // Handle array types
-    if is_array_type(actual_type) {
-        if let Some(inner_type) = get_array_inner_type(actual_type) {
-            let inner_schema_type = get_schema_type_from_rust_type(inner_type);
-
-            // Extract inner type name for well-known library types
-            let inner_type_name = get_type_name(inner_type);
-
-            let is_inner_datetime = matches!(
-                inner_type_name.as_deref(),
-                Some("DateTime") | Some("NaiveDateTime")
-            );
-            let is_inner_date_only =
-                matches!(inner_type_name.as_deref(), Some("NaiveDate") | Some("Date"));
-            let is_inner_uuid = matches!(inner_type_name.as_deref(), Some("Uuid"));
-
-            if is_inner_datetime {
-                return quote! {
-                    ::serde_json::json!({
-                        "type": "array",
-                        #desc_prop
-                        "items": {
-                            "type": "string",
-                            "format": "date-time"
-                        }
-                    })
-                };
-            }
-
-            if is_inner_date_only {
-                return quote! {
-                    ::serde_json::json!({
-                        "type": "array",
-                        #desc_prop
-                        "items": {
-                            "type": "string",
-                            "format": "date"
-                        }
-                    })
-                };
-            }
-
-            if is_inner_uuid {
-                return quote! {
-                    ::serde_json::json!({
-                        "type": "array",
-                        #desc_prop
-                        "items": {
-                            "type": "string",
-                            "format": "uuid"
-                        }
-                    })
-                };
-            }
-
-            if inner_schema_type == "object" {
-                // For arrays of complex types
-                return quote! {
-                    {
-                        let items_schema = <#inner_type as ::rstructor::schema::SchemaType>::schema().to_json();
-                        ::serde_json::json!({
-                            "type": "array",
-                            #desc_prop
-                            "items": items_schema
-                        })
-                    }
-                };
-            } else {
-                return quote! {
-                    ::serde_json::json!({
-                        "type": "array",
-                        #desc_prop
-                        "items": {
-                            "type": #inner_schema_type
-                        }
-                    })
-                };
-            }
-        } else {
-            return quote! {
-                ::serde_json::json!({
-                    "type": "array",
-                    #desc_prop
-                    "items": {
-                        "type": "string"
-                    }
-                })
-            };
-        }
-    }
-
     // Handle custom object types
     if schema_type == "object"
         && let Type::Path(type_path) = actual_type
@@ -780,7 +735,7 @@ fn generate_internally_tagged_enum_schema(
                     ::serde_json::Value::String(#tag_name.to_string())
                 }];
 
-                for field in &fields.named {
+                for field in ordered_fields(&fields.named) {
                     if let Some(field_ident) = &field.ident {
                         let original_field_name = field_ident.to_string();
                         let field_attrs = parse_field_attributes(field);
@@ -796,7 +751,7 @@ fn generate_internally_tagged_enum_schema(
                             .unwrap_or_else(|| format!("Field {}", field_name_str));
 
                         let is_optional = is_option_type(&field.ty);
-                        let field_schema = generate_field_schema(&field.ty, &Some(field_desc));
+                        let field_schema = generate_field_schema(field, &Some(field_desc));
 
                         let field_name_str_owned = field_name_str.clone();
                         prop_setters.push(quote! {
@@ -1040,7 +995,7 @@ fn generate_adjacently_tagged_enum_schema(
                 if fields.unnamed.len() == 1 {
                     // Single field: {"tag": "Variant", "content": value}
                     let field = fields.unnamed.first().unwrap();
-                    let field_schema = generate_field_schema(&field.ty, &None);
+                    let field_schema = generate_field_schema(field, &None);
 
                     // Create an explicit description for single unnamed field
                     let explicit_description = format!(
@@ -1076,7 +1031,7 @@ fn generate_adjacently_tagged_enum_schema(
                     // Multiple fields: {"tag": "Variant", "content": [values...]}
                     let mut field_schemas = Vec::new();
                     for field in fields.unnamed.iter() {
-                        let field_schema = generate_field_schema(&field.ty, &None);
+                        let field_schema = generate_field_schema(field, &None);
                         field_schemas.push(field_schema);
                     }
                     let field_count = fields.unnamed.len();
@@ -1134,7 +1089,7 @@ fn generate_adjacently_tagged_enum_schema(
                 let mut required_content_fields = Vec::new();
                 let mut field_names = Vec::new();
 
-                for field in &fields.named {
+                for field in ordered_fields(&fields.named) {
                     if let Some(field_ident) = &field.ident {
                         let original_field_name = field_ident.to_string();
                         let field_attrs = parse_field_attributes(field);
@@ -1152,7 +1107,7 @@ fn generate_adjacently_tagged_enum_schema(
                             .unwrap_or_else(|| format!("Field {}", field_name_str));
 
                         let is_optional = is_option_type(&field.ty);
-                        let field_schema = generate_field_schema(&field.ty, &Some(field_desc));
+                        let field_schema = generate_field_schema(field, &Some(field_desc));
 
                         let field_name_str_owned = field_name_str.clone();
                         prop_setters.push(quote! {
@@ -1305,13 +1260,13 @@ fn generate_untagged_enum_schema(
                 if fields.unnamed.len() == 1 {
                     // Single field - just the value
                     let field = fields.unnamed.first().unwrap();
-                    let field_schema = generate_field_schema(&field.ty, &Some(description.clone()));
+                    let field_schema = generate_field_schema(field, &Some(description.clone()));
                     variant_schemas.push(quote! { #field_schema });
                 } else {
                     // Multiple fields - array
                     let mut field_schemas = Vec::new();
                     for field in fields.unnamed.iter() {
-                        let field_schema = generate_field_schema(&field.ty, &None);
+                        let field_schema = generate_field_schema(field, &None);
                         field_schemas.push(field_schema);
                     }
                     let field_count = fields.unnamed.len();
@@ -1339,7 +1294,7 @@ fn generate_untagged_enum_schema(
                 let mut prop_setters = Vec::new();
                 let mut required_fields = Vec::new();
 
-                for field in &fields.named {
+                for field in ordered_fields(&fields.named) {
                     if let Some(field_ident) = &field.ident {
                         let original_field_name = field_ident.to_string();
                         let field_attrs = parse_field_attributes(field);
@@ -1355,7 +1310,7 @@ fn generate_untagged_enum_schema(
                             .unwrap_or_else(|| format!("Field {}", field_name_str));
 
                         let is_optional = is_option_type(&field.ty);
-                        let field_schema = generate_field_schema(&field.ty, &Some(field_desc));
+                        let field_schema = generate_field_schema(field, &Some(field_desc));
 
                         let field_name_str_owned = field_name_str.clone();
                         prop_setters.push(quote! {
@@ -1459,6 +1414,8 @@ fn generate_container_setters(container_attrs: &ContainerAttributes) -> TokenStr
         });
     }
 
+    container_setters.extend(container_attrs.counter_examples_setter());
+
     if !container_setters.is_empty() {
         quote! {
             #(#container_setters)*
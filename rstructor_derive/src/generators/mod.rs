@@ -1,5 +1,12 @@
+//! Schema generation. A change here that alters the schema emitted for an
+//! unchanged type must bump `rstructor::SCHEMA_GENERATION_VERSION` and add an
+//! entry to `SCHEMA_GENERATION_CHANGES`.
+
 pub mod enum_schema;
+pub mod schema_fn;
 pub mod struct_schema;
+pub mod type_schema;
 
 pub use enum_schema::generate_enum_schema;
+pub use schema_fn::finish_schema_fn;
 pub use struct_schema::generate_struct_schema;
@@ -0,0 +1,134 @@
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use quote::quote;
+use syn::{Data, GenericParam, ImplItem, ItemImpl};
+
+use crate::container_attrs::ContainerAttributes;
+use crate::parsers::field_parser::parse_field_attributes;
+use crate::parsers::variant_parser::parse_variant_attributes;
+
+/// Wrap the body of `schema()` in a generated `SchemaType` impl.
+///
+/// The body runs inside `build_schema`, which turns a type reached again
+/// while its own schema is being built (`Box<Self>` in an enum, or mutually
+/// recursive structs) into a `$ref`, and passes the result through
+/// `share_definitions`. That lifts the `$defs` of embedded recursive types to
+/// the root (where their `$ref`s resolve) and moves repeated nested types
+/// into `$defs`.
+///
+/// The result is then built once and cloned afterwards, except for types
+/// with type or const parameters: a `static` inside a generic function is
+/// shared by every instantiation, so `Page<User>` and `Page<Order>` would get
+/// whichever schema was built first.
+///
+/// Descriptions given per locale (`#[llm(description(en = "..", de = ".."))]`)
+/// are recorded on the built schema by `with_translations`, keyed by the
+/// default text, for `Schema::localized` to swap in later.
+pub fn finish_schema_fn(
+    schema_impl: TokenStream,
+    name: &syn::Ident,
+    container_attrs: &ContainerAttributes,
+    data: &Data,
+    generics: &syn::Generics,
+) -> TokenStream {
+    let is_generic = generics
+        .params
+        .iter()
+        .any(|param| !matches!(param, GenericParam::Lifetime(_)));
+    let cache = !container_attrs.no_schema_cache && !is_generic;
+    let Ok(mut item) = syn::parse2::<ItemImpl>(schema_impl.clone()) else {
+        return schema_impl;
+    };
+    for impl_item in &mut item.items {
+        if let ImplItem::Fn(function) = impl_item
+            && function.sig.ident == "schema"
+        {
+            let block = &function.block;
+            let translations = description_translations(container_attrs, data);
+            let block = match &container_attrs.extra_schema {
+                Some(extra) => quote! {
+                    {
+                        ::rstructor::schema::__private::with_extra_schema(#block, #extra)
+                    }
+                },
+                None => quote! { #block },
+            };
+            let build = if translations.is_empty() {
+                quote! { #block }
+            } else {
+                let entries = translations.iter().map(|(locale, source, text)| {
+                    quote! { (#locale, #source, #text) }
+                });
+                quote! {
+                    {
+                        ::rstructor::schema::__private::with_translations(
+                            #block,
+                            &[#(#entries),*],
+                        )
+                    }
+                }
+            };
+            let def_name = name.to_string();
+            function.block = if cache {
+                syn::parse_quote!({
+                    static SCHEMA: ::std::sync::OnceLock<::rstructor::schema::Schema> =
+                        ::std::sync::OnceLock::new();
+                    ::rstructor::schema::__private::build_schema::<Self>(
+                        #def_name,
+                        ::std::option::Option::Some(&SCHEMA),
+                        || #build,
+                    )
+                })
+            } else {
+                syn::parse_quote!({
+                    ::rstructor::schema::__private::build_schema::<Self>(
+                        #def_name,
+                        ::std::option::Option::None,
+                        || #build,
+                    )
+                })
+            };
+        }
+    }
+    item.into_token_stream()
+}
+
+/// `(locale, default text, translated text)` for every description of the
+/// container, its fields, and its variants and their fields.
+fn description_translations(
+    container_attrs: &ContainerAttributes,
+    data: &Data,
+) -> Vec<(String, String, String)> {
+    let mut described = vec![(
+        container_attrs.description.clone(),
+        container_attrs.description_translations.clone(),
+    )];
+    let fields: Vec<&syn::Field> = match data {
+        Data::Struct(data_struct) => data_struct.fields.iter().collect(),
+        Data::Enum(data_enum) => {
+            for variant in &data_enum.variants {
+                let attrs = parse_variant_attributes(variant);
+                described.push((attrs.description, attrs.description_translations));
+            }
+            data_enum
+                .variants
+                .iter()
+                .flat_map(|variant| &variant.fields)
+                .collect()
+        }
+        Data::Union(_) => Vec::new(),
+    };
+    for field in fields {
+        let attrs = parse_field_attributes(field);
+        described.push((attrs.description, attrs.description_translations));
+    }
+    described
+        .into_iter()
+        .filter_map(|(source, translations)| Some((source?, translations)))
+        .flat_map(|(source, translations)| {
+            translations
+                .into_iter()
+                .map(move |(locale, text)| (locale, source.clone(), text))
+        })
+        .collect()
+}
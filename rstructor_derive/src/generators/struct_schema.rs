@@ -1,14 +1,16 @@
 use proc_macro2::TokenStream;
 use quote::quote;
-use syn::{DataStruct, Fields, Ident, Type};
+use syn::{DataStruct, Fields, Ident};
 
 use crate::container_attrs::ContainerAttributes;
-use crate::parsers::field_parser::parse_field_attributes;
+use crate::generators::type_schema::{
+    generate_nested_type_schema, generate_type_schema, sniffed_fallback_schema,
+};
+use crate::parsers::field_parser::{ordered_fields, parse_field_attributes};
 use crate::type_utils::{
-    generics_with_bounds, get_array_inner_type, get_box_inner_type, get_map_types,
-    get_option_inner_type, get_schema_type_from_rust_type, get_tuple_element_types, get_type_name,
-    is_array_type, is_box_type, is_json_value_type, is_map_type, is_option_type, is_self_reference,
-    is_tuple_type,
+    generics_with_bounds, get_schema_type_from_rust_type, get_tuple_element_types, get_type_name,
+    is_array_type, is_json_value_type, is_map_type, is_option_type, is_self_reference,
+    is_tuple_type, strip_transparent_wrappers,
 };
 
 /// Generate the schema implementation for a struct
@@ -33,7 +35,7 @@ pub fn generate_struct_schema(
                 }
             }
 
-            for field in &fields.named {
+            for field in ordered_fields(&fields.named) {
                 // Parse field attributes first to check for serde rename
                 let attrs = parse_field_attributes(field);
 
@@ -49,49 +51,65 @@ pub fn generate_struct_schema(
                 };
                 let is_optional = is_option_type(&field.ty);
 
+                // Option and Box are transparent in the schema (optionality is
+                // expressed through `required`), so classify what they wrap
+                let actual_type = strip_transparent_wrappers(&field.ty);
+
                 // Get schema type
-                let schema_type = get_schema_type_from_rust_type(&field.ty);
+                let schema_type = get_schema_type_from_rust_type(actual_type);
 
                 // Extract type name for well-known library types only (exact matches, no heuristics)
-                let type_name = get_type_name(&field.ty);
-
-                // Also extract the inner type name when the field is Optional,
-                // so Option<NaiveDate>, Option<DateTime>, Option<Uuid> etc. get proper format metadata
-                let inner_type_name = if is_optional {
-                    get_type_name(get_option_inner_type(&field.ty))
-                } else {
-                    None
-                };
+                let type_name = get_type_name(actual_type);
 
                 // Check for well-known library types by exact match only (no contains checks)
                 let is_datetime_type = matches!(
                     type_name.as_deref(),
                     Some("DateTime") | Some("NaiveDateTime")
-                ) || matches!(
-                    inner_type_name.as_deref(),
-                    Some("DateTime") | Some("NaiveDateTime")
                 );
                 let is_date_only_type =
-                    matches!(type_name.as_deref(), Some("NaiveDate") | Some("Date"))
-                        || matches!(inner_type_name.as_deref(), Some("NaiveDate") | Some("Date"));
-                let is_uuid_type = matches!(type_name.as_deref(), Some("Uuid"))
-                    || matches!(inner_type_name.as_deref(), Some("Uuid"));
+                    matches!(type_name.as_deref(), Some("NaiveDate") | Some("Date"));
+                let is_uuid_type = matches!(type_name.as_deref(), Some("Uuid"));
 
                 // Create field property
                 // IMPORTANT: Default to treating unknown types as structs (objects)
                 // Structs are far more common than enums, and this is the safest default
-                let field_prop = if is_datetime_type || is_date_only_type || is_uuid_type {
+                let field_prop = if let Some(schema_fn) = &attrs.schema_with {
+                    // #[llm(schema_with = "path")]: the function supplies the
+                    // schema, for types that cannot implement SchemaType
+                    quote! {
+                        let mut props = match #schema_fn().to_json() {
+                            ::serde_json::Value::Object(m) => m,
+                            _ => ::serde_json::Map::new(),
+                        };
+                    }
+                } else if let Some(wire) = &attrs.wire {
+                    // #[llm(serialized_as = "..")] or a known serde `with`
+                    // helper: describe the wire format, not the Rust type
+                    let wire_schema = wire.schema();
+                    quote! {
+                        let mut props = match #wire_schema {
+                            ::serde_json::Value::Object(m) => m,
+                            _ => ::serde_json::Map::new(),
+                        };
+                    }
+                } else if type_name.as_deref() == Some(struct_name_str.as_str()) {
+                    // Self-referential type (e.g. Option<Box<Self>>): use $ref
+                    // to prevent infinite recursion at schema() time. The root
+                    // schema places the struct's definition under $defs in this
+                    // case. Checked before the date/uuid sniff so a recursive
+                    // `struct Date` never probes its own schema().
+                    quote! {
+                        let mut props = ::serde_json::Map::new();
+                        props.insert("$ref".to_string(),
+                            ::serde_json::Value::String(format!("#/$defs/{}", #struct_name_str)));
+                    }
+                } else if is_datetime_type || is_date_only_type || is_uuid_type {
                     // Well-known library type *names* (chrono's DateTime/NaiveDate/...,
                     // uuid's Uuid). These names are only a heuristic: a user-defined
                     // `struct Date` deriving Instructor must keep its real schema. So
                     // probe at compile time (via autoref specialization): if the field
                     // type implements SchemaType, its own schema wins; otherwise fall
                     // back to the sniffed string/format schema.
-                    let actual_type = if is_optional {
-                        get_option_inner_type(&field.ty)
-                    } else {
-                        &field.ty
-                    };
                     let fallback = sniffed_fallback_schema(is_datetime_type, is_date_only_type);
                     quote! {
                         // Create property for this well-known (date/uuid) field,
@@ -110,171 +128,32 @@ pub fn generate_struct_schema(
                             m
                         };
                     }
-                } else if is_array_type(&field.ty)
-                    || (is_optional && is_array_type(get_option_inner_type(&field.ty)))
-                {
-                    // For array types (including Option<Vec<T>>), we need to add the 'items' property
-                    let actual_array_type =
-                        if is_optional && is_array_type(get_option_inner_type(&field.ty)) {
-                            get_option_inner_type(&field.ty)
-                        } else {
-                            &field.ty
+                } else if is_array_type(actual_type) || is_map_type(actual_type) {
+                    // Collections (Vec<T>, HashSet<T>, HashMap<K, V>, ...): walk
+                    // every level of nesting so inner items/values keep their
+                    // full schema, e.g. Vec<Option<Vec<i32>>>
+                    let collection_schema =
+                        generate_type_schema(actual_type, Some(&struct_name_str));
+                    quote! {
+                        let mut props = match #collection_schema {
+                            ::serde_json::Value::Object(m) => m,
+                            _ => ::serde_json::Map::new(),
                         };
-                    if let Some(inner_type) = get_array_inner_type(actual_array_type) {
-                        // Generate the items schema, recursing into nested
-                        // collections so e.g. Vec<Vec<i32>> keeps its inner items
-                        let items_expr = generate_array_items_schema(inner_type, &struct_name_str);
-                        quote! {
-                            // Create property for this array field
-                            let mut props = ::serde_json::Map::new();
-                            props.insert("type".to_string(), ::serde_json::Value::String(#schema_type.to_string()));
-
-                            // Add items schema (recursive for nested collections)
-                            props.insert("items".to_string(), #items_expr);
-                        }
-                    } else {
-                        // Fallback for array without detectable item type
-                        quote! {
-                            // Create property for this array field (fallback)
-                            let mut props = ::serde_json::Map::new();
-                            props.insert("type".to_string(), ::serde_json::Value::String(#schema_type.to_string()));
-
-                            // Add default items schema
-                            let mut items_schema = ::serde_json::Map::new();
-                            items_schema.insert("type".to_string(), ::serde_json::Value::String("string".to_string()));
-                            props.insert("items".to_string(), ::serde_json::Value::Object(items_schema));
-                        }
                     }
-                } else if is_json_value_type(&field.ty)
-                    || (is_optional && is_json_value_type(get_option_inner_type(&field.ty)))
-                {
+                } else if is_json_value_type(actual_type) {
                     // For serde_json::Value, use an empty schema (any JSON is valid)
                     quote! {
                         let mut props = ::serde_json::Map::new();
                         // Empty object schema means any JSON value is accepted
                     }
-                } else if is_map_type(&field.ty)
-                    || (is_optional && is_map_type(get_option_inner_type(&field.ty)))
-                {
-                    // For HashMap<K, V> or BTreeMap<K, V>
-                    let actual_type = if is_optional {
-                        get_option_inner_type(&field.ty)
-                    } else {
-                        &field.ty
-                    };
-                    if let Some((key_ty, val_ty)) = get_map_types(actual_type) {
-                        // Use SchemaType::schema() for all value types to get complete schema
-                        // This ensures arrays get proper `items`, objects get properties, etc.
-                        // For enum keys, extract the enum variants and add them to description
-                        // so that Gemini can use the correct keys instead of generic placeholders
-                        quote! {
-                            let mut props = ::serde_json::Map::new();
-                            props.insert("type".to_string(), ::serde_json::Value::String("object".to_string()));
-                            let value_schema = <#val_ty as ::rstructor::schema::SchemaType>::schema();
-                            props.insert("additionalProperties".to_string(), value_schema.to_json());
-
-                            // Try to extract enum keys from key type schema (for enum keys)
-                            let key_schema = <#key_ty as ::rstructor::schema::SchemaType>::schema();
-                            if let Some(enum_values) = key_schema.to_json().get("enum")
-                                .and_then(|e| e.as_array())
-                            {
-                                let keys: Vec<String> = enum_values
-                                    .iter()
-                                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                                    .collect();
-                                if !keys.is_empty() {
-                                    let keys_hint = format!("Keys: [{}]", keys.join(", "));
-                                    props.insert("description".to_string(), ::serde_json::Value::String(keys_hint));
-                                    // Also store enum keys as a structured extension field
-                                    // so backends can extract them without parsing the description
-                                    let keys_json: Vec<::serde_json::Value> = keys.iter()
-                                        .map(|k| ::serde_json::Value::String(k.clone()))
-                                        .collect();
-                                    props.insert("x-enum-keys".to_string(), ::serde_json::Value::Array(keys_json));
-                                }
-                            }
-                        }
-                    } else {
-                        // Fallback for map without detectable types
-                        quote! {
-                            let mut props = ::serde_json::Map::new();
-                            props.insert("type".to_string(), ::serde_json::Value::String("object".to_string()));
-                        }
-                    }
-                } else if is_box_type(&field.ty)
-                    || (is_optional && is_box_type(get_option_inner_type(&field.ty)))
-                {
-                    // For Box<T>, unwrap and use the inner type's schema
-                    let actual_type = if is_optional {
-                        get_option_inner_type(&field.ty)
-                    } else {
-                        &field.ty
-                    };
-                    if let Some(inner_ty) = get_box_inner_type(actual_type) {
-                        let inner_schema_type = get_schema_type_from_rust_type(inner_ty);
-                        if is_self_reference(inner_ty, &struct_name_str) {
-                            // Self-referential type (e.g. Option<Box<Self>>): use $ref
-                            // to prevent infinite recursion at schema() time, mirroring
-                            // the guard in the array branch. The root schema places the
-                            // struct's definition under $defs in this case.
-                            quote! {
-                                let mut props = ::serde_json::Map::new();
-                                props.insert("$ref".to_string(),
-                                    ::serde_json::Value::String(format!("#/$defs/{}", #struct_name_str)));
-                            }
-                        } else if inner_schema_type == "object" {
-                            // Inner type is a complex type, use its schema
-                            quote! {
-                                let nested_schema = <#inner_ty as ::rstructor::schema::SchemaType>::schema();
-                                let props_json = nested_schema.to_json();
-                                let mut props = if let ::serde_json::Value::Object(m) = props_json {
-                                    m
-                                } else {
-                                    let mut m = ::serde_json::Map::new();
-                                    m.insert("type".to_string(), ::serde_json::Value::String("object".to_string()));
-                                    m
-                                };
-                            }
-                        } else {
-                            // Inner type is a primitive
-                            quote! {
-                                let mut props = ::serde_json::Map::new();
-                                props.insert("type".to_string(), ::serde_json::Value::String(#inner_schema_type.to_string()));
-                            }
-                        }
-                    } else {
-                        // Fallback
-                        quote! {
-                            let mut props = ::serde_json::Map::new();
-                            props.insert("type".to_string(), ::serde_json::Value::String("object".to_string()));
-                        }
-                    }
-                } else if is_tuple_type(&field.ty)
-                    || (is_optional && is_tuple_type(get_option_inner_type(&field.ty)))
-                {
+                } else if is_tuple_type(actual_type) {
                     // For tuples, generate array with prefixItems
-                    let actual_type = if is_optional {
-                        get_option_inner_type(&field.ty)
-                    } else {
-                        &field.ty
-                    };
                     if let Some(element_types) = get_tuple_element_types(actual_type) {
                         let element_count = element_types.len();
                         // Generate schema for each element
                         let element_schemas: Vec<TokenStream> = element_types
                             .iter()
-                            .map(|elem_ty| {
-                                let elem_schema_type = get_schema_type_from_rust_type(elem_ty);
-                                if elem_schema_type == "object" {
-                                    quote! {
-                                        <#elem_ty as ::rstructor::schema::SchemaType>::schema().to_json()
-                                    }
-                                } else {
-                                    quote! {
-                                        ::serde_json::json!({"type": #elem_schema_type})
-                                    }
-                                }
-                            })
+                            .map(|elem_ty| generate_nested_type_schema(elem_ty, None))
                             .collect();
                         quote! {
                             let mut props = ::serde_json::Map::new();
@@ -296,12 +175,6 @@ pub fn generate_struct_schema(
                 } else if type_name.is_some() && schema_type == "object" {
                     // For nested struct fields, embed the inner type's schema directly
                     // This requires the inner type to implement SchemaType
-                    // If it's an Option<T>, unwrap to get T first
-                    let actual_type = if is_optional {
-                        get_option_inner_type(&field.ty)
-                    } else {
-                        &field.ty
-                    };
                     quote! {
                         // Get the nested type's schema directly
                         let nested_schema = <#actual_type as ::rstructor::schema::SchemaType>::schema();
@@ -343,6 +216,21 @@ pub fn generate_struct_schema(
                     property_setters.push(desc_prop);
                 }
 
+                // Add numeric constraints (also enforced by the derived `validate`)
+                if !attrs.numeric.is_empty() {
+                    property_setters.push(attrs.numeric.schema_setters());
+                }
+
+                // Add string constraints (also enforced by the derived `validate`)
+                if !attrs.string.is_empty() {
+                    property_setters.push(attrs.string.schema_setters());
+                }
+
+                // Add array constraints (also enforced by the derived `validate`)
+                if !attrs.array.is_empty() {
+                    property_setters.push(attrs.array.schema_setters());
+                }
+
                 // Add single example if available
                 if let Some(ex_val) = &attrs.example_value {
                     let ex_prop = quote! {
@@ -364,6 +252,13 @@ pub fn generate_struct_schema(
                     property_setters.push(exs_prop);
                 }
 
+                // Merge extra keywords last so they can override generated ones
+                if let Some(extra) = &attrs.extra_schema {
+                    property_setters.push(quote! {
+                        ::rstructor::schema::__private::merge_extra_schema(&mut props, #extra);
+                    });
+                }
+
                 // Add the property to the schema
                 let add_prop = quote! {
                     // Add property to the schema
@@ -414,6 +309,8 @@ pub fn generate_struct_schema(
         });
     }
 
+    container_setters.extend(container_attrs.counter_examples_setter());
+
     // Combine all container attribute setters
     let container_setter = if !container_setters.is_empty() {
         quote! {
@@ -505,109 +402,6 @@ pub fn generate_struct_schema(
     }
 }
 
-/// Generate an expression evaluating to the sniffed fallback schema for a
-/// well-known library type name: date-time (`DateTime`/`NaiveDateTime`), date
-/// (`NaiveDate`/`Date`), or uuid (`Uuid`).
-fn sniffed_fallback_schema(is_datetime: bool, is_date_only: bool) -> TokenStream {
-    if is_datetime {
-        quote! {
-            ::serde_json::json!({
-                "type": "string",
-                "format": "date-time",
-                "description": "ISO-8601 formatted date and time"
-            })
-        }
-    } else if is_date_only {
-        quote! {
-            ::serde_json::json!({
-                "type": "string",
-                "format": "date",
-                "description": "ISO-8601 formatted date (YYYY-MM-DD)"
-            })
-        }
-    } else {
-        quote! {
-            ::serde_json::json!({
-                "type": "string",
-                "format": "uuid",
-                "description": "UUID identifier string"
-            })
-        }
-    }
-}
-
-/// Generate an expression evaluating to the JSON Schema `items` value for an
-/// array whose element type is `inner_type`.
-///
-/// Recurses into nested collections (`Vec<Vec<i32>>`, `Vec<HashSet<String>>`,
-/// ...) so every nesting level carries its own `items` schema, and emits a
-/// `$ref` for self-referential element types to prevent infinite recursion.
-fn generate_array_items_schema(inner_type: &Type, struct_name_str: &str) -> TokenStream {
-    // Nested collections: recurse so the inner `items` schema is preserved
-    if is_array_type(inner_type)
-        && let Some(next_inner) = get_array_inner_type(inner_type)
-    {
-        let nested_items = generate_array_items_schema(next_inner, struct_name_str);
-        return quote! {
-            {
-                let mut items_schema = ::serde_json::Map::new();
-                items_schema.insert("type".to_string(), ::serde_json::Value::String("array".to_string()));
-                items_schema.insert("items".to_string(), #nested_items);
-                ::serde_json::Value::Object(items_schema)
-            }
-        };
-    }
-
-    // Self-referential element types use $ref to prevent infinite recursion.
-    // This must run before the well-known-name sniff below: a recursive
-    // struct that happens to be named `Date` must still get a $ref, not a
-    // probe that would call its own schema() and never terminate.
-    if is_self_reference(inner_type, struct_name_str) {
-        return quote! {
-            ::serde_json::json!({ "$ref": format!("#/$defs/{}", #struct_name_str) })
-        };
-    }
-
-    // Check for well-known library types by exact match only (no heuristics)
-    let inner_type_name = get_type_name(inner_type);
-    let is_datetime = matches!(
-        inner_type_name.as_deref(),
-        Some("DateTime") | Some("NaiveDateTime")
-    );
-    let is_date_only = matches!(inner_type_name.as_deref(), Some("NaiveDate") | Some("Date"));
-    let is_uuid = matches!(inner_type_name.as_deref(), Some("Uuid"));
-
-    if is_datetime || is_date_only || is_uuid {
-        // Name match is only a heuristic: prefer the element type's own
-        // SchemaType impl (user-defined `struct Date`), falling back to the
-        // sniffed string/format schema (chrono/uuid types).
-        let fallback = sniffed_fallback_schema(is_datetime, is_date_only);
-        return quote! {
-            {
-                #[allow(unused_imports)]
-                use ::rstructor::schema::__private::SchemaProbeFallback as _;
-                ::rstructor::schema::__private::SchemaProbe::<#inner_type>::new()
-                    .rstructor_schema_or(#fallback)
-            }
-        };
-    }
-
-    let inner_schema_type = get_schema_type_from_rust_type(inner_type);
-    if inner_schema_type == "object" {
-        // For nested structs (and Box/HashMap wrappers), embed the inner
-        // type's schema directly. This requires the inner type to implement
-        // SchemaType.
-        quote! {
-            <#inner_type as ::rstructor::schema::SchemaType>::schema().to_json()
-        }
-    } else {
-        // Standard handling for primitive types
-        quote! {
-            ::serde_json::json!({ "type": #inner_schema_type })
-        }
-    }
-}
-
 /// Apply serde rename_all transformation to a field/variant name
 pub fn apply_rename_all(name: &str, rename_all: &str) -> String {
     match rename_all {
@@ -0,0 +1,181 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Type;
+
+use crate::type_utils::{
+    TypeShape, get_schema_type_from_rust_type, get_type_name, is_json_value_type, is_optional,
+    type_shape,
+};
+
+/// Generate an expression evaluating to the JSON Schema (a `serde_json::Value`)
+/// for `ty`, walking any nesting of `Option`, `Box`, arrays and maps so every
+/// level keeps its full schema: `Vec<Option<Vec<i32>>>` gets `items` at both
+/// array levels, `Option<HashMap<String, Vec<T>>>` gets `additionalProperties`
+/// with `items`, and so on.
+///
+/// `self_name` is the struct whose schema is being generated, if it is
+/// self-referential: references to it become `$ref`s into the root `$defs`
+/// instead of recursing forever at `schema()` time.
+pub fn generate_type_schema(ty: &Type, self_name: Option<&str>) -> TokenStream {
+    match type_shape(ty) {
+        TypeShape::Array { item, unique } => {
+            let items = generate_nested_type_schema(item, self_name);
+            let unique_items = unique.then(|| {
+                quote! {
+                    schema.insert("uniqueItems".to_string(), ::serde_json::Value::Bool(true));
+                }
+            });
+            quote! {
+                {
+                    let mut schema = ::serde_json::Map::new();
+                    schema.insert("type".to_string(), ::serde_json::Value::String("array".to_string()));
+                    schema.insert("items".to_string(), #items);
+                    #unique_items
+                    ::serde_json::Value::Object(schema)
+                }
+            }
+        }
+        TypeShape::Map { key, value } => {
+            let values = generate_nested_type_schema(value, self_name);
+            // For enum keys, list the variants in the description so models
+            // (Gemini in particular) use the real keys instead of placeholders
+            quote! {
+                {
+                    let mut schema = ::serde_json::Map::new();
+                    schema.insert("type".to_string(), ::serde_json::Value::String("object".to_string()));
+                    schema.insert("additionalProperties".to_string(), #values);
+
+                    // Try to extract enum keys from key type schema (for enum keys),
+                    // either a plain `enum` or a oneOf of `const` values
+                    let key_schema = <#key as ::rstructor::schema::SchemaType>::schema().to_json();
+                    let enum_values: Vec<::serde_json::Value> = match key_schema.get("enum").and_then(|e| e.as_array()) {
+                        Some(values) => values.clone(),
+                        None => key_schema
+                            .get("oneOf")
+                            .and_then(|o| o.as_array())
+                            .map(|branches| branches.iter().filter_map(|b| b.get("const").cloned()).collect())
+                            .unwrap_or_default(),
+                    };
+                    let keys: Vec<String> = enum_values
+                        .iter()
+                        .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                        .collect();
+                    if !keys.is_empty() {
+                        let keys_hint = format!("Keys: [{}]", keys.join(", "));
+                        schema.insert("description".to_string(), ::serde_json::Value::String(keys_hint));
+                        // Also store enum keys as a structured extension field
+                        // so backends can extract them without parsing the description
+                        let keys_json: Vec<::serde_json::Value> = keys.iter()
+                            .map(|k| ::serde_json::Value::String(k.clone()))
+                            .collect();
+                        schema.insert("x-enum-keys".to_string(), ::serde_json::Value::Array(keys_json));
+                    }
+                    ::serde_json::Value::Object(schema)
+                }
+            }
+        }
+        TypeShape::Leaf(ty) => generate_leaf_schema(ty, self_name),
+    }
+}
+
+/// [`generate_type_schema`] for a type below field level: an array item, map
+/// value or tuple element. An outer `Option` is stripped at field level, where
+/// the parent's `required` list expresses it, but here it has to admit `null`:
+/// `Vec<Option<i32>>` gets items `{"type": ["integer", "null"]}`.
+pub fn generate_nested_type_schema(ty: &Type, self_name: Option<&str>) -> TokenStream {
+    let schema = generate_type_schema(ty, self_name);
+    if is_optional(ty) {
+        quote! { ::rstructor::schema::__private::nullable(#schema) }
+    } else {
+        schema
+    }
+}
+
+fn generate_leaf_schema(ty: &Type, self_name: Option<&str>) -> TokenStream {
+    let type_name = get_type_name(ty);
+
+    // Self-referential types use $ref to prevent infinite recursion. This
+    // must run before the well-known-name sniff below: a recursive struct
+    // that happens to be named `Date` must still get a $ref, not a probe
+    // that would call its own schema() and never terminate.
+    if let Some(self_name) = self_name
+        && type_name.as_deref() == Some(self_name)
+    {
+        return quote! {
+            ::serde_json::json!({ "$ref": format!("#/$defs/{}", #self_name) })
+        };
+    }
+
+    // Check for well-known library types by exact match only (no heuristics)
+    let is_datetime = matches!(
+        type_name.as_deref(),
+        Some("DateTime") | Some("NaiveDateTime")
+    );
+    let is_date_only = matches!(type_name.as_deref(), Some("NaiveDate") | Some("Date"));
+    let is_uuid = matches!(type_name.as_deref(), Some("Uuid"));
+
+    if is_datetime || is_date_only || is_uuid {
+        // Name match is only a heuristic: prefer the type's own SchemaType
+        // impl (user-defined `struct Date`), falling back to the sniffed
+        // string/format schema (chrono/uuid types).
+        let fallback = sniffed_fallback_schema(is_datetime, is_date_only);
+        return quote! {
+            {
+                #[allow(unused_imports)]
+                use ::rstructor::schema::__private::SchemaProbeFallback as _;
+                ::rstructor::schema::__private::SchemaProbe::<#ty>::new()
+                    .rstructor_schema_or(#fallback)
+            }
+        };
+    }
+
+    if is_json_value_type(ty) {
+        // Empty schema means any JSON value is accepted
+        return quote! { ::serde_json::json!({}) };
+    }
+
+    let schema_type = get_schema_type_from_rust_type(ty);
+    if matches!(schema_type, "object" | "array") {
+        // Structs, enums and tuples: embed the type's own schema. This
+        // requires the type to implement SchemaType.
+        quote! {
+            <#ty as ::rstructor::schema::SchemaType>::schema().to_json()
+        }
+    } else {
+        // Standard handling for primitive types
+        quote! {
+            ::serde_json::json!({ "type": #schema_type })
+        }
+    }
+}
+
+/// Generate an expression evaluating to the sniffed fallback schema for a
+/// well-known library type name: date-time (`DateTime`/`NaiveDateTime`), date
+/// (`NaiveDate`/`Date`), or uuid (`Uuid`).
+pub fn sniffed_fallback_schema(is_datetime: bool, is_date_only: bool) -> TokenStream {
+    if is_datetime {
+        quote! {
+            ::serde_json::json!({
+                "type": "string",
+                "format": "date-time",
+                "description": "ISO-8601 formatted date and time"
+            })
+        }
+    } else if is_date_only {
+        quote! {
+            ::serde_json::json!({
+                "type": "string",
+                "format": "date",
+                "description": "ISO-8601 formatted date (YYYY-MM-DD)"
+            })
+        }
+    } else {
+        quote! {
+            ::serde_json::json!({
+                "type": "string",
+                "format": "uuid",
+                "description": "UUID identifier string"
+            })
+        }
+    }
+}
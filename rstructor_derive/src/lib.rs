@@ -11,6 +11,7 @@ mod parsers;
 mod type_utils;
 
 use container_attrs::ContainerAttributes;
+use parsers::serde_parser::{parse_deserialize_name, skip_meta_value};
 use proc_macro::TokenStream;
 use syn::{Data, DeriveInput, Fields, parse_macro_input};
 
@@ -71,6 +72,330 @@ use syn::{Data, DeriveInput, Fields, parse_macro_input};
 ///
 /// The validation function is called automatically when the LLM response is deserialized.
 ///
+/// # Property Order
+///
+/// Properties appear in the schema in declaration order, and models tend to
+/// generate fields in schema order — so put fields the model should reason about
+/// first (e.g. `reasoning` before `answer`) near the top. `#[llm(order = n)]`
+/// overrides this without reordering the struct: fields with an `order` come
+/// first, ascending, followed by the rest in declaration order.
+///
+/// ```
+/// use rstructor::{Instructor, SchemaType};
+/// use serde::{Serialize, Deserialize};
+///
+/// #[derive(Instructor, Serialize, Deserialize)]
+/// struct Answer {
+///     answer: String,
+///     #[llm(order = 0, description = "Step-by-step reasoning")]
+///     reasoning: String,
+/// }
+///
+/// let schema = Answer::schema().to_json();
+/// let keys: Vec<_> = schema["properties"].as_object().unwrap().keys().collect();
+/// assert_eq!(keys, ["reasoning", "answer"]);
+/// ```
+///
+/// # Numeric Constraints
+///
+/// `minimum`, `maximum`, `exclusive_minimum`, `exclusive_maximum` and
+/// `multiple_of` on a numeric struct field (or an `Option` of one) add the
+/// matching JSON Schema keywords to its property. The derived `validate` also
+/// checks them after deserialization, because some providers' strict modes
+/// drop these keywords, and an out-of-range value is re-asked like any other
+/// validation failure.
+///
+/// ```
+/// use rstructor::{Instructor, SchemaType};
+/// use serde::{Serialize, Deserialize};
+///
+/// #[derive(Instructor, Serialize, Deserialize)]
+/// struct Patient {
+///     #[llm(minimum = 0, maximum = 150)]
+///     age: u8,
+///     #[llm(exclusive_minimum = 0.0, multiple_of = 0.5)]
+///     dose_mg: f64,
+/// }
+///
+/// let schema = Patient::schema().to_json();
+/// assert_eq!(schema["properties"]["age"]["maximum"], 150);
+///
+/// let bad = Patient { age: 30, dose_mg: 0.75 };
+/// assert!(bad.validate().is_err());
+/// ```
+///
+/// # String Constraints
+///
+/// `min_length`, `max_length`, `pattern` and `format` on a `String` field (or
+/// an `Option` of one) work the same way: they become `minLength`,
+/// `maxLength`, `pattern` and `format` in the schema and are checked by the
+/// derived `validate`. Lengths count characters. `format` is checked for
+/// `email`, `uri`, `uuid`, `date`, `time`, `date-time`, `hostname`, `ipv4` and
+/// `ipv6`; any other format is only a hint in the schema.
+///
+/// ```
+/// use rstructor::{Instructor, SchemaType};
+/// use serde::{Serialize, Deserialize};
+///
+/// #[derive(Instructor, Serialize, Deserialize)]
+/// struct Signup {
+///     #[llm(min_length = 1, max_length = 200)]
+///     display_name: String,
+///     #[llm(pattern = "^[A-Z]{2}$")]
+///     country: String,
+///     #[llm(format = "email")]
+///     email: String,
+/// }
+///
+/// let schema = Signup::schema().to_json();
+/// assert_eq!(schema["properties"]["email"]["format"], "email");
+///
+/// let bad = Signup {
+///     display_name: "Ada".into(),
+///     country: "gb".into(),
+///     email: "ada@example.com".into(),
+/// };
+/// assert!(bad.validate().is_err());
+/// ```
+///
+/// # Array Constraints
+///
+/// `min_items`, `max_items` and the `unique_items` flag on a `Vec` field (or
+/// an `Option` of one) become `minItems`, `maxItems` and `uniqueItems`, and the
+/// derived `validate` checks them too. Items are compared by their serialized
+/// JSON, so element types need no `PartialEq`.
+///
+/// ```
+/// use rstructor::{Instructor, SchemaType};
+/// use serde::{Serialize, Deserialize};
+///
+/// #[derive(Instructor, Serialize, Deserialize)]
+/// struct Article {
+///     #[llm(min_items = 3, max_items = 5, unique_items)]
+///     tags: Vec<String>,
+/// }
+///
+/// let schema = Article::schema().to_json();
+/// assert_eq!(schema["properties"]["tags"]["maxItems"], 5);
+///
+/// let repeated = Article { tags: vec!["rust".into(), "llm".into(), "rust".into()] };
+/// assert!(repeated.validate().is_err());
+/// ```
+///
+/// # Output Language
+///
+/// `#[llm(language = "..")]` on a `String` field (or an `Option` of one)
+/// requires its text to be written in that language, named by ISO 639-3 code
+/// (`"deu"`) or English name (`"German"`). With the `language` feature enabled,
+/// the derived `validate` detects the language of the text and fails if it
+/// confidently reads as another one, so a model that answers in English when
+/// extracting from a German document is re-asked. Text too short to tell
+/// passes. Using the attribute without the feature is a compile error.
+///
+/// ```
+/// use rstructor::Instructor;
+/// use serde::{Serialize, Deserialize};
+///
+/// #[derive(Instructor, Serialize, Deserialize)]
+/// struct Summary {
+///     #[llm(language = "German")]
+///     text: String,
+/// }
+///
+/// let english = Summary {
+///     text: "The tenant must pay the rent on the first day of every month.".into(),
+/// };
+/// assert!(english.validate().is_err());
+/// ```
+///
+/// # Custom Field Schemas
+///
+/// `#[llm(schema_with = "path::to::fn")]` takes a field's schema from a
+/// `fn() -> rstructor::Schema` instead of the field type's `SchemaType` impl,
+/// for types that can't have one: foreign types, or trait objects behind a
+/// serde adapter. The derive only handles the schema; (de)serialization stays
+/// with serde, e.g. `#[serde(with = "..")]`.
+///
+/// ```
+/// use rstructor::{Instructor, Schema, SchemaType};
+/// use serde::{Deserialize, Deserializer, Serialize, Serializer};
+/// use std::time::Duration;
+///
+/// fn seconds_schema() -> Schema {
+///     Schema::new(serde_json::json!({ "type": "integer", "minimum": 0 }))
+/// }
+///
+/// fn to_secs<S: Serializer>(d: &Duration, s: S) -> Result<S::Ok, S::Error> {
+///     s.serialize_u64(d.as_secs())
+/// }
+///
+/// fn from_secs<'de, D: Deserializer<'de>>(d: D) -> Result<Duration, D::Error> {
+///     u64::deserialize(d).map(Duration::from_secs)
+/// }
+///
+/// #[derive(Instructor, Serialize, Deserialize)]
+/// struct Timer {
+///     #[llm(schema_with = "seconds_schema")]
+///     #[serde(serialize_with = "to_secs", deserialize_with = "from_secs")]
+///     length: Duration,
+/// }
+///
+/// let schema = Timer::schema().to_json();
+/// assert_eq!(schema["properties"]["length"]["type"], "integer");
+/// ```
+///
+/// # Extra Schema Keywords
+///
+/// `#[llm(extra_schema = r#"{..}"#)]` merges a JSON object into the generated
+/// schema: on the type, into its root schema; on a field, into that field's
+/// schema. It is an escape hatch for provider-specific or vendor-extension
+/// keywords (`x-order`, `propertyOrdering`, ..) the derive doesn't model.
+/// Keys replace generated keywords of the same name, and the JSON is checked
+/// at compile time.
+///
+/// ```
+/// use rstructor::{Instructor, SchemaType};
+/// use serde::{Serialize, Deserialize};
+///
+/// #[derive(Instructor, Serialize, Deserialize)]
+/// #[llm(extra_schema = r#"{"x-order": ["name", "age"]}"#)]
+/// struct Person {
+///     name: String,
+///     #[llm(extra_schema = r#"{"x-unit": "years"}"#)]
+///     age: u32,
+/// }
+///
+/// let schema = Person::schema().to_json();
+/// assert_eq!(schema["x-order"], serde_json::json!(["name", "age"]));
+/// assert_eq!(schema["properties"]["age"]["x-unit"], "years");
+/// ```
+///
+/// # Serialized Representation
+///
+/// A field whose `#[serde(with = "..")]` (or `serialize_with`) helper changes
+/// its JSON shape gets a schema for that shape: chrono's `ts_seconds`,
+/// `ts_milliseconds`, `ts_microseconds` and `ts_nanoseconds`, the `time`
+/// crate's `timestamp`, `rfc3339` and `iso8601`, and `humantime_serde` are
+/// recognized, including their `option` variants. For any other helper, name
+/// the JSON type with `#[llm(serialized_as = "integer")]` and add
+/// `format = ".."` if useful. String, numeric and array constraints on such a
+/// field only go into the schema; `validate` cannot check them against the
+/// Rust value.
+///
+/// ```
+/// use chrono::{DateTime, Utc};
+/// use rstructor::{Instructor, SchemaType};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Instructor, Serialize, Deserialize)]
+/// struct Event {
+///     #[serde(with = "chrono::serde::ts_seconds")]
+///     starts_at: DateTime<Utc>,
+///     #[llm(serialized_as = "string", format = "duration")]
+///     #[serde(with = "duration_text")]
+///     length: std::time::Duration,
+/// }
+///
+/// mod duration_text {
+///     use serde::{Deserialize, Deserializer, Serializer};
+///     use std::time::Duration;
+///
+///     pub fn serialize<S: Serializer>(d: &Duration, s: S) -> Result<S::Ok, S::Error> {
+///         s.serialize_str(&format!("PT{}S", d.as_secs()))
+///     }
+///
+///     pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Duration, D::Error> {
+///         let text = String::deserialize(d)?;
+///         let secs = text.trim_start_matches("PT").trim_end_matches('S');
+///         secs.parse().map(Duration::from_secs).map_err(serde::de::Error::custom)
+///     }
+/// }
+///
+/// let schema = Event::schema().to_json();
+/// assert_eq!(schema["properties"]["starts_at"]["type"], "integer");
+/// assert_eq!(schema["properties"]["starts_at"]["format"], "unix-timestamp");
+/// assert_eq!(schema["properties"]["length"]["type"], "string");
+/// assert_eq!(schema["properties"]["length"]["format"], "duration");
+/// ```
+///
+/// # Skipping Fields
+///
+/// `#[llm(skip)]` leaves a field out of the schema and its `required` list, so
+/// the model is never asked for it (internal IDs, values computed after
+/// extraction). It only affects the schema: serde still deserializes the
+/// struct, so the field must be able to come back missing. That is true of
+/// `Option` fields; anything else needs `#[serde(default)]`, or deserializing
+/// the model's reply fails.
+///
+/// Fields serde never deserializes, `#[serde(skip)]` and
+/// `#[serde(skip_deserializing)]`, are left out the same way without an
+/// `#[llm(skip)]`, since serde would discard whatever the model sent.
+///
+/// ```
+/// use rstructor::{Instructor, SchemaType};
+/// use serde::{Serialize, Deserialize};
+///
+/// #[derive(Instructor, Serialize, Deserialize)]
+/// struct Invoice {
+///     total: f64,
+///     #[llm(skip)]
+///     #[serde(default)]
+///     internal_id: u64,
+///     #[serde(skip)]
+///     processed: bool,
+/// }
+///
+/// let schema = Invoice::schema().to_json();
+/// assert!(schema["properties"].get("internal_id").is_none());
+/// assert!(schema["properties"].get("processed").is_none());
+/// assert_eq!(schema["required"], serde_json::json!(["total"]));
+/// ```
+///
+/// # Schema Caching
+///
+/// The derived `schema()` builds the schema once per type and returns a clone
+/// of it afterwards, so retries and batch loops do not rebuild the whole JSON
+/// tree each time. Generic types are rebuilt on every call, since one cache
+/// would be shared by every instantiation. Opt out with
+/// `#[llm(no_schema_cache)]` when a field's schema can change at runtime,
+/// e.g. a `schema_with` function that reads configuration.
+///
+/// ```
+/// use rstructor::{Instructor, SchemaType};
+/// use serde::{Serialize, Deserialize};
+///
+/// #[derive(Instructor, Serialize, Deserialize)]
+/// #[llm(no_schema_cache)]
+/// struct Ticket {
+///     subject: String,
+/// }
+///
+/// assert_eq!(Ticket::schema().to_json()["title"], "Ticket");
+/// ```
+///
+/// ## Translated descriptions
+///
+/// `description(en = "..", de = "..")` gives a description per locale, on
+/// containers, fields, and variants alike. The first one is the schema's
+/// description; the others are recorded with it and swapped in by
+/// `Schema::localized` or a client's `.schema_locale(..)`.
+///
+/// ```
+/// use rstructor::{Instructor, SchemaType};
+/// use serde::{Serialize, Deserialize};
+///
+/// #[derive(Instructor, Serialize, Deserialize)]
+/// struct Receipt {
+///     #[llm(description(en = "Name of the shop", de = "Name des Geschäfts"))]
+///     merchant: String,
+/// }
+///
+/// let schema = Receipt::schema();
+/// assert_eq!(schema.to_json()["properties"]["merchant"]["description"], "Name of the shop");
+/// let german = schema.localized("de").to_json();
+/// assert_eq!(german["properties"]["merchant"]["description"], "Name des Geschäfts");
+/// ```
+///
 /// # Examples
 ///
 /// ## Field-level attributes
@@ -137,9 +462,14 @@ use syn::{Data, DeriveInput, Fields, parse_macro_input};
 ///
 /// ### Container Attributes
 ///
-/// - `description`: A description of the struct or enum
+/// - `description`: A description of the struct or enum, or one per locale with
+///   `description(en = "..", de = "..")`
 /// - `title`: A custom title for the JSON Schema (defaults to the type name)
 /// - `examples`: Example instances of the struct or enum
+/// - `counter_example`: An output the model must not imitate, rendered as "Do NOT
+///   return output like this" guidance when the schema is spelled out in the prompt
+///   (JSON mode). Takes a Rust value such as `"N/A"` or
+///   `serde_json::json!({"name": "N/A"})`, and may be repeated
 ///
 /// ### Serde Integration
 ///
@@ -165,6 +495,13 @@ pub fn derive_instructor(input: TokenStream) -> TokenStream {
         }
         _ => panic!("Instructor can only be derived for structs and enums"),
     };
+    let schema_impl = generators::finish_schema_fn(
+        schema_impl,
+        name,
+        &container_attrs,
+        &input.data,
+        &input.generics,
+    );
 
     // Generate the Instructor trait implementation.
     //
@@ -172,7 +509,8 @@ pub fn derive_instructor(input: TokenStream) -> TokenStream {
     // `Instructor` (nested structs/enums, and their contents through `Option`,
     // `Vec`, `Box`, and string-keyed maps), then runs this type's own
     // `#[llm(validate = "...")]` function, if any.
-    let field_validation = generate_field_validation(&input.data);
+    let field_validation = generate_field_validation(&input.data, &container_attrs);
+    let constraint_validation = generate_constraint_validation(&input.data, &container_attrs);
     let container_validate = if let Some(validate_fn) = &container_attrs.validate {
         let validate_path: syn::Path =
             syn::parse_str(validate_fn).expect("validate attribute must be a valid function path");
@@ -199,17 +537,28 @@ pub fn derive_instructor(input: TokenStream) -> TokenStream {
                 #[allow(unused_imports)]
                 use ::rstructor::model::__private::ProbeFallback as _;
                 #field_validation
+                #constraint_validation
                 #container_validate
                 ::rstructor::error::Result::Ok(())
             }
         }
     };
 
-    // Combine the two implementations
+    // Register non-generic types for lookup by name; a no-op unless
+    // rstructor's `registry` feature is enabled.
+    let registration = input
+        .generics
+        .params
+        .is_empty()
+        .then(|| quote::quote! { ::rstructor::__register_schema!(#name); });
+
+    // Combine the implementations
     let combined = quote::quote! {
         #schema_impl
 
         #instructor_impl
+
+        #registration
     };
 
     combined.into()
@@ -221,24 +570,41 @@ pub fn derive_instructor(input: TokenStream) -> TokenStream {
 /// Each field is wrapped in `__private::Probe`, whose `rstructor_probe` resolves
 /// (via autoref specialization) to the field's `Instructor::validate` when the
 /// field type implements `Instructor`, and to a no-op otherwise — so primitive
-/// fields cost nothing while nested `Instructor` values are validated.
-fn generate_field_validation(data: &Data) -> proc_macro2::TokenStream {
+/// fields cost nothing while nested `Instructor` values are validated. The
+/// probe is given the field's schema name to qualify a nested error's path;
+/// positional fields are named `[index]`, and a lone one (a newtype) is
+/// transparent.
+fn generate_field_validation(
+    data: &Data,
+    container_attrs: &ContainerAttributes,
+) -> proc_macro2::TokenStream {
+    fn positional_names(count: usize) -> Vec<String> {
+        if count == 1 {
+            vec![String::new()]
+        } else {
+            (0..count).map(|i| format!("[{i}]")).collect()
+        }
+    }
+
     match data {
         Data::Struct(data_struct) => match &data_struct.fields {
             Fields::Named(named) => {
                 let probes = named.named.iter().map(|f| {
                     let ident = f.ident.as_ref().unwrap();
+                    let attrs = parsers::field_parser::parse_field_attributes(f);
+                    let name = schema_field_name(ident, &attrs, container_attrs);
                     quote::quote! {
-                        ::rstructor::model::__private::Probe(&self.#ident).rstructor_probe()?;
+                        ::rstructor::model::__private::Probe(&self.#ident).rstructor_probe(#name)?;
                     }
                 });
                 quote::quote! { #(#probes)* }
             }
             Fields::Unnamed(unnamed) => {
-                let probes = unnamed.unnamed.iter().enumerate().map(|(i, _)| {
+                let names = positional_names(unnamed.unnamed.len());
+                let probes = names.iter().enumerate().map(|(i, name)| {
                     let index = syn::Index::from(i);
                     quote::quote! {
-                        ::rstructor::model::__private::Probe(&self.#index).rstructor_probe()?;
+                        ::rstructor::model::__private::Probe(&self.#index).rstructor_probe(#name)?;
                     }
                 });
                 quote::quote! { #(#probes)* }
@@ -255,9 +621,19 @@ fn generate_field_validation(data: &Data) -> proc_macro2::TokenStream {
                             .iter()
                             .map(|f| f.ident.clone().unwrap())
                             .collect();
+                        let names: Vec<_> = named
+                            .named
+                            .iter()
+                            .map(|f| {
+                                let attrs = parsers::field_parser::parse_field_attributes(f);
+                                attrs
+                                    .serde_rename
+                                    .unwrap_or_else(|| f.ident.as_ref().unwrap().to_string())
+                            })
+                            .collect();
                         quote::quote! {
                             Self::#vname { #(#binds),* } => {
-                                #( ::rstructor::model::__private::Probe(#binds).rstructor_probe()?; )*
+                                #( ::rstructor::model::__private::Probe(#binds).rstructor_probe(#names)?; )*
                             }
                         }
                     }
@@ -265,9 +641,10 @@ fn generate_field_validation(data: &Data) -> proc_macro2::TokenStream {
                         let binds: Vec<_> = (0..unnamed.unnamed.len())
                             .map(|i| quote::format_ident!("field{}", i))
                             .collect();
+                        let names = positional_names(binds.len());
                         quote::quote! {
                             Self::#vname( #(#binds),* ) => {
-                                #( ::rstructor::model::__private::Probe(#binds).rstructor_probe()?; )*
+                                #( ::rstructor::model::__private::Probe(#binds).rstructor_probe(#names)?; )*
                             }
                         }
                     }
@@ -284,26 +661,104 @@ fn generate_field_validation(data: &Data) -> proc_macro2::TokenStream {
     }
 }
 
+/// The name a struct field has in the schema, after serde renames.
+fn schema_field_name(
+    ident: &syn::Ident,
+    attrs: &parsers::field_parser::FieldAttributes,
+    container_attrs: &ContainerAttributes,
+) -> String {
+    match (&attrs.serde_rename, &container_attrs.serde_rename_all) {
+        (Some(rename), _) => rename.clone(),
+        (None, Some(rename_all)) => {
+            generators::struct_schema::apply_rename_all(&ident.to_string(), rename_all)
+        }
+        (None, None) => ident.to_string(),
+    }
+}
+
+/// Generate checks of `#[llm(minimum = .., maximum = .., ..)]` bounds,
+/// `#[llm(min_length = .., pattern = .., ..)]` string constraints and
+/// `#[llm(min_items = .., unique_items, ..)]` array constraints and
+/// `#[llm(language = "..")]` on the named fields of a struct, reporting each
+/// field under its schema name.
+fn generate_constraint_validation(
+    data: &Data,
+    container_attrs: &ContainerAttributes,
+) -> proc_macro2::TokenStream {
+    let Data::Struct(syn::DataStruct {
+        fields: Fields::Named(named),
+        ..
+    }) = data
+    else {
+        return quote::quote! {};
+    };
+    let checks = named.named.iter().filter_map(|field| {
+        let attrs = parsers::field_parser::parse_field_attributes(field);
+        // A field serialized as something other than its Rust type has
+        // constraints on the wire value only, so they stay schema-only
+        if attrs.skip
+            || attrs.wire.is_some()
+            || (attrs.numeric.is_empty()
+                && attrs.string.is_empty()
+                && attrs.array.is_empty()
+                && attrs.language.is_none())
+        {
+            return None;
+        }
+        let ident = field.ident.as_ref().unwrap();
+        let name = schema_field_name(ident, &attrs, container_attrs);
+        let numeric = (!attrs.numeric.is_empty()).then(|| {
+            let bounds = attrs.numeric.bounds();
+            quote::quote! {
+                ::rstructor::model::__private::check_numeric(#name, &self.#ident, &#bounds)?;
+            }
+        });
+        let string = (!attrs.string.is_empty()).then(|| {
+            let bounds = attrs.string.bounds();
+            quote::quote! {
+                ::rstructor::model::__private::check_string(#name, &self.#ident, &#bounds)?;
+            }
+        });
+        let array = (!attrs.array.is_empty()).then(|| {
+            let bounds = attrs.array.bounds();
+            quote::quote! {
+                ::rstructor::model::__private::check_array(#name, &self.#ident, &#bounds)?;
+            }
+        });
+        let language = attrs.language.as_ref().map(|language| {
+            quote::quote! {
+                ::rstructor::model::__private::check_language(#name, &self.#ident, #language)?;
+            }
+        });
+        Some(quote::quote! { #numeric #string #array #language })
+    });
+    quote::quote! { #(#checks)* }
+}
+
 use quote::ToTokens;
 
 fn extract_container_attributes(attrs: &[syn::Attribute]) -> ContainerAttributes {
     let mut description = None;
+    let mut description_translations = Vec::new();
     let mut title = None;
     let mut examples = Vec::new();
+    let mut counter_examples = Vec::new();
     let mut serde_rename_all = None;
     let mut validate = None;
     let mut serde_tag = None;
     let mut serde_content = None;
     let mut serde_untagged = false;
+    let mut no_schema_cache = false;
+    let mut extra_schema = None;
 
     // First, check for llm-specific attributes
     for attr in attrs {
         if attr.path().is_ident("llm") {
             let _ = attr.parse_nested_meta(|meta| {
                 if meta.path.is_ident("description") {
-                    let value = meta.value()?;
-                    let content: syn::LitStr = value.parse()?;
-                    description = Some(content.value());
+                    let parsed = parsers::description_parser::parse_description(&meta)?;
+                    description = Some(parsed.text);
+                    description_translations = parsed.translations;
                 } else if meta.path.is_ident("title") {
                     let value = meta.value()?;
                     let content: syn::LitStr = value.parse()?;
@@ -312,6 +767,10 @@ fn extract_container_attributes(attrs: &[syn::Attribute]) -> ContainerAttributes
                     let value = meta.value()?;
                     let content: syn::LitStr = value.parse()?;
                     validate = Some(content.value());
+                } else if meta.path.is_ident("no_schema_cache") {
+                    no_schema_cache = true;
+                } else if meta.path.is_ident("extra_schema") {
+                    extra_schema = Some(parsers::extra_schema_parser::parse_extra_schema(&meta)?);
                 } else if meta.path.is_ident("examples") {
                     // Handle array syntax like examples = ["one", "two"]
                     let value = meta.value()?;
@@ -338,6 +797,10 @@ fn extract_container_attributes(attrs: &[syn::Attribute]) -> ContainerAttributes
                             }
                         }
                     }
+                } else if meta.path.is_ident("counter_example") {
+                    counter_examples.push(parsers::counter_example_parser::parse_counter_example(
+                        &meta,
+                    )?);
                 }
                 Ok(())
             });
@@ -349,9 +812,9 @@ fn extract_container_attributes(attrs: &[syn::Attribute]) -> ContainerAttributes
         if attr.path().is_ident("serde") {
             let _ = attr.parse_nested_meta(|meta| {
                 if meta.path.is_ident("rename_all") {
-                    let value = meta.value()?;
-                    let content: syn::LitStr = value.parse()?;
-                    serde_rename_all = Some(content.value());
+                    if let Some(style) = parse_deserialize_name(&meta)? {
+                        serde_rename_all = Some(style);
+                    }
                 } else if meta.path.is_ident("tag") {
                     let value = meta.value()?;
                     let content: syn::LitStr = value.parse()?;
@@ -362,6 +825,8 @@ fn extract_container_attributes(attrs: &[syn::Attribute]) -> ContainerAttributes
                     serde_content = Some(content.value());
                 } else if meta.path.is_ident("untagged") {
                     serde_untagged = true;
+                } else {
+                    skip_meta_value(&meta)?;
                 }
                 Ok(())
             });
@@ -370,12 +835,16 @@ fn extract_container_attributes(attrs: &[syn::Attribute]) -> ContainerAttributes
 
     ContainerAttributes::builder()
         .description(description)
+        .description_translations(description_translations)
         .title(title)
         .examples(examples)
+        .counter_examples(counter_examples)
         .serde_rename_all(serde_rename_all)
         .validate(validate)
         .serde_tag(serde_tag)
         .serde_content(serde_content)
         .serde_untagged(serde_untagged)
+        .no_schema_cache(no_schema_cache)
+        .extra_schema(extra_schema)
         .build()
 }
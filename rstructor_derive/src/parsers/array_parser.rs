@@ -2,65 +2,6 @@ use proc_macro2::TokenStream;
 use quote::{ToTokens, quote};
 use syn::{Expr, ExprArray, Lit, Token, bracketed, parse::Parse};
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use quote::quote;
-    use syn::parse_str;
-
-    // Test the ArrayAttr structure directly
-    #[test]
-    fn test_array_attr_parse() {
-        // Create a string array
-        let input = "[\"apple\", \"banana\", \"cherry\"]";
-        let array_expr: syn::ExprArray = parse_str(input).unwrap();
-
-        // Create an ArrayAttr
-        let array_attr = ArrayAttr {
-            expr_array: array_expr,
-        };
-
-        // Check the array elements
-        assert_eq!(array_attr.expr_array.elems.len(), 3);
-    }
-
-    #[test]
-    fn test_array_attr_types() {
-        // Test with different types
-        let string_array = "[\"apple\", \"banana\"]";
-        let int_array = "[1, 2, 3]";
-        let bool_array = "[true, false]";
-        let mixed_array = "[\"string\", 42, true]";
-
-        // Parse each type
-        let string_expr: syn::ExprArray = parse_str(string_array).unwrap();
-        let int_expr: syn::ExprArray = parse_str(int_array).unwrap();
-        let bool_expr: syn::ExprArray = parse_str(bool_array).unwrap();
-        let mixed_expr: syn::ExprArray = parse_str(mixed_array).unwrap();
-
-        // Check lengths
-        assert_eq!(string_expr.elems.len(), 2);
-        assert_eq!(int_expr.elems.len(), 3);
-        assert_eq!(bool_expr.elems.len(), 2);
-        assert_eq!(mixed_expr.elems.len(), 3);
-    }
-
-    #[test]
-    fn test_tokenize_array_elements() {
-        // Test tokenizing array elements for strings
-        let string_array = "[\"apple\", \"banana\"]";
-        let string_expr: syn::ExprArray = parse_str(string_array).unwrap();
-
-        // Check first element using quote
-        let first_elem = &string_expr.elems[0];
-        let tokens = quote! { #first_elem };
-        let token_string = tokens.to_string();
-
-        // The tokenized string should include quotes
-        assert!(token_string.contains("apple"));
-    }
-}
-
 /// Utility struct to parse array literal expressions
 /// Handles array literals like [1, 2, 3] or ["a", "b", "c"]
 pub struct ArrayAttr {
@@ -154,3 +95,62 @@ pub fn parse_array_literal(value: &syn::parse::ParseBuffer) -> Option<Vec<TokenS
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::quote;
+    use syn::parse_str;
+
+    // Test the ArrayAttr structure directly
+    #[test]
+    fn test_array_attr_parse() {
+        // Create a string array
+        let input = "[\"apple\", \"banana\", \"cherry\"]";
+        let array_expr: syn::ExprArray = parse_str(input).unwrap();
+
+        // Create an ArrayAttr
+        let array_attr = ArrayAttr {
+            expr_array: array_expr,
+        };
+
+        // Check the array elements
+        assert_eq!(array_attr.expr_array.elems.len(), 3);
+    }
+
+    #[test]
+    fn test_array_attr_types() {
+        // Test with different types
+        let string_array = "[\"apple\", \"banana\"]";
+        let int_array = "[1, 2, 3]";
+        let bool_array = "[true, false]";
+        let mixed_array = "[\"string\", 42, true]";
+
+        // Parse each type
+        let string_expr: syn::ExprArray = parse_str(string_array).unwrap();
+        let int_expr: syn::ExprArray = parse_str(int_array).unwrap();
+        let bool_expr: syn::ExprArray = parse_str(bool_array).unwrap();
+        let mixed_expr: syn::ExprArray = parse_str(mixed_array).unwrap();
+
+        // Check lengths
+        assert_eq!(string_expr.elems.len(), 2);
+        assert_eq!(int_expr.elems.len(), 3);
+        assert_eq!(bool_expr.elems.len(), 2);
+        assert_eq!(mixed_expr.elems.len(), 3);
+    }
+
+    #[test]
+    fn test_tokenize_array_elements() {
+        // Test tokenizing array elements for strings
+        let string_array = "[\"apple\", \"banana\"]";
+        let string_expr: syn::ExprArray = parse_str(string_array).unwrap();
+
+        // Check first element using quote
+        let first_elem = &string_expr.elems[0];
+        let tokens = quote! { #first_elem };
+        let token_string = tokens.to_string();
+
+        // The tokenized string should include quotes
+        assert!(token_string.contains("apple"));
+    }
+}
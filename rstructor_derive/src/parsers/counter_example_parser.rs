@@ -0,0 +1,65 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::meta::ParseNestedMeta;
+use syn::{Expr, ExprLit, Lit};
+
+/// Parse the value of an `#[llm(counter_example = ..)]` attribute into an
+/// expression building its `serde_json::Value`.
+pub fn parse_counter_example(meta: &ParseNestedMeta) -> syn::Result<TokenStream> {
+    match meta.value()?.parse::<Expr>() {
+        Ok(expr) => Ok(counter_example_value(&expr)),
+        Err(e) => panic!(
+            "counter_example must be a Rust value such as \"N/A\", 0 or serde_json::json!({{..}}): {e}"
+        ),
+    }
+}
+
+/// A string literal is kept as a string; anything else (other literals,
+/// `serde_json::json!({..})`) goes through `serde_json::Value::from`, so a
+/// value without a JSON representation fails to compile.
+///
+/// A string holding a JSON object or array is rejected: counter-examples are
+/// written in Rust syntax, never as serialized JSON.
+fn counter_example_value(expr: &Expr) -> TokenStream {
+    if let Expr::Lit(ExprLit {
+        lit: Lit::Str(lit_str),
+        ..
+    }) = expr
+    {
+        let text = lit_str.value();
+        let trimmed = text.trim_start();
+        if trimmed.starts_with('{') || trimmed.starts_with('[') {
+            panic!(
+                "counter_example takes a Rust value, not a JSON string; write serde_json::json!({text}) instead"
+            );
+        }
+        return quote! { ::serde_json::Value::String(#text.to_string()) };
+    }
+    quote! { ::serde_json::Value::from(#expr) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_str;
+
+    #[test]
+    fn strings_stay_strings_and_other_values_convert() {
+        let string = counter_example_value(&parse_str("\"unknown\"").unwrap());
+        assert!(string.to_string().contains("Value :: String"));
+
+        let object =
+            counter_example_value(&parse_str("::serde_json::json!({\"name\": \"N/A\"})").unwrap());
+        assert!(
+            object
+                .to_string()
+                .starts_with(":: serde_json :: Value :: from")
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "not a JSON string")]
+    fn json_strings_are_rejected() {
+        counter_example_value(&parse_str(r##"r#"{"name": "N/A"}"#"##).unwrap());
+    }
+}
@@ -0,0 +1,40 @@
+use syn::LitStr;
+use syn::meta::ParseNestedMeta;
+
+/// A description from `description = ".."`, or from
+/// `description(en = "..", de = "..")` with one text per locale.
+pub struct Description {
+    /// The text put in the schema: the plain string, or the first locale's.
+    pub text: String,
+    /// `(locale, text)` for every other locale, with `_` in the locale
+    /// written as `-` (`pt_BR` becomes `pt-BR`).
+    pub translations: Vec<(String, String)>,
+}
+
+/// Parse the value of an `#[llm(description ..)]` attribute.
+pub fn parse_description(meta: &ParseNestedMeta) -> syn::Result<Description> {
+    if meta.input.peek(syn::Token![=]) {
+        let content: LitStr = meta.value()?.parse()?;
+        return Ok(Description {
+            text: content.value(),
+            translations: Vec::new(),
+        });
+    }
+    let mut texts = Vec::new();
+    meta.parse_nested_meta(|locale| {
+        let Some(ident) = locale.path.get_ident() else {
+            return Err(locale.error("expected a locale such as `en` or `pt_BR`"));
+        };
+        let content: LitStr = locale.value()?.parse()?;
+        texts.push((ident.to_string().replace('_', "-"), content.value()));
+        Ok(())
+    })?;
+    let mut texts = texts.into_iter();
+    let Some((_, text)) = texts.next() else {
+        return Err(meta.error("expected at least one `locale = \"..\"` description"));
+    };
+    Ok(Description {
+        text,
+        translations: texts.collect(),
+    })
+}
@@ -0,0 +1,14 @@
+use syn::LitStr;
+use syn::meta::ParseNestedMeta;
+
+/// Parse the value of an `#[llm(extra_schema = "..")]` attribute, returning
+/// the JSON text after checking that it is an object.
+pub fn parse_extra_schema(meta: &ParseNestedMeta) -> syn::Result<String> {
+    let content: LitStr = meta.value()?.parse()?;
+    let json = content.value();
+    match serde_json::from_str::<serde_json::Value>(&json) {
+        Ok(serde_json::Value::Object(_)) => Ok(json),
+        Ok(_) => panic!("extra_schema must be a JSON object, got {json}"),
+        Err(e) => panic!("extra_schema is not valid JSON ({e}): {json}"),
+    }
+}
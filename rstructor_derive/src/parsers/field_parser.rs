@@ -3,23 +3,261 @@ use quote::quote;
 use syn::Field;
 
 use crate::parsers::array_parser::parse_array_literal;
+use crate::parsers::description_parser::parse_description;
+use crate::parsers::extra_schema_parser::parse_extra_schema;
+use crate::parsers::serde_parser::{
+    WireFormat, known_serde_helper, parse_deserialize_name, skip_meta_value,
+};
 use crate::type_utils::{TypeCategory, get_option_inner_type, get_type_category, is_option_type};
 
 /// Represents parsed field attributes
 pub struct FieldAttributes {
     pub description: Option<String>,
+    /// `(locale, text)` from #[llm(description(en = "..", de = ".."))]
+    pub description_translations: Vec<(String, String)>,
     pub example_value: Option<TokenStream>,
     pub examples_array: Vec<TokenStream>,
     /// Field rename from #[serde(rename = "...")]
     pub serde_rename: Option<String>,
+    /// Explicit position from #[llm(order = n)]
+    pub order: Option<i64>,
+    /// Left out of the schema entirely via #[llm(skip)], or because serde never
+    /// deserializes it (#[serde(skip)] / #[serde(skip_deserializing)])
+    pub skip: bool,
+    /// Bounds from #[llm(minimum = .., maximum = .., multiple_of = ..)]
+    pub numeric: NumericConstraints,
+    /// Checks from #[llm(min_length = .., max_length = .., pattern = .., format = ..)]
+    pub string: StringConstraints,
+    /// Checks from #[llm(min_items = .., max_items = .., unique_items)]
+    pub array: ArrayConstraints,
+    /// Language the text must be written in, from #[llm(language = "..")]
+    pub language: Option<String>,
+    /// Keywords merged into the field's schema, from #[llm(extra_schema = "..")]
+    pub extra_schema: Option<String>,
+    /// Function supplying the field's schema, from #[llm(schema_with = "path")]
+    pub schema_with: Option<syn::Path>,
+    /// What the field is serialized as, from #[llm(serialized_as = "..")] or a
+    /// recognized #[serde(with = "..")] helper; the schema follows it instead
+    /// of the Rust type
+    pub wire: Option<WireFormat>,
+}
+
+/// A numeric literal from an attribute, remembering whether it was an integer
+/// so the schema keeps `0` rather than `0.0`.
+#[derive(Clone, Copy)]
+pub struct NumberLit {
+    value: f64,
+    integer: bool,
+}
+
+impl NumberLit {
+    /// Parse an optionally negated integer or float literal.
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let negative = input.parse::<Option<syn::Token![-]>>()?.is_some();
+        let (value, integer) = match input.parse::<syn::Lit>()? {
+            syn::Lit::Int(lit) => (lit.base10_parse::<f64>()?, true),
+            syn::Lit::Float(lit) => (lit.base10_parse::<f64>()?, false),
+            lit => return Err(syn::Error::new(lit.span(), "expected a number")),
+        };
+        Ok(Self {
+            value: if negative { -value } else { value },
+            integer,
+        })
+    }
+
+    fn json(self) -> TokenStream {
+        if self.integer {
+            let value = self.value as i64;
+            quote! { ::serde_json::json!(#value) }
+        } else {
+            let value = self.value;
+            quote! { ::serde_json::json!(#value) }
+        }
+    }
+}
+
+/// JSON Schema numeric keywords set on a field.
+#[derive(Default)]
+pub struct NumericConstraints {
+    pub minimum: Option<NumberLit>,
+    pub maximum: Option<NumberLit>,
+    pub exclusive_minimum: Option<NumberLit>,
+    pub exclusive_maximum: Option<NumberLit>,
+    pub multiple_of: Option<NumberLit>,
+}
+
+impl NumericConstraints {
+    pub fn is_empty(&self) -> bool {
+        self.keywords().all(|(_, bound)| bound.is_none())
+    }
+
+    fn keywords(&self) -> impl Iterator<Item = (&'static str, Option<NumberLit>)> {
+        [
+            ("minimum", self.minimum),
+            ("maximum", self.maximum),
+            ("exclusiveMinimum", self.exclusive_minimum),
+            ("exclusiveMaximum", self.exclusive_maximum),
+            ("multipleOf", self.multiple_of),
+        ]
+        .into_iter()
+    }
+
+    /// Statements inserting the keywords into the property map `props`.
+    pub fn schema_setters(&self) -> TokenStream {
+        let setters = self.keywords().filter_map(|(keyword, bound)| {
+            let value = bound?.json();
+            Some(quote! { props.insert(#keyword.to_string(), #value); })
+        });
+        quote! { #(#setters)* }
+    }
+
+    /// A `__private::NumericBounds` expression for the runtime check.
+    pub fn bounds(&self) -> TokenStream {
+        let field = |bound: Option<NumberLit>| match bound {
+            Some(bound) => {
+                let value = bound.value;
+                quote! { ::core::option::Option::Some(#value) }
+            }
+            None => quote! { ::core::option::Option::None },
+        };
+        let (minimum, maximum) = (field(self.minimum), field(self.maximum));
+        let exclusive_minimum = field(self.exclusive_minimum);
+        let exclusive_maximum = field(self.exclusive_maximum);
+        let multiple_of = field(self.multiple_of);
+        quote! {
+            ::rstructor::model::__private::NumericBounds {
+                minimum: #minimum,
+                maximum: #maximum,
+                exclusive_minimum: #exclusive_minimum,
+                exclusive_maximum: #exclusive_maximum,
+                multiple_of: #multiple_of,
+            }
+        }
+    }
+}
+
+/// JSON Schema string keywords set on a field.
+#[derive(Default)]
+pub struct StringConstraints {
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+    pub pattern: Option<String>,
+    pub format: Option<String>,
+}
+
+impl StringConstraints {
+    pub fn is_empty(&self) -> bool {
+        self.min_length.is_none()
+            && self.max_length.is_none()
+            && self.pattern.is_none()
+            && self.format.is_none()
+    }
+
+    /// Statements inserting the keywords into the property map `props`.
+    pub fn schema_setters(&self) -> TokenStream {
+        let lengths = [
+            ("minLength", self.min_length),
+            ("maxLength", self.max_length),
+        ]
+        .into_iter()
+        .filter_map(|(keyword, length)| {
+            let length = length?;
+            Some(quote! { props.insert(#keyword.to_string(), ::serde_json::json!(#length)); })
+        });
+        let texts = [("pattern", &self.pattern), ("format", &self.format)]
+            .into_iter()
+            .filter_map(|(keyword, text)| {
+                let text = text.as_ref()?;
+                Some(quote! { props.insert(#keyword.to_string(), ::serde_json::json!(#text)); })
+            });
+        quote! { #(#lengths)* #(#texts)* }
+    }
+
+    /// A `__private::StringBounds` expression for the runtime check.
+    pub fn bounds(&self) -> TokenStream {
+        fn option<T: quote::ToTokens>(value: Option<T>) -> TokenStream {
+            match value {
+                Some(value) => quote! { ::core::option::Option::Some(#value) },
+                None => quote! { ::core::option::Option::None },
+            }
+        }
+        let min_length = option(self.min_length);
+        let max_length = option(self.max_length);
+        let pattern = option(self.pattern.as_deref());
+        let format = option(self.format.as_deref());
+        quote! {
+            ::rstructor::model::__private::StringBounds {
+                min_length: #min_length,
+                max_length: #max_length,
+                pattern: #pattern,
+                format: #format,
+            }
+        }
+    }
+}
+
+/// JSON Schema array keywords set on a field.
+#[derive(Default)]
+pub struct ArrayConstraints {
+    pub min_items: Option<usize>,
+    pub max_items: Option<usize>,
+    pub unique_items: bool,
+}
+
+impl ArrayConstraints {
+    pub fn is_empty(&self) -> bool {
+        self.min_items.is_none() && self.max_items.is_none() && !self.unique_items
+    }
+
+    /// Statements inserting the keywords into the property map `props`.
+    pub fn schema_setters(&self) -> TokenStream {
+        let counts = [("minItems", self.min_items), ("maxItems", self.max_items)]
+            .into_iter()
+            .filter_map(|(keyword, count)| {
+                let count = count?;
+                Some(quote! { props.insert(#keyword.to_string(), ::serde_json::json!(#count)); })
+            });
+        let unique = self.unique_items.then(|| {
+            quote! { props.insert("uniqueItems".to_string(), ::serde_json::Value::Bool(true)); }
+        });
+        quote! { #(#counts)* #unique }
+    }
+
+    /// A `__private::ArrayBounds` expression for the runtime check.
+    pub fn bounds(&self) -> TokenStream {
+        let option = |count: Option<usize>| match count {
+            Some(count) => quote! { ::core::option::Option::Some(#count) },
+            None => quote! { ::core::option::Option::None },
+        };
+        let (min_items, max_items) = (option(self.min_items), option(self.max_items));
+        let unique_items = self.unique_items;
+        quote! {
+            ::rstructor::model::__private::ArrayBounds {
+                min_items: #min_items,
+                max_items: #max_items,
+                unique_items: #unique_items,
+            }
+        }
+    }
 }
 
 /// Parse a single field's llm and serde attributes
 pub fn parse_field_attributes(field: &Field) -> FieldAttributes {
     let mut description = None;
+    let mut description_translations = Vec::new();
     let mut example_value = None;
     let mut examples_array = Vec::new();
     let mut serde_rename = None;
+    let mut order = None;
+    let mut skip = false;
+    let mut numeric = NumericConstraints::default();
+    let mut string = StringConstraints::default();
+    let mut array = ArrayConstraints::default();
+    let mut language = None;
+    let mut extra_schema = None;
+    let mut schema_with = None;
+    let mut serialized_as = None;
+    let mut serde_helper = None;
 
     // Get the base type (unwrapping Option if present)
     let is_optional = is_option_type(&field.ty);
@@ -35,9 +273,22 @@ pub fn parse_field_attributes(field: &Field) -> FieldAttributes {
         if attr.path().is_ident("serde") {
             let _ = attr.parse_nested_meta(|meta| {
                 if meta.path.is_ident("rename") {
-                    let value = meta.value()?;
-                    let content: syn::LitStr = value.parse()?;
-                    serde_rename = Some(content.value());
+                    if let Some(name) = parse_deserialize_name(&meta)? {
+                        serde_rename = Some(name);
+                    }
+                } else if meta.path.is_ident("skip") || meta.path.is_ident("skip_deserializing") {
+                    // The model's value would be thrown away, so never ask for it
+                    skip = true;
+                } else if meta.path.is_ident("with")
+                    || meta.path.is_ident("serialize_with")
+                    || meta.path.is_ident("deserialize_with")
+                {
+                    let content: syn::LitStr = meta.value()?.parse()?;
+                    if serde_helper.is_none() {
+                        serde_helper = known_serde_helper(&content.value());
+                    }
+                } else {
+                    skip_meta_value(&meta)?;
                 }
                 Ok(())
             });
@@ -47,9 +298,63 @@ pub fn parse_field_attributes(field: &Field) -> FieldAttributes {
             // Parse attribute arguments
             let _result = attr.parse_nested_meta(|meta| {
                 if meta.path.is_ident("description") {
-                    let value = meta.value()?;
-                    let content: syn::LitStr = value.parse()?;
-                    description = Some(content.value());
+                    let parsed = parse_description(&meta)?;
+                    description = Some(parsed.text);
+                    description_translations = parsed.translations;
+                } else if meta.path.is_ident("skip") {
+                    skip = true;
+                } else if meta.path.is_ident("minimum") {
+                    numeric.minimum = Some(NumberLit::parse(meta.value()?)?);
+                } else if meta.path.is_ident("maximum") {
+                    numeric.maximum = Some(NumberLit::parse(meta.value()?)?);
+                } else if meta.path.is_ident("exclusive_minimum") {
+                    numeric.exclusive_minimum = Some(NumberLit::parse(meta.value()?)?);
+                } else if meta.path.is_ident("exclusive_maximum") {
+                    numeric.exclusive_maximum = Some(NumberLit::parse(meta.value()?)?);
+                } else if meta.path.is_ident("multiple_of") {
+                    numeric.multiple_of = Some(NumberLit::parse(meta.value()?)?);
+                } else if meta.path.is_ident("min_length") {
+                    let content: syn::LitInt = meta.value()?.parse()?;
+                    string.min_length = Some(content.base10_parse()?);
+                } else if meta.path.is_ident("max_length") {
+                    let content: syn::LitInt = meta.value()?.parse()?;
+                    string.max_length = Some(content.base10_parse()?);
+                } else if meta.path.is_ident("pattern") {
+                    let content: syn::LitStr = meta.value()?.parse()?;
+                    string.pattern = Some(content.value());
+                } else if meta.path.is_ident("format") {
+                    let content: syn::LitStr = meta.value()?.parse()?;
+                    string.format = Some(content.value());
+                } else if meta.path.is_ident("language") {
+                    let content: syn::LitStr = meta.value()?.parse()?;
+                    language = Some(content.value());
+                } else if meta.path.is_ident("extra_schema") {
+                    extra_schema = Some(parse_extra_schema(&meta)?);
+                } else if meta.path.is_ident("min_items") {
+                    let content: syn::LitInt = meta.value()?.parse()?;
+                    array.min_items = Some(content.base10_parse()?);
+                } else if meta.path.is_ident("max_items") {
+                    let content: syn::LitInt = meta.value()?.parse()?;
+                    array.max_items = Some(content.base10_parse()?);
+                } else if meta.path.is_ident("unique_items") {
+                    array.unique_items = true;
+                } else if meta.path.is_ident("schema_with") {
+                    let content: syn::LitStr = meta.value()?.parse()?;
+                    schema_with = Some(content.parse::<syn::Path>()?);
+                } else if meta.path.is_ident("serialized_as") {
+                    let content: syn::LitStr = meta.value()?.parse()?;
+                    let json_type = content.value();
+                    if !WireFormat::JSON_TYPES.contains(&json_type.as_str()) {
+                        panic!(
+                            "serialized_as must be one of {:?}, got {:?}",
+                            WireFormat::JSON_TYPES,
+                            json_type
+                        );
+                    }
+                    serialized_as = Some(json_type);
+                } else if meta.path.is_ident("order") {
+                    let content: syn::LitInt = meta.value()?.parse()?;
+                    order = Some(content.base10_parse()?);
                 } else if meta.path.is_ident("example") {
                     let value = meta.value()?;
 
@@ -270,8 +575,38 @@ pub fn parse_field_attributes(field: &Field) -> FieldAttributes {
 
     FieldAttributes {
         description,
+        description_translations,
         example_value,
         examples_array,
         serde_rename,
+        order,
+        skip,
+        numeric,
+        string,
+        array,
+        language,
+        extra_schema,
+        schema_with,
+        wire: serialized_as
+            .map(|json_type| WireFormat {
+                json_type,
+                format: None,
+                description: None,
+            })
+            .or(serde_helper),
     }
 }
+
+/// Fields in schema property order: fields with `#[llm(order = n)]` first,
+/// ascending by `n`, then the rest in declaration order. The sort is stable, so
+/// ties keep declaration order too. Skipped fields are left out.
+pub fn ordered_fields<'a>(fields: impl IntoIterator<Item = &'a Field>) -> Vec<&'a Field> {
+    let mut fields: Vec<(Option<i64>, &Field)> = fields
+        .into_iter()
+        .map(|field| (parse_field_attributes(field), field))
+        .filter(|(attrs, _)| !attrs.skip)
+        .map(|(attrs, field)| (attrs.order, field))
+        .collect();
+    fields.sort_by_key(|(order, _)| (order.is_none(), *order));
+    fields.into_iter().map(|(_, field)| field).collect()
+}
@@ -1,3 +1,7 @@
 pub mod array_parser;
+pub mod counter_example_parser;
+pub mod description_parser;
+pub mod extra_schema_parser;
 pub mod field_parser;
+pub mod serde_parser;
 pub mod variant_parser;
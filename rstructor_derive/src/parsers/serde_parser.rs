@@ -0,0 +1,169 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::LitStr;
+use syn::meta::ParseNestedMeta;
+
+/// The name serde deserializes under, from `rename = "name"` or
+/// `rename(deserialize = "name", ..)` (likewise for `rename_all`). The schema
+/// must use the deserialize side, since that is the key the model's reply is
+/// parsed with; `rename(serialize = "..")` alone yields `None`.
+pub fn parse_deserialize_name(meta: &ParseNestedMeta) -> syn::Result<Option<String>> {
+    if meta.input.peek(syn::Token![=]) {
+        let content: LitStr = meta.value()?.parse()?;
+        return Ok(Some(content.value()));
+    }
+    let mut name = None;
+    meta.parse_nested_meta(|inner| {
+        if inner.path.is_ident("deserialize") {
+            let content: LitStr = inner.value()?.parse()?;
+            name = Some(content.value());
+        } else {
+            skip_meta_value(&inner)?;
+        }
+        Ok(())
+    })?;
+    Ok(name)
+}
+
+/// Consume the value of a serde attribute we do not interpret (`= ".."` or
+/// `(..)`), so the ones after it in the same `#[serde(..)]` are still seen.
+pub fn skip_meta_value(meta: &ParseNestedMeta) -> syn::Result<()> {
+    if meta.input.peek(syn::Token![=]) {
+        meta.value()?.parse::<syn::Expr>()?;
+    } else if meta.input.peek(syn::token::Paren) {
+        let content;
+        syn::parenthesized!(content in meta.input);
+        content.parse::<proc_macro2::TokenStream>()?;
+    }
+    Ok(())
+}
+
+/// The JSON a field is actually (de)serialized as, when that differs from
+/// what its Rust type suggests: set with `#[llm(serialized_as = "integer")]`,
+/// or recognized from a well-known `#[serde(with = "..")]` helper.
+pub struct WireFormat {
+    /// JSON Schema `type`.
+    pub json_type: String,
+    /// JSON Schema `format`, for recognized helpers.
+    pub format: Option<&'static str>,
+    /// What the value means, for recognized helpers.
+    pub description: Option<&'static str>,
+}
+
+impl WireFormat {
+    /// JSON Schema types accepted by `serialized_as`.
+    pub const JSON_TYPES: [&'static str; 6] =
+        ["string", "integer", "number", "boolean", "array", "object"];
+
+    /// An expression evaluating to the field's schema as a `serde_json::Value`.
+    pub fn schema(&self) -> TokenStream {
+        let json_type = &self.json_type;
+        let format = self.format.map(|format| {
+            quote! { schema.insert("format".to_string(), ::serde_json::Value::from(#format)); }
+        });
+        let description = self.description.map(|description| {
+            quote! {
+                schema.insert("description".to_string(), ::serde_json::Value::from(#description));
+            }
+        });
+        quote! {
+            {
+                let mut schema = ::serde_json::Map::new();
+                schema.insert("type".to_string(), ::serde_json::Value::from(#json_type));
+                #format
+                #description
+                ::serde_json::Value::Object(schema)
+            }
+        }
+    }
+}
+
+/// The wire format of a well-known serde helper module or function used in
+/// `with`, `serialize_with` or `deserialize_with`: chrono's `ts_*` timestamp
+/// modules, the `time` crate's `timestamp`/`rfc3339`/`iso8601`, and
+/// `humantime_serde`. Their variants for `Option` fields map the same way.
+pub fn known_serde_helper(path: &str) -> Option<WireFormat> {
+    let mut segments: Vec<&str> = path.split("::").map(str::trim).collect();
+    if matches!(segments.last(), Some(&"serialize" | &"deserialize")) {
+        segments.pop();
+    }
+    if segments.last() == Some(&"option") {
+        segments.pop();
+    }
+    // chrono names its variants for `Option` fields `ts_seconds_option` etc.
+    if let Some(last) = segments.last_mut() {
+        *last = last.strip_suffix("_option").unwrap_or(last);
+    }
+    let timestamp = |unit: &'static str| {
+        let description = match unit {
+            "seconds" => "Unix timestamp in seconds",
+            "milliseconds" => "Unix timestamp in milliseconds",
+            "microseconds" => "Unix timestamp in microseconds",
+            _ => "Unix timestamp in nanoseconds",
+        };
+        WireFormat {
+            json_type: "integer".to_string(),
+            format: (unit == "seconds").then_some("unix-timestamp"),
+            description: Some(description),
+        }
+    };
+    let date_time = || WireFormat {
+        json_type: "string".to_string(),
+        format: Some("date-time"),
+        description: None,
+    };
+    match segments.as_slice() {
+        [.., "ts_seconds"] | [.., "serde", "timestamp"] => Some(timestamp("seconds")),
+        [.., "ts_milliseconds"] | [.., "timestamp", "milliseconds"] => {
+            Some(timestamp("milliseconds"))
+        }
+        [.., "ts_microseconds"] | [.., "timestamp", "microseconds"] => {
+            Some(timestamp("microseconds"))
+        }
+        [.., "ts_nanoseconds"] | [.., "timestamp", "nanoseconds"] => Some(timestamp("nanoseconds")),
+        [.., "serde", "rfc3339" | "iso8601"] => Some(date_time()),
+        ["humantime_serde"] => Some(WireFormat {
+            json_type: "string".to_string(),
+            format: None,
+            description: Some("Duration such as \"1h 30m\" or \"250ms\""),
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wire(path: &str) -> Option<(String, Option<&'static str>)> {
+        known_serde_helper(path).map(|wire| (wire.json_type, wire.format))
+    }
+
+    #[test]
+    fn recognizes_timestamp_helpers_and_their_variants() {
+        let seconds = Some(("integer".to_string(), Some("unix-timestamp")));
+        assert_eq!(wire("chrono::serde::ts_seconds"), seconds);
+        assert_eq!(wire("chrono::serde::ts_seconds_option"), seconds);
+        assert_eq!(wire("ts_seconds::option"), seconds);
+        assert_eq!(wire("chrono::serde::ts_seconds::serialize"), seconds);
+        assert_eq!(wire("time::serde::timestamp"), seconds);
+        assert_eq!(
+            wire("chrono::serde::ts_milliseconds"),
+            Some(("integer".to_string(), None))
+        );
+        assert_eq!(
+            wire("time::serde::timestamp::milliseconds"),
+            Some(("integer".to_string(), None))
+        );
+    }
+
+    #[test]
+    fn recognizes_string_helpers_only_by_full_name() {
+        let date_time = Some(("string".to_string(), Some("date-time")));
+        assert_eq!(wire("time::serde::rfc3339"), date_time);
+        assert_eq!(wire("time::serde::iso8601::option"), date_time);
+        assert_eq!(wire("humantime_serde"), Some(("string".to_string(), None)));
+        assert_eq!(wire("my_app::timestamp"), None);
+        assert_eq!(wire("rfc3339"), None);
+    }
+}
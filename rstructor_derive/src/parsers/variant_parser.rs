@@ -1,8 +1,13 @@
 use syn::Variant;
 
+use crate::parsers::description_parser::parse_description;
+use crate::parsers::serde_parser::{parse_deserialize_name, skip_meta_value};
+
 /// Represents parsed variant attributes
 pub struct VariantAttributes {
     pub description: Option<String>,
+    /// `(locale, text)` from #[llm(description(en = "..", de = ".."))]
+    pub description_translations: Vec<(String, String)>,
     /// Variant rename from #[serde(rename = "...")]
     pub serde_rename: Option<String>,
 }
@@ -10,6 +15,7 @@ pub struct VariantAttributes {
 /// Parse a single enum variant's llm and serde attributes
 pub fn parse_variant_attributes(variant: &Variant) -> VariantAttributes {
     let mut description = None;
+    let mut description_translations = Vec::new();
     let mut serde_rename = None;
 
     // Extract attributes
@@ -18,9 +24,11 @@ pub fn parse_variant_attributes(variant: &Variant) -> VariantAttributes {
         if attr.path().is_ident("serde") {
             let _ = attr.parse_nested_meta(|meta| {
                 if meta.path.is_ident("rename") {
-                    let value = meta.value()?;
-                    let content: syn::LitStr = value.parse()?;
-                    serde_rename = Some(content.value());
+                    if let Some(name) = parse_deserialize_name(&meta)? {
+                        serde_rename = Some(name);
+                    }
+                } else {
+                    skip_meta_value(&meta)?;
                 }
                 Ok(())
             });
@@ -30,9 +38,9 @@ pub fn parse_variant_attributes(variant: &Variant) -> VariantAttributes {
             // Parse attribute arguments
             let _result = attr.parse_nested_meta(|meta| {
                 if meta.path.is_ident("description") {
-                    let value = meta.value()?;
-                    let content: syn::LitStr = value.parse()?;
-                    description = Some(content.value());
+                    let parsed = parse_description(&meta)?;
+                    description = Some(parsed.text);
+                    description_translations = parsed.translations;
                 }
                 Ok(())
             });
@@ -41,6 +49,7 @@ pub fn parse_variant_attributes(variant: &Variant) -> VariantAttributes {
 
     VariantAttributes {
         description,
+        description_translations,
         serde_rename,
     }
 }
@@ -41,6 +41,39 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_strip_transparent_wrappers() {
+        let nested: Type = parse_quote!(Option<Box<Option<Vec<u8>>>>);
+        let expected: Type = parse_quote!(Vec<u8>);
+        assert_eq!(strip_transparent_wrappers(&nested), &expected);
+
+        // A bare `Option` has no inner type to strip to
+        let bare: Type = parse_quote!(Option);
+        assert_eq!(strip_transparent_wrappers(&bare), &bare);
+    }
+
+    #[test]
+    fn test_type_shape_walks_nesting() {
+        let ty: Type = parse_quote!(Option<Vec<Option<HashMap<String, Box<Leaf>>>>>);
+        let TypeShape::Array { item, unique } = type_shape(&ty) else {
+            panic!("expected an array");
+        };
+        assert!(!unique);
+        let TypeShape::Map { value, .. } = type_shape(item) else {
+            panic!("expected a map");
+        };
+        let TypeShape::Leaf(leaf) = type_shape(value) else {
+            panic!("expected a leaf");
+        };
+        assert_eq!(get_type_name(leaf).as_deref(), Some("Leaf"));
+
+        let set: Type = parse_quote!(std::collections::BTreeSet<String>);
+        assert!(matches!(
+            type_shape(&set),
+            TypeShape::Array { unique: true, .. }
+        ));
+    }
+
     #[test]
     fn test_get_type_category() {
         // Create test types
@@ -214,8 +247,8 @@ pub fn get_schema_type_from_rust_type(ty: &Type) -> &'static str {
                 }
                 // Recognize UUID type
                 "Uuid" => return "string",
-                "Option" => {
-                    // For Option<T>, we need to look at the inner type
+                "Option" | "Box" => {
+                    // For Option<T> and Box<T>, we need to look at the inner type
                     if let PathArguments::AngleBracketed(args) = &segment.arguments
                         && let Some(GenericArgument::Type(inner_ty)) = args.args.first()
                     {
@@ -341,6 +374,57 @@ pub fn get_tuple_element_types(ty: &Type) -> Option<Vec<&Type>> {
     None
 }
 
+/// Strip `Option` and `Box` layers, however deeply nested (`Option<Box<T>>`,
+/// `Box<Option<T>>`, `Option<Option<T>>`, ...).
+///
+/// Both are transparent in the generated schema: `Box<T>` serializes as `T`,
+/// and optionality is expressed through the parent's `required` list.
+pub fn strip_transparent_wrappers(ty: &Type) -> &Type {
+    let inner = if is_option_type(ty) {
+        get_option_inner_type(ty)
+    } else {
+        get_box_inner_type(ty).unwrap_or(ty)
+    };
+    if std::ptr::eq(inner, ty) {
+        ty
+    } else {
+        strip_transparent_wrappers(inner)
+    }
+}
+
+/// Whether `ty` is an `Option`, possibly behind `Box` layers (`Box<Option<T>>`).
+pub fn is_optional(ty: &Type) -> bool {
+    is_option_type(ty) || get_box_inner_type(ty).is_some_and(is_optional)
+}
+
+/// The structure of a type as schema generation walks it, one level at a time.
+///
+/// Recursing with [`type_shape`] on the element or value type composes
+/// schemas for arbitrary nesting such as `Option<Vec<Option<Vec<T>>>>` or
+/// `Vec<HashMap<String, Box<T>>>`.
+#[derive(Debug, Clone, Copy)]
+pub enum TypeShape<'a> {
+    /// `Vec<T>`, `HashSet<T>`, ...; `unique` for the set types
+    Array { item: &'a Type, unique: bool },
+    /// `HashMap<K, V>` or `BTreeMap<K, V>`
+    Map { key: &'a Type, value: &'a Type },
+    /// Anything else: a struct, enum, primitive, tuple or `serde_json::Value`
+    Leaf(&'a Type),
+}
+
+/// Classify `ty` after stripping its `Option`/`Box` wrappers.
+pub fn type_shape(ty: &Type) -> TypeShape<'_> {
+    let ty = strip_transparent_wrappers(ty);
+    if let Some(item) = get_array_inner_type(ty) {
+        let unique = matches!(get_type_name(ty).as_deref(), Some("HashSet" | "BTreeSet"));
+        return TypeShape::Array { item, unique };
+    }
+    if let Some((key, value)) = get_map_types(ty) {
+        return TypeShape::Map { key, value };
+    }
+    TypeShape::Leaf(ty)
+}
+
 /// Get the final type name after unwrapping Option and Box
 /// Returns the core type name for detecting self-references
 pub fn get_core_type_name(ty: &Type) -> Option<String> {
@@ -364,6 +448,12 @@ pub fn get_core_type_name(ty: &Type) -> Option<String> {
                         return get_core_type_name(inner_ty);
                     }
                 }
+                "HashMap" | "BTreeMap" => {
+                    // For maps, the value type is what gets nested
+                    if let Some((_, value_ty)) = get_map_types(ty) {
+                        return get_core_type_name(value_ty);
+                    }
+                }
                 _ => return Some(type_name),
             }
         }
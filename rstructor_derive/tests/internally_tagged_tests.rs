@@ -347,13 +347,20 @@ enum SimpleColor {
 fn test_untagged_all_unit_enum_is_string_enum() {
     let schema = SimpleColor::schema().to_json();
 
-    // Should be a simple {"type": "string", "enum": [...]} schema
+    // Should be a string schema; the variant descriptions turn the plain
+    // `enum` array into a oneOf of {"const", "description"} pairs
     assert_eq!(
         schema["type"], "string",
         "untagged all-unit enum should be string type"
     );
-    let enum_vals = schema["enum"].as_array().expect("should have enum values");
-    let values: Vec<&str> = enum_vals.iter().map(|v| v.as_str().unwrap()).collect();
+    let branches = schema["oneOf"]
+        .as_array()
+        .expect("should have oneOf values");
+    let values: Vec<&str> = branches
+        .iter()
+        .map(|b| b["const"].as_str().unwrap())
+        .collect();
+    assert_eq!(branches[0]["description"], "Red color");
     assert!(values.contains(&"Red"));
     assert!(values.contains(&"Green"));
     assert!(values.contains(&"Blue"));
@@ -427,6 +434,7 @@ struct RecursiveNode {
     #[llm(description = "Node label")]
     label: String,
     #[llm(description = "Child nodes")]
+    #[allow(clippy::vec_box)] // exercises Box handling inside a recursive Vec
     children: Vec<Box<RecursiveNode>>,
 }
 
@@ -456,9 +464,10 @@ fn test_recursive_newtype_variant_fields_appear() {
         "recursive newtype variant should include recursive child field"
     );
     assert!(
-        root_variant["$defs"].get("RecursiveNode").is_some(),
-        "recursive definitions should be preserved for nested $ref values"
+        schema["$defs"].get("RecursiveNode").is_some(),
+        "recursive definitions should be lifted to the root, where nested $refs resolve"
     );
+    assert!(root_variant.get("$defs").is_none());
 
     let required: Vec<&str> = root_variant["required"]
         .as_array()
@@ -2,19 +2,23 @@ use async_trait::async_trait;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::sync::Arc;
 use std::time::Duration;
 use tracing::{debug, error, info, instrument, trace, warn};
 
 use crate::backend::model_macro::define_model_enum;
 use crate::backend::{
-    AnthropicMessageContent, ChatMessage, DEFAULT_REQUEST_TIMEOUT, GenerateResult, LLMClient,
-    MaterializeInternalOutput, MaterializeResult, ModelInfo, ThinkingLevel, TokenUsage,
-    ValidationFailureContext, build_anthropic_message_content, build_http_client,
-    check_response_status, generate_with_retry_with_history, handle_http_error,
-    materialize_with_media_with_retry, parse_validate_and_create_output, prepare_strict_schema,
+    AnthropicMessageContent, ChatMessage, ContextOverflow, DEFAULT_REQUEST_TIMEOUT, GenerateResult,
+    HttpClientCell, LLMClient, MaterializeInternalOutput, MaterializeResult, ModelInfo,
+    OutputStrategy, RateLimiter, ThinkingLevel, TokenUsage, UsageTracker, ValidationFailureContext,
+    build_anthropic_message_content, check_response_status, generate_with_retry_with_conversation,
+    generate_with_retry_with_history, materialize_with_media_with_retry, model_override,
+    parse_validate_and_create_output, prepare_strict_schema, send_limited, split_system_messages,
+    with_idempotency_key,
 };
 use crate::error::{ApiErrorKind, RStructorError, Result};
 use crate::model::Instructor;
+use crate::schema::PropertyRenames;
 
 define_model_enum! {
     /// Anthropic models available for completion
@@ -63,6 +67,22 @@ define_model_enum! {
     }
 }
 
+impl From<&AnthropicModel> for crate::backend::ModelId {
+    fn from(model: &AnthropicModel) -> Self {
+        crate::backend::ModelId::new(crate::backend::Provider::Anthropic, model.as_str())
+    }
+}
+
+impl From<crate::backend::ModelTier> for AnthropicModel {
+    fn from(tier: crate::backend::ModelTier) -> Self {
+        AnthropicModel::from_string(tier.resolve(crate::backend::Provider::Anthropic).name)
+    }
+}
+
+/// Anthropic's 32 MB limit on a Messages API request, from the "Request
+/// size limits" section of its API overview.
+const MAX_REQUEST_BYTES: usize = 32_000_000;
+
 /// Configuration for the Anthropic client
 #[derive(Debug, Clone)]
 pub struct AnthropicConfig {
@@ -74,19 +94,51 @@ pub struct AnthropicConfig {
     /// Defaults to [`DEFAULT_REQUEST_TIMEOUT`](crate::DEFAULT_REQUEST_TIMEOUT) (5 minutes).
     pub timeout: Option<Duration>,
     pub max_retries: Option<usize>,
+    /// Collect fields the model returned but the schema does not declare into
+    /// [`MaterializeResult::extra_fields`](crate::MaterializeResult::extra_fields).
+    pub capture_unknown_fields: bool,
+    /// Ask for a rationale in a generated
+    /// [`EXPLANATION_FIELD`](crate::EXPLANATION_FIELD) property, returned in
+    /// [`MaterializeResult::explanation`](crate::MaterializeResult::explanation).
+    pub explain: bool,
+    /// What `materialize` does when the prompt exceeds the context window.
+    pub context_overflow: ContextOverflow,
+    /// System prompt sent with every request, as the provider's system
+    /// instruction rather than as part of the user prompt.
+    pub system_prompt: Option<String>,
+    /// Locale the schema's descriptions are sent in; see
+    /// [`Schema::localized`](crate::Schema::localized).
+    pub schema_locale: Option<String>,
+    /// Translations consulted before those recorded by the derive.
+    pub description_catalog: Option<Arc<crate::schema::DescriptionCatalog>>,
+    /// Request and token budgets this client's requests wait for, possibly
+    /// shared with other clients.
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    /// Counts the requests and tokens of this client's structured calls,
+    /// possibly shared with other clients.
+    pub usage_tracker: UsageTracker,
+    /// Largest request body this client sends; bigger requests fail with
+    /// [`RStructorError::RequestTooLarge`] before anything is uploaded.
+    pub max_request_bytes: Option<usize>,
     /// Custom base URL for Anthropic-compatible APIs
     /// Defaults to "https://api.anthropic.com/v1" if not set
     pub base_url: Option<String>,
     /// Thinking level for Claude 4.x models (Sonnet 4, Opus 4, etc.)
     /// When enabled, temperature is automatically set to 1.0 as required by the API
     pub thinking_level: Option<ThinkingLevel>,
+    /// How `materialize` asks for structured output: native structured outputs
+    /// ([`OutputStrategy::JsonSchema`], the default) or a forced tool call
+    /// ([`OutputStrategy::ToolCalling`]).
+    pub output_strategy: OutputStrategy,
 }
 
-/// Anthropic client for generating completions
+/// Anthropic client for generating completions.
+///
+/// Cheap to clone and `Send + Sync`; see [sharing clients](crate::LLMClient#sharing-clients).
 #[derive(Clone)]
 pub struct AnthropicClient {
-    config: AnthropicConfig,
-    client: reqwest::Client,
+    config: Arc<AnthropicConfig>,
+    client: HttpClientCell,
 }
 
 // Anthropic API request and response structures
@@ -104,18 +156,35 @@ struct OutputFormat {
     schema: Value,
 }
 
+/// A tool definition; used to receive structured output as `tool_use` input
+#[derive(Debug, Serialize)]
+struct ToolDefinition {
+    name: String,
+    description: String,
+    input_schema: Value,
+}
+
 #[derive(Debug, Serialize)]
 struct CompletionRequest {
     model: String,
     messages: Vec<AnthropicMessage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system: Option<String>,
     temperature: f32,
     max_tokens: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
     thinking: Option<ClaudeThinkingConfig>,
     #[serde(skip_serializing_if = "Option::is_none")]
     output_format: Option<OutputFormat>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<Vec<ToolDefinition>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<Value>,
 }
 
+/// Name of the tool the model is forced to call under [`OutputStrategy::ToolCalling`].
+const STRUCTURED_OUTPUT_TOOL: &str = "structured_output";
+
 #[derive(Debug, Serialize)]
 struct ClaudeThinkingConfig {
     #[serde(rename = "type")]
@@ -150,13 +219,34 @@ fn effective_max_tokens(
 struct ContentBlock {
     #[serde(rename = "type")]
     block_type: String,
+    #[serde(default)]
     text: String,
+    /// Arguments of a `tool_use` block
+    #[serde(default)]
+    input: Option<Value>,
 }
 
 #[derive(Debug, Deserialize)]
 struct UsageInfo {
+    #[serde(default)]
     input_tokens: u64,
+    #[serde(default)]
     output_tokens: u64,
+    /// Prompt tokens written to the prompt cache, not part of `input_tokens`
+    #[serde(default)]
+    cache_creation_input_tokens: u64,
+    /// Prompt tokens read from the prompt cache, not part of `input_tokens`
+    #[serde(default)]
+    cache_read_input_tokens: u64,
+}
+
+impl UsageInfo {
+    /// Usage with every prompt token counted as input, cached or not.
+    fn token_usage(&self, model: String) -> TokenUsage {
+        let input =
+            self.input_tokens + self.cache_creation_input_tokens + self.cache_read_input_tokens;
+        TokenUsage::new(model, input, self.output_tokens)
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -202,14 +292,24 @@ impl AnthropicClient {
             max_tokens: None,
             timeout: Some(DEFAULT_REQUEST_TIMEOUT), // Default: 5-minute request timeout
             max_retries: Some(3),                   // Default: 3 retries with error feedback
-            base_url: None,                         // Default: use official Anthropic API
+            capture_unknown_fields: false,
+            explain: false,
+            context_overflow: ContextOverflow::default(),
+            system_prompt: None,
+            schema_locale: None,
+            description_catalog: None,
+            rate_limiter: None,
+            usage_tracker: UsageTracker::new(),
+            max_request_bytes: Some(MAX_REQUEST_BYTES),
+            base_url: None,       // Default: use official Anthropic API
             thinking_level: None, // Default: no extended thinking (faster responses)
+            output_strategy: OutputStrategy::JsonSchema,
         };
 
         debug!("Anthropic client created with default configuration");
         Ok(Self {
-            config,
-            client: build_http_client(DEFAULT_REQUEST_TIMEOUT),
+            config: Arc::new(config),
+            client: HttpClientCell::default(),
         })
     }
 
@@ -244,14 +344,24 @@ impl AnthropicClient {
             max_tokens: None,
             timeout: Some(DEFAULT_REQUEST_TIMEOUT), // Default: 5-minute request timeout
             max_retries: Some(3),                   // Default: 3 retries with error feedback
-            base_url: None,                         // Default: use official Anthropic API
+            capture_unknown_fields: false,
+            explain: false,
+            context_overflow: ContextOverflow::default(),
+            system_prompt: None,
+            schema_locale: None,
+            description_catalog: None,
+            rate_limiter: None,
+            usage_tracker: UsageTracker::new(),
+            max_request_bytes: Some(MAX_REQUEST_BYTES),
+            base_url: None,       // Default: use official Anthropic API
             thinking_level: None, // Default: no extended thinking (faster responses)
+            output_strategy: OutputStrategy::JsonSchema,
         };
 
         debug!("Anthropic client created with default configuration");
         Ok(Self {
-            config,
-            client: build_http_client(DEFAULT_REQUEST_TIMEOUT),
+            config: Arc::new(config),
+            client: HttpClientCell::default(),
         })
     }
 
@@ -259,12 +369,34 @@ impl AnthropicClient {
 }
 
 impl AnthropicClient {
+    /// Convert chat messages to Anthropic's format. Anthropic takes system
+    /// instructions outside the message list, so system messages in the history
+    /// are moved into the returned `system` text after the configured prompt.
+    fn api_messages(
+        &self,
+        messages: &[ChatMessage],
+    ) -> Result<(Option<String>, Vec<AnthropicMessage>)> {
+        let (system, turns) = split_system_messages(self.config.system_prompt.as_deref(), messages);
+        let api_messages = turns
+            .into_iter()
+            .map(|msg| {
+                Ok(AnthropicMessage {
+                    role: msg.role.as_str().to_string(),
+                    content: build_anthropic_message_content(msg)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok((system, api_messages))
+    }
+
     /// Internal implementation of materialize (without retry logic)
     /// Accepts conversation history for multi-turn interactions.
     /// Returns the data, raw response, and optional usage info.
     ///
     /// Uses Anthropic's native Structured Outputs with `output_format: json_schema`
-    /// for guaranteed schema compliance.
+    /// for guaranteed schema compliance, or, with [`OutputStrategy::ToolCalling`],
+    /// a single tool whose input schema is `T`'s schema and a `tool_choice`
+    /// forcing the model to call it.
     ///
     /// The raw response is included to enable conversation history tracking for retries,
     /// which improves prompt caching efficiency.
@@ -278,32 +410,46 @@ impl AnthropicClient {
     where
         T: Instructor + DeserializeOwned + Send + 'static,
     {
-        info!("Generating structured response with Anthropic (native structured outputs)");
+        let use_tool = match self.config.output_strategy {
+            OutputStrategy::JsonSchema => false,
+            OutputStrategy::ToolCalling => true,
+            OutputStrategy::JsonMode => {
+                return Err((
+                    RStructorError::Unsupported(
+                        "Anthropic has no JSON mode; use OutputStrategy::JsonSchema or OutputStrategy::ToolCalling".to_string(),
+                    ),
+                    None,
+                ));
+            }
+        };
+        info!(
+            strategy = ?self.config.output_strategy,
+            "Generating structured response with Anthropic"
+        );
 
         // Get the schema for type T
-        let schema = T::schema();
+        let schema = self.output_schema::<T>();
         trace!("Retrieved JSON schema for type");
 
+        // Tool input property names are validated like function parameters;
+        // unsafe names are replaced and mapped back on the tool_use input
+        let (schema, renames) = if use_tool {
+            schema.with_safe_property_names()
+        } else {
+            (schema, PropertyRenames::default())
+        };
+
         // Prepare schema with additionalProperties: false recursively for all nested objects
         let schema_json = prepare_strict_schema(&schema);
 
         // Build API messages from conversation history
         // With native structured outputs, we don't need to include schema instructions in the prompt
-        let api_messages: Vec<AnthropicMessage> = messages
-            .iter()
-            .map(|msg| {
-                Ok(AnthropicMessage {
-                    role: msg.role.as_str().to_string(),
-                    content: build_anthropic_message_content(msg)?,
-                })
-            })
-            .collect::<Result<Vec<_>>>()
-            .map_err(|e| (e, None))?;
+        let (system, api_messages) = self.api_messages(messages).map_err(|e| (e, None))?;
 
         // Build thinking config for Claude 4.x models
         let is_thinking_model = self.config.model.as_str().contains("sonnet-4")
             || self.config.model.as_str().contains("opus-4");
-        let thinking_config = self.config.thinking_level.and_then(|level| {
+        let mut thinking_config = self.config.thinking_level.and_then(|level| {
             if is_thinking_model && level.claude_thinking_enabled() {
                 Some(ClaudeThinkingConfig {
                     thinking_type: "enabled".to_string(),
@@ -313,6 +459,10 @@ impl AnthropicClient {
                 None
             }
         });
+        // Extended thinking cannot be combined with a forced tool choice
+        if use_tool && thinking_config.take().is_some() {
+            warn!("Extended thinking is disabled when materializing via tool calling");
+        }
 
         // Claude requires temperature=1 when thinking is enabled
         let effective_temp = if thinking_config.is_some() {
@@ -321,10 +471,25 @@ impl AnthropicClient {
             self.config.temperature
         };
 
-        // Create output format for native structured outputs
-        let output_format = OutputFormat {
-            format_type: "json_schema".to_string(),
-            schema: schema_json,
+        // Pass the schema either as the native output format or as the input
+        // schema of a tool the model must call
+        let (output_format, tools, tool_choice) = if use_tool {
+            let tool = ToolDefinition {
+                name: STRUCTURED_OUTPUT_TOOL.to_string(),
+                description: match T::schema_name() {
+                    Some(name) => format!("Record the extracted {name}."),
+                    None => "Record the extracted data.".to_string(),
+                },
+                input_schema: schema_json,
+            };
+            let choice = serde_json::json!({ "type": "tool", "name": STRUCTURED_OUTPUT_TOOL });
+            (None, Some(vec![tool]), Some(choice))
+        } else {
+            let output_format = OutputFormat {
+                format_type: "json_schema".to_string(),
+                schema: schema_json,
+            };
+            (Some(output_format), None, None)
         };
 
         // Build the request with native structured outputs
@@ -333,12 +498,15 @@ impl AnthropicClient {
             api_messages.len()
         );
         let request = CompletionRequest {
-            model: self.config.model.as_str().to_string(),
+            model: model_override().unwrap_or_else(|| self.config.model.as_str().to_string()),
             messages: api_messages,
+            system,
             temperature: effective_temp,
             max_tokens: effective_max_tokens(self.config.max_tokens, thinking_config.as_ref()),
             thinking: thinking_config,
-            output_format: Some(output_format),
+            output_format,
+            tools,
+            tool_choice,
         };
 
         // Send the request to Anthropic with structured outputs beta header
@@ -354,17 +522,21 @@ impl AnthropicClient {
             .unwrap_or("https://api.anthropic.com/v1");
         let url = format!("{}/messages", base_url);
         debug!(url = %url, "Using Anthropic API endpoint");
-        let response = self
-            .client
-            .post(&url)
-            .header("x-api-key", &self.config.api_key)
+        let mut builder = with_idempotency_key(self.http().post(&url))
+            .header("x-api-key", self.api_key_for_call())
             .header("anthropic-version", "2023-06-01")
-            .header("anthropic-beta", "structured-outputs-2025-11-13")
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| (handle_http_error(e, "Anthropic"), None))?;
+            .header("Content-Type", "application/json");
+        if !use_tool {
+            builder = builder.header("anthropic-beta", "structured-outputs-2025-11-13");
+        }
+        let response = send_limited(
+            "Anthropic",
+            self.config.rate_limiter.as_deref(),
+            self.config.max_request_bytes,
+            builder.json(&request),
+        )
+        .await
+        .map_err(|e| (e, None))?;
 
         // Parse the response
         let response = check_response_status(response, "Anthropic")
@@ -385,15 +557,24 @@ impl AnthropicClient {
         let usage = completion
             .usage
             .as_ref()
-            .map(|u| TokenUsage::new(model_name.clone(), u.input_tokens, u.output_tokens));
-
-        // Extract the content, assuming the first block is text containing JSON
-        let raw_response = match completion
-            .content
-            .iter()
-            .find(|block| block.block_type == "text")
-            .map(|block| block.text.clone())
-        {
+            .map(|u| u.token_usage(model_name.clone()));
+
+        // Extract the JSON: the forced tool call's input, or the first text block
+        let raw_response = if use_tool {
+            completion
+                .content
+                .iter()
+                .find(|block| block.block_type == "tool_use")
+                .and_then(|block| block.input.clone())
+                .map(|input| renames.restore(input).to_string())
+        } else {
+            completion
+                .content
+                .iter()
+                .find(|block| block.block_type == "text")
+                .map(|block| block.text.clone())
+        };
+        let raw_response = match raw_response {
             Some(text) => {
                 debug!(
                     content_len = text.len(),
@@ -402,12 +583,16 @@ impl AnthropicClient {
                 text
             }
             None => {
-                error!("No text content in Anthropic response");
+                error!("No structured content in Anthropic response");
                 return Err((
                     RStructorError::api_error(
                         "Anthropic",
                         ApiErrorKind::UnexpectedResponse {
-                            details: "No text content in response".to_string(),
+                            details: if use_tool {
+                                "No tool_use block in response".to_string()
+                            } else {
+                                "No text content in response".to_string()
+                            },
                         },
                     ),
                     None,
@@ -418,7 +603,7 @@ impl AnthropicClient {
         // Parse the JSON content directly using shared utility
         // With native structured outputs, the response is guaranteed to be valid JSON
         trace!(json = %raw_response, "Parsing structured output response");
-        parse_validate_and_create_output(raw_response, usage)
+        parse_validate_and_create_output(raw_response, usage, "Anthropic")
     }
 
     /// Internal implementation of raw text generation (no structured output).
@@ -451,25 +636,20 @@ impl AnthropicClient {
         };
 
         // Build API messages, including any attached media blocks
-        let api_messages: Vec<AnthropicMessage> = messages
-            .iter()
-            .map(|msg| {
-                Ok(AnthropicMessage {
-                    role: msg.role.as_str().to_string(),
-                    content: build_anthropic_message_content(msg)?,
-                })
-            })
-            .collect::<Result<Vec<_>>>()?;
+        let (system, api_messages) = self.api_messages(messages)?;
 
         // Build the request (no output_format for raw text generation)
         debug!("Building Anthropic API request for text generation");
         let request = CompletionRequest {
             model: self.config.model.as_str().to_string(),
             messages: api_messages,
+            system,
             temperature: effective_temp,
             max_tokens: effective_max_tokens(self.config.max_tokens, thinking_config.as_ref()),
             thinking: thinking_config,
             output_format: None, // Raw text generation doesn't use structured outputs
+            tools: None,
+            tool_choice: None,
         };
 
         // Send the request to Anthropic
@@ -485,16 +665,18 @@ impl AnthropicClient {
             .unwrap_or("https://api.anthropic.com/v1");
         let url = format!("{}/messages", base_url);
         debug!(url = %url, "Using Anthropic API endpoint");
-        let response = self
-            .client
-            .post(&url)
-            .header("x-api-key", &self.config.api_key)
-            .header("anthropic-version", "2023-06-01")
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| handle_http_error(e, "Anthropic"))?;
+        let response = send_limited(
+            "Anthropic",
+            self.config.rate_limiter.as_deref(),
+            self.config.max_request_bytes,
+            self.http()
+                .post(&url)
+                .header("x-api-key", self.api_key_for_call())
+                .header("anthropic-version", "2023-06-01")
+                .header("Content-Type", "application/json")
+                .json(&request),
+        )
+        .await?;
 
         // Parse the response
         let response = check_response_status(response, "Anthropic").await?;
@@ -510,10 +692,7 @@ impl AnthropicClient {
             .model
             .clone()
             .unwrap_or_else(|| self.config.model.as_str().to_string());
-        let usage = completion
-            .usage
-            .as_ref()
-            .map(|u| TokenUsage::new(model_name, u.input_tokens, u.output_tokens));
+        let usage = completion.usage.as_ref().map(|u| u.token_usage(model_name));
 
         // Extract the content
         debug!("Extracting text content from response blocks");
@@ -548,7 +727,8 @@ crate::impl_client_builder_methods! {
     client_type: AnthropicClient,
     config_type: AnthropicConfig,
     model_type: AnthropicModel,
-    provider_name: "Anthropic"
+    provider_name: "Anthropic",
+    max_temperature: 1.0
 }
 
 impl AnthropicClient {
@@ -565,7 +745,7 @@ impl AnthropicClient {
             new_base_url = %base_url_str,
             "Setting custom base URL"
         );
-        self.config.base_url = Some(base_url_str);
+        Arc::make_mut(&mut self.config).base_url = Some(base_url_str);
         self
     }
 
@@ -602,7 +782,35 @@ impl AnthropicClient {
             new_level = ?level,
             "Setting thinking level"
         );
-        self.config.thinking_level = Some(level);
+        Arc::make_mut(&mut self.config).thinking_level = Some(level);
+        self
+    }
+
+    /// Choose how `materialize` asks Claude for structured output.
+    ///
+    /// The default, [`OutputStrategy::JsonSchema`], uses native structured
+    /// outputs. [`OutputStrategy::ToolCalling`] instead defines one tool whose
+    /// input schema is the target type's schema and forces the model to call
+    /// it, reading the result from the `tool_use` block. Use it for models or
+    /// Anthropic-compatible endpoints without structured outputs. Extended
+    /// thinking cannot be combined with a forced tool call, so it is skipped
+    /// in that mode. Streaming methods always use native structured outputs,
+    /// and [`OutputStrategy::JsonMode`] is not supported by Anthropic.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rstructor::{AnthropicClient, OutputStrategy};
+    ///
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = AnthropicClient::from_env()?
+    ///     .output_strategy(OutputStrategy::ToolCalling);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[tracing::instrument(skip(self))]
+    pub fn output_strategy(mut self, strategy: OutputStrategy) -> Self {
+        Arc::make_mut(&mut self.config).output_strategy = strategy;
         self
     }
 }
@@ -649,6 +857,9 @@ impl AnthropicClient {
         if let Some(of) = output_format {
             body["output_format"] = of;
         }
+        if let Some(system) = &self.config.system_prompt {
+            body["system"] = serde_json::json!(system);
+        }
         body
     }
 
@@ -657,25 +868,30 @@ impl AnthropicClient {
         &self,
         body: serde_json::Value,
     ) -> impl std::future::Future<Output = Result<reqwest::Response>> + Send + 'static {
-        let client = self.client.clone();
-        let api_key = self.config.api_key.clone();
+        let client = self.http().clone();
+        let api_key = self.api_key_for_call();
         let base_url = self
             .config
             .base_url
             .clone()
             .unwrap_or_else(|| "https://api.anthropic.com/v1".to_string());
+        let rate_limiter = self.config.rate_limiter.clone();
+        let max_request_bytes = self.config.max_request_bytes;
         async move {
             let url = format!("{}/messages", base_url);
-            let resp = client
-                .post(&url)
-                .header("x-api-key", &api_key)
-                .header("anthropic-version", "2023-06-01")
-                .header("anthropic-beta", "structured-outputs-2025-11-13")
-                .header("Content-Type", "application/json")
-                .json(&body)
-                .send()
-                .await
-                .map_err(|e| handle_http_error(e, "Anthropic"))?;
+            let resp = send_limited(
+                "Anthropic",
+                rate_limiter.as_deref(),
+                max_request_bytes,
+                client
+                    .post(&url)
+                    .header("x-api-key", &api_key)
+                    .header("anthropic-version", "2023-06-01")
+                    .header("anthropic-beta", "structured-outputs-2025-11-13")
+                    .header("Content-Type", "application/json")
+                    .json(&body),
+            )
+            .await?;
             check_response_status(resp, "Anthropic").await
         }
     }
@@ -692,21 +908,25 @@ impl crate::backend::tools::ToolRunner for AnthropicClient {
         toolbox: &crate::backend::tools::Toolbox,
         max_iterations: usize,
     ) -> Result<String> {
+        let system =
+            crate::backend::tools::tool_run_system(self.config.system_prompt.as_deref(), system);
         let base_url = self
             .config
             .base_url
             .as_deref()
             .unwrap_or("https://api.anthropic.com/v1");
         crate::backend::tools::run_anthropic_tools(
-            &self.client,
+            self.http(),
             base_url,
-            &self.config.api_key,
+            &self.api_key_for_call(),
+            self.config.rate_limiter.as_deref(),
+            self.config.max_request_bytes,
             self.config.model.as_str(),
             self.config.temperature,
             self.config
                 .max_tokens
                 .unwrap_or(DEFAULT_ANTHROPIC_MAX_TOKENS),
-            system,
+            system.as_deref(),
             prompt,
             media,
             toolbox,
@@ -735,15 +955,19 @@ impl LLMClient for AnthropicClient {
     where
         T: Instructor + DeserializeOwned + Send + 'static,
     {
-        let output = generate_with_retry_with_history(
-            |messages: Vec<ChatMessage>| {
-                let this = self;
-                async move { this.materialize_internal::<T>(&messages).await }
-            },
-            prompt,
-            self.config.max_retries,
-        )
-        .await?;
+        let output = self
+            .config
+            .usage_tracker
+            .scope(generate_with_retry_with_history(
+                |messages: Vec<ChatMessage>| {
+                    let this = self;
+                    async move { this.materialize_internal::<T>(&messages).await }
+                },
+                prompt,
+                self.config.max_retries,
+                &self.config.context_overflow,
+            ))
+            .await?;
         Ok(output.data)
     }
 
@@ -761,16 +985,19 @@ impl LLMClient for AnthropicClient {
     where
         T: Instructor + DeserializeOwned + Send + 'static,
     {
-        materialize_with_media_with_retry(
-            |messages: Vec<ChatMessage>| {
-                let this = self;
-                async move { this.materialize_internal::<T>(&messages).await }
-            },
-            prompt,
-            media,
-            self.config.max_retries,
-        )
-        .await
+        self.config
+            .usage_tracker
+            .scope(materialize_with_media_with_retry(
+                |messages: Vec<ChatMessage>| {
+                    let this = self;
+                    async move { this.materialize_internal::<T>(&messages).await }
+                },
+                prompt,
+                media,
+                self.config.max_retries,
+                &self.config.context_overflow,
+            ))
+            .await
     }
 
     #[instrument(
@@ -786,16 +1013,20 @@ impl LLMClient for AnthropicClient {
     where
         T: Instructor + DeserializeOwned + Send + 'static,
     {
-        let output = generate_with_retry_with_history(
-            |messages: Vec<ChatMessage>| {
-                let this = self;
-                async move { this.materialize_internal::<T>(&messages).await }
-            },
-            prompt,
-            self.config.max_retries,
-        )
-        .await?;
-        Ok(MaterializeResult::new(output.data, output.usage))
+        let output = self
+            .config
+            .usage_tracker
+            .scope(generate_with_retry_with_history(
+                |messages: Vec<ChatMessage>| {
+                    let this = self;
+                    async move { this.materialize_internal::<T>(&messages).await }
+                },
+                prompt,
+                self.config.max_retries,
+                &self.config.context_overflow,
+            ))
+            .await?;
+        Ok(output.into_result(self.config.capture_unknown_fields))
     }
 
     #[instrument(
@@ -843,6 +1074,47 @@ impl LLMClient for AnthropicClient {
         self.generate_internal(&[ChatMessage::user(prompt)]).await
     }
 
+    #[instrument(
+        name = "anthropic_materialize_conversation",
+        skip(self, messages),
+        fields(
+            type_name = std::any::type_name::<T>(),
+            model = %self.config.model.as_str(),
+            history_len = messages.len()
+        )
+    )]
+    async fn materialize_conversation<T>(
+        &self,
+        messages: &[ChatMessage],
+    ) -> Result<MaterializeResult<T>>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        let output = self
+            .config
+            .usage_tracker
+            .scope(generate_with_retry_with_conversation(
+                |messages: Vec<ChatMessage>| {
+                    let this = self;
+                    async move { this.materialize_internal::<T>(&messages).await }
+                },
+                messages,
+                self.config.max_retries,
+                &self.config.context_overflow,
+            ))
+            .await?;
+        Ok(output.into_result(self.config.capture_unknown_fields))
+    }
+
+    #[instrument(
+        name = "anthropic_generate_conversation",
+        skip(self, messages),
+        fields(model = %self.config.model.as_str(), history_len = messages.len())
+    )]
+    async fn generate_conversation(&self, messages: &[ChatMessage]) -> Result<GenerateResult> {
+        self.generate_internal(messages).await
+    }
+
     #[cfg(feature = "streaming")]
     fn generate_stream<'a>(&'a self, prompt: &'a str) -> crate::backend::streaming::TextStream<'a>
     where
@@ -864,7 +1136,7 @@ impl LLMClient for AnthropicClient {
         T: Instructor + DeserializeOwned + Send + 'static,
         Self: Sync,
     {
-        let schema_json = prepare_strict_schema(&T::schema());
+        let schema_json = prepare_strict_schema(&self.localized_schema::<T>());
         let output_format = serde_json::json!({
             "type": "json_schema",
             "schema": schema_json,
@@ -885,7 +1157,7 @@ impl LLMClient for AnthropicClient {
         T: Instructor + DeserializeOwned + Send + 'static,
         Self: Sync,
     {
-        let item_schema = prepare_strict_schema(&T::schema());
+        let item_schema = prepare_strict_schema(&self.localized_schema::<T>());
         let wrapper = crate::backend::streaming::array_wrapper_schema(item_schema, true);
         let output_format = serde_json::json!({ "type": "json_schema", "schema": wrapper });
         let body = self.stream_body(prompt, Some(output_format));
@@ -909,15 +1181,17 @@ impl LLMClient for AnthropicClient {
 
         debug!(url = %url, "Fetching available models from Anthropic");
 
-        let response = self
-            .client
-            .get(&url)
-            .header("x-api-key", &self.config.api_key)
-            .header("anthropic-version", "2023-06-01")
-            .header("Content-Type", "application/json")
-            .send()
-            .await
-            .map_err(|e| handle_http_error(e, "Anthropic"))?;
+        let response = send_limited(
+            "Anthropic",
+            self.config.rate_limiter.as_deref(),
+            self.config.max_request_bytes,
+            self.http()
+                .get(&url)
+                .header("x-api-key", self.api_key_for_call())
+                .header("anthropic-version", "2023-06-01")
+                .header("Content-Type", "application/json"),
+        )
+        .await?;
 
         let response = check_response_status(response, "Anthropic").await?;
 
@@ -6,23 +6,42 @@
 //! trait object. [`AnyClient`] solves the common need behind that limitation
 //! ("choose a provider at runtime and keep it in a single type") by wrapping each
 //! concrete client in an enum that itself implements [`LLMClient`].
+//!
+//! [`client_from_str`] builds one from a `"provider:model"` string, so the
+//! provider can come from a config file or CLI flag. To hold other clients
+//! (a `MockClient`, a wrapper of your own) behind the same type, use a
+//! [`DynLLMClient`](crate::DynLLMClient) trait object instead.
+
+use std::str::FromStr;
 
 use async_trait::async_trait;
 use serde::de::DeserializeOwned;
 
 use crate::backend::usage::{GenerateResult, MaterializeResult};
-use crate::backend::{LLMClient, MediaFile, ModelInfo};
+use crate::backend::{ChatMessage, LLMClient, MediaFile, ModelInfo};
 use crate::error::{ApiErrorKind, RStructorError, Result};
 use crate::model::Instructor;
 
 #[cfg(feature = "anthropic")]
 use crate::backend::anthropic::AnthropicClient;
+#[cfg(feature = "azure")]
+use crate::backend::azure::AzureOpenAIClient;
+#[cfg(feature = "bedrock")]
+use crate::backend::bedrock::BedrockClient;
 #[cfg(feature = "gemini")]
 use crate::backend::gemini::GeminiClient;
 #[cfg(feature = "grok")]
 use crate::backend::grok::GrokClient;
+#[cfg(feature = "groq")]
+use crate::backend::groq::GroqClient;
+#[cfg(feature = "ollama")]
+use crate::backend::ollama::OllamaClient;
 #[cfg(feature = "openai")]
 use crate::backend::openai::OpenAIClient;
+#[cfg(feature = "together")]
+use crate::backend::together::TogetherClient;
+#[cfg(feature = "vertex")]
+use crate::backend::vertex::VertexAIClient;
 
 /// Identifies an LLM provider for runtime selection via [`AnyClient`].
 ///
@@ -41,6 +60,99 @@ pub enum Provider {
     /// Google Gemini (reads `GEMINI_API_KEY`).
     #[cfg(feature = "gemini")]
     Gemini,
+    /// A local Ollama server (reads `OLLAMA_HOST`; no API key).
+    #[cfg(feature = "ollama")]
+    Ollama,
+    /// Azure OpenAI (reads `AZURE_OPENAI_ENDPOINT` and `AZURE_OPENAI_API_KEY`).
+    #[cfg(feature = "azure")]
+    AzureOpenAI,
+    /// AWS Bedrock (reads `AWS_REGION` and AWS credentials or a Bedrock API key).
+    #[cfg(feature = "bedrock")]
+    Bedrock,
+    /// Google Vertex AI (reads `GOOGLE_CLOUD_PROJECT` and Application Default
+    /// Credentials).
+    #[cfg(feature = "vertex")]
+    VertexAI,
+    /// Groq (reads `GROQ_API_KEY`).
+    #[cfg(feature = "groq")]
+    Groq,
+    /// Together.ai (reads `TOGETHER_API_KEY`).
+    #[cfg(feature = "together")]
+    Together,
+}
+
+/// Provider names accepted by [`Provider::from_str`], with the feature each
+/// needs. The first name of each provider is its canonical one.
+const PROVIDER_NAMES: &[(&str, &str)] = &[
+    ("openai", "openai"),
+    ("anthropic", "anthropic"),
+    ("claude", "anthropic"),
+    ("grok", "grok"),
+    ("xai", "grok"),
+    ("gemini", "gemini"),
+    ("google", "gemini"),
+    ("ollama", "ollama"),
+    ("azure", "azure"),
+    ("azure-openai", "azure"),
+    ("bedrock", "bedrock"),
+    ("aws-bedrock", "bedrock"),
+    ("vertex", "vertex"),
+    ("vertex-ai", "vertex"),
+    ("vertexai", "vertex"),
+    ("groq", "groq"),
+    ("together", "together"),
+    ("together-ai", "together"),
+    ("togetherai", "together"),
+];
+
+impl FromStr for Provider {
+    type Err = RStructorError;
+
+    /// Parse a provider name, case-insensitively: `openai`, `anthropic`
+    /// (or `claude`), `grok` (or `xai`), `gemini` (or `google`), `ollama`,
+    /// `azure` (or `azure-openai`), `bedrock` (or `aws-bedrock`), `vertex`
+    /// (or `vertex-ai`, `vertexai`), `groq`, `together` (or `together-ai`,
+    /// `togetherai`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RStructorError::ConfigError`] for an unknown name, or for a
+    /// provider whose Cargo feature is not enabled.
+    fn from_str(name: &str) -> Result<Self> {
+        let name = name.trim().to_ascii_lowercase();
+        let Some(&(_, feature)) = PROVIDER_NAMES.iter().find(|(known, _)| *known == name) else {
+            let known: Vec<&str> = PROVIDER_NAMES.iter().map(|(known, _)| *known).collect();
+            return Err(RStructorError::ConfigError(format!(
+                "unknown provider `{name}` (expected one of: {})",
+                known.join(", ")
+            )));
+        };
+        match feature {
+            #[cfg(feature = "openai")]
+            "openai" => Ok(Self::OpenAI),
+            #[cfg(feature = "anthropic")]
+            "anthropic" => Ok(Self::Anthropic),
+            #[cfg(feature = "grok")]
+            "grok" => Ok(Self::Grok),
+            #[cfg(feature = "gemini")]
+            "gemini" => Ok(Self::Gemini),
+            #[cfg(feature = "ollama")]
+            "ollama" => Ok(Self::Ollama),
+            #[cfg(feature = "azure")]
+            "azure" => Ok(Self::AzureOpenAI),
+            #[cfg(feature = "bedrock")]
+            "bedrock" => Ok(Self::Bedrock),
+            #[cfg(feature = "vertex")]
+            "vertex" => Ok(Self::VertexAI),
+            #[cfg(feature = "groq")]
+            "groq" => Ok(Self::Groq),
+            #[cfg(feature = "together")]
+            "together" => Ok(Self::Together),
+            _ => Err(RStructorError::ConfigError(format!(
+                "provider `{name}` requires rstructor's `{feature}` feature"
+            ))),
+        }
+    }
 }
 
 /// A provider-agnostic client chosen at runtime.
@@ -51,7 +163,8 @@ pub enum Provider {
 /// single, `Clone`, `Send + Sync` type that can hold whichever provider you
 /// selected at runtime (from a CLI flag, config file, env, etc.).
 ///
-/// Construct it with [`from_env_for`](Self::from_env_for), with
+/// Construct it with [`from_env_for`](Self::from_env_for), from a
+/// `"provider:model"` string with [`client_from_str`], with
 /// [`LLMClient::from_env`] (which auto-detects from the environment), or with
 /// `From<ConcreteClient>` when you need custom configuration:
 ///
@@ -101,6 +214,24 @@ pub enum AnyClient {
     /// A Gemini client.
     #[cfg(feature = "gemini")]
     Gemini(GeminiClient),
+    /// An Ollama client.
+    #[cfg(feature = "ollama")]
+    Ollama(OllamaClient),
+    /// An Azure OpenAI client.
+    #[cfg(feature = "azure")]
+    AzureOpenAI(AzureOpenAIClient),
+    /// An AWS Bedrock client.
+    #[cfg(feature = "bedrock")]
+    Bedrock(BedrockClient),
+    /// A Google Vertex AI client.
+    #[cfg(feature = "vertex")]
+    VertexAI(VertexAIClient),
+    /// A Groq client.
+    #[cfg(feature = "groq")]
+    Groq(GroqClient),
+    /// A Together.ai client.
+    #[cfg(feature = "together")]
+    Together(TogetherClient),
 }
 
 impl AnyClient {
@@ -119,6 +250,90 @@ impl AnyClient {
             Provider::Grok => Ok(Self::Grok(GrokClient::from_env()?)),
             #[cfg(feature = "gemini")]
             Provider::Gemini => Ok(Self::Gemini(GeminiClient::from_env()?)),
+            #[cfg(feature = "ollama")]
+            Provider::Ollama => Ok(Self::Ollama(OllamaClient::from_env()?)),
+            #[cfg(feature = "azure")]
+            Provider::AzureOpenAI => Ok(Self::AzureOpenAI(AzureOpenAIClient::from_env()?)),
+            #[cfg(feature = "bedrock")]
+            Provider::Bedrock => Ok(Self::Bedrock(BedrockClient::from_env()?)),
+            #[cfg(feature = "vertex")]
+            Provider::VertexAI => Ok(Self::VertexAI(VertexAIClient::from_env()?)),
+            #[cfg(feature = "groq")]
+            Provider::Groq => Ok(Self::Groq(GroqClient::from_env()?)),
+            #[cfg(feature = "together")]
+            Provider::Together => Ok(Self::Together(TogetherClient::from_env()?)),
+        }
+    }
+
+    /// Build a client from a `"provider:model"` string such as
+    /// `"openai:gpt-4o"` or `"ollama:llama3.1:8b"`; see [`client_from_str`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RStructorError::ConfigError`] for an unknown or disabled
+    /// provider, or the provider's error if its environment is not set up.
+    pub fn from_model_string(spec: &str) -> Result<Self> {
+        let (provider, model) = match spec.split_once(':') {
+            Some((provider, model)) => (provider, Some(model.trim())),
+            None => (spec, None),
+        };
+        let client = Self::from_env_for(provider.parse()?)?;
+        Ok(match model.filter(|model| !model.is_empty()) {
+            Some(model) => client.with_model(model),
+            None => client,
+        })
+    }
+
+    /// This client with its model set to `model`.
+    fn with_model(self, model: &str) -> Self {
+        match self {
+            #[cfg(feature = "openai")]
+            Self::OpenAI(c) => Self::OpenAI(c.model(model)),
+            #[cfg(feature = "anthropic")]
+            Self::Anthropic(c) => Self::Anthropic(c.model(model)),
+            #[cfg(feature = "grok")]
+            Self::Grok(c) => Self::Grok(c.model(model)),
+            #[cfg(feature = "gemini")]
+            Self::Gemini(c) => Self::Gemini(c.model(model)),
+            #[cfg(feature = "ollama")]
+            Self::Ollama(c) => Self::Ollama(c.model(model)),
+            #[cfg(feature = "azure")]
+            Self::AzureOpenAI(c) => Self::AzureOpenAI(c.model(model)),
+            #[cfg(feature = "bedrock")]
+            Self::Bedrock(c) => Self::Bedrock(c.model(model)),
+            #[cfg(feature = "vertex")]
+            Self::VertexAI(c) => Self::VertexAI(c.model(model)),
+            #[cfg(feature = "groq")]
+            Self::Groq(c) => Self::Groq(c.model(model)),
+            #[cfg(feature = "together")]
+            Self::Together(c) => Self::Together(c.model(model)),
+        }
+    }
+
+    /// The configured model, qualified by provider.
+    #[must_use]
+    pub fn model_id(&self) -> crate::backend::ModelId {
+        match self {
+            #[cfg(feature = "openai")]
+            Self::OpenAI(c) => c.model_id(),
+            #[cfg(feature = "anthropic")]
+            Self::Anthropic(c) => c.model_id(),
+            #[cfg(feature = "grok")]
+            Self::Grok(c) => c.model_id(),
+            #[cfg(feature = "gemini")]
+            Self::Gemini(c) => c.model_id(),
+            #[cfg(feature = "ollama")]
+            Self::Ollama(c) => c.model_id(),
+            #[cfg(feature = "azure")]
+            Self::AzureOpenAI(c) => c.model_id(),
+            #[cfg(feature = "bedrock")]
+            Self::Bedrock(c) => c.model_id(),
+            #[cfg(feature = "vertex")]
+            Self::VertexAI(c) => c.model_id(),
+            #[cfg(feature = "groq")]
+            Self::Groq(c) => c.model_id(),
+            #[cfg(feature = "together")]
+            Self::Together(c) => c.model_id(),
         }
     }
 
@@ -134,10 +349,114 @@ impl AnyClient {
             Self::Grok(_) => Provider::Grok,
             #[cfg(feature = "gemini")]
             Self::Gemini(_) => Provider::Gemini,
+            #[cfg(feature = "ollama")]
+            Self::Ollama(_) => Provider::Ollama,
+            #[cfg(feature = "azure")]
+            Self::AzureOpenAI(_) => Provider::AzureOpenAI,
+            #[cfg(feature = "bedrock")]
+            Self::Bedrock(_) => Provider::Bedrock,
+            #[cfg(feature = "vertex")]
+            Self::VertexAI(_) => Provider::VertexAI,
+            #[cfg(feature = "groq")]
+            Self::Groq(_) => Provider::Groq,
+            #[cfg(feature = "together")]
+            Self::Together(_) => Provider::Together,
+        }
+    }
+
+    /// Capabilities of the wrapped client's configured model.
+    #[must_use]
+    pub fn capabilities(&self) -> crate::backend::ProviderCapabilities {
+        match self {
+            #[cfg(feature = "openai")]
+            Self::OpenAI(c) => c.capabilities(),
+            #[cfg(feature = "anthropic")]
+            Self::Anthropic(c) => c.capabilities(),
+            #[cfg(feature = "grok")]
+            Self::Grok(c) => c.capabilities(),
+            #[cfg(feature = "gemini")]
+            Self::Gemini(c) => c.capabilities(),
+            #[cfg(feature = "ollama")]
+            Self::Ollama(c) => c.capabilities(),
+            #[cfg(feature = "azure")]
+            Self::AzureOpenAI(c) => c.capabilities(),
+            #[cfg(feature = "bedrock")]
+            Self::Bedrock(c) => c.capabilities(),
+            #[cfg(feature = "vertex")]
+            Self::VertexAI(c) => c.capabilities(),
+            #[cfg(feature = "groq")]
+            Self::Groq(c) => c.capabilities(),
+            #[cfg(feature = "together")]
+            Self::Together(c) => c.capabilities(),
+        }
+    }
+
+    /// Requests and tokens of the wrapped client's structured calls so far.
+    #[must_use]
+    pub fn usage_snapshot(&self) -> crate::backend::UsageSnapshot {
+        match self {
+            #[cfg(feature = "openai")]
+            Self::OpenAI(c) => c.usage_snapshot(),
+            #[cfg(feature = "anthropic")]
+            Self::Anthropic(c) => c.usage_snapshot(),
+            #[cfg(feature = "grok")]
+            Self::Grok(c) => c.usage_snapshot(),
+            #[cfg(feature = "gemini")]
+            Self::Gemini(c) => c.usage_snapshot(),
+            #[cfg(feature = "ollama")]
+            Self::Ollama(c) => c.usage_snapshot(),
+            #[cfg(feature = "azure")]
+            Self::AzureOpenAI(c) => c.usage_snapshot(),
+            #[cfg(feature = "bedrock")]
+            Self::Bedrock(c) => c.usage_snapshot(),
+            #[cfg(feature = "vertex")]
+            Self::VertexAI(c) => c.usage_snapshot(),
+            #[cfg(feature = "groq")]
+            Self::Groq(c) => c.usage_snapshot(),
+            #[cfg(feature = "together")]
+            Self::Together(c) => c.usage_snapshot(),
         }
     }
 }
 
+impl FromStr for AnyClient {
+    type Err = RStructorError;
+
+    /// Same as [`AnyClient::from_model_string`].
+    fn from_str(spec: &str) -> Result<Self> {
+        Self::from_model_string(spec)
+    }
+}
+
+/// Build a client from a `"provider:model"` string, so the provider and model
+/// can be chosen by configuration rather than at compile time.
+///
+/// The provider is parsed by [`Provider::from_str`] and its API key read from
+/// the environment as in [`AnyClient::from_env_for`]. Everything after the
+/// first `:` is the model, so Ollama tags such as `llama3.1:8b` work; without
+/// a model the provider's default is used.
+///
+/// ```no_run
+/// # async fn ex() -> rstructor::Result<()> {
+/// use rstructor::LLMClient;
+///
+/// let spec = std::env::var("LLM").unwrap_or_else(|_| "openai:gpt-4o".to_string());
+/// let client = rstructor::client_from_str(&spec)?;
+/// let spam: bool = client.materialize("Is this email spam? ...").await?;
+/// # let _ = spam;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns [`RStructorError::ConfigError`] for an unknown or disabled
+/// provider, or the provider's error if its environment is not set up (for
+/// example a missing API key).
+pub fn client_from_str(spec: &str) -> Result<AnyClient> {
+    AnyClient::from_model_string(spec)
+}
+
 #[cfg(feature = "openai")]
 impl From<OpenAIClient> for AnyClient {
     fn from(client: OpenAIClient) -> Self {
@@ -166,6 +485,48 @@ impl From<GeminiClient> for AnyClient {
     }
 }
 
+#[cfg(feature = "azure")]
+impl From<AzureOpenAIClient> for AnyClient {
+    fn from(client: AzureOpenAIClient) -> Self {
+        Self::AzureOpenAI(client)
+    }
+}
+
+#[cfg(feature = "ollama")]
+impl From<OllamaClient> for AnyClient {
+    fn from(client: OllamaClient) -> Self {
+        Self::Ollama(client)
+    }
+}
+
+#[cfg(feature = "bedrock")]
+impl From<BedrockClient> for AnyClient {
+    fn from(client: BedrockClient) -> Self {
+        Self::Bedrock(client)
+    }
+}
+
+#[cfg(feature = "vertex")]
+impl From<VertexAIClient> for AnyClient {
+    fn from(client: VertexAIClient) -> Self {
+        Self::VertexAI(client)
+    }
+}
+
+#[cfg(feature = "groq")]
+impl From<GroqClient> for AnyClient {
+    fn from(client: GroqClient) -> Self {
+        Self::Groq(client)
+    }
+}
+
+#[cfg(feature = "together")]
+impl From<TogetherClient> for AnyClient {
+    fn from(client: TogetherClient) -> Self {
+        Self::Together(client)
+    }
+}
+
 /// Dispatch a method call to whichever provider this `AnyClient` wraps.
 macro_rules! dispatch {
     ($self:expr, $client:ident => $call:expr) => {
@@ -178,6 +539,18 @@ macro_rules! dispatch {
             Self::Grok($client) => $call,
             #[cfg(feature = "gemini")]
             Self::Gemini($client) => $call,
+            #[cfg(feature = "ollama")]
+            Self::Ollama($client) => $call,
+            #[cfg(feature = "azure")]
+            Self::AzureOpenAI($client) => $call,
+            #[cfg(feature = "bedrock")]
+            Self::Bedrock($client) => $call,
+            #[cfg(feature = "vertex")]
+            Self::VertexAI($client) => $call,
+            #[cfg(feature = "groq")]
+            Self::Groq($client) => $call,
+            #[cfg(feature = "together")]
+            Self::Together($client) => $call,
         }
     };
 }
@@ -217,11 +590,28 @@ impl LLMClient for AnyClient {
         dispatch!(self, c => c.generate_with_metadata(prompt).await)
     }
 
+    async fn materialize_conversation<T>(
+        &self,
+        messages: &[ChatMessage],
+    ) -> Result<MaterializeResult<T>>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        dispatch!(self, c => c.materialize_conversation(messages).await)
+    }
+
+    async fn generate_conversation(&self, messages: &[ChatMessage]) -> Result<GenerateResult> {
+        dispatch!(self, c => c.generate_conversation(messages).await)
+    }
+
     /// Auto-detect a provider from the environment.
     ///
-    /// Enabled providers are tried in order (OpenAI, Anthropic, Grok, Gemini)
-    /// and the first one whose API-key variable is set is used. For deterministic
-    /// selection, prefer [`AnyClient::from_env_for`].
+    /// Enabled providers are tried in order (OpenAI, Anthropic, Grok, Gemini,
+    /// Azure OpenAI, Groq, Together.ai) and the first one whose API-key variable is set is used. Ollama, which
+    /// needs no key, is chosen last if `OLLAMA_HOST` is set. Bedrock and
+    /// Vertex AI are never auto-detected, since cloud credentials in the
+    /// environment are usually there for other services; select it with [`AnyClient::from_env_for`],
+    /// which is also the way to a deterministic choice.
     ///
     /// # Errors
     ///
@@ -244,6 +634,22 @@ impl LLMClient for AnyClient {
         if std::env::var("GEMINI_API_KEY").is_ok() {
             return Ok(Self::Gemini(GeminiClient::from_env()?));
         }
+        #[cfg(feature = "azure")]
+        if std::env::var("AZURE_OPENAI_API_KEY").is_ok() {
+            return Ok(Self::AzureOpenAI(AzureOpenAIClient::from_env()?));
+        }
+        #[cfg(feature = "groq")]
+        if std::env::var("GROQ_API_KEY").is_ok() {
+            return Ok(Self::Groq(GroqClient::from_env()?));
+        }
+        #[cfg(feature = "together")]
+        if std::env::var("TOGETHER_API_KEY").is_ok() {
+            return Ok(Self::Together(TogetherClient::from_env()?));
+        }
+        #[cfg(feature = "ollama")]
+        if std::env::var("OLLAMA_HOST").is_ok() {
+            return Ok(Self::Ollama(OllamaClient::from_env()?));
+        }
         Err(RStructorError::api_error(
             "AnyClient",
             ApiErrorKind::AuthenticationFailed,
@@ -0,0 +1,833 @@
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, info, instrument, trace};
+
+use crate::backend::openai::Model;
+#[cfg(feature = "streaming")]
+use crate::backend::text_messages;
+use crate::backend::{
+    ChatMessage, ContextOverflow, DEFAULT_REQUEST_TIMEOUT, GenerateResult, HttpClientCell,
+    LLMClient, MaterializeInternalOutput, MaterializeResult, ModelInfo,
+    OpenAICompatibleChatCompletionRequest, OpenAICompatibleChatCompletionResponse, RateLimiter,
+    ResponseFormat, ThinkingLevel, TokenUsage, UsageTracker, ValidationFailureContext,
+    check_response_status, convert_openai_compatible_chat_messages,
+    generate_with_retry_with_conversation, generate_with_retry_with_history,
+    materialize_with_media_with_retry, model_override, parse_validate_and_create_output,
+    send_limited, with_idempotency_key,
+};
+use crate::error::{ApiErrorKind, RStructorError, Result};
+use crate::model::Instructor;
+
+/// Azure OpenAI data-plane API version sent when none is configured.
+pub const DEFAULT_AZURE_API_VERSION: &str = "2024-10-21";
+
+/// Configuration for the Azure OpenAI client
+#[derive(Debug, Clone)]
+pub struct AzureOpenAIConfig {
+    /// Resource key, sent in the `api-key` header.
+    pub api_key: String,
+    /// Resource endpoint, e.g. "https://my-resource.openai.azure.com".
+    pub endpoint: String,
+    /// Value of the `api-version` query parameter.
+    pub api_version: String,
+    /// The OpenAI model behind the deployment. Selects the deployment (see
+    /// `deployments`) and decides capabilities such as reasoning support.
+    pub model: Model,
+    /// Deployment name for each model name. Models without an entry are sent
+    /// to a deployment named after the model, Azure's default naming.
+    pub deployments: HashMap<String, String>,
+    pub temperature: f32,
+    pub max_tokens: Option<u32>,
+    /// Total timeout for each HTTP request.
+    /// Defaults to [`DEFAULT_REQUEST_TIMEOUT`](crate::DEFAULT_REQUEST_TIMEOUT) (5 minutes).
+    pub timeout: Option<Duration>,
+    pub max_retries: Option<usize>,
+    /// Collect fields the model returned but the schema does not declare into
+    /// [`MaterializeResult::extra_fields`](crate::MaterializeResult::extra_fields).
+    pub capture_unknown_fields: bool,
+    /// Ask for a rationale in a generated
+    /// [`EXPLANATION_FIELD`](crate::EXPLANATION_FIELD) property, returned in
+    /// [`MaterializeResult::explanation`](crate::MaterializeResult::explanation).
+    pub explain: bool,
+    /// What `materialize` does when the prompt exceeds the context window.
+    pub context_overflow: ContextOverflow,
+    /// System prompt sent with every request, as the provider's system
+    /// instruction rather than as part of the user prompt.
+    pub system_prompt: Option<String>,
+    /// Locale the schema's descriptions are sent in; see
+    /// [`Schema::localized`](crate::Schema::localized).
+    pub schema_locale: Option<String>,
+    /// Translations consulted before those recorded by the derive.
+    pub description_catalog: Option<Arc<crate::schema::DescriptionCatalog>>,
+    /// Request and token budgets this client's requests wait for, possibly
+    /// shared with other clients.
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    /// Counts the requests and tokens of this client's structured calls,
+    /// possibly shared with other clients.
+    pub usage_tracker: UsageTracker,
+    /// Largest request body this client sends; bigger requests fail with
+    /// [`RStructorError::RequestTooLarge`] before anything is uploaded.
+    /// `None` (the default: Azure OpenAI publishes no limit) sends any size.
+    pub max_request_bytes: Option<usize>,
+    /// Replaces `endpoint`, e.g. for an API Management gateway in front of
+    /// the resource.
+    pub base_url: Option<String>,
+    /// Thinking level for GPT-5.x deployments (reasoning effort)
+    pub thinking_level: Option<ThinkingLevel>,
+}
+
+/// Azure OpenAI client for generating completions.
+///
+/// Azure serves OpenAI models from named *deployments*: requests go to
+/// `{endpoint}/openai/deployments/{deployment}/chat/completions?api-version=...`
+/// and authenticate with an `api-key` header, so [`OpenAIClient`](crate::OpenAIClient)
+/// with a `base_url` cannot reach it. Models are configured with
+/// [`OpenAIModel`](crate::OpenAIModel) values and routed to deployments with
+/// [`deployment_for`](Self::deployment_for).
+///
+/// Cheap to clone and `Send + Sync`; see [sharing clients](crate::LLMClient#sharing-clients).
+#[derive(Clone)]
+pub struct AzureOpenAIClient {
+    config: Arc<AzureOpenAIConfig>,
+    client: HttpClientCell,
+}
+
+impl AzureOpenAIClient {
+    /// Create a client for the Azure OpenAI resource at `endpoint`.
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoint` - Resource endpoint, e.g. "https://my-resource.openai.azure.com"
+    /// * `api_key` - One of the resource's keys
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rstructor::{AzureOpenAIClient, OpenAIModel};
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = AzureOpenAIClient::new("https://my-resource.openai.azure.com", "key")?
+    ///     .model(OpenAIModel::Gpt41Mini)
+    ///     .deployment_for(OpenAIModel::Gpt41Mini, "extraction-prod");
+    /// assert_eq!(client.deployment(), "extraction-prod");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(name = "azure_openai_client_new", skip(endpoint, api_key), fields(model = ?Model::Gpt41))]
+    pub fn new(endpoint: impl Into<String>, api_key: impl Into<String>) -> Result<Self> {
+        let api_key = api_key.into();
+        if api_key.is_empty() {
+            return Err(RStructorError::api_error(
+                "Azure OpenAI",
+                ApiErrorKind::AuthenticationFailed,
+            ));
+        }
+        info!("Creating new Azure OpenAI client");
+        trace!("API key length: {}", api_key.len());
+
+        let config = AzureOpenAIConfig {
+            api_key,
+            endpoint: endpoint.into().trim_end_matches('/').to_string(),
+            api_version: DEFAULT_AZURE_API_VERSION.to_string(),
+            model: Model::Gpt41, // Default to GPT-4.1 (widely deployed on Azure)
+            deployments: HashMap::new(),
+            temperature: 0.0,
+            max_tokens: None,
+            timeout: Some(DEFAULT_REQUEST_TIMEOUT), // Default: 5-minute request timeout
+            max_retries: Some(3),                   // Default: 3 retries with error feedback
+            capture_unknown_fields: false,
+            explain: false,
+            context_overflow: ContextOverflow::default(),
+            system_prompt: None,
+            schema_locale: None,
+            description_catalog: None,
+            rate_limiter: None,
+            usage_tracker: UsageTracker::new(),
+            max_request_bytes: None,
+            base_url: None,
+            thinking_level: Some(ThinkingLevel::Medium),
+        };
+
+        debug!("Azure OpenAI client created with default configuration");
+        Ok(Self {
+            config: Arc::new(config),
+            client: HttpClientCell::default(),
+        })
+    }
+
+    /// Create a client from `AZURE_OPENAI_ENDPOINT` and `AZURE_OPENAI_API_KEY`.
+    ///
+    /// `AZURE_OPENAI_API_VERSION` overrides the API version, and
+    /// `AZURE_OPENAI_DEPLOYMENT` names the deployment serving the default model.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `AZURE_OPENAI_ENDPOINT` or `AZURE_OPENAI_API_KEY` is not set.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use rstructor::AzureOpenAIClient;
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = AzureOpenAIClient::from_env()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(name = "azure_openai_client_from_env")]
+    pub fn from_env() -> Result<Self> {
+        let missing =
+            || RStructorError::api_error("Azure OpenAI", ApiErrorKind::AuthenticationFailed);
+        let endpoint = std::env::var("AZURE_OPENAI_ENDPOINT").map_err(|_| missing())?;
+        let api_key = std::env::var("AZURE_OPENAI_API_KEY").map_err(|_| missing())?;
+
+        info!("Creating new Azure OpenAI client from environment variables");
+        let mut client = Self::new(endpoint, api_key)?;
+        if let Ok(version) = std::env::var("AZURE_OPENAI_API_VERSION") {
+            client = client.api_version(version);
+        }
+        if let Ok(deployment) = std::env::var("AZURE_OPENAI_DEPLOYMENT") {
+            let model = client.config.model.clone();
+            client = client.deployment_for(model, deployment);
+        }
+        Ok(client)
+    }
+
+    // Builder methods are generated by the macro below
+
+    /// The deployment requests for the configured model are sent to.
+    pub fn deployment(&self) -> &str {
+        self.deployment_of(self.config.model.as_str())
+    }
+
+    fn deployment_of<'a>(&'a self, model: &'a str) -> &'a str {
+        self.config
+            .deployments
+            .get(model)
+            .map_or(model, String::as_str)
+    }
+
+    /// URL of `path` under the deployment serving `model`.
+    fn deployment_url(&self, model: &str, path: &str) -> String {
+        format!(
+            "{}/openai/deployments/{}/{}?api-version={}",
+            self.base(),
+            self.deployment_of(model),
+            path,
+            self.config.api_version
+        )
+    }
+
+    fn base(&self) -> &str {
+        self.config
+            .base_url
+            .as_deref()
+            .unwrap_or(&self.config.endpoint)
+    }
+
+    /// Reasoning effort and temperature for the configured model.
+    fn sampling(&self) -> (Option<String>, f32) {
+        let reasoning_effort = if self.capabilities().thinking {
+            self.config
+                .thinking_level
+                .and_then(|level| level.openai_reasoning_effort().map(|s| s.to_string()))
+        } else {
+            None
+        };
+        // GPT-5.x with reasoning requires temperature=1.0
+        let temperature = if reasoning_effort.is_some() {
+            1.0
+        } else {
+            self.config.temperature
+        };
+        (reasoning_effort, temperature)
+    }
+
+    /// POST a chat completion to the deployment serving `request.model`.
+    async fn chat(
+        &self,
+        request: &OpenAICompatibleChatCompletionRequest,
+        idempotent: bool,
+    ) -> Result<OpenAICompatibleChatCompletionResponse> {
+        let url = self.deployment_url(&request.model, "chat/completions");
+        debug!(url = %url, "Sending request to Azure OpenAI");
+        let builder = self.http().post(&url);
+        let builder = if idempotent {
+            with_idempotency_key(builder)
+        } else {
+            builder
+        };
+        let response = send_limited(
+            "Azure OpenAI",
+            self.config.rate_limiter.as_deref(),
+            self.config.max_request_bytes,
+            builder
+                .header("api-key", self.api_key_for_call())
+                .header("Content-Type", "application/json")
+                .json(request),
+        )
+        .await?;
+
+        let response = check_response_status(response, "Azure OpenAI").await?;
+
+        debug!("Successfully received response from Azure OpenAI");
+        let completion: OpenAICompatibleChatCompletionResponse =
+            response.json().await.map_err(|e| {
+                error!(error = %e, "Failed to parse JSON response from Azure OpenAI");
+                e
+            })?;
+        if completion.choices.is_empty() {
+            error!("Azure OpenAI returned empty choices array");
+            return Err(RStructorError::api_error(
+                "Azure OpenAI",
+                ApiErrorKind::UnexpectedResponse {
+                    details: "No completion choices returned".to_string(),
+                },
+            ));
+        }
+        Ok(completion)
+    }
+
+    /// The assistant text and token usage of a completion.
+    fn reply(
+        &self,
+        completion: &OpenAICompatibleChatCompletionResponse,
+    ) -> Result<(String, Option<TokenUsage>)> {
+        let model_name = completion
+            .model
+            .clone()
+            .unwrap_or_else(|| self.config.model.as_str().to_string());
+        let usage = completion
+            .usage
+            .as_ref()
+            .map(|u| TokenUsage::new(model_name, u.prompt_tokens, u.completion_tokens));
+        trace!(finish_reason = %completion.choices[0].finish_reason, "Completion finish reason");
+        match &completion.choices[0].message.content {
+            Some(content) => Ok((content.clone(), usage)),
+            None => {
+                error!("No content in Azure OpenAI response");
+                Err(RStructorError::api_error(
+                    "Azure OpenAI",
+                    ApiErrorKind::UnexpectedResponse {
+                        details: "No content in response".to_string(),
+                    },
+                ))
+            }
+        }
+    }
+
+    /// Internal implementation of materialize (without retry logic)
+    /// Accepts conversation history for multi-turn interactions.
+    /// Returns the data, raw response, and optional usage info.
+    ///
+    /// Uses native Structured Outputs (`response_format: json_schema`), as
+    /// [`OpenAIClient`](crate::OpenAIClient) does.
+    async fn materialize_internal<T>(
+        &self,
+        messages: &[ChatMessage],
+    ) -> std::result::Result<
+        MaterializeInternalOutput<T>,
+        (RStructorError, Option<ValidationFailureContext>),
+    >
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        info!("Generating structured response with Azure OpenAI (native structured outputs)");
+
+        let schema_name = T::schema_name().unwrap_or_else(|| "output".to_string());
+        let response_format = ResponseFormat::json_schema(
+            schema_name,
+            self.output_schema::<T>().to_openai_strict(),
+            Some("Output in the specified format. Include ALL required fields and follow the schema exactly.".to_string()),
+        );
+        let (reasoning_effort, temperature) = self.sampling();
+        let api_messages = convert_openai_compatible_chat_messages(
+            messages,
+            "Azure OpenAI",
+            self.config.system_prompt.as_deref(),
+        )
+        .map_err(|e| (e, None))?;
+
+        debug!(
+            "Building Azure OpenAI request with structured outputs (history_len={})",
+            api_messages.len()
+        );
+        let request = OpenAICompatibleChatCompletionRequest {
+            model: model_override().unwrap_or_else(|| self.config.model.as_str().to_string()),
+            messages: api_messages,
+            response_format: Some(response_format),
+            temperature,
+            max_tokens: self.config.max_tokens,
+            reasoning_effort,
+        };
+
+        let completion = self.chat(&request, true).await.map_err(|e| (e, None))?;
+        let (raw_response, usage) = self.reply(&completion).map_err(|e| (e, None))?;
+        debug!(
+            content_len = raw_response.len(),
+            "Structured output received from Azure OpenAI"
+        );
+        parse_validate_and_create_output(raw_response, usage, "Azure OpenAI")
+    }
+
+    /// Internal implementation of raw text generation (no structured output).
+    async fn generate_internal(&self, messages: &[ChatMessage]) -> Result<GenerateResult> {
+        info!("Generating raw text response with Azure OpenAI");
+        let (reasoning_effort, temperature) = self.sampling();
+        let request = OpenAICompatibleChatCompletionRequest {
+            model: self.config.model.as_str().to_string(),
+            messages: convert_openai_compatible_chat_messages(
+                messages,
+                "Azure OpenAI",
+                self.config.system_prompt.as_deref(),
+            )?,
+            response_format: None,
+            temperature,
+            max_tokens: self.config.max_tokens,
+            reasoning_effort,
+        };
+        let completion = self.chat(&request, false).await?;
+        let (text, usage) = self.reply(&completion)?;
+        Ok(GenerateResult::new(text, usage))
+    }
+}
+
+// Generate builder methods using macro
+crate::impl_client_builder_methods! {
+    client_type: AzureOpenAIClient,
+    config_type: AzureOpenAIConfig,
+    model_type: Model,
+    provider_name: "Azure OpenAI",
+    max_temperature: 2.0
+}
+
+impl AzureOpenAIClient {
+    /// Send requests for `model` to the deployment named `deployment`.
+    ///
+    /// Routing follows the model, so a [`ContextOverflow::Escalate`] retry on a
+    /// larger model goes to that model's deployment.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rstructor::{AzureOpenAIClient, OpenAIModel};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = AzureOpenAIClient::new("https://my-resource.openai.azure.com", "key")?
+    ///     .deployment_for(OpenAIModel::Gpt41, "gpt41-eastus")
+    ///     .deployment_for(OpenAIModel::Gpt41Mini, "gpt41mini-eastus");
+    /// assert_eq!(client.deployment(), "gpt41-eastus");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[tracing::instrument(skip(self, model, deployment))]
+    pub fn deployment_for(
+        mut self,
+        model: impl Into<Model>,
+        deployment: impl Into<String>,
+    ) -> Self {
+        let model = model.into();
+        let deployment = deployment.into();
+        tracing::debug!(model = %model.as_str(), deployment = %deployment, "Mapping deployment");
+        Arc::make_mut(&mut self.config)
+            .deployments
+            .insert(model.as_str().to_string(), deployment);
+        self
+    }
+
+    /// Set the `api-version` query parameter (default [`DEFAULT_AZURE_API_VERSION`]).
+    #[tracing::instrument(skip(self, api_version))]
+    pub fn api_version(mut self, api_version: impl Into<String>) -> Self {
+        Arc::make_mut(&mut self.config).api_version = api_version.into();
+        self
+    }
+
+    /// Route requests through `base_url` instead of the resource endpoint,
+    /// e.g. an API Management gateway. The `/openai/deployments/...` path is
+    /// appended to it.
+    #[tracing::instrument(skip(self, base_url))]
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        let base_url_str = base_url.into();
+        tracing::debug!(
+            previous_base_url = ?self.config.base_url,
+            new_base_url = %base_url_str,
+            "Setting custom base URL"
+        );
+        Arc::make_mut(&mut self.config).base_url = Some(base_url_str);
+        self
+    }
+
+    /// Set the thinking level for GPT-5.x deployments (reasoning effort).
+    ///
+    /// Behaves as [`OpenAIClient::thinking_level`](crate::OpenAIClient::thinking_level).
+    #[tracing::instrument(skip(self))]
+    pub fn thinking_level(mut self, level: ThinkingLevel) -> Self {
+        Arc::make_mut(&mut self.config).thinking_level = Some(level);
+        self
+    }
+}
+
+#[cfg(feature = "streaming")]
+impl AzureOpenAIClient {
+    /// Build the JSON request body for a streaming call (`stream: true`),
+    /// optionally with a structured-output `response_format`.
+    fn stream_body(
+        &self,
+        prompt: &str,
+        response_format: Option<ResponseFormat>,
+    ) -> serde_json::Value {
+        let (reasoning_effort, temperature) = self.sampling();
+        let request = OpenAICompatibleChatCompletionRequest {
+            model: self.config.model.as_str().to_string(),
+            messages: text_messages(self.config.system_prompt.as_deref(), prompt),
+            response_format,
+            temperature,
+            max_tokens: self.config.max_tokens,
+            reasoning_effort,
+        };
+        let mut body = serde_json::to_value(&request).unwrap_or_else(|_| serde_json::json!({}));
+        body["stream"] = serde_json::Value::Bool(true);
+        body
+    }
+
+    /// Send a streaming request and return the raw SSE response.
+    fn send_stream(
+        &self,
+        body: serde_json::Value,
+    ) -> impl std::future::Future<Output = Result<reqwest::Response>> + Send + 'static {
+        let client = self.http().clone();
+        let api_key = self.api_key_for_call();
+        let url = self.deployment_url(self.config.model.as_str(), "chat/completions");
+        let rate_limiter = self.config.rate_limiter.clone();
+        let max_request_bytes = self.config.max_request_bytes;
+        async move {
+            let resp = send_limited(
+                "Azure OpenAI",
+                rate_limiter.as_deref(),
+                max_request_bytes,
+                client
+                    .post(&url)
+                    .header("api-key", api_key)
+                    .header("Content-Type", "application/json")
+                    .json(&body),
+            )
+            .await?;
+            check_response_status(resp, "Azure OpenAI").await
+        }
+    }
+}
+
+#[async_trait]
+impl LLMClient for AzureOpenAIClient {
+    fn from_env() -> Result<Self> {
+        Self::from_env()
+    }
+
+    #[instrument(
+        name = "azure_openai_materialize",
+        skip(self, prompt),
+        fields(
+            type_name = std::any::type_name::<T>(),
+            deployment = %self.deployment(),
+            prompt_len = prompt.len()
+        )
+    )]
+    async fn materialize<T>(&self, prompt: &str) -> Result<T>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        let output = self
+            .config
+            .usage_tracker
+            .scope(generate_with_retry_with_history(
+                |messages: Vec<ChatMessage>| {
+                    let this = self;
+                    async move { this.materialize_internal::<T>(&messages).await }
+                },
+                prompt,
+                self.config.max_retries,
+                &self.config.context_overflow,
+            ))
+            .await?;
+        Ok(output.data)
+    }
+
+    #[instrument(
+        name = "azure_openai_materialize_with_media",
+        skip(self, prompt, media),
+        fields(
+            type_name = std::any::type_name::<T>(),
+            deployment = %self.deployment(),
+            prompt_len = prompt.len(),
+            media_len = media.len()
+        )
+    )]
+    async fn materialize_with_media<T>(&self, prompt: &str, media: &[super::MediaFile]) -> Result<T>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        self.config
+            .usage_tracker
+            .scope(materialize_with_media_with_retry(
+                |messages: Vec<ChatMessage>| {
+                    let this = self;
+                    async move { this.materialize_internal::<T>(&messages).await }
+                },
+                prompt,
+                media,
+                self.config.max_retries,
+                &self.config.context_overflow,
+            ))
+            .await
+    }
+
+    #[instrument(
+        name = "azure_openai_materialize_with_metadata",
+        skip(self, prompt),
+        fields(
+            type_name = std::any::type_name::<T>(),
+            deployment = %self.deployment(),
+            prompt_len = prompt.len()
+        )
+    )]
+    async fn materialize_with_metadata<T>(&self, prompt: &str) -> Result<MaterializeResult<T>>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        let output = self
+            .config
+            .usage_tracker
+            .scope(generate_with_retry_with_history(
+                |messages: Vec<ChatMessage>| {
+                    let this = self;
+                    async move { this.materialize_internal::<T>(&messages).await }
+                },
+                prompt,
+                self.config.max_retries,
+                &self.config.context_overflow,
+            ))
+            .await?;
+        Ok(output.into_result(self.config.capture_unknown_fields))
+    }
+
+    #[instrument(
+        name = "azure_openai_generate",
+        skip(self, prompt),
+        fields(deployment = %self.deployment(), prompt_len = prompt.len())
+    )]
+    async fn generate(&self, prompt: &str) -> Result<String> {
+        let result = self.generate_with_metadata(prompt).await?;
+        Ok(result.text)
+    }
+
+    #[instrument(
+        name = "azure_openai_generate_with_media",
+        skip(self, prompt, media),
+        fields(
+            deployment = %self.deployment(),
+            prompt_len = prompt.len(),
+            media_len = media.len()
+        )
+    )]
+    async fn generate_with_media(
+        &self,
+        prompt: &str,
+        media: &[super::MediaFile],
+    ) -> Result<String> {
+        let result = self
+            .generate_internal(&[ChatMessage::user_with_media(prompt, media.to_vec())])
+            .await?;
+        Ok(result.text)
+    }
+
+    #[instrument(
+        name = "azure_openai_generate_with_metadata",
+        skip(self, prompt),
+        fields(deployment = %self.deployment(), prompt_len = prompt.len())
+    )]
+    async fn generate_with_metadata(&self, prompt: &str) -> Result<GenerateResult> {
+        self.generate_internal(&[ChatMessage::user(prompt)]).await
+    }
+
+    #[instrument(
+        name = "azure_openai_materialize_conversation",
+        skip(self, messages),
+        fields(
+            type_name = std::any::type_name::<T>(),
+            deployment = %self.deployment(),
+            history_len = messages.len()
+        )
+    )]
+    async fn materialize_conversation<T>(
+        &self,
+        messages: &[ChatMessage],
+    ) -> Result<MaterializeResult<T>>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        let output = self
+            .config
+            .usage_tracker
+            .scope(generate_with_retry_with_conversation(
+                |messages: Vec<ChatMessage>| {
+                    let this = self;
+                    async move { this.materialize_internal::<T>(&messages).await }
+                },
+                messages,
+                self.config.max_retries,
+                &self.config.context_overflow,
+            ))
+            .await?;
+        Ok(output.into_result(self.config.capture_unknown_fields))
+    }
+
+    #[instrument(
+        name = "azure_openai_generate_conversation",
+        skip(self, messages),
+        fields(deployment = %self.deployment(), history_len = messages.len())
+    )]
+    async fn generate_conversation(&self, messages: &[ChatMessage]) -> Result<GenerateResult> {
+        self.generate_internal(messages).await
+    }
+
+    #[cfg(feature = "streaming")]
+    fn generate_stream<'a>(&'a self, prompt: &'a str) -> crate::backend::streaming::TextStream<'a>
+    where
+        Self: Sync,
+    {
+        let body = self.stream_body(prompt, None);
+        crate::backend::streaming::sse_text_stream(
+            self.send_stream(body),
+            crate::backend::streaming::openai_delta,
+        )
+    }
+
+    #[cfg(feature = "streaming")]
+    fn materialize_stream<'a, T>(
+        &'a self,
+        prompt: &'a str,
+    ) -> crate::backend::streaming::ObjectStream<'a, T>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+        Self: Sync,
+    {
+        let schema_name = T::schema_name().unwrap_or_else(|| "output".to_string());
+        let response_format = ResponseFormat::json_schema(
+            schema_name,
+            self.localized_schema::<T>().to_openai_strict(),
+            Some("Output in the specified format. Include ALL required fields and follow the schema exactly.".to_string()),
+        );
+        let body = self.stream_body(prompt, Some(response_format));
+        crate::backend::streaming::object_stream(
+            self.send_stream(body),
+            crate::backend::streaming::openai_delta,
+        )
+    }
+
+    #[cfg(feature = "streaming")]
+    fn materialize_iter<'a, T>(
+        &'a self,
+        prompt: &'a str,
+    ) -> crate::backend::streaming::ItemStream<'a, T>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+        Self: Sync,
+    {
+        let item_schema = self.localized_schema::<T>().to_openai_strict();
+        let wrapper = crate::backend::streaming::array_wrapper_schema(item_schema, true);
+        let response_format = ResponseFormat::json_schema(
+            "items".to_string(),
+            wrapper,
+            Some("Return a JSON object with an `items` array; each element must follow the item schema exactly.".to_string()),
+        );
+        let body = self.stream_body(prompt, Some(response_format));
+        crate::backend::streaming::iter_stream(
+            self.send_stream(body),
+            crate::backend::streaming::openai_delta,
+            crate::backend::streaming::finalize_item::<T>,
+        )
+    }
+
+    /// List the models the resource can deploy, via `/openai/models`.
+    ///
+    /// Azure has no data-plane endpoint listing *deployments*; use these ids
+    /// with [`deployment_for`](AzureOpenAIClient::deployment_for).
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        let url = format!(
+            "{}/openai/models?api-version={}",
+            self.base(),
+            self.config.api_version
+        );
+        debug!(url = %url, "Fetching available models from Azure OpenAI");
+
+        let response = send_limited(
+            "Azure OpenAI",
+            self.config.rate_limiter.as_deref(),
+            self.config.max_request_bytes,
+            self.http()
+                .get(&url)
+                .header("api-key", self.api_key_for_call()),
+        )
+        .await?;
+
+        let response = check_response_status(response, "Azure OpenAI").await?;
+
+        let json: serde_json::Value = response.json().await.map_err(|e| {
+            error!(error = %e, "Failed to parse models response from Azure OpenAI");
+            e
+        })?;
+
+        let models = json
+            .get("data")
+            .and_then(|data| data.as_array())
+            .map(|models_array| {
+                models_array
+                    .iter()
+                    .filter_map(|model| {
+                        let id = model.get("id").and_then(|id| id.as_str())?;
+                        // Chat completion models only, as for OpenAI
+                        id.starts_with("gpt-").then(|| ModelInfo {
+                            id: id.to_string(),
+                            name: None,
+                            description: None,
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        debug!(count = models.len(), "Fetched Azure OpenAI models");
+        Ok(models)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deployment_defaults_to_the_model_name() {
+        let client = AzureOpenAIClient::new("https://res.openai.azure.com/", "key")
+            .unwrap()
+            .model(Model::Gpt4OMini);
+        assert_eq!(client.deployment(), "gpt-4o-mini");
+        assert_eq!(
+            client.deployment_url("gpt-4o-mini", "chat/completions"),
+            format!(
+                "https://res.openai.azure.com/openai/deployments/gpt-4o-mini/chat/completions?api-version={DEFAULT_AZURE_API_VERSION}"
+            )
+        );
+    }
+
+    #[test]
+    fn escalated_models_use_their_own_deployment() {
+        let client = AzureOpenAIClient::new("https://res.openai.azure.com", "key")
+            .unwrap()
+            .deployment_for(Model::Gpt41, "big")
+            .deployment_for(Model::Gpt41Mini, "small")
+            .model(Model::Gpt41Mini);
+        assert_eq!(client.deployment(), "small");
+        assert_eq!(client.deployment_of("gpt-4.1"), "big");
+    }
+}
@@ -0,0 +1,86 @@
+//! Time-boxed structured calls for interactive UIs.
+//!
+//! [`LLMClient::materialize_within`](crate::LLMClient::materialize_within)
+//! runs `materialize` against a time limit. The retry engine records each
+//! reply that failed validation in the calling task, so when the limit passes
+//! during a re-ask, the last of those replies is returned as
+//! [`BestEffort::Unvalidated`] instead of leaving the UI waiting on the retry.
+
+use std::cell::RefCell;
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+
+use crate::backend::{LLMClient, ValidationFailureContext};
+use crate::error::{RStructorError, Result};
+use crate::model::Instructor;
+
+/// The outcome of [`LLMClient::materialize_within`](crate::LLMClient::materialize_within).
+#[derive(Debug, Clone, PartialEq)]
+pub enum BestEffort<T> {
+    /// The reply validated before the time limit.
+    Valid(T),
+    /// The time limit passed while a failed reply was being re-asked. `value`
+    /// is that reply, which deserialized but did not pass validation for the
+    /// reason in `error`.
+    Unvalidated {
+        /// The last reply that deserialized.
+        value: T,
+        /// Why it failed validation.
+        error: String,
+    },
+}
+
+impl<T> BestEffort<T> {
+    /// Whether the value passed validation.
+    pub fn is_valid(&self) -> bool {
+        matches!(self, Self::Valid(_))
+    }
+
+    /// The value, validated or not.
+    pub fn into_inner(self) -> T {
+        match self {
+            Self::Valid(value) | Self::Unvalidated { value, .. } => value,
+        }
+    }
+}
+
+tokio::task_local! {
+    /// The last reply that failed validation in the current task's call.
+    static LAST_FAILURE: RefCell<Option<ValidationFailureContext>>;
+}
+
+/// Note that the retry engine rejected a reply, for `materialize_within`.
+pub(crate) fn note_failed_reply(ctx: &ValidationFailureContext) {
+    let _ = LAST_FAILURE.try_with(|last| *last.borrow_mut() = Some(ctx.clone()));
+}
+
+pub(crate) async fn materialize_within<C, T>(
+    client: &C,
+    prompt: &str,
+    limit: Duration,
+) -> Result<BestEffort<T>>
+where
+    C: LLMClient + Sync + ?Sized,
+    T: Instructor + DeserializeOwned + Send + 'static,
+{
+    LAST_FAILURE
+        .scope(RefCell::new(None), async {
+            match tokio::time::timeout(limit, client.materialize::<T>(prompt)).await {
+                Ok(result) => result.map(BestEffort::Valid),
+                Err(_) => {
+                    let last = LAST_FAILURE.with(|last| last.borrow_mut().take());
+                    last.and_then(|ctx| {
+                        let value =
+                            crate::backend::utils::parse_unvalidated::<T>(&ctx.raw_response)?;
+                        Some(BestEffort::Unvalidated {
+                            value,
+                            error: ctx.error_message,
+                        })
+                    })
+                    .ok_or(RStructorError::Timeout)
+                }
+            }
+        })
+        .await
+}
@@ -0,0 +1,170 @@
+//! Capping retries across several requests that make up one operation.
+//!
+//! `max_retries` bounds each call on its own, so a pipeline of four extraction
+//! steps with `max_retries(3)` can send up to sixteen requests. A
+//! [`RetryBudget`] is a shared allowance of retries: every call made inside
+//! [`RetryBudget::scope`] draws its re-asks and transient-error retries from
+//! the same pool, and once it is spent the next failure is returned as-is.
+//!
+//! ```no_run
+//! # #[cfg(feature = "openai")]
+//! # async fn run() -> rstructor::Result<()> {
+//! use rstructor::{Instructor, LLMClient, OpenAIClient, RetryBudget};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Instructor, Serialize, Deserialize)]
+//! struct Summary { text: String }
+//!
+//! #[derive(Instructor, Serialize, Deserialize)]
+//! struct Topics { topics: Vec<String> }
+//!
+//! let client = OpenAIClient::from_env()?;
+//! let budget = RetryBudget::new(3);
+//! let (summary, topics) = budget
+//!     .scope(async {
+//!         let summary: Summary = client.materialize("Summarize: ...").await?;
+//!         let topics: Topics = client.materialize("List the topics of: ...").await?;
+//!         Ok::<_, rstructor::RStructorError>((summary, topics))
+//!     })
+//!     .await?;
+//! println!("{} retries left", budget.remaining());
+//! # let _ = (summary, topics);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A pool of retries shared by every structured request made within
+/// [`scope`](Self::scope).
+///
+/// Each retry the engine would send — a validation re-ask, a retry after a
+/// rate limit or transient error, or a [`ContextOverflow`](crate::ContextOverflow)
+/// recovery — takes one retry from the budget. The per-client `max_retries`
+/// still applies; the budget only adds a ceiling across calls. Clones share the
+/// same pool.
+///
+/// The budget is carried by the current task, so calls inside a
+/// `tokio::spawn`ed task are not covered unless the spawned future is wrapped
+/// in `scope` too.
+#[derive(Debug, Clone)]
+pub struct RetryBudget {
+    state: Arc<BudgetState>,
+}
+
+#[derive(Debug)]
+struct BudgetState {
+    max_retries: usize,
+    used: AtomicUsize,
+}
+
+tokio::task_local! {
+    /// Budget the current task's requests draw their retries from.
+    static RETRY_BUDGET: RetryBudget;
+}
+
+impl RetryBudget {
+    /// A budget allowing `max_retries` retries in total.
+    ///
+    /// ```
+    /// use rstructor::RetryBudget;
+    ///
+    /// let budget = RetryBudget::new(5);
+    /// assert_eq!(budget.remaining(), 5);
+    /// assert_eq!(budget.used(), 0);
+    /// ```
+    #[must_use]
+    pub fn new(max_retries: usize) -> Self {
+        Self {
+            state: Arc::new(BudgetState {
+                max_retries,
+                used: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    /// The total number of retries this budget allows.
+    #[must_use]
+    pub fn max_retries(&self) -> usize {
+        self.state.max_retries
+    }
+
+    /// Retries taken so far.
+    #[must_use]
+    pub fn used(&self) -> usize {
+        self.state.used.load(Ordering::Relaxed)
+    }
+
+    /// Retries still available.
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        self.state.max_retries.saturating_sub(self.used())
+    }
+
+    /// Whether every retry has been used.
+    #[must_use]
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// Run `future` with this budget covering every request it makes.
+    ///
+    /// Scopes nest: an inner scope's requests draw only from the inner budget.
+    pub async fn scope<F: Future>(&self, future: F) -> F::Output {
+        RETRY_BUDGET.scope(self.clone(), future).await
+    }
+
+    /// Take one retry, returning `false` if none are left.
+    fn try_take(&self) -> bool {
+        self.state
+            .used
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| {
+                (used < self.state.max_retries).then_some(used + 1)
+            })
+            .is_ok()
+    }
+}
+
+/// Take one retry from the budget in scope, if any. Returns `false` only when
+/// a budget is in scope and has been spent.
+pub(crate) fn take_retry() -> bool {
+    RETRY_BUDGET.try_with(RetryBudget::try_take).unwrap_or(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn retries_are_drawn_from_the_scoped_budget() {
+        assert!(take_retry(), "no budget in scope means no limit");
+
+        let budget = RetryBudget::new(2);
+        let taken = budget
+            .scope(async { [take_retry(), take_retry(), take_retry()] })
+            .await;
+        assert_eq!(taken, [true, true, false]);
+        assert!(budget.is_exhausted());
+        assert_eq!(budget.used(), 2);
+    }
+
+    #[tokio::test]
+    async fn clones_share_one_pool() {
+        let budget = RetryBudget::new(3);
+        let other = budget.clone();
+        budget.scope(async { take_retry() }).await;
+        other.scope(async { take_retry() }).await;
+        assert_eq!(budget.remaining(), 1);
+    }
+
+    #[tokio::test]
+    async fn inner_scope_takes_precedence() {
+        let outer = RetryBudget::new(5);
+        let inner = RetryBudget::new(0);
+        let taken = outer.scope(inner.scope(async { take_retry() })).await;
+        assert!(!taken);
+        assert_eq!(outer.used(), 0);
+    }
+}
@@ -0,0 +1,345 @@
+//! What each provider and model can do.
+//!
+//! [`ProviderCapabilities::for_model`] answers "can this model see images?",
+//! "how much output can it produce?" and "does the client's
+//! [`ThinkingLevel`](crate::ThinkingLevel) apply to it?" from a built-in table
+//! keyed on model-family prefixes. The backends consult the same table when
+//! deciding whether to send reasoning settings, and it is public so routers and
+//! UIs can make the same decisions before picking a model.
+//!
+//! Limits for unrecognized models (`Custom` identifiers, local endpoints) are
+//! `None` and media support is reported as `false`: the table only claims what
+//! it knows.
+//!
+//! ```
+//! # #[cfg(feature = "anthropic")]
+//! # {
+//! use rstructor::{AnthropicModel, ModelId, ProviderCapabilities};
+//!
+//! let caps = ProviderCapabilities::for_model(&ModelId::from(&AnthropicModel::ClaudeSonnet46));
+//! assert!(caps.vision);
+//! assert_eq!(caps.max_context_tokens, Some(200_000));
+//! # }
+//! ```
+
+use crate::backend::Provider;
+
+/// How a provider can be asked for structured output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OutputStrategy {
+    /// Native JSON Schema constrained decoding (the strategy `materialize` uses).
+    JsonSchema,
+    /// Free-form JSON mode (`response_format: {"type": "json_object"}` on
+    /// OpenAI-compatible APIs): valid JSON, but not constrained to a schema,
+    /// which is described in the prompt instead.
+    JsonMode,
+    /// Structured arguments of a forced tool/function call.
+    ToolCalling,
+}
+
+/// A model identifier qualified by its provider.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ModelId {
+    /// The provider serving the model.
+    pub provider: Provider,
+    /// The exact API model identifier.
+    pub name: String,
+}
+
+impl ModelId {
+    /// Create a model identifier for `provider`.
+    pub fn new(provider: Provider, name: impl Into<String>) -> Self {
+        Self {
+            provider,
+            name: name.into(),
+        }
+    }
+}
+
+/// Capabilities of one model, as returned by [`ProviderCapabilities::for_model`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProviderCapabilities {
+    /// Structured-output strategies the provider's API offers.
+    pub output_strategies: &'static [OutputStrategy],
+    /// Accepts image input.
+    pub vision: bool,
+    /// Accepts audio input.
+    pub audio: bool,
+    /// Context window in tokens, when known.
+    pub max_context_tokens: Option<u32>,
+    /// Maximum output tokens per response, when known.
+    pub max_output_tokens: Option<u32>,
+    /// The client's [`ThinkingLevel`](crate::ThinkingLevel) is applied to this model.
+    pub thinking: bool,
+}
+
+/// Strategies of the OpenAI-style APIs (OpenAI, Grok, Gemini).
+#[cfg(any(feature = "openai", feature = "grok", feature = "gemini"))]
+const ALL_STRATEGIES: &[OutputStrategy] = &[
+    OutputStrategy::JsonSchema,
+    OutputStrategy::JsonMode,
+    OutputStrategy::ToolCalling,
+];
+/// Anthropic has no schema-less JSON mode.
+#[cfg(feature = "anthropic")]
+const ANTHROPIC_STRATEGIES: &[OutputStrategy] =
+    &[OutputStrategy::JsonSchema, OutputStrategy::ToolCalling];
+/// Bedrock's Converse API can force a tool call on Claude only, and has no
+/// schema-constrained output.
+#[cfg(feature = "bedrock")]
+const BEDROCK_CLAUDE_STRATEGIES: &[OutputStrategy] =
+    &[OutputStrategy::ToolCalling, OutputStrategy::JsonMode];
+#[cfg(feature = "bedrock")]
+const BEDROCK_STRATEGIES: &[OutputStrategy] = &[OutputStrategy::JsonMode];
+/// Groq and Together.ai are driven in JSON mode; see [`OutputStrategy::JsonMode`].
+#[cfg(any(feature = "groq", feature = "together"))]
+const HOSTED_STRATEGIES: &[OutputStrategy] = &[OutputStrategy::JsonMode];
+/// Ollama's `format` takes a schema or `"json"`.
+#[cfg(feature = "ollama")]
+const OLLAMA_STRATEGIES: &[OutputStrategy] =
+    &[OutputStrategy::JsonSchema, OutputStrategy::JsonMode];
+
+impl ProviderCapabilities {
+    /// Look up the capabilities of `model`.
+    pub fn for_model(model: &ModelId) -> Self {
+        let name = model.name.as_str();
+        match model.provider {
+            #[cfg(feature = "openai")]
+            Provider::OpenAI => openai(name),
+            #[cfg(feature = "anthropic")]
+            Provider::Anthropic => anthropic(name),
+            #[cfg(feature = "grok")]
+            Provider::Grok => grok(name),
+            #[cfg(feature = "gemini")]
+            Provider::Gemini => gemini(name),
+            #[cfg(feature = "ollama")]
+            Provider::Ollama => ollama(name),
+            // Azure deployments serve OpenAI models
+            #[cfg(feature = "azure")]
+            Provider::AzureOpenAI => openai(name),
+            #[cfg(feature = "bedrock")]
+            Provider::Bedrock => bedrock(name),
+            // Vertex AI serves the Gemini models
+            #[cfg(feature = "vertex")]
+            Provider::VertexAI => gemini(name),
+            #[cfg(feature = "groq")]
+            Provider::Groq => ProviderCapabilities::unknown(HOSTED_STRATEGIES),
+            #[cfg(feature = "together")]
+            Provider::Together => ProviderCapabilities::unknown(HOSTED_STRATEGIES),
+        }
+    }
+
+    fn unknown(output_strategies: &'static [OutputStrategy]) -> Self {
+        Self {
+            output_strategies,
+            vision: false,
+            audio: false,
+            max_context_tokens: None,
+            max_output_tokens: None,
+            thinking: false,
+        }
+    }
+
+    #[cfg(any(
+        feature = "openai",
+        feature = "anthropic",
+        feature = "bedrock",
+        feature = "gemini"
+    ))]
+    fn limits(mut self, context: u32, output: u32) -> Self {
+        self.max_context_tokens = Some(context);
+        self.max_output_tokens = Some(output);
+        self
+    }
+}
+
+#[cfg(feature = "openai")]
+fn openai(name: &str) -> ProviderCapabilities {
+    let base = ProviderCapabilities::unknown(ALL_STRATEGIES);
+    let vision = ProviderCapabilities {
+        vision: true,
+        ..base.clone()
+    };
+    if name.starts_with("gpt-5") {
+        ProviderCapabilities {
+            thinking: true,
+            ..vision
+        }
+        .limits(400_000, 128_000)
+    } else if name.starts_with("gpt-4.1") {
+        vision.limits(1_047_576, 32_768)
+    } else if name.starts_with("gpt-4o") {
+        vision.limits(128_000, 16_384)
+    } else if name.starts_with("gpt-4-turbo") {
+        vision.limits(128_000, 4_096)
+    } else if name == "gpt-4" {
+        base.limits(8_192, 8_192)
+    } else if name.starts_with("gpt-3.5-turbo") {
+        base.limits(16_385, 4_096)
+    } else {
+        base
+    }
+}
+
+#[cfg(feature = "anthropic")]
+fn anthropic(name: &str) -> ProviderCapabilities {
+    if !name.starts_with("claude-") {
+        return ProviderCapabilities::unknown(ANTHROPIC_STRATEGIES);
+    }
+    ProviderCapabilities {
+        vision: true,
+        thinking: true,
+        ..ProviderCapabilities::unknown(ANTHROPIC_STRATEGIES)
+    }
+    .limits(200_000, claude_max_output(name))
+}
+
+/// Output token limit of the Claude model `name` (`claude-...`).
+#[cfg(any(feature = "anthropic", feature = "bedrock"))]
+fn claude_max_output(name: &str) -> u32 {
+    if name.starts_with("claude-opus-4-0") || name.starts_with("claude-opus-4-1") {
+        32_000
+    } else if ["claude-opus-4-6", "claude-opus-4-7", "claude-opus-4-8"]
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
+    {
+        128_000
+    } else {
+        64_000
+    }
+}
+
+#[cfg(feature = "grok")]
+fn grok(name: &str) -> ProviderCapabilities {
+    if name.starts_with("grok-4") {
+        ProviderCapabilities {
+            vision: true,
+            max_context_tokens: Some(256_000),
+            ..ProviderCapabilities::unknown(ALL_STRATEGIES)
+        }
+    } else {
+        ProviderCapabilities::unknown(ALL_STRATEGIES)
+    }
+}
+
+#[cfg(feature = "gemini")]
+fn gemini(name: &str) -> ProviderCapabilities {
+    if !name.starts_with("gemini-") {
+        return ProviderCapabilities::unknown(ALL_STRATEGIES);
+    }
+    let max_output = if name.starts_with("gemini-2.0") {
+        8_192
+    } else {
+        65_536
+    };
+    ProviderCapabilities {
+        vision: true,
+        audio: true,
+        thinking: name.starts_with("gemini-3"),
+        ..ProviderCapabilities::unknown(ALL_STRATEGIES)
+    }
+    .limits(1_048_576, max_output)
+}
+
+#[cfg(feature = "bedrock")]
+fn bedrock(name: &str) -> ProviderCapabilities {
+    // Cross-region inference profiles prefix the model id with a geography
+    let name = [
+        "us.", "eu.", "apac.", "us-gov.", "jp.", "au.", "ca.", "global.",
+    ]
+    .iter()
+    .find_map(|geo| name.strip_prefix(geo))
+    .unwrap_or(name);
+    if let Some(claude) = name.strip_prefix("anthropic.") {
+        // Extended thinking isn't requested through the Converse API
+        return ProviderCapabilities {
+            vision: true,
+            ..ProviderCapabilities::unknown(BEDROCK_CLAUDE_STRATEGIES)
+        }
+        .limits(200_000, claude_max_output(claude));
+    }
+    let vision = ["meta.llama4", "meta.llama3-2-11b", "meta.llama3-2-90b"]
+        .iter()
+        .any(|prefix| name.starts_with(prefix));
+    ProviderCapabilities {
+        vision,
+        ..ProviderCapabilities::unknown(BEDROCK_STRATEGIES)
+    }
+}
+
+#[cfg(feature = "ollama")]
+fn ollama(name: &str) -> ProviderCapabilities {
+    // Local models vary too much to know their limits; only flag the
+    // well-known multimodal families.
+    let vision = ["gemma3", "llava", "llama3.2-vision", "qwen2.5vl"]
+        .iter()
+        .any(|prefix| name.starts_with(prefix));
+    ProviderCapabilities {
+        vision,
+        ..ProviderCapabilities::unknown(OLLAMA_STRATEGIES)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "openai")]
+    #[test]
+    fn openai_families() {
+        let gpt5 = ProviderCapabilities::for_model(&ModelId::new(Provider::OpenAI, "gpt-5.5"));
+        assert!(gpt5.thinking && gpt5.vision);
+        assert_eq!(gpt5.max_output_tokens, Some(128_000));
+
+        let gpt4o = ProviderCapabilities::for_model(&ModelId::new(Provider::OpenAI, "gpt-4o"));
+        assert!(!gpt4o.thinking);
+        assert!(
+            gpt4o
+                .output_strategies
+                .contains(&OutputStrategy::JsonSchema)
+        );
+    }
+
+    #[cfg(feature = "gemini")]
+    #[test]
+    fn gemini_thinking_only_on_gemini_3() {
+        let g3 = ProviderCapabilities::for_model(&ModelId::new(
+            Provider::Gemini,
+            "gemini-3-pro-preview",
+        ));
+        let g25 =
+            ProviderCapabilities::for_model(&ModelId::new(Provider::Gemini, "gemini-2.5-pro"));
+        assert!(g3.thinking);
+        assert!(!g25.thinking);
+        assert!(g25.audio);
+    }
+
+    #[cfg(feature = "bedrock")]
+    #[test]
+    fn bedrock_claude_through_inference_profiles() {
+        let caps = ProviderCapabilities::for_model(&ModelId::new(
+            Provider::Bedrock,
+            "us.anthropic.claude-opus-4-1-20250805-v1:0",
+        ));
+        assert_eq!(caps.output_strategies, BEDROCK_CLAUDE_STRATEGIES);
+        assert_eq!(caps.max_output_tokens, Some(32_000));
+        assert!(caps.vision && !caps.thinking);
+
+        let llama = ProviderCapabilities::for_model(&ModelId::new(
+            Provider::Bedrock,
+            "meta.llama3-3-70b-instruct-v1:0",
+        ));
+        assert_eq!(llama.output_strategies, BEDROCK_STRATEGIES);
+        assert!(!llama.vision);
+    }
+
+    #[cfg(feature = "anthropic")]
+    #[test]
+    fn unknown_models_claim_nothing() {
+        let caps =
+            ProviderCapabilities::for_model(&ModelId::new(Provider::Anthropic, "my-proxy-model"));
+        assert_eq!(caps.max_context_tokens, None);
+        assert!(!caps.vision && !caps.thinking);
+        assert_eq!(caps.output_strategies, ANTHROPIC_STRATEGIES);
+    }
+}
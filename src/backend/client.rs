@@ -2,6 +2,7 @@ use async_trait::async_trait;
 use serde::de::DeserializeOwned;
 
 use crate::backend::ModelInfo;
+use crate::backend::messages::{ChatMessage, single_prompt};
 use crate::backend::usage::{GenerateResult, MaterializeResult};
 use crate::error::Result;
 use crate::model::Instructor;
@@ -19,9 +20,11 @@ use crate::model::Instructor;
 /// The `mime_type` decides how each provider encodes the attachment: `image/*`
 /// is sent in the provider's image format, and `application/pdf` is routed to
 /// the provider's document/file format (OpenAI `file` part for inline data,
-/// Anthropic `document` block, Gemini `inlineData`/`fileData`). Combinations a
-/// provider does not document — e.g. any PDF on Grok, or a URL-based PDF on
-/// OpenAI — produce a clear error instead of a silently broken request.
+/// Anthropic `document` block, Gemini `inlineData`/`fileData`). `audio/*` is
+/// sent as an OpenAI `input_audio` part (inline WAV or MP3) or a Gemini
+/// `inlineData`/`fileData` part. Combinations a provider does not document —
+/// e.g. any PDF on Grok, audio on Anthropic, or a URL-based PDF on OpenAI —
+/// produce a clear error instead of a silently broken request.
 ///
 /// # Examples
 ///
@@ -103,6 +106,138 @@ impl MediaFile {
             data: Some(encoded),
         }
     }
+
+    /// Create inline audio from a recording's bytes, detecting the format
+    /// (WAV, MP3, AAC, FLAC, Ogg or AIFF) from its header.
+    ///
+    /// Gemini accepts all of these; OpenAI accepts WAV and MP3 on its
+    /// audio-capable models (e.g. `gpt-4o-audio-preview`). Other providers
+    /// reject audio with a clear error.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`InvalidInput`](std::io::ErrorKind::InvalidInput) error if
+    /// the bytes aren't in one of the formats above; use
+    /// [`from_bytes`](Self::from_bytes) with the MIME type instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rstructor::MediaFile;
+    ///
+    /// let wav = b"RIFF\x24\x00\x00\x00WAVEfmt ";
+    /// assert_eq!(MediaFile::from_audio(wav)?.mime_type, "audio/wav");
+    /// assert!(MediaFile::from_audio(b"not audio").is_err());
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    #[cfg(feature = "_client")]
+    pub fn from_audio(data: impl AsRef<[u8]>) -> std::io::Result<Self> {
+        let data = data.as_ref();
+        let mime_type = sniff_audio(data).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "unrecognized audio format; use MediaFile::from_bytes with its MIME type",
+            )
+        })?;
+        Ok(Self::from_bytes(data, mime_type))
+    }
+
+    /// Read a file from disk as inline media, taking the MIME type from its
+    /// extension (`png`, `jpg`/`jpeg`, `gif`, `webp`, `pdf`, `wav`, `mp3`,
+    /// `flac` or `ogg`).
+    ///
+    /// Use [`from_bytes`](Self::from_bytes) for other types or when the
+    /// extension doesn't match the content.
+    ///
+    /// # Errors
+    ///
+    /// Returns the I/O error if the file can't be read, or an
+    /// [`InvalidInput`](std::io::ErrorKind::InvalidInput) error if its
+    /// extension isn't one of the above.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rstructor::MediaFile;
+    ///
+    /// let receipt = MediaFile::from_path("receipt.jpg")?;
+    /// assert_eq!(receipt.mime_type, "image/jpeg");
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    #[cfg(feature = "_client")]
+    pub fn from_path(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase);
+        let mime_type = match extension.as_deref() {
+            Some("png") => "image/png",
+            Some("jpg" | "jpeg") => "image/jpeg",
+            Some("gif") => "image/gif",
+            Some("webp") => "image/webp",
+            Some("pdf") => "application/pdf",
+            Some("wav") => "audio/wav",
+            Some("mp3") => "audio/mpeg",
+            Some("flac") => "audio/flac",
+            Some("ogg") => "audio/ogg",
+            _ => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!(
+                        "can't tell the media type of {}; use MediaFile::from_bytes",
+                        path.display()
+                    ),
+                ));
+            }
+        };
+        Ok(Self::from_bytes(std::fs::read(path)?, mime_type))
+    }
+}
+
+/// MIME type of an audio recording, from its leading magic bytes.
+#[cfg(feature = "_client")]
+fn sniff_audio(data: &[u8]) -> Option<&'static str> {
+    match data {
+        [
+            b'R',
+            b'I',
+            b'F',
+            b'F',
+            _,
+            _,
+            _,
+            _,
+            b'W',
+            b'A',
+            b'V',
+            b'E',
+            ..,
+        ] => Some("audio/wav"),
+        [
+            b'F',
+            b'O',
+            b'R',
+            b'M',
+            _,
+            _,
+            _,
+            _,
+            b'A',
+            b'I',
+            b'F',
+            b'F',
+            ..,
+        ] => Some("audio/aiff"),
+        [b'f', b'L', b'a', b'C', ..] => Some("audio/flac"),
+        [b'O', b'g', b'g', b'S', ..] => Some("audio/ogg"),
+        [b'I', b'D', b'3', ..] => Some("audio/mpeg"),
+        // ADTS frames (AAC) and MPEG audio frames share the 0xFFF sync word;
+        // only MPEG audio sets the layer bits
+        [0xFF, b, ..] if b & 0xF6 == 0xF0 => Some("audio/aac"),
+        [0xFF, b, ..] if b & 0xE0 == 0xE0 => Some("audio/mpeg"),
+        _ => None,
+    }
 }
 
 /// LLMClient trait defines the interface for all LLM API clients.
@@ -129,7 +264,40 @@ impl MediaFile {
 /// - Requests default to a 5-minute total timeout (`DEFAULT_REQUEST_TIMEOUT`) with a
 ///   30-second connect timeout (`DEFAULT_CONNECT_TIMEOUT`), so a hung connection can
 ///   never block forever
-/// - Timeout is applied immediately when `timeout()` is called - no need to call `build()`
+/// - OpenAI, Anthropic and Grok `materialize` requests carry an `Idempotency-Key` header;
+///   it stays the same when a transient failure is retried and changes when the model
+///   is re-asked, so a retried request whose response was lost is not billed twice
+/// - `build()` validates the whole configuration and constructs the HTTP client once;
+///   it is optional, since an unbuilt client builds its HTTP client on first request
+///
+/// # Sharing clients
+///
+/// Every built-in client (and [`AnyClient`](crate::AnyClient)) is `Clone + Send + Sync`.
+/// Cloning is cheap: the HTTP connection pool and the configuration live behind
+/// `Arc`s, so a clone is two reference-count bumps. Store a client directly in
+/// application state (e.g. axum's `State`) and clone it into spawned tasks — there
+/// is no need to wrap it in `Arc` yourself. A builder call on a clone (say,
+/// `.temperature(0.7)`) copies the configuration first and never affects other clones.
+///
+/// ```no_run
+/// # #[cfg(feature = "openai")]
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// use rstructor::{LLMClient, OpenAIClient};
+///
+/// let client = OpenAIClient::from_env()?;
+/// let handles: Vec<_> = ["Paris", "Tokyo"]
+///     .into_iter()
+///     .map(|city| {
+///         let client = client.clone();
+///         tokio::spawn(async move { client.generate(&format!("One fact about {city}")).await })
+///     })
+///     .collect();
+/// for handle in handles {
+///     println!("{}", handle.await??);
+/// }
+/// # Ok(())
+/// # }
+/// ```
 ///
 /// # Examples
 ///
@@ -276,6 +444,144 @@ pub trait LLMClient {
     where
         T: Instructor + DeserializeOwned + Send + 'static;
 
+    /// Materialize with a time limit, for interactive UIs that would rather
+    /// show an imperfect answer than wait on a re-ask.
+    ///
+    /// Returns [`BestEffort::Valid`](crate::BestEffort::Valid) if a reply
+    /// validates within `limit`. If the limit passes while a reply that failed
+    /// validation is being re-asked, the call is abandoned and that reply is
+    /// returned as [`BestEffort::Unvalidated`](crate::BestEffort::Unvalidated)
+    /// with its validation error. Errors before the limit (including running
+    /// out of retries) are returned as usual.
+    ///
+    /// # Errors
+    ///
+    /// [`RStructorError::Timeout`](crate::RStructorError::Timeout) if the limit
+    /// passes before any reply deserialized, plus any error `materialize`
+    /// returns.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use rstructor::{BestEffort, LLMClient, OpenAIClient, Instructor};
+    /// # use serde::{Serialize, Deserialize};
+    /// # #[derive(Instructor, Serialize, Deserialize)]
+    /// # struct Movie { title: String }
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// use std::time::Duration;
+    ///
+    /// let client = OpenAIClient::from_env()?;
+    /// match client.materialize_within::<Movie>("Describe Inception", Duration::from_secs(2)).await? {
+    ///     BestEffort::Valid(movie) => println!("{}", movie.title),
+    ///     BestEffort::Unvalidated { value, error } => println!("{} (unchecked: {error})", value.title),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "_client")]
+    async fn materialize_within<T>(
+        &self,
+        prompt: &str,
+        limit: std::time::Duration,
+    ) -> Result<crate::BestEffort<T>>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+        Self: Sync,
+    {
+        crate::backend::best_effort::materialize_within(self, prompt, limit).await
+    }
+
+    /// Extract items of type `T` from several documents in one call, each
+    /// attributed to the document it came from.
+    ///
+    /// Every document goes into the prompt marked with its
+    /// [`id`](crate::Document::id), and the model returns a list of items that
+    /// each name their document. Ids are constrained in the schema and checked
+    /// on the reply; an unknown id, or an item failing
+    /// [`Instructor::validate`], is re-asked. Items come back in the model's
+    /// order.
+    ///
+    /// # Errors
+    ///
+    /// [`RStructorError::ValidationError`](crate::RStructorError::ValidationError)
+    /// if two documents share an id, plus any error `materialize` returns.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use rstructor::{Document, LLMClient, OpenAIClient, Instructor};
+    /// # use serde::{Serialize, Deserialize};
+    /// # #[derive(Instructor, Serialize, Deserialize)]
+    /// # struct Deadline { task: String, date: String }
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let docs = [
+    ///     Document::new("email-1", "Please send the report by Friday."),
+    ///     Document::new("email-2", "Invoices are due on March 3."),
+    /// ];
+    /// let client = OpenAIClient::from_env()?;
+    /// for (doc_id, deadline) in client
+    ///     .materialize_corpus::<Deadline>(&docs, "List every deadline.")
+    ///     .await?
+    /// {
+    ///     println!("{doc_id}: {} by {}", deadline.task, deadline.date);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "_client")]
+    async fn materialize_corpus<T>(
+        &self,
+        docs: &[crate::Document],
+        prompt: &str,
+    ) -> Result<Vec<(crate::DocId, T)>>
+    where
+        T: Instructor + Send + 'static,
+        Self: Sync,
+    {
+        crate::backend::corpus::materialize_corpus(self, docs, prompt).await
+    }
+
+    /// Structured extraction for models with no JSON mode: ask for each field
+    /// of `T` in its own plain-text prompt and assemble `T` locally.
+    ///
+    /// Each property of `T`'s schema gets a [`generate`](Self::generate)
+    /// call asking for just that value, which is then picked out of the reply
+    /// by the property's type: the first number for numeric fields, a yes/no
+    /// for booleans, one of the listed options for enums, the text itself for
+    /// strings and embedded JSON for anything else. A reply without a usable
+    /// value is re-asked once; an optional field can be answered `none`. The
+    /// assembled value is then deserialized and validated. This trades one
+    /// call per field for much higher validity on weak models.
+    ///
+    /// # Errors
+    ///
+    /// [`RStructorError::Unsupported`](crate::RStructorError::Unsupported) if
+    /// `T` isn't a struct with named fields;
+    /// [`RStructorError::ValidationError`](crate::RStructorError::ValidationError)
+    /// if a required field has no value after the re-ask or the assembled `T`
+    /// fails validation; plus any error `generate` returns.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use rstructor::{LLMClient, OllamaClient, Instructor};
+    /// # use serde::{Serialize, Deserialize};
+    /// # #[derive(Instructor, Serialize, Deserialize)]
+    /// # struct Movie { title: String, year: u16 }
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = OllamaClient::new().model("tinyllama");
+    /// let movie: Movie = client.materialize_per_field("Alien came out in 1979.").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    async fn materialize_per_field<T>(&self, prompt: &str) -> Result<T>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+        Self: Sync,
+    {
+        crate::backend::per_field::materialize_per_field(self, prompt).await
+    }
+
     /// Raw completion without structure (returns plain text).
     ///
     /// This method provides a simpler interface for getting raw text completions
@@ -357,6 +663,48 @@ pub trait LLMClient {
     /// ```
     async fn generate_with_metadata(&self, prompt: &str) -> Result<GenerateResult>;
 
+    /// Materialize a structured object as the next turn of a conversation.
+    ///
+    /// `messages` is the conversation so far, ending with the user turn to
+    /// answer. Validation retries continue the same conversation, and the
+    /// result's [`conversation`](MaterializeResult::conversation) is `messages`
+    /// followed by any rejected replies with their feedback and the accepted
+    /// reply. [`Conversation`](crate::Conversation) keeps this history for you.
+    ///
+    /// The default implementation forwards a lone text-only user message to
+    /// [`materialize_with_metadata`](Self::materialize_with_metadata), and
+    /// otherwise returns [`RStructorError::Unsupported`](crate::RStructorError::Unsupported)
+    /// so that history is never silently dropped. All built-in clients override it.
+    async fn materialize_conversation<T>(
+        &self,
+        messages: &[ChatMessage],
+    ) -> Result<MaterializeResult<T>>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        match single_prompt(messages) {
+            Some(prompt) => self.materialize_with_metadata(prompt).await,
+            None => Err(crate::error::RStructorError::Unsupported(
+                "this client does not support multi-turn conversations".to_string(),
+            )),
+        }
+    }
+
+    /// Raw text completion as the next turn of a conversation.
+    ///
+    /// Like [`materialize_conversation`](Self::materialize_conversation), but
+    /// the reply is plain text. The default implementation forwards a lone
+    /// text-only user message to [`generate_with_metadata`](Self::generate_with_metadata)
+    /// and otherwise returns [`RStructorError::Unsupported`](crate::RStructorError::Unsupported).
+    async fn generate_conversation(&self, messages: &[ChatMessage]) -> Result<GenerateResult> {
+        match single_prompt(messages) {
+            Some(prompt) => self.generate_with_metadata(prompt).await,
+            None => Err(crate::error::RStructorError::Unsupported(
+                "this client does not support multi-turn conversations".to_string(),
+            )),
+        }
+    }
+
     /// Stream a raw text completion as a sequence of token deltas.
     ///
     /// Returns a [`Stream`](futures_util::Stream) of text chunks; concatenating
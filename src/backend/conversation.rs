@@ -0,0 +1,208 @@
+//! Multi-turn chats that end in a typed extraction.
+//!
+//! A [`Conversation`] holds the message history of a chat. Free-form turns go
+//! through [`send`](Conversation::send), and once the user has said enough,
+//! [`materialize`](Conversation::materialize) answers the latest turn with a
+//! validated `T`, using the same retry and re-ask machinery as
+//! [`LLMClient::materialize`]. Every reply is appended, so the chat can go on
+//! afterwards.
+//!
+//! ```
+//! # #[cfg(feature = "mock")]
+//! # #[tokio::main]
+//! # async fn main() -> rstructor::Result<()> {
+//! use rstructor::{Conversation, Instructor, MockClient};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Instructor, Serialize, Deserialize)]
+//! struct Booking {
+//!     city: String,
+//!     nights: u32,
+//! }
+//!
+//! let client = MockClient::new()
+//!     .with_response("Sure, how many nights?")
+//!     .with_response(r#"{"city": "Lisbon", "nights": 3}"#);
+//!
+//! let mut chat = Conversation::new().with_system("You are a travel agent.");
+//! let question = chat.send(&client, "I'd like a hotel in Lisbon.").await?;
+//! assert_eq!(question, "Sure, how many nights?");
+//!
+//! chat.user("Three, please. Book it.");
+//! let booking: Booking = chat.materialize(&client).await?;
+//! assert_eq!((booking.city.as_str(), booking.nights), ("Lisbon", 3));
+//! assert_eq!(chat.len(), 5);
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "mock"))]
+//! # fn main() {}
+//! ```
+
+use serde::de::DeserializeOwned;
+
+use crate::backend::usage::MaterializeResult;
+use crate::backend::{ChatMessage, ChatRole, LLMClient, MediaFile};
+use crate::error::{RStructorError, Result};
+use crate::model::Instructor;
+
+/// The message history of a multi-turn chat.
+///
+/// System messages in the history are sent in the provider's system role,
+/// after the client's own `system_prompt` if it has one, wherever they appear.
+#[derive(Debug, Clone, Default)]
+pub struct Conversation {
+    messages: Vec<ChatMessage>,
+}
+
+impl Conversation {
+    /// An empty conversation.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start the conversation with a system message.
+    #[must_use]
+    pub fn with_system(mut self, content: impl Into<String>) -> Self {
+        self.push(ChatMessage::system(content));
+        self
+    }
+
+    /// Append a message.
+    pub fn push(&mut self, message: ChatMessage) -> &mut Self {
+        self.messages.push(message);
+        self
+    }
+
+    /// Append a user message.
+    pub fn user(&mut self, content: impl Into<String>) -> &mut Self {
+        self.push(ChatMessage::user(content))
+    }
+
+    /// Append a user message with attached media.
+    pub fn user_with_media(
+        &mut self,
+        content: impl Into<String>,
+        media: Vec<MediaFile>,
+    ) -> &mut Self {
+        self.push(ChatMessage::user_with_media(content, media))
+    }
+
+    /// Append an assistant message, e.g. to replay a reply produced elsewhere.
+    pub fn assistant(&mut self, content: impl Into<String>) -> &mut Self {
+        self.push(ChatMessage::assistant(content))
+    }
+
+    /// The messages so far, oldest first.
+    pub fn messages(&self) -> &[ChatMessage] {
+        &self.messages
+    }
+
+    /// Mutable access to the history, for edits beyond appending and
+    /// truncating (say, redacting an earlier turn).
+    pub fn messages_mut(&mut self) -> &mut Vec<ChatMessage> {
+        &mut self.messages
+    }
+
+    /// The content of the latest assistant message, if any.
+    pub fn last_reply(&self) -> Option<&str> {
+        self.messages
+            .iter()
+            .rev()
+            .find(|msg| msg.role == ChatRole::Assistant)
+            .map(|msg| msg.content.as_str())
+    }
+
+    /// Number of messages.
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Whether there are no messages.
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Remove and return the latest message.
+    pub fn pop(&mut self) -> Option<ChatMessage> {
+        self.messages.pop()
+    }
+
+    /// Keep only the first `len` messages.
+    pub fn truncate(&mut self, len: usize) {
+        self.messages.truncate(len);
+    }
+
+    /// Remove every message.
+    pub fn clear(&mut self) {
+        self.messages.clear();
+    }
+
+    /// The history as a plain message list.
+    pub fn into_messages(self) -> Vec<ChatMessage> {
+        self.messages
+    }
+
+    /// Append `prompt` as a user turn and the model's text reply after it,
+    /// returning the reply. On error the history is left as it was.
+    pub async fn send(&mut self, client: &(impl LLMClient + Sync), prompt: &str) -> Result<String> {
+        self.user(prompt);
+        match client.generate_conversation(&self.messages).await {
+            Ok(result) => {
+                self.assistant(result.text.clone());
+                Ok(result.text)
+            }
+            Err(e) => {
+                self.messages.pop();
+                Err(e)
+            }
+        }
+    }
+
+    /// Answer the latest user turn with a validated `T`, appending the accepted
+    /// reply. Rejected replies and their feedback are not kept in the history.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RStructorError::ConfigError`] unless the conversation ends
+    /// with a user message, and otherwise whatever the client returns.
+    pub async fn materialize<T>(&mut self, client: &(impl LLMClient + Sync)) -> Result<T>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        self.materialize_with_metadata(client)
+            .await
+            .map(|result| result.data)
+    }
+
+    /// Like [`materialize`](Self::materialize), but returns the full
+    /// [`MaterializeResult`] with token usage and the conversation including
+    /// any re-asks.
+    pub async fn materialize_with_metadata<T>(
+        &mut self,
+        client: &(impl LLMClient + Sync),
+    ) -> Result<MaterializeResult<T>>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        if self.messages.last().map(|msg| msg.role) != Some(ChatRole::User) {
+            return Err(RStructorError::ConfigError(
+                "a conversation must end with a user message to be answered".to_string(),
+            ));
+        }
+        let result = client.materialize_conversation::<T>(&self.messages).await?;
+        if let Some(reply) = result
+            .conversation
+            .last()
+            .filter(|msg| msg.role == ChatRole::Assistant)
+        {
+            self.messages.push(reply.clone());
+        }
+        Ok(result)
+    }
+}
+
+impl From<Vec<ChatMessage>> for Conversation {
+    fn from(messages: Vec<ChatMessage>) -> Self {
+        Self { messages }
+    }
+}
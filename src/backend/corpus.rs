@@ -0,0 +1,164 @@
+//! Extraction across several documents with per-item attribution.
+//!
+//! [`LLMClient::materialize_corpus`](crate::LLMClient::materialize_corpus)
+//! puts every [`Document`] into one prompt, each marked with its id, and asks
+//! for a list of items that each name the document they came from. The ids
+//! are an `enum` in the schema and are checked again on the reply, so an item
+//! attributed to a document that wasn't sent is re-asked like any other
+//! validation failure.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use serde_json::{Value, json};
+
+use crate::backend::LLMClient;
+use crate::backend::dyn_client::{ValueValidator, materialize_dynamic};
+use crate::error::{RStructorError, Result};
+use crate::model::Instructor;
+use crate::schema::Schema;
+
+/// Identifies a [`Document`] in a corpus.
+pub type DocId = String;
+
+/// One document of a corpus for
+/// [`materialize_corpus`](crate::LLMClient::materialize_corpus).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Document {
+    /// Id the extracted items are attributed to; unique within a corpus.
+    pub id: DocId,
+    /// The document's text.
+    pub text: String,
+}
+
+impl Document {
+    /// A document with the given id and text.
+    pub fn new(id: impl Into<DocId>, text: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            text: text.into(),
+        }
+    }
+}
+
+pub(crate) async fn materialize_corpus<C, T>(
+    client: &C,
+    docs: &[Document],
+    prompt: &str,
+) -> Result<Vec<(DocId, T)>>
+where
+    C: LLMClient + Sync + ?Sized,
+    T: Instructor + Send + 'static,
+{
+    if docs.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut ids = HashSet::new();
+    if let Some(duplicate) = docs.iter().find(|doc| !ids.insert(doc.id.as_str())) {
+        return Err(RStructorError::ValidationError(format!(
+            "document id `{}` is used more than once",
+            duplicate.id
+        )));
+    }
+
+    let ids: Vec<DocId> = docs.iter().map(|doc| doc.id.clone()).collect();
+    let schema = corpus_schema(&T::schema(), &ids);
+    let known = ids.clone();
+    let validator: ValueValidator = Arc::new(move |value| {
+        attributed::<T>(value, &known)?
+            .iter()
+            .enumerate()
+            .try_for_each(|(i, (_, item))| {
+                item.validate()
+                    .map_err(|e| RStructorError::ValidationError(format!("items[{i}]: {e}")))
+            })
+    });
+    let value = materialize_dynamic(
+        client,
+        &corpus_prompt(docs, prompt),
+        &schema,
+        Some(validator),
+    )
+    .await?;
+    attributed(&value, &ids)
+}
+
+/// An object holding the attributed `items`, with `item`'s `$defs` hoisted to
+/// the root so their `$ref`s still resolve.
+fn corpus_schema(item: &Schema, ids: &[DocId]) -> Schema {
+    let mut item = item.to_json();
+    let defs = item.as_object_mut().and_then(|obj| obj.remove("$defs"));
+    let mut schema = json!({
+        "type": "object",
+        "title": "CorpusExtraction",
+        "properties": {
+            "items": {
+                "type": "array",
+                "description": "Every item found in the documents, each attributed to the document it came from",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "document_id": {
+                            "type": "string",
+                            "enum": ids,
+                            "description": "Id of the document the item was found in",
+                        },
+                        "item": item,
+                    },
+                    "required": ["document_id", "item"],
+                    "additionalProperties": false,
+                },
+            },
+        },
+        "required": ["items"],
+        "additionalProperties": false,
+    });
+    if let (Some(defs), Value::Object(root)) = (defs, &mut schema) {
+        root.insert("$defs".to_string(), defs);
+    }
+    Schema::new(schema)
+}
+
+fn corpus_prompt(docs: &[Document], prompt: &str) -> String {
+    let mut text = format!(
+        "{prompt}\n\nThe documents below are each marked with an id. Extract items from \
+         all of them, and set `document_id` on each item to the id of the document it \
+         came from.\n"
+    );
+    for doc in docs {
+        text.push_str(&format!(
+            "\n<document id=\"{}\">\n{}\n</document>\n",
+            doc.id, doc.text
+        ));
+    }
+    text
+}
+
+/// The `(document id, item)` pairs of a reply.
+fn attributed<T: Instructor>(value: &Value, ids: &[DocId]) -> Result<Vec<(DocId, T)>> {
+    let no_items = || RStructorError::ValidationError("reply has no `items` array".to_string());
+    let items = value
+        .get("items")
+        .and_then(Value::as_array)
+        .ok_or_else(no_items)?;
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let invalid = |e: String| RStructorError::ValidationError(format!("items[{i}]: {e}"));
+            let id = entry
+                .get("document_id")
+                .and_then(Value::as_str)
+                .ok_or_else(|| invalid("missing `document_id`".to_string()))?;
+            if !ids.iter().any(|known| known == id) {
+                return Err(invalid(format!(
+                    "`{id}` is not one of the document ids: {}",
+                    ids.join(", ")
+                )));
+            }
+            let item = entry.get("item").cloned().unwrap_or(Value::Null);
+            let item = T::deserialize(item).map_err(|e| invalid(e.to_string()))?;
+            Ok((id.to_string(), item))
+        })
+        .collect()
+}
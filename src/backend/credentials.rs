@@ -0,0 +1,136 @@
+//! Per-call API keys for multi-tenant applications.
+//!
+//! A SaaS platform that bills each tenant's usage to that tenant's own
+//! provider account would otherwise need one client — and one copy of every
+//! pipeline built around it — per tenant. [`scoped_api_key`] instead swaps one
+//! provider's key for every request made inside a future, so a single shared
+//! client serves every tenant. Clients of other providers called in the same
+//! future keep their configured keys. For one call,
+//! [`Request::api_key`](crate::Request::api_key) (or
+//! `client.with_api_key_for_call(provider, key)`) does the same.
+//!
+//! ```no_run
+//! # #[cfg(feature = "openai")]
+//! # async fn run(tenant_key: String) -> rstructor::Result<()> {
+//! use rstructor::{Instructor, LLMClient, OpenAIClient, Provider, scoped_api_key};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Instructor, Serialize, Deserialize)]
+//! struct Invoice { total: f64 }
+//!
+//! let shared = OpenAIClient::from_env()?;
+//! let invoice: Invoice =
+//!     scoped_api_key(Provider::OpenAI, tenant_key, shared.materialize("Extract the invoice: ..."))
+//!         .await?;
+//! # let _ = invoice;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+
+use crate::backend::Provider;
+use crate::backend::limiter::provider_name;
+
+/// Per-call keys, by the provider name the backends report errors under.
+type ApiKeys = Arc<HashMap<&'static str, String>>;
+
+tokio::task_local! {
+    /// Keys that replace the configured API keys of their providers' clients
+    /// for the current task.
+    static API_KEY_OVERRIDES: ApiKeys;
+}
+
+/// The keys in scope with `overrides` added, replacing any earlier key for
+/// the same provider.
+fn with_overrides(overrides: impl IntoIterator<Item = (Provider, String)>) -> ApiKeys {
+    let mut keys = API_KEY_OVERRIDES
+        .try_with(|keys| HashMap::clone(keys))
+        .unwrap_or_default();
+    keys.extend(
+        overrides
+            .into_iter()
+            .map(|(provider, api_key)| (provider_name(provider), api_key)),
+    );
+    Arc::new(keys)
+}
+
+/// Run `future` with `api_key` in place of the configured key of every
+/// `provider` client it calls. Clients of other providers keep their keys.
+///
+/// Covers retries, streaming, tool loops and [`AnyClient`](crate::AnyClient)
+/// dispatch. The key is carried by the current task, so calls inside a
+/// `tokio::spawn`ed task are not covered unless the spawned future is wrapped
+/// too. Scopes nest: keys for different providers combine, and the innermost
+/// key for a provider wins.
+pub async fn scoped_api_key<F: Future>(
+    provider: Provider,
+    api_key: impl Into<String>,
+    future: F,
+) -> F::Output {
+    scoped_api_keys([(provider, api_key.into())], future).await
+}
+
+/// Like [`scoped_api_key`], for several providers at once.
+pub(crate) async fn scoped_api_keys<F: Future>(
+    api_keys: impl IntoIterator<Item = (Provider, String)>,
+    future: F,
+) -> F::Output {
+    API_KEY_OVERRIDES
+        .scope(with_overrides(api_keys), future)
+        .await
+}
+
+/// Call `f` with `api_keys` overriding their providers' configured keys, for
+/// work (such as building a stream) that resolves its key synchronously.
+#[cfg(feature = "streaming")]
+pub(crate) fn sync_scoped_api_keys<R>(
+    api_keys: impl IntoIterator<Item = (Provider, String)>,
+    f: impl FnOnce() -> R,
+) -> R {
+    API_KEY_OVERRIDES.sync_scope(with_overrides(api_keys), f)
+}
+
+/// The per-call key in scope for the provider named `provider`, if any.
+pub(crate) fn api_key_override(provider: &str) -> Option<String> {
+    API_KEY_OVERRIDES
+        .try_with(|keys| keys.get(provider).cloned())
+        .ok()
+        .flatten()
+}
+
+#[cfg(all(test, feature = "openai", feature = "anthropic"))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn innermost_scope_wins_per_provider() {
+        assert_eq!(api_key_override("OpenAI"), None);
+        let keys = scoped_api_key(Provider::OpenAI, "outer", async {
+            scoped_api_key(Provider::Anthropic, "claude", async {
+                let inner = scoped_api_key(Provider::OpenAI, "inner", async {
+                    api_key_override("OpenAI")
+                })
+                .await;
+                (
+                    api_key_override("OpenAI"),
+                    inner,
+                    api_key_override("Anthropic"),
+                )
+            })
+            .await
+        })
+        .await;
+        assert_eq!(
+            keys,
+            (
+                Some("outer".into()),
+                Some("inner".into()),
+                Some("claude".into())
+            )
+        );
+        assert_eq!(api_key_override("OpenAI"), None);
+    }
+}
@@ -0,0 +1,271 @@
+//! Known-deprecated model identifiers and their replacements.
+//!
+//! Providers retire models on a schedule, and a retired id turns into a hard
+//! `404`/`400` in production. Every client's `model(..)` builder checks the
+//! bundled table below and logs a structured `warn!` (fields `provider`, `model`,
+//! `replacement`) when a deprecated id is configured; calling
+//! `upgrade_deprecated_model()` on the client switches to the documented
+//! replacement instead.
+//!
+//! The table only knows about models retired before this release. For anything
+//! newer, [`check_model_listed`] asks the provider's `list_models` endpoint.
+//!
+//! ```
+//! # #[cfg(feature = "anthropic")]
+//! # fn main() -> rstructor::Result<()> {
+//! use rstructor::AnthropicClient;
+//!
+//! let client = AnthropicClient::new("key")?
+//!     .model("claude-3-opus-20240229") // warns: deprecated
+//!     .upgrade_deprecated_model();
+//! assert_eq!(client.model_id().name, "claude-opus-4-1-20250805");
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "anthropic"))]
+//! # fn main() {}
+//! ```
+
+use tracing::warn;
+
+use crate::backend::{LLMClient, ModelId, Provider};
+use crate::error::Result;
+
+/// A deprecated model and the model that replaces it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Deprecation {
+    /// The provider serving the model.
+    pub provider: Provider,
+    /// The deprecated model identifier.
+    pub model: &'static str,
+    /// The provider's documented replacement.
+    pub replacement: &'static str,
+}
+
+const fn deprecated(
+    provider: Provider,
+    model: &'static str,
+    replacement: &'static str,
+) -> Deprecation {
+    Deprecation {
+        provider,
+        model,
+        replacement,
+    }
+}
+
+const DEPRECATIONS: &[Deprecation] = &[
+    #[cfg(feature = "openai")]
+    deprecated(Provider::OpenAI, "gpt-4.5-preview", "gpt-4.1"),
+    #[cfg(feature = "openai")]
+    deprecated(Provider::OpenAI, "gpt-4-32k", "gpt-4.1"),
+    #[cfg(feature = "openai")]
+    deprecated(Provider::OpenAI, "gpt-4-vision-preview", "gpt-4.1"),
+    #[cfg(feature = "anthropic")]
+    deprecated(Provider::Anthropic, "claude-2.0", "claude-sonnet-4-6"),
+    #[cfg(feature = "anthropic")]
+    deprecated(Provider::Anthropic, "claude-2.1", "claude-sonnet-4-6"),
+    #[cfg(feature = "anthropic")]
+    deprecated(
+        Provider::Anthropic,
+        "claude-3-sonnet-20240229",
+        "claude-sonnet-4-6",
+    ),
+    #[cfg(feature = "anthropic")]
+    deprecated(
+        Provider::Anthropic,
+        "claude-3-opus-20240229",
+        "claude-opus-4-1-20250805",
+    ),
+    #[cfg(feature = "anthropic")]
+    deprecated(
+        Provider::Anthropic,
+        "claude-3-5-sonnet-20240620",
+        "claude-sonnet-4-5-20250929",
+    ),
+    #[cfg(feature = "anthropic")]
+    deprecated(
+        Provider::Anthropic,
+        "claude-3-5-sonnet-20241022",
+        "claude-sonnet-4-5-20250929",
+    ),
+    #[cfg(feature = "anthropic")]
+    deprecated(
+        Provider::Anthropic,
+        "claude-3-7-sonnet-20250219",
+        "claude-sonnet-4-6",
+    ),
+    #[cfg(feature = "anthropic")]
+    deprecated(
+        Provider::Anthropic,
+        "claude-3-haiku-20240307",
+        "claude-haiku-4-5-20251001",
+    ),
+    #[cfg(feature = "anthropic")]
+    deprecated(
+        Provider::Anthropic,
+        "claude-3-5-haiku-20241022",
+        "claude-haiku-4-5-20251001",
+    ),
+    #[cfg(feature = "grok")]
+    deprecated(Provider::Grok, "grok-beta", "grok-4.3"),
+    #[cfg(feature = "grok")]
+    deprecated(Provider::Grok, "grok-2-1212", "grok-4.3"),
+    #[cfg(feature = "gemini")]
+    deprecated(Provider::Gemini, "gemini-1.5-pro", "gemini-2.5-pro"),
+    #[cfg(feature = "gemini")]
+    deprecated(Provider::Gemini, "gemini-1.5-flash", "gemini-2.5-flash"),
+    #[cfg(feature = "gemini")]
+    deprecated(Provider::Gemini, "gemini-2.0-flash", "gemini-2.5-flash"),
+    #[cfg(feature = "gemini")]
+    deprecated(Provider::Gemini, "gemini-2.0-flash-001", "gemini-2.5-flash"),
+    #[cfg(feature = "gemini")]
+    deprecated(
+        Provider::Gemini,
+        "gemini-2.0-flash-lite",
+        "gemini-2.5-flash-lite",
+    ),
+    #[cfg(feature = "gemini")]
+    deprecated(
+        Provider::Gemini,
+        "gemini-2.0-flash-lite-001",
+        "gemini-2.5-flash-lite",
+    ),
+];
+
+impl ModelId {
+    /// The bundled deprecation entry for this model, if it is known-deprecated.
+    pub fn deprecation(&self) -> Option<&'static Deprecation> {
+        DEPRECATIONS
+            .iter()
+            .find(|d| d.provider == self.provider && d.model == self.name)
+    }
+
+    /// This model, or its documented replacement if it is known-deprecated.
+    pub fn upgraded(&self) -> ModelId {
+        match self.deprecation() {
+            Some(d) => ModelId::new(self.provider, d.replacement),
+            None => self.clone(),
+        }
+    }
+}
+
+/// Log a structured warning if `model` is known-deprecated.
+pub(crate) fn warn_if_deprecated(model: &ModelId) {
+    if let Some(d) = model.deprecation() {
+        warn!(
+            provider = ?d.provider,
+            model = d.model,
+            replacement = d.replacement,
+            "Configured model is deprecated; call upgrade_deprecated_model() or switch to the replacement"
+        );
+    }
+}
+
+/// Whether `model` appears in `client.list_models()`, logging a warning if not.
+///
+/// Catches retirements newer than the bundled table. Costs one API call.
+///
+/// # Errors
+///
+/// Returns any error from [`LLMClient::list_models`].
+pub async fn check_model_listed<C: LLMClient + Sync>(client: &C, model: &ModelId) -> Result<bool> {
+    let listed = client
+        .list_models()
+        .await?
+        .iter()
+        .any(|m| m.id == model.name);
+    if !listed {
+        warn!(
+            provider = ?model.provider,
+            model = %model.name,
+            "Configured model is not listed by the provider; it may be retired"
+        );
+    }
+    Ok(listed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "gemini")]
+    #[test]
+    fn deprecated_model_upgrades_to_replacement() {
+        let model = ModelId::new(Provider::Gemini, "gemini-1.5-pro");
+        assert_eq!(model.deprecation().unwrap().replacement, "gemini-2.5-pro");
+        assert_eq!(model.upgraded().name, "gemini-2.5-pro");
+    }
+
+    /// Replacements are never guessed: each is a named model of its
+    /// provider's enum, and deprecated models that have a variant are marked
+    /// `#[deprecated]` on it.
+    #[cfg(feature = "gemini")]
+    #[test]
+    #[allow(deprecated)]
+    fn deprecated_gemini_variants_match_the_table() {
+        use crate::backend::gemini::Model;
+
+        for model in [
+            Model::Gemini20Flash,
+            Model::Gemini20Flash001,
+            Model::Gemini20FlashLite,
+            Model::Gemini20FlashLite001,
+        ] {
+            let deprecation = ModelId::from(&model).deprecation().unwrap();
+            let replacement = Model::from_string(deprecation.replacement);
+            assert!(!matches!(replacement, Model::Custom(_)), "{deprecation:?}");
+        }
+    }
+
+    #[test]
+    fn replacements_are_named_models() {
+        for d in DEPRECATIONS {
+            let named = match d.provider {
+                #[cfg(feature = "openai")]
+                Provider::OpenAI => !matches!(
+                    crate::backend::openai::Model::from_string(d.replacement),
+                    crate::backend::openai::Model::Custom(_)
+                ),
+                #[cfg(feature = "anthropic")]
+                Provider::Anthropic => !matches!(
+                    crate::backend::anthropic::AnthropicModel::from_string(d.replacement),
+                    crate::backend::anthropic::AnthropicModel::Custom(_)
+                ),
+                #[cfg(feature = "grok")]
+                Provider::Grok => !matches!(
+                    crate::backend::grok::Model::from_string(d.replacement),
+                    crate::backend::grok::Model::Custom(_)
+                ),
+                #[cfg(feature = "gemini")]
+                Provider::Gemini => !matches!(
+                    crate::backend::gemini::Model::from_string(d.replacement),
+                    crate::backend::gemini::Model::Custom(_)
+                ),
+                #[allow(unreachable_patterns)]
+                _ => true,
+            };
+            assert!(
+                named,
+                "{d:?} names a replacement this crate does not define"
+            );
+        }
+    }
+
+    #[cfg(feature = "openai")]
+    #[test]
+    fn current_model_is_not_deprecated() {
+        let model = ModelId::new(Provider::OpenAI, "gpt-5.5");
+        assert!(model.deprecation().is_none());
+        assert_eq!(model.upgraded(), model);
+    }
+
+    #[cfg(all(feature = "openai", feature = "anthropic"))]
+    #[test]
+    fn lookup_is_per_provider() {
+        assert!(
+            ModelId::new(Provider::OpenAI, "claude-2.1")
+                .deprecation()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,293 @@
+//! Distillation mode: extract with an expensive model, shadow-check a cheap one.
+//!
+//! [`DistillClient`] wraps a *primary* client (the model you trust today) and a
+//! *shadow* client (the cheaper model you would like to move to). Every call is
+//! answered by the primary; on a configurable sample of `materialize*` calls the
+//! shadow is also run on the same input and its output is compared with the
+//! primary's. Per-schema [`DistillStats`] then tell you how often the cheap
+//! model agrees — the evidence you need before downgrading a schema.
+//!
+//! ```
+//! # #[cfg(feature = "mock")]
+//! # #[tokio::main]
+//! # async fn main() -> rstructor::Result<()> {
+//! use rstructor::{DistillClient, Instructor, LLMClient, MockClient};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Instructor, Serialize, Deserialize)]
+//! struct Movie {
+//!     title: String,
+//! }
+//!
+//! let big = MockClient::new().with_default_response(r#"{"title": "Inception"}"#);
+//! let small = MockClient::new().with_default_response(r#"{"title": "Inception"}"#);
+//! let client = DistillClient::new(big, small).sample_every(1);
+//!
+//! let movie: Movie = client.materialize("Describe Inception").await?;
+//! assert_eq!(movie.title, "Inception");
+//! assert_eq!(client.stats_for::<Movie>().agreement_rate(), Some(1.0));
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "mock"))]
+//! # fn main() {}
+//! ```
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use tracing::{debug, warn};
+
+use crate::backend::usage::{GenerateResult, MaterializeResult};
+use crate::backend::{ChatMessage, LLMClient, MediaFile, ModelInfo};
+use crate::error::{RStructorError, Result};
+use crate::model::Instructor;
+
+/// Default sampling period: shadow one in every ten structured calls.
+pub const DEFAULT_DISTILL_SAMPLE_EVERY: u64 = 10;
+
+/// Agreement counters between the primary and shadow model for one schema.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DistillStats {
+    /// Shadow runs that produced a valid value which was compared to the primary.
+    pub compared: u64,
+    /// Compared runs where the shadow output equalled the primary output.
+    pub agreed: u64,
+    /// Shadow runs that failed (API error, invalid JSON, failed validation).
+    pub shadow_failed: u64,
+}
+
+impl DistillStats {
+    /// Total number of shadow runs, successful or not.
+    pub fn shadow_runs(&self) -> u64 {
+        self.compared + self.shadow_failed
+    }
+
+    /// Fraction of shadow runs that matched the primary, counting shadow
+    /// failures as disagreements. `None` until at least one shadow run.
+    pub fn agreement_rate(&self) -> Option<f64> {
+        let runs = self.shadow_runs();
+        (runs > 0).then(|| self.agreed as f64 / runs as f64)
+    }
+
+    fn merge(&mut self, other: &DistillStats) {
+        self.compared += other.compared;
+        self.agreed += other.agreed;
+        self.shadow_failed += other.shadow_failed;
+    }
+}
+
+#[derive(Default)]
+struct DistillState {
+    calls: AtomicU64,
+    stats: Mutex<HashMap<String, DistillStats>>,
+}
+
+/// An [`LLMClient`] that answers with a primary model and shadow-runs a cheaper
+/// one on a sample of structured calls, tracking agreement per schema.
+///
+/// The primary's result (or error) is always what the caller receives; shadow
+/// errors are recorded in [`DistillStats::shadow_failed`] and never surface.
+/// Sampled calls run the shadow after the primary, so they pay its latency.
+///
+/// Outputs are compared as JSON values after deserialization and validation, so
+/// key order and whitespace do not matter but every field value must match.
+///
+/// `DistillClient` is `Clone`; clones share their statistics.
+#[derive(Clone)]
+pub struct DistillClient<P, S> {
+    primary: P,
+    shadow: S,
+    sample_every: u64,
+    state: Arc<DistillState>,
+}
+
+impl<P, S> DistillClient<P, S> {
+    /// Wrap a `primary` (expensive, trusted) and `shadow` (cheap, candidate) client.
+    ///
+    /// Shadows one in every [`DEFAULT_DISTILL_SAMPLE_EVERY`] structured calls.
+    pub fn new(primary: P, shadow: S) -> Self {
+        Self {
+            primary,
+            shadow,
+            sample_every: DEFAULT_DISTILL_SAMPLE_EVERY,
+            state: Arc::new(DistillState::default()),
+        }
+    }
+
+    /// Shadow one in every `n` structured calls (`1` = every call, `0` = never).
+    #[must_use]
+    pub fn sample_every(mut self, n: u64) -> Self {
+        self.sample_every = n;
+        self
+    }
+
+    /// The primary client.
+    pub fn primary(&self) -> &P {
+        &self.primary
+    }
+
+    /// The shadow client.
+    pub fn shadow(&self) -> &S {
+        &self.shadow
+    }
+
+    /// Agreement statistics keyed by schema name.
+    pub fn stats(&self) -> HashMap<String, DistillStats> {
+        self.state.stats.lock().unwrap().clone()
+    }
+
+    /// Agreement statistics for the schema of `T`.
+    pub fn stats_for<T: Instructor>(&self) -> DistillStats {
+        self.state
+            .stats
+            .lock()
+            .unwrap()
+            .get(&schema_key::<T>())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Agreement statistics summed over every schema.
+    pub fn total_stats(&self) -> DistillStats {
+        let mut total = DistillStats::default();
+        for stats in self.state.stats.lock().unwrap().values() {
+            total.merge(stats);
+        }
+        total
+    }
+
+    /// Reset all counters.
+    pub fn reset_stats(&self) {
+        self.state.stats.lock().unwrap().clear();
+    }
+
+    /// Whether this call should be shadowed, advancing the call counter.
+    fn should_shadow(&self) -> bool {
+        if self.sample_every == 0 {
+            return false;
+        }
+        self.state
+            .calls
+            .fetch_add(1, Ordering::Relaxed)
+            .is_multiple_of(self.sample_every)
+    }
+
+    fn record<T: Instructor>(&self, primary: &T, shadow: Result<T>) {
+        let key = schema_key::<T>();
+        let mut all = self.state.stats.lock().unwrap();
+        let stats = all.entry(key.clone()).or_default();
+        match shadow {
+            Ok(shadow) => {
+                stats.compared += 1;
+                let agreed =
+                    serde_json::to_value(primary).ok() == serde_json::to_value(&shadow).ok();
+                if agreed {
+                    stats.agreed += 1;
+                } else {
+                    debug!(schema = %key, "Shadow model disagreed with primary");
+                }
+            }
+            Err(e) => {
+                stats.shadow_failed += 1;
+                warn!(schema = %key, error = %e, "Shadow model failed");
+            }
+        }
+    }
+}
+
+fn schema_key<T: Instructor>() -> String {
+    T::schema_name().unwrap_or_else(|| std::any::type_name::<T>().to_string())
+}
+
+#[async_trait]
+impl<P, S> LLMClient for DistillClient<P, S>
+where
+    P: LLMClient + Send + Sync,
+    S: LLMClient + Send + Sync,
+{
+    async fn materialize<T>(&self, prompt: &str) -> Result<T>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        let data: T = self.primary.materialize(prompt).await?;
+        if self.should_shadow() {
+            let shadow = self.shadow.materialize::<T>(prompt).await;
+            self.record(&data, shadow);
+        }
+        Ok(data)
+    }
+
+    async fn materialize_with_media<T>(&self, prompt: &str, media: &[MediaFile]) -> Result<T>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        let data: T = self.primary.materialize_with_media(prompt, media).await?;
+        if self.should_shadow() {
+            let shadow = self.shadow.materialize_with_media::<T>(prompt, media).await;
+            self.record(&data, shadow);
+        }
+        Ok(data)
+    }
+
+    async fn materialize_with_metadata<T>(&self, prompt: &str) -> Result<MaterializeResult<T>>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        let result = self.primary.materialize_with_metadata::<T>(prompt).await?;
+        if self.should_shadow() {
+            let shadow = self.shadow.materialize::<T>(prompt).await;
+            self.record(&result.data, shadow);
+        }
+        Ok(result)
+    }
+
+    async fn materialize_conversation<T>(
+        &self,
+        messages: &[ChatMessage],
+    ) -> Result<MaterializeResult<T>>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        let result = self.primary.materialize_conversation::<T>(messages).await?;
+        if self.should_shadow() {
+            let shadow = self
+                .shadow
+                .materialize_conversation::<T>(messages)
+                .await
+                .map(|shadow| shadow.data);
+            self.record(&result.data, shadow);
+        }
+        Ok(result)
+    }
+
+    async fn generate(&self, prompt: &str) -> Result<String> {
+        self.primary.generate(prompt).await
+    }
+
+    async fn generate_with_media(&self, prompt: &str, media: &[MediaFile]) -> Result<String> {
+        self.primary.generate_with_media(prompt, media).await
+    }
+
+    async fn generate_with_metadata(&self, prompt: &str) -> Result<GenerateResult> {
+        self.primary.generate_with_metadata(prompt).await
+    }
+
+    async fn generate_conversation(&self, messages: &[ChatMessage]) -> Result<GenerateResult> {
+        self.primary.generate_conversation(messages).await
+    }
+
+    /// Not supported: a `DistillClient` needs two configured clients, so build it
+    /// with [`DistillClient::new`].
+    fn from_env() -> Result<Self> {
+        Err(RStructorError::Unsupported(
+            "DistillClient wraps two clients; construct it with DistillClient::new".to_string(),
+        ))
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        self.primary.list_models().await
+    }
+}
@@ -0,0 +1,172 @@
+//! Object-safe clients for choosing a provider at runtime.
+//!
+//! [`LLMClient::materialize`] is generic over the target type, so
+//! `Box<dyn LLMClient>` is not a type. [`DynLLMClient`] is the type-erased
+//! counterpart: the schema is passed as a value and the reply comes back as
+//! JSON. Every `LLMClient` implements it, and [`DynMaterializeExt`] restores
+//! the typed `materialize::<T>()` on top, so application state can hold a
+//! `Box<dyn DynLLMClient>` (or `Arc<dyn DynLLMClient>`) and swap it freely.
+//!
+//! ```no_run
+//! # #[cfg(feature = "openai")]
+//! # async fn run() -> rstructor::Result<()> {
+//! use rstructor::{DynLLMClient, DynMaterializeExt, Instructor, OpenAIClient};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Instructor, Serialize, Deserialize)]
+//! struct Movie { title: String }
+//!
+//! let client: Box<dyn DynLLMClient> = Box::new(OpenAIClient::from_env()?);
+//! let movie: Movie = client.materialize("Describe Inception").await?;
+//! # let _ = movie;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::backend::LLMClient;
+use crate::error::{RStructorError, Result};
+use crate::model::Instructor;
+use crate::schema::{Schema, SchemaType};
+
+/// Extra validation for [`DynLLMClient::materialize_value_validated`]. An
+/// error is fed back to the model and the request re-asked, as with
+/// [`Instructor::validate`].
+pub type ValueValidator = Arc<dyn Fn(&Value) -> Result<()> + Send + Sync>;
+
+/// The schema and validator of the current type-erased request.
+#[derive(Clone)]
+struct DynamicTarget {
+    schema: Schema,
+    validator: Option<ValueValidator>,
+}
+
+tokio::task_local! {
+    static DYNAMIC: DynamicTarget;
+}
+
+/// Stand-in target type whose schema and validation come from [`DYNAMIC`].
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+struct DynamicValue(Value);
+
+impl SchemaType for DynamicValue {
+    fn schema() -> Schema {
+        DYNAMIC
+            .try_with(|target| target.schema.clone())
+            .unwrap_or_else(|_| Schema::new(json!({})))
+    }
+
+    fn schema_name() -> Option<String> {
+        DYNAMIC
+            .try_with(|target| {
+                let title = target.schema.schema.get("title")?.as_str()?;
+                Some(title.to_string())
+            })
+            .ok()?
+    }
+}
+
+impl Instructor for DynamicValue {
+    fn validate(&self) -> Result<()> {
+        match DYNAMIC.try_with(|target| target.validator.clone()) {
+            Ok(Some(validator)) => validator(&self.0),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Object-safe, type-erased view of an [`LLMClient`]. Implemented
+/// automatically for every `LLMClient`.
+///
+/// Requests go through the client's own `materialize`, so retries, re-asks,
+/// object-root wrapping and per-client configuration all apply.
+#[async_trait]
+pub trait DynLLMClient: Send + Sync {
+    /// Materialize a JSON value matching `schema`.
+    async fn materialize_value(&self, prompt: &str, schema: &Schema) -> Result<Value>;
+
+    /// Like [`materialize_value`](Self::materialize_value), re-asking while
+    /// `validator` rejects the reply.
+    async fn materialize_value_validated(
+        &self,
+        prompt: &str,
+        schema: &Schema,
+        validator: ValueValidator,
+    ) -> Result<Value>;
+
+    /// Generate free-form text (see [`LLMClient::generate`]).
+    async fn generate_text(&self, prompt: &str) -> Result<String>;
+}
+
+#[async_trait]
+impl<C: LLMClient + Send + Sync> DynLLMClient for C {
+    async fn materialize_value(&self, prompt: &str, schema: &Schema) -> Result<Value> {
+        materialize_dynamic(self, prompt, schema, None).await
+    }
+
+    async fn materialize_value_validated(
+        &self,
+        prompt: &str,
+        schema: &Schema,
+        validator: ValueValidator,
+    ) -> Result<Value> {
+        materialize_dynamic(self, prompt, schema, Some(validator)).await
+    }
+
+    async fn generate_text(&self, prompt: &str) -> Result<String> {
+        self.generate(prompt).await
+    }
+}
+
+/// Materialize a value for a runtime `schema`, re-asking while `validator`
+/// rejects it.
+pub(crate) async fn materialize_dynamic<C: LLMClient + Sync + ?Sized>(
+    client: &C,
+    prompt: &str,
+    schema: &Schema,
+    validator: Option<ValueValidator>,
+) -> Result<Value> {
+    let target = DynamicTarget {
+        schema: schema.clone(),
+        validator,
+    };
+    let value = DYNAMIC
+        .scope(target, client.materialize::<DynamicValue>(prompt))
+        .await?;
+    Ok(value.0)
+}
+
+/// Typed `materialize` for a `dyn DynLLMClient`.
+#[async_trait]
+pub trait DynMaterializeExt {
+    /// Materialize a `T` through a type-erased client: `T`'s schema is sent,
+    /// and the reply must deserialize into `T` and pass [`Instructor::validate`]
+    /// (re-asking otherwise) before it is returned.
+    async fn materialize<T>(&self, prompt: &str) -> Result<T>
+    where
+        T: Instructor + Send + 'static;
+}
+
+#[async_trait]
+impl DynMaterializeExt for dyn DynLLMClient + '_ {
+    async fn materialize<T>(&self, prompt: &str) -> Result<T>
+    where
+        T: Instructor + Send + 'static,
+    {
+        let validator: ValueValidator = Arc::new(|value| to_typed::<T>(value)?.validate());
+        let value = self
+            .materialize_value_validated(prompt, &T::schema(), validator)
+            .await?;
+        to_typed(&value)
+    }
+}
+
+fn to_typed<T: Instructor>(value: &Value) -> Result<T> {
+    T::deserialize(value).map_err(|e| RStructorError::ValidationError(e.to_string()))
+}
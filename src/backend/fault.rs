@@ -0,0 +1,400 @@
+//! Latency and fault injection for chaos-testing client configurations.
+//!
+//! [`FaultInjectingClient`] wraps any [`LLMClient`] (a real provider or a
+//! [`MockClient`](crate::MockClient)) and, on each call, injects the faults it
+//! was configured with at their given probabilities: added latency, a timeout,
+//! malformed JSON, or a specific [`ApiErrorKind`]. It exercises the code around
+//! a client, such as retry budgets, fallbacks between providers or circuit
+//! breakers, against failures that are hard to provoke from a real API.
+//!
+//! Malformed JSON is injected below the wrapped client's reply parser: the raw
+//! reply is cut off before the client parses it, so the client's own parse
+//! error and validation re-ask run exactly as they would for a real
+//! truncated reply.
+//!
+//! Faults are drawn from a seedable generator, so a failing run can be replayed
+//! with [`seed`](FaultInjectingClient::seed). The wrapper needs no async
+//! runtime: delays are timed on a helper thread.
+//!
+//! This module is only compiled with the `test-util` feature.
+//!
+//! ```no_run
+//! # async fn ex<C: rstructor::LLMClient + Send + Sync>(client: C) {
+//! use rstructor::{ApiErrorKind, FaultInjectingClient, LLMClient};
+//! use std::time::Duration;
+//!
+//! let chaos = FaultInjectingClient::new(client)
+//!     .seed(7)
+//!     .delay(0.5, Duration::from_millis(300))
+//!     .api_error(0.1, ApiErrorKind::ServiceUnavailable)
+//!     .malformed_json(0.05);
+//! let reply = chaos.generate("Say hello").await;
+//! println!("{reply:?}, injected so far: {:?}", chaos.injected());
+//! # }
+//! ```
+
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use tracing::debug;
+
+use crate::backend::timer::sleep;
+use crate::backend::usage::{GenerateResult, MaterializeResult};
+use crate::backend::{ChatMessage, LLMClient, MediaFile, ModelInfo};
+use crate::error::{ApiErrorKind, RStructorError, Result};
+use crate::model::Instructor;
+
+/// Provider named in injected API errors.
+const PROVIDER: &str = "FaultInjection";
+
+tokio::task_local! {
+    /// Set for a call under an injected malformed-JSON fault until its first
+    /// reply has been cut off.
+    static MALFORM_REPLY: Arc<AtomicBool>;
+}
+
+/// Cut `reply` off halfway if the current call is under an injected
+/// malformed-JSON fault and no earlier reply of the call has been cut.
+///
+/// Called through [`fault_injection_seam`](crate::backend::reply::fault_injection_seam)
+/// just before a reply is parsed, so only the first
+/// attempt of a call is corrupted and any re-ask sees the real reply.
+pub(crate) fn malform_reply(reply: &mut String) {
+    let armed = MALFORM_REPLY
+        .try_with(|armed| armed.swap(false, Ordering::Relaxed))
+        .unwrap_or(false);
+    if armed {
+        debug!("Injecting malformed JSON");
+        truncate(reply);
+    }
+}
+
+/// Counts of the faults a [`FaultInjectingClient`] has injected so far.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InjectedFaults {
+    /// Calls that were delayed before reaching the wrapped client.
+    pub delays: u64,
+    /// Calls that failed with [`RStructorError::Timeout`].
+    pub timeouts: u64,
+    /// Calls whose reply was cut off halfway before it was parsed.
+    pub malformed: u64,
+    /// Calls that failed with an injected [`ApiErrorKind`].
+    pub api_errors: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+struct FaultConfig {
+    delay: Option<(f64, Duration)>,
+    timeout: Option<(f64, Duration)>,
+    malformed_json: f64,
+    api_errors: Vec<(f64, ApiErrorKind)>,
+}
+
+#[derive(Debug)]
+struct FaultState {
+    rng: u64,
+    injected: InjectedFaults,
+}
+
+impl FaultState {
+    /// Whether an event of `probability` happens, drawn with SplitMix64.
+    fn roll(&mut self, probability: f64) -> bool {
+        if probability <= 0.0 {
+            return false;
+        }
+        self.rng = self.rng.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.rng;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        ((z >> 11) as f64 / (1u64 << 53) as f64) < probability
+    }
+}
+
+/// What happens to one call, decided before it starts.
+struct Plan {
+    delay: Option<Duration>,
+    outcome: Outcome,
+}
+
+enum Outcome {
+    Forward,
+    Malformed,
+    Timeout(Duration),
+    ApiError(ApiErrorKind),
+}
+
+/// An [`LLMClient`] that injects latency and failures into the calls it
+/// forwards to the wrapped client.
+///
+/// Each call first draws the delay, then at most one failure, checked in the
+/// order timeout, API errors (in the order they were added), malformed JSON.
+/// A timeout waits out its duration and returns [`RStructorError::Timeout`];
+/// an API error returns at once. Neither reaches the wrapped client. Malformed
+/// JSON does call the wrapped client, as a real malformed reply is still a
+/// billed request, and cuts its first reply off halfway: in `materialize*`
+/// before the wrapped client parses it, so the call fails or re-asks the model
+/// exactly as the client handles a truncated reply, and in `generate*` in the
+/// returned text. Clients without a parser this module hooks into (anything
+/// other than the built-in providers and [`MockClient`](crate::MockClient))
+/// see no malformed replies.
+///
+/// Clones share the generator and the [`injected`](Self::injected) counts.
+#[derive(Clone)]
+pub struct FaultInjectingClient<C> {
+    inner: C,
+    config: Arc<FaultConfig>,
+    state: Arc<Mutex<FaultState>>,
+}
+
+impl<C> FaultInjectingClient<C> {
+    /// Wrap `inner`, injecting nothing until faults are added.
+    pub fn new(inner: C) -> Self {
+        let seed = std::collections::hash_map::RandomState::new()
+            .build_hasher()
+            .finish();
+        Self {
+            inner,
+            config: Arc::default(),
+            state: Arc::new(Mutex::new(FaultState {
+                rng: seed,
+                injected: InjectedFaults::default(),
+            })),
+        }
+    }
+
+    /// Draw faults from a generator seeded with `seed`, so a run can be
+    /// replayed call for call.
+    #[must_use]
+    pub fn seed(self, seed: u64) -> Self {
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).rng = seed;
+        self
+    }
+
+    /// Delay a call by `duration` with `probability` (0.0 to 1.0) before it
+    /// proceeds.
+    #[must_use]
+    pub fn delay(mut self, probability: f64, duration: Duration) -> Self {
+        Arc::make_mut(&mut self.config).delay = Some((probability, duration));
+        self
+    }
+
+    /// With `probability`, wait `after` and then fail the call with
+    /// [`RStructorError::Timeout`], as a client timeout would.
+    #[must_use]
+    pub fn timeout(mut self, probability: f64, after: Duration) -> Self {
+        Arc::make_mut(&mut self.config).timeout = Some((probability, after));
+        self
+    }
+
+    /// With `probability`, cut the wrapped client's first reply to a call off
+    /// halfway, leaving malformed JSON for it to parse.
+    #[must_use]
+    pub fn malformed_json(mut self, probability: f64) -> Self {
+        Arc::make_mut(&mut self.config).malformed_json = probability;
+        self
+    }
+
+    /// Fail a call with `kind` with `probability`. May be called repeatedly
+    /// to inject several kinds.
+    #[must_use]
+    pub fn api_error(mut self, probability: f64, kind: ApiErrorKind) -> Self {
+        Arc::make_mut(&mut self.config)
+            .api_errors
+            .push((probability, kind));
+        self
+    }
+
+    /// The wrapped client.
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// The faults injected so far, across clones of this client.
+    pub fn injected(&self) -> InjectedFaults {
+        self.state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .injected
+    }
+
+    fn plan(&self) -> Plan {
+        let config = &self.config;
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let delay = config
+            .delay
+            .filter(|&(probability, _)| state.roll(probability))
+            .map(|(_, duration)| duration);
+        let outcome = if let Some((_, after)) = config
+            .timeout
+            .filter(|&(probability, _)| state.roll(probability))
+        {
+            state.injected.timeouts += 1;
+            Outcome::Timeout(after)
+        } else if let Some((_, kind)) = config
+            .api_errors
+            .iter()
+            .find(|(probability, _)| state.roll(*probability))
+        {
+            state.injected.api_errors += 1;
+            Outcome::ApiError(kind.clone())
+        } else if state.roll(config.malformed_json) {
+            Outcome::Malformed
+        } else {
+            Outcome::Forward
+        };
+        state.injected.delays += u64::from(delay.is_some());
+        Plan { delay, outcome }
+    }
+
+    /// Run `call` under this call's faults; `malform` cuts off text the call
+    /// returns unparsed, through [`malform_reply`].
+    async fn inject<R>(
+        &self,
+        call: impl Future<Output = Result<R>>,
+        malform: impl FnOnce(R) -> R,
+    ) -> Result<R> {
+        let plan = self.plan();
+        if let Some(delay) = plan.delay {
+            debug!(?delay, "Injecting latency");
+            sleep(delay).await;
+        }
+        match plan.outcome {
+            Outcome::Forward => call.await,
+            Outcome::Malformed => {
+                let armed = Arc::new(AtomicBool::new(true));
+                let result = MALFORM_REPLY
+                    .scope(armed.clone(), async { call.await.map(malform) })
+                    .await;
+                if !armed.load(Ordering::Relaxed) {
+                    self.state
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .injected
+                        .malformed += 1;
+                }
+                result
+            }
+            Outcome::Timeout(after) => {
+                debug!(?after, "Injecting timeout");
+                sleep(after).await;
+                Err(RStructorError::Timeout)
+            }
+            Outcome::ApiError(kind) => {
+                debug!(%kind, "Injecting API error");
+                Err(RStructorError::api_error(PROVIDER, kind))
+            }
+        }
+    }
+}
+
+/// Cut `text` off halfway, like a reply that ran out of output tokens.
+fn truncate(text: &mut String) {
+    let mut end = text.len() / 2;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    text.truncate(end);
+}
+
+/// Leave a reply alone: parsed replies were cut off by [`malform_reply`] before
+/// parsing, and the model list is never malformed.
+fn unchanged<R>(reply: R) -> R {
+    reply
+}
+
+#[async_trait]
+impl<C> LLMClient for FaultInjectingClient<C>
+where
+    C: LLMClient + Send + Sync,
+{
+    async fn materialize<T>(&self, prompt: &str) -> Result<T>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        self.inject(self.inner.materialize::<T>(prompt), unchanged)
+            .await
+    }
+
+    async fn materialize_with_media<T>(&self, prompt: &str, media: &[MediaFile]) -> Result<T>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        self.inject(
+            self.inner.materialize_with_media::<T>(prompt, media),
+            unchanged,
+        )
+        .await
+    }
+
+    async fn materialize_with_metadata<T>(&self, prompt: &str) -> Result<MaterializeResult<T>>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        self.inject(self.inner.materialize_with_metadata::<T>(prompt), unchanged)
+            .await
+    }
+
+    async fn materialize_conversation<T>(
+        &self,
+        messages: &[ChatMessage],
+    ) -> Result<MaterializeResult<T>>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        self.inject(
+            self.inner.materialize_conversation::<T>(messages),
+            unchanged,
+        )
+        .await
+    }
+
+    async fn generate(&self, prompt: &str) -> Result<String> {
+        self.inject(self.inner.generate(prompt), |mut text| {
+            malform_reply(&mut text);
+            text
+        })
+        .await
+    }
+
+    async fn generate_with_media(&self, prompt: &str, media: &[MediaFile]) -> Result<String> {
+        self.inject(self.inner.generate_with_media(prompt, media), |mut text| {
+            malform_reply(&mut text);
+            text
+        })
+        .await
+    }
+
+    async fn generate_with_metadata(&self, prompt: &str) -> Result<GenerateResult> {
+        self.inject(self.inner.generate_with_metadata(prompt), |mut result| {
+            malform_reply(&mut result.text);
+            result
+        })
+        .await
+    }
+
+    async fn generate_conversation(&self, messages: &[ChatMessage]) -> Result<GenerateResult> {
+        self.inject(self.inner.generate_conversation(messages), |mut result| {
+            malform_reply(&mut result.text);
+            result
+        })
+        .await
+    }
+
+    /// Build the wrapped client from its environment; no faults are injected
+    /// until some are added.
+    fn from_env() -> Result<Self> {
+        Ok(Self::new(C::from_env()?))
+    }
+
+    /// Delays, timeouts and API errors apply here too; the model list is
+    /// never malformed.
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        self.inject(self.inner.list_models(), unchanged).await
+    }
+}
@@ -0,0 +1,335 @@
+//! Recording provider request/response pairs as replayable fixtures.
+//!
+//! [`Fixture`] files can be loaded and saved in any client build. Recording
+//! them from live calls needs the `test-util` feature, so other builds neither
+//! read `RSTRUCTOR_RECORD_FIXTURES` nor touch the send path.
+//!
+//! With recording on, every non-streaming provider call writes one
+//! [`Fixture`] to `<dir>/<provider>/NNN.json`. The files feed offline tests of
+//! the response-parsing layer: serve a fixture's response body from a local
+//! mock server and point a client at it, and a provider that adds or reshapes
+//! response fields shows up as a failing test instead of a production error.
+//!
+//! Recordings are anonymized on the way out. Header values (API keys), the
+//! host and the query string (Gemini's `key=`) are never written, ids and
+//! timestamps the provider generates are replaced with placeholders, and long
+//! strings such as base64 media are elided. Prompts and model output are kept
+//! verbatim, so record with inputs you are happy to commit.
+
+use std::path::{Path, PathBuf};
+#[cfg(feature = "test-util")]
+use std::sync::RwLock;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+#[cfg(feature = "test-util")]
+use tracing::{debug, warn};
+
+/// Environment variable that turns recording on (its value is the fixtures
+/// directory) unless [`record_fixtures`] or [`stop_recording_fixtures`] was
+/// called.
+#[cfg(feature = "test-util")]
+pub const RECORD_FIXTURES_ENV: &str = "RSTRUCTOR_RECORD_FIXTURES";
+
+/// Response body fields whose values are generated per call.
+#[cfg(feature = "test-util")]
+const VOLATILE_FIELDS: &[&str] = &[
+    "id",
+    "created",
+    "created_at",
+    "system_fingerprint",
+    "responseId",
+    "request_id",
+];
+
+/// Strings longer than this (in bytes) are replaced with a length marker.
+#[cfg(feature = "test-util")]
+const MAX_STRING_LEN: usize = 2048;
+
+#[cfg(feature = "test-util")]
+enum Mode {
+    FromEnv,
+    Dir(PathBuf),
+    Off,
+}
+
+#[cfg(feature = "test-util")]
+static MODE: RwLock<Mode> = RwLock::new(Mode::FromEnv);
+
+/// Record every subsequent non-streaming provider call into `dir`.
+///
+/// Applies process-wide, to every client. Files are written as
+/// `<dir>/<provider>/NNN.json`, numbered from the first free index.
+#[cfg(feature = "test-util")]
+pub fn record_fixtures(dir: impl Into<PathBuf>) {
+    *MODE.write().unwrap_or_else(|e| e.into_inner()) = Mode::Dir(dir.into());
+}
+
+/// Stop recording, including recording turned on by [`RECORD_FIXTURES_ENV`].
+#[cfg(feature = "test-util")]
+pub fn stop_recording_fixtures() {
+    *MODE.write().unwrap_or_else(|e| e.into_inner()) = Mode::Off;
+}
+
+/// The directory calls are currently recorded into, if any.
+#[cfg(feature = "test-util")]
+pub fn fixture_recording_dir() -> Option<PathBuf> {
+    match &*MODE.read().unwrap_or_else(|e| e.into_inner()) {
+        Mode::Dir(dir) => Some(dir.clone()),
+        Mode::Off => None,
+        Mode::FromEnv => std::env::var_os(RECORD_FIXTURES_ENV)
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from),
+    }
+}
+
+/// One recorded provider call.
+///
+/// ```
+/// use rstructor::Fixture;
+/// use serde_json::json;
+///
+/// let fixture: Fixture = serde_json::from_value(json!({
+///     "provider": "OpenAI",
+///     "request": { "method": "POST", "path": "/v1/chat/completions", "body": {} },
+///     "response": { "status": 200, "body": { "choices": [] } }
+/// }))
+/// .unwrap();
+/// assert_eq!(fixture.response.status, 200);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fixture {
+    /// Provider name, as in error messages (e.g. `"OpenAI"`, `"Gemini"`).
+    pub provider: String,
+    /// What was sent.
+    pub request: FixtureRequest,
+    /// What came back.
+    pub response: FixtureResponse,
+}
+
+/// The request half of a [`Fixture`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FixtureRequest {
+    /// HTTP method.
+    pub method: String,
+    /// URL path, without host or query string.
+    pub path: String,
+    /// JSON body (`null` for requests without one).
+    pub body: Value,
+}
+
+/// The response half of a [`Fixture`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FixtureResponse {
+    /// HTTP status code.
+    pub status: u16,
+    /// JSON body, or a string if the body was not JSON.
+    pub body: Value,
+}
+
+impl Fixture {
+    /// Read one fixture file.
+    pub fn load(path: impl AsRef<Path>) -> std::io::Result<Fixture> {
+        let text = std::fs::read_to_string(path)?;
+        serde_json::from_str(&text).map_err(std::io::Error::other)
+    }
+
+    /// Every `*.json` fixture under `dir` (recursively), sorted by path.
+    pub fn load_dir(dir: impl AsRef<Path>) -> std::io::Result<Vec<(PathBuf, Fixture)>> {
+        let mut paths = Vec::new();
+        collect_json_files(dir.as_ref(), &mut paths)?;
+        paths.sort();
+        paths
+            .into_iter()
+            .map(|path| Fixture::load(&path).map(|fixture| (path, fixture)))
+            .collect()
+    }
+
+    /// Write this fixture to `<dir>/<provider>/NNN.json` and return the path.
+    pub fn save(&self, dir: impl AsRef<Path>) -> std::io::Result<PathBuf> {
+        let dir = dir.as_ref().join(provider_slug(&self.provider));
+        std::fs::create_dir_all(&dir)?;
+        let text = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        for index in 1.. {
+            let path = dir.join(format!("{index:03}.json"));
+            match std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(mut file) => {
+                    std::io::Write::write_all(&mut file, format!("{text}\n").as_bytes())?;
+                    return Ok(path);
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        unreachable!("an unbounded range always has a free index")
+    }
+}
+
+fn collect_json_files(dir: &Path, paths: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_json_files(&path, paths)?;
+        } else if path.extension().is_some_and(|ext| ext == "json") {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// `"Azure OpenAI"` → `"azure-openai"`.
+fn provider_slug(provider: &str) -> String {
+    provider
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Send `request`, recording the exchange when recording is on.
+///
+/// Streaming responses are passed through untouched: buffering them here
+/// would defeat streaming.
+#[cfg(feature = "test-util")]
+pub(crate) async fn send_recorded(
+    provider: &str,
+    request: reqwest::RequestBuilder,
+) -> reqwest::Result<reqwest::Response> {
+    let Some(dir) = fixture_recording_dir() else {
+        return request.send().await;
+    };
+    let sent = request.try_clone().and_then(|clone| clone.build().ok());
+    let response = request.send().await?;
+    let is_json = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|content_type| {
+            content_type.contains("json") && !content_type.contains("ndjson")
+        });
+    let Some(sent) = sent.filter(|_| is_json) else {
+        return Ok(response);
+    };
+
+    let status = response.status();
+    let version = response.version();
+    let headers = response.headers().clone();
+    let bytes = response.bytes().await?;
+
+    let fixture = Fixture {
+        provider: provider.to_string(),
+        request: FixtureRequest {
+            method: sent.method().to_string(),
+            path: sent.url().path().to_string(),
+            body: sent
+                .body()
+                .and_then(|body| body.as_bytes())
+                .map_or(Value::Null, |body| anonymize(json_or_text(body))),
+        },
+        response: FixtureResponse {
+            status: status.as_u16(),
+            body: anonymize(json_or_text(&bytes)),
+        },
+    };
+    match fixture.save(&dir) {
+        Ok(path) => debug!(path = %path.display(), "Recorded provider fixture"),
+        Err(e) => warn!(dir = %dir.display(), error = %e, "Failed to record provider fixture"),
+    }
+
+    let mut rebuilt = http::Response::new(bytes);
+    *rebuilt.status_mut() = status;
+    *rebuilt.version_mut() = version;
+    *rebuilt.headers_mut() = headers;
+    Ok(reqwest::Response::from(rebuilt))
+}
+
+#[cfg(feature = "test-util")]
+fn json_or_text(bytes: &[u8]) -> Value {
+    serde_json::from_slice(bytes)
+        .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(bytes).into_owned()))
+}
+
+/// Replace per-call ids and timestamps with placeholders and elide long strings.
+#[cfg(feature = "test-util")]
+fn anonymize(mut value: Value) -> Value {
+    fn walk(value: &mut Value) {
+        match value {
+            Value::Object(obj) => {
+                for (key, child) in obj.iter_mut() {
+                    if VOLATILE_FIELDS.contains(&key.as_str()) {
+                        *child = match child {
+                            Value::Number(_) => Value::from(0),
+                            _ => Value::from(format!("<{key}>")),
+                        };
+                    } else {
+                        walk(child);
+                    }
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(walk),
+            Value::String(text) if text.len() > MAX_STRING_LEN => {
+                *text = format!("<{} bytes elided>", text.len());
+            }
+            _ => {}
+        }
+    }
+    walk(&mut value);
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn anonymize_replaces_volatile_fields_and_long_strings() {
+        let media = "A".repeat(MAX_STRING_LEN + 1);
+        let body = anonymize(json!({
+            "id": "chatcmpl-abc123",
+            "created": 1_700_000_000,
+            "choices": [{ "message": { "content": "{\"a\":1}" } }],
+            "image": media
+        }));
+        assert_eq!(body["id"], "<id>");
+        assert_eq!(body["created"], 0);
+        assert_eq!(body["choices"][0]["message"]["content"], "{\"a\":1}");
+        assert_eq!(
+            body["image"],
+            format!("<{} bytes elided>", MAX_STRING_LEN + 1)
+        );
+    }
+
+    #[test]
+    fn save_numbers_files_per_provider_and_load_dir_reads_them_back() {
+        let dir = std::env::temp_dir().join(format!("rstructor-fixtures-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let fixture = Fixture {
+            provider: "Azure OpenAI".to_string(),
+            request: FixtureRequest {
+                method: "POST".to_string(),
+                path: "/chat/completions".to_string(),
+                body: json!({ "model": "gpt-4o" }),
+            },
+            response: FixtureResponse {
+                status: 200,
+                body: json!({ "choices": [] }),
+            },
+        };
+
+        let first = fixture.save(&dir).unwrap();
+        let second = fixture.save(&dir).unwrap();
+        assert!(first.ends_with("azure-openai/001.json"));
+        assert!(second.ends_with("azure-openai/002.json"));
+
+        let loaded = Fixture::load_dir(&dir).unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[0].1, fixture);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -2,15 +2,18 @@ use async_trait::async_trait;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::sync::Arc;
 use std::time::Duration;
 use tracing::{debug, error, info, instrument, trace, warn};
 
 use crate::backend::model_macro::define_model_enum;
 use crate::backend::{
-    ChatMessage, DEFAULT_REQUEST_TIMEOUT, GenerateResult, LLMClient, MaterializeInternalOutput,
-    MaterializeResult, ModelInfo, ThinkingLevel, TokenUsage, ValidationFailureContext,
-    build_http_client, check_response_status, generate_with_retry_with_history, handle_http_error,
-    materialize_with_media_with_retry, parse_validate_and_create_output,
+    ChatMessage, ContextOverflow, DEFAULT_REQUEST_TIMEOUT, GenerateResult, HttpClientCell,
+    LLMClient, MaterializeInternalOutput, MaterializeResult, ModelInfo, RateLimiter, ThinkingLevel,
+    TokenUsage, UsageTracker, ValidationFailureContext, check_response_status,
+    generate_with_retry_with_conversation, generate_with_retry_with_history,
+    materialize_with_media_with_retry, model_override, parse_validate_and_create_output,
+    send_limited, split_system_messages,
 };
 use crate::error::{ApiErrorKind, RStructorError, Result};
 use crate::model::Instructor;
@@ -64,12 +67,16 @@ define_model_enum! {
         /// Gemini 2.5 Flash Image (image generation/analysis tuned variant)
         Gemini25FlashImage => "gemini-2.5-flash-image",
         /// Gemini 2.0 Flash (deprecated 2.0 Flash model)
+        #[deprecated(note = "deprecated by Google; use `gemini-2.5-flash` (`Gemini25Flash`)")]
         Gemini20Flash => "gemini-2.0-flash",
         /// Gemini 2.0 Flash 001 (deprecated specific version of 2.0 Flash)
+        #[deprecated(note = "deprecated by Google; use `gemini-2.5-flash` (`Gemini25Flash`)")]
         Gemini20Flash001 => "gemini-2.0-flash-001",
         /// Gemini 2.0 Flash Lite (deprecated smaller 2.0 Flash variant)
+        #[deprecated(note = "deprecated by Google; use `gemini-2.5-flash-lite` (`Gemini25FlashLite`)")]
         Gemini20FlashLite => "gemini-2.0-flash-lite",
         /// Gemini 2.0 Flash Lite 001 (deprecated specific version of 2.0 Flash Lite)
+        #[deprecated(note = "deprecated by Google; use `gemini-2.5-flash-lite` (`Gemini25FlashLite`)")]
         Gemini20FlashLite001 => "gemini-2.0-flash-lite-001",
         /// Gemini Pro Latest (alias for latest Pro model)
         GeminiProLatest => "gemini-pro-latest",
@@ -80,6 +87,22 @@ define_model_enum! {
     }
 }
 
+impl From<&Model> for crate::backend::ModelId {
+    fn from(model: &Model) -> Self {
+        crate::backend::ModelId::new(crate::backend::Provider::Gemini, model.as_str())
+    }
+}
+
+impl From<crate::backend::ModelTier> for Model {
+    fn from(tier: crate::backend::ModelTier) -> Self {
+        Model::from_string(tier.resolve(crate::backend::Provider::Gemini).name)
+    }
+}
+
+/// Gemini's 20 MB limit on a request with inline data, from the Gemini API
+/// docs on passing files inline instead of through the Files API.
+const MAX_REQUEST_BYTES: usize = 20_000_000;
+
 /// Configuration for the Gemini client
 #[derive(Debug, Clone)]
 pub struct GeminiConfig {
@@ -91,6 +114,32 @@ pub struct GeminiConfig {
     /// Defaults to [`DEFAULT_REQUEST_TIMEOUT`](crate::DEFAULT_REQUEST_TIMEOUT) (5 minutes).
     pub timeout: Option<Duration>,
     pub max_retries: Option<usize>,
+    /// Collect fields the model returned but the schema does not declare into
+    /// [`MaterializeResult::extra_fields`](crate::MaterializeResult::extra_fields).
+    pub capture_unknown_fields: bool,
+    /// Ask for a rationale in a generated
+    /// [`EXPLANATION_FIELD`](crate::EXPLANATION_FIELD) property, returned in
+    /// [`MaterializeResult::explanation`](crate::MaterializeResult::explanation).
+    pub explain: bool,
+    /// What `materialize` does when the prompt exceeds the context window.
+    pub context_overflow: ContextOverflow,
+    /// System prompt sent with every request, as the provider's system
+    /// instruction rather than as part of the user prompt.
+    pub system_prompt: Option<String>,
+    /// Locale the schema's descriptions are sent in; see
+    /// [`Schema::localized`](crate::Schema::localized).
+    pub schema_locale: Option<String>,
+    /// Translations consulted before those recorded by the derive.
+    pub description_catalog: Option<Arc<crate::schema::DescriptionCatalog>>,
+    /// Request and token budgets this client's requests wait for, possibly
+    /// shared with other clients.
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    /// Counts the requests and tokens of this client's structured calls,
+    /// possibly shared with other clients.
+    pub usage_tracker: UsageTracker,
+    /// Largest request body this client sends; bigger requests fail with
+    /// [`RStructorError::RequestTooLarge`] before anything is uploaded.
+    pub max_request_bytes: Option<usize>,
     /// Custom base URL for Gemini-compatible APIs
     /// Defaults to "https://generativelanguage.googleapis.com/v1beta" if not set
     pub base_url: Option<String>,
@@ -99,16 +148,18 @@ pub struct GeminiConfig {
     pub thinking_level: Option<ThinkingLevel>,
 }
 
-/// Gemini client for generating completions
+/// Gemini client for generating completions.
+///
+/// Cheap to clone and `Send + Sync`; see [sharing clients](crate::LLMClient#sharing-clients).
 #[derive(Clone)]
 pub struct GeminiClient {
-    config: GeminiConfig,
-    client: reqwest::Client,
+    config: Arc<GeminiConfig>,
+    client: HttpClientCell,
 }
 
-// Gemini API request and response structures
+// Gemini API request and response structures, shared with Vertex AI
 #[derive(Debug, Serialize)]
-struct Content {
+pub(super) struct Content {
     #[serde(skip_serializing_if = "Option::is_none")]
     role: Option<String>,
     parts: Vec<Part>,
@@ -145,26 +196,28 @@ struct InlineData {
 }
 
 #[derive(Debug, Serialize)]
-struct GenerateContentRequest {
-    contents: Vec<Content>,
-    generation_config: GenerationConfig,
+pub(super) struct GenerateContentRequest {
+    #[serde(rename = "systemInstruction", skip_serializing_if = "Option::is_none")]
+    pub(super) system_instruction: Option<Content>,
+    pub(super) contents: Vec<Content>,
+    pub(super) generation_config: GenerationConfig,
 }
 
 #[derive(Debug, Serialize)]
-struct GenerationConfig {
-    temperature: f32,
+pub(super) struct GenerationConfig {
+    pub(super) temperature: f32,
     #[serde(skip_serializing_if = "Option::is_none")]
-    max_output_tokens: Option<u32>,
+    pub(super) max_output_tokens: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    response_mime_type: Option<String>,
+    pub(super) response_mime_type: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    response_schema: Option<Value>,
+    pub(super) response_schema: Option<Value>,
     #[serde(skip_serializing_if = "Option::is_none", rename = "thinkingConfig")]
-    thinking_config: Option<ThinkingConfig>,
+    pub(super) thinking_config: Option<ThinkingConfig>,
 }
 
 #[derive(Debug, Serialize)]
-struct ThinkingConfig {
+pub(super) struct ThinkingConfig {
     #[serde(rename = "thinkingLevel")]
     thinking_level: String,
 }
@@ -175,10 +228,21 @@ struct UsageMetadata {
     prompt_token_count: u64,
     #[serde(rename = "candidatesTokenCount", default)]
     candidates_token_count: u64,
+    /// Thinking tokens, billed as output but not part of `candidatesTokenCount`
+    #[serde(rename = "thoughtsTokenCount", default)]
+    thoughts_token_count: u64,
+}
+
+impl UsageMetadata {
+    /// Usage with thinking tokens counted as output.
+    fn token_usage(&self, model: String) -> TokenUsage {
+        let output = self.candidates_token_count + self.thoughts_token_count;
+        TokenUsage::new(model, self.prompt_token_count, output)
+    }
 }
 
 #[derive(Debug, Deserialize)]
-struct GenerateContentResponse {
+pub(super) struct GenerateContentResponse {
     candidates: Vec<Candidate>,
     #[serde(rename = "usageMetadata", default)]
     usage_metadata: Option<UsageMetadata>,
@@ -206,9 +270,11 @@ struct CandidatePart {
 /// Convert provider-agnostic chat messages into Gemini `contents`, including any
 /// attached media as `inlineData` (base64) or `fileData` (URI) parts. Gemini
 /// passes the MIME type through, so images and PDFs share the same encoding.
-fn chat_messages_to_contents(messages: &[ChatMessage]) -> Vec<Content> {
+fn chat_messages_to_contents<'a>(
+    messages: impl IntoIterator<Item = &'a ChatMessage>,
+) -> Vec<Content> {
     messages
-        .iter()
+        .into_iter()
         .map(|msg| {
             // Gemini uses "user" and "model" (not "assistant")
             let role = if msg.role.as_str() == "assistant" {
@@ -247,6 +313,169 @@ fn chat_messages_to_contents(messages: &[ChatMessage]) -> Vec<Content> {
         .collect()
 }
 
+/// A system instruction with `text` as its only part.
+fn system_content(text: String) -> Content {
+    Content {
+        role: None,
+        parts: vec![Part::Text { text }],
+    }
+}
+
+/// The `systemInstruction` and `contents` for a conversation. Gemini has no
+/// system role, so system messages in the history join the configured
+/// system prompt.
+pub(super) fn conversation_contents(
+    system_prompt: Option<&str>,
+    messages: &[ChatMessage],
+) -> (Option<Content>, Vec<Content>) {
+    let (system, turns) = split_system_messages(system_prompt, messages);
+    (system.map(system_content), chat_messages_to_contents(turns))
+}
+
+/// The thinking config for `level`, sent only to models that think (Gemini 3.x).
+pub(super) fn thinking_config(
+    supports_thinking: bool,
+    level: Option<ThinkingLevel>,
+) -> Option<ThinkingConfig> {
+    if !supports_thinking {
+        return None;
+    }
+    level.and_then(|level| {
+        level.gemini_level().map(|l| ThinkingConfig {
+            thinking_level: l.to_string(),
+        })
+    })
+}
+
+/// Usage of a reply, attributed to the model version that served it.
+fn reply_usage(completion: &GenerateContentResponse, model: &str) -> Option<TokenUsage> {
+    let model_name = completion
+        .model_version
+        .clone()
+        .unwrap_or_else(|| model.to_string());
+    completion
+        .usage_metadata
+        .as_ref()
+        .map(|u| u.token_usage(model_name))
+}
+
+/// Parse and validate the structured output in a `generateContent` reply,
+/// turning internally tagged enums back into adjacently tagged ones first.
+#[allow(clippy::result_large_err)]
+pub(super) fn structured_reply<T>(
+    completion: GenerateContentResponse,
+    model: &str,
+    adjacently_tagged_info: Option<&crate::backend::utils::AdjacentlyTaggedEnumInfo>,
+    provider: &'static str,
+) -> std::result::Result<
+    MaterializeInternalOutput<T>,
+    (RStructorError, Option<ValidationFailureContext>),
+>
+where
+    T: Instructor + DeserializeOwned + Send + 'static,
+{
+    if completion.candidates.is_empty() {
+        error!("{provider} API returned empty candidates array");
+        return Err((
+            RStructorError::api_error(
+                provider,
+                ApiErrorKind::UnexpectedResponse {
+                    details: "No completion candidates returned".to_string(),
+                },
+            ),
+            None,
+        ));
+    }
+
+    let usage = reply_usage(&completion, model);
+
+    let candidate = &completion.candidates[0];
+    trace!(finish_reason = ?candidate.finish_reason, "Completion finish reason");
+
+    let parts = &candidate.content.parts;
+    debug!(parts = parts.len(), "Processing candidate content parts");
+    for part in parts {
+        if let Some(text) = &part.text {
+            let mut raw_response = text.clone();
+            debug!(content_len = raw_response.len(), "Processing text part");
+            // With native response_schema, the response is guaranteed to be valid JSON
+            trace!(json = %raw_response, "Parsing structured output response");
+
+            // Transform internally tagged enums back to adjacently tagged format if needed
+            if let Some(enum_info) = adjacently_tagged_info
+                && let Ok(mut json_value) = serde_json::from_str::<serde_json::Value>(&raw_response)
+            {
+                crate::backend::utils::transform_internally_to_adjacently_tagged(
+                    &mut json_value,
+                    enum_info,
+                );
+                raw_response = serde_json::to_string(&json_value).unwrap_or(raw_response);
+            }
+
+            // Parse and validate the response using shared utility
+            return parse_validate_and_create_output(raw_response, usage, provider);
+        }
+    }
+
+    error!("No text content in {provider} response");
+    Err((
+        RStructorError::api_error(
+            provider,
+            ApiErrorKind::UnexpectedResponse {
+                details: "No text content in response".to_string(),
+            },
+        ),
+        None,
+    ))
+}
+
+/// The text of a `generateContent` reply to a plain generation request.
+pub(super) fn text_reply(
+    completion: GenerateContentResponse,
+    model: &str,
+    provider: &'static str,
+) -> Result<GenerateResult> {
+    if completion.candidates.is_empty() {
+        error!("{provider} API returned empty candidates array");
+        return Err(RStructorError::api_error(
+            provider,
+            ApiErrorKind::UnexpectedResponse {
+                details: "No completion candidates returned".to_string(),
+            },
+        ));
+    }
+
+    let usage = reply_usage(&completion, model);
+
+    let candidate = &completion.candidates[0];
+    trace!(finish_reason = %candidate.finish_reason, "Completion finish reason");
+
+    // Extract the text content
+    match candidate
+        .content
+        .parts
+        .first()
+        .and_then(|p| p.text.as_ref())
+    {
+        Some(text) => {
+            debug!(
+                content_len = text.len(),
+                "Successfully extracted text content from response"
+            );
+            Ok(GenerateResult::new(text.clone(), usage))
+        }
+        None => {
+            error!("No text content in {provider} response");
+            Err(RStructorError::api_error(
+                provider,
+                ApiErrorKind::UnexpectedResponse {
+                    details: "No text content in response".to_string(),
+                },
+            ))
+        }
+    }
+}
+
 impl GeminiClient {
     /// Create a new Gemini client with the provided API key.
     ///
@@ -280,11 +509,20 @@ impl GeminiClient {
             max_tokens: None,
             timeout: Some(DEFAULT_REQUEST_TIMEOUT), // Default: 5-minute request timeout
             max_retries: Some(3),                   // Default: 3 retries with error feedback
-            base_url: None,                         // Default: use official Gemini API
+            capture_unknown_fields: false,
+            explain: false,
+            context_overflow: ContextOverflow::default(),
+            system_prompt: None,
+            schema_locale: None,
+            description_catalog: None,
+            rate_limiter: None,
+            usage_tracker: UsageTracker::new(),
+            max_request_bytes: Some(MAX_REQUEST_BYTES),
+            base_url: None, // Default: use official Gemini API
             thinking_level: Some(ThinkingLevel::Low), // Default to Low thinking for Gemini 3.x
         };
 
-        let client = build_http_client(DEFAULT_REQUEST_TIMEOUT);
+        let client = HttpClientCell::default();
 
         info!(
             model = %config.model.as_str(),
@@ -292,7 +530,10 @@ impl GeminiClient {
             "Created Gemini client"
         );
 
-        Ok(Self { config, client })
+        Ok(Self {
+            config: Arc::new(config),
+            client,
+        })
     }
 
     /// Create a new Gemini client by reading the API key from the `GEMINI_API_KEY` environment variable.
@@ -322,24 +563,42 @@ impl GeminiClient {
             max_tokens: None,
             timeout: Some(DEFAULT_REQUEST_TIMEOUT), // Default: 5-minute request timeout
             max_retries: Some(3),                   // Default: 3 retries with error feedback
-            base_url: None,                         // Default: use official Gemini API
+            capture_unknown_fields: false,
+            explain: false,
+            context_overflow: ContextOverflow::default(),
+            system_prompt: None,
+            schema_locale: None,
+            description_catalog: None,
+            rate_limiter: None,
+            usage_tracker: UsageTracker::new(),
+            max_request_bytes: Some(MAX_REQUEST_BYTES),
+            base_url: None, // Default: use official Gemini API
             thinking_level: Some(ThinkingLevel::Low), // Default to Low thinking for Gemini 3.x
         };
 
-        let client = build_http_client(DEFAULT_REQUEST_TIMEOUT);
+        let client = HttpClientCell::default();
 
         info!(
             model = %config.model.as_str(),
             "Created Gemini client from environment variable"
         );
 
-        Ok(Self { config, client })
+        Ok(Self {
+            config: Arc::new(config),
+            client,
+        })
     }
 
     // Builder methods are generated by the macro below
 }
 
 impl GeminiClient {
+    /// The configured system prompt as a `systemInstruction`.
+    #[cfg(feature = "streaming")]
+    fn system_instruction(&self) -> Option<Content> {
+        self.config.system_prompt.clone().map(system_content)
+    }
+
     /// Internal implementation of materialize (without retry logic)
     /// Accepts conversation history for multi-turn interactions.
     /// Returns the data, raw response, and optional usage info.
@@ -361,32 +620,26 @@ impl GeminiClient {
     {
         info!("Generating structured response with Gemini");
 
-        let schema = T::schema();
+        let schema = self.output_schema::<T>();
         let schema_name = T::schema_name().unwrap_or_else(|| "output".to_string());
         trace!(schema_name = schema_name, "Retrieved JSON schema for type");
 
         // Build API contents from conversation history
         // With native response_schema, we don't need to include schema instructions in the prompt
-        let contents = chat_messages_to_contents(messages);
+        let (system_instruction, contents) =
+            conversation_contents(self.config.system_prompt.as_deref(), messages);
 
         // Build thinking config only for Gemini 3.x models
-        let is_gemini3 = self.config.model.as_str().starts_with("gemini-3");
-        let thinking_config = if is_gemini3 {
-            self.config.thinking_level.and_then(|level| {
-                level.gemini_level().map(|l| ThinkingConfig {
-                    thinking_level: l.to_string(),
-                })
-            })
-        } else {
-            None
-        };
+        let thinking_config =
+            thinking_config(self.capabilities().thinking, self.config.thinking_level);
 
         // Extract adjacently tagged enum info before transformation (for response conversion)
         let adjacently_tagged_info =
-            crate::backend::utils::extract_adjacently_tagged_info(&schema.to_json());
+            crate::backend::utils::extract_adjacently_tagged_info(&schema.inline().to_json());
 
         // Prepare schema for Gemini by stripping unsupported keywords (examples, additionalProperties, etc.)
-        let gemini_schema = crate::backend::utils::prepare_gemini_schema(&schema);
+        let gemini_schema =
+            crate::backend::utils::prepare_gemini_schema(&schema).map_err(|e| (e, None))?;
         let generation_config = GenerationConfig {
             temperature: self.config.temperature,
             max_output_tokens: self.config.max_tokens,
@@ -396,6 +649,7 @@ impl GeminiClient {
         };
 
         let request = GenerateContentRequest {
+            system_instruction,
             contents,
             generation_config,
         };
@@ -408,7 +662,7 @@ impl GeminiClient {
         let url = format!(
             "{}/models/{}:generateContent",
             base_url,
-            self.config.model.as_str()
+            model_override().unwrap_or_else(|| self.config.model.as_str().to_string())
         );
         debug!(
             url = %url,
@@ -416,15 +670,18 @@ impl GeminiClient {
             history_len = messages.len(),
             "Sending request to Gemini API"
         );
-        let response = self
-            .client
-            .post(&url)
-            .query(&[("key", &self.config.api_key)])
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| (handle_http_error(e, "Gemini"), None))?;
+        let response = send_limited(
+            "Gemini",
+            self.config.rate_limiter.as_deref(),
+            self.config.max_request_bytes,
+            self.http()
+                .post(&url)
+                .query(&[("key", self.api_key_for_call())])
+                .header("Content-Type", "application/json")
+                .json(&request),
+        )
+        .await
+        .map_err(|e| (e, None))?;
 
         let response = check_response_status(response, "Gemini")
             .await
@@ -436,71 +693,12 @@ impl GeminiClient {
             (RStructorError::from(e), None)
         })?;
 
-        if completion.candidates.is_empty() {
-            error!("Gemini API returned empty candidates array");
-            return Err((
-                RStructorError::api_error(
-                    "Gemini",
-                    ApiErrorKind::UnexpectedResponse {
-                        details: "No completion candidates returned".to_string(),
-                    },
-                ),
-                None,
-            ));
-        }
-
-        // Extract usage info
-        let model_name = completion
-            .model_version
-            .clone()
-            .unwrap_or_else(|| self.config.model.as_str().to_string());
-        let usage = completion.usage_metadata.as_ref().map(|u| {
-            TokenUsage::new(
-                model_name.clone(),
-                u.prompt_token_count,
-                u.candidates_token_count,
-            )
-        });
-
-        let candidate = &completion.candidates[0];
-        trace!(finish_reason = ?candidate.finish_reason, "Completion finish reason");
-
-        let parts = &candidate.content.parts;
-        debug!(parts = parts.len(), "Processing candidate content parts");
-        for part in parts {
-            if let Some(text) = &part.text {
-                let mut raw_response = text.clone();
-                debug!(content_len = raw_response.len(), "Processing text part");
-                // With native response_schema, the response is guaranteed to be valid JSON
-                trace!(json = %raw_response, "Parsing structured output response");
-
-                // Transform internally tagged enums back to adjacently tagged format if needed
-                if let Some(ref enum_info) = adjacently_tagged_info
-                    && let Ok(mut json_value) =
-                        serde_json::from_str::<serde_json::Value>(&raw_response)
-                {
-                    crate::backend::utils::transform_internally_to_adjacently_tagged(
-                        &mut json_value,
-                        enum_info,
-                    );
-                    raw_response = serde_json::to_string(&json_value).unwrap_or(raw_response);
-                }
-
-                // Parse and validate the response using shared utility
-                return parse_validate_and_create_output(raw_response, usage);
-            }
-        }
-
-        error!("No text content in Gemini response");
-        Err((
-            RStructorError::api_error(
-                "Gemini",
-                ApiErrorKind::UnexpectedResponse {
-                    details: "No text content in response".to_string(),
-                },
-            ),
-            None,
-        ))
+        structured_reply(
+            completion,
+            self.config.model.as_str(),
+            adjacently_tagged_info.as_ref(),
+            "Gemini",
+        )
     }
 
     /// Internal implementation of raw text generation (no structured output).
@@ -512,21 +710,16 @@ impl GeminiClient {
         info!("Generating raw text response with Gemini");
 
         // Build thinking config only for Gemini 3.x models
-        let is_gemini3 = self.config.model.as_str().starts_with("gemini-3");
-        let thinking_config = if is_gemini3 {
-            self.config.thinking_level.and_then(|level| {
-                level.gemini_level().map(|l| ThinkingConfig {
-                    thinking_level: l.to_string(),
-                })
-            })
-        } else {
-            None
-        };
+        let thinking_config =
+            thinking_config(self.capabilities().thinking, self.config.thinking_level);
 
         // Build the request, including any attached media parts
         debug!("Building Gemini API request");
+        let (system_instruction, contents) =
+            conversation_contents(self.config.system_prompt.as_deref(), messages);
         let request = GenerateContentRequest {
-            contents: chat_messages_to_contents(messages),
+            system_instruction,
+            contents,
             generation_config: GenerationConfig {
                 temperature: self.config.temperature,
                 max_output_tokens: self.config.max_tokens,
@@ -552,15 +745,17 @@ impl GeminiClient {
             model = %self.config.model.as_str(),
             "Sending request to Gemini API"
         );
-        let response = self
-            .client
-            .post(&url)
-            .query(&[("key", &self.config.api_key)])
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| handle_http_error(e, "Gemini"))?;
+        let response = send_limited(
+            "Gemini",
+            self.config.rate_limiter.as_deref(),
+            self.config.max_request_bytes,
+            self.http()
+                .post(&url)
+                .query(&[("key", self.api_key_for_call())])
+                .header("Content-Type", "application/json")
+                .json(&request),
+        )
+        .await?;
 
         // Parse the response
         let response = check_response_status(response, "Gemini").await?;
@@ -571,53 +766,7 @@ impl GeminiClient {
             e
         })?;
 
-        if completion.candidates.is_empty() {
-            error!("Gemini API returned empty candidates array");
-            return Err(RStructorError::api_error(
-                "Gemini",
-                ApiErrorKind::UnexpectedResponse {
-                    details: "No completion candidates returned".to_string(),
-                },
-            ));
-        }
-
-        // Extract usage info
-        let model_name = completion
-            .model_version
-            .clone()
-            .unwrap_or_else(|| self.config.model.as_str().to_string());
-        let usage = completion
-            .usage_metadata
-            .as_ref()
-            .map(|u| TokenUsage::new(model_name, u.prompt_token_count, u.candidates_token_count));
-
-        let candidate = &completion.candidates[0];
-        trace!(finish_reason = %candidate.finish_reason, "Completion finish reason");
-
-        // Extract the text content
-        match candidate
-            .content
-            .parts
-            .first()
-            .and_then(|p| p.text.as_ref())
-        {
-            Some(text) => {
-                debug!(
-                    content_len = text.len(),
-                    "Successfully extracted text content from response"
-                );
-                Ok(GenerateResult::new(text.clone(), usage))
-            }
-            None => {
-                error!("No text content in Gemini response");
-                Err(RStructorError::api_error(
-                    "Gemini",
-                    ApiErrorKind::UnexpectedResponse {
-                        details: "No text content in response".to_string(),
-                    },
-                ))
-            }
-        }
+        text_reply(completion, self.config.model.as_str(), "Gemini")
     }
 }
 
@@ -626,7 +775,8 @@ crate::impl_client_builder_methods! {
     client_type: GeminiClient,
     config_type: GeminiConfig,
     model_type: Model,
-    provider_name: "Gemini"
+    provider_name: "Gemini",
+    max_temperature: 2.0
 }
 
 impl GeminiClient {
@@ -643,7 +793,7 @@ impl GeminiClient {
             new_base_url = %base_url_str,
             "Setting custom base URL"
         );
-        self.config.base_url = Some(base_url_str);
+        Arc::make_mut(&mut self.config).base_url = Some(base_url_str);
         self
     }
 
@@ -682,7 +832,7 @@ impl GeminiClient {
             new_level = ?level,
             "Setting thinking level"
         );
-        self.config.thinking_level = Some(level);
+        Arc::make_mut(&mut self.config).thinking_level = Some(level);
         self
     }
 }
@@ -693,17 +843,10 @@ impl GeminiClient {
     /// structured-output `response_schema`. (Gemini streams via the
     /// `:streamGenerateContent?alt=sse` endpoint; the body itself is unchanged.)
     fn stream_body(&self, prompt: &str, response_schema: Option<Value>) -> Value {
-        let is_gemini3 = self.config.model.as_str().starts_with("gemini-3");
-        let thinking_config = if is_gemini3 {
-            self.config.thinking_level.and_then(|level| {
-                level.gemini_level().map(|l| ThinkingConfig {
-                    thinking_level: l.to_string(),
-                })
-            })
-        } else {
-            None
-        };
+        let thinking_config =
+            thinking_config(self.capabilities().thinking, self.config.thinking_level);
         let request = GenerateContentRequest {
+            system_instruction: self.system_instruction(),
             contents: vec![Content {
                 role: Some("user".to_string()),
                 parts: vec![Part::Text {
@@ -728,24 +871,29 @@ impl GeminiClient {
         &self,
         body: Value,
     ) -> impl std::future::Future<Output = Result<reqwest::Response>> + Send + 'static {
-        let client = self.client.clone();
-        let api_key = self.config.api_key.clone();
+        let client = self.http().clone();
+        let api_key = self.api_key_for_call();
         let base_url = self
             .config
             .base_url
             .clone()
             .unwrap_or_else(|| "https://generativelanguage.googleapis.com/v1beta".to_string());
         let model = self.config.model.as_str().to_string();
+        let rate_limiter = self.config.rate_limiter.clone();
+        let max_request_bytes = self.config.max_request_bytes;
         async move {
             let url = format!("{}/models/{}:streamGenerateContent", base_url, model);
-            let resp = client
-                .post(&url)
-                .query(&[("alt", "sse"), ("key", api_key.as_str())])
-                .header("Content-Type", "application/json")
-                .json(&body)
-                .send()
-                .await
-                .map_err(|e| handle_http_error(e, "Gemini"))?;
+            let resp = send_limited(
+                "Gemini",
+                rate_limiter.as_deref(),
+                max_request_bytes,
+                client
+                    .post(&url)
+                    .query(&[("alt", "sse"), ("key", api_key.as_str())])
+                    .header("Content-Type", "application/json")
+                    .json(&body),
+            )
+            .await?;
             check_response_status(resp, "Gemini").await
         }
     }
@@ -762,19 +910,23 @@ impl crate::backend::tools::ToolRunner for GeminiClient {
         toolbox: &crate::backend::tools::Toolbox,
         max_iterations: usize,
     ) -> Result<String> {
+        let system =
+            crate::backend::tools::tool_run_system(self.config.system_prompt.as_deref(), system);
         let base_url = self
             .config
             .base_url
             .as_deref()
             .unwrap_or("https://generativelanguage.googleapis.com/v1beta");
         crate::backend::tools::run_gemini_tools(
-            &self.client,
+            self.http(),
             base_url,
-            &self.config.api_key,
+            &self.api_key_for_call(),
+            self.config.rate_limiter.as_deref(),
+            self.config.max_request_bytes,
             self.config.model.as_str(),
             self.config.temperature,
             self.config.max_tokens,
-            system,
+            system.as_deref(),
             prompt,
             media,
             toolbox,
@@ -803,15 +955,19 @@ impl LLMClient for GeminiClient {
     where
         T: Instructor + DeserializeOwned + Send + 'static,
     {
-        let output = generate_with_retry_with_history(
-            |messages: Vec<ChatMessage>| {
-                let this = self;
-                async move { this.materialize_internal::<T>(&messages).await }
-            },
-            prompt,
-            self.config.max_retries,
-        )
-        .await?;
+        let output = self
+            .config
+            .usage_tracker
+            .scope(generate_with_retry_with_history(
+                |messages: Vec<ChatMessage>| {
+                    let this = self;
+                    async move { this.materialize_internal::<T>(&messages).await }
+                },
+                prompt,
+                self.config.max_retries,
+                &self.config.context_overflow,
+            ))
+            .await?;
         Ok(output.data)
     }
 
@@ -829,16 +985,19 @@ impl LLMClient for GeminiClient {
     where
         T: Instructor + DeserializeOwned + Send + 'static,
     {
-        materialize_with_media_with_retry(
-            |messages: Vec<ChatMessage>| {
-                let this = self;
-                async move { this.materialize_internal::<T>(&messages).await }
-            },
-            prompt,
-            media,
-            self.config.max_retries,
-        )
-        .await
+        self.config
+            .usage_tracker
+            .scope(materialize_with_media_with_retry(
+                |messages: Vec<ChatMessage>| {
+                    let this = self;
+                    async move { this.materialize_internal::<T>(&messages).await }
+                },
+                prompt,
+                media,
+                self.config.max_retries,
+                &self.config.context_overflow,
+            ))
+            .await
     }
 
     #[instrument(
@@ -854,16 +1013,20 @@ impl LLMClient for GeminiClient {
     where
         T: Instructor + DeserializeOwned + Send + 'static,
     {
-        let output = generate_with_retry_with_history(
-            |messages: Vec<ChatMessage>| {
-                let this = self;
-                async move { this.materialize_internal::<T>(&messages).await }
-            },
-            prompt,
-            self.config.max_retries,
-        )
-        .await?;
-        Ok(MaterializeResult::new(output.data, output.usage))
+        let output = self
+            .config
+            .usage_tracker
+            .scope(generate_with_retry_with_history(
+                |messages: Vec<ChatMessage>| {
+                    let this = self;
+                    async move { this.materialize_internal::<T>(&messages).await }
+                },
+                prompt,
+                self.config.max_retries,
+                &self.config.context_overflow,
+            ))
+            .await?;
+        Ok(output.into_result(self.config.capture_unknown_fields))
     }
 
     #[instrument(
@@ -911,6 +1074,47 @@ impl LLMClient for GeminiClient {
         self.generate_internal(&[ChatMessage::user(prompt)]).await
     }
 
+    #[instrument(
+        name = "gemini_materialize_conversation",
+        skip(self, messages),
+        fields(
+            type_name = std::any::type_name::<T>(),
+            model = %self.config.model.as_str(),
+            history_len = messages.len()
+        )
+    )]
+    async fn materialize_conversation<T>(
+        &self,
+        messages: &[ChatMessage],
+    ) -> Result<MaterializeResult<T>>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        let output = self
+            .config
+            .usage_tracker
+            .scope(generate_with_retry_with_conversation(
+                |messages: Vec<ChatMessage>| {
+                    let this = self;
+                    async move { this.materialize_internal::<T>(&messages).await }
+                },
+                messages,
+                self.config.max_retries,
+                &self.config.context_overflow,
+            ))
+            .await?;
+        Ok(output.into_result(self.config.capture_unknown_fields))
+    }
+
+    #[instrument(
+        name = "gemini_generate_conversation",
+        skip(self, messages),
+        fields(model = %self.config.model.as_str(), history_len = messages.len())
+    )]
+    async fn generate_conversation(&self, messages: &[ChatMessage]) -> Result<GenerateResult> {
+        self.generate_internal(messages).await
+    }
+
     #[cfg(feature = "streaming")]
     fn generate_stream<'a>(&'a self, prompt: &'a str) -> crate::backend::streaming::TextStream<'a>
     where
@@ -932,12 +1136,15 @@ impl LLMClient for GeminiClient {
         T: Instructor + DeserializeOwned + Send + 'static,
         Self: Sync,
     {
-        let schema = T::schema();
+        let schema = self.localized_schema::<T>();
         // Gemini may return internally-tagged enums; capture the mapping so the
         // final buffer can be transformed back before deserializing into `T`.
         let adjacently_tagged_info =
-            crate::backend::utils::extract_adjacently_tagged_info(&schema.to_json());
-        let gemini_schema = crate::backend::utils::prepare_gemini_schema(&schema);
+            crate::backend::utils::extract_adjacently_tagged_info(&schema.inline().to_json());
+        let gemini_schema = match crate::backend::utils::prepare_gemini_schema(&schema) {
+            Ok(gemini_schema) => gemini_schema,
+            Err(e) => return Box::pin(futures_util::stream::once(async move { Err(e) })),
+        };
         let body = self.stream_body(prompt, Some(gemini_schema));
 
         let finalize = move |raw: &str| -> Result<T> {
@@ -948,7 +1155,7 @@ impl LLMClient for GeminiClient {
                 crate::backend::utils::transform_internally_to_adjacently_tagged(&mut value, info);
                 json = serde_json::to_string(&value).unwrap_or(json);
             }
-            crate::backend::utils::parse_and_validate_response::<T>(&json).map_err(|(e, _)| e)
+            crate::backend::reply::parse_and_validate_response::<T>(&json).map_err(|(e, _)| e)
         };
 
         crate::backend::streaming::object_stream_with(
@@ -967,10 +1174,13 @@ impl LLMClient for GeminiClient {
         T: Instructor + DeserializeOwned + Send + 'static,
         Self: Sync,
     {
-        let schema = T::schema();
+        let schema = self.localized_schema::<T>();
         let adjacently_tagged_info =
-            crate::backend::utils::extract_adjacently_tagged_info(&schema.to_json());
-        let item_schema = crate::backend::utils::prepare_gemini_schema(&schema);
+            crate::backend::utils::extract_adjacently_tagged_info(&schema.inline().to_json());
+        let item_schema = match crate::backend::utils::prepare_gemini_schema(&schema) {
+            Ok(item_schema) => item_schema,
+            Err(e) => return Box::pin(futures_util::stream::once(async move { Err(e) })),
+        };
         let wrapper = crate::backend::streaming::array_wrapper_schema(item_schema, false);
         let body = self.stream_body(prompt, Some(wrapper));
 
@@ -1002,17 +1212,19 @@ impl LLMClient for GeminiClient {
             .base_url
             .as_deref()
             .unwrap_or("https://generativelanguage.googleapis.com/v1beta");
-        let url = format!("{}/models?key={}", base_url, self.config.api_key);
+        let url = format!("{}/models?key={}", base_url, self.api_key_for_call());
 
         debug!("Fetching available models from Gemini");
 
-        let response = self
-            .client
-            .get(&url)
-            .header("Content-Type", "application/json")
-            .send()
-            .await
-            .map_err(|e| handle_http_error(e, "Gemini"))?;
+        let response = send_limited(
+            "Gemini",
+            self.config.rate_limiter.as_deref(),
+            self.config.max_request_bytes,
+            self.http()
+                .get(&url)
+                .header("Content-Type", "application/json"),
+        )
+        .await?;
 
         let response = check_response_status(response, "Gemini").await?;
 
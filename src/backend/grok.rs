@@ -1,19 +1,21 @@
 use async_trait::async_trait;
 use serde::de::DeserializeOwned;
+use std::sync::Arc;
 use std::time::Duration;
 use tracing::{debug, error, info, instrument, trace, warn};
 
 use crate::backend::model_macro::define_model_enum;
+#[cfg(feature = "streaming")]
+use crate::backend::text_messages;
 use crate::backend::{
-    ChatMessage, DEFAULT_REQUEST_TIMEOUT, GenerateResult, LLMClient, MaterializeInternalOutput,
-    MaterializeResult, ModelInfo, OpenAICompatibleChatCompletionRequest,
-    OpenAICompatibleChatCompletionResponse, ResponseFormat, TokenUsage, ValidationFailureContext,
-    build_http_client, check_response_status, convert_openai_compatible_chat_messages,
-    generate_with_retry_with_history, handle_http_error, materialize_with_media_with_retry,
-    parse_validate_and_create_output, prepare_strict_schema,
+    ChatMessage, ContextOverflow, DEFAULT_REQUEST_TIMEOUT, GenerateResult, HttpClientCell,
+    LLMClient, MaterializeInternalOutput, MaterializeResult, ModelInfo,
+    OpenAICompatibleChatCompletionRequest, OpenAICompatibleChatCompletionResponse, RateLimiter,
+    ResponseFormat, TokenUsage, UsageTracker, ValidationFailureContext, check_response_status,
+    convert_openai_compatible_chat_messages, generate_with_retry_with_conversation,
+    generate_with_retry_with_history, materialize_with_media_with_retry, model_override,
+    parse_validate_and_create_output, prepare_strict_schema, send_limited, with_idempotency_key,
 };
-#[cfg(feature = "streaming")]
-use crate::backend::{OpenAICompatibleChatMessage, OpenAICompatibleMessageContent};
 use crate::error::{ApiErrorKind, RStructorError, Result};
 use crate::model::Instructor;
 
@@ -55,6 +57,18 @@ define_model_enum! {
     }
 }
 
+impl From<&Model> for crate::backend::ModelId {
+    fn from(model: &Model) -> Self {
+        crate::backend::ModelId::new(crate::backend::Provider::Grok, model.as_str())
+    }
+}
+
+impl From<crate::backend::ModelTier> for Model {
+    fn from(tier: crate::backend::ModelTier) -> Self {
+        Model::from_string(tier.resolve(crate::backend::Provider::Grok).name)
+    }
+}
+
 /// Configuration for the Grok client
 #[derive(Debug, Clone)]
 pub struct GrokConfig {
@@ -66,16 +80,48 @@ pub struct GrokConfig {
     /// Defaults to [`DEFAULT_REQUEST_TIMEOUT`](crate::DEFAULT_REQUEST_TIMEOUT) (5 minutes).
     pub timeout: Option<Duration>,
     pub max_retries: Option<usize>,
+    /// Collect fields the model returned but the schema does not declare into
+    /// [`MaterializeResult::extra_fields`](crate::MaterializeResult::extra_fields).
+    pub capture_unknown_fields: bool,
+    /// Ask for a rationale in a generated
+    /// [`EXPLANATION_FIELD`](crate::EXPLANATION_FIELD) property, returned in
+    /// [`MaterializeResult::explanation`](crate::MaterializeResult::explanation).
+    pub explain: bool,
+    /// What `materialize` does when the prompt exceeds the context window.
+    pub context_overflow: ContextOverflow,
+    /// System prompt sent with every request, as the provider's system
+    /// instruction rather than as part of the user prompt.
+    pub system_prompt: Option<String>,
+    /// Locale the schema's descriptions are sent in; see
+    /// [`Schema::localized`](crate::Schema::localized).
+    pub schema_locale: Option<String>,
+    /// Translations consulted before those recorded by the derive.
+    pub description_catalog: Option<Arc<crate::schema::DescriptionCatalog>>,
+    /// Request and token budgets this client's requests wait for, possibly
+    /// shared with other clients.
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    /// Counts the requests and tokens of this client's structured calls,
+    /// possibly shared with other clients.
+    pub usage_tracker: UsageTracker,
+    /// Largest request body this client sends; bigger requests fail with
+    /// [`RStructorError::RequestTooLarge`] before anything is uploaded.
+    /// `None` (the default: xAI publishes no limit) sends any size.
+    pub max_request_bytes: Option<usize>,
+    /// How the tool loop offers tools to the model.
+    #[cfg(feature = "tools")]
+    pub tool_mode: crate::backend::tools::ToolMode,
     /// Custom base URL for Grok-compatible APIs (e.g., local LLMs, proxy endpoints)
     /// Defaults to "https://api.x.ai/v1" if not set
     pub base_url: Option<String>,
 }
 
-/// Grok client for generating completions
+/// Grok client for generating completions.
+///
+/// Cheap to clone and `Send + Sync`; see [sharing clients](crate::LLMClient#sharing-clients).
 #[derive(Clone)]
 pub struct GrokClient {
-    config: GrokConfig,
-    client: reqwest::Client,
+    config: Arc<GrokConfig>,
+    client: HttpClientCell,
 }
 
 // Grok uses shared OpenAI-compatible chat completion request/response types.
@@ -116,13 +162,24 @@ impl GrokClient {
             max_tokens: None,
             timeout: Some(DEFAULT_REQUEST_TIMEOUT), // Default: 5-minute request timeout
             max_retries: Some(3),                   // Default: 3 retries with error feedback
-            base_url: None,                         // Default: use official Grok API
+            capture_unknown_fields: false,
+            explain: false,
+            context_overflow: ContextOverflow::default(),
+            system_prompt: None,
+            schema_locale: None,
+            description_catalog: None,
+            rate_limiter: None,
+            usage_tracker: UsageTracker::new(),
+            max_request_bytes: None,
+            #[cfg(feature = "tools")]
+            tool_mode: crate::backend::tools::ToolMode::Tools,
+            base_url: None, // Default: use official Grok API
         };
 
         debug!("Grok client created with default configuration");
         Ok(Self {
-            config,
-            client: build_http_client(DEFAULT_REQUEST_TIMEOUT),
+            config: Arc::new(config),
+            client: HttpClientCell::default(),
         })
     }
 
@@ -156,13 +213,24 @@ impl GrokClient {
             max_tokens: None,
             timeout: Some(DEFAULT_REQUEST_TIMEOUT), // Default: 5-minute request timeout
             max_retries: Some(3),                   // Default: 3 retries with error feedback
-            base_url: None,                         // Default: use official Grok API
+            capture_unknown_fields: false,
+            explain: false,
+            context_overflow: ContextOverflow::default(),
+            system_prompt: None,
+            schema_locale: None,
+            description_catalog: None,
+            rate_limiter: None,
+            usage_tracker: UsageTracker::new(),
+            max_request_bytes: None,
+            #[cfg(feature = "tools")]
+            tool_mode: crate::backend::tools::ToolMode::Tools,
+            base_url: None, // Default: use official Grok API
         };
 
         debug!("Grok client created with default configuration");
         Ok(Self {
-            config,
-            client: build_http_client(DEFAULT_REQUEST_TIMEOUT),
+            config: Arc::new(config),
+            client: HttpClientCell::default(),
         })
     }
 
@@ -192,7 +260,7 @@ impl GrokClient {
         info!("Generating structured response with Grok (native structured outputs)");
 
         // Get the schema for type T
-        let schema = T::schema();
+        let schema = self.output_schema::<T>();
         let schema_name = T::schema_name().unwrap_or_else(|| "output".to_string());
         trace!(schema_name = schema_name, "Retrieved JSON schema for type");
 
@@ -201,8 +269,12 @@ impl GrokClient {
 
         // Build API messages from conversation history
         // With native structured outputs, we don't need to include schema instructions in the prompt
-        let api_messages =
-            convert_openai_compatible_chat_messages(messages, "Grok").map_err(|e| (e, None))?;
+        let api_messages = convert_openai_compatible_chat_messages(
+            messages,
+            "Grok",
+            self.config.system_prompt.as_deref(),
+        )
+        .map_err(|e| (e, None))?;
 
         // Create response format for native structured outputs
         let response_format = ResponseFormat::json_schema(schema_name.clone(), schema_json, None);
@@ -212,7 +284,7 @@ impl GrokClient {
             api_messages.len()
         );
         let request = OpenAICompatibleChatCompletionRequest {
-            model: self.config.model.as_str().to_string(),
+            model: model_override().unwrap_or_else(|| self.config.model.as_str().to_string()),
             messages: api_messages,
             response_format: Some(response_format),
             temperature: self.config.temperature,
@@ -227,15 +299,20 @@ impl GrokClient {
             .unwrap_or("https://api.x.ai/v1");
         let url = format!("{}/chat/completions", base_url);
         debug!(url = %url, "Sending request to Grok API with structured outputs");
-        let response = self
-            .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.config.api_key))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| (handle_http_error(e, "Grok"), None))?;
+        let response = send_limited(
+            "Grok",
+            self.config.rate_limiter.as_deref(),
+            self.config.max_request_bytes,
+            with_idempotency_key(self.http().post(&url))
+                .header(
+                    "Authorization",
+                    format!("Bearer {}", self.api_key_for_call()),
+                )
+                .header("Content-Type", "application/json")
+                .json(&request),
+        )
+        .await
+        .map_err(|e| (e, None))?;
 
         let response = check_response_status(response, "Grok")
             .await
@@ -284,7 +361,7 @@ impl GrokClient {
 
             // Parse and validate the response using shared utility
             trace!(json = %raw_response, "Parsing structured output response");
-            parse_validate_and_create_output(raw_response, usage)
+            parse_validate_and_create_output(raw_response, usage, "Grok")
         } else {
             error!("No content in Grok API response");
             Err((
@@ -312,7 +389,11 @@ impl GrokClient {
         debug!("Building Grok API request for text generation");
         let request = OpenAICompatibleChatCompletionRequest {
             model: self.config.model.as_str().to_string(),
-            messages: convert_openai_compatible_chat_messages(messages, "Grok")?,
+            messages: convert_openai_compatible_chat_messages(
+                messages,
+                "Grok",
+                self.config.system_prompt.as_deref(),
+            )?,
             response_format: None,
             temperature: self.config.temperature,
             max_tokens: self.config.max_tokens,
@@ -327,15 +408,20 @@ impl GrokClient {
             .unwrap_or("https://api.x.ai/v1");
         let url = format!("{}/chat/completions", base_url);
         debug!(url = %url, "Sending request to Grok API");
-        let response = self
-            .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.config.api_key))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| handle_http_error(e, "Grok"))?;
+        let response = send_limited(
+            "Grok",
+            self.config.rate_limiter.as_deref(),
+            self.config.max_request_bytes,
+            self.http()
+                .post(&url)
+                .header(
+                    "Authorization",
+                    format!("Bearer {}", self.api_key_for_call()),
+                )
+                .header("Content-Type", "application/json")
+                .json(&request),
+        )
+        .await?;
 
         // Parse the response
         let response = check_response_status(response, "Grok").await?;
@@ -393,7 +479,8 @@ crate::impl_client_builder_methods! {
     client_type: GrokClient,
     config_type: GrokConfig,
     model_type: Model,
-    provider_name: "Grok"
+    provider_name: "Grok",
+    max_temperature: 2.0
 }
 
 impl GrokClient {
@@ -410,7 +497,7 @@ impl GrokClient {
             new_base_url = %base_url_str,
             "Setting custom base URL"
         );
-        self.config.base_url = Some(base_url_str);
+        Arc::make_mut(&mut self.config).base_url = Some(base_url_str);
         self
     }
 }
@@ -426,10 +513,7 @@ impl GrokClient {
     ) -> serde_json::Value {
         let request = OpenAICompatibleChatCompletionRequest {
             model: self.config.model.as_str().to_string(),
-            messages: vec![OpenAICompatibleChatMessage {
-                role: "user".to_string(),
-                content: OpenAICompatibleMessageContent::Text(prompt.to_string()),
-            }],
+            messages: text_messages(self.config.system_prompt.as_deref(), prompt),
             response_format,
             temperature: self.config.temperature,
             max_tokens: self.config.max_tokens,
@@ -445,28 +529,59 @@ impl GrokClient {
         &self,
         body: serde_json::Value,
     ) -> impl std::future::Future<Output = Result<reqwest::Response>> + Send + 'static {
-        let client = self.client.clone();
-        let api_key = self.config.api_key.clone();
+        let client = self.http().clone();
+        let api_key = self.api_key_for_call();
         let base_url = self
             .config
             .base_url
             .clone()
             .unwrap_or_else(|| "https://api.x.ai/v1".to_string());
+        let rate_limiter = self.config.rate_limiter.clone();
+        let max_request_bytes = self.config.max_request_bytes;
         async move {
             let url = format!("{}/chat/completions", base_url);
-            let resp = client
-                .post(&url)
-                .header("Authorization", format!("Bearer {api_key}"))
-                .header("Content-Type", "application/json")
-                .json(&body)
-                .send()
-                .await
-                .map_err(|e| handle_http_error(e, "Grok"))?;
+            let resp = send_limited(
+                "Grok",
+                rate_limiter.as_deref(),
+                max_request_bytes,
+                client
+                    .post(&url)
+                    .header("Authorization", format!("Bearer {api_key}"))
+                    .header("Content-Type", "application/json")
+                    .json(&body),
+            )
+            .await?;
             check_response_status(resp, "Grok").await
         }
     }
 }
 
+#[cfg(feature = "tools")]
+impl GrokClient {
+    /// Choose how `with_tools(..).run(..)` offers tools to the model.
+    ///
+    /// The default, [`ToolMode::Tools`](crate::ToolMode::Tools), sends
+    /// `tools` and `tool_choice`. [`ToolMode::LegacyFunctions`](crate::ToolMode::LegacyFunctions)
+    /// sends the deprecated `functions` and `function_call` instead, for
+    /// older OpenAI-compatible endpoints that do not understand `tools`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rstructor::{GrokClient, ToolMode};
+    ///
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = GrokClient::new("api-key")?.tool_mode(ToolMode::LegacyFunctions);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[tracing::instrument(skip(self))]
+    pub fn tool_mode(mut self, mode: crate::backend::tools::ToolMode) -> Self {
+        Arc::make_mut(&mut self.config).tool_mode = mode;
+        self
+    }
+}
+
 #[cfg(feature = "tools")]
 #[async_trait]
 impl crate::backend::tools::ToolRunner for GrokClient {
@@ -478,6 +593,8 @@ impl crate::backend::tools::ToolRunner for GrokClient {
         toolbox: &crate::backend::tools::Toolbox,
         max_iterations: usize,
     ) -> Result<String> {
+        let system =
+            crate::backend::tools::tool_run_system(self.config.system_prompt.as_deref(), system);
         let base_url = self
             .config
             .base_url
@@ -486,15 +603,18 @@ impl crate::backend::tools::ToolRunner for GrokClient {
         let url = format!("{}/chat/completions", base_url);
 
         crate::backend::tools::run_openai_compatible_tools(
-            &self.client,
+            self.http(),
             &url,
-            &self.config.api_key,
+            &self.api_key_for_call(),
+            self.config.rate_limiter.as_deref(),
+            self.config.max_request_bytes,
+            self.config.tool_mode,
             "Grok",
             self.config.model.as_str(),
             self.config.temperature,
             self.config.max_tokens,
             None,
-            system,
+            system.as_deref(),
             prompt,
             media,
             toolbox,
@@ -523,15 +643,19 @@ impl LLMClient for GrokClient {
     where
         T: Instructor + DeserializeOwned + Send + 'static,
     {
-        let output = generate_with_retry_with_history(
-            |messages: Vec<ChatMessage>| {
-                let this = self;
-                async move { this.materialize_internal::<T>(&messages).await }
-            },
-            prompt,
-            self.config.max_retries,
-        )
-        .await?;
+        let output = self
+            .config
+            .usage_tracker
+            .scope(generate_with_retry_with_history(
+                |messages: Vec<ChatMessage>| {
+                    let this = self;
+                    async move { this.materialize_internal::<T>(&messages).await }
+                },
+                prompt,
+                self.config.max_retries,
+                &self.config.context_overflow,
+            ))
+            .await?;
         Ok(output.data)
     }
 
@@ -549,16 +673,19 @@ impl LLMClient for GrokClient {
     where
         T: Instructor + DeserializeOwned + Send + 'static,
     {
-        materialize_with_media_with_retry(
-            |messages: Vec<ChatMessage>| {
-                let this = self;
-                async move { this.materialize_internal::<T>(&messages).await }
-            },
-            prompt,
-            media,
-            self.config.max_retries,
-        )
-        .await
+        self.config
+            .usage_tracker
+            .scope(materialize_with_media_with_retry(
+                |messages: Vec<ChatMessage>| {
+                    let this = self;
+                    async move { this.materialize_internal::<T>(&messages).await }
+                },
+                prompt,
+                media,
+                self.config.max_retries,
+                &self.config.context_overflow,
+            ))
+            .await
     }
 
     #[instrument(
@@ -574,16 +701,20 @@ impl LLMClient for GrokClient {
     where
         T: Instructor + DeserializeOwned + Send + 'static,
     {
-        let output = generate_with_retry_with_history(
-            |messages: Vec<ChatMessage>| {
-                let this = self;
-                async move { this.materialize_internal::<T>(&messages).await }
-            },
-            prompt,
-            self.config.max_retries,
-        )
-        .await?;
-        Ok(MaterializeResult::new(output.data, output.usage))
+        let output = self
+            .config
+            .usage_tracker
+            .scope(generate_with_retry_with_history(
+                |messages: Vec<ChatMessage>| {
+                    let this = self;
+                    async move { this.materialize_internal::<T>(&messages).await }
+                },
+                prompt,
+                self.config.max_retries,
+                &self.config.context_overflow,
+            ))
+            .await?;
+        Ok(output.into_result(self.config.capture_unknown_fields))
     }
 
     #[instrument(
@@ -631,6 +762,47 @@ impl LLMClient for GrokClient {
         self.generate_internal(&[ChatMessage::user(prompt)]).await
     }
 
+    #[instrument(
+        name = "grok_materialize_conversation",
+        skip(self, messages),
+        fields(
+            type_name = std::any::type_name::<T>(),
+            model = %self.config.model.as_str(),
+            history_len = messages.len()
+        )
+    )]
+    async fn materialize_conversation<T>(
+        &self,
+        messages: &[ChatMessage],
+    ) -> Result<MaterializeResult<T>>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        let output = self
+            .config
+            .usage_tracker
+            .scope(generate_with_retry_with_conversation(
+                |messages: Vec<ChatMessage>| {
+                    let this = self;
+                    async move { this.materialize_internal::<T>(&messages).await }
+                },
+                messages,
+                self.config.max_retries,
+                &self.config.context_overflow,
+            ))
+            .await?;
+        Ok(output.into_result(self.config.capture_unknown_fields))
+    }
+
+    #[instrument(
+        name = "grok_generate_conversation",
+        skip(self, messages),
+        fields(model = %self.config.model.as_str(), history_len = messages.len())
+    )]
+    async fn generate_conversation(&self, messages: &[ChatMessage]) -> Result<GenerateResult> {
+        self.generate_internal(messages).await
+    }
+
     #[cfg(feature = "streaming")]
     fn generate_stream<'a>(&'a self, prompt: &'a str) -> crate::backend::streaming::TextStream<'a>
     where
@@ -652,7 +824,7 @@ impl LLMClient for GrokClient {
         T: Instructor + DeserializeOwned + Send + 'static,
         Self: Sync,
     {
-        let schema = T::schema();
+        let schema = self.localized_schema::<T>();
         let schema_name = T::schema_name().unwrap_or_else(|| "output".to_string());
         let schema_json = prepare_strict_schema(&schema);
         let response_format = ResponseFormat::json_schema(schema_name, schema_json, None);
@@ -672,7 +844,7 @@ impl LLMClient for GrokClient {
         T: Instructor + DeserializeOwned + Send + 'static,
         Self: Sync,
     {
-        let item_schema = prepare_strict_schema(&T::schema());
+        let item_schema = prepare_strict_schema(&self.localized_schema::<T>());
         let wrapper = crate::backend::streaming::array_wrapper_schema(item_schema, true);
         let response_format = ResponseFormat::json_schema("items".to_string(), wrapper, None);
         let body = self.stream_body(prompt, Some(response_format));
@@ -696,14 +868,19 @@ impl LLMClient for GrokClient {
 
         debug!(url = %url, "Fetching available models from Grok");
 
-        let response = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", self.config.api_key))
-            .header("Content-Type", "application/json")
-            .send()
-            .await
-            .map_err(|e| handle_http_error(e, "Grok"))?;
+        let response = send_limited(
+            "Grok",
+            self.config.rate_limiter.as_deref(),
+            self.config.max_request_bytes,
+            self.http()
+                .get(&url)
+                .header(
+                    "Authorization",
+                    format!("Bearer {}", self.api_key_for_call()),
+                )
+                .header("Content-Type", "application/json"),
+        )
+        .await?;
 
         let response = check_response_status(response, "Grok").await?;
 
@@ -758,4 +935,12 @@ mod tests {
             .timeout(Duration::from_secs(10));
         assert_eq!(client.config.timeout, Some(Duration::from_secs(10)));
     }
+
+    #[test]
+    fn request_size_is_unchecked_unless_a_limit_is_set() {
+        let client = GrokClient::new("test-key").unwrap();
+        assert_eq!(client.config.max_request_bytes, None);
+        let client = client.max_request_bytes(10_000_000);
+        assert_eq!(client.config.max_request_bytes, Some(10_000_000));
+    }
 }
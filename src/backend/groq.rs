@@ -0,0 +1,82 @@
+use crate::backend::ModelInfo;
+use crate::backend::model_macro::define_model_enum;
+use crate::backend::openai_hosted::define_hosted_client;
+
+define_model_enum! {
+    /// Models served by Groq
+    ///
+    /// For the latest available models and their identifiers, check the
+    /// [Groq Models Documentation](https://console.groq.com/docs/models).
+    /// Any other name can be given as a string or through the `Custom` variant:
+    ///
+    /// ```rust
+    /// use rstructor::GroqModel;
+    ///
+    /// let model = GroqModel::from_string("llama-3.3-70b-specdec");
+    /// assert_eq!(model, GroqModel::Custom("llama-3.3-70b-specdec".to_string()));
+    /// ```
+    pub enum Model {
+        /// GPT-OSS 120B (OpenAI's open-weight reasoning model)
+        GptOss120b => "openai/gpt-oss-120b",
+        /// GPT-OSS 20B (smaller open-weight reasoning model)
+        GptOss20b => "openai/gpt-oss-20b",
+        /// Llama 3.3 70B Versatile (general-purpose production model)
+        Llama3370bVersatile => "llama-3.3-70b-versatile",
+        /// Llama 3.1 8B Instant (fast, low-cost production model)
+        Llama318bInstant => "llama-3.1-8b-instant",
+        /// Llama 4 Maverick 17B 128E (multimodal preview model)
+        Llama4Maverick => "meta-llama/llama-4-maverick-17b-128e-instruct",
+        /// Llama 4 Scout 17B 16E (multimodal preview model)
+        Llama4Scout => "meta-llama/llama-4-scout-17b-16e-instruct",
+        /// Kimi K2 Instruct (Moonshot AI's mixture-of-experts preview model)
+        KimiK2 => "moonshotai/kimi-k2-instruct-0905",
+        /// Qwen3 32B (Alibaba's preview model)
+        Qwen332b => "qwen/qwen3-32b",
+    }
+}
+
+define_hosted_client! {
+    /// Groq client for generating completions.
+    ///
+    /// Groq serves open models behind an OpenAI-compatible API that rejects
+    /// the `json_schema` response format, so structured output is requested
+    /// in JSON mode with the schema in a system message. Reads
+    /// `GROQ_API_KEY` in [`from_env`](Self::from_env).
+    ///
+    /// Cheap to clone and `Send + Sync`; see [sharing clients](crate::LLMClient#sharing-clients).
+    ///
+    /// ```no_run
+    /// # use rstructor::{GroqClient, GroqModel};
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = GroqClient::from_env()?.model(GroqModel::Llama3370bVersatile);
+    /// # Ok(())
+    /// # }
+    /// ```
+    client: GroqClient,
+    model_type: Model,
+    default_model: Model::Llama3370bVersatile,
+    provider: Groq,
+    provider_name: "Groq",
+    api_key_env: "GROQ_API_KEY",
+    base_url: "https://api.groq.com/openai/v1",
+    model_info: chat_model,
+}
+
+/// A chat model from Groq's `/models` list; speech and guard models are skipped.
+fn chat_model(model: &serde_json::Value) -> Option<ModelInfo> {
+    let id = model.get("id")?.as_str()?;
+    if ["whisper", "tts", "guard"]
+        .iter()
+        .any(|kind| id.contains(kind))
+    {
+        return None;
+    }
+    Some(ModelInfo {
+        id: id.to_string(),
+        name: None,
+        description: model
+            .get("owned_by")
+            .and_then(|owner| owner.as_str())
+            .map(|owner| format!("Owned by {owner}")),
+    })
+}
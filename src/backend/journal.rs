@@ -0,0 +1,418 @@
+//! A write-ahead journal of provider requests, for reconciling spend.
+//!
+//! With a journal set, every provider request is appended to a JSON Lines
+//! file and synced to disk *before* it is sent, with its provider, model and
+//! estimated token count. Once the reply is in, a second line finalizes the
+//! request with the usage the provider reported. A process that crashes or is
+//! killed mid-request leaves a request that was sent but never finalized, and
+//! [`JournalEntry::billable_tokens`] falls back to its estimate, so the
+//! journal can still be reconciled against the provider's bill.
+//!
+//! ```no_run
+//! # fn main() -> std::io::Result<()> {
+//! use rstructor::{RequestJournal, set_request_journal};
+//!
+//! set_request_journal(RequestJournal::open("requests.journal")?);
+//!
+//! // After a restart, account for what the previous run spent:
+//! let entries = RequestJournal::read("requests.journal")?;
+//! let unfinished = entries.iter().filter(|e| !e.is_finished()).count();
+//! let tokens: u64 = entries.iter().map(|e| e.billable_tokens()).sum();
+//! println!("{tokens} tokens, {unfinished} requests interrupted");
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::future::Future;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::backend::TokenUsage;
+use crate::backend::rate_limit::request_cost;
+
+/// An append-only file recording each provider request before it is sent.
+///
+/// Clones share the same file. Install one process-wide with
+/// [`set_request_journal`].
+#[derive(Debug, Clone)]
+pub struct RequestJournal {
+    inner: Arc<JournalFile>,
+}
+
+#[derive(Debug)]
+struct JournalFile {
+    path: PathBuf,
+    file: Mutex<File>,
+    /// Prefix of this handle's request ids, unique per `open`.
+    run: String,
+    next: AtomicU64,
+}
+
+/// One request read back from a journal file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JournalEntry {
+    /// Unique id of the request within the journal.
+    pub id: String,
+    /// Provider name, as in error messages (e.g. `"OpenAI"`, `"Gemini"`).
+    pub provider: String,
+    /// The model requested, when it could be read from the request body or URL.
+    pub model: Option<String>,
+    /// Input tokens estimated from the request body, plus the output tokens
+    /// it allows.
+    pub estimated_tokens: u64,
+    /// When the request was about to be sent.
+    pub sent_at: SystemTime,
+    /// When the request was finalized; `None` if the process stopped first.
+    pub finished_at: Option<SystemTime>,
+    /// The usage the provider reported, if it was finalized with any.
+    pub usage: Option<TokenUsage>,
+}
+
+impl JournalEntry {
+    /// Whether the request was finalized. An unfinished request may or may
+    /// not have reached the provider, and may have been billed.
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.finished_at.is_some()
+    }
+
+    /// The tokens to account for: the reported usage, or the estimate when
+    /// the provider's count never arrived.
+    #[must_use]
+    pub fn billable_tokens(&self) -> u64 {
+        self.usage
+            .as_ref()
+            .map_or(self.estimated_tokens, TokenUsage::total_tokens)
+    }
+}
+
+/// A line of the journal file.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum Record {
+    Sent {
+        id: String,
+        at_ms: u64,
+        provider: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        model: Option<String>,
+        estimated_tokens: u64,
+    },
+    Finished {
+        id: String,
+        at_ms: u64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        usage: Option<RecordedUsage>,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RecordedUsage {
+    model: String,
+    input_tokens: u64,
+    output_tokens: u64,
+}
+
+impl RequestJournal {
+    /// Open `path` for appending, creating it if needed. Entries already in
+    /// the file are kept.
+    pub fn open(path: impl Into<PathBuf>) -> std::io::Result<Self> {
+        let path = path.into();
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)?;
+        let run = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos());
+        Ok(Self {
+            inner: Arc::new(JournalFile {
+                path,
+                file: Mutex::new(file),
+                run: format!("{run:x}"),
+                next: AtomicU64::new(1),
+            }),
+        })
+    }
+
+    /// The file this journal appends to.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.inner.path
+    }
+
+    /// Every request in this journal's file; see [`RequestJournal::read`].
+    pub fn entries(&self) -> std::io::Result<Vec<JournalEntry>> {
+        Self::read(&self.inner.path)
+    }
+
+    /// Every request recorded in the journal file at `path`, in the order
+    /// they were sent, with their finalization merged in.
+    ///
+    /// A line left half-written by a crash is skipped.
+    pub fn read(path: impl AsRef<Path>) -> std::io::Result<Vec<JournalEntry>> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut entries: Vec<JournalEntry> = Vec::new();
+        let mut index: HashMap<String, usize> = HashMap::new();
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let Ok(record) = serde_json::from_str::<Record>(&line) else {
+                warn!(line = %line, "Skipping unreadable request journal line");
+                continue;
+            };
+            match record {
+                Record::Sent {
+                    id,
+                    at_ms,
+                    provider,
+                    model,
+                    estimated_tokens,
+                } => {
+                    index.insert(id.clone(), entries.len());
+                    entries.push(JournalEntry {
+                        id,
+                        provider,
+                        model,
+                        estimated_tokens,
+                        sent_at: from_millis(at_ms),
+                        finished_at: None,
+                        usage: None,
+                    });
+                }
+                Record::Finished { id, at_ms, usage } => {
+                    if let Some(entry) = index.get(&id).map(|&i| &mut entries[i]) {
+                        entry.finished_at = Some(from_millis(at_ms));
+                        entry.usage = usage
+                            .map(|u| TokenUsage::new(u.model, u.input_tokens, u.output_tokens));
+                    }
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    fn next_id(&self) -> String {
+        let seq = self.inner.next.fetch_add(1, Ordering::Relaxed);
+        format!("{}-{seq}", self.inner.run)
+    }
+
+    /// Append `record` and sync it to disk.
+    fn append(&self, record: &Record) {
+        let written = serde_json::to_string(record)
+            .map_err(std::io::Error::other)
+            .and_then(|line| {
+                let mut file = self.inner.file.lock().unwrap_or_else(|e| e.into_inner());
+                file.write_all(format!("{line}\n").as_bytes())?;
+                file.sync_data()
+            });
+        if let Err(e) = written {
+            warn!(path = %self.inner.path.display(), error = %e, "Failed to write request journal");
+        }
+    }
+
+    fn finish(&self, id: String, usage: Option<&TokenUsage>) {
+        self.append(&Record::Finished {
+            id,
+            at_ms: now_millis(),
+            usage: usage.map(|u| RecordedUsage {
+                model: u.model.clone(),
+                input_tokens: u.input_tokens,
+                output_tokens: u.output_tokens,
+            }),
+        });
+    }
+}
+
+static JOURNAL: RwLock<Option<RequestJournal>> = RwLock::new(None);
+
+/// Journal every subsequent provider request to `journal`.
+///
+/// Applies process-wide, to every client. A journal that can't be written is
+/// logged and the request is sent anyway.
+pub fn set_request_journal(journal: RequestJournal) {
+    *JOURNAL.write().unwrap_or_else(|e| e.into_inner()) = Some(journal);
+}
+
+/// Stop journaling requests.
+pub fn clear_request_journal() {
+    *JOURNAL.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// The journal requests are currently recorded to, if any.
+pub fn request_journal() -> Option<RequestJournal> {
+    JOURNAL.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64)
+}
+
+fn from_millis(ms: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_millis(ms)
+}
+
+/// Requests journaled during the current retry-engine attempt.
+type Pending = Arc<Mutex<Vec<(RequestJournal, String)>>>;
+
+tokio::task_local! {
+    /// Requests sent by the attempt the retry engine is running, finalized
+    /// with the attempt's usage once it is known.
+    static ATTEMPT: Pending;
+}
+
+/// A request recorded as sent, to be finalized once its reply is in.
+pub(crate) struct Sent {
+    journal: RequestJournal,
+    id: String,
+}
+
+/// Record `request` as about to be sent, if a journal is set.
+pub(crate) fn note_sent(provider: &str, request: &reqwest::RequestBuilder) -> Option<Sent> {
+    let journal = request_journal()?;
+    let built = request.try_clone().and_then(|clone| clone.build().ok());
+    let body = built.as_ref().and_then(|sent| sent.body()?.as_bytes());
+    let model = body
+        .and_then(|body| serde_json::from_slice::<serde_json::Value>(body).ok())
+        .and_then(|body| body.get("model")?.as_str().map(str::to_string))
+        .or_else(|| {
+            built
+                .as_ref()
+                .and_then(|sent| model_from_path(sent.url().path()))
+        });
+    let id = journal.next_id();
+    journal.append(&Record::Sent {
+        id: id.clone(),
+        at_ms: now_millis(),
+        provider: provider.to_string(),
+        model,
+        estimated_tokens: body.map_or(0, request_cost),
+    });
+    Some(Sent { journal, id })
+}
+
+impl Sent {
+    /// The reply is in. Inside a retry-engine attempt the request is
+    /// finalized with the attempt's usage; otherwise it is finalized now,
+    /// without usage.
+    pub(crate) fn settle(self) {
+        let deferred = ATTEMPT.try_with(|pending| {
+            pending
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push((self.journal.clone(), self.id.clone()));
+        });
+        if deferred.is_err() {
+            self.journal.finish(self.id, None);
+        }
+    }
+}
+
+/// The requests one retry-engine attempt sent.
+#[derive(Default)]
+pub(crate) struct Attempt {
+    sent: Vec<(RequestJournal, String)>,
+}
+
+impl Attempt {
+    /// Finalize the attempt's requests. `usage` is recorded on the last one;
+    /// any earlier ones are finalized without usage.
+    pub(crate) fn finish(self, usage: Option<&TokenUsage>) {
+        let last = self.sent.len().saturating_sub(1);
+        for (i, (journal, id)) in self.sent.into_iter().enumerate() {
+            journal.finish(id, usage.filter(|_| i == last));
+        }
+    }
+}
+
+/// Run one retry-engine attempt, collecting the requests it sends.
+pub(crate) async fn attempt<F: Future>(future: F) -> (F::Output, Attempt) {
+    if request_journal().is_none() {
+        return (future.await, Attempt::default());
+    }
+    let pending = Pending::default();
+    let output = ATTEMPT.scope(Arc::clone(&pending), future).await;
+    let sent = std::mem::take(&mut *pending.lock().unwrap_or_else(|e| e.into_inner()));
+    (output, Attempt { sent })
+}
+
+/// The model in a request path: `/models/{model}:generateContent` (Gemini,
+/// Vertex AI) or `/model/{model}/converse` (Bedrock).
+fn model_from_path(path: &str) -> Option<String> {
+    let mut segments = path.split('/');
+    while let Some(segment) = segments.next() {
+        match segment {
+            "models" => {
+                let model = segments.next()?;
+                return Some(model.split(':').next().unwrap_or(model).to_string());
+            }
+            "model" => return segments.next().map(str::to_string),
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn models_are_read_from_request_paths() {
+        assert_eq!(
+            model_from_path("/v1beta/models/gemini-2.5-pro:generateContent").as_deref(),
+            Some("gemini-2.5-pro")
+        );
+        assert_eq!(
+            model_from_path("/model/anthropic.claude-haiku-4-5-v1:0/converse").as_deref(),
+            Some("anthropic.claude-haiku-4-5-v1:0")
+        );
+        assert_eq!(model_from_path("/v1/chat/completions"), None);
+    }
+
+    #[test]
+    fn read_merges_finalization_and_skips_torn_lines() {
+        let path = std::env::temp_dir().join(format!("rstructor-journal-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let journal = RequestJournal::open(&path).unwrap();
+        let first = journal.next_id();
+        let second = journal.next_id();
+        for id in [&first, &second] {
+            journal.append(&Record::Sent {
+                id: id.clone(),
+                at_ms: now_millis(),
+                provider: "OpenAI".to_string(),
+                model: Some("gpt-4o".to_string()),
+                estimated_tokens: 100,
+            });
+        }
+        journal.finish(first.clone(), Some(&TokenUsage::new("gpt-4o-2024", 30, 12)));
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"{\"event\":\"finished\",\"id\":")
+            .unwrap();
+
+        let entries = journal.entries().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].id, first);
+        assert!(entries[0].is_finished());
+        assert_eq!(entries[0].billable_tokens(), 42);
+        assert!(!entries[1].is_finished());
+        assert_eq!(entries[1].billable_tokens(), 100);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,341 @@
+//! Dataset labeling with a human in the loop.
+//!
+//! A [`Labeler`] runs items through `materialize` one at a time, asking the
+//! model for a label and how confident it is in it. A confident, valid label
+//! is accepted as-is. A label below the confidence threshold, or a reply that
+//! still fails validation after the client's retries, goes to a
+//! [`ReviewQueue`] for a person to correct. Corrections are added to the
+//! prompt of later items as few-shot examples, so the model picks up the
+//! reviewer's judgement as the run goes on.
+//!
+//! ```
+//! # #[cfg(feature = "mock")]
+//! # #[tokio::main]
+//! # async fn main() -> rstructor::Result<()> {
+//! use rstructor::{Instructor, LabelOutcome, Labeler, MockClient, ReviewItem};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Instructor, Serialize, Deserialize, Debug, PartialEq)]
+//! struct Sentiment {
+//!     positive: bool,
+//! }
+//!
+//! let client = MockClient::new()
+//!     .with_response(r#"{"score": 0.95, "value": {"positive": true}}"#)
+//!     .with_response(r#"{"score": 0.4, "value": {"positive": true}}"#);
+//! // A reviewer would normally look at `item.input` and `item.proposed`.
+//! let reviewer = |_item: ReviewItem<Sentiment>| Some(Sentiment { positive: false });
+//! let mut labeler = Labeler::new(client, reviewer).instructions("Is the review positive?");
+//!
+//! let labels = labeler.label_all(["Loved it", "Well, it was long"]).await?;
+//! assert!(matches!(labels[0], LabelOutcome::Accepted { .. }));
+//! assert!(matches!(labels[1], LabelOutcome::Corrected { .. }));
+//! assert_eq!(labeler.examples().len(), 1);
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "mock"))]
+//! # fn main() {}
+//! ```
+
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use tracing::{debug, info};
+
+use crate::backend::LLMClient;
+use crate::backend::materialize_ext::Scored;
+use crate::error::{RStructorError, Result};
+use crate::model::Instructor;
+
+/// Default confidence below which a label is sent for review.
+pub const DEFAULT_REVIEW_THRESHOLD: f64 = 0.7;
+
+/// Default number of corrected examples kept in the prompt.
+pub const DEFAULT_MAX_EXAMPLES: usize = 8;
+
+/// Why an item was sent for review.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReviewReason {
+    /// The model's confidence in its label was below the threshold.
+    LowConfidence {
+        /// The confidence the model gave, from 0.0 to 1.0.
+        confidence: f64,
+    },
+    /// No reply passed validation within the client's retries.
+    Invalid {
+        /// The error of the last reply.
+        error: String,
+    },
+}
+
+/// An item waiting for a person to label it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReviewItem<T> {
+    /// The item being labeled.
+    pub input: String,
+    /// The model's label, when it gave a valid one.
+    pub proposed: Option<T>,
+    /// Why the item needs review.
+    pub reason: ReviewReason,
+}
+
+/// Where a [`Labeler`] sends items that need a person's judgement.
+///
+/// Implemented for closures taking a [`ReviewItem`]; implement it directly to
+/// review asynchronously, e.g. by posting the item to a labeling tool and
+/// waiting for the answer.
+#[async_trait]
+pub trait ReviewQueue<T: Send + 'static>: Send + Sync {
+    /// The correct label for `item`, or `None` to leave it unlabeled.
+    async fn review(&self, item: ReviewItem<T>) -> Option<T>;
+}
+
+#[async_trait]
+impl<T, F> ReviewQueue<T> for F
+where
+    T: Send + 'static,
+    F: Fn(ReviewItem<T>) -> Option<T> + Send + Sync,
+{
+    async fn review(&self, item: ReviewItem<T>) -> Option<T> {
+        self(item)
+    }
+}
+
+/// The result of labeling one item.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LabelOutcome<T> {
+    /// The model's label, given with enough confidence.
+    Accepted {
+        /// The label.
+        value: T,
+        /// The confidence the model gave, from 0.0 to 1.0.
+        confidence: f64,
+    },
+    /// A label supplied by the reviewer.
+    Corrected {
+        /// The label.
+        value: T,
+        /// Why the item was reviewed.
+        reason: ReviewReason,
+    },
+    /// The reviewer left the item unlabeled.
+    Skipped {
+        /// Why the item was reviewed.
+        reason: ReviewReason,
+    },
+}
+
+impl<T> LabelOutcome<T> {
+    /// The label, unless the item was skipped.
+    pub fn value(&self) -> Option<&T> {
+        match self {
+            Self::Accepted { value, .. } | Self::Corrected { value, .. } => Some(value),
+            Self::Skipped { .. } => None,
+        }
+    }
+
+    /// The label, unless the item was skipped.
+    pub fn into_value(self) -> Option<T> {
+        match self {
+            Self::Accepted { value, .. } | Self::Corrected { value, .. } => Some(value),
+            Self::Skipped { .. } => None,
+        }
+    }
+}
+
+/// Labels items of type `T` with a client, sending uncertain ones to a
+/// [`ReviewQueue`]. See the [module docs](self).
+pub struct Labeler<C, T, Q> {
+    client: C,
+    review_queue: Q,
+    instructions: Option<String>,
+    review_threshold: f64,
+    max_examples: usize,
+    examples: VecDeque<(String, String)>,
+    _label: PhantomData<fn() -> T>,
+}
+
+impl<C, T, Q> Labeler<C, T, Q>
+where
+    C: LLMClient + Sync,
+    T: Instructor + Serialize + DeserializeOwned + Send + 'static,
+    Q: ReviewQueue<T>,
+{
+    /// Label with `client`, sending items that need review to `review_queue`.
+    pub fn new(client: C, review_queue: Q) -> Self {
+        Self {
+            client,
+            review_queue,
+            instructions: None,
+            review_threshold: DEFAULT_REVIEW_THRESHOLD,
+            max_examples: DEFAULT_MAX_EXAMPLES,
+            examples: VecDeque::new(),
+            _label: PhantomData,
+        }
+    }
+
+    /// What to label and how, placed at the start of every prompt.
+    #[must_use]
+    pub fn instructions(mut self, instructions: impl Into<String>) -> Self {
+        self.instructions = Some(instructions.into());
+        self
+    }
+
+    /// Send labels the model is less confident in than `threshold` (0.0 to
+    /// 1.0) for review. Defaults to [`DEFAULT_REVIEW_THRESHOLD`].
+    #[must_use]
+    pub fn review_threshold(mut self, threshold: f64) -> Self {
+        self.review_threshold = threshold;
+        self
+    }
+
+    /// Keep the latest `n` corrections as few-shot examples (default
+    /// [`DEFAULT_MAX_EXAMPLES`]). `0` leaves the prompt without examples.
+    #[must_use]
+    pub fn max_examples(mut self, n: usize) -> Self {
+        self.max_examples = n;
+        while self.examples.len() > n {
+            self.examples.pop_front();
+        }
+        self
+    }
+
+    /// Start with `value` as the correct label of `input`, as if a reviewer
+    /// had corrected it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RStructorError::SerializationError`] if `value` can't be
+    /// serialized.
+    pub fn example(mut self, input: impl Into<String>, value: &T) -> Result<Self> {
+        self.add_example(input.into(), value)?;
+        Ok(self)
+    }
+
+    /// The few-shot examples in the prompt, oldest first, as input and the
+    /// label's JSON.
+    pub fn examples(&self) -> Vec<(&str, &str)> {
+        self.examples
+            .iter()
+            .map(|(input, label)| (input.as_str(), label.as_str()))
+            .collect()
+    }
+
+    /// The client used for labeling.
+    pub fn client(&self) -> &C {
+        &self.client
+    }
+
+    /// Label one item.
+    ///
+    /// # Errors
+    ///
+    /// Returns the client's error when the request itself fails (for example
+    /// an authentication or network error). Replies that fail validation are
+    /// sent for review instead.
+    pub async fn label(&mut self, input: &str) -> Result<LabelOutcome<T>> {
+        let (proposed, reason) = match self
+            .client
+            .materialize::<Scored<T>>(&self.prompt(input))
+            .await
+        {
+            Ok(scored) if scored.score >= self.review_threshold => {
+                debug!(confidence = scored.score, "Label accepted");
+                return Ok(LabelOutcome::Accepted {
+                    value: scored.value,
+                    confidence: scored.score,
+                });
+            }
+            Ok(scored) => (
+                Some(scored.value),
+                ReviewReason::LowConfidence {
+                    confidence: scored.score,
+                },
+            ),
+            Err(e) if is_reply_error(&e) => (
+                None,
+                ReviewReason::Invalid {
+                    error: e.to_string(),
+                },
+            ),
+            Err(e) => return Err(e),
+        };
+        info!(?reason, "Sending item for review");
+        let item = ReviewItem {
+            input: input.to_string(),
+            proposed,
+            reason: reason.clone(),
+        };
+        match self.review_queue.review(item).await {
+            Some(value) => {
+                self.add_example(input.to_string(), &value)?;
+                Ok(LabelOutcome::Corrected { value, reason })
+            }
+            None => Ok(LabelOutcome::Skipped { reason }),
+        }
+    }
+
+    /// Label `inputs` in order, each with the corrections made so far.
+    ///
+    /// # Errors
+    ///
+    /// Stops at the first item whose request fails; see [`label`](Self::label).
+    pub async fn label_all<I>(&mut self, inputs: I) -> Result<Vec<LabelOutcome<T>>>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let mut outcomes = Vec::new();
+        for input in inputs {
+            outcomes.push(self.label(input.as_ref()).await?);
+        }
+        Ok(outcomes)
+    }
+
+    fn add_example(&mut self, input: String, value: &T) -> Result<()> {
+        if self.max_examples == 0 {
+            return Ok(());
+        }
+        let label = serde_json::to_string(value)
+            .map_err(|e| RStructorError::SerializationError(e.to_string()))?;
+        if self.examples.len() == self.max_examples {
+            self.examples.pop_front();
+        }
+        self.examples.push_back((input, label));
+        Ok(())
+    }
+
+    fn prompt(&self, input: &str) -> String {
+        let mut prompt = String::new();
+        if let Some(instructions) = &self.instructions {
+            prompt.push_str(instructions);
+            prompt.push_str("\n\n");
+        }
+        if !self.examples.is_empty() {
+            prompt.push_str("Correctly labeled examples:\n\n");
+            for (example, label) in &self.examples {
+                prompt.push_str(&format!("Input:\n{example}\nLabel:\n{label}\n\n"));
+            }
+        }
+        prompt.push_str(
+            "Label the input below. Set `score` to your confidence in the label, \
+             from 0.0 (a guess) to 1.0 (certain), and put the label in `value`.\n\n",
+        );
+        prompt.push_str(&format!("Input:\n{input}"));
+        prompt
+    }
+}
+
+/// Whether `e` is about the model's reply rather than the request.
+fn is_reply_error(e: &RStructorError) -> bool {
+    matches!(
+        e,
+        RStructorError::ValidationError(_)
+            | RStructorError::SchemaError(_)
+            | RStructorError::SerializationError(_)
+            | RStructorError::JsonError(_)
+    )
+}
@@ -0,0 +1,665 @@
+//! Process-wide caps on concurrent requests per provider.
+//!
+//! Each client instance is independent, so fan-out code that builds a client
+//! per task (or clones one into hundreds of tasks) can open far more
+//! simultaneous requests than a provider account allows, and every one of them
+//! comes back rate limited. [`set_concurrency_limit`] caps in-flight requests
+//! for a provider across every client in the process; requests beyond the cap
+//! wait for a free slot instead of being sent.
+//!
+//! Waiting requests are served by [`Priority`], then in arrival order, so
+//! interactive traffic sharing a limit with a background backfill does not
+//! queue behind it: run the backfill inside
+//! [`scoped_priority`]`(Priority::Background, ..)` and every freed slot goes to
+//! a waiting interactive request first.
+//!
+//! ```no_run
+//! # #[cfg(feature = "openai")]
+//! # fn main() -> rstructor::Result<()> {
+//! use rstructor::{Provider, set_concurrency_limit};
+//!
+//! // At most 8 OpenAI requests in flight, however many clients exist.
+//! set_concurrency_limit(Provider::OpenAI, 8)?;
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "openai"))]
+//! # fn main() {}
+//! ```
+//!
+//! A fixed cap has to be tuned for the provider's worst day. An
+//! [adaptive limit](set_adaptive_concurrency_limit) tunes itself instead,
+//! AIMD-style: it shrinks when the provider starts answering 429s or slowing
+//! down and grows back as requests succeed, and [`concurrency_metrics`]
+//! reports where it currently stands.
+
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+
+use tokio::sync::oneshot;
+
+use crate::backend::Provider;
+use crate::backend::payload::check_request_size;
+use crate::backend::rate_limit::{RateLimiter, request_cost};
+use crate::backend::utils::{handle_http_error, parse_retry_after};
+use crate::error::{RStructorError, Result};
+
+/// How urgently a request needs one of a provider's limited slots.
+///
+/// Only matters while a [concurrency limit](set_concurrency_limit) is full or
+/// a [`RateLimiter`] is out of budget: a freed slot, or the next budget, goes
+/// to the highest-priority waiting request. Requests that already hold a slot
+/// are never interrupted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    /// Bulk or backfill work that should yield to everything else.
+    Background,
+    /// The priority of requests outside any [`scoped_priority`].
+    #[default]
+    Normal,
+    /// A user is waiting on the result.
+    Interactive,
+}
+
+impl Priority {
+    const ALL: [Priority; 3] = [Self::Interactive, Self::Normal, Self::Background];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+tokio::task_local! {
+    static PRIORITY: Priority;
+}
+
+/// Run `future` with every request it sends queued at `priority` when a
+/// concurrency limit is full or a rate limiter is out of budget.
+///
+/// The priority is carried by the current task, like
+/// [`scoped_api_key`](crate::scoped_api_key): work `tokio::spawn`ed inside
+/// `future` runs at [`Priority::Normal`] unless wrapped too, and a stream
+/// takes the priority in effect where it is polled. Scopes nest: the
+/// innermost priority wins.
+///
+/// ```no_run
+/// # #[cfg(feature = "openai")]
+/// # async fn run(client: rstructor::OpenAIClient, docs: Vec<String>) -> rstructor::Result<()> {
+/// use rstructor::{LLMClient, Priority, scoped_priority};
+///
+/// // Backfill yields every freed slot to interactive requests.
+/// scoped_priority(Priority::Background, async {
+///     for doc in &docs {
+///         client.generate(doc).await?;
+///     }
+///     Ok::<_, rstructor::RStructorError>(())
+/// })
+/// .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn scoped_priority<F: Future>(priority: Priority, future: F) -> F::Output {
+    PRIORITY.scope(priority, future).await
+}
+
+pub(super) fn current_priority() -> Priority {
+    PRIORITY.try_with(|p| *p).unwrap_or_default()
+}
+
+/// An AIMD concurrency limit that backs off when a provider is overloaded.
+///
+/// The limit starts at `max`. A rate-limited (429), unavailable (503) or
+/// overloaded (529) response, a timeout, or a response slower than the
+/// [latency threshold](Self::latency_threshold) multiplies it by the
+/// [decrease factor](Self::decrease_factor), never going below `min`; a run of
+/// overloads keeps halving it, so a struggling provider sees load drop off
+/// exponentially instead of a retry storm. Overloads reported by requests sent
+/// before the last decrease do not count again. Once the limit's worth of
+/// requests in a row succeed, it grows by one, back up to `max`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdaptiveConcurrency {
+    min: usize,
+    max: usize,
+    latency_threshold: Option<Duration>,
+    decrease_factor: f64,
+}
+
+impl AdaptiveConcurrency {
+    /// Adapt between `min` and `max` in-flight requests, halving on overload.
+    pub fn new(min: usize, max: usize) -> Self {
+        Self {
+            min,
+            max,
+            latency_threshold: None,
+            decrease_factor: 0.5,
+        }
+    }
+
+    /// Treat a response whose headers take longer than `threshold` as an
+    /// overload signal. Off by default.
+    pub fn latency_threshold(mut self, threshold: Duration) -> Self {
+        self.latency_threshold = Some(threshold);
+        self
+    }
+
+    /// The factor the limit is multiplied by on overload, between 0 and 1
+    /// exclusive. Defaults to 0.5.
+    pub fn decrease_factor(mut self, factor: f64) -> Self {
+        self.decrease_factor = factor;
+        self
+    }
+
+    fn check(&self) -> Result<()> {
+        if self.min == 0 || self.min > self.max {
+            return Err(RStructorError::ConfigError(format!(
+                "adaptive concurrency bounds must satisfy 1 <= min <= max, got {}..={}",
+                self.min, self.max
+            )));
+        }
+        if !(self.decrease_factor > 0.0 && self.decrease_factor < 1.0) {
+            return Err(RStructorError::ConfigError(format!(
+                "decrease factor must be between 0 and 1, got {}",
+                self.decrease_factor
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// A snapshot of one provider's concurrency limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConcurrencyMetrics {
+    /// The current limit; moves over time when the limit is adaptive.
+    pub limit: usize,
+    /// Requests holding a slot. Can briefly exceed `limit` after a decrease.
+    pub in_flight: usize,
+    /// Requests queued for a slot.
+    pub waiting: usize,
+    /// Times an adaptive limit has backed off since it was set.
+    pub decreases: u64,
+}
+
+/// Slots of one provider's limit, handed to waiters by priority.
+#[derive(Debug)]
+pub(super) struct Gate {
+    adaptive: Option<AdaptiveConcurrency>,
+    state: Mutex<GateState>,
+}
+
+#[derive(Debug, Default)]
+struct GateState {
+    limit: usize,
+    in_flight: usize,
+    /// FIFO queues indexed by [`Priority::index`].
+    waiters: [VecDeque<oneshot::Sender<()>>; 3],
+    /// Bumped on every decrease; overloads from an older epoch are ignored.
+    epoch: u64,
+    /// Successes since the limit last changed, toward the next increase.
+    successes: usize,
+    decreases: u64,
+}
+
+impl GateState {
+    /// Hand a slot to the highest-priority live waiter, if there is one.
+    fn hand_over(&mut self) -> bool {
+        for priority in Priority::ALL {
+            while let Some(tx) = self.waiters[priority.index()].pop_front() {
+                if tx.send(()).is_ok() {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+}
+
+impl Gate {
+    pub(super) fn new(max_in_flight: usize) -> Self {
+        Self::with_limit(max_in_flight, None)
+    }
+
+    fn adaptive(adaptive: AdaptiveConcurrency) -> Self {
+        Self::with_limit(adaptive.max, Some(adaptive))
+    }
+
+    fn with_limit(limit: usize, adaptive: Option<AdaptiveConcurrency>) -> Self {
+        Self {
+            adaptive,
+            state: Mutex::new(GateState {
+                limit,
+                ..GateState::default()
+            }),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, GateState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Wait for a slot; it is held until the returned permit drops.
+    pub(super) async fn acquire(self: Arc<Self>, priority: Priority) -> Permit {
+        let rx = {
+            let mut state = self.lock();
+            if state.in_flight < state.limit {
+                state.in_flight += 1;
+                let epoch = state.epoch;
+                drop(state);
+                return Permit { gate: self, epoch };
+            }
+            let (tx, rx) = oneshot::channel();
+            state.waiters[priority.index()].push_back(tx);
+            rx
+        };
+        let mut waiting = Waiting {
+            gate: Arc::clone(&self),
+            rx,
+        };
+        // The gate never drops a sender it queued without first sending, so
+        // the slot has been handed over once this resolves. The grant is
+        // consumed here, so dropping `waiting` afterwards releases nothing.
+        let _ = (&mut waiting.rx).await;
+        drop(waiting);
+        let epoch = self.lock().epoch;
+        Permit { gate: self, epoch }
+    }
+
+    /// Hand a freed slot to the highest-priority live waiter, or free it.
+    /// After a decrease, slots above the new limit are freed instead.
+    fn release(&self) {
+        let mut state = self.lock();
+        if state.in_flight <= state.limit && state.hand_over() {
+            return;
+        }
+        state.in_flight -= 1;
+    }
+
+    /// Feed the outcome of a request sent in `epoch` to an adaptive limit.
+    fn record(&self, epoch: u64, overloaded: bool) {
+        let Some(adaptive) = self.adaptive else {
+            return;
+        };
+        let mut state = self.lock();
+        if overloaded {
+            if epoch != state.epoch {
+                return;
+            }
+            let reduced = (state.limit as f64 * adaptive.decrease_factor) as usize;
+            state.limit = reduced.max(adaptive.min);
+            state.epoch += 1;
+            state.successes = 0;
+            state.decreases += 1;
+            tracing::warn!(
+                limit = state.limit,
+                "provider overloaded; reducing concurrency"
+            );
+        } else if state.limit < adaptive.max {
+            state.successes += 1;
+            if state.successes >= state.limit {
+                state.successes = 0;
+                state.limit += 1;
+                tracing::debug!(limit = state.limit, "increasing concurrency");
+                if state.in_flight < state.limit && state.hand_over() {
+                    state.in_flight += 1;
+                }
+            }
+        }
+    }
+
+    fn metrics(&self) -> ConcurrencyMetrics {
+        let state = self.lock();
+        ConcurrencyMetrics {
+            limit: state.limit,
+            in_flight: state.in_flight,
+            waiting: state
+                .waiters
+                .iter()
+                .flatten()
+                .filter(|tx| !tx.is_closed())
+                .count(),
+            decreases: state.decreases,
+        }
+    }
+}
+
+/// A held slot, released on drop.
+pub(super) struct Permit {
+    gate: Arc<Gate>,
+    /// The gate's epoch when the slot was granted.
+    epoch: u64,
+}
+
+impl Permit {
+    /// Report how the request sent under this permit went.
+    fn observe(&self, response: &reqwest::Result<reqwest::Response>, latency: Duration) {
+        let Some(adaptive) = self.gate.adaptive else {
+            return;
+        };
+        let slow = adaptive
+            .latency_threshold
+            .is_some_and(|threshold| latency > threshold);
+        match response {
+            Ok(response) => {
+                let status = response.status();
+                // 529 is Anthropic's `overloaded_error`
+                let overloaded = status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                    || status == reqwest::StatusCode::SERVICE_UNAVAILABLE
+                    || status.as_u16() == 529;
+                if overloaded || slow {
+                    self.gate.record(self.epoch, true);
+                } else if status.is_success() {
+                    self.gate.record(self.epoch, false);
+                }
+            }
+            Err(e) if e.is_timeout() || slow => self.gate.record(self.epoch, true),
+            Err(_) => {}
+        }
+    }
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        self.gate.release();
+    }
+}
+
+/// A queued acquire; passes on a slot granted after its future was dropped.
+struct Waiting {
+    gate: Arc<Gate>,
+    rx: oneshot::Receiver<()>,
+}
+
+impl Drop for Waiting {
+    fn drop(&mut self) {
+        self.rx.close();
+        if self.rx.try_recv().is_ok() {
+            self.gate.release();
+        }
+    }
+}
+
+/// Limits keyed by the provider name the backends report errors under.
+fn registry() -> &'static RwLock<HashMap<&'static str, Arc<Gate>>> {
+    static REGISTRY: OnceLock<RwLock<HashMap<&'static str, Arc<Gate>>>> = OnceLock::new();
+    REGISTRY.get_or_init(Default::default)
+}
+
+/// The name the backends report `provider`'s errors under.
+pub(crate) fn provider_name(provider: Provider) -> &'static str {
+    match provider {
+        #[cfg(feature = "openai")]
+        Provider::OpenAI => "OpenAI",
+        #[cfg(feature = "anthropic")]
+        Provider::Anthropic => "Anthropic",
+        #[cfg(feature = "grok")]
+        Provider::Grok => "Grok",
+        #[cfg(feature = "gemini")]
+        Provider::Gemini => "Gemini",
+        #[cfg(feature = "ollama")]
+        Provider::Ollama => "Ollama",
+        #[cfg(feature = "azure")]
+        Provider::AzureOpenAI => "Azure OpenAI",
+        #[cfg(feature = "bedrock")]
+        Provider::Bedrock => "Bedrock",
+        #[cfg(feature = "vertex")]
+        Provider::VertexAI => "Vertex AI",
+        #[cfg(feature = "groq")]
+        Provider::Groq => "Groq",
+        #[cfg(feature = "together")]
+        Provider::Together => "Together",
+    }
+}
+
+/// Cap the number of concurrent in-flight requests to `provider` across every
+/// client in the process.
+///
+/// A request holds its slot from when it is sent until its response headers
+/// arrive; a streaming response does not keep the slot while its body streams.
+/// Replacing an existing limit applies to requests sent afterwards.
+///
+/// # Errors
+///
+/// Returns [`RStructorError::ConfigError`] if `max_in_flight` is zero.
+pub fn set_concurrency_limit(provider: Provider, max_in_flight: usize) -> Result<()> {
+    if max_in_flight == 0 {
+        return Err(RStructorError::ConfigError(
+            "concurrency limit must be at least 1".to_string(),
+        ));
+    }
+    registry()
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(provider_name(provider), Arc::new(Gate::new(max_in_flight)));
+    Ok(())
+}
+
+/// Like [`set_concurrency_limit`], but the limit adapts to how the provider
+/// is coping: it backs off multiplicatively on 429s, 503s, 529s, timeouts and (if
+/// configured) slow responses, and recovers one slot at a time. Watch it with
+/// [`concurrency_metrics`].
+///
+/// ```no_run
+/// # #[cfg(feature = "openai")]
+/// # fn main() -> rstructor::Result<()> {
+/// use std::time::Duration;
+/// use rstructor::{AdaptiveConcurrency, Provider, set_adaptive_concurrency_limit};
+///
+/// set_adaptive_concurrency_limit(
+///     Provider::OpenAI,
+///     AdaptiveConcurrency::new(2, 32).latency_threshold(Duration::from_secs(20)),
+/// )?;
+/// # Ok(())
+/// # }
+/// # #[cfg(not(feature = "openai"))]
+/// # fn main() {}
+/// ```
+///
+/// # Errors
+///
+/// Returns [`RStructorError::ConfigError`] unless `1 <= min <= max` and the
+/// decrease factor is strictly between 0 and 1.
+pub fn set_adaptive_concurrency_limit(
+    provider: Provider,
+    adaptive: AdaptiveConcurrency,
+) -> Result<()> {
+    adaptive.check()?;
+    registry()
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(provider_name(provider), Arc::new(Gate::adaptive(adaptive)));
+    Ok(())
+}
+
+/// Remove the process-wide limit for `provider`.
+pub fn clear_concurrency_limit(provider: Provider) {
+    registry()
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(provider_name(provider));
+}
+
+/// The process-wide limit for `provider`, if one is set. For an adaptive
+/// limit this is its current value.
+pub fn concurrency_limit(provider: Provider) -> Option<usize> {
+    concurrency_metrics(provider).map(|metrics| metrics.limit)
+}
+
+/// The current limit, in-flight and waiting requests for `provider`, if it
+/// has a limit.
+pub fn concurrency_metrics(provider: Provider) -> Option<ConcurrencyMetrics> {
+    registry()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(provider_name(provider))
+        .map(|gate| gate.metrics())
+}
+
+/// Send `request`, first waiting for `rate_limiter`'s budgets and for a slot
+/// if `provider` has a limit, and record the exchange if fixture recording is
+/// on (`test-util` builds only). A body over `max_request_bytes` fails with
+/// [`RStructorError::RequestTooLarge`] without being sent. A 429, 503 or 529 with a `retry-after` pauses
+/// `rate_limiter` for that long.
+pub(crate) async fn send_limited(
+    provider: &str,
+    rate_limiter: Option<&RateLimiter>,
+    max_request_bytes: Option<usize>,
+    request: reqwest::RequestBuilder,
+) -> Result<reqwest::Response> {
+    if rate_limiter.is_some() || max_request_bytes.is_some() {
+        let built = request.try_clone().and_then(|clone| clone.build().ok());
+        let body = built.as_ref().and_then(|sent| sent.body()?.as_bytes());
+        if let (Some(limit), Some(body)) = (max_request_bytes, body) {
+            check_request_size(provider, limit, body)?;
+        }
+        if let Some(rate_limiter) = rate_limiter {
+            rate_limiter
+                .acquire(body.map_or(0, request_cost), current_priority())
+                .await;
+        }
+    }
+    let gate = registry()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(provider)
+        .map(Arc::clone);
+    let permit = match gate {
+        Some(gate) => Some(gate.acquire(current_priority()).await),
+        None => None,
+    };
+    let journaled = super::journal::note_sent(provider, &request);
+    let started = Instant::now();
+    #[cfg(feature = "test-util")]
+    let response = super::fixtures::send_recorded(provider, request).await;
+    #[cfg(not(feature = "test-util"))]
+    let response = request.send().await;
+    if let Some(sent) = journaled {
+        sent.settle();
+    }
+    if let Some(permit) = &permit {
+        permit.observe(&response, started.elapsed());
+    }
+    if let (Some(rate_limiter), Ok(response)) = (rate_limiter, &response)
+        && matches!(response.status().as_u16(), 429 | 503 | 529)
+        && let Some(retry_after) = response
+            .headers()
+            .get("retry-after")
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_retry_after)
+    {
+        rate_limiter.pause(retry_after);
+    }
+    response.map_err(|e| handle_http_error(e, provider))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn freed_slots_go_to_the_highest_priority_waiter() {
+        let gate = Arc::new(Gate::new(1));
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let held = Arc::clone(&gate).acquire(Priority::Normal).await;
+
+        let mut tasks = Vec::new();
+        for (priority, label) in [
+            (Priority::Background, "background-1"),
+            (Priority::Normal, "normal"),
+            (Priority::Background, "background-2"),
+            (Priority::Interactive, "interactive"),
+        ] {
+            let (gate, order) = (Arc::clone(&gate), Arc::clone(&order));
+            tasks.push(tokio::spawn(async move {
+                let _permit = gate.acquire(priority).await;
+                order.lock().unwrap().push(label);
+            }));
+            // Let the task join the queue before the next one.
+            tokio::task::yield_now().await;
+        }
+
+        drop(held);
+        for task in tasks {
+            task.await.unwrap();
+        }
+        assert_eq!(
+            *order.lock().unwrap(),
+            ["interactive", "normal", "background-1", "background-2"]
+        );
+        assert_eq!(gate.lock().in_flight, 0);
+    }
+
+    #[tokio::test]
+    async fn abandoned_waiter_passes_its_slot_on() {
+        let gate = Arc::new(Gate::new(1));
+        let held = Arc::clone(&gate).acquire(Priority::Normal).await;
+        let abandoned = tokio::spawn(Arc::clone(&gate).acquire(Priority::Interactive));
+        tokio::task::yield_now().await;
+        abandoned.abort();
+        let _ = abandoned.await;
+
+        drop(held);
+        let _again = Arc::clone(&gate).acquire(Priority::Background).await;
+        assert_eq!(gate.lock().in_flight, 1);
+    }
+
+    #[tokio::test]
+    async fn adaptive_limit_halves_once_per_epoch_and_recovers_additively() {
+        let gate = Arc::new(Gate::adaptive(AdaptiveConcurrency::new(1, 8)));
+        let mut permits = Vec::new();
+        for _ in 0..8 {
+            permits.push(Arc::clone(&gate).acquire(Priority::Normal).await);
+        }
+
+        // A burst of 429s from one window backs off once, not eight times.
+        for permit in &permits {
+            gate.record(permit.epoch, true);
+        }
+        assert_eq!(gate.metrics().limit, 4);
+        assert_eq!(gate.metrics().decreases, 1);
+
+        // Slots above the new limit are freed rather than handed over.
+        let waiter = tokio::spawn(Arc::clone(&gate).acquire(Priority::Normal));
+        tokio::task::yield_now().await;
+        drop(permits);
+        let permit = waiter.await.unwrap();
+        assert_eq!(gate.metrics().in_flight, 1);
+
+        // Repeated overloads keep shrinking the limit, down to `min`.
+        for _ in 0..3 {
+            let epoch = gate.lock().epoch;
+            gate.record(epoch, true);
+        }
+        assert_eq!(gate.metrics().limit, 1);
+
+        // One full window of successes grows it by one.
+        gate.record(permit.epoch, false);
+        assert_eq!(gate.metrics().limit, 2);
+        gate.record(permit.epoch, false);
+        assert_eq!(gate.metrics().limit, 2);
+        gate.record(permit.epoch, false);
+        assert_eq!(gate.metrics().limit, 3);
+    }
+
+    #[test]
+    fn adaptive_bounds_are_checked() {
+        assert!(AdaptiveConcurrency::new(0, 4).check().is_err());
+        assert!(AdaptiveConcurrency::new(5, 4).check().is_err());
+        assert!(
+            AdaptiveConcurrency::new(1, 4)
+                .decrease_factor(1.0)
+                .check()
+                .is_err()
+        );
+        assert!(AdaptiveConcurrency::new(4, 4).check().is_ok());
+    }
+
+    #[tokio::test]
+    async fn scoped_priority_nests() {
+        assert_eq!(current_priority(), Priority::Normal);
+        let inner = scoped_priority(Priority::Background, async {
+            scoped_priority(Priority::Interactive, async { current_priority() }).await
+        })
+        .await;
+        assert_eq!(inner, Priority::Interactive);
+    }
+}
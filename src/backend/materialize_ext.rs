@@ -0,0 +1,268 @@
+//! Type-directed entry points: `Person::materialize(&client, prompt)`.
+//!
+//! [`MaterializeExt`] is implemented for every [`Instructor`] type, so the type
+//! being extracted can lead the call instead of a turbofish on the client. It also
+//! hosts helpers that only make sense per type, such as
+//! [`materialize_many`](MaterializeExt::materialize_many) and
+//! [`materialize_ranked`](MaterializeExt::materialize_ranked).
+//!
+//! ```
+//! # #[cfg(feature = "mock")]
+//! # #[tokio::main]
+//! # async fn main() -> rstructor::Result<()> {
+//! use rstructor::{Instructor, MaterializeExt, MockClient};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Instructor, Serialize, Deserialize)]
+//! struct Person {
+//!     name: String,
+//! }
+//!
+//! let client = MockClient::new().with_response(r#"{"name": "Ada"}"#);
+//! let person = Person::materialize(&client, "Describe someone").await?;
+//! assert_eq!(person.name, "Ada");
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "mock"))]
+//! # fn main() {}
+//! ```
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::backend::usage::MaterializeResult;
+use crate::backend::{LLMClient, MediaFile};
+use crate::error::{RStructorError, Result};
+use crate::model::Instructor;
+use crate::schema::{Schema, SchemaType, share_definitions};
+
+/// Associated-function form of the [`LLMClient`] structured calls, implemented
+/// for every [`Instructor`] type.
+#[async_trait]
+pub trait MaterializeExt: Instructor + DeserializeOwned + Send + 'static {
+    /// Equivalent to `client.materialize::<Self>(prompt)`.
+    async fn materialize<C>(client: &C, prompt: &str) -> Result<Self>
+    where
+        C: LLMClient + Sync,
+    {
+        client.materialize::<Self>(prompt).await
+    }
+
+    /// Equivalent to `client.materialize_with_media::<Self>(prompt, media)`.
+    async fn materialize_with_media<C>(
+        client: &C,
+        prompt: &str,
+        media: &[MediaFile],
+    ) -> Result<Self>
+    where
+        C: LLMClient + Sync,
+    {
+        client.materialize_with_media::<Self>(prompt, media).await
+    }
+
+    /// Equivalent to `client.materialize_with_metadata::<Self>(prompt)`.
+    async fn materialize_with_metadata<C>(
+        client: &C,
+        prompt: &str,
+    ) -> Result<MaterializeResult<Self>>
+    where
+        C: LLMClient + Sync,
+    {
+        client.materialize_with_metadata::<Self>(prompt).await
+    }
+
+    /// Extract a list of `Self` from one prompt.
+    ///
+    /// The model is asked for a JSON object with an `items` array of `Self` (a
+    /// bare top-level array is not accepted by every provider's structured
+    /// output mode); each element is validated, and the whole list is re-asked
+    /// on failure like any other `materialize` call.
+    async fn materialize_many<C>(client: &C, prompt: &str) -> Result<Vec<Self>>
+    where
+        C: LLMClient + Sync,
+    {
+        let list: ItemList<Self> = client.materialize(prompt).await?;
+        Ok(list.items)
+    }
+
+    /// Ask for `k` alternative answers to one prompt, each with a score the
+    /// model assigns itself, best first.
+    ///
+    /// For search- and recommendation-style extraction: the model fills a
+    /// `{ "candidates": [{ "score": .., "value": .. }] }` envelope, every
+    /// candidate is validated (a score outside `0.0..=1.0` is re-asked like
+    /// any other validation failure), and the list is sorted by descending
+    /// score. Ties keep the model's order. At most `k` candidates are
+    /// returned; a model that finds fewer good answers may return fewer.
+    ///
+    /// ```
+    /// # #[cfg(feature = "mock")]
+    /// # #[tokio::main]
+    /// # async fn main() -> rstructor::Result<()> {
+    /// use rstructor::{Instructor, MaterializeExt, MockClient};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Instructor, Serialize, Deserialize)]
+    /// struct Film {
+    ///     title: String,
+    /// }
+    ///
+    /// let client = MockClient::new().with_response(
+    ///     r#"{"candidates": [
+    ///         {"score": 0.6, "value": {"title": "Tenet"}},
+    ///         {"score": 0.9, "value": {"title": "Inception"}}
+    ///     ]}"#,
+    /// );
+    /// let ranked = Film::materialize_ranked(&client, "Films like Interstellar", 2).await?;
+    /// assert_eq!(ranked[0].value.title, "Inception");
+    /// assert_eq!(ranked[1].score, 0.6);
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(feature = "mock"))]
+    /// # fn main() {}
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RStructorError::ConfigError`] if `k` is zero, and otherwise
+    /// whatever the underlying `materialize` call returns.
+    async fn materialize_ranked<C>(client: &C, prompt: &str, k: usize) -> Result<Vec<Scored<Self>>>
+    where
+        C: LLMClient + Sync,
+    {
+        if k == 0 {
+            return Err(RStructorError::ConfigError(
+                "materialize_ranked needs at least one candidate (k = 0)".to_string(),
+            ));
+        }
+        let prompt = format!(
+            "{prompt}\n\nGive {k} distinct candidate answers, best first. Score each \
+             from 0.0 to 1.0 for how well it answers the request."
+        );
+        let ranked: Ranked<Self> = client.materialize(&prompt).await?;
+        let mut candidates = ranked.candidates;
+        candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+        candidates.truncate(k);
+        Ok(candidates)
+    }
+}
+
+impl<T> MaterializeExt for T where T: Instructor + DeserializeOwned + Send + 'static {}
+
+/// The `{ "items": [ ... ] }` wrapper requested by `materialize_many`.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(deserialize = "T: DeserializeOwned", serialize = "T: Serialize"))]
+struct ItemList<T> {
+    items: Vec<T>,
+}
+
+impl<T: SchemaType> SchemaType for ItemList<T> {
+    fn schema() -> Schema {
+        // Hoist the item's `$defs` so its root-relative `$ref`s still resolve.
+        share_definitions(Schema::new(json!({
+            "type": "object",
+            "properties": { "items": Vec::<T>::schema().to_json() },
+            "required": ["items"],
+        })))
+    }
+
+    fn schema_name() -> Option<String> {
+        let item_name = T::schema_name().unwrap_or_else(|| "Item".to_string());
+        Some(format!("{item_name}List"))
+    }
+}
+
+impl<T: Instructor> Instructor for ItemList<T> {
+    fn validate(&self) -> Result<()> {
+        self.items.validate()
+    }
+}
+
+/// One candidate answer from
+/// [`materialize_ranked`](MaterializeExt::materialize_ranked) with the score
+/// the model gave it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound(deserialize = "T: DeserializeOwned", serialize = "T: Serialize"))]
+pub struct Scored<T> {
+    /// How well `value` answers the prompt, from 0.0 (poorly) to 1.0 (fully),
+    /// as judged by the model.
+    pub score: f64,
+    /// The candidate answer.
+    pub value: T,
+}
+
+impl<T: SchemaType> SchemaType for Scored<T> {
+    fn schema() -> Schema {
+        share_definitions(Schema::new(json!({
+            "type": "object",
+            "properties": {
+                "score": {
+                    "type": "number",
+                    "minimum": 0.0,
+                    "maximum": 1.0,
+                    "description": "How well this candidate answers the request, from 0.0 to 1.0"
+                },
+                "value": T::schema().to_json(),
+            },
+            "required": ["score", "value"],
+        })))
+    }
+
+    fn schema_name() -> Option<String> {
+        let value_name = T::schema_name().unwrap_or_else(|| "Value".to_string());
+        Some(format!("Scored{value_name}"))
+    }
+}
+
+impl<T: Instructor> Instructor for Scored<T> {
+    fn validate(&self) -> Result<()> {
+        if !(0.0..=1.0).contains(&self.score) {
+            return Err(RStructorError::ValidationError(format!(
+                "score must be between 0.0 and 1.0, got {}",
+                self.score
+            )));
+        }
+        self.value.validate()
+    }
+}
+
+/// The `{ "candidates": [ ... ] }` envelope requested by `materialize_ranked`.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(deserialize = "T: DeserializeOwned", serialize = "T: Serialize"))]
+struct Ranked<T> {
+    candidates: Vec<Scored<T>>,
+}
+
+impl<T: SchemaType> SchemaType for Ranked<T> {
+    fn schema() -> Schema {
+        share_definitions(Schema::new(json!({
+            "type": "object",
+            "properties": {
+                "candidates": {
+                    "type": "array",
+                    "items": Scored::<T>::schema().to_json(),
+                    "minItems": 1
+                }
+            },
+            "required": ["candidates"],
+        })))
+    }
+
+    fn schema_name() -> Option<String> {
+        let value_name = T::schema_name().unwrap_or_else(|| "Value".to_string());
+        Some(format!("Ranked{value_name}"))
+    }
+}
+
+impl<T: Instructor> Instructor for Ranked<T> {
+    fn validate(&self) -> Result<()> {
+        if self.candidates.is_empty() {
+            return Err(RStructorError::ValidationError(
+                "candidates must not be empty".to_string(),
+            ));
+        }
+        self.candidates.validate()
+    }
+}
@@ -13,9 +13,18 @@ pub(crate) enum OpenAICompatibleMessageContent {
 #[derive(Debug, Serialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub(crate) enum OpenAICompatibleMessagePart {
-    Text { text: String },
-    ImageUrl { image_url: OpenAICompatibleImageUrl },
-    File { file: OpenAICompatibleFile },
+    Text {
+        text: String,
+    },
+    ImageUrl {
+        image_url: OpenAICompatibleImageUrl,
+    },
+    File {
+        file: OpenAICompatibleFile,
+    },
+    InputAudio {
+        input_audio: OpenAICompatibleInputAudio,
+    },
 }
 
 #[derive(Debug, Serialize)]
@@ -35,6 +44,15 @@ pub(crate) struct OpenAICompatibleFile {
     pub(crate) file_data: String,
 }
 
+/// An audio content part for OpenAI chat completions, see
+/// <https://platform.openai.com/docs/guides/audio>:
+/// `{"type": "input_audio", "input_audio": {"data": ..., "format": "wav"}}`.
+#[derive(Debug, Serialize)]
+pub(crate) struct OpenAICompatibleInputAudio {
+    pub(crate) data: String,
+    pub(crate) format: &'static str,
+}
+
 #[derive(Debug, Serialize)]
 #[serde(untagged)]
 pub(crate) enum AnthropicMessageContent {
@@ -94,6 +112,8 @@ pub(crate) fn build_openai_compatible_message_content(
             });
         } else if media.mime_type == "application/pdf" {
             parts.push(openai_compatible_pdf_part(media, provider_name)?);
+        } else if media.mime_type.starts_with("audio/") && provider_name != "Grok" {
+            parts.push(openai_compatible_audio_part(media, provider_name)?);
         } else {
             return Err(unsupported_media_type(media, provider_name));
         }
@@ -120,6 +140,7 @@ fn openai_compatible_pdf_part(
                           Extract the PDF's text or render its pages to images and \
                           attach those instead"
                     .to_string(),
+                provider_error: None,
             },
         ));
     }
@@ -130,6 +151,7 @@ fn openai_compatible_pdf_part(
                 provider_name,
                 ApiErrorKind::BadRequest {
                     details: "MediaFile inline data cannot be empty".to_string(),
+                    provider_error: None,
                 },
             ));
         }
@@ -152,6 +174,7 @@ fn openai_compatible_pdf_part(
                      attachments; download the file and attach the bytes inline with \
                      MediaFile::from_bytes(bytes, \"application/pdf\") instead"
                 ),
+                provider_error: None,
             },
         ))
     } else {
@@ -159,20 +182,62 @@ fn openai_compatible_pdf_part(
             provider_name,
             ApiErrorKind::BadRequest {
                 details: "MediaFile must include either inline data or uri".to_string(),
+                provider_error: None,
             },
         ))
     }
 }
 
+/// Build the `input_audio` part for OpenAI (and Azure OpenAI) chat
+/// completions, which take inline WAV or MP3 only.
+fn openai_compatible_audio_part(
+    media: &crate::backend::client::MediaFile,
+    provider_name: &str,
+) -> Result<OpenAICompatibleMessagePart> {
+    let bad_request = |details: String| {
+        RStructorError::api_error(
+            provider_name,
+            ApiErrorKind::BadRequest {
+                details,
+                provider_error: None,
+            },
+        )
+    };
+    let format = match media.mime_type.as_str() {
+        "audio/wav" | "audio/x-wav" | "audio/wave" => "wav",
+        "audio/mpeg" | "audio/mp3" => "mp3",
+        other => {
+            return Err(bad_request(format!(
+                "{provider_name} audio input must be WAV or MP3, got {other:?}"
+            )));
+        }
+    };
+    match media.data.as_ref() {
+        Some(data) if !data.is_empty() => Ok(OpenAICompatibleMessagePart::InputAudio {
+            input_audio: OpenAICompatibleInputAudio {
+                data: data.clone(),
+                format,
+            },
+        }),
+        Some(_) => Err(bad_request(
+            "MediaFile inline data cannot be empty".to_string(),
+        )),
+        None => Err(bad_request(format!(
+            "{provider_name} chat completions does not support URL-based audio; \
+             attach the recording inline with MediaFile::from_audio(bytes) instead"
+        ))),
+    }
+}
+
 /// Error for MIME types with no documented attachment pathway on this provider.
 fn unsupported_media_type(
     media: &crate::backend::client::MediaFile,
     provider_name: &str,
 ) -> RStructorError {
-    let supported = if provider_name == "Grok" {
-        "image/*"
-    } else {
-        "image/* and application/pdf"
+    let supported = match provider_name {
+        "Grok" => "image/*",
+        "Anthropic" => "image/* and application/pdf",
+        _ => "image/*, application/pdf and audio/*",
     };
     RStructorError::api_error(
         provider_name,
@@ -182,6 +247,7 @@ fn unsupported_media_type(
                  attachments are supported on this provider",
                 media.mime_type,
             ),
+            provider_error: None,
         },
     )
 }
@@ -210,6 +276,7 @@ pub(crate) fn build_anthropic_message_content(
                     "Anthropic",
                     ApiErrorKind::BadRequest {
                         details: "MediaFile inline data cannot be empty".to_string(),
+                        provider_error: None,
                     },
                 ));
             }
@@ -218,6 +285,7 @@ pub(crate) fn build_anthropic_message_content(
                     "Anthropic",
                     ApiErrorKind::BadRequest {
                         details: "MediaFile mime_type cannot be empty".to_string(),
+                        provider_error: None,
                     },
                 ));
             }
@@ -234,6 +302,7 @@ pub(crate) fn build_anthropic_message_content(
                 "Anthropic",
                 ApiErrorKind::BadRequest {
                     details: "MediaFile must include either inline data or uri".to_string(),
+                    provider_error: None,
                 },
             ));
         };
@@ -259,6 +328,7 @@ fn media_to_url(media: &crate::backend::client::MediaFile, provider_name: &str)
                 provider_name,
                 ApiErrorKind::BadRequest {
                     details: "MediaFile inline data cannot be empty".to_string(),
+                    provider_error: None,
                 },
             ));
         }
@@ -267,6 +337,7 @@ fn media_to_url(media: &crate::backend::client::MediaFile, provider_name: &str)
                 provider_name,
                 ApiErrorKind::BadRequest {
                     details: "MediaFile mime_type cannot be empty".to_string(),
+                    provider_error: None,
                 },
             ));
         }
@@ -278,6 +349,7 @@ fn media_to_url(media: &crate::backend::client::MediaFile, provider_name: &str)
             provider_name,
             ApiErrorKind::BadRequest {
                 details: "MediaFile must include either inline data or uri".to_string(),
+                provider_error: None,
             },
         ))
     }
@@ -392,20 +464,62 @@ mod tests {
     }
 
     #[test]
-    fn test_openai_non_image_non_pdf_is_a_clear_error() {
+    fn test_openai_unsupported_media_type_is_a_clear_error() {
         let msg = ChatMessage::user_with_media(
-            "transcribe",
-            vec![MediaFile::from_bytes(b"abc", "audio/mpeg")],
+            "describe",
+            vec![MediaFile::from_bytes(b"abc", "video/mp4")],
         );
         let err = build_openai_compatible_message_content(&msg, "OpenAI")
-            .expect_err("audio attachments have no chat-completions pathway");
+            .expect_err("video attachments have no chat-completions pathway");
         let text = err.to_string();
         assert!(
-            text.contains("unsupported media type") && text.contains("audio/mpeg"),
+            text.contains("unsupported media type") && text.contains("video/mp4"),
             "error should name the offending MIME type, got: {text}"
         );
     }
 
+    // ---- Audio routing ----
+
+    #[test]
+    fn test_openai_inline_audio_becomes_input_audio_part() {
+        let msg = ChatMessage::user_with_media(
+            "summarize the call",
+            vec![MediaFile::from_bytes(b"abc", "audio/mpeg")],
+        );
+        let content =
+            build_openai_compatible_message_content(&msg, "OpenAI").expect("content should build");
+        let json = serde_json::to_value(&content).expect("content should serialize");
+        assert_eq!(
+            json[1],
+            serde_json::json!({
+                "type": "input_audio",
+                "input_audio": { "data": "YWJj", "format": "mp3" }
+            })
+        );
+    }
+
+    #[test]
+    fn test_openai_audio_needs_inline_wav_or_mp3() {
+        for media in [
+            MediaFile::from_bytes(b"abc", "audio/flac"),
+            MediaFile::new("https://example.com/call.wav", "audio/wav"),
+        ] {
+            let msg = ChatMessage::user_with_media("summarize", vec![media]);
+            let err = build_openai_compatible_message_content(&msg, "OpenAI").unwrap_err();
+            assert!(err.to_string().contains("audio"), "{err}");
+        }
+    }
+
+    #[test]
+    fn test_grok_rejects_audio() {
+        let msg = ChatMessage::user_with_media(
+            "summarize",
+            vec![MediaFile::from_bytes(b"abc", "audio/wav")],
+        );
+        let err = build_openai_compatible_message_content(&msg, "Grok").unwrap_err();
+        assert!(err.to_string().contains("unsupported media type"), "{err}");
+    }
+
     // ---- PDF routing: Grok ----
 
     #[test]
@@ -4,6 +4,10 @@
 //! enabling conversation history to be maintained across retry attempts for prompt caching benefits.
 
 use super::client::MediaFile;
+#[cfg(feature = "_client")]
+use crate::backend::MaterializeResult;
+#[cfg(feature = "_client")]
+use crate::schema::{SchemaType, split_explanation, unknown_fields_in_reply, unwrap_object_root};
 
 /// Role of a chat message participant.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -101,6 +105,33 @@ impl ChatMessage {
     }
 }
 
+/// The prompt of a conversation that is a single text-only user message.
+pub(crate) fn single_prompt(messages: &[ChatMessage]) -> Option<&str> {
+    match messages {
+        [msg] if msg.role == ChatRole::User && msg.media.is_empty() => Some(&msg.content),
+        _ => None,
+    }
+}
+
+/// Split `messages` for providers that take the system prompt outside the
+/// message list: the configured system prompt and any system messages in the
+/// history, joined by blank lines, and the remaining turns.
+#[cfg(any(feature = "anthropic", feature = "bedrock", feature = "gemini"))]
+pub(crate) fn split_system_messages<'a>(
+    system_prompt: Option<&str>,
+    messages: &'a [ChatMessage],
+) -> (Option<String>, Vec<&'a ChatMessage>) {
+    let (system, turns): (Vec<_>, Vec<_>) = messages
+        .iter()
+        .partition(|msg| msg.role == ChatRole::System);
+    let system: Vec<&str> = system_prompt
+        .into_iter()
+        .chain(system.iter().map(|msg| msg.content.as_str()))
+        .collect();
+    let system = (!system.is_empty()).then(|| system.join("\n\n"));
+    (system, turns)
+}
+
 /// Result from a materialize internal call, including the raw response for retry handling.
 ///
 /// This struct captures both the successfully parsed data and the raw response string,
@@ -111,12 +142,15 @@ pub struct MaterializeInternalOutput<T> {
     /// The parsed and validated data
     pub data: T,
     /// The raw response string from the model (JSON or text).
-    /// This is stored for debugging and future features, even though it may not be
-    /// directly accessed in the success path.
-    #[allow(dead_code)]
     pub raw_response: String,
     /// Token usage information if available
     pub usage: Option<crate::backend::TokenUsage>,
+    /// Token usage summed over every attempt of the call, including replies
+    /// that were rejected. Filled in by the retry engine; `usage` until then.
+    pub total_usage: Option<crate::backend::TokenUsage>,
+    /// The full conversation that produced `data`, ending with the accepted
+    /// assistant reply. Filled in by the retry engine; empty until then.
+    pub conversation: Vec<ChatMessage>,
 }
 
 #[cfg(feature = "_client")]
@@ -126,31 +160,77 @@ impl<T> MaterializeInternalOutput<T> {
         Self {
             data,
             raw_response,
+            total_usage: usage.clone(),
             usage,
+            conversation: Vec::new(),
         }
     }
+
+    /// Attach the conversation `messages` that produced this output, followed
+    /// by the accepted assistant reply.
+    pub(crate) fn with_conversation(mut self, mut messages: Vec<ChatMessage>) -> Self {
+        messages.push(ChatMessage::assistant(self.raw_response.clone()));
+        self.conversation = messages;
+        self
+    }
+
+    /// The public result, with the reply's rationale and, when
+    /// `capture_unknown_fields` is set, its undeclared fields.
+    pub(crate) fn into_result(self, capture_unknown_fields: bool) -> MaterializeResult<T>
+    where
+        T: SchemaType,
+    {
+        let schema = T::schema();
+        let (reply, explanation) = match split_explanation(&schema, &self.raw_response) {
+            Some((json, explanation)) => (json, Some(explanation)),
+            None => (self.raw_response, None),
+        };
+        let reply = unwrap_object_root(&schema, &reply).unwrap_or(reply);
+        let extra_fields = if capture_unknown_fields {
+            unknown_fields_in_reply(&schema, &reply)
+        } else {
+            Default::default()
+        };
+        MaterializeResult::new(self.data, self.usage)
+            .with_total_usage(self.total_usage)
+            .with_conversation(self.conversation)
+            .with_extra_fields(extra_fields)
+            .with_explanation(explanation)
+    }
 }
 
 /// Error context for validation failures that preserves the raw response.
 ///
 /// This allows the retry logic to include the failed response in the conversation
 /// history, enabling the model to see what it generated wrong.
-#[cfg(feature = "_client")]
+#[cfg(any(feature = "_client", feature = "mock"))]
+#[cfg_attr(not(feature = "_client"), allow(dead_code))]
 #[derive(Debug, Clone)]
 pub struct ValidationFailureContext {
     /// The validation error message
     pub error_message: String,
     /// The raw response that failed validation
     pub raw_response: String,
+    /// Tokens used by the request that produced the rejected response
+    pub usage: Option<crate::backend::TokenUsage>,
 }
 
-#[cfg(feature = "_client")]
+#[cfg(any(feature = "_client", feature = "mock"))]
+#[cfg_attr(not(feature = "_client"), allow(dead_code))]
 impl ValidationFailureContext {
     /// Create a new validation failure context.
     pub fn new(error_message: impl Into<String>, raw_response: impl Into<String>) -> Self {
         Self {
             error_message: error_message.into(),
             raw_response: raw_response.into(),
+            usage: None,
         }
     }
+
+    /// Attach the token usage of the rejected response.
+    #[must_use]
+    pub fn with_usage(mut self, usage: Option<crate::backend::TokenUsage>) -> Self {
+        self.usage = usage;
+        self
+    }
 }
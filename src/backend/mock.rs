@@ -51,17 +51,25 @@
 
 use std::collections::VecDeque;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use async_trait::async_trait;
 use serde::de::DeserializeOwned;
 use serde_json::Value;
 
-use crate::backend::ModelInfo;
 use crate::backend::client::{LLMClient, MediaFile};
+#[cfg(feature = "streaming")]
+use crate::backend::reply::parse_and_validate_response;
+use crate::backend::reply::{fault_injection_seam, parse_reply, validation_retry_feedback};
+use crate::backend::timer::sleep;
 use crate::backend::usage::{GenerateResult, MaterializeResult, TokenUsage};
+use crate::backend::{ChatMessage, ModelInfo};
 use crate::error::{RStructorError, Result};
 use crate::model::Instructor;
-use crate::schema::SchemaType;
+use crate::schema::{SchemaType, split_explanation, unknown_fields_in_reply};
+
+/// Provider named in errors the mock reports, e.g. for truncated replies.
+const PROVIDER: &str = "Mock";
 
 /// One scripted reply the mock will hand back for a call.
 ///
@@ -125,6 +133,20 @@ fn clone_error(e: &RStructorError) -> RStructorError {
         RStructorError::SerializationError(s) => RStructorError::SerializationError(s.clone()),
         RStructorError::Timeout => RStructorError::Timeout,
         RStructorError::Unsupported(s) => RStructorError::Unsupported(s.clone()),
+        RStructorError::ConfigError(s) => RStructorError::ConfigError(s.clone()),
+        RStructorError::RequestTooLarge {
+            provider,
+            bytes,
+            limit,
+            component,
+            component_bytes,
+        } => RStructorError::RequestTooLarge {
+            provider: provider.clone(),
+            bytes: *bytes,
+            limit: *limit,
+            component: *component,
+            component_bytes: *component_bytes,
+        },
         // Sources below don't implement Clone; preserve the message instead.
         #[cfg(feature = "_client")]
         RStructorError::HttpError(_) => RStructorError::Unsupported(e.to_string()),
@@ -157,6 +179,10 @@ pub enum RequestKind {
     GenerateWithMedia,
     /// [`LLMClient::generate_with_metadata`](crate::LLMClient::generate_with_metadata)
     GenerateWithMetadata,
+    /// [`LLMClient::materialize_conversation`](crate::LLMClient::materialize_conversation)
+    MaterializeConversation,
+    /// [`LLMClient::generate_conversation`](crate::LLMClient::generate_conversation)
+    GenerateConversation,
     /// [`LLMClient::list_models`](crate::LLMClient::list_models)
     ListModels,
     /// [`LLMClient::generate_stream`](crate::LLMClient::generate_stream)
@@ -190,6 +216,9 @@ pub struct RecordedRequest {
     /// Media attached to the call (for `materialize_with_media`,
     /// `generate_with_media`, and the tool loop).
     pub media: Vec<MediaFile>,
+    /// The turns before the prompt (for `*_conversation` calls, whose prompt
+    /// and media are the final message's; empty otherwise).
+    pub history: Vec<ChatMessage>,
     /// Tool names offered to the call (for the tool loop; empty otherwise).
     #[cfg(feature = "tools")]
     pub tool_names: Vec<String>,
@@ -208,6 +237,8 @@ pub struct MockRequestView<'a> {
     pub schema_name: Option<&'a str>,
     /// Media attached to the call.
     pub media: &'a [MediaFile],
+    /// The turns before the prompt.
+    pub history: &'a [ChatMessage],
     /// Tool names offered to the call.
     #[cfg(feature = "tools")]
     pub tool_names: &'a [String],
@@ -221,11 +252,25 @@ impl<'a> MockRequestView<'a> {
             schema: None,
             schema_name: None,
             media: &[],
+            history: &[],
             #[cfg(feature = "tools")]
             tool_names: &[],
         }
     }
 
+    /// A view of a `*_conversation` call: the final message is the prompt.
+    fn conversation(kind: RequestKind, messages: &'a [ChatMessage]) -> Self {
+        match messages.split_last() {
+            Some((last, history)) => {
+                let mut view = Self::bare(kind, &last.content);
+                view.media = &last.media;
+                view.history = history;
+                view
+            }
+            None => Self::bare(kind, ""),
+        }
+    }
+
     fn to_recorded(&self) -> RecordedRequest {
         RecordedRequest {
             kind: self.kind,
@@ -233,6 +278,7 @@ impl<'a> MockRequestView<'a> {
             schema: self.schema.cloned(),
             schema_name: self.schema_name.map(str::to_string),
             media: self.media.to_vec(),
+            history: self.history.to_vec(),
             #[cfg(feature = "tools")]
             tool_names: self.tool_names.to_vec(),
         }
@@ -251,6 +297,10 @@ struct MockState {
     /// Extra parse+validate attempts on failure (simulates the provider re-ask
     /// loop): on a failed `materialize`, consume the next queued response.
     retries: Mutex<usize>,
+    /// Report undeclared fields in `materialize_with_metadata` results.
+    capture_unknown_fields: Mutex<bool>,
+    /// Simulated time each model call (and each re-ask attempt) takes.
+    latency: Mutex<Duration>,
     /// Optional scripted tool invocations performed during the tool loop.
     #[cfg(feature = "tools")]
     tool_script: Mutex<VecDeque<(String, Value)>>,
@@ -273,6 +323,8 @@ impl Default for MockState {
             ))),
             default_usage: Mutex::new(None),
             retries: Mutex::new(0),
+            capture_unknown_fields: Mutex::new(false),
+            latency: Mutex::new(Duration::ZERO),
             #[cfg(feature = "tools")]
             tool_script: Mutex::new(VecDeque::new()),
         }
@@ -456,6 +508,39 @@ impl MockClient {
         self
     }
 
+    /// Report fields the scripted reply contains but the schema does not declare
+    /// in [`MaterializeResult::extra_fields`], like a real client configured with
+    /// `capture_unknown_fields(true)`.
+    #[must_use]
+    pub fn with_unknown_field_capture(self) -> Self {
+        *self.inner.capture_unknown_fields.lock().unwrap() = true;
+        self
+    }
+
+    /// Make every model call take `latency` before it answers, as a provider
+    /// round-trip would; each re-ask attempt under
+    /// [`with_retries`](MockClient::with_retries) waits again. Useful for
+    /// testing timeouts, cancellation and concurrency limits. The wait does not
+    /// block the executor and needs no particular async runtime.
+    ///
+    /// ```
+    /// # use rstructor::{MockClient, LLMClient};
+    /// # use std::time::Duration;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let client = MockClient::new()
+    ///     .with_response("slow")
+    ///     .with_latency(Duration::from_millis(200));
+    /// let timed_out = tokio::time::timeout(Duration::from_millis(10), client.generate("p")).await;
+    /// assert!(timed_out.is_err());
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_latency(self, latency: Duration) -> Self {
+        *self.inner.latency.lock().unwrap() = latency;
+        self
+    }
+
     /// Script tool invocations the mock performs (in order) during the tool loop,
     /// before returning the final answer. Each `(name, args)` calls the matching
     /// tool in the toolbox, so the tool's `invoke` is exercised offline.
@@ -523,6 +608,17 @@ impl MockClient {
         self.inner.log.lock().unwrap().push(view.to_recorded());
     }
 
+    /// Wait out the configured latency, then pick a response.
+    async fn respond(&self, view: &MockRequestView<'_>) -> MockResponse {
+        self.wait().await;
+        self.pick_response(view)
+    }
+
+    async fn wait(&self) {
+        let latency = *self.inner.latency.lock().unwrap();
+        sleep(latency).await;
+    }
+
     /// Pick a response without recording: responder closure, then queue, then default.
     fn pick_response(&self, view: &MockRequestView) -> MockResponse {
         {
@@ -539,18 +635,52 @@ impl MockClient {
         self.inner.default_response.lock().unwrap().clone()
     }
 
-    fn resolve_materialize<T>(&self, view: &MockRequestView) -> Result<T>
+    async fn resolve_materialize<T>(&self, view: &MockRequestView<'_>) -> Result<T>
+    where
+        T: Instructor + DeserializeOwned,
+    {
+        self.resolve_materialize_with_conversation(view)
+            .await
+            .map(|(data, _)| data)
+    }
+
+    /// Like `resolve_materialize`, but also returns the simulated conversation:
+    /// the prompt, each rejected payload followed by the feedback the retry
+    /// engine would send for it, and the accepted payload.
+    async fn resolve_materialize_with_conversation<T>(
+        &self,
+        view: &MockRequestView<'_>,
+    ) -> Result<(T, Vec<ChatMessage>)>
     where
         T: Instructor + DeserializeOwned,
     {
         let attempts = 1 + *self.inner.retries.lock().unwrap();
+        let mut conversation = view.history.to_vec();
+        conversation.push(ChatMessage::user_with_media(
+            view.prompt,
+            view.media.to_vec(),
+        ));
         let mut last_err: Option<RStructorError> = None;
         for _ in 0..attempts {
-            match self.pick_response(view) {
-                MockResponse::Text(s) => match parse_and_validate::<T>(&s) {
-                    Ok(v) => return Ok(v),
-                    Err(e) => last_err = Some(e),
-                },
+            match self.respond(view).await {
+                MockResponse::Text(s) => {
+                    let s = fault_injection_seam(s);
+                    match parse_reply::<T>(&s, PROVIDER) {
+                        Ok(v) => {
+                            conversation.push(ChatMessage::assistant(s));
+                            return Ok((v, conversation));
+                        }
+                        // Re-ask the way the retry engine does
+                        Err((e, Some(ctx))) => {
+                            conversation.push(ChatMessage::assistant(s));
+                            conversation.push(ChatMessage::user(validation_retry_feedback(
+                                &ctx.error_message,
+                            )));
+                            last_err = Some(e);
+                        }
+                        Err((e, None)) => return Err(e),
+                    }
+                }
                 // An explicitly scripted error is returned verbatim (not retried).
                 MockResponse::Error(e) => return Err(e),
             }
@@ -559,22 +689,37 @@ impl MockClient {
             RStructorError::Unsupported("MockClient: no scripted response configured".to_string())
         }))
     }
-}
 
-/// Mirror of the real `parse_and_validate_response`: deserialize then validate,
-/// mapping a JSON parse failure to a [`ValidationError`](RStructorError::ValidationError)
-/// (matching live providers so tests behave identically against either).
-fn parse_and_validate<T>(raw: &str) -> Result<T>
-where
-    T: Instructor + DeserializeOwned,
-{
-    let value: T = serde_json::from_str(raw).map_err(|e| {
-        RStructorError::ValidationError(format!(
-            "Failed to parse response as JSON: {e}\nPartial JSON: {raw}"
-        ))
-    })?;
-    value.validate()?;
-    Ok(value)
+    /// Like `resolve_materialize_with_conversation`, but packaged as the
+    /// `MaterializeResult` a provider returns, with the default usage.
+    async fn resolve_materialize_with_metadata<T>(
+        &self,
+        view: &MockRequestView<'_>,
+    ) -> Result<MaterializeResult<T>>
+    where
+        T: Instructor + DeserializeOwned,
+    {
+        let (data, conversation) = self
+            .resolve_materialize_with_conversation::<T>(view)
+            .await?;
+        let usage = self.inner.default_usage.lock().unwrap().clone();
+        let schema = T::schema();
+        let reply = conversation.last().map(|reply| reply.content.as_str());
+        let (reply, explanation) = match reply.and_then(|r| split_explanation(&schema, r)) {
+            Some((json, explanation)) => (Some(json), Some(explanation)),
+            None => (reply.map(str::to_string), None),
+        };
+        let extra_fields = match reply {
+            Some(reply) if *self.inner.capture_unknown_fields.lock().unwrap() => {
+                unknown_fields_in_reply(&schema, &reply)
+            }
+            _ => Default::default(),
+        };
+        Ok(MaterializeResult::new(data, usage)
+            .with_conversation(conversation)
+            .with_extra_fields(extra_fields)
+            .with_explanation(explanation))
+    }
 }
 
 #[async_trait]
@@ -589,7 +734,7 @@ impl LLMClient for MockClient {
         view.schema = Some(&schema);
         view.schema_name = schema_name.as_deref();
         self.record(&view);
-        self.resolve_materialize::<T>(&view)
+        self.resolve_materialize::<T>(&view).await
     }
 
     async fn materialize_with_media<T>(&self, prompt: &str, media: &[MediaFile]) -> Result<T>
@@ -603,7 +748,7 @@ impl LLMClient for MockClient {
         view.schema_name = schema_name.as_deref();
         view.media = media;
         self.record(&view);
-        self.resolve_materialize::<T>(&view)
+        self.resolve_materialize::<T>(&view).await
     }
 
     async fn materialize_with_metadata<T>(&self, prompt: &str) -> Result<MaterializeResult<T>>
@@ -616,15 +761,30 @@ impl LLMClient for MockClient {
         view.schema = Some(&schema);
         view.schema_name = schema_name.as_deref();
         self.record(&view);
-        let data = self.resolve_materialize::<T>(&view)?;
-        let usage = self.inner.default_usage.lock().unwrap().clone();
-        Ok(MaterializeResult { data, usage })
+        self.resolve_materialize_with_metadata::<T>(&view).await
+    }
+
+    async fn materialize_conversation<T>(
+        &self,
+        messages: &[ChatMessage],
+    ) -> Result<MaterializeResult<T>>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        let schema = <T as SchemaType>::schema().to_json();
+        let schema_name = <T as SchemaType>::schema_name();
+        let mut view =
+            MockRequestView::conversation(RequestKind::MaterializeConversation, messages);
+        view.schema = Some(&schema);
+        view.schema_name = schema_name.as_deref();
+        self.record(&view);
+        self.resolve_materialize_with_metadata::<T>(&view).await
     }
 
     async fn generate(&self, prompt: &str) -> Result<String> {
         let view = MockRequestView::bare(RequestKind::Generate, prompt);
         self.record(&view);
-        match self.pick_response(&view) {
+        match self.respond(&view).await {
             MockResponse::Text(s) => Ok(s),
             MockResponse::Error(e) => Err(e),
         }
@@ -634,7 +794,7 @@ impl LLMClient for MockClient {
         let mut view = MockRequestView::bare(RequestKind::GenerateWithMedia, prompt);
         view.media = media;
         self.record(&view);
-        match self.pick_response(&view) {
+        match self.respond(&view).await {
             MockResponse::Text(s) => Ok(s),
             MockResponse::Error(e) => Err(e),
         }
@@ -643,7 +803,18 @@ impl LLMClient for MockClient {
     async fn generate_with_metadata(&self, prompt: &str) -> Result<GenerateResult> {
         let view = MockRequestView::bare(RequestKind::GenerateWithMetadata, prompt);
         self.record(&view);
-        let text = match self.pick_response(&view) {
+        let text = match self.respond(&view).await {
+            MockResponse::Text(s) => s,
+            MockResponse::Error(e) => return Err(e),
+        };
+        let usage = self.inner.default_usage.lock().unwrap().clone();
+        Ok(GenerateResult { text, usage })
+    }
+
+    async fn generate_conversation(&self, messages: &[ChatMessage]) -> Result<GenerateResult> {
+        let view = MockRequestView::conversation(RequestKind::GenerateConversation, messages);
+        self.record(&view);
+        let text = match self.respond(&view).await {
             MockResponse::Text(s) => s,
             MockResponse::Error(e) => return Err(e),
         };
@@ -660,6 +831,7 @@ impl LLMClient for MockClient {
         self.record(&view);
         let resp = self.pick_response(&view);
         Box::pin(async_stream::try_stream! {
+            self.wait().await;
             let s = match resp {
                 MockResponse::Text(s) => s,
                 MockResponse::Error(e) => Err(e)?,
@@ -686,6 +858,7 @@ impl LLMClient for MockClient {
         self.record(&view);
         let resp = self.pick_response(&view);
         Box::pin(async_stream::try_stream! {
+            self.wait().await;
             let s = match resp {
                 MockResponse::Text(s) => s,
                 MockResponse::Error(e) => Err(e)?,
@@ -697,7 +870,7 @@ impl LLMClient for MockClient {
                 ))
             })?;
             yield StreamedObject::Partial(snapshot);
-            let value: T = parse_and_validate::<T>(&s)?;
+            let value: T = parse_and_validate_response::<T>(&s).map_err(|(e, _)| e)?;
             yield StreamedObject::Complete(value);
         })
     }
@@ -719,6 +892,7 @@ impl LLMClient for MockClient {
         self.record(&view);
         let resp = self.pick_response(&view);
         Box::pin(async_stream::try_stream! {
+            self.wait().await;
             let s = match resp {
                 MockResponse::Text(s) => s,
                 MockResponse::Error(e) => Err(e)?,
@@ -794,7 +968,7 @@ impl crate::backend::tools::ToolRunner for MockClient {
             }
         }
 
-        match self.pick_response(&view) {
+        match self.respond(&view).await {
             MockResponse::Text(s) => Ok(s),
             MockResponse::Error(e) => Err(e),
         }
@@ -807,6 +981,7 @@ mod tests {
     // Bring the derive macro (and its `#[llm(...)]` helper attribute) into scope;
     // `super::*` only re-exports the `Instructor` *trait* used by the impl bounds.
     use crate::Instructor;
+    use crate::backend::ChatRole;
     use serde::{Deserialize, Serialize};
 
     #[derive(Instructor, Serialize, Deserialize, Debug, PartialEq)]
@@ -823,6 +998,21 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn replies_are_parsed_like_live_ones() {
+        let client = MockClient::new()
+            .with_response("```json\n{\"title\":\"Inception\",\"year\":2010}\n```")
+            .with_response(r#"Here it is: {"title":"Alien","year":1979} Enjoy!"#);
+        let fenced: Movie = client.materialize("p").await.unwrap();
+        assert_eq!(fenced.year, 2010);
+        let prose: Movie = client.materialize("p").await.unwrap();
+        assert_eq!(prose.year, 1979);
+
+        let client = MockClient::new().with_response(r#"{"title":"Incep"#);
+        let err = client.materialize::<Movie>("p").await.unwrap_err();
+        assert!(err.to_string().contains("truncated output"), "{err}");
+    }
+
     #[tokio::test]
     async fn materialize_returns_scripted_json() {
         let client = MockClient::new().with_response(r#"{"title":"Inception","year":2010}"#);
@@ -930,4 +1120,31 @@ mod tests {
             .unwrap();
         assert_eq!(result.usage.unwrap().total_tokens(), 30);
     }
+
+    #[tokio::test]
+    async fn metadata_carries_reask_conversation() {
+        let client = MockClient::new()
+            .with_response(r#"{"title":"Old","year":1700}"#)
+            .with_response(r#"{"title":"Metropolis","year":1927}"#)
+            .with_retries(1);
+        let result = client
+            .materialize_with_metadata::<Movie>("a film")
+            .await
+            .unwrap();
+        let roles: Vec<_> = result.conversation.iter().map(|m| m.role).collect();
+        assert_eq!(
+            roles,
+            [
+                ChatRole::User,
+                ChatRole::Assistant,
+                ChatRole::User,
+                ChatRole::Assistant
+            ]
+        );
+        assert_eq!(result.conversation[0].content, "a film");
+        let feedback = &result.conversation[2].content;
+        assert!(feedback.contains("year too early"));
+        assert!(feedback.starts_with("Your previous response contained validation errors"));
+        assert_eq!(result.attempts(), 2);
+    }
 }
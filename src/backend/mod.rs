@@ -1,6 +1,32 @@
 #[cfg(feature = "_client")]
 mod any_client;
+#[cfg(feature = "_client")]
+mod best_effort;
+#[cfg(feature = "_client")]
+mod budget;
+#[cfg(feature = "_client")]
+pub mod capabilities;
 pub mod client;
+mod conversation;
+#[cfg(feature = "_client")]
+mod corpus;
+#[cfg(feature = "_client")]
+mod credentials;
+#[cfg(feature = "_client")]
+pub mod deprecation;
+pub mod distill;
+#[cfg(feature = "_client")]
+mod dyn_client;
+#[cfg(feature = "test-util")]
+pub mod fault;
+#[cfg(feature = "_client")]
+mod fixtures;
+#[cfg(feature = "_client")]
+mod journal;
+pub mod labeling;
+#[cfg(feature = "_client")]
+mod limiter;
+pub mod materialize_ext;
 #[cfg(feature = "_client")]
 mod media;
 mod messages;
@@ -10,40 +36,191 @@ pub mod mock;
 mod model_macro;
 #[cfg(feature = "_client")]
 mod openai_compatible;
+#[cfg(any(feature = "groq", feature = "together"))]
+mod openai_hosted;
+#[cfg(feature = "_client")]
+mod overflow;
+#[cfg(feature = "_client")]
+mod payload;
+mod per_field;
+pub mod pricing;
+#[cfg(feature = "_client")]
+mod rate_limit;
+#[cfg(any(feature = "_client", feature = "mock"))]
+mod reply;
 #[cfg(feature = "_client")]
 mod request;
 #[cfg(feature = "streaming")]
 pub mod streaming;
+#[cfg(feature = "_client")]
+mod sweep;
+#[cfg(feature = "_client")]
+mod telemetry;
+#[cfg(feature = "_client")]
+mod tier;
+#[cfg(any(feature = "mock", feature = "test-util"))]
+mod timer;
 #[cfg(feature = "tools")]
 pub mod tools;
 pub mod usage;
 #[cfg(feature = "_client")]
+mod usage_tracker;
+#[cfg(feature = "_client")]
 mod utils;
+#[cfg(feature = "webhook")]
+pub mod webhook;
 
 #[cfg(feature = "anthropic")]
 pub mod anthropic;
+#[cfg(feature = "azure")]
+pub mod azure;
+#[cfg(feature = "bedrock")]
+pub mod bedrock;
 #[cfg(feature = "gemini")]
 pub mod gemini;
+#[cfg(feature = "vertex")]
+mod google_auth;
 #[cfg(feature = "grok")]
 pub mod grok;
+#[cfg(feature = "groq")]
+pub mod groq;
+#[cfg(feature = "ollama")]
+pub mod ollama;
 #[cfg(feature = "openai")]
 pub mod openai;
+#[cfg(feature = "bedrock")]
+mod sigv4;
+#[cfg(feature = "together")]
+pub mod together;
+#[cfg(feature = "vertex")]
+pub mod vertex;
 
 #[cfg(feature = "_client")]
-pub use any_client::{AnyClient, Provider};
+pub use any_client::{AnyClient, Provider, client_from_str};
+#[cfg(feature = "_client")]
+pub use best_effort::BestEffort;
+#[cfg(feature = "_client")]
+pub use budget::RetryBudget;
+#[cfg(feature = "_client")]
+pub use capabilities::{ModelId, OutputStrategy, ProviderCapabilities};
 pub use client::{LLMClient, MediaFile};
+pub use conversation::Conversation;
+#[cfg(feature = "_client")]
+pub use corpus::{DocId, Document};
+#[cfg(feature = "_client")]
+pub use credentials::scoped_api_key;
+#[cfg(feature = "streaming")]
+pub(crate) use credentials::sync_scoped_api_keys;
+#[cfg(feature = "_client")]
+pub(crate) use credentials::{api_key_override, scoped_api_keys};
+#[cfg(feature = "_client")]
+pub(crate) use deprecation::warn_if_deprecated;
+#[cfg(feature = "_client")]
+pub use deprecation::{Deprecation, check_model_listed};
+pub use distill::{DEFAULT_DISTILL_SAMPLE_EVERY, DistillClient, DistillStats};
+#[cfg(feature = "_client")]
+pub use dyn_client::{DynLLMClient, DynMaterializeExt, ValueValidator};
+#[cfg(feature = "test-util")]
+pub use fault::{FaultInjectingClient, InjectedFaults};
+#[cfg(feature = "_client")]
+pub use fixtures::{Fixture, FixtureRequest, FixtureResponse};
+#[cfg(all(feature = "_client", feature = "test-util"))]
+pub use fixtures::{
+    RECORD_FIXTURES_ENV, fixture_recording_dir, record_fixtures, stop_recording_fixtures,
+};
+#[cfg(feature = "_client")]
+pub use journal::{
+    JournalEntry, RequestJournal, clear_request_journal, request_journal, set_request_journal,
+};
+pub use labeling::{
+    DEFAULT_MAX_EXAMPLES, DEFAULT_REVIEW_THRESHOLD, LabelOutcome, Labeler, ReviewItem, ReviewQueue,
+    ReviewReason,
+};
+#[cfg(feature = "_client")]
+pub(crate) use limiter::send_limited;
+#[cfg(feature = "_client")]
+pub use limiter::{
+    AdaptiveConcurrency, ConcurrencyMetrics, Priority, clear_concurrency_limit, concurrency_limit,
+    concurrency_metrics, scoped_priority, set_adaptive_concurrency_limit, set_concurrency_limit,
+};
+pub use materialize_ext::{MaterializeExt, Scored};
+#[cfg(any(feature = "anthropic", feature = "bedrock", feature = "gemini"))]
+pub(crate) use messages::split_system_messages;
 pub use messages::{ChatMessage, ChatRole};
 #[cfg(feature = "_client")]
 pub use messages::{MaterializeInternalOutput, ValidationFailureContext};
 #[cfg(feature = "mock")]
 pub use mock::{MockClient, MockRequestView, MockResponse, RecordedRequest, RequestKind};
 #[cfg(feature = "_client")]
+pub use overflow::ContextOverflow;
+#[cfg(feature = "_client")]
+pub(crate) use overflow::model_override;
+pub use pricing::{
+    CostEstimate, LITELLM_PRICING_URL, ModelPricing, load_litellm_pricing, set_model_pricing,
+};
+#[cfg(feature = "_client")]
+pub use pricing::{refresh_pricing, refresh_pricing_from};
+#[cfg(feature = "_client")]
+pub use rate_limit::{RateLimiter, Requests, Tokens};
+#[cfg(feature = "_client")]
 pub use request::{Request, RequestExt};
 #[cfg(feature = "streaming")]
-pub use streaming::{ItemStream, ObjectStream, StreamedObject, TextStream};
+pub use streaming::{ItemStream, ObjectStream, Partial, StreamedObject, TextStream};
+#[cfg(feature = "_client")]
+pub use sweep::{DEFAULT_SWEEP_SAMPLES, SweepGrid, SweepReport, SweepResult, SweepSettings, sweep};
+#[cfg(feature = "_client")]
+pub use telemetry::{
+    DRIFT_THRESHOLD, DRIFT_WINDOW, ExtractionRecord, SchemaDrift, SchemaTelemetry,
+    TELEMETRY_HISTORY, WindowStats, clear_telemetry_hook, drift_report, reset_telemetry,
+    schema_telemetry, set_telemetry_hook,
+};
+#[cfg(feature = "_client")]
+pub use tier::ModelTier;
 #[cfg(feature = "tools")]
-pub use tools::{DynTool, FnTool, Tool, ToolRunner, Toolbox};
+pub use tools::{DynTool, FnTool, Tool, ToolMode, ToolRunner, Toolbox, TypedFnTool};
 pub use usage::{GenerateResult, MaterializeResult, TokenUsage};
+#[cfg(feature = "_client")]
+pub use usage_tracker::{UsageSnapshot, UsageTracker};
+#[cfg(feature = "webhook")]
+pub use webhook::{UsageEvent, UsageOperation, WebhookClient};
+
+// Clients are stored in shared application state and cloned into tasks, so each
+// must stay cheap to clone and thread-safe. A non-`Send`/`Sync` field added to
+// any of them fails to compile here.
+#[cfg(any(feature = "_client", feature = "mock"))]
+const _: () = {
+    const fn assert_shareable<T: Clone + Send + Sync + 'static>() {}
+    #[cfg(feature = "openai")]
+    assert_shareable::<openai::OpenAIClient>();
+    #[cfg(feature = "anthropic")]
+    assert_shareable::<anthropic::AnthropicClient>();
+    #[cfg(feature = "azure")]
+    assert_shareable::<azure::AzureOpenAIClient>();
+    #[cfg(feature = "bedrock")]
+    assert_shareable::<bedrock::BedrockClient>();
+    #[cfg(feature = "grok")]
+    assert_shareable::<grok::GrokClient>();
+    #[cfg(feature = "groq")]
+    assert_shareable::<groq::GroqClient>();
+    #[cfg(feature = "ollama")]
+    assert_shareable::<ollama::OllamaClient>();
+    #[cfg(feature = "together")]
+    assert_shareable::<together::TogetherClient>();
+    #[cfg(feature = "vertex")]
+    assert_shareable::<vertex::VertexAIClient>();
+    #[cfg(feature = "gemini")]
+    assert_shareable::<gemini::GeminiClient>();
+    #[cfg(feature = "_client")]
+    assert_shareable::<AnyClient>();
+    #[cfg(feature = "mock")]
+    assert_shareable::<MockClient>();
+    #[cfg(feature = "mock")]
+    assert_shareable::<DistillClient<MockClient, MockClient>>();
+    #[cfg(all(feature = "webhook", feature = "mock"))]
+    assert_shareable::<WebhookClient<MockClient>>();
+    #[cfg(all(feature = "test-util", feature = "mock"))]
+    assert_shareable::<FaultInjectingClient<MockClient>>();
+};
 
 /// Information about an available model from an LLM provider.
 ///
@@ -79,19 +256,34 @@ pub(crate) use media::{
     build_openai_compatible_message_content,
 };
 #[cfg(feature = "streaming")]
-pub(crate) use openai_compatible::OpenAICompatibleChatMessage;
+pub(crate) use openai_compatible::text_messages;
 #[cfg(feature = "_client")]
 pub(crate) use openai_compatible::{
     OpenAICompatibleChatCompletionRequest, OpenAICompatibleChatCompletionResponse,
     convert_openai_compatible_chat_messages,
 };
+#[cfg(feature = "webhook")]
+pub(crate) use utils::build_http_client;
+#[cfg(any(
+    feature = "anthropic",
+    feature = "bedrock",
+    feature = "grok",
+    feature = "ollama"
+))]
+pub(crate) use utils::prepare_strict_schema;
+#[cfg(any(feature = "openai", feature = "anthropic", feature = "grok"))]
+pub(crate) use utils::with_idempotency_key;
 #[cfg(feature = "_client")]
 pub use utils::{DEFAULT_CONNECT_TIMEOUT, DEFAULT_REQUEST_TIMEOUT};
 #[cfg(feature = "_client")]
+pub use utils::{
+    GEMINI_MAX_SCHEMA_DEPTH, SchemaChange, SchemaSanitizeReport, sanitize_gemini_schema,
+};
+#[cfg(feature = "_client")]
 pub(crate) use utils::{
-    ResponseFormat, build_http_client, check_response_status, generate_with_retry_with_history,
-    handle_http_error, materialize_with_media_with_retry, parse_validate_and_create_output,
-    prepare_strict_schema,
+    HttpClientCell, ResponseFormat, check_response_status, generate_with_retry_with_conversation,
+    generate_with_retry_with_history, materialize_with_media_with_retry,
+    parse_validate_and_create_output,
 };
 
 /// Thinking level configuration for models that support extended reasoning.
@@ -51,6 +51,8 @@ macro_rules! define_model_enum {
             Custom(String),
         }
 
+        // Variants may be `#[deprecated]`; the conversions still name them.
+        #[allow(deprecated)]
         impl $name {
             /// Return the exact API model identifier for this model.
             pub fn as_str(&self) -> &str {
@@ -0,0 +1,768 @@
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, info, instrument, trace};
+
+use crate::backend::model_macro::define_model_enum;
+use crate::backend::{
+    ChatMessage, ContextOverflow, DEFAULT_REQUEST_TIMEOUT, GenerateResult, HttpClientCell,
+    LLMClient, MaterializeInternalOutput, MaterializeResult, ModelInfo, OutputStrategy,
+    RateLimiter, TokenUsage, UsageTracker, ValidationFailureContext, check_response_status,
+    generate_with_retry_with_conversation, generate_with_retry_with_history,
+    materialize_with_media_with_retry, model_override, parse_validate_and_create_output,
+    prepare_strict_schema, send_limited,
+};
+use crate::error::{ApiErrorKind, RStructorError, Result};
+use crate::model::Instructor;
+
+/// Where a local Ollama server listens unless `OLLAMA_HOST` says otherwise.
+const DEFAULT_BASE_URL: &str = "http://localhost:11434";
+
+define_model_enum! {
+    /// Common models for a local Ollama server
+    ///
+    /// Ollama serves whatever models have been pulled (`ollama pull <name>`);
+    /// these variants cover popular ones. Any other tag, such as `"qwen3:32b"`,
+    /// can be used as a string. See the [Ollama library](https://ollama.com/library).
+    ///
+    /// ```rust
+    /// use rstructor::OllamaModel;
+    ///
+    /// let model = OllamaModel::from_string("qwen3:32b");
+    /// assert_eq!(model.as_str(), "qwen3:32b");
+    /// ```
+    pub enum Model {
+        /// Llama 3.2 (3B; small and fast)
+        Llama32 => "llama3.2",
+        /// Llama 3.3 (70B)
+        Llama33 => "llama3.3",
+        /// Qwen 3 (8B default tag)
+        Qwen3 => "qwen3",
+        /// Gemma 3 (4B default tag, accepts images)
+        Gemma3 => "gemma3",
+        /// Mistral (7B)
+        Mistral => "mistral",
+        /// Phi-4 (14B)
+        Phi4 => "phi4",
+    }
+}
+
+impl From<&Model> for crate::backend::ModelId {
+    fn from(model: &Model) -> Self {
+        crate::backend::ModelId::new(crate::backend::Provider::Ollama, model.as_str())
+    }
+}
+
+impl From<crate::backend::ModelTier> for Model {
+    fn from(tier: crate::backend::ModelTier) -> Self {
+        Model::from_string(tier.resolve(crate::backend::Provider::Ollama).name)
+    }
+}
+
+/// Configuration for the Ollama client
+#[derive(Debug, Clone)]
+pub struct OllamaConfig {
+    /// Bearer token sent as `Authorization`, for servers behind an
+    /// authenticating proxy. Empty (the default) sends no header.
+    pub api_key: String,
+    pub model: Model,
+    pub temperature: f32,
+    /// Sent as Ollama's `num_predict` option.
+    pub max_tokens: Option<u32>,
+    /// Total timeout for each HTTP request.
+    /// Defaults to [`DEFAULT_REQUEST_TIMEOUT`](crate::DEFAULT_REQUEST_TIMEOUT) (5 minutes).
+    pub timeout: Option<Duration>,
+    pub max_retries: Option<usize>,
+    /// Collect fields the model returned but the schema does not declare into
+    /// [`MaterializeResult::extra_fields`](crate::MaterializeResult::extra_fields).
+    pub capture_unknown_fields: bool,
+    /// Ask for a rationale in a generated
+    /// [`EXPLANATION_FIELD`](crate::EXPLANATION_FIELD) property, returned in
+    /// [`MaterializeResult::explanation`](crate::MaterializeResult::explanation).
+    pub explain: bool,
+    /// What `materialize` does when the prompt exceeds the context window.
+    pub context_overflow: ContextOverflow,
+    /// System prompt sent with every request, as the provider's system
+    /// instruction rather than as part of the user prompt.
+    pub system_prompt: Option<String>,
+    /// Locale the schema's descriptions are sent in; see
+    /// [`Schema::localized`](crate::Schema::localized).
+    pub schema_locale: Option<String>,
+    /// Translations consulted before those recorded by the derive.
+    pub description_catalog: Option<Arc<crate::schema::DescriptionCatalog>>,
+    /// Request and token budgets this client's requests wait for, possibly
+    /// shared with other clients.
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    /// Counts the requests and tokens of this client's structured calls,
+    /// possibly shared with other clients.
+    pub usage_tracker: UsageTracker,
+    /// Largest request body this client sends; bigger requests fail with
+    /// [`RStructorError::RequestTooLarge`] before anything is uploaded.
+    pub max_request_bytes: Option<usize>,
+    /// How `materialize` asks for structured output: a JSON Schema `format`
+    /// (the default) or `format: "json"` with the schema in the prompt.
+    pub output_strategy: OutputStrategy,
+    /// Server URL without the `/api` suffix.
+    /// Defaults to "http://localhost:11434" if not set
+    pub base_url: Option<String>,
+}
+
+/// Client for a local (or self-hosted) [Ollama](https://ollama.com) server.
+///
+/// Talks to Ollama's native `/api/chat` endpoint and needs no API key.
+///
+/// Cheap to clone and `Send + Sync`; see [sharing clients](crate::LLMClient#sharing-clients).
+#[derive(Clone)]
+pub struct OllamaClient {
+    config: Arc<OllamaConfig>,
+    client: HttpClientCell,
+}
+
+/// One message in an `/api/chat` request.
+#[derive(Debug, Serialize)]
+struct OllamaMessage {
+    role: String,
+    content: String,
+    /// Base64-encoded images (no `data:` prefix).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    images: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct OllamaOptions {
+    temperature: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    num_predict: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatRequest {
+    model: String,
+    messages: Vec<OllamaMessage>,
+    stream: bool,
+    /// `"json"` for JSON mode, or a JSON Schema object to constrain decoding.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<Value>,
+    options: OllamaOptions,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponseMessage {
+    #[serde(default)]
+    content: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    model: Option<String>,
+    message: Option<ChatResponseMessage>,
+    done_reason: Option<String>,
+    prompt_eval_count: Option<u64>,
+    eval_count: Option<u64>,
+}
+
+impl OllamaClient {
+    /// Create a client for the Ollama server at `http://localhost:11434`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rstructor::OllamaClient;
+    /// let client = OllamaClient::new().model("qwen3");
+    /// # let _ = client;
+    /// ```
+    #[instrument(name = "ollama_client_new", fields(model = ?Model::Llama32))]
+    pub fn new() -> Self {
+        info!("Creating new Ollama client");
+        let config = OllamaConfig {
+            api_key: String::new(),
+            model: Model::Llama32, // Default to Llama 3.2 (small enough for most machines)
+            temperature: 0.0,
+            max_tokens: None,
+            timeout: Some(DEFAULT_REQUEST_TIMEOUT), // Default: 5-minute request timeout
+            max_retries: Some(3),                   // Default: 3 retries with error feedback
+            capture_unknown_fields: false,
+            explain: false,
+            context_overflow: ContextOverflow::default(),
+            system_prompt: None,
+            schema_locale: None,
+            description_catalog: None,
+            rate_limiter: None,
+            usage_tracker: UsageTracker::new(),
+            max_request_bytes: None,
+            output_strategy: OutputStrategy::JsonSchema,
+            base_url: None, // Default: local server
+        };
+
+        debug!("Ollama client created with default configuration");
+        Self {
+            config: Arc::new(config),
+            client: HttpClientCell::default(),
+        }
+    }
+
+    /// Create a client for the server named by the `OLLAMA_HOST` environment
+    /// variable, falling back to `http://localhost:11434` when it is unset.
+    ///
+    /// `OLLAMA_HOST` may omit the scheme (`127.0.0.1:11434`), as the Ollama
+    /// CLI allows. Never fails: no API key is needed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use rstructor::OllamaClient;
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = OllamaClient::from_env()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(name = "ollama_client_from_env")]
+    pub fn from_env() -> Result<Self> {
+        let client = Self::new();
+        match std::env::var("OLLAMA_HOST") {
+            Ok(host) if !host.trim().is_empty() => {
+                let host = host.trim().trim_end_matches('/');
+                if host.starts_with("http://") || host.starts_with("https://") {
+                    Ok(client.base_url(host))
+                } else {
+                    Ok(client.base_url(format!("http://{host}")))
+                }
+            }
+            _ => Ok(client),
+        }
+    }
+
+    // Builder methods are generated by the macro below
+
+    fn base(&self) -> &str {
+        self.config.base_url.as_deref().unwrap_or(DEFAULT_BASE_URL)
+    }
+
+    /// Attach the bearer token, if one is configured.
+    fn authorize(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        let api_key = self.api_key_for_call();
+        if api_key.is_empty() {
+            request
+        } else {
+            request.header("Authorization", format!("Bearer {api_key}"))
+        }
+    }
+
+    /// POST a chat request to `/api/chat` and decode the reply.
+    async fn chat(&self, request: &ChatRequest) -> Result<ChatResponse> {
+        let url = format!("{}/api/chat", self.base());
+        debug!(url = %url, "Sending request to Ollama");
+        let response = send_limited(
+            "Ollama",
+            self.config.rate_limiter.as_deref(),
+            self.config.max_request_bytes,
+            self.authorize(self.http().post(&url))
+                .header("Content-Type", "application/json")
+                .json(request),
+        )
+        .await?;
+
+        let response = check_response_status(response, "Ollama").await?;
+
+        debug!("Successfully received response from Ollama");
+        response.json().await.map_err(|e| {
+            error!(error = %e, "Failed to parse JSON response from Ollama");
+            RStructorError::from(e)
+        })
+    }
+
+    fn options(&self) -> OllamaOptions {
+        OllamaOptions {
+            temperature: self.config.temperature,
+            num_predict: self.config.max_tokens,
+        }
+    }
+
+    /// Pull the reply text and token usage out of a chat response.
+    fn reply(&self, completion: ChatResponse) -> Result<(String, Option<TokenUsage>)> {
+        trace!(done_reason = ?completion.done_reason, "Completion done reason");
+        let model_name = completion
+            .model
+            .unwrap_or_else(|| self.config.model.as_str().to_string());
+        let usage = match (completion.prompt_eval_count, completion.eval_count) {
+            (None, None) => None,
+            (input, output) => Some(TokenUsage::new(
+                model_name,
+                input.unwrap_or(0),
+                output.unwrap_or(0),
+            )),
+        };
+        match completion.message {
+            Some(message) => Ok((message.content, usage)),
+            None => {
+                error!("No message in Ollama response");
+                Err(RStructorError::api_error(
+                    "Ollama",
+                    ApiErrorKind::UnexpectedResponse {
+                        details: "No message in response".to_string(),
+                    },
+                ))
+            }
+        }
+    }
+
+    /// Internal implementation of materialize (without retry logic)
+    /// Accepts conversation history for multi-turn interactions.
+    /// Returns the data, raw response, and optional usage info.
+    ///
+    /// With [`OutputStrategy::JsonSchema`] the schema is passed as `format`,
+    /// which Ollama enforces with grammar-constrained decoding. With
+    /// [`OutputStrategy::JsonMode`] `format` is `"json"` and the schema is
+    /// described in a system message instead.
+    async fn materialize_internal<T>(
+        &self,
+        messages: &[ChatMessage],
+    ) -> std::result::Result<
+        MaterializeInternalOutput<T>,
+        (RStructorError, Option<ValidationFailureContext>),
+    >
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        info!(
+            strategy = ?self.config.output_strategy,
+            "Generating structured response with Ollama"
+        );
+
+        let schema = self.output_schema::<T>();
+        let schema_json = prepare_strict_schema(&schema);
+        let system_prompt = self.config.system_prompt.as_deref();
+        let mut api_messages = convert_messages(messages, system_prompt).map_err(|e| (e, None))?;
+        let format = match self.config.output_strategy {
+            OutputStrategy::JsonSchema => schema_json,
+            OutputStrategy::JsonMode => {
+                // After the configured system prompt, if any.
+                api_messages.insert(
+                    usize::from(system_prompt.is_some()),
+                    OllamaMessage {
+                        role: "system".to_string(),
+                        content: schema.prompt_instructions(),
+                        images: Vec::new(),
+                    },
+                );
+                Value::from("json")
+            }
+            OutputStrategy::ToolCalling => {
+                return Err((
+                    RStructorError::Unsupported(
+                        "Ollama materializes via `format`; use OutputStrategy::JsonSchema or OutputStrategy::JsonMode".to_string(),
+                    ),
+                    None,
+                ));
+            }
+        };
+
+        debug!(
+            "Building Ollama chat request with structured output (history_len={})",
+            api_messages.len()
+        );
+        let request = ChatRequest {
+            model: model_override().unwrap_or_else(|| self.config.model.as_str().to_string()),
+            messages: api_messages,
+            stream: false,
+            format: Some(format),
+            options: self.options(),
+        };
+
+        let completion = self.chat(&request).await.map_err(|e| (e, None))?;
+        let (raw_response, usage) = self.reply(completion).map_err(|e| (e, None))?;
+        trace!(json = %raw_response, "Parsing structured output response");
+        parse_validate_and_create_output(raw_response, usage, "Ollama")
+    }
+
+    /// Internal implementation of raw text generation (no structured output).
+    async fn generate_internal(&self, messages: &[ChatMessage]) -> Result<GenerateResult> {
+        info!("Generating raw text response with Ollama");
+        let request = ChatRequest {
+            model: self.config.model.as_str().to_string(),
+            messages: convert_messages(messages, self.config.system_prompt.as_deref())?,
+            stream: false,
+            format: None,
+            options: self.options(),
+        };
+        let (text, usage) = self.reply(self.chat(&request).await?)?;
+        debug!(content_len = text.len(), "Received text response");
+        Ok(GenerateResult::new(text, usage))
+    }
+}
+
+impl Default for OllamaClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Convert chat history to Ollama messages, led by a `system` message when
+/// `system_prompt` is set. Ollama only accepts inline, base64-encoded images,
+/// so URL media and other MIME types are rejected.
+fn convert_messages(
+    messages: &[ChatMessage],
+    system_prompt: Option<&str>,
+) -> Result<Vec<OllamaMessage>> {
+    let system = system_prompt.map(|system| {
+        Ok(OllamaMessage {
+            role: "system".to_string(),
+            content: system.to_string(),
+            images: Vec::new(),
+        })
+    });
+    system
+        .into_iter()
+        .chain(messages.iter().map(|msg| {
+            let images = msg
+                .media
+                .iter()
+                .map(|media| {
+                    let bad_request = |details: String| {
+                        RStructorError::api_error(
+                            "Ollama",
+                            ApiErrorKind::BadRequest {
+                                details,
+                                provider_error: None,
+                            },
+                        )
+                    };
+                    if !media.mime_type.starts_with("image/") {
+                        return Err(bad_request(format!(
+                            "unsupported media type {:?} for Ollama: only image/* attachments are supported on this provider",
+                            media.mime_type
+                        )));
+                    }
+                    match media.data.as_ref() {
+                        Some(data) if !data.is_empty() => Ok(data.clone()),
+                        _ => Err(bad_request(
+                            "Ollama requires inline image data; use MediaFile::from_bytes".to_string(),
+                        )),
+                    }
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Ok(OllamaMessage {
+                role: msg.role.as_str().to_string(),
+                content: msg.content.clone(),
+                images,
+            })
+        }))
+        .collect()
+}
+
+// Generate builder methods using macro
+crate::impl_client_builder_methods! {
+    client_type: OllamaClient,
+    config_type: OllamaConfig,
+    model_type: Model,
+    provider_name: "Ollama",
+    max_temperature: 2.0,
+    api_key_required: false
+}
+
+impl OllamaClient {
+    /// Set the server URL (e.g. `http://gpu-box:11434`), without the `/api` suffix.
+    #[tracing::instrument(skip(self, base_url))]
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        let base_url_str = base_url.into();
+        tracing::debug!(
+            previous_base_url = ?self.config.base_url,
+            new_base_url = %base_url_str,
+            "Setting custom base URL"
+        );
+        Arc::make_mut(&mut self.config).base_url = Some(base_url_str);
+        self
+    }
+
+    /// Send `api_key` as a bearer token, for Ollama servers behind an
+    /// authenticating reverse proxy. A local server needs none.
+    #[tracing::instrument(skip(self, api_key))]
+    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+        Arc::make_mut(&mut self.config).api_key = api_key.into();
+        self
+    }
+
+    /// Choose how `materialize` asks the model for structured output.
+    ///
+    /// The default, [`OutputStrategy::JsonSchema`], passes the schema as
+    /// `format` so decoding is constrained to it. Older Ollama servers (before
+    /// 0.5) only understand `format: "json"`; [`OutputStrategy::JsonMode`]
+    /// sends that and describes the schema in a system message instead.
+    /// [`OutputStrategy::ToolCalling`] is not supported.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rstructor::{OllamaClient, OutputStrategy};
+    ///
+    /// let client = OllamaClient::new().output_strategy(OutputStrategy::JsonMode);
+    /// # let _ = client;
+    /// ```
+    #[tracing::instrument(skip(self))]
+    pub fn output_strategy(mut self, strategy: OutputStrategy) -> Self {
+        Arc::make_mut(&mut self.config).output_strategy = strategy;
+        self
+    }
+}
+
+#[async_trait]
+impl LLMClient for OllamaClient {
+    fn from_env() -> Result<Self> {
+        Self::from_env()
+    }
+
+    #[instrument(
+        name = "ollama_materialize",
+        skip(self, prompt),
+        fields(
+            type_name = std::any::type_name::<T>(),
+            model = %self.config.model.as_str(),
+            prompt_len = prompt.len()
+        )
+    )]
+    async fn materialize<T>(&self, prompt: &str) -> Result<T>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        let output = self
+            .config
+            .usage_tracker
+            .scope(generate_with_retry_with_history(
+                |messages: Vec<ChatMessage>| {
+                    let this = self;
+                    async move { this.materialize_internal::<T>(&messages).await }
+                },
+                prompt,
+                self.config.max_retries,
+                &self.config.context_overflow,
+            ))
+            .await?;
+        Ok(output.data)
+    }
+
+    #[instrument(
+        name = "ollama_materialize_with_media",
+        skip(self, prompt, media),
+        fields(
+            type_name = std::any::type_name::<T>(),
+            model = %self.config.model.as_str(),
+            prompt_len = prompt.len(),
+            media_len = media.len()
+        )
+    )]
+    async fn materialize_with_media<T>(&self, prompt: &str, media: &[super::MediaFile]) -> Result<T>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        self.config
+            .usage_tracker
+            .scope(materialize_with_media_with_retry(
+                |messages: Vec<ChatMessage>| {
+                    let this = self;
+                    async move { this.materialize_internal::<T>(&messages).await }
+                },
+                prompt,
+                media,
+                self.config.max_retries,
+                &self.config.context_overflow,
+            ))
+            .await
+    }
+
+    #[instrument(
+        name = "ollama_materialize_with_metadata",
+        skip(self, prompt),
+        fields(
+            type_name = std::any::type_name::<T>(),
+            model = %self.config.model.as_str(),
+            prompt_len = prompt.len()
+        )
+    )]
+    async fn materialize_with_metadata<T>(&self, prompt: &str) -> Result<MaterializeResult<T>>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        let output = self
+            .config
+            .usage_tracker
+            .scope(generate_with_retry_with_history(
+                |messages: Vec<ChatMessage>| {
+                    let this = self;
+                    async move { this.materialize_internal::<T>(&messages).await }
+                },
+                prompt,
+                self.config.max_retries,
+                &self.config.context_overflow,
+            ))
+            .await?;
+        Ok(output.into_result(self.config.capture_unknown_fields))
+    }
+
+    #[instrument(
+        name = "ollama_generate",
+        skip(self, prompt),
+        fields(
+            model = %self.config.model.as_str(),
+            prompt_len = prompt.len()
+        )
+    )]
+    async fn generate(&self, prompt: &str) -> Result<String> {
+        let result = self.generate_with_metadata(prompt).await?;
+        Ok(result.text)
+    }
+
+    #[instrument(
+        name = "ollama_generate_with_media",
+        skip(self, prompt, media),
+        fields(
+            model = %self.config.model.as_str(),
+            prompt_len = prompt.len(),
+            media_len = media.len()
+        )
+    )]
+    async fn generate_with_media(
+        &self,
+        prompt: &str,
+        media: &[super::MediaFile],
+    ) -> Result<String> {
+        let result = self
+            .generate_internal(&[ChatMessage::user_with_media(prompt, media.to_vec())])
+            .await?;
+        Ok(result.text)
+    }
+
+    #[instrument(
+        name = "ollama_generate_with_metadata",
+        skip(self, prompt),
+        fields(
+            model = %self.config.model.as_str(),
+            prompt_len = prompt.len()
+        )
+    )]
+    async fn generate_with_metadata(&self, prompt: &str) -> Result<GenerateResult> {
+        self.generate_internal(&[ChatMessage::user(prompt)]).await
+    }
+
+    #[instrument(
+        name = "ollama_materialize_conversation",
+        skip(self, messages),
+        fields(
+            type_name = std::any::type_name::<T>(),
+            model = %self.config.model.as_str(),
+            history_len = messages.len()
+        )
+    )]
+    async fn materialize_conversation<T>(
+        &self,
+        messages: &[ChatMessage],
+    ) -> Result<MaterializeResult<T>>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        let output = self
+            .config
+            .usage_tracker
+            .scope(generate_with_retry_with_conversation(
+                |messages: Vec<ChatMessage>| {
+                    let this = self;
+                    async move { this.materialize_internal::<T>(&messages).await }
+                },
+                messages,
+                self.config.max_retries,
+                &self.config.context_overflow,
+            ))
+            .await?;
+        Ok(output.into_result(self.config.capture_unknown_fields))
+    }
+
+    #[instrument(
+        name = "ollama_generate_conversation",
+        skip(self, messages),
+        fields(model = %self.config.model.as_str(), history_len = messages.len())
+    )]
+    async fn generate_conversation(&self, messages: &[ChatMessage]) -> Result<GenerateResult> {
+        self.generate_internal(messages).await
+    }
+
+    /// List the models pulled onto the server, via `/api/tags`.
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        let url = format!("{}/api/tags", self.base());
+        debug!(url = %url, "Fetching available models from Ollama");
+
+        let response = send_limited(
+            "Ollama",
+            self.config.rate_limiter.as_deref(),
+            self.config.max_request_bytes,
+            self.authorize(self.http().get(&url)),
+        )
+        .await?;
+
+        let response = check_response_status(response, "Ollama").await?;
+
+        let json: Value = response.json().await.map_err(|e| {
+            error!(error = %e, "Failed to parse models response from Ollama");
+            e
+        })?;
+
+        let models = json
+            .get("models")
+            .and_then(|models| models.as_array())
+            .map(|models_array| {
+                models_array
+                    .iter()
+                    .filter_map(|model| {
+                        let id = model.get("name").and_then(|name| name.as_str())?;
+                        let details = model.get("details");
+                        let detail = |key: &str| {
+                            details
+                                .and_then(|d| d.get(key))
+                                .and_then(|v| v.as_str())
+                                .filter(|s| !s.is_empty())
+                        };
+                        let description = match (detail("family"), detail("parameter_size")) {
+                            (Some(family), Some(size)) => Some(format!("{family}, {size}")),
+                            (Some(only), None) | (None, Some(only)) => Some(only.to_string()),
+                            (None, None) => None,
+                        };
+                        Some(ModelInfo {
+                            id: id.to_string(),
+                            name: None,
+                            description,
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        debug!(count = models.len(), "Fetched Ollama models");
+        Ok(models)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_client_needs_no_key_and_builds() {
+        let client = OllamaClient::new().build().unwrap();
+        assert!(client.config.api_key.is_empty());
+        assert_eq!(client.base(), DEFAULT_BASE_URL);
+    }
+
+    #[test]
+    fn url_images_are_rejected() {
+        let message = ChatMessage::user_with_media(
+            "describe",
+            vec![crate::MediaFile::new(
+                "https://example.com/a.png",
+                "image/png",
+            )],
+        );
+        let err = convert_messages(&[message], None).unwrap_err();
+        assert!(err.to_string().contains("inline image data"), "{err}");
+    }
+}
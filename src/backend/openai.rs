@@ -1,19 +1,23 @@
 use async_trait::async_trait;
 use serde::de::DeserializeOwned;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tracing::{debug, error, info, instrument, trace, warn};
 
 use crate::backend::model_macro::define_model_enum;
+#[cfg(feature = "streaming")]
+use crate::backend::text_messages;
 use crate::backend::{
-    ChatMessage, DEFAULT_REQUEST_TIMEOUT, GenerateResult, LLMClient, MaterializeInternalOutput,
-    MaterializeResult, ModelInfo, OpenAICompatibleChatCompletionRequest,
-    OpenAICompatibleChatCompletionResponse, ResponseFormat, ThinkingLevel, TokenUsage,
-    ValidationFailureContext, build_http_client, check_response_status,
-    convert_openai_compatible_chat_messages, generate_with_retry_with_history, handle_http_error,
-    materialize_with_media_with_retry, parse_validate_and_create_output, prepare_strict_schema,
+    ChatMessage, ContextOverflow, DEFAULT_REQUEST_TIMEOUT, GenerateResult, HttpClientCell,
+    LLMClient, MaterializeInternalOutput, MaterializeResult, ModelInfo,
+    OpenAICompatibleChatCompletionRequest, OpenAICompatibleChatCompletionResponse, OutputStrategy,
+    RateLimiter, ResponseFormat, ThinkingLevel, TokenUsage, UsageTracker, ValidationFailureContext,
+    check_response_status, convert_openai_compatible_chat_messages,
+    generate_with_retry_with_conversation, generate_with_retry_with_history,
+    materialize_with_media_with_retry, model_override, parse_validate_and_create_output,
+    send_limited, with_idempotency_key,
 };
-#[cfg(feature = "streaming")]
-use crate::backend::{OpenAICompatibleChatMessage, OpenAICompatibleMessageContent};
 use crate::error::{ApiErrorKind, RStructorError, Result};
 use crate::model::Instructor;
 
@@ -96,6 +100,22 @@ define_model_enum! {
     }
 }
 
+impl From<&Model> for crate::backend::ModelId {
+    fn from(model: &Model) -> Self {
+        crate::backend::ModelId::new(crate::backend::Provider::OpenAI, model.as_str())
+    }
+}
+
+impl From<crate::backend::ModelTier> for Model {
+    fn from(tier: crate::backend::ModelTier) -> Self {
+        Model::from_string(tier.resolve(crate::backend::Provider::OpenAI).name)
+    }
+}
+
+/// OpenAI's 50 MB limit on the total payload of a request, from the
+/// "Images and vision" guide's input requirements.
+const MAX_REQUEST_BYTES: usize = 50_000_000;
+
 /// Configuration for the OpenAI client
 #[derive(Debug, Clone)]
 pub struct OpenAIConfig {
@@ -107,6 +127,45 @@ pub struct OpenAIConfig {
     /// Defaults to [`DEFAULT_REQUEST_TIMEOUT`](crate::DEFAULT_REQUEST_TIMEOUT) (5 minutes).
     pub timeout: Option<Duration>,
     pub max_retries: Option<usize>,
+    /// Collect fields the model returned but the schema does not declare into
+    /// [`MaterializeResult::extra_fields`](crate::MaterializeResult::extra_fields).
+    pub capture_unknown_fields: bool,
+    /// Ask for a rationale in a generated
+    /// [`EXPLANATION_FIELD`](crate::EXPLANATION_FIELD) property, returned in
+    /// [`MaterializeResult::explanation`](crate::MaterializeResult::explanation).
+    pub explain: bool,
+    /// What `materialize` does when the prompt exceeds the context window.
+    pub context_overflow: ContextOverflow,
+    /// System prompt sent with every request, as the provider's system
+    /// instruction rather than as part of the user prompt.
+    pub system_prompt: Option<String>,
+    /// Locale the schema's descriptions are sent in; see
+    /// [`Schema::localized`](crate::Schema::localized).
+    pub schema_locale: Option<String>,
+    /// Translations consulted before those recorded by the derive.
+    pub description_catalog: Option<Arc<crate::schema::DescriptionCatalog>>,
+    /// Request and token budgets this client's requests wait for, possibly
+    /// shared with other clients.
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    /// Counts the requests and tokens of this client's structured calls,
+    /// possibly shared with other clients.
+    pub usage_tracker: UsageTracker,
+    /// Largest request body this client sends; bigger requests fail with
+    /// [`RStructorError::RequestTooLarge`] before anything is uploaded.
+    pub max_request_bytes: Option<usize>,
+    /// How the tool loop offers tools to the model.
+    #[cfg(feature = "tools")]
+    pub tool_mode: crate::backend::tools::ToolMode,
+    /// How `materialize` asks for structured output. `None` (the default)
+    /// sends `json_schema` and falls back to [`OutputStrategy::JsonMode`] on
+    /// endpoints that have rejected it.
+    pub output_strategy: Option<OutputStrategy>,
+    /// Endpoints (base URL and model) found to reject `json_schema`, shared
+    /// between clones of this client.
+    json_schema_rejected: Arc<Mutex<HashSet<String>>>,
+    /// Provider named in errors, logs and concurrency limits; differs from
+    /// "OpenAI" for the hosted OpenAI-compatible clients built on this one.
+    provider: &'static str,
     /// Custom base URL for OpenAI-compatible APIs (e.g., local LLMs, proxy endpoints)
     /// Defaults to "https://api.openai.com/v1" if not set
     pub base_url: Option<String>,
@@ -115,11 +174,13 @@ pub struct OpenAIConfig {
     pub thinking_level: Option<ThinkingLevel>,
 }
 
-/// OpenAI client for generating completions
+/// OpenAI client for generating completions.
+///
+/// Cheap to clone and `Send + Sync`; see [sharing clients](crate::LLMClient#sharing-clients).
 #[derive(Clone)]
 pub struct OpenAIClient {
-    config: OpenAIConfig,
-    client: reqwest::Client,
+    config: Arc<OpenAIConfig>,
+    client: HttpClientCell,
 }
 
 // ResponseFormat and JsonSchemaFormat are imported from utils and shared
@@ -160,14 +221,28 @@ impl OpenAIClient {
             max_tokens: None,
             timeout: Some(DEFAULT_REQUEST_TIMEOUT), // Default: 5-minute request timeout
             max_retries: Some(3),                   // Default: 3 retries with error feedback
-            base_url: None,                         // Default: use official OpenAI API
+            capture_unknown_fields: false,
+            explain: false,
+            context_overflow: ContextOverflow::default(),
+            system_prompt: None,
+            schema_locale: None,
+            description_catalog: None,
+            rate_limiter: None,
+            usage_tracker: UsageTracker::new(),
+            max_request_bytes: Some(MAX_REQUEST_BYTES),
+            #[cfg(feature = "tools")]
+            tool_mode: crate::backend::tools::ToolMode::Tools,
+            output_strategy: None,
+            json_schema_rejected: Arc::default(),
+            provider: "OpenAI",
+            base_url: None, // Default: use official OpenAI API
             thinking_level: Some(ThinkingLevel::Medium), // GPT-5.5 defaults to medium reasoning
         };
 
         debug!("OpenAI client created with default configuration");
         Ok(Self {
-            config,
-            client: build_http_client(DEFAULT_REQUEST_TIMEOUT),
+            config: Arc::new(config),
+            client: HttpClientCell::default(),
         })
     }
 
@@ -201,14 +276,28 @@ impl OpenAIClient {
             max_tokens: None,
             timeout: Some(DEFAULT_REQUEST_TIMEOUT), // Default: 5-minute request timeout
             max_retries: Some(3),                   // Default: 3 retries with error feedback
-            base_url: None,                         // Default: use official OpenAI API
+            capture_unknown_fields: false,
+            explain: false,
+            context_overflow: ContextOverflow::default(),
+            system_prompt: None,
+            schema_locale: None,
+            description_catalog: None,
+            rate_limiter: None,
+            usage_tracker: UsageTracker::new(),
+            max_request_bytes: Some(MAX_REQUEST_BYTES),
+            #[cfg(feature = "tools")]
+            tool_mode: crate::backend::tools::ToolMode::Tools,
+            output_strategy: None,
+            json_schema_rejected: Arc::default(),
+            provider: "OpenAI",
+            base_url: None, // Default: use official OpenAI API
             thinking_level: Some(ThinkingLevel::Medium), // GPT-5.5 defaults to medium reasoning
         };
 
         debug!("OpenAI client created with default configuration");
         Ok(Self {
-            config,
-            client: build_http_client(DEFAULT_REQUEST_TIMEOUT),
+            config: Arc::new(config),
+            client: HttpClientCell::default(),
         })
     }
 
@@ -220,7 +309,11 @@ crate::impl_client_builder_methods! {
     client_type: OpenAIClient,
     config_type: OpenAIConfig,
     model_type: Model,
-    provider_name: "OpenAI"
+    provider_name: "OpenAI",
+    max_temperature: 2.0,
+    api_key_required: true,
+    // Groq and Together clients serve through this one under their own name.
+    key_provider: |config: &OpenAIConfig| config.provider
 }
 
 impl OpenAIClient {
@@ -250,7 +343,7 @@ impl OpenAIClient {
             new_base_url = %base_url_str,
             "Setting custom base URL"
         );
-        self.config.base_url = Some(base_url_str);
+        Arc::make_mut(&mut self.config).base_url = Some(base_url_str);
         self
     }
 
@@ -288,50 +381,129 @@ impl OpenAIClient {
             new_level = ?level,
             "Setting thinking level"
         );
-        self.config.thinking_level = Some(level);
+        Arc::make_mut(&mut self.config).thinking_level = Some(level);
         self
     }
 
-    /// Internal implementation of materialize (without retry logic)
-    /// Accepts conversation history for multi-turn interactions.
-    /// Returns the data, raw response, and optional usage info.
+    /// Choose how `materialize` asks the model for structured output.
     ///
-    /// Uses OpenAI's native Structured Outputs with `response_format: json_schema`
-    /// for guaranteed schema compliance.
+    /// By default the client sends `response_format: json_schema` and, if the
+    /// endpoint rejects that format type (as many vLLM and Together.ai
+    /// deployments do), retries the call in [`OutputStrategy::JsonMode`] and
+    /// keeps using it for that endpoint and model from then on. This is a
+    /// reactive fallback, not capability probing: nothing is checked up front,
+    /// the first structured call to such an endpoint pays for one rejected
+    /// request, and errors about the schema itself are returned without
+    /// switching modes. Setting a strategy disables the fallback.
+    /// [`OutputStrategy::JsonMode`] sends `response_format: json_object` and
+    /// describes the schema in a system message; the reply is still parsed and
+    /// validated, and re-asked on failure. [`OutputStrategy::ToolCalling`] is
+    /// not supported.
     ///
-    /// The raw response is included to enable conversation history tracking for retries,
-    /// which improves prompt caching efficiency.
-    async fn materialize_internal<T>(
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rstructor::{OpenAIClient, OutputStrategy};
+    ///
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = OpenAIClient::new("api-key")?
+    ///     .base_url("http://localhost:8000/v1")
+    ///     .model("meta-llama/Llama-3.1-8B-Instruct")
+    ///     .output_strategy(OutputStrategy::JsonMode);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[tracing::instrument(skip(self))]
+    pub fn output_strategy(mut self, strategy: OutputStrategy) -> Self {
+        Arc::make_mut(&mut self.config).output_strategy = Some(strategy);
+        self
+    }
+
+    /// Name errors, logs and concurrency limits after `provider` rather than
+    /// OpenAI, for clients of OpenAI-compatible hosts. Drops OpenAI's request
+    /// size limit, which the host need not share.
+    #[cfg(any(feature = "groq", feature = "together"))]
+    pub(crate) fn serving_as(mut self, provider: &'static str) -> Self {
+        let config = Arc::make_mut(&mut self.config);
+        config.provider = provider;
+        config.max_request_bytes = None;
+        self
+    }
+
+    /// The strategy `materialize` uses next: the configured one, or
+    /// `json_schema` unless this endpoint has rejected it.
+    fn effective_output_strategy(&self) -> OutputStrategy {
+        self.config.output_strategy.unwrap_or_else(|| {
+            let rejected = self
+                .config
+                .json_schema_rejected
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .contains(&self.endpoint_key());
+            if rejected {
+                OutputStrategy::JsonMode
+            } else {
+                OutputStrategy::JsonSchema
+            }
+        })
+    }
+
+    /// Identifies the endpoint and model a `json_schema` rejection applies to.
+    fn endpoint_key(&self) -> String {
+        format!(
+            "{} {}",
+            self.config
+                .base_url
+                .as_deref()
+                .unwrap_or("https://api.openai.com/v1"),
+            model_override().unwrap_or_else(|| self.config.model.as_str().to_string())
+        )
+    }
+
+    /// Send one structured-output request with `strategy`.
+    async fn structured_completion<T>(
         &self,
         messages: &[ChatMessage],
-    ) -> std::result::Result<
-        MaterializeInternalOutput<T>,
-        (RStructorError, Option<ValidationFailureContext>),
-    >
+        strategy: OutputStrategy,
+    ) -> Result<OpenAICompatibleChatCompletionResponse>
     where
-        T: Instructor + DeserializeOwned + Send + 'static,
+        T: Instructor,
     {
-        info!("Generating structured response with OpenAI (native structured outputs)");
-
         // Get the schema for type T
-        let schema = T::schema();
+        let schema = self.output_schema::<T>();
         let schema_name = T::schema_name().unwrap_or_else(|| "output".to_string());
         // Avoid calling to_string() in trace to prevent potential stack overflow with complex schemas
         trace!(schema_name = schema_name, "Retrieved JSON schema for type");
 
-        // Prepare schema with additionalProperties: false recursively for all nested objects
-        let schema_json = prepare_strict_schema(&schema);
-
-        // Create response format with JSON schema (strict mode)
-        let response_format = ResponseFormat::json_schema(
-            schema_name.clone(),
-            schema_json,
-            Some("Output in the specified format. Include ALL required fields and follow the schema exactly.".to_string()),
-        );
+        // Rewrite the schema into the subset OpenAI strict mode accepts
+        let schema_json = schema.to_openai_strict();
+
+        let mut history = messages.to_vec();
+        let response_format = match strategy {
+            // Create response format with JSON schema (strict mode)
+            OutputStrategy::JsonSchema => ResponseFormat::json_schema(
+                schema_name.clone(),
+                schema_json,
+                Some("Output in the specified format. Include ALL required fields and follow the schema exactly.".to_string()),
+            ),
+            OutputStrategy::JsonMode => {
+                // After the configured system prompt, if any.
+                history.insert(
+                    0,
+                    ChatMessage::system(schema.prompt_instructions()),
+                );
+                ResponseFormat::JsonObject
+            }
+            OutputStrategy::ToolCalling => {
+                return Err(RStructorError::Unsupported(
+                    "OpenAI materializes via `response_format`; use OutputStrategy::JsonSchema or OutputStrategy::JsonMode".to_string(),
+                ));
+            }
+        };
 
         // Build reasoning_effort for GPT-5.x models
-        let is_gpt5 = self.config.model.as_str().starts_with("gpt-5");
-        let reasoning_effort = if is_gpt5 {
+        let supports_thinking = self.capabilities().thinking;
+        let reasoning_effort = if supports_thinking {
             self.config
                 .thinking_level
                 .and_then(|level| level.openai_reasoning_effort().map(|s| s.to_string()))
@@ -347,16 +519,19 @@ impl OpenAIClient {
         };
 
         // Convert ChatMessage to OpenAI's format
-        let api_messages =
-            convert_openai_compatible_chat_messages(messages, "OpenAI").map_err(|e| (e, None))?;
+        let api_messages = convert_openai_compatible_chat_messages(
+            &history,
+            self.config.provider,
+            self.config.system_prompt.as_deref(),
+        )?;
 
-        // Build the request with native structured outputs
+        // Build the request with structured outputs
         debug!(
             "Building OpenAI API request with structured outputs (history_len={})",
             api_messages.len()
         );
         let request = OpenAICompatibleChatCompletionRequest {
-            model: self.config.model.as_str().to_string(),
+            model: model_override().unwrap_or_else(|| self.config.model.as_str().to_string()),
             messages: api_messages,
             response_format: Some(response_format),
             temperature: effective_temp,
@@ -372,33 +547,83 @@ impl OpenAIClient {
             .unwrap_or("https://api.openai.com/v1");
         let url = format!("{}/chat/completions", base_url);
         debug!(url = %url, "Sending request to OpenAI API");
-        let response = self
-            .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.config.api_key))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| (handle_http_error(e, "OpenAI"), None))?;
+        let response = send_limited(
+            self.config.provider,
+            self.config.rate_limiter.as_deref(),
+            self.config.max_request_bytes,
+            with_idempotency_key(self.http().post(&url))
+                .header(
+                    "Authorization",
+                    format!("Bearer {}", self.api_key_for_call()),
+                )
+                .header("Content-Type", "application/json")
+                .json(&request),
+        )
+        .await?;
 
         // Parse the response
-        let response = check_response_status(response, "OpenAI")
-            .await
-            .map_err(|e| (e, None))?;
+        let response = check_response_status(response, self.config.provider).await?;
 
         debug!("Successfully received response from OpenAI");
-        let completion: OpenAICompatibleChatCompletionResponse =
-            response.json().await.map_err(|e| {
-                error!(error = %e, "Failed to parse JSON response from OpenAI");
-                (RStructorError::from(e), None)
-            })?;
+        response.json().await.map_err(|e| {
+            error!(error = %e, "Failed to parse JSON response from OpenAI");
+            RStructorError::from(e)
+        })
+    }
+
+    /// Internal implementation of materialize (without retry logic)
+    /// Accepts conversation history for multi-turn interactions.
+    /// Returns the data, raw response, and optional usage info.
+    ///
+    /// Uses OpenAI's native Structured Outputs with `response_format: json_schema`
+    /// for guaranteed schema compliance, or `json_object` with the schema in a
+    /// system message under [`OutputStrategy::JsonMode`].
+    ///
+    /// The raw response is included to enable conversation history tracking for retries,
+    /// which improves prompt caching efficiency.
+    async fn materialize_internal<T>(
+        &self,
+        messages: &[ChatMessage],
+    ) -> std::result::Result<
+        MaterializeInternalOutput<T>,
+        (RStructorError, Option<ValidationFailureContext>),
+    >
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        let strategy = self.effective_output_strategy();
+        info!(strategy = ?strategy, "Generating structured response with OpenAI");
+
+        let completion = match self.structured_completion::<T>(messages, strategy).await {
+            Err(err)
+                if strategy == OutputStrategy::JsonSchema
+                    && self.config.output_strategy.is_none()
+                    && err
+                        .api_error_kind()
+                        .and_then(ApiErrorKind::provider_error)
+                        .is_some_and(|e| e.is_json_schema_unsupported()) =>
+            {
+                warn!(
+                    endpoint = %self.endpoint_key(),
+                    "Endpoint rejected json_schema; falling back to json_object mode"
+                );
+                self.config
+                    .json_schema_rejected
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .insert(self.endpoint_key());
+                self.structured_completion::<T>(messages, OutputStrategy::JsonMode)
+                    .await
+            }
+            result => result,
+        }
+        .map_err(|e| (e, None))?;
 
         if completion.choices.is_empty() {
             error!("OpenAI returned empty choices array");
             return Err((
                 RStructorError::api_error(
-                    "OpenAI",
+                    self.config.provider,
                     ApiErrorKind::UnexpectedResponse {
                         details: "No completion choices returned".to_string(),
                     },
@@ -429,12 +654,12 @@ impl OpenAIClient {
             );
 
             // Parse and validate the response using shared utility
-            parse_validate_and_create_output(raw_response, usage)
+            parse_validate_and_create_output(raw_response, usage, self.config.provider)
         } else {
             error!("No content in OpenAI response");
             Err((
                 RStructorError::api_error(
-                    "OpenAI",
+                    self.config.provider,
                     ApiErrorKind::UnexpectedResponse {
                         details: "No content in response".to_string(),
                     },
@@ -453,8 +678,8 @@ impl OpenAIClient {
         info!("Generating raw text response with OpenAI");
 
         // Build reasoning_effort for GPT-5.x models
-        let is_gpt5 = self.config.model.as_str().starts_with("gpt-5");
-        let reasoning_effort = if is_gpt5 {
+        let supports_thinking = self.capabilities().thinking;
+        let reasoning_effort = if supports_thinking {
             self.config
                 .thinking_level
                 .and_then(|level| level.openai_reasoning_effort().map(|s| s.to_string()))
@@ -473,7 +698,11 @@ impl OpenAIClient {
         debug!("Building OpenAI API request for text generation");
         let request = OpenAICompatibleChatCompletionRequest {
             model: self.config.model.as_str().to_string(),
-            messages: convert_openai_compatible_chat_messages(messages, "OpenAI")?,
+            messages: convert_openai_compatible_chat_messages(
+                messages,
+                self.config.provider,
+                self.config.system_prompt.as_deref(),
+            )?,
             response_format: None,
             temperature: effective_temp,
             max_tokens: self.config.max_tokens,
@@ -488,18 +717,23 @@ impl OpenAIClient {
             .unwrap_or("https://api.openai.com/v1");
         let url = format!("{}/chat/completions", base_url);
         debug!(url = %url, "Sending request to OpenAI API");
-        let response = self
-            .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.config.api_key))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| handle_http_error(e, "OpenAI"))?;
+        let response = send_limited(
+            self.config.provider,
+            self.config.rate_limiter.as_deref(),
+            self.config.max_request_bytes,
+            self.http()
+                .post(&url)
+                .header(
+                    "Authorization",
+                    format!("Bearer {}", self.api_key_for_call()),
+                )
+                .header("Content-Type", "application/json")
+                .json(&request),
+        )
+        .await?;
 
         // Parse the response
-        let response = check_response_status(response, "OpenAI").await?;
+        let response = check_response_status(response, self.config.provider).await?;
 
         debug!("Successfully received response from OpenAI");
         let completion: OpenAICompatibleChatCompletionResponse =
@@ -511,7 +745,7 @@ impl OpenAIClient {
         if completion.choices.is_empty() {
             error!("OpenAI returned empty choices array");
             return Err(RStructorError::api_error(
-                "OpenAI",
+                self.config.provider,
                 ApiErrorKind::UnexpectedResponse {
                     details: "No completion choices returned".to_string(),
                 },
@@ -540,7 +774,7 @@ impl OpenAIClient {
         } else {
             error!("No content in OpenAI response");
             Err(RStructorError::api_error(
-                "OpenAI",
+                self.config.provider,
                 ApiErrorKind::UnexpectedResponse {
                     details: "No content in response".to_string(),
                 },
@@ -558,8 +792,8 @@ impl OpenAIClient {
         prompt: &str,
         response_format: Option<ResponseFormat>,
     ) -> serde_json::Value {
-        let is_gpt5 = self.config.model.as_str().starts_with("gpt-5");
-        let reasoning_effort = if is_gpt5 {
+        let supports_thinking = self.capabilities().thinking;
+        let reasoning_effort = if supports_thinking {
             self.config
                 .thinking_level
                 .and_then(|level| level.openai_reasoning_effort().map(|s| s.to_string()))
@@ -573,10 +807,7 @@ impl OpenAIClient {
         };
         let request = OpenAICompatibleChatCompletionRequest {
             model: self.config.model.as_str().to_string(),
-            messages: vec![OpenAICompatibleChatMessage {
-                role: "user".to_string(),
-                content: OpenAICompatibleMessageContent::Text(prompt.to_string()),
-            }],
+            messages: text_messages(self.config.system_prompt.as_deref(), prompt),
             response_format,
             temperature: effective_temp,
             max_tokens: self.config.max_tokens,
@@ -592,28 +823,60 @@ impl OpenAIClient {
         &self,
         body: serde_json::Value,
     ) -> impl std::future::Future<Output = Result<reqwest::Response>> + Send + 'static {
-        let client = self.client.clone();
-        let api_key = self.config.api_key.clone();
+        let client = self.http().clone();
+        let api_key = self.api_key_for_call();
         let base_url = self
             .config
             .base_url
             .clone()
             .unwrap_or_else(|| "https://api.openai.com/v1".to_string());
+        let rate_limiter = self.config.rate_limiter.clone();
+        let max_request_bytes = self.config.max_request_bytes;
+        let provider = self.config.provider;
         async move {
             let url = format!("{}/chat/completions", base_url);
-            let resp = client
-                .post(&url)
-                .header("Authorization", format!("Bearer {api_key}"))
-                .header("Content-Type", "application/json")
-                .json(&body)
-                .send()
-                .await
-                .map_err(|e| handle_http_error(e, "OpenAI"))?;
-            check_response_status(resp, "OpenAI").await
+            let resp = send_limited(
+                provider,
+                rate_limiter.as_deref(),
+                max_request_bytes,
+                client
+                    .post(&url)
+                    .header("Authorization", format!("Bearer {api_key}"))
+                    .header("Content-Type", "application/json")
+                    .json(&body),
+            )
+            .await?;
+            check_response_status(resp, provider).await
         }
     }
 }
 
+#[cfg(feature = "tools")]
+impl OpenAIClient {
+    /// Choose how `with_tools(..).run(..)` offers tools to the model.
+    ///
+    /// The default, [`ToolMode::Tools`](crate::ToolMode::Tools), sends
+    /// `tools` and `tool_choice`. [`ToolMode::LegacyFunctions`](crate::ToolMode::LegacyFunctions)
+    /// sends the deprecated `functions` and `function_call` instead, for
+    /// older OpenAI-compatible endpoints that do not understand `tools`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rstructor::{OpenAIClient, ToolMode};
+    ///
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = OpenAIClient::new("api-key")?.tool_mode(ToolMode::LegacyFunctions);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[tracing::instrument(skip(self))]
+    pub fn tool_mode(mut self, mode: crate::backend::tools::ToolMode) -> Self {
+        Arc::make_mut(&mut self.config).tool_mode = mode;
+        self
+    }
+}
+
 #[cfg(feature = "tools")]
 #[async_trait]
 impl crate::backend::tools::ToolRunner for OpenAIClient {
@@ -625,6 +888,8 @@ impl crate::backend::tools::ToolRunner for OpenAIClient {
         toolbox: &crate::backend::tools::Toolbox,
         max_iterations: usize,
     ) -> Result<String> {
+        let system =
+            crate::backend::tools::tool_run_system(self.config.system_prompt.as_deref(), system);
         let base_url = self
             .config
             .base_url
@@ -635,23 +900,26 @@ impl crate::backend::tools::ToolRunner for OpenAIClient {
         // GPT-5.x models require temperature=1.0. `reasoning_effort` combined with
         // function tools is rejected on /v1/chat/completions, so it is omitted for
         // the tool loop.
-        let is_gpt5 = self.config.model.as_str().starts_with("gpt-5");
-        let effective_temp = if is_gpt5 {
+        let supports_thinking = self.capabilities().thinking;
+        let effective_temp = if supports_thinking {
             1.0
         } else {
             self.config.temperature
         };
 
         crate::backend::tools::run_openai_compatible_tools(
-            &self.client,
+            self.http(),
             &url,
-            &self.config.api_key,
-            "OpenAI",
+            &self.api_key_for_call(),
+            self.config.rate_limiter.as_deref(),
+            self.config.max_request_bytes,
+            self.config.tool_mode,
+            self.config.provider,
             self.config.model.as_str(),
             effective_temp,
             self.config.max_tokens,
             None,
-            system,
+            system.as_deref(),
             prompt,
             media,
             toolbox,
@@ -680,15 +948,19 @@ impl LLMClient for OpenAIClient {
     where
         T: Instructor + DeserializeOwned + Send + 'static,
     {
-        let output = generate_with_retry_with_history(
-            |messages: Vec<ChatMessage>| {
-                let this = self;
-                async move { this.materialize_internal::<T>(&messages).await }
-            },
-            prompt,
-            self.config.max_retries,
-        )
-        .await?;
+        let output = self
+            .config
+            .usage_tracker
+            .scope(generate_with_retry_with_history(
+                |messages: Vec<ChatMessage>| {
+                    let this = self;
+                    async move { this.materialize_internal::<T>(&messages).await }
+                },
+                prompt,
+                self.config.max_retries,
+                &self.config.context_overflow,
+            ))
+            .await?;
         Ok(output.data)
     }
 
@@ -706,16 +978,19 @@ impl LLMClient for OpenAIClient {
     where
         T: Instructor + DeserializeOwned + Send + 'static,
     {
-        materialize_with_media_with_retry(
-            |messages: Vec<ChatMessage>| {
-                let this = self;
-                async move { this.materialize_internal::<T>(&messages).await }
-            },
-            prompt,
-            media,
-            self.config.max_retries,
-        )
-        .await
+        self.config
+            .usage_tracker
+            .scope(materialize_with_media_with_retry(
+                |messages: Vec<ChatMessage>| {
+                    let this = self;
+                    async move { this.materialize_internal::<T>(&messages).await }
+                },
+                prompt,
+                media,
+                self.config.max_retries,
+                &self.config.context_overflow,
+            ))
+            .await
     }
 
     #[instrument(
@@ -731,16 +1006,20 @@ impl LLMClient for OpenAIClient {
     where
         T: Instructor + DeserializeOwned + Send + 'static,
     {
-        let output = generate_with_retry_with_history(
-            |messages: Vec<ChatMessage>| {
-                let this = self;
-                async move { this.materialize_internal::<T>(&messages).await }
-            },
-            prompt,
-            self.config.max_retries,
-        )
-        .await?;
-        Ok(MaterializeResult::new(output.data, output.usage))
+        let output = self
+            .config
+            .usage_tracker
+            .scope(generate_with_retry_with_history(
+                |messages: Vec<ChatMessage>| {
+                    let this = self;
+                    async move { this.materialize_internal::<T>(&messages).await }
+                },
+                prompt,
+                self.config.max_retries,
+                &self.config.context_overflow,
+            ))
+            .await?;
+        Ok(output.into_result(self.config.capture_unknown_fields))
     }
 
     #[instrument(
@@ -788,6 +1067,47 @@ impl LLMClient for OpenAIClient {
         self.generate_internal(&[ChatMessage::user(prompt)]).await
     }
 
+    #[instrument(
+        name = "openai_materialize_conversation",
+        skip(self, messages),
+        fields(
+            type_name = std::any::type_name::<T>(),
+            model = %self.config.model.as_str(),
+            history_len = messages.len()
+        )
+    )]
+    async fn materialize_conversation<T>(
+        &self,
+        messages: &[ChatMessage],
+    ) -> Result<MaterializeResult<T>>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        let output = self
+            .config
+            .usage_tracker
+            .scope(generate_with_retry_with_conversation(
+                |messages: Vec<ChatMessage>| {
+                    let this = self;
+                    async move { this.materialize_internal::<T>(&messages).await }
+                },
+                messages,
+                self.config.max_retries,
+                &self.config.context_overflow,
+            ))
+            .await?;
+        Ok(output.into_result(self.config.capture_unknown_fields))
+    }
+
+    #[instrument(
+        name = "openai_generate_conversation",
+        skip(self, messages),
+        fields(model = %self.config.model.as_str(), history_len = messages.len())
+    )]
+    async fn generate_conversation(&self, messages: &[ChatMessage]) -> Result<GenerateResult> {
+        self.generate_internal(messages).await
+    }
+
     #[cfg(feature = "streaming")]
     fn generate_stream<'a>(&'a self, prompt: &'a str) -> crate::backend::streaming::TextStream<'a>
     where
@@ -809,9 +1129,9 @@ impl LLMClient for OpenAIClient {
         T: Instructor + DeserializeOwned + Send + 'static,
         Self: Sync,
     {
-        let schema = T::schema();
+        let schema = self.localized_schema::<T>();
         let schema_name = T::schema_name().unwrap_or_else(|| "output".to_string());
-        let schema_json = prepare_strict_schema(&schema);
+        let schema_json = schema.to_openai_strict();
         let response_format = ResponseFormat::json_schema(
             schema_name,
             schema_json,
@@ -833,7 +1153,7 @@ impl LLMClient for OpenAIClient {
         T: Instructor + DeserializeOwned + Send + 'static,
         Self: Sync,
     {
-        let item_schema = prepare_strict_schema(&T::schema());
+        let item_schema = self.localized_schema::<T>().to_openai_strict();
         let wrapper = crate::backend::streaming::array_wrapper_schema(item_schema, true);
         let response_format = ResponseFormat::json_schema(
             "items".to_string(),
@@ -853,6 +1173,37 @@ impl LLMClient for OpenAIClient {
     /// Returns a list of GPT models available for chat completions.
     /// Filters out embedding, whisper, and other non-chat models.
     async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        let models: Vec<ModelInfo> = self
+            .model_entries()
+            .await?
+            .iter()
+            .filter_map(|model| {
+                let id = model.get("id").and_then(|id| id.as_str())?;
+                // Filter to only GPT models (chat completion models).
+                // The legacy o-series reasoning models are deliberately
+                // excluded: they reject the request parameters this
+                // client sends (`temperature`, `max_tokens`).
+                if id.starts_with("gpt-") {
+                    Some(ModelInfo {
+                        id: id.to_string(),
+                        name: None,
+                        description: None,
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        debug!(count = models.len(), "Fetched OpenAI models");
+        Ok(models)
+    }
+}
+
+impl OpenAIClient {
+    /// The unfiltered entries of the `/models` endpoint, which OpenAI wraps
+    /// in a `data` array and some compatible hosts return bare.
+    pub(crate) async fn model_entries(&self) -> Result<Vec<serde_json::Value>> {
         let base_url = self
             .config
             .base_url
@@ -860,52 +1211,36 @@ impl LLMClient for OpenAIClient {
             .unwrap_or("https://api.openai.com/v1");
         let url = format!("{}/models", base_url);
 
-        debug!(url = %url, "Fetching available models from OpenAI");
-
-        let response = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", self.config.api_key))
-            .header("Content-Type", "application/json")
-            .send()
-            .await
-            .map_err(|e| handle_http_error(e, "OpenAI"))?;
+        debug!(url = %url, "Fetching available models from {}", self.config.provider);
+
+        let response = send_limited(
+            self.config.provider,
+            self.config.rate_limiter.as_deref(),
+            self.config.max_request_bytes,
+            self.http()
+                .get(&url)
+                .header(
+                    "Authorization",
+                    format!("Bearer {}", self.api_key_for_call()),
+                )
+                .header("Content-Type", "application/json"),
+        )
+        .await?;
 
-        let response = check_response_status(response, "OpenAI").await?;
+        let response = check_response_status(response, self.config.provider).await?;
 
         let json: serde_json::Value = response.json().await.map_err(|e| {
-            error!(error = %e, "Failed to parse models response from OpenAI");
+            error!(error = %e, "Failed to parse models response from {}", self.config.provider);
             e
         })?;
 
-        let models = json
-            .get("data")
-            .and_then(|data| data.as_array())
-            .map(|models_array| {
-                models_array
-                    .iter()
-                    .filter_map(|model| {
-                        let id = model.get("id").and_then(|id| id.as_str())?;
-                        // Filter to only GPT models (chat completion models).
-                        // The legacy o-series reasoning models are deliberately
-                        // excluded: they reject the request parameters this
-                        // client sends (`temperature`, `max_tokens`).
-                        if id.starts_with("gpt-") {
-                            Some(ModelInfo {
-                                id: id.to_string(),
-                                name: None,
-                                description: None,
-                            })
-                        } else {
-                            None
-                        }
-                    })
-                    .collect::<Vec<_>>()
-            })
-            .unwrap_or_default();
-
-        debug!(count = models.len(), "Fetched OpenAI models");
-        Ok(models)
+        Ok(match json {
+            serde_json::Value::Array(models) => models,
+            mut json => match json.get_mut("data").map(serde_json::Value::take) {
+                Some(serde_json::Value::Array(models)) => models,
+                _ => Vec::new(),
+            },
+        })
     }
 }
 
@@ -927,4 +1262,16 @@ mod tests {
             .timeout(Duration::from_secs(10));
         assert_eq!(client.config.timeout, Some(Duration::from_secs(10)));
     }
+
+    #[test]
+    fn clones_share_config_until_reconfigured() {
+        let client = OpenAIClient::new("test-key").unwrap();
+        let clone = client.clone();
+        assert!(Arc::ptr_eq(&client.config, &clone.config));
+
+        let warmer = clone.temperature(0.7);
+        assert!(!Arc::ptr_eq(&client.config, &warmer.config));
+        assert_eq!(client.config.temperature, 0.0);
+        assert_eq!(warmer.config.temperature, 0.7);
+    }
 }
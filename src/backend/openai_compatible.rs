@@ -12,18 +12,45 @@ pub(crate) struct OpenAICompatibleChatMessage {
     pub content: OpenAICompatibleMessageContent,
 }
 
+/// Convert chat history into API messages, led by a `system` message when
+/// the client has a system prompt.
 pub(crate) fn convert_openai_compatible_chat_messages(
     messages: &[ChatMessage],
     provider_name: &str,
+    system_prompt: Option<&str>,
 ) -> Result<Vec<OpenAICompatibleChatMessage>> {
-    messages
-        .iter()
-        .map(|msg| {
+    let system = system_prompt.map(|prompt| {
+        Ok(OpenAICompatibleChatMessage {
+            role: "system".to_string(),
+            content: OpenAICompatibleMessageContent::Text(prompt.to_string()),
+        })
+    });
+    system
+        .into_iter()
+        .chain(messages.iter().map(|msg| {
             Ok(OpenAICompatibleChatMessage {
                 role: msg.role.as_str().to_string(),
                 content: build_openai_compatible_message_content(msg, provider_name)?,
             })
-        })
+        }))
+        .collect()
+}
+
+/// A single-turn text conversation, led by a `system` message when the
+/// client has a system prompt.
+#[cfg(feature = "streaming")]
+pub(crate) fn text_messages(
+    system_prompt: Option<&str>,
+    prompt: &str,
+) -> Vec<OpenAICompatibleChatMessage> {
+    let text = |role: &str, text: &str| OpenAICompatibleChatMessage {
+        role: role.to_string(),
+        content: OpenAICompatibleMessageContent::Text(text.to_string()),
+    };
+    system_prompt
+        .map(|system| text("system", system))
+        .into_iter()
+        .chain([text("user", prompt)])
         .collect()
 }
 
@@ -81,7 +108,7 @@ mod tests {
     #[test]
     fn test_convert_openai_compatible_chat_messages_text_only() {
         let messages = vec![ChatMessage::user("hello")];
-        let converted = convert_openai_compatible_chat_messages(&messages, "OpenAI")
+        let converted = convert_openai_compatible_chat_messages(&messages, "OpenAI", None)
             .expect("conversion should succeed");
 
         assert_eq!(converted.len(), 1);
@@ -96,7 +123,7 @@ mod tests {
             "describe image",
             vec![MediaFile::from_bytes(b"abc", "image/png")],
         )];
-        let converted = convert_openai_compatible_chat_messages(&messages, "OpenAI")
+        let converted = convert_openai_compatible_chat_messages(&messages, "OpenAI", None)
             .expect("conversion should succeed");
 
         assert_eq!(converted.len(), 1);
@@ -105,6 +132,20 @@ mod tests {
         assert_eq!(json["content"][1]["type"], "image_url");
     }
 
+    #[test]
+    fn test_system_prompt_leads_the_messages() {
+        let messages = vec![ChatMessage::user("hello")];
+        let converted =
+            convert_openai_compatible_chat_messages(&messages, "OpenAI", Some("Be terse."))
+                .expect("conversion should succeed");
+
+        assert_eq!(converted.len(), 2);
+        assert_eq!(converted[0].role, "system");
+        let json = serde_json::to_value(&converted[0]).expect("serialization should succeed");
+        assert_eq!(json["content"], serde_json::json!("Be terse."));
+        assert_eq!(converted[1].role, "user");
+    }
+
     /// Build a minimal request with all `Option` fields set to `None`.
     fn request_with_none_options() -> OpenAICompatibleChatCompletionRequest {
         OpenAICompatibleChatCompletionRequest {
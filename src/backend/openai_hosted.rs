@@ -0,0 +1,295 @@
+//! Clients for hosts that serve open models behind an OpenAI-compatible API.
+//!
+//! Groq and Together.ai accept OpenAI's chat completions requests but not its
+//! `json_schema` response format, so their clients wrap an
+//! [`OpenAIClient`](crate::OpenAIClient) pinned to
+//! [`OutputStrategy::JsonMode`](crate::OutputStrategy::JsonMode):
+//! `response_format: {"type": "json_object"}` with the schema in a system
+//! message. [`define_hosted_client!`] generates such a wrapper, which only
+//! changes the base URL, the API key variable, the model type and the
+//! provider named in errors and logs.
+
+/// Generate a hosted client wrapping an `OpenAIClient`.
+///
+/// `model_info` maps one entry of the host's `/models` response to a
+/// [`ModelInfo`](crate::ModelInfo), or `None` for models that cannot chat.
+macro_rules! define_hosted_client {
+    (
+        $(#[$meta:meta])*
+        client: $client:ident,
+        model_type: $model:ty,
+        default_model: $default_model:expr,
+        provider: $provider:ident,
+        provider_name: $name:literal,
+        api_key_env: $env:literal,
+        base_url: $base_url:literal,
+        model_info: $model_info:expr $(,)?
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone)]
+        pub struct $client {
+            inner: $crate::OpenAIClient,
+        }
+
+        impl $client {
+            #[doc = concat!("Create a new ", $name, " client with the provided API key.")]
+            ///
+            /// # Errors
+            ///
+            /// Returns an authentication error if `api_key` is empty.
+            #[tracing::instrument(name = "hosted_client_new", skip(api_key), fields(provider = $name))]
+            pub fn new(api_key: impl Into<String>) -> $crate::Result<Self> {
+                let api_key = api_key.into();
+                if api_key.is_empty() {
+                    return Err($crate::RStructorError::api_error(
+                        $name,
+                        $crate::ApiErrorKind::AuthenticationFailed,
+                    ));
+                }
+                let model: $model = $default_model;
+                tracing::info!("Creating new {} client", $name);
+                Ok(Self {
+                    inner: $crate::OpenAIClient::new(api_key)?
+                        .serving_as($name)
+                        .base_url($base_url)
+                        .output_strategy($crate::OutputStrategy::JsonMode)
+                        .model(model.as_str()),
+                })
+            }
+
+            #[doc = concat!(
+                "Create a new ", $name, " client by reading the API key from the `",
+                $env, "` environment variable."
+            )]
+            ///
+            /// # Errors
+            ///
+            #[doc = concat!("Returns an error if `", $env, "` is not set.")]
+            pub fn from_env() -> $crate::Result<Self> {
+                let api_key = std::env::var($env).map_err(|_| {
+                    $crate::RStructorError::api_error(
+                        $name,
+                        $crate::ApiErrorKind::AuthenticationFailed,
+                    )
+                })?;
+                Self::new(api_key)
+            }
+
+            /// Validate the full configuration and construct the HTTP client;
+            /// see [`OpenAIClient::build`](crate::OpenAIClient::build).
+            ///
+            /// # Errors
+            ///
+            /// Returns [`RStructorError::ConfigError`](crate::RStructorError::ConfigError)
+            /// for an invalid configuration.
+            pub fn build(self) -> $crate::Result<Self> {
+                match self.inner.build() {
+                    Ok(inner) => Ok(Self { inner }),
+                    // The wrapped client names itself in its messages.
+                    Err($crate::RStructorError::ConfigError(message)) => Err(
+                        $crate::RStructorError::ConfigError(message.replacen("OpenAI", $name, 1)),
+                    ),
+                    Err(e) => Err(e),
+                }
+            }
+
+            /// Set the model to use. Accepts either a Model enum variant or a
+            /// string; unknown names become `Custom(name)`.
+            pub fn model<M: Into<$model>>(self, model: M) -> Self {
+                let model: $model = model.into();
+                Self {
+                    inner: self.inner.model(model.as_str()),
+                }
+            }
+
+            /// The configured model, qualified by provider.
+            pub fn model_id(&self) -> $crate::backend::ModelId {
+                $crate::backend::ModelId::new(
+                    $crate::backend::Provider::$provider,
+                    self.inner.model_id().name,
+                )
+            }
+
+            /// Capabilities of the configured model (context window, output
+            /// limit, media and thinking support).
+            pub fn capabilities(&self) -> $crate::backend::ProviderCapabilities {
+                $crate::backend::ProviderCapabilities::for_model(&self.model_id())
+            }
+
+            /// Requests and tokens of this client's structured calls so far,
+            /// counting every retry. Clones of the client share the same counts.
+            #[must_use]
+            pub fn usage_snapshot(&self) -> $crate::UsageSnapshot {
+                self.inner.usage_snapshot()
+            }
+        }
+
+        $crate::backend::openai_hosted::delegate_builder_methods! {
+            $client;
+            fn base_url(base_url: impl Into<String>);
+            fn temperature(temp: f32);
+            fn max_tokens(max: u32);
+            fn timeout(timeout: std::time::Duration);
+            fn max_retries(max_retries: usize);
+            fn no_retries();
+            fn capture_unknown_fields(enabled: bool);
+            fn explain(enabled: bool);
+            fn on_context_overflow(policy: $crate::ContextOverflow);
+            fn system_prompt(prompt: impl Into<String>);
+            fn schema_locale(locale: impl Into<String>);
+            fn description_catalog(catalog: $crate::DescriptionCatalog);
+            fn rate_limit(requests: $crate::Requests, tokens: $crate::Tokens);
+            fn rate_limiter(limiter: std::sync::Arc<$crate::RateLimiter>);
+            fn usage_tracker(tracker: $crate::UsageTracker);
+            fn max_request_bytes(bytes: usize);
+            fn without_request_size_limit();
+        }
+
+        #[async_trait::async_trait]
+        impl $crate::LLMClient for $client {
+            fn from_env() -> $crate::Result<Self> {
+                Self::from_env()
+            }
+
+            async fn materialize<T>(&self, prompt: &str) -> $crate::Result<T>
+            where
+                T: $crate::Instructor + serde::de::DeserializeOwned + Send + 'static,
+            {
+                self.inner.materialize(prompt).await
+            }
+
+            async fn materialize_with_media<T>(
+                &self,
+                prompt: &str,
+                media: &[$crate::MediaFile],
+            ) -> $crate::Result<T>
+            where
+                T: $crate::Instructor + serde::de::DeserializeOwned + Send + 'static,
+            {
+                self.inner.materialize_with_media(prompt, media).await
+            }
+
+            async fn materialize_with_metadata<T>(
+                &self,
+                prompt: &str,
+            ) -> $crate::Result<$crate::MaterializeResult<T>>
+            where
+                T: $crate::Instructor + serde::de::DeserializeOwned + Send + 'static,
+            {
+                self.inner.materialize_with_metadata(prompt).await
+            }
+
+            async fn generate(&self, prompt: &str) -> $crate::Result<String> {
+                self.inner.generate(prompt).await
+            }
+
+            async fn generate_with_media(
+                &self,
+                prompt: &str,
+                media: &[$crate::MediaFile],
+            ) -> $crate::Result<String> {
+                self.inner.generate_with_media(prompt, media).await
+            }
+
+            async fn generate_with_metadata(
+                &self,
+                prompt: &str,
+            ) -> $crate::Result<$crate::GenerateResult> {
+                self.inner.generate_with_metadata(prompt).await
+            }
+
+            async fn materialize_conversation<T>(
+                &self,
+                messages: &[$crate::ChatMessage],
+            ) -> $crate::Result<$crate::MaterializeResult<T>>
+            where
+                T: $crate::Instructor + serde::de::DeserializeOwned + Send + 'static,
+            {
+                self.inner.materialize_conversation(messages).await
+            }
+
+            async fn generate_conversation(
+                &self,
+                messages: &[$crate::ChatMessage],
+            ) -> $crate::Result<$crate::GenerateResult> {
+                self.inner.generate_conversation(messages).await
+            }
+
+            // `materialize_stream` and `materialize_iter` keep the trait's
+            // defaults: the wrapped client streams with `json_schema`.
+            #[cfg(feature = "streaming")]
+            fn generate_stream<'a>(
+                &'a self,
+                prompt: &'a str,
+            ) -> $crate::backend::streaming::TextStream<'a>
+            where
+                Self: Sync,
+            {
+                self.inner.generate_stream(prompt)
+            }
+
+            #[doc = concat!("Fetch the chat models available on ", $name, ".")]
+            async fn list_models(&self) -> $crate::Result<Vec<$crate::ModelInfo>> {
+                let models: Vec<$crate::ModelInfo> = self
+                    .inner
+                    .model_entries()
+                    .await?
+                    .iter()
+                    .filter_map($model_info)
+                    .collect();
+                tracing::debug!(count = models.len(), "Fetched {} models", $name);
+                Ok(models)
+            }
+        }
+
+        #[cfg(feature = "tools")]
+        #[async_trait::async_trait]
+        impl $crate::backend::tools::ToolRunner for $client {
+            async fn run_tool_loop(
+                &self,
+                system: Option<&str>,
+                prompt: &str,
+                media: &[$crate::MediaFile],
+                toolbox: &$crate::backend::tools::Toolbox,
+                max_iterations: usize,
+            ) -> $crate::Result<String> {
+                self.inner
+                    .run_tool_loop(system, prompt, media, toolbox, max_iterations)
+                    .await
+            }
+        }
+
+        impl From<&$model> for $crate::backend::ModelId {
+            fn from(model: &$model) -> Self {
+                $crate::backend::ModelId::new($crate::backend::Provider::$provider, model.as_str())
+            }
+        }
+
+        impl From<$crate::backend::ModelTier> for $model {
+            fn from(tier: $crate::backend::ModelTier) -> Self {
+                <$model>::from_string(tier.resolve($crate::backend::Provider::$provider).name)
+            }
+        }
+    };
+}
+
+/// Generate builder methods that forward to the wrapped `OpenAIClient`.
+macro_rules! delegate_builder_methods {
+    ($client:ident; $(fn $method:ident($($arg:ident: $ty:ty),*);)*) => {
+        impl $client {
+            $(
+                #[doc = concat!(
+                    "See [`OpenAIClient::", stringify!($method),
+                    "`](crate::OpenAIClient::", stringify!($method), ")."
+                )]
+                pub fn $method(self, $($arg: $ty),*) -> Self {
+                    Self {
+                        inner: self.inner.$method($($arg),*),
+                    }
+                }
+            )*
+        }
+    };
+}
+
+pub(crate) use {define_hosted_client, delegate_builder_methods};
@@ -0,0 +1,257 @@
+//! Recovering from prompts that exceed the model's context window.
+//!
+//! A [`ContextLengthExceeded`](crate::ApiErrorKind::ContextLengthExceeded)
+//! error is deterministic: resending the same prompt fails the same way, so the
+//! retry engine never retries it as-is. Configuring a [`ContextOverflow`] policy
+//! with a client's `on_context_overflow(..)` builder instead either shortens the
+//! prompt or moves the request to a model with a larger window.
+//!
+//! ```no_run
+//! # #[cfg(feature = "openai")]
+//! # fn main() -> rstructor::Result<()> {
+//! use rstructor::{ContextOverflow, OpenAIClient};
+//!
+//! let client = OpenAIClient::from_env()?
+//!     .model("gpt-5.4-mini")
+//!     .on_context_overflow(ContextOverflow::Escalate("gpt-5.5".into()));
+//! # let _ = client;
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "openai"))]
+//! # fn main() {}
+//! ```
+
+use std::future::Future;
+
+use tracing::warn;
+
+use crate::backend::utils::{generate_with_retry_with_initial_messages, take_retry_or_log};
+use crate::backend::{ChatMessage, ChatRole, MaterializeInternalOutput, ValidationFailureContext};
+use crate::error::{ApiErrorKind, RStructorError, Result};
+
+/// How many times [`ContextOverflow::Truncate`] halves the prompt before giving up.
+const MAX_OVERFLOW_TRUNCATIONS: usize = 3;
+
+/// Marker appended to a prompt shortened by [`ContextOverflow::Truncate`].
+const TRUNCATION_MARKER: &str = "\n\n[... input truncated to fit the context window ...]";
+
+/// What a client does when a structured request exceeds the context window.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ContextOverflow {
+    /// Return the [`ContextLengthExceeded`](crate::ApiErrorKind::ContextLengthExceeded)
+    /// error (the default).
+    #[default]
+    Fail,
+    /// Keep the first half of the longest user message and try again, up to
+    /// three times. Suits inputs whose beginning carries
+    /// the information, such as documents with front matter; the tail is lost.
+    Truncate,
+    /// Send the request once more to this larger-context model.
+    Escalate(String),
+}
+
+tokio::task_local! {
+    /// Model the current request was escalated to by [`ContextOverflow::Escalate`].
+    static MODEL_OVERRIDE: String;
+}
+
+/// The model to send the current request to instead of the configured one,
+/// set while an escalated request is in flight.
+pub(crate) fn model_override() -> Option<String> {
+    MODEL_OVERRIDE.try_with(Clone::clone).ok()
+}
+
+fn is_context_overflow(err: &RStructorError) -> bool {
+    matches!(
+        err.api_error_kind(),
+        Some(ApiErrorKind::ContextLengthExceeded { .. })
+    )
+}
+
+/// Halve the longest user message, keeping its beginning. Returns `false`
+/// when there is nothing left worth truncating.
+fn truncate_longest_user_message(messages: &mut [ChatMessage]) -> bool {
+    let Some(message) = messages
+        .iter_mut()
+        .filter(|m| m.role == ChatRole::User)
+        .max_by_key(|m| m.content.len())
+    else {
+        return false;
+    };
+    let content = message
+        .content
+        .strip_suffix(TRUNCATION_MARKER)
+        .unwrap_or(&message.content);
+    if content.len() < 2 * TRUNCATION_MARKER.len() {
+        return false;
+    }
+    let keep = content.floor_char_boundary(content.len() / 2);
+    message.content = format!("{}{}", &content[..keep], TRUNCATION_MARKER);
+    true
+}
+
+/// Run the retry engine, applying `policy` when the request overflows the
+/// context window.
+pub(crate) async fn generate_with_overflow_policy<F, Fut, T>(
+    mut generate_fn: F,
+    initial_messages: Vec<ChatMessage>,
+    max_retries: Option<usize>,
+    policy: &ContextOverflow,
+) -> Result<MaterializeInternalOutput<T>>
+where
+    F: FnMut(Vec<ChatMessage>) -> Fut,
+    Fut: Future<
+        Output = std::result::Result<
+            MaterializeInternalOutput<T>,
+            (RStructorError, Option<ValidationFailureContext>),
+        >,
+    >,
+{
+    let result = generate_with_retry_with_initial_messages(
+        &mut generate_fn,
+        initial_messages.clone(),
+        max_retries,
+    )
+    .await;
+    match policy {
+        ContextOverflow::Fail => result,
+        ContextOverflow::Truncate => {
+            let mut result = result;
+            let mut messages = initial_messages;
+            for truncation in 1..=MAX_OVERFLOW_TRUNCATIONS {
+                match result {
+                    Err(err) if is_context_overflow(&err) => {
+                        if !truncate_longest_user_message(&mut messages) || !take_retry_or_log(&err)
+                        {
+                            return Err(err);
+                        }
+                        warn!(
+                            truncation,
+                            error = %err,
+                            "Prompt exceeds the context window; retrying with the longest message halved"
+                        );
+                        result = generate_with_retry_with_initial_messages(
+                            &mut generate_fn,
+                            messages.clone(),
+                            max_retries,
+                        )
+                        .await;
+                    }
+                    done => return done,
+                }
+            }
+            result
+        }
+        ContextOverflow::Escalate(model) => match result {
+            Err(err) if is_context_overflow(&err) && take_retry_or_log(&err) => {
+                warn!(
+                    model = %model,
+                    error = %err,
+                    "Prompt exceeds the context window; retrying on a larger-context model"
+                );
+                MODEL_OVERRIDE
+                    .scope(
+                        model.clone(),
+                        generate_with_retry_with_initial_messages(
+                            generate_fn,
+                            initial_messages,
+                            max_retries,
+                        ),
+                    )
+                    .await
+            }
+            done => done,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    fn overflow() -> (RStructorError, Option<ValidationFailureContext>) {
+        (
+            RStructorError::api_error(
+                "Test",
+                ApiErrorKind::ContextLengthExceeded {
+                    details: "too long".into(),
+                    provider_error: None,
+                },
+            ),
+            None,
+        )
+    }
+
+    #[tokio::test]
+    async fn truncate_halves_the_prompt_until_it_fits() {
+        let seen = Mutex::new(Vec::new());
+        let output = generate_with_overflow_policy(
+            |messages: Vec<ChatMessage>| {
+                let len = messages[0].content.len();
+                seen.lock().unwrap().push(len);
+                async move {
+                    if len > 400 {
+                        Err(overflow())
+                    } else {
+                        Ok(MaterializeInternalOutput::new(len, String::new(), None))
+                    }
+                }
+            },
+            vec![ChatMessage::user("x".repeat(1000))],
+            None,
+            &ContextOverflow::Truncate,
+        )
+        .await
+        .unwrap();
+
+        let seen = seen.into_inner().unwrap();
+        assert_eq!(seen.len(), 3);
+        assert_eq!(seen[1], 500 + TRUNCATION_MARKER.len());
+        assert_eq!(output.data, 250 + TRUNCATION_MARKER.len());
+    }
+
+    #[tokio::test]
+    async fn escalate_reruns_once_on_the_larger_model() {
+        let models = Mutex::new(Vec::new());
+        let output = generate_with_overflow_policy(
+            |_messages: Vec<ChatMessage>| {
+                let model = model_override();
+                models.lock().unwrap().push(model.clone());
+                async move {
+                    match model {
+                        Some(model) => {
+                            Ok(MaterializeInternalOutput::new(model, String::new(), None))
+                        }
+                        None => Err(overflow()),
+                    }
+                }
+            },
+            vec![ChatMessage::user("long prompt")],
+            Some(2),
+            &ContextOverflow::Escalate("big-model".into()),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(output.data, "big-model");
+        // The overflow was not retried on the original model.
+        assert_eq!(
+            models.into_inner().unwrap(),
+            [None, Some("big-model".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn fail_returns_the_overflow_error() {
+        let err = generate_with_overflow_policy::<_, _, ()>(
+            |_messages: Vec<ChatMessage>| async { Err(overflow()) },
+            vec![ChatMessage::user("long prompt")],
+            Some(3),
+            &ContextOverflow::Fail,
+        )
+        .await
+        .unwrap_err();
+        assert!(is_context_overflow(&err));
+    }
+}
@@ -0,0 +1,150 @@
+//! Request size limits checked before a request is sent.
+//!
+//! Providers reject requests over a fixed size with a 413, but only after the
+//! whole body, often megabytes of base64 images or PDFs, has been uploaded.
+//! Clients check the serialized body against their `max_request_bytes(..)`
+//! first and fail locally with [`RStructorError::RequestTooLarge`], naming the
+//! part of the request that takes up most of it.
+
+use serde_json::Value;
+
+use crate::error::{PayloadComponent, RStructorError, Result};
+
+/// Request keys whose value is the output schema or tool definitions:
+/// OpenAI-compatible `response_format` and `tools`, Anthropic and Gemini
+/// `tools`, Gemini `generationConfig.responseSchema`/`responseJsonSchema` and
+/// Ollama's `format`.
+const SCHEMA_KEYS: &[&str] = &[
+    "response_format",
+    "tools",
+    "responseSchema",
+    "responseJsonSchema",
+    "format",
+];
+
+/// Bytes of a request body taken up by the schema and by media; the rest is
+/// the prompt.
+#[derive(Debug, Default, PartialEq)]
+struct Sizes {
+    schema: usize,
+    media: usize,
+}
+
+/// Fail with [`RStructorError::RequestTooLarge`] if `body` is over `limit`.
+pub(crate) fn check_request_size(provider: &str, limit: usize, body: &[u8]) -> Result<()> {
+    if body.len() <= limit {
+        return Ok(());
+    }
+    let mut sizes = Sizes::default();
+    if let Ok(value) = serde_json::from_slice::<Value>(body) {
+        measure(&value, None, &mut sizes);
+    }
+    let prompt = body.len().saturating_sub(sizes.schema + sizes.media);
+    let (component, component_bytes) = [
+        (PayloadComponent::Prompt, prompt),
+        (PayloadComponent::Schema, sizes.schema),
+        (PayloadComponent::Media, sizes.media),
+    ]
+    .into_iter()
+    .max_by_key(|(_, bytes)| *bytes)
+    .expect("three components");
+    Err(RStructorError::RequestTooLarge {
+        provider: provider.to_string(),
+        bytes: body.len(),
+        limit,
+        component,
+        component_bytes,
+    })
+}
+
+fn measure(value: &Value, key: Option<&str>, sizes: &mut Sizes) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                if SCHEMA_KEYS.contains(&key.as_str()) && (value.is_object() || value.is_array()) {
+                    sizes.schema += serde_json::to_vec(value).map_or(0, |bytes| bytes.len());
+                } else {
+                    measure(value, Some(key), sizes);
+                }
+            }
+        }
+        // Array items keep the array's key, so each of Ollama's `images` counts
+        Value::Array(items) => {
+            for item in items {
+                measure(item, key, sizes);
+            }
+        }
+        Value::String(text) if is_media(key, text) => sizes.media += text.len(),
+        _ => {}
+    }
+}
+
+/// Base64 media: Anthropic and Gemini `data`, OpenAI `file_data`, Ollama
+/// `images`, and `data:` URLs wherever they appear.
+fn is_media(key: Option<&str>, text: &str) -> bool {
+    matches!(key, Some("data" | "file_data" | "images")) || text.starts_with("data:")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sizes(body: &Value) -> Sizes {
+        let mut sizes = Sizes::default();
+        measure(body, None, &mut sizes);
+        sizes
+    }
+
+    #[test]
+    fn media_is_found_in_every_provider_shape() {
+        let image = "A".repeat(100);
+        let openai = json!({ "messages": [{ "content": [
+            { "type": "image_url", "image_url": { "url": format!("data:image/png;base64,{image}") } }
+        ]}]});
+        assert_eq!(sizes(&openai).media, 122);
+        let anthropic = json!({ "messages": [{ "content": [
+            { "type": "image", "source": { "type": "base64", "data": image } }
+        ]}]});
+        assert_eq!(sizes(&anthropic).media, 100);
+        let gemini = json!({ "contents": [{ "parts": [{ "inlineData": { "data": image } }] }] });
+        assert_eq!(sizes(&gemini).media, 100);
+        let ollama = json!({ "messages": [{ "images": [image, image] }] });
+        assert_eq!(sizes(&ollama).media, 200);
+    }
+
+    #[test]
+    fn schemas_and_tools_count_as_schema() {
+        let body = json!({
+            "response_format": { "type": "json_schema" },
+            "tools": [{ "name": "t" }],
+            "generationConfig": { "responseSchema": { "type": "object" } },
+            "messages": [{ "content": "hi", "input_audio": { "format": "wav" } }]
+        });
+        assert_eq!(
+            sizes(&body),
+            Sizes {
+                schema: 22 + 14 + 17,
+                media: 0
+            }
+        );
+    }
+
+    #[test]
+    fn the_largest_component_is_reported() {
+        let body = json!({
+            "messages": [{ "content": "x".repeat(50) }],
+            "response_format": { "schema": "y".repeat(500) }
+        })
+        .to_string();
+        assert!(check_request_size("OpenAI", body.len(), body.as_bytes()).is_ok());
+        let err = check_request_size("OpenAI", 100, body.as_bytes()).unwrap_err();
+        let RStructorError::RequestTooLarge {
+            component, limit, ..
+        } = err
+        else {
+            panic!("{err:?}");
+        };
+        assert_eq!((component, limit), (PayloadComponent::Schema, 100));
+    }
+}
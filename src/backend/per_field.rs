@@ -0,0 +1,365 @@
+//! Field-by-field extraction for models without a JSON mode.
+//!
+//! Weak or text-only models often can't produce a whole JSON object that
+//! matches a schema, but can answer a narrow question about one value.
+//! [`LLMClient::materialize_per_field`](crate::LLMClient::materialize_per_field)
+//! asks for each property of the schema in its own plain-text prompt, picks
+//! the value out of the reply by the property's type (the first integer in
+//! the reply for an integer, one of the listed options for an enum, ...), and
+//! assembles and validates the struct locally. It costs one call per field,
+//! plus one more for each reply the value couldn't be found in.
+
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Number, Value};
+use tracing::{debug, warn};
+
+use crate::backend::LLMClient;
+use crate::error::{RStructorError, Result};
+use crate::model::Instructor;
+use crate::parsing::find_json_candidates;
+
+/// Replies that mean "not stated" for an optional field.
+const NO_VALUE: &[&str] = &["", "none", "null", "n/a", "unknown", "not stated"];
+
+pub(crate) async fn materialize_per_field<C, T>(client: &C, prompt: &str) -> Result<T>
+where
+    C: LLMClient + Sync + ?Sized,
+    T: Instructor + DeserializeOwned + Send + 'static,
+{
+    let schema = T::schema().inline().to_json();
+    let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+        return Err(RStructorError::Unsupported(
+            "per-field extraction needs a struct with named fields".to_string(),
+        ));
+    };
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|names| names.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    let mut object = Map::new();
+    for (name, property) in properties {
+        let field = Field {
+            name,
+            property,
+            required: required.contains(&name.as_str()),
+        };
+        if let Some(value) = ask_field(client, prompt, &field).await? {
+            object.insert(name.clone(), value);
+        }
+    }
+
+    let value: T = serde_json::from_value(Value::Object(object))?;
+    value.validate()?;
+    Ok(value)
+}
+
+struct Field<'a> {
+    name: &'a str,
+    property: &'a Value,
+    required: bool,
+}
+
+/// Ask for one field, re-asking once if its value can't be found in the reply.
+async fn ask_field<C>(client: &C, prompt: &str, field: &Field<'_>) -> Result<Option<Value>>
+where
+    C: LLMClient + Sync + ?Sized,
+{
+    let question = field_prompt(prompt, field);
+    let reply = client.generate(&question).await?;
+    if let Some(value) = read_field(&reply, field) {
+        return Ok(value);
+    }
+    debug!(field = field.name, reply = %reply, "No value found, re-asking");
+    let retry = format!(
+        "{question}\n\nYour previous reply was:\n{reply}\n\n\
+         That didn't contain a usable value. Reply with the value only."
+    );
+    let reply = client.generate(&retry).await?;
+    match read_field(&reply, field) {
+        Some(value) => Ok(value),
+        None if !field.required => {
+            warn!(
+                field = field.name,
+                "No value found, leaving optional field unset"
+            );
+            Ok(None)
+        }
+        None => Err(RStructorError::ValidationError(format!(
+            "no value for `{}` in the model's reply: {reply}",
+            field.name
+        ))),
+    }
+}
+
+fn field_prompt(prompt: &str, field: &Field<'_>) -> String {
+    let mut question = format!(
+        "{prompt}\n\nFrom the text above, give only the value of `{}`",
+        field.name
+    );
+    if let Some(description) = field.property.get("description").and_then(Value::as_str) {
+        question.push_str(&format!(" ({description})"));
+    }
+    question.push_str(".\n");
+    question.push_str(&type_hint(field.property));
+    if !field.required {
+        question.push_str("\nIf the text doesn't say, reply none.");
+    }
+    question.push_str("\nReply with the value alone, without any explanation.");
+    question
+}
+
+fn type_hint(property: &Value) -> String {
+    if let Some(options) = enum_options(property) {
+        let options: Vec<String> = options.iter().map(display_option).collect();
+        return format!("It must be one of: {}.", options.join(", "));
+    }
+    match primary_type(property) {
+        Some("string") => "Reply with the text itself, without quotes.".to_string(),
+        Some("integer") => "It is a whole number.".to_string(),
+        Some("number") => "It is a number.".to_string(),
+        Some("boolean") => "Reply true or false.".to_string(),
+        _ => format!("Reply with JSON matching this schema: {property}"),
+    }
+}
+
+/// The value of `field` in `reply`: `Some(None)` for an optional field the
+/// reply says isn't stated, `None` if the reply has no usable value.
+fn read_field(reply: &str, field: &Field<'_>) -> Option<Option<Value>> {
+    let text = strip_label(unquote(reply.trim()), field.name);
+    if !field.required && NO_VALUE.contains(&text.to_ascii_lowercase().as_str()) {
+        return Some(None);
+    }
+    let value = if let Some(options) = enum_options(field.property) {
+        find_option(text, &options)
+    } else {
+        match primary_type(field.property) {
+            Some("string") => (!text.is_empty()).then(|| Value::String(text.to_string())),
+            Some("integer") => find_number(text, false),
+            Some("number") => find_number(text, true),
+            Some("boolean") => find_boolean(text),
+            _ => find_json(text),
+        }
+    };
+    value.map(Some)
+}
+
+/// The values an enum property allows: its `enum` list, or the `const` of
+/// each `oneOf`/`anyOf` branch (how an enum with variant descriptions is
+/// written). `None` if the property isn't an enum.
+fn enum_options(property: &Value) -> Option<Vec<Value>> {
+    if let Some(options) = property.get("enum").and_then(Value::as_array) {
+        return Some(options.clone());
+    }
+    let branches = ["oneOf", "anyOf"]
+        .iter()
+        .find_map(|key| property.get(*key).and_then(Value::as_array))?;
+    let mut options = Vec::new();
+    for branch in branches {
+        match branch.get("const") {
+            Some(option) => options.push(option.clone()),
+            None if branch.get("type").and_then(Value::as_str) == Some("null") => {}
+            None => return None,
+        }
+    }
+    (!options.is_empty()).then_some(options)
+}
+
+/// The schema's `type`, or its first non-null one when it lists several.
+fn primary_type(property: &Value) -> Option<&str> {
+    match property.get("type")? {
+        Value::String(ty) => Some(ty),
+        Value::Array(types) => types
+            .iter()
+            .filter_map(Value::as_str)
+            .find(|t| *t != "null"),
+        _ => None,
+    }
+}
+
+fn display_option(option: &Value) -> String {
+    match option {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn unquote(text: &str) -> &str {
+    ['"', '\'', '`']
+        .iter()
+        .find_map(|q| text.strip_prefix(*q)?.strip_suffix(*q))
+        .unwrap_or(text)
+        .trim()
+}
+
+/// `text` without a leading `name:` label.
+fn strip_label<'a>(text: &'a str, name: &str) -> &'a str {
+    let label = text
+        .split_once(':')
+        .filter(|(label, _)| label.trim().trim_matches('`').eq_ignore_ascii_case(name));
+    match label {
+        Some((_, rest)) => unquote(rest.trim()),
+        None => text,
+    }
+}
+
+/// The option the reply names: an exact match, else the one mentioned first.
+fn find_option(text: &str, options: &[Value]) -> Option<Value> {
+    let lower = text.to_lowercase();
+    let names: Vec<String> = options
+        .iter()
+        .map(|o| display_option(o).to_lowercase())
+        .collect();
+    if let Some(i) = names.iter().position(|name| *name == lower) {
+        return Some(options[i].clone());
+    }
+    names
+        .iter()
+        .enumerate()
+        .filter_map(|(i, name)| Some((lower.find(name.as_str())?, usize::MAX - name.len(), i)))
+        .min()
+        .map(|(_, _, i)| options[i].clone())
+}
+
+/// The first number in `text`: `-?\d+`, plus `(\.\d+)?([eE][-+]?\d+)?` for
+/// fractional numbers.
+fn find_number(text: &str, fractional: bool) -> Option<Value> {
+    let bytes = text.as_bytes();
+    let start = bytes.iter().position(u8::is_ascii_digit)?;
+    let start = if start > 0 && bytes[start - 1] == b'-' {
+        start - 1
+    } else {
+        start
+    };
+    let digits = |from: usize| {
+        from + bytes[from..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count()
+    };
+    let mut end = digits(start + usize::from(bytes[start] == b'-'));
+    if fractional {
+        if bytes.get(end) == Some(&b'.') && bytes.get(end + 1).is_some_and(u8::is_ascii_digit) {
+            end = digits(end + 1);
+        }
+        if matches!(bytes.get(end), Some(b'e' | b'E')) {
+            let sign = usize::from(matches!(bytes.get(end + 1), Some(b'+' | b'-')));
+            if bytes.get(end + 1 + sign).is_some_and(u8::is_ascii_digit) {
+                end = digits(end + 1 + sign);
+            }
+        }
+        let number: f64 = text[start..end].parse().ok()?;
+        return Number::from_f64(number).map(Value::Number);
+    }
+    text[start..end].parse::<i64>().ok().map(Value::from)
+}
+
+/// The first yes/no word in `text`.
+fn find_boolean(text: &str) -> Option<Value> {
+    text.split(|c: char| !c.is_alphanumeric()).find_map(|word| {
+        match word.to_ascii_lowercase().as_str() {
+            "true" | "yes" => Some(Value::Bool(true)),
+            "false" | "no" => Some(Value::Bool(false)),
+            _ => None,
+        }
+    })
+}
+
+/// The reply as JSON, or the first JSON value embedded in it.
+fn find_json(text: &str) -> Option<Value> {
+    serde_json::from_str(text).ok().or_else(|| {
+        find_json_candidates(text)
+            .first()
+            .and_then(|candidate| serde_json::from_str(candidate).ok())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn read(reply: &str, property: Value, required: bool) -> Option<Option<Value>> {
+        read_field(
+            reply,
+            &Field {
+                name: "year",
+                property: &property,
+                required,
+            },
+        )
+    }
+
+    #[test]
+    fn values_are_found_by_type() {
+        let integer = json!({ "type": "integer" });
+        assert_eq!(
+            read("It came out in 1979.", integer.clone(), true),
+            Some(Some(json!(1979)))
+        );
+        assert_eq!(
+            read("year: -40", integer.clone(), true),
+            Some(Some(json!(-40)))
+        );
+        assert_eq!(read("I'm not sure", integer, true), None);
+        assert_eq!(
+            read("About 2.5e3 or so", json!({ "type": "number" }), true),
+            Some(Some(json!(2500.0)))
+        );
+        assert_eq!(
+            read("Yes, it was.", json!({ "type": "boolean" }), true),
+            Some(Some(json!(true)))
+        );
+        assert_eq!(
+            read("\"Alien\"", json!({ "type": "string" }), true),
+            Some(Some(json!("Alien")))
+        );
+        assert_eq!(
+            read(
+                "Probably [\"a\", \"b\"].",
+                json!({ "type": "array", "items": { "type": "string" } }),
+                true
+            ),
+            Some(Some(json!(["a", "b"])))
+        );
+    }
+
+    #[test]
+    fn enums_match_exactly_then_by_first_mention() {
+        let property = json!({ "type": "string", "enum": ["Drama", "Sci-Fi", "Horror"] });
+        assert_eq!(
+            read("sci-fi", property.clone(), true),
+            Some(Some(json!("Sci-Fi")))
+        );
+        assert_eq!(
+            read("It's Horror, with some Drama", property.clone(), true),
+            Some(Some(json!("Horror")))
+        );
+        assert_eq!(read("Comedy", property, true), None);
+    }
+
+    #[test]
+    fn described_enums_are_read_from_their_const_branches() {
+        let property = json!({
+            "type": "string",
+            "oneOf": [
+                { "const": "Drama", "description": "Serious, character-driven stories" },
+                { "const": "Sci-Fi" }
+            ]
+        });
+        assert_eq!(type_hint(&property), "It must be one of: Drama, Sci-Fi.");
+        assert_eq!(
+            read("sci-fi", property.clone(), true),
+            Some(Some(json!("Sci-Fi")))
+        );
+        assert_eq!(read("Comedy", property, true), None);
+    }
+
+    #[test]
+    fn optional_fields_accept_none() {
+        let property = json!({ "type": "integer" });
+        assert_eq!(read("None", property.clone(), false), Some(None));
+        assert_eq!(read("None", property, true), None);
+    }
+}
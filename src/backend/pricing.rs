@@ -0,0 +1,319 @@
+//! Per-model token prices, for turning [`TokenUsage`] into dollars.
+//!
+//! [`TokenUsage::estimate_cost`] looks the model up in a built-in table of list
+//! prices in USD per million tokens. An entry covers the model itself and its
+//! dated snapshots (`gpt-4o` also prices `gpt-4o-2024-08-06`), and the longest
+//! matching entry wins. Cheaper or pricier variants (`-mini`, `-fast`, `-pro`
+//! and the like) are only priced by an entry of their own, never by the
+//! model they are a variant of. Models the table does not know, including anything
+//! released after this version, have no estimate rather than a guess.
+//!
+//! Prices change more often than releases ship, so the table can be
+//! overridden at runtime: [`set_model_pricing`] for a single model, or
+//! [`load_litellm_pricing`] / [`refresh_pricing`] for LiteLLM's community
+//! maintained pricing file. Overrides take precedence over the built-in table.
+//!
+//! Estimates use the standard rate for every token. Discounts for cached
+//! prompt tokens or batch requests, and tiered pricing for long prompts, are
+//! not applied.
+//!
+//! ```
+//! use rstructor::TokenUsage;
+//!
+//! let usage = TokenUsage::new("gpt-4o-mini", 20_000, 1_000);
+//! let cost = usage.estimate_cost().unwrap();
+//! assert!((cost.total_cost - 0.0036).abs() < 1e-9);
+//! ```
+
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+
+use serde_json::Value;
+
+use crate::backend::usage::TokenUsage;
+
+/// URL of LiteLLM's pricing file, fetched by [`refresh_pricing`].
+pub const LITELLM_PRICING_URL: &str =
+    "https://raw.githubusercontent.com/BerriAI/litellm/main/model_prices_and_context_window.json";
+
+/// The price of a model in USD per million tokens.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelPricing {
+    /// USD per million input (prompt) tokens.
+    pub input_per_mtok: f64,
+    /// USD per million output (completion) tokens, including reasoning tokens.
+    pub output_per_mtok: f64,
+}
+
+/// The estimated cost of a call in USD, from [`TokenUsage::estimate_cost`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CostEstimate {
+    /// Cost of the input tokens.
+    pub input_cost: f64,
+    /// Cost of the output tokens.
+    pub output_cost: f64,
+    /// `input_cost + output_cost`.
+    pub total_cost: f64,
+}
+
+const fn priced(
+    model: &'static str,
+    input_per_mtok: f64,
+    output_per_mtok: f64,
+) -> (&'static str, ModelPricing) {
+    (
+        model,
+        ModelPricing {
+            input_per_mtok,
+            output_per_mtok,
+        },
+    )
+}
+
+/// List prices at the time of this release, from each provider's pricing
+/// page (openai.com/api/pricing, claude.com/pricing, docs.x.ai/docs/models,
+/// ai.google.dev/gemini-api/docs/pricing). Every [`ModelTier`](crate::ModelTier)
+/// default has an entry.
+const PRICES: &[(&str, ModelPricing)] = &[
+    // OpenAI
+    priced("gpt-5.5-pro", 30.0, 180.0),
+    priced("gpt-5.5", 5.0, 30.0),
+    priced("gpt-5.4-mini", 0.75, 4.5),
+    priced("gpt-5.2-pro", 21.0, 168.0),
+    priced("gpt-5.2", 1.75, 14.0),
+    priced("gpt-5.1", 1.25, 10.0),
+    priced("gpt-5-pro", 15.0, 120.0),
+    priced("gpt-5", 1.25, 10.0),
+    priced("gpt-5-mini", 0.25, 2.0),
+    priced("gpt-5-nano", 0.05, 0.4),
+    priced("gpt-4.1", 2.0, 8.0),
+    priced("gpt-4.1-mini", 0.4, 1.6),
+    priced("gpt-4.1-nano", 0.1, 0.4),
+    priced("gpt-4o", 2.5, 10.0),
+    priced("gpt-4o-mini", 0.15, 0.6),
+    priced("gpt-4-turbo", 10.0, 30.0),
+    priced("gpt-4", 30.0, 60.0),
+    priced("gpt-3.5-turbo", 0.5, 1.5),
+    // Anthropic
+    priced("claude-opus-4-8", 5.0, 25.0),
+    priced("claude-opus-4-7", 5.0, 25.0),
+    priced("claude-opus-4-6", 5.0, 25.0),
+    priced("claude-opus-4-5", 5.0, 25.0),
+    priced("claude-opus-4-1", 15.0, 75.0),
+    priced("claude-opus-4-20250514", 15.0, 75.0),
+    priced("claude-sonnet-4", 3.0, 15.0),
+    priced("claude-haiku-4-5", 1.0, 5.0),
+    // Gemini (prompts up to 200k tokens)
+    priced("gemini-3.5-flash", 0.5, 3.0),
+    priced("gemini-3.1-pro-preview", 2.0, 12.0),
+    priced("gemini-3.1-flash-lite", 0.25, 1.5),
+    priced("gemini-3-pro-preview", 2.0, 12.0),
+    priced("gemini-3-flash-preview", 0.5, 3.0),
+    priced("gemini-2.5-pro", 1.25, 10.0),
+    priced("gemini-2.5-flash", 0.3, 2.5),
+    priced("gemini-2.5-flash-lite", 0.1, 0.4),
+    priced("gemini-2.0-flash", 0.1, 0.4),
+    priced("gemini-2.0-flash-lite", 0.075, 0.3),
+    // Grok
+    priced("grok-4.3", 1.25, 2.5),
+    priced("grok-4.20", 2.0, 6.0),
+    priced("grok-4", 3.0, 15.0),
+    priced("grok-4-fast", 0.2, 0.5),
+    priced("grok-4-1-fast", 0.2, 0.5),
+];
+
+/// Prices set at runtime, by model name.
+fn overrides() -> &'static RwLock<HashMap<String, ModelPricing>> {
+    static OVERRIDES: OnceLock<RwLock<HashMap<String, ModelPricing>>> = OnceLock::new();
+    OVERRIDES.get_or_init(Default::default)
+}
+
+/// Name segments that mark a variant priced differently from its model.
+const VARIANTS: &[&str] = &["mini", "nano", "lite", "fast", "pro", "turbo"];
+
+/// Whether the entry `key` covers `model`: the model itself, or a snapshot of
+/// it named `{key}-..` that is not one of its [`VARIANTS`].
+fn covers(key: &str, model: &str) -> bool {
+    model.strip_prefix(key).is_some_and(|rest| {
+        rest.is_empty()
+            || rest
+                .strip_prefix('-')
+                .is_some_and(|rest| !rest.split('-').any(|part| VARIANTS.contains(&part)))
+    })
+}
+
+/// The longest entry covering `model`.
+fn longest_match<'a>(
+    entries: impl Iterator<Item = (&'a str, ModelPricing)>,
+    model: &str,
+) -> Option<ModelPricing> {
+    entries
+        .filter(|(key, _)| covers(key, model))
+        .max_by_key(|(key, _)| key.len())
+        .map(|(_, pricing)| pricing)
+}
+
+impl ModelPricing {
+    /// The price of `model`: a runtime override if one covers it, otherwise
+    /// the built-in table's entry, or `None` if neither knows the model.
+    pub fn for_model(model: &str) -> Option<Self> {
+        let overrides = overrides().read().unwrap_or_else(|e| e.into_inner());
+        longest_match(overrides.iter().map(|(k, v)| (k.as_str(), *v)), model)
+            .or_else(|| longest_match(PRICES.iter().copied(), model))
+    }
+
+    /// The cost of `input_tokens` and `output_tokens` at this price.
+    pub fn cost(&self, input_tokens: u64, output_tokens: u64) -> CostEstimate {
+        let input_cost = input_tokens as f64 * self.input_per_mtok / 1_000_000.0;
+        let output_cost = output_tokens as f64 * self.output_per_mtok / 1_000_000.0;
+        CostEstimate {
+            input_cost,
+            output_cost,
+            total_cost: input_cost + output_cost,
+        }
+    }
+}
+
+impl TokenUsage {
+    /// The cost of this call in USD, or `None` if the model's price is not
+    /// known. See the [`pricing`](crate::backend::pricing) module for where
+    /// prices come from.
+    pub fn estimate_cost(&self) -> Option<CostEstimate> {
+        ModelPricing::for_model(&self.model)
+            .map(|pricing| pricing.cost(self.input_tokens, self.output_tokens))
+    }
+}
+
+/// Price `model` (and its snapshots) at `pricing` from now on, overriding the
+/// built-in table.
+pub fn set_model_pricing(model: impl Into<String>, pricing: ModelPricing) {
+    overrides()
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(model.into(), pricing);
+}
+
+/// Load prices from LiteLLM's `model_prices_and_context_window.json` format
+/// as overrides, returning how many models were priced.
+///
+/// Entries without both `input_cost_per_token` and `output_cost_per_token`
+/// are skipped. A provider prefix in the key (`gemini/gemini-2.5-pro`) is
+/// dropped, so entries apply to the plain model name; where the file also
+/// has an unprefixed entry for the model, that one wins.
+pub fn load_litellm_pricing(json: &Value) -> usize {
+    let Some(entries) = json.as_object() else {
+        return 0;
+    };
+    let mut prices = HashMap::new();
+    for (key, entry) in entries {
+        let per_token = |field: &str| entry.get(field).and_then(Value::as_f64);
+        let (Some(input), Some(output)) = (
+            per_token("input_cost_per_token"),
+            per_token("output_cost_per_token"),
+        ) else {
+            continue;
+        };
+        let pricing = ModelPricing {
+            input_per_mtok: input * 1_000_000.0,
+            output_per_mtok: output * 1_000_000.0,
+        };
+        match key.rsplit_once('/') {
+            Some((_, model)) => {
+                prices.entry(model.to_string()).or_insert(pricing);
+            }
+            None => {
+                prices.insert(key.clone(), pricing);
+            }
+        }
+    }
+    let count = prices.len();
+    overrides()
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .extend(prices);
+    count
+}
+
+/// Fetch LiteLLM's pricing file from [`LITELLM_PRICING_URL`] and load it with
+/// [`load_litellm_pricing`], returning how many models were priced.
+///
+/// # Errors
+///
+/// Returns [`RStructorError::HttpError`](crate::RStructorError::HttpError) if
+/// the file can't be fetched or is not JSON.
+#[cfg(feature = "_client")]
+pub async fn refresh_pricing() -> crate::Result<usize> {
+    refresh_pricing_from(LITELLM_PRICING_URL).await
+}
+
+/// [`refresh_pricing`] from a mirror or pinned copy of the file at `url`.
+///
+/// # Errors
+///
+/// Returns [`RStructorError::HttpError`](crate::RStructorError::HttpError) if
+/// the file can't be fetched or is not JSON.
+#[cfg(feature = "_client")]
+pub async fn refresh_pricing_from(url: &str) -> crate::Result<usize> {
+    let json: Value = reqwest::get(url).await?.error_for_status()?.json().await?;
+    let count = load_litellm_pricing(&json);
+    tracing::debug!(models = count, url, "Loaded model pricing");
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshots_share_their_model_price() {
+        let dated = ModelPricing::for_model("gpt-4o-2024-08-06").unwrap();
+        assert_eq!(dated, ModelPricing::for_model("gpt-4o").unwrap());
+        assert_eq!(
+            ModelPricing::for_model("gpt-4o-mini")
+                .unwrap()
+                .input_per_mtok,
+            0.15
+        );
+    }
+
+    #[test]
+    fn a_version_prefix_is_not_a_match() {
+        assert!(ModelPricing::for_model("gpt-5.9").is_none());
+        assert!(ModelPricing::for_model("claude-opus-4-9").is_none());
+        assert!(ModelPricing::for_model("llama3").is_none());
+    }
+
+    #[test]
+    fn variants_are_not_priced_as_their_model() {
+        assert_eq!(
+            ModelPricing::for_model("grok-4-1-fast-reasoning"),
+            ModelPricing::for_model("grok-4-fast")
+        );
+        assert!(ModelPricing::for_model("gpt-4o-nano").is_none());
+        assert!(ModelPricing::for_model("claude-sonnet-4-6-lite").is_none());
+        let reasoning = ModelPricing::for_model("grok-4.20-0309-reasoning").unwrap();
+        assert_eq!(reasoning.input_per_mtok, 2.0);
+    }
+
+    #[test]
+    fn overrides_take_precedence() {
+        set_model_pricing(
+            "gemini-2.0-flash-lite-test",
+            ModelPricing {
+                input_per_mtok: 1.0,
+                output_per_mtok: 2.0,
+            },
+        );
+        let cost = TokenUsage::new("gemini-2.0-flash-lite-test-001", 500_000, 250_000)
+            .estimate_cost()
+            .unwrap();
+        assert_eq!(
+            cost,
+            CostEstimate {
+                input_cost: 0.5,
+                output_cost: 0.5,
+                total_cost: 1.0
+            }
+        );
+    }
+}
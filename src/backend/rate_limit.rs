@@ -0,0 +1,387 @@
+//! Request and token budgets enforced before requests are sent.
+//!
+//! Providers limit both requests and tokens per minute, and going over either
+//! turns every extra request into a 429 that costs a retry. A [`RateLimiter`]
+//! keeps a client under those budgets by delaying requests until the budget
+//! has room, by [`Priority`](crate::Priority) and then in arrival order, so a
+//! [background](crate::scoped_priority) backfill queued on a shared limiter
+//! does not hold up interactive requests. When the provider answers 429 or 503 with a
+//! `retry-after`, every request through the limiter waits it out, not just the
+//! one that was refused.
+//!
+//! Set one per client with `.rate_limit(..)`, or share one between clients
+//! that draw on the same account with `.rate_limiter(Arc<RateLimiter>)`:
+//!
+//! ```no_run
+//! # #[cfg(feature = "openai")]
+//! # fn main() -> rstructor::Result<()> {
+//! use std::sync::Arc;
+//! use rstructor::{OpenAIClient, RateLimiter, Requests, Tokens};
+//!
+//! let limiter = Arc::new(
+//!     RateLimiter::new()
+//!         .requests(Requests::per_minute(500))
+//!         .tokens(Tokens::per_minute(200_000)),
+//! );
+//! let fast = OpenAIClient::from_env()?.model("gpt-4o-mini").rate_limiter(limiter.clone());
+//! let smart = OpenAIClient::from_env()?.model("gpt-4o").rate_limiter(limiter);
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "openai"))]
+//! # fn main() {}
+//! ```
+
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use super::limiter::{Gate, Priority};
+
+/// A request budget: at most `count` requests per `period`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Requests {
+    count: u64,
+    period: Duration,
+}
+
+impl Requests {
+    /// At most `count` requests in any `period`.
+    pub fn new(count: u64, period: Duration) -> Self {
+        Self { count, period }
+    }
+
+    /// At most `count` requests per second.
+    pub fn per_second(count: u64) -> Self {
+        Self::new(count, Duration::from_secs(1))
+    }
+
+    /// At most `count` requests per minute.
+    pub fn per_minute(count: u64) -> Self {
+        Self::new(count, Duration::from_secs(60))
+    }
+
+    /// At most `count` requests per hour.
+    pub fn per_hour(count: u64) -> Self {
+        Self::new(count, Duration::from_secs(3600))
+    }
+}
+
+/// A token budget: at most `count` tokens per `period`.
+///
+/// A request is charged, when it is sent, the tokens estimated from its body
+/// (see [`estimate_tokens`](crate::context::estimate_tokens)) plus the output
+/// tokens it allows (`max_tokens` or the provider's equivalent), which is how
+/// providers count requests against their own token limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tokens {
+    count: u64,
+    period: Duration,
+}
+
+impl Tokens {
+    /// At most `count` tokens in any `period`.
+    pub fn new(count: u64, period: Duration) -> Self {
+        Self { count, period }
+    }
+
+    /// At most `count` tokens per second.
+    pub fn per_second(count: u64) -> Self {
+        Self::new(count, Duration::from_secs(1))
+    }
+
+    /// At most `count` tokens per minute.
+    pub fn per_minute(count: u64) -> Self {
+        Self::new(count, Duration::from_secs(60))
+    }
+
+    /// At most `count` tokens per hour.
+    pub fn per_hour(count: u64) -> Self {
+        Self::new(count, Duration::from_secs(3600))
+    }
+}
+
+/// A token bucket holding up to `capacity`, refilled continuously so that a
+/// full `period` restores all of it.
+#[derive(Debug)]
+struct Bucket {
+    capacity: f64,
+    per_second: f64,
+    available: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn new(count: u64, period: Duration) -> Self {
+        let capacity = count.max(1) as f64;
+        Self {
+            capacity,
+            per_second: capacity / period.as_secs_f64().max(f64::EPSILON),
+            available: capacity,
+            updated: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.available = (self.available + elapsed * self.per_second).min(self.capacity);
+        self.updated = now;
+    }
+
+    /// How long until `cost` can be taken. A cost above the capacity only
+    /// needs a full bucket, and leaves it in debt.
+    fn wait(&self, cost: f64) -> Duration {
+        let missing = cost.min(self.capacity) - self.available;
+        if missing <= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(missing / self.per_second)
+        }
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    requests: Option<Bucket>,
+    tokens: Option<Bucket>,
+    /// No request is sent before this, from the latest `retry-after`.
+    paused_until: Option<Instant>,
+}
+
+/// Request and token budgets shared by every request of the clients it is
+/// set on. Cheap to share via `Arc`; see the [module docs](self).
+#[derive(Debug)]
+pub struct RateLimiter {
+    requests: Option<Requests>,
+    tokens: Option<Tokens>,
+    /// Waiting requests take turns by priority, then in arrival order.
+    turns: Arc<Gate>,
+    state: Mutex<State>,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RateLimiter {
+    /// A limiter without budgets, which still honors `retry-after`.
+    pub fn new() -> Self {
+        Self {
+            requests: None,
+            tokens: None,
+            turns: Arc::new(Gate::new(1)),
+            state: Mutex::new(State {
+                requests: None,
+                tokens: None,
+                paused_until: None,
+            }),
+        }
+    }
+
+    /// Limit the number of requests.
+    #[must_use]
+    pub fn requests(mut self, requests: Requests) -> Self {
+        self.requests = Some(requests);
+        self.lock().requests = Some(Bucket::new(requests.count, requests.period));
+        self
+    }
+
+    /// Limit the number of tokens.
+    #[must_use]
+    pub fn tokens(mut self, tokens: Tokens) -> Self {
+        self.tokens = Some(tokens);
+        self.lock().tokens = Some(Bucket::new(tokens.count, tokens.period));
+        self
+    }
+
+    /// The request budget, if any.
+    pub fn request_budget(&self) -> Option<Requests> {
+        self.requests
+    }
+
+    /// The token budget, if any.
+    pub fn token_budget(&self) -> Option<Tokens> {
+        self.tokens
+    }
+
+    /// How much longer requests are held back by a provider's `retry-after`.
+    pub fn paused_for(&self) -> Option<Duration> {
+        let until = self.lock().paused_until?;
+        let remaining = until.saturating_duration_since(Instant::now());
+        (!remaining.is_zero()).then_some(remaining)
+    }
+
+    /// Hold back every request until `duration` from now, unless an earlier
+    /// pause already lasts longer.
+    pub fn pause(&self, duration: Duration) {
+        let until = Instant::now() + duration;
+        let mut state = self.lock();
+        if state.paused_until.is_none_or(|paused| paused < until) {
+            state.paused_until = Some(until);
+        }
+    }
+
+    /// Wait until a request costing `tokens` fits the budgets, then charge it.
+    /// Requests waiting at a higher `priority` are charged first.
+    pub(crate) async fn acquire(&self, tokens: u64, priority: Priority) {
+        let _turn = Arc::clone(&self.turns).acquire(priority).await;
+        loop {
+            let wait = {
+                let mut guard = self.lock();
+                let state = &mut *guard;
+                let now = Instant::now();
+                let paused = state
+                    .paused_until
+                    .map_or(Duration::ZERO, |until| until.saturating_duration_since(now));
+                let mut wait = paused;
+                for (bucket, cost) in [
+                    (state.requests.as_mut(), 1.0),
+                    (state.tokens.as_mut(), tokens as f64),
+                ] {
+                    if let Some(bucket) = bucket {
+                        bucket.refill(now);
+                        wait = wait.max(bucket.wait(cost));
+                    }
+                }
+                if wait.is_zero() {
+                    if let Some(bucket) = state.requests.as_mut() {
+                        bucket.available -= 1.0;
+                    }
+                    if let Some(bucket) = state.tokens.as_mut() {
+                        bucket.available -= tokens as f64;
+                    }
+                    return;
+                }
+                wait
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Whether the budgets can ever admit a request: no zero counts or
+    /// periods.
+    pub(crate) fn is_satisfiable(&self) -> bool {
+        let budgets = [
+            self.requests.map(|r| (r.count, r.period)),
+            self.tokens.map(|t| (t.count, t.period)),
+        ];
+        budgets
+            .into_iter()
+            .flatten()
+            .all(|(count, period)| count > 0 && !period.is_zero())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Tokens a request body is charged: its estimated size plus the output
+/// tokens it allows.
+pub(crate) fn request_cost(body: &[u8]) -> u64 {
+    let text = String::from_utf8_lossy(body);
+    let input = crate::context::estimate_tokens(&text) as u64;
+    let output = serde_json::from_slice::<serde_json::Value>(body)
+        .ok()
+        .and_then(|body| {
+            [
+                "/max_tokens",
+                "/max_completion_tokens",
+                "/generationConfig/maxOutputTokens",
+                "/options/num_predict",
+            ]
+            .iter()
+            .find_map(|pointer| body.pointer(pointer)?.as_u64())
+        })
+        .unwrap_or(0);
+    input + output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn requests_beyond_the_budget_wait_for_the_refill() {
+        let limiter = RateLimiter::new().requests(Requests::new(2, Duration::from_millis(200)));
+        let start = Instant::now();
+        for _ in 0..2 {
+            limiter.acquire(0, Priority::Normal).await;
+        }
+        assert!(start.elapsed() < Duration::from_millis(50));
+        limiter.acquire(0, Priority::Normal).await;
+        assert!(start.elapsed() >= Duration::from_millis(90));
+    }
+
+    #[tokio::test]
+    async fn interactive_requests_are_charged_before_queued_background_ones() {
+        let limiter =
+            Arc::new(RateLimiter::new().requests(Requests::new(1, Duration::from_millis(50))));
+        let order = Arc::new(Mutex::new(Vec::new()));
+        // The first request empties the bucket and holds its turn while it
+        // waits for the refill, so everything after it queues.
+        limiter.acquire(0, Priority::Normal).await;
+
+        let mut tasks = Vec::new();
+        for (priority, label) in [
+            (Priority::Normal, "normal"),
+            (Priority::Background, "background-1"),
+            (Priority::Background, "background-2"),
+            (Priority::Interactive, "interactive"),
+        ] {
+            let (limiter, order) = (Arc::clone(&limiter), Arc::clone(&order));
+            tasks.push(tokio::spawn(async move {
+                limiter.acquire(0, priority).await;
+                order.lock().unwrap().push(label);
+            }));
+            // Let the task join the queue before the next one.
+            tokio::task::yield_now().await;
+        }
+        for task in tasks {
+            task.await.unwrap();
+        }
+        assert_eq!(
+            *order.lock().unwrap(),
+            ["normal", "interactive", "background-1", "background-2"]
+        );
+    }
+
+    #[tokio::test]
+    async fn a_pause_holds_back_every_request() {
+        let limiter = RateLimiter::new();
+        limiter.pause(Duration::from_millis(100));
+        limiter.pause(Duration::from_millis(10));
+        assert!(limiter.paused_for().unwrap() > Duration::from_millis(50));
+        let start = Instant::now();
+        limiter.acquire(0, Priority::Normal).await;
+        assert!(start.elapsed() >= Duration::from_millis(90));
+        assert_eq!(limiter.paused_for(), None);
+    }
+
+    #[test]
+    fn oversized_requests_only_need_a_full_bucket() {
+        let bucket = Bucket::new(100, Duration::from_secs(60));
+        assert_eq!(bucket.wait(1_000.0), Duration::ZERO);
+    }
+
+    #[test]
+    fn output_allowance_is_added_to_the_estimate() {
+        let body = br#"{"model":"m","max_tokens":100}"#;
+        assert_eq!(request_cost(body), 8 + 100);
+        let gemini = br#"{"generationConfig":{"maxOutputTokens":50}}"#;
+        assert_eq!(request_cost(gemini), 11 + 50);
+    }
+
+    #[test]
+    fn zero_budgets_are_unsatisfiable() {
+        let zero_requests = RateLimiter::new().requests(Requests::per_minute(0));
+        assert!(!zero_requests.is_satisfiable());
+        let zero_period = RateLimiter::new().tokens(Tokens::new(10, Duration::ZERO));
+        assert!(!zero_period.is_satisfiable());
+        assert!(
+            RateLimiter::new()
+                .tokens(Tokens::per_minute(10))
+                .is_satisfiable()
+        );
+    }
+}
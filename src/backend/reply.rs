@@ -0,0 +1,211 @@
+//! Turning a model's reply into a validated value, and the feedback a rejected
+//! reply is re-asked with.
+//!
+//! Shared by the live providers (through `parse_validate_and_create_output`
+//! and the retry engine) and by `MockClient`, so a scripted reply is accepted,
+//! rejected and re-asked exactly as the same text from a real model would be.
+
+use serde::de::DeserializeOwned;
+use tracing::{debug, error, warn};
+
+use crate::backend::messages::ValidationFailureContext;
+use crate::error::{ApiErrorKind, RStructorError};
+use crate::model::Instructor;
+use crate::parsing::{extract_json_from_markdown, find_json_candidates, is_truncated_json};
+use crate::schema::{split_explanation, unwrap_object_root, unwrapped_root_schema};
+
+/// Test seam: the single point where a reply can be tampered with before it
+/// is parsed. With `test-util`, an injected malformed-JSON fault cuts the
+/// reply off here (see [`FaultInjectingClient`](crate::FaultInjectingClient));
+/// other builds return it untouched.
+#[cfg(feature = "test-util")]
+pub(crate) fn fault_injection_seam(mut reply: String) -> String {
+    crate::backend::fault::malform_reply(&mut reply);
+    reply
+}
+
+/// Test seam: the single point where a reply can be tampered with before it
+/// is parsed; `test-util` builds inject faults here.
+#[cfg(not(feature = "test-util"))]
+pub(crate) fn fault_injection_seam(reply: String) -> String {
+    reply
+}
+
+/// Parse a reply into a validated `T`.
+///
+/// Structurally truncated output (see [`is_truncated_json`]) is reported as
+/// [`ApiErrorKind::UnexpectedResponse`] with details `"truncated output"`
+/// instead of a serde error at an arbitrary byte offset. The rationale property
+/// and the object an array or scalar root was wrapped in are stripped before
+/// [`parse_and_validate_response`]. Every rejection carries a
+/// [`ValidationFailureContext`], so the retry loop re-asks the model.
+#[allow(clippy::result_large_err)]
+pub(crate) fn parse_reply<T>(
+    raw_response: &str,
+    provider_name: &str,
+) -> std::result::Result<T, (RStructorError, Option<ValidationFailureContext>)>
+where
+    T: Instructor + DeserializeOwned,
+{
+    if is_truncated_json(extract_json_from_markdown(raw_response)) {
+        warn!(
+            content_len = raw_response.len(),
+            "{} returned truncated JSON output", provider_name
+        );
+        return Err((
+            RStructorError::api_error(
+                provider_name,
+                ApiErrorKind::UnexpectedResponse {
+                    details: "truncated output".to_string(),
+                },
+            ),
+            Some(ValidationFailureContext::new(
+                TRUNCATED_OUTPUT_FEEDBACK,
+                raw_response,
+            )),
+        ));
+    }
+
+    // Parse without the rationale property so it never reaches the domain type,
+    // and without the object an array or scalar root was wrapped in
+    let schema = T::schema();
+    let stripped = split_explanation(&schema, raw_response).map(|(json, _)| json);
+    let reply = stripped.as_deref().unwrap_or(raw_response);
+    let unwrapped = unwrap_object_root(&schema, reply);
+    parse_and_validate_response::<T>(unwrapped.as_deref().unwrap_or(reply))
+}
+
+/// Parse a raw JSON response and validate it against the Instructor trait.
+///
+/// This function handles:
+/// 1. Extracting the JSON from a Markdown code fence or surrounding prose
+/// 2. JSON parsing with detailed error messages; when the response holds several
+///    JSON values, the first that deserializes and validates as `T` is used
+/// 3. Custom validation via the Instructor trait
+///
+/// # Arguments
+///
+/// * `raw_response` - The raw JSON string from the LLM
+///
+/// # Returns
+///
+/// The parsed and validated data, or an error with validation context
+#[allow(clippy::result_large_err)]
+pub(crate) fn parse_and_validate_response<T>(
+    raw_response: &str,
+) -> std::result::Result<T, (RStructorError, Option<ValidationFailureContext>)>
+where
+    T: Instructor + DeserializeOwned,
+{
+    // Parse the JSON content into our target type. If it does not parse or does
+    // not validate, consider the other JSON values embedded in the response.
+    let mut coerced = false;
+    let parsed = match serde_json::from_str::<T>(extract_json_from_markdown(raw_response)) {
+        Ok(parsed) if parsed.validate().is_ok() => return Ok(parsed),
+        Ok(parsed) => Ok(select_json_candidate(raw_response).unwrap_or(parsed)),
+        Err(e) => select_json_candidate(raw_response)
+            .or_else(|| {
+                let parsed = coerce_reply(raw_response)?;
+                coerced = true;
+                Some(parsed)
+            })
+            .ok_or(e),
+    };
+    let result: T = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            let error_msg = format!(
+                "Failed to parse response as JSON: {}\nPartial JSON: {}",
+                e, raw_response
+            );
+            error!(
+                error = %e,
+                content = %raw_response,
+                "JSON parsing error"
+            );
+            return Err((
+                RStructorError::ValidationError(error_msg.clone()),
+                Some(ValidationFailureContext::new(
+                    error_msg,
+                    raw_response.to_string(),
+                )),
+            ));
+        }
+    };
+
+    // Apply any custom validation (business logic beyond schema)
+    if let Err(e) = result.validate() {
+        error!(error = ?e, "Custom validation failed");
+        let error_msg = e.to_string();
+        return Err((
+            e,
+            Some(ValidationFailureContext::new(
+                error_msg,
+                raw_response.to_string(),
+            )),
+        ));
+    }
+
+    #[cfg(feature = "_client")]
+    if coerced {
+        crate::backend::telemetry::note_coercion();
+    }
+    Ok(result)
+}
+
+/// Pick among several JSON values embedded in a response: the first that
+/// deserializes into `T` and passes validation, else the first that deserializes.
+fn select_json_candidate<T>(raw_response: &str) -> Option<T>
+where
+    T: Instructor + DeserializeOwned,
+{
+    let mut fallback = None;
+    for candidate in find_json_candidates(raw_response) {
+        let Ok(parsed) = serde_json::from_str::<T>(candidate) else {
+            continue;
+        };
+        if parsed.validate().is_ok() {
+            debug!("Selected embedded JSON candidate that validates");
+            return Some(parsed);
+        }
+        fallback.get_or_insert(parsed);
+    }
+    fallback
+}
+
+/// Deserialize a reply that failed only on type mismatches, after
+/// [`Schema::coerce`](crate::Schema::coerce) has repaired them. Saves a re-ask
+/// for `"42"` in an integer field and the like.
+fn coerce_reply<T>(raw_response: &str) -> Option<T>
+where
+    T: Instructor + DeserializeOwned,
+{
+    let mut value: serde_json::Value =
+        serde_json::from_str(extract_json_from_markdown(raw_response)).ok()?;
+    let coerced = unwrapped_root_schema(&T::schema()).coerce(&mut value);
+    if coerced == 0 {
+        return None;
+    }
+    let parsed = serde_json::from_value(value).ok()?;
+    debug!(coerced, "Deserialized after coercing mismatched values");
+    Some(parsed)
+}
+
+/// Feedback sent back to the model when its JSON was cut off mid-document.
+const TRUNCATED_OUTPUT_FEEDBACK: &str = "Your previous response was truncated before the JSON document was complete (unclosed braces/brackets or an unterminated string). Return the complete JSON document, keeping string values concise so the whole response fits within the output limit.";
+
+/// Builds the user-role feedback message sent back to the LLM when a response
+/// fails schema or custom validation (the re-ask prompt).
+///
+/// The array guidance deliberately avoids any minimum-count instruction.
+/// Telling the model to include "at least N" items induces fabrication: a
+/// model that correctly extracted one item (or zero) from the source material
+/// would be instructed to invent more. Instead, the prompt requires arrays to
+/// contain only entries actually supported by the input — a single-item or
+/// empty array is valid when that is all the data supports.
+pub(crate) fn validation_retry_feedback(error_message: &str) -> String {
+    format!(
+        "Your previous response contained validation errors. Please provide a complete, valid JSON response that includes ALL required fields and follows the schema exactly.\n\nError details:\n{}\n\nPlease fix the issues in your response. Make sure to:\n1. Include ALL required fields exactly as specified in the schema\n2. For enum fields, use EXACTLY one of the allowed values from the description\n3. CRITICAL: For arrays where items.type = 'object':\n   - You MUST provide an array of OBJECTS, not strings or primitive values\n   - Each object must be a complete JSON object with all its required fields\n4. Arrays must contain ONLY items actually supported by the input: an array may have a single item or be empty if that is what the data supports — NEVER invent entries to pad an array\n5. Verify all nested objects have their complete structure\n6. Follow ALL type specifications (string, number, boolean, array, object)",
+        error_message
+    )
+}
@@ -1,7 +1,8 @@
 //! A fluent request builder over any [`LLMClient`].
 //!
-//! Attach context with `with_system`, images with `with_media`, and tools with
-//! `with_tools`, then choose a terminal: `materialize` (structured), `generate`
+//! Attach context with `with_system`, images with `with_media`, tools with
+//! `with_tools`, and a tenant's own provider API key with `with_api_key_for_call`, then
+//! choose a terminal: `materialize` (structured), `generate`
 //! (text), `run` (text, using tools if attached), or — with the `streaming`
 //! feature — `materialize_iter` / `materialize_stream` / `generate_stream`.
 //!
@@ -18,9 +19,11 @@
 //! # Ok(()) }
 //! ```
 
+use std::future::Future;
+
 use serde::de::DeserializeOwned;
 
-use crate::backend::{LLMClient, MediaFile};
+use crate::backend::{LLMClient, MediaFile, Provider, scoped_api_keys};
 use crate::error::Result;
 use crate::model::Instructor;
 
@@ -29,6 +32,7 @@ pub struct Request<'a, C: ?Sized> {
     client: &'a C,
     system: Option<String>,
     media: Vec<MediaFile>,
+    api_keys: Vec<(Provider, String)>,
     #[cfg(feature = "tools")]
     tools: Option<&'a crate::backend::tools::Toolbox>,
     #[cfg(feature = "tools")]
@@ -41,6 +45,7 @@ impl<'a, C: ?Sized> Request<'a, C> {
             client,
             system: None,
             media: Vec::new(),
+            api_keys: Vec::new(),
             #[cfg(feature = "tools")]
             tools: None,
             #[cfg(feature = "tools")]
@@ -65,6 +70,17 @@ impl<'a, C: ?Sized> Request<'a, C> {
         self
     }
 
+    /// Authenticate this request's calls to `provider` with `api_key` instead
+    /// of the configured key, e.g. to bill a tenant's own provider account
+    /// through a shared client. Applies to every retry the request makes; set
+    /// one key per provider for clients that fall back across providers. See
+    /// [`scoped_api_key`](crate::scoped_api_key) to cover several calls.
+    #[must_use]
+    pub fn api_key(mut self, provider: Provider, api_key: impl Into<String>) -> Self {
+        self.api_keys.push((provider, api_key.into()));
+        self
+    }
+
     /// Attach a [`Toolbox`](crate::Toolbox); `run` will let the model call its
     /// tools. Requires the `tools` feature.
     #[cfg(feature = "tools")]
@@ -91,6 +107,15 @@ impl<'a, C: ?Sized> Request<'a, C> {
     }
 }
 
+/// Await `future` under the request's per-call API keys, if any were set.
+async fn with_keys<F: Future>(api_keys: Vec<(Provider, String)>, future: F) -> F::Output {
+    if api_keys.is_empty() {
+        future.await
+    } else {
+        scoped_api_keys(api_keys, future).await
+    }
+}
+
 impl<C: LLMClient + Sync + ?Sized> Request<'_, C> {
     /// Materialize a structured `T`, applying any attached system context and media.
     pub async fn materialize<T>(self, prompt: &str) -> Result<T>
@@ -98,23 +123,29 @@ impl<C: LLMClient + Sync + ?Sized> Request<'_, C> {
         T: Instructor + DeserializeOwned + Send + 'static,
     {
         let prompt = self.combined(prompt);
-        if self.media.is_empty() {
-            self.client.materialize(&prompt).await
-        } else {
-            self.client
-                .materialize_with_media(&prompt, &self.media)
-                .await
-        }
+        let call = async {
+            if self.media.is_empty() {
+                self.client.materialize(&prompt).await
+            } else {
+                self.client
+                    .materialize_with_media(&prompt, &self.media)
+                    .await
+            }
+        };
+        with_keys(self.api_keys.clone(), call).await
     }
 
     /// Generate raw text, applying any attached system context and media.
     pub async fn generate(self, prompt: &str) -> Result<String> {
         let prompt = self.combined(prompt);
-        if self.media.is_empty() {
-            self.client.generate(&prompt).await
-        } else {
-            self.client.generate_with_media(&prompt, &self.media).await
-        }
+        let call = async {
+            if self.media.is_empty() {
+                self.client.generate(&prompt).await
+            } else {
+                self.client.generate_with_media(&prompt, &self.media).await
+            }
+        };
+        with_keys(self.api_keys.clone(), call).await
     }
 }
 
@@ -131,8 +162,9 @@ impl<'a, C: LLMClient + Sync + ?Sized> Request<'a, C> {
         use futures_util::StreamExt;
         let combined = self.combined(prompt);
         let client = self.client;
+        let api_keys = self.api_keys;
         Box::pin(async_stream::try_stream! {
-            let mut inner = client.materialize_iter::<T>(&combined);
+            let mut inner = crate::backend::sync_scoped_api_keys(api_keys, || client.materialize_iter::<T>(&combined));
             while let Some(item) = inner.next().await {
                 yield item?;
             }
@@ -144,8 +176,9 @@ impl<'a, C: LLMClient + Sync + ?Sized> Request<'a, C> {
         use futures_util::StreamExt;
         let combined = self.combined(prompt);
         let client = self.client;
+        let api_keys = self.api_keys;
         Box::pin(async_stream::try_stream! {
-            let mut inner = client.generate_stream(&combined);
+            let mut inner = crate::backend::sync_scoped_api_keys(api_keys, || client.generate_stream(&combined));
             while let Some(chunk) = inner.next().await {
                 yield chunk?;
             }
@@ -164,8 +197,9 @@ impl<'a, C: LLMClient + Sync + ?Sized> Request<'a, C> {
         use futures_util::StreamExt;
         let combined = self.combined(prompt);
         let client = self.client;
+        let api_keys = self.api_keys;
         Box::pin(async_stream::try_stream! {
-            let mut inner = client.materialize_stream::<T>(&combined);
+            let mut inner = crate::backend::sync_scoped_api_keys(api_keys, || client.materialize_stream::<T>(&combined));
             while let Some(obj) = inner.next().await {
                 yield obj?;
             }
@@ -180,6 +214,11 @@ impl<C: crate::backend::tools::ToolRunner + LLMClient + Sync + ?Sized> Request<'
     /// initial user turn. With no tools attached this is equivalent to
     /// [`generate`](Self::generate).
     pub async fn run(self, prompt: &str) -> Result<String> {
+        let api_keys = self.api_keys.clone();
+        with_keys(api_keys, self.run_inner(prompt)).await
+    }
+
+    async fn run_inner(self, prompt: &str) -> Result<String> {
         match self.tools {
             Some(toolbox) => {
                 self.client
@@ -225,6 +264,16 @@ pub trait RequestExt: LLMClient {
         Request::new(self).media(media.to_vec())
     }
 
+    /// Start a request whose calls to `provider` authenticate with `api_key`
+    /// instead of the configured key (see [`Request::api_key`]).
+    fn with_api_key_for_call(
+        &self,
+        provider: Provider,
+        api_key: impl Into<String>,
+    ) -> Request<'_, Self> {
+        Request::new(self).api_key(provider, api_key)
+    }
+
     /// Start a request with a [`Toolbox`](crate::Toolbox); call `.run(prompt)` to
     /// run the agentic loop. Requires the `tools` feature.
     #[cfg(feature = "tools")]
@@ -0,0 +1,234 @@
+//! AWS Signature Version 4 request signing, for Bedrock.
+//!
+//! [`sign`] computes the `Authorization` header (plus `x-amz-date` and, for
+//! temporary credentials, `x-amz-security-token`) over the method, URL, the
+//! given headers and the exact body bytes, following
+//! <https://docs.aws.amazon.com/IAM/latest/UserGuide/create-signed-request.html>.
+//! Path segments are encoded twice, as every service but S3 expects, so a
+//! Bedrock model id such as `anthropic.claude-...-v1:0` is signed as `...v1%253A0`.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, Mac};
+use reqwest::Url;
+use sha2::{Digest, Sha256};
+
+const ALGORITHM: &str = "AWS4-HMAC-SHA256";
+
+/// Who signs, for which region and service, and when.
+pub(crate) struct SigningParams<'a> {
+    pub access_key_id: &'a str,
+    pub secret_access_key: &'a str,
+    /// Session token of temporary (STS) credentials.
+    pub session_token: Option<&'a str>,
+    pub region: &'a str,
+    pub service: &'a str,
+    pub time: SystemTime,
+}
+
+/// The headers to add to a request so that it is signed: `x-amz-date`,
+/// `x-amz-security-token` for temporary credentials, and `authorization`.
+///
+/// `headers` are signed alongside `host` and must be sent unchanged.
+pub(crate) fn sign(
+    params: &SigningParams<'_>,
+    method: &str,
+    url: &Url,
+    headers: &[(&str, &str)],
+    body: &[u8],
+) -> Vec<(&'static str, String)> {
+    let amz_date = amz_date(params.time);
+    let date = &amz_date[..8];
+
+    let host = match url.port() {
+        Some(port) => format!("{}:{port}", url.host_str().unwrap_or_default()),
+        None => url.host_str().unwrap_or_default().to_string(),
+    };
+    let mut signed: Vec<(String, String)> = headers
+        .iter()
+        .map(|(name, value)| (name.to_ascii_lowercase(), value.trim().to_string()))
+        .collect();
+    signed.push(("host".to_string(), host));
+    signed.push(("x-amz-date".to_string(), amz_date.clone()));
+    if let Some(token) = params.session_token {
+        signed.push(("x-amz-security-token".to_string(), token.to_string()));
+    }
+    signed.sort();
+
+    let canonical_headers: String = signed
+        .iter()
+        .map(|(name, value)| format!("{name}:{value}\n"))
+        .collect();
+    let signed_headers = signed
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>()
+        .join(";");
+    let canonical_request = format!(
+        "{method}\n{}\n{}\n{canonical_headers}\n{signed_headers}\n{}",
+        canonical_uri(url.path()),
+        canonical_query(url),
+        hex(&Sha256::digest(body)),
+    );
+
+    let scope = format!("{date}/{}/{}/aws4_request", params.region, params.service);
+    let string_to_sign = format!(
+        "{ALGORITHM}\n{amz_date}\n{scope}\n{}",
+        hex(&Sha256::digest(canonical_request.as_bytes()))
+    );
+    let key = [params.region, params.service, "aws4_request"].iter().fold(
+        hmac(
+            format!("AWS4{}", params.secret_access_key).as_bytes(),
+            date.as_bytes(),
+        ),
+        |key, part| hmac(&key, part.as_bytes()),
+    );
+    let signature = hex(&hmac(&key, string_to_sign.as_bytes()));
+
+    let mut added = vec![("x-amz-date", amz_date)];
+    if let Some(token) = params.session_token {
+        added.push(("x-amz-security-token", token.to_string()));
+    }
+    added.push((
+        "authorization",
+        format!(
+            "{ALGORITHM} Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+            params.access_key_id
+        ),
+    ));
+    added
+}
+
+/// Each segment of the (already encoded) path encoded again.
+fn canonical_uri(path: &str) -> String {
+    if path.is_empty() {
+        return "/".to_string();
+    }
+    path.split('/')
+        .map(uri_encode)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Query parameters encoded and sorted by name, then value.
+fn canonical_query(url: &Url) -> String {
+    let mut pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(name, value)| (uri_encode(&name), uri_encode(&value)))
+        .collect();
+    pairs.sort();
+    pairs
+        .iter()
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Percent-encode everything but RFC 3986 unreserved characters.
+pub(crate) fn uri_encode(text: &str) -> String {
+    text.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// `time` as `YYYYMMDDTHHMMSSZ` in UTC.
+fn amz_date(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    let (days, secs_of_day) = ((secs / 86_400) as i64, secs % 86_400);
+    // Civil date from days since the epoch (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}{month:02}{day:02}T{:02}{:02}{:02}Z",
+        secs_of_day / 3_600,
+        secs_of_day % 3_600 / 60,
+        secs_of_day % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn params(session_token: Option<&str>) -> SigningParams<'_> {
+        SigningParams {
+            access_key_id: "AKIDEXAMPLE",
+            secret_access_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            session_token,
+            region: "us-east-1",
+            service: "service",
+            // 2015-08-30T12:36:00Z
+            time: UNIX_EPOCH + Duration::from_secs(1_440_938_160),
+        }
+    }
+
+    #[test]
+    fn matches_the_aws_test_suite() {
+        // `get-vanilla` from the AWS SigV4 test suite
+        let url = Url::parse("https://example.amazonaws.com/").unwrap();
+        let headers = sign(&params(None), "GET", &url, &[], b"");
+        assert_eq!(headers[0], ("x-amz-date", "20150830T123600Z".to_string()));
+        assert_eq!(
+            headers[1].1,
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, \
+             SignedHeaders=host;x-amz-date, \
+             Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+        );
+    }
+
+    #[test]
+    fn session_tokens_are_sent_and_signed() {
+        let url = Url::parse("https://example.amazonaws.com/").unwrap();
+        let headers = sign(&params(Some("token")), "GET", &url, &[], b"");
+        assert_eq!(headers[1], ("x-amz-security-token", "token".to_string()));
+        assert!(
+            headers[2]
+                .1
+                .contains("SignedHeaders=host;x-amz-date;x-amz-security-token")
+        );
+    }
+
+    #[test]
+    fn paths_are_encoded_twice_and_queries_sorted() {
+        let url = Url::parse("https://h/model/meta.llama3-v1%3A0/converse?b=2&a=x%20y").unwrap();
+        assert_eq!(
+            canonical_uri(url.path()),
+            "/model/meta.llama3-v1%253A0/converse"
+        );
+        assert_eq!(canonical_query(&url), "a=x%20y&b=2");
+    }
+
+    #[test]
+    fn dates_are_utc() {
+        assert_eq!(amz_date(UNIX_EPOCH), "19700101T000000Z");
+        assert_eq!(
+            amz_date(UNIX_EPOCH + Duration::from_secs(951_782_400)),
+            "20000229T000000Z"
+        );
+    }
+}
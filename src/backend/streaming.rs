@@ -19,6 +19,7 @@
 //! This module is only compiled with the `streaming` feature.
 
 use std::future::Future;
+use std::marker::PhantomData;
 use std::pin::Pin;
 
 use async_stream::try_stream;
@@ -28,6 +29,7 @@ use serde_json::Value;
 
 use crate::error::{RStructorError, Result};
 use crate::model::Instructor;
+use crate::parsing::complete_json;
 
 /// A boxed stream of text deltas. Each item is either an incremental piece of the
 /// model's text output or a transport/decode error.
@@ -55,6 +57,93 @@ impl<T> StreamedObject<T> {
             StreamedObject::Partial(_) => None,
         }
     }
+
+    /// A typed view of the snapshot, if this is a
+    /// [`Partial`](StreamedObject::Partial) item.
+    pub fn partial(self) -> Option<Partial<T>> {
+        match self {
+            StreamedObject::Partial(value) => Some(Partial::new(value)),
+            StreamedObject::Complete(_) => None,
+        }
+    }
+}
+
+/// A snapshot of a `T` that is still being generated, read field by field.
+///
+/// Every field of `T` is effectively optional here: a field the model has not
+/// reached yet is absent, and [`field`](Self::field) returns `None` for it
+/// instead of failing the whole snapshot. Scalars appear once they are complete;
+/// strings and arrays grow from one snapshot to the next, so a UI can render
+/// them as they fill in.
+///
+/// ```
+/// use rstructor::{Partial, StreamedObject};
+/// use serde_json::json;
+///
+/// struct Movie; // stands in for a type deriving `Instructor`
+///
+/// let item = StreamedObject::<Movie>::Partial(json!({ "title": "Incep" }));
+/// let partial = item.partial().unwrap();
+/// assert_eq!(partial.field::<String>("title").as_deref(), Some("Incep"));
+/// assert_eq!(partial.field::<u16>("year"), None);
+/// ```
+pub struct Partial<T> {
+    value: Value,
+    _marker: PhantomData<fn() -> T>,
+}
+
+// Manual impls: `T` is only a marker, so these need no bounds on it.
+impl<T> Clone for Partial<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.value.clone())
+    }
+}
+
+impl<T> std::fmt::Debug for Partial<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Partial").field(&self.value).finish()
+    }
+}
+
+impl<T> Partial<T> {
+    /// Wrap a snapshot of a `T`'s JSON.
+    pub fn new(value: Value) -> Self {
+        Self {
+            value,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Whether `name` has started to arrive.
+    pub fn has(&self, name: &str) -> bool {
+        self.value.get(name).is_some()
+    }
+
+    /// The field `name` as an `F`, or `None` while it is absent or does not
+    /// yet deserialize as `F`.
+    pub fn field<F: DeserializeOwned>(&self, name: &str) -> Option<F> {
+        F::deserialize(self.value.get(name)?).ok()
+    }
+
+    /// The raw JSON snapshot.
+    pub fn as_value(&self) -> &Value {
+        &self.value
+    }
+
+    /// Consume the view, returning the raw JSON snapshot.
+    pub fn into_value(self) -> Value {
+        self.value
+    }
+}
+
+impl<T: DeserializeOwned> Partial<T> {
+    /// The snapshot as a `T`, once it has every required field.
+    ///
+    /// The value is not validated and its last string may still be cut short;
+    /// wait for [`StreamedObject::Complete`] for the final, validated value.
+    pub fn try_complete(&self) -> Option<T> {
+        T::deserialize(&self.value).ok()
+    }
 }
 
 /// One decoded SSE event of interest.
@@ -152,7 +241,7 @@ where
     F: Fn(&Value) -> Option<String> + Send + 'a,
 {
     object_stream_with(send, extract, |raw: &str| {
-        super::utils::parse_and_validate_response::<T>(raw).map_err(|(err, _ctx)| err)
+        super::reply::parse_and_validate_response::<T>(raw).map_err(|(err, _ctx)| err)
     })
 }
 
@@ -250,111 +339,6 @@ pub(crate) fn gemini_delta(event: &Value) -> Option<String> {
     if text.is_empty() { None } else { Some(text) }
 }
 
-/// Repair a possibly-truncated JSON prefix into a parseable JSON value.
-///
-/// Returns `Some(value)` only when the repaired text actually parses, so callers
-/// never see invalid JSON; when the prefix is too incomplete to safely complete
-/// (e.g. a half-written number) it returns `None` and the caller simply waits for
-/// more input. This is intended for emitting progressive snapshots of streamed
-/// structured output — the authoritative final parse always uses the raw buffer.
-pub(crate) fn complete_json(s: &str) -> Option<Value> {
-    let repaired = repair_json(s)?;
-    serde_json::from_str(&repaired).ok()
-}
-
-/// Best-effort completion of a truncated JSON prefix: close an open string, drop a
-/// dangling key/comma, and close any open objects/arrays. The result is validated
-/// by [`complete_json`] before use, so imperfect repairs are simply discarded.
-fn repair_json(s: &str) -> Option<String> {
-    let s = s.trim();
-    if s.is_empty() {
-        return None;
-    }
-
-    let mut out = String::with_capacity(s.len() + 8);
-    let mut stack: Vec<char> = Vec::new();
-    let mut in_string = false;
-    let mut escaped = false;
-
-    for c in s.chars() {
-        if in_string {
-            out.push(c);
-            if escaped {
-                escaped = false;
-            } else if c == '\\' {
-                escaped = true;
-            } else if c == '"' {
-                in_string = false;
-            }
-        } else {
-            match c {
-                '"' => {
-                    in_string = true;
-                    out.push(c);
-                }
-                '{' => {
-                    stack.push('{');
-                    out.push(c);
-                }
-                '[' => {
-                    stack.push('[');
-                    out.push(c);
-                }
-                '}' => {
-                    if stack.pop() != Some('{') {
-                        return None;
-                    }
-                    out.push(c);
-                }
-                ']' => {
-                    if stack.pop() != Some('[') {
-                        return None;
-                    }
-                    out.push(c);
-                }
-                _ => out.push(c),
-            }
-        }
-    }
-
-    // A trailing incomplete escape (`...\`) inside a string: drop the backslash.
-    if in_string && escaped {
-        out.pop();
-    }
-    // Close an open string.
-    if in_string {
-        out.push('"');
-    }
-
-    // Trim trailing structural debris that can't be completed: a dangling comma,
-    // or a dangling object key (`"key":` with no value yet).
-    loop {
-        let trimmed_len = out.trim_end().len();
-        out.truncate(trimmed_len);
-        if out.ends_with(',') {
-            out.pop();
-            continue;
-        }
-        if out.ends_with(':') {
-            // Drop the dangling `"key":` back to the previous `{` or `,`.
-            if let Some(cut) = out.rfind(['{', ',']) {
-                out.truncate(cut + 1);
-            } else {
-                return None;
-            }
-            continue;
-        }
-        break;
-    }
-
-    // Close any still-open containers, innermost first.
-    for &opener in stack.iter().rev() {
-        out.push(if opener == '{' { '}' } else { ']' });
-    }
-
-    Some(out)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -434,67 +418,6 @@ mod tests {
         );
     }
 
-    #[test]
-    fn complete_json_closes_open_string_and_object() {
-        assert_eq!(
-            complete_json(r#"{"name": "Ali"#).unwrap(),
-            json!({"name": "Ali"})
-        );
-    }
-
-    #[test]
-    fn complete_json_drops_dangling_key_and_comma() {
-        assert_eq!(complete_json(r#"{"a": 1, "b":"#).unwrap(), json!({"a": 1}));
-        assert_eq!(complete_json(r#"{"a": 1, "#).unwrap(), json!({"a": 1}));
-        assert_eq!(complete_json(r#"{"a": 1,"#).unwrap(), json!({"a": 1}));
-    }
-
-    #[test]
-    fn complete_json_closes_nested_and_arrays() {
-        assert_eq!(
-            complete_json(r#"{"items":[{"x":1},{"x":2"#).unwrap(),
-            json!({"items":[{"x":1},{"x":2}]})
-        );
-        assert_eq!(complete_json(r#"[1, 2, 3"#).unwrap(), json!([1, 2, 3]));
-        assert_eq!(complete_json(r#"[1, 2, "#).unwrap(), json!([1, 2]));
-    }
-
-    #[test]
-    fn complete_json_skips_incomplete_primitive() {
-        // A half-written number/keyword can't be safely completed → None.
-        assert!(complete_json(r#"{"a": tr"#).is_none());
-        assert!(complete_json(r#"{"a": 12."#).is_none());
-        assert!(complete_json("").is_none());
-    }
-
-    #[test]
-    fn complete_json_handles_escapes() {
-        assert_eq!(
-            complete_json(r#"{"s": "line\"#).unwrap(),
-            json!({"s": "line"})
-        );
-        assert_eq!(
-            complete_json(r#"{"s": "a\nb"#).unwrap(),
-            json!({"s": "a\nb"})
-        );
-    }
-
-    #[test]
-    fn complete_json_progressive_prefixes_converge() {
-        let full = r#"{"name":"Alice","age":30,"tags":["x","y"]}"#;
-        // Every prefix either yields None or a valid JSON value, and the full
-        // string yields the exact object.
-        for i in 1..=full.len() {
-            if let Some(v) = complete_json(&full[..i]) {
-                assert!(v.is_object() || v.is_array());
-            }
-        }
-        assert_eq!(
-            complete_json(full).unwrap(),
-            json!({"name":"Alice","age":30,"tags":["x","y"]})
-        );
-    }
-
     // --- SSE decoder edge cases ---
 
     #[test]
@@ -559,63 +482,36 @@ mod tests {
         );
     }
 
-    // --- complete_json edge cases ---
+    // --- StreamedObject / Partial helpers ---
 
-    #[test]
-    fn complete_json_rejects_truncated_unicode_escape() {
-        // The braces/quotes are balanced so repair produces a string, but the
-        // truncated `\u00` escape makes it invalid JSON → None.
-        assert!(complete_json(r#"{"s":"\u00"#).is_none());
+    #[derive(serde::Deserialize, Debug, PartialEq)]
+    struct Movie {
+        title: String,
+        year: u16,
     }
 
     #[test]
-    fn complete_json_rejects_unbalanced_or_extra_closers() {
-        // Extra `}` and a mismatched `]` can't be repaired.
-        assert!(complete_json(r#"{"a":1}}"#).is_none());
-        assert!(complete_json(r#"{"a":1]"#).is_none());
-    }
+    fn partial_reads_present_fields_and_skips_missing_ones() {
+        let partial = StreamedObject::<Movie>::Partial(json!({ "title": "Metro", "year": null }))
+            .partial()
+            .unwrap();
+        assert!(partial.has("title"));
+        assert!(!partial.has("director"));
+        assert_eq!(partial.field::<String>("title").as_deref(), Some("Metro"));
+        assert_eq!(partial.field::<u16>("year"), None);
+        assert_eq!(partial.try_complete(), None);
 
-    #[test]
-    fn complete_json_handles_odd_and_even_trailing_backslashes() {
-        // Even backslashes: `a\\` is a complete escaped backslash; the value is a
-        // single backslash. (`json!({"s": "a\\"})` is the string `a\`.)
-        assert_eq!(complete_json(r#"{"s":"a\\"#).unwrap(), json!({"s": "a\\"}));
-        // Odd backslashes: the dangling final `\` is an incomplete escape and is
-        // dropped, leaving the same completed value.
-        assert_eq!(complete_json(r#"{"s":"a\\\"#).unwrap(), json!({"s": "a\\"}));
-    }
-
-    #[test]
-    fn complete_json_rejects_dangling_minus_but_allows_negative_exponent() {
-        assert!(complete_json(r#"{"a":-"#).is_none());
+        let done = Partial::<Movie>::new(json!({ "title": "Metropolis", "year": 1927 }));
         assert_eq!(
-            complete_json(r#"{"a":-1.2e10"#).unwrap(),
-            json!({"a": -1.2e10})
+            done.try_complete(),
+            Some(Movie {
+                title: "Metropolis".into(),
+                year: 1927
+            })
         );
+        assert!(StreamedObject::Complete(42).partial().is_none());
     }
 
-    #[test]
-    fn complete_json_rejects_dangling_colon_without_container() {
-        // A dangling key/colon with no surrounding `{`/`,` to cut back to → None.
-        assert!(complete_json(r#""key":"#).is_none());
-        assert!(complete_json("x:").is_none());
-    }
-
-    #[test]
-    fn complete_json_passes_top_level_scalars_through() {
-        assert_eq!(complete_json("42").unwrap(), json!(42));
-        assert_eq!(complete_json("true").unwrap(), json!(true));
-        assert_eq!(complete_json(r#""hello"#).unwrap(), json!("hello"));
-        assert_eq!(complete_json("[1,2,3]").unwrap(), json!([1, 2, 3]));
-    }
-
-    #[test]
-    fn complete_json_trims_trailing_whitespace_and_comma() {
-        assert_eq!(complete_json("{\"a\":1,  \n  ").unwrap(), json!({"a": 1}));
-    }
-
-    // --- StreamedObject helper ---
-
     #[test]
     fn streamed_object_complete_accessor() {
         assert_eq!(StreamedObject::Complete(42).complete(), Some(42));
@@ -0,0 +1,459 @@
+//! Sampling-parameter sweeps for picking settings for a new schema.
+//!
+//! [`sweep`] runs every combination of a small [`SweepGrid`] (temperatures,
+//! thinking levels, output strategies) a few times against one prompt, with
+//! retries disabled, and reports how often the first reply was valid and what
+//! the valid replies cost.
+//!
+//! ```no_run
+//! # #[cfg(feature = "anthropic")]
+//! # async fn run() -> rstructor::Result<()> {
+//! use rstructor::{AnthropicClient, Instructor, OutputStrategy, SweepGrid, ThinkingLevel};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Instructor, Serialize, Deserialize)]
+//! struct Invoice { total: f64 }
+//!
+//! let client = AnthropicClient::from_env()?.into();
+//! let grid = SweepGrid::new()
+//!     .temperatures([0.0, 0.7])
+//!     .thinking_levels([ThinkingLevel::Off, ThinkingLevel::Low])
+//!     .output_strategies([OutputStrategy::JsonSchema, OutputStrategy::ToolCalling])
+//!     .samples(5)
+//!     .pricing(3.0, 15.0);
+//! let report = rstructor::sweep::<Invoice>(&client, "Extract the invoice: ...", &grid).await?;
+//! for result in &report.results {
+//!     println!("{:?}: {:.0}% valid", result.settings, result.validity_rate() * 100.0);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use serde::de::DeserializeOwned;
+use tracing::{debug, info};
+
+use crate::backend::{AnyClient, LLMClient, OutputStrategy, ThinkingLevel, TokenUsage};
+use crate::error::{RStructorError, Result};
+use crate::model::Instructor;
+
+/// Default number of requests per combination.
+pub const DEFAULT_SWEEP_SAMPLES: usize = 3;
+
+/// The parameter values [`sweep`] tries. An axis left empty keeps the
+/// client's configured value.
+#[derive(Debug, Clone)]
+pub struct SweepGrid {
+    temperatures: Vec<f32>,
+    thinking_levels: Vec<ThinkingLevel>,
+    output_strategies: Vec<OutputStrategy>,
+    samples: usize,
+    pricing: Option<(f64, f64)>,
+}
+
+impl Default for SweepGrid {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SweepGrid {
+    /// An empty grid: one combination (the client as configured), sampled
+    /// [`DEFAULT_SWEEP_SAMPLES`] times.
+    pub fn new() -> Self {
+        Self {
+            temperatures: Vec::new(),
+            thinking_levels: Vec::new(),
+            output_strategies: Vec::new(),
+            samples: DEFAULT_SWEEP_SAMPLES,
+            pricing: None,
+        }
+    }
+
+    /// Temperatures to try.
+    #[must_use]
+    pub fn temperatures(mut self, temperatures: impl IntoIterator<Item = f32>) -> Self {
+        self.temperatures = temperatures.into_iter().collect();
+        self
+    }
+
+    /// Thinking levels to try.
+    #[must_use]
+    pub fn thinking_levels(mut self, levels: impl IntoIterator<Item = ThinkingLevel>) -> Self {
+        self.thinking_levels = levels.into_iter().collect();
+        self
+    }
+
+    /// Output strategies to try.
+    #[must_use]
+    pub fn output_strategies(
+        mut self,
+        strategies: impl IntoIterator<Item = OutputStrategy>,
+    ) -> Self {
+        self.output_strategies = strategies.into_iter().collect();
+        self
+    }
+
+    /// Requests per combination (default [`DEFAULT_SWEEP_SAMPLES`]).
+    #[must_use]
+    pub fn samples(mut self, samples: usize) -> Self {
+        self.samples = samples;
+        self
+    }
+
+    /// Price in USD per million input and output tokens, used to fill
+    /// [`SweepResult::cost_usd`].
+    #[must_use]
+    pub fn pricing(mut self, input_per_mtok: f64, output_per_mtok: f64) -> Self {
+        self.pricing = Some((input_per_mtok, output_per_mtok));
+        self
+    }
+
+    /// Every combination, in grid order (temperature outermost).
+    fn combinations(&self) -> Vec<SweepSettings> {
+        fn axis<T: Copy>(values: &[T]) -> Vec<Option<T>> {
+            if values.is_empty() {
+                vec![None]
+            } else {
+                values.iter().copied().map(Some).collect()
+            }
+        }
+        let mut combinations = Vec::new();
+        for temperature in axis(&self.temperatures) {
+            for thinking_level in axis(&self.thinking_levels) {
+                for output_strategy in axis(&self.output_strategies) {
+                    combinations.push(SweepSettings {
+                        temperature,
+                        thinking_level,
+                        output_strategy,
+                    });
+                }
+            }
+        }
+        combinations
+    }
+}
+
+/// One combination of a [`SweepGrid`]. `None` means the client's configured
+/// value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SweepSettings {
+    /// Sampling temperature.
+    pub temperature: Option<f32>,
+    /// Thinking level.
+    pub thinking_level: Option<ThinkingLevel>,
+    /// Structured-output strategy.
+    pub output_strategy: Option<OutputStrategy>,
+}
+
+/// How one combination performed.
+#[derive(Debug, Clone)]
+pub struct SweepResult {
+    /// The settings tried.
+    pub settings: SweepSettings,
+    /// Requests made (0 if the combination was skipped).
+    pub samples: usize,
+    /// Requests whose first reply parsed and validated.
+    pub valid: usize,
+    /// Token usage summed over the valid replies, when the provider reports it.
+    pub usage: Option<TokenUsage>,
+    /// Cost of [`usage`](Self::usage) in USD, when [`SweepGrid::pricing`] is set.
+    pub cost_usd: Option<f64>,
+    /// The last error, if any request failed.
+    pub last_error: Option<String>,
+    /// Why the combination was not run: the client cannot be configured
+    /// with these settings.
+    pub skipped: Option<String>,
+}
+
+impl SweepResult {
+    /// Fraction of requests that produced a valid reply (0 when skipped).
+    pub fn validity_rate(&self) -> f64 {
+        if self.samples == 0 {
+            0.0
+        } else {
+            self.valid as f64 / self.samples as f64
+        }
+    }
+
+    /// Average cost of a valid reply in USD, when known.
+    pub fn cost_per_valid_usd(&self) -> Option<f64> {
+        self.cost_usd
+            .filter(|_| self.valid > 0)
+            .map(|cost| cost / self.valid as f64)
+    }
+}
+
+/// The results of a [`sweep`], one per combination in grid order.
+#[derive(Debug, Clone)]
+pub struct SweepReport {
+    /// Per-combination results.
+    pub results: Vec<SweepResult>,
+}
+
+impl SweepReport {
+    /// The combination with the highest validity rate, cheapest per valid
+    /// reply among ties (unknown cost ranks last). `None` if no combination
+    /// produced a valid reply.
+    pub fn best(&self) -> Option<&SweepResult> {
+        self.results
+            .iter()
+            .filter(|result| result.skipped.is_none() && result.valid > 0)
+            .max_by(|a, b| {
+                let cost = |r: &SweepResult| r.cost_per_valid_usd().unwrap_or(f64::INFINITY);
+                a.validity_rate()
+                    .total_cmp(&b.validity_rate())
+                    .then_with(|| cost(b).total_cmp(&cost(a)))
+            })
+    }
+}
+
+/// Try every combination of `grid` on `prompt`, materializing `T` the
+/// configured number of times each with retries disabled.
+///
+/// Combinations run one after another. Those the provider cannot be
+/// configured for (a thinking level on Grok or Ollama, an output strategy
+/// the client does not implement) are reported as
+/// [`skipped`](SweepResult::skipped) rather than failing the sweep.
+///
+/// # Errors
+///
+/// Returns [`RStructorError::ConfigError`] if the grid has zero samples.
+pub async fn sweep<T>(client: &AnyClient, prompt: &str, grid: &SweepGrid) -> Result<SweepReport>
+where
+    T: Instructor + DeserializeOwned + Send + 'static,
+{
+    if grid.samples == 0 {
+        return Err(RStructorError::ConfigError(
+            "a sweep needs at least one sample per combination".to_string(),
+        ));
+    }
+    let mut results = Vec::new();
+    for settings in grid.combinations() {
+        let mut result = SweepResult {
+            settings,
+            samples: 0,
+            valid: 0,
+            usage: None,
+            cost_usd: None,
+            last_error: None,
+            skipped: None,
+        };
+        let configured = match configure(client, &settings) {
+            Ok(configured) => configured,
+            Err(reason) => {
+                debug!(?settings, %reason, "Skipping sweep combination");
+                result.skipped = Some(reason);
+                results.push(result);
+                continue;
+            }
+        };
+        for _ in 0..grid.samples {
+            result.samples += 1;
+            match configured.materialize_with_metadata::<T>(prompt).await {
+                Ok(output) => {
+                    result.valid += 1;
+                    if let Some(usage) = output.usage {
+                        result.usage = Some(match result.usage.take() {
+                            Some(total) => TokenUsage::new(
+                                usage.model,
+                                total.input_tokens + usage.input_tokens,
+                                total.output_tokens + usage.output_tokens,
+                            ),
+                            None => usage,
+                        });
+                    }
+                }
+                Err(err) => result.last_error = Some(err.to_string()),
+            }
+        }
+        result.cost_usd = grid
+            .pricing
+            .zip(result.usage.as_ref())
+            .map(|((input, output), u)| {
+                (u.input_tokens as f64 * input + u.output_tokens as f64 * output) / 1_000_000.0
+            });
+        info!(
+            ?settings,
+            valid = result.valid,
+            samples = result.samples,
+            "Sweep combination finished"
+        );
+        results.push(result);
+    }
+    Ok(SweepReport { results })
+}
+
+/// `client` with `settings` applied and retries disabled, or why the client
+/// cannot take them.
+fn configure(
+    client: &AnyClient,
+    settings: &SweepSettings,
+) -> std::result::Result<AnyClient, String> {
+    let temperature = settings.temperature;
+    let strategy = settings.output_strategy;
+    Ok(match client.clone() {
+        #[cfg(feature = "openai")]
+        AnyClient::OpenAI(mut c) => {
+            if strategy == Some(OutputStrategy::ToolCalling) {
+                return Err("OpenAI clients have no tool-calling output".to_string());
+            }
+            if let Some(strategy) = strategy {
+                c = c.output_strategy(strategy);
+            }
+            if let Some(level) = settings.thinking_level {
+                c = c.thinking_level(level);
+            }
+            if let Some(temperature) = temperature {
+                c = c.temperature(temperature);
+            }
+            AnyClient::OpenAI(c.no_retries())
+        }
+        #[cfg(feature = "anthropic")]
+        AnyClient::Anthropic(mut c) => {
+            if strategy == Some(OutputStrategy::JsonMode) {
+                return Err("Anthropic has no JSON mode".to_string());
+            }
+            if let Some(strategy) = strategy {
+                c = c.output_strategy(strategy);
+            }
+            if let Some(level) = settings.thinking_level {
+                c = c.thinking_level(level);
+            }
+            if let Some(temperature) = temperature {
+                c = c.temperature(temperature);
+            }
+            AnyClient::Anthropic(c.no_retries())
+        }
+        #[cfg(feature = "grok")]
+        AnyClient::Grok(mut c) => {
+            schema_output_only("Grok", strategy)?;
+            no_thinking("Grok", settings)?;
+            if let Some(temperature) = temperature {
+                c = c.temperature(temperature);
+            }
+            AnyClient::Grok(c.no_retries())
+        }
+        #[cfg(feature = "gemini")]
+        AnyClient::Gemini(mut c) => {
+            schema_output_only("Gemini", strategy)?;
+            if let Some(level) = settings.thinking_level {
+                c = c.thinking_level(level);
+            }
+            if let Some(temperature) = temperature {
+                c = c.temperature(temperature);
+            }
+            AnyClient::Gemini(c.no_retries())
+        }
+        #[cfg(feature = "ollama")]
+        AnyClient::Ollama(mut c) => {
+            if strategy == Some(OutputStrategy::ToolCalling) {
+                return Err("Ollama clients have no tool-calling output".to_string());
+            }
+            no_thinking("Ollama", settings)?;
+            if let Some(strategy) = strategy {
+                c = c.output_strategy(strategy);
+            }
+            if let Some(temperature) = temperature {
+                c = c.temperature(temperature);
+            }
+            AnyClient::Ollama(c.no_retries())
+        }
+        #[cfg(feature = "azure")]
+        AnyClient::AzureOpenAI(mut c) => {
+            schema_output_only("Azure OpenAI", strategy)?;
+            if let Some(level) = settings.thinking_level {
+                c = c.thinking_level(level);
+            }
+            if let Some(temperature) = temperature {
+                c = c.temperature(temperature);
+            }
+            AnyClient::AzureOpenAI(c.no_retries())
+        }
+        #[cfg(feature = "bedrock")]
+        AnyClient::Bedrock(mut c) => {
+            if strategy == Some(OutputStrategy::JsonSchema) {
+                return Err("Bedrock clients have no JSON Schema output".to_string());
+            }
+            no_thinking("Bedrock", settings)?;
+            if let Some(strategy) = strategy {
+                c = c.output_strategy(strategy);
+            }
+            if let Some(temperature) = temperature {
+                c = c.temperature(temperature);
+            }
+            AnyClient::Bedrock(c.no_retries())
+        }
+        #[cfg(feature = "vertex")]
+        AnyClient::VertexAI(mut c) => {
+            schema_output_only("Vertex AI", strategy)?;
+            if let Some(level) = settings.thinking_level {
+                c = c.thinking_level(level);
+            }
+            if let Some(temperature) = temperature {
+                c = c.temperature(temperature);
+            }
+            AnyClient::VertexAI(c.no_retries())
+        }
+        #[cfg(feature = "groq")]
+        AnyClient::Groq(mut c) => {
+            json_mode_only("Groq", strategy)?;
+            no_thinking("Groq", settings)?;
+            if let Some(temperature) = temperature {
+                c = c.temperature(temperature);
+            }
+            AnyClient::Groq(c.no_retries())
+        }
+        #[cfg(feature = "together")]
+        AnyClient::Together(mut c) => {
+            json_mode_only("Together", strategy)?;
+            no_thinking("Together", settings)?;
+            if let Some(temperature) = temperature {
+                c = c.temperature(temperature);
+            }
+            AnyClient::Together(c.no_retries())
+        }
+    })
+}
+
+/// Clients that always use native structured outputs.
+#[cfg(any(feature = "grok", feature = "gemini", feature = "azure"))]
+fn schema_output_only(
+    provider: &str,
+    strategy: Option<OutputStrategy>,
+) -> std::result::Result<(), String> {
+    match strategy {
+        None | Some(OutputStrategy::JsonSchema) => Ok(()),
+        Some(other) => Err(format!(
+            "{provider} clients do not support {other:?} output"
+        )),
+    }
+}
+
+/// Clients that always use JSON mode.
+#[cfg(any(feature = "groq", feature = "together"))]
+fn json_mode_only(
+    provider: &str,
+    strategy: Option<OutputStrategy>,
+) -> std::result::Result<(), String> {
+    match strategy {
+        None | Some(OutputStrategy::JsonMode) => Ok(()),
+        Some(other) => Err(format!(
+            "{provider} clients do not support {other:?} output"
+        )),
+    }
+}
+
+/// Clients without a thinking-level setting.
+#[cfg(any(
+    feature = "grok",
+    feature = "ollama",
+    feature = "bedrock",
+    feature = "groq",
+    feature = "together"
+))]
+fn no_thinking(provider: &str, settings: &SweepSettings) -> std::result::Result<(), String> {
+    match settings.thinking_level {
+        None => Ok(()),
+        Some(_) => Err(format!("{provider} clients have no thinking level")),
+    }
+}
@@ -0,0 +1,365 @@
+//! Per-schema extraction accuracy, tracked over time.
+//!
+//! A provider silently updating the model behind an alias can start failing
+//! validation on one particular schema while every other extraction looks
+//! fine. Each structured call records an [`ExtractionRecord`] — attempts,
+//! validation failures, whether the accepted reply needed
+//! [`Schema::coerce`](crate::Schema::coerce) to repair type mismatches, and
+//! the outcome — keyed by the schema's fingerprint. Totals are
+//! available from [`schema_telemetry`], every record is passed to the hook set
+//! with [`set_telemetry_hook`] for export, and [`drift_report`] compares each
+//! schema's latest calls against its earlier ones.
+//!
+//! ```no_run
+//! # fn main() {
+//! use rstructor::{drift_report, set_telemetry_hook};
+//!
+//! set_telemetry_hook(|record| {
+//!     tracing::info!(schema = %record.schema_name, attempts = record.attempts, "extraction");
+//! });
+//!
+//! // Later, e.g. from a periodic health check:
+//! for drift in drift_report().into_iter().filter(|d| d.drifted) {
+//!     tracing::warn!(
+//!         schema = %drift.schema_name,
+//!         before = drift.baseline.validation_failure_rate,
+//!         now = drift.recent.validation_failure_rate,
+//!         "extraction accuracy degraded"
+//!     );
+//! }
+//! # }
+//! ```
+
+use std::cell::Cell;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::SystemTime;
+
+use crate::backend::MaterializeInternalOutput;
+use crate::error::Result;
+use crate::schema::SchemaType;
+
+/// Records kept per schema for [`drift_report`]; older ones are dropped.
+pub const TELEMETRY_HISTORY: usize = 1000;
+
+/// Calls in the "recent" window of a [`SchemaDrift`].
+pub const DRIFT_WINDOW: usize = 50;
+
+/// Increase in a failure rate, from baseline to recent window, that counts as drift.
+pub const DRIFT_THRESHOLD: f64 = 0.1;
+
+/// The outcome of one structured call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtractionRecord {
+    /// The schema's name (e.g. the struct name).
+    pub schema_name: String,
+    /// Stable hash of the schema's canonical form
+    /// ([`SchemaFingerprint::hash`](crate::SchemaFingerprint::hash)); changes
+    /// when the schema does.
+    pub fingerprint: String,
+    /// When the call finished.
+    pub at: SystemTime,
+    /// Requests sent, including re-asks and transient-error retries.
+    pub attempts: u32,
+    /// Replies that failed to parse or validate.
+    pub validation_failures: u32,
+    /// Whether the accepted reply only deserialized after
+    /// [`Schema::coerce`](crate::Schema::coerce) repaired type mismatches
+    /// (`"42"` for an integer and the like).
+    pub coerced: bool,
+    /// Whether the call returned a value.
+    pub succeeded: bool,
+}
+
+/// Cumulative counts for one schema since the process started (or
+/// [`reset_telemetry`]).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SchemaTelemetry {
+    pub schema_name: String,
+    pub fingerprint: String,
+    /// Structured calls made.
+    pub calls: u64,
+    /// Calls that returned an error.
+    pub failures: u64,
+    /// Replies that failed to parse or validate, across all calls.
+    pub validation_failures: u64,
+    /// Requests beyond the first of each call.
+    pub retries: u64,
+    /// Accepted replies whose type mismatches were repaired by
+    /// [`Schema::coerce`](crate::Schema::coerce).
+    pub coercions: u64,
+}
+
+/// Rates over a window of calls.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WindowStats {
+    pub calls: usize,
+    /// Fraction of calls with at least one validation failure.
+    pub validation_failure_rate: f64,
+    /// Fraction of calls that returned an error.
+    pub failure_rate: f64,
+    /// Fraction of successful calls whose reply had to be coerced.
+    pub coercion_rate: f64,
+    /// Mean requests sent per call.
+    pub mean_attempts: f64,
+}
+
+impl WindowStats {
+    fn of<'a>(records: impl Iterator<Item = &'a ExtractionRecord>) -> Self {
+        let (mut calls, mut invalid, mut failed, mut succeeded, mut coerced, mut attempts) =
+            (0, 0, 0, 0, 0, 0u64);
+        for record in records {
+            calls += 1;
+            invalid += usize::from(record.validation_failures > 0);
+            failed += usize::from(!record.succeeded);
+            succeeded += usize::from(record.succeeded);
+            coerced += usize::from(record.coerced);
+            attempts += u64::from(record.attempts);
+        }
+        let rate = |n: usize, of: usize| if of == 0 { 0.0 } else { n as f64 / of as f64 };
+        Self {
+            calls,
+            validation_failure_rate: rate(invalid, calls),
+            failure_rate: rate(failed, calls),
+            coercion_rate: rate(coerced, succeeded),
+            mean_attempts: if calls == 0 {
+                0.0
+            } else {
+                attempts as f64 / calls as f64
+            },
+        }
+    }
+}
+
+/// One schema's latest [`DRIFT_WINDOW`] calls compared with the calls
+/// retained before them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaDrift {
+    pub schema_name: String,
+    pub fingerprint: String,
+    pub baseline: WindowStats,
+    pub recent: WindowStats,
+    /// Whether the validation-failure or error rate rose by at least
+    /// [`DRIFT_THRESHOLD`].
+    pub drifted: bool,
+}
+
+#[derive(Default)]
+struct History {
+    totals: SchemaTelemetry,
+    records: VecDeque<ExtractionRecord>,
+}
+
+type Hook = Arc<dyn Fn(&ExtractionRecord) + Send + Sync>;
+
+fn registry() -> &'static Mutex<HashMap<String, History>> {
+    static REGISTRY: OnceLock<Mutex<HashMap<String, History>>> = OnceLock::new();
+    REGISTRY.get_or_init(Default::default)
+}
+
+fn hook() -> &'static RwLock<Option<Hook>> {
+    static HOOK: OnceLock<RwLock<Option<Hook>>> = OnceLock::new();
+    HOOK.get_or_init(Default::default)
+}
+
+/// Call `hook` with every [`ExtractionRecord`], e.g. to export it to a
+/// metrics system. Replaces any previous hook. The hook runs on the calling
+/// task after each structured call, so it should not block.
+pub fn set_telemetry_hook(hook_fn: impl Fn(&ExtractionRecord) + Send + Sync + 'static) {
+    *hook().write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(hook_fn));
+}
+
+/// Remove the hook set with [`set_telemetry_hook`].
+pub fn clear_telemetry_hook() {
+    *hook().write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Cumulative counts for every schema seen, ordered by schema name.
+pub fn schema_telemetry() -> Vec<SchemaTelemetry> {
+    let registry = registry().lock().unwrap_or_else(|e| e.into_inner());
+    let mut all: Vec<_> = registry.values().map(|h| h.totals.clone()).collect();
+    all.sort_by(|a, b| (&a.schema_name, &a.fingerprint).cmp(&(&b.schema_name, &b.fingerprint)));
+    all
+}
+
+/// Compare each schema's latest [`DRIFT_WINDOW`] calls with the calls
+/// retained before them. Schemas without at least [`DRIFT_WINDOW`] calls in
+/// both windows are left out.
+pub fn drift_report() -> Vec<SchemaDrift> {
+    let registry = registry().lock().unwrap_or_else(|e| e.into_inner());
+    let mut report: Vec<_> = registry.values().filter_map(drift_of).collect();
+    report.sort_by(|a, b| (&a.schema_name, &a.fingerprint).cmp(&(&b.schema_name, &b.fingerprint)));
+    report
+}
+
+fn drift_of(history: &History) -> Option<SchemaDrift> {
+    let split = history.records.len().checked_sub(DRIFT_WINDOW)?;
+    if split < DRIFT_WINDOW {
+        return None;
+    }
+    let baseline = WindowStats::of(history.records.range(..split));
+    let recent = WindowStats::of(history.records.range(split..));
+    let drifted = recent.validation_failure_rate - baseline.validation_failure_rate
+        >= DRIFT_THRESHOLD
+        || recent.failure_rate - baseline.failure_rate >= DRIFT_THRESHOLD;
+    Some(SchemaDrift {
+        schema_name: history.totals.schema_name.clone(),
+        fingerprint: history.totals.fingerprint.clone(),
+        baseline,
+        recent,
+        drifted,
+    })
+}
+
+/// Forget all recorded telemetry (the hook is kept).
+pub fn reset_telemetry() {
+    registry().lock().unwrap_or_else(|e| e.into_inner()).clear();
+}
+
+fn record(record: ExtractionRecord) {
+    {
+        let mut registry = registry().lock().unwrap_or_else(|e| e.into_inner());
+        let history = registry.entry(record.fingerprint.clone()).or_default();
+        let totals = &mut history.totals;
+        if totals.calls == 0 {
+            totals.schema_name = record.schema_name.clone();
+            totals.fingerprint = record.fingerprint.clone();
+        }
+        totals.calls += 1;
+        totals.failures += u64::from(!record.succeeded);
+        totals.validation_failures += u64::from(record.validation_failures);
+        totals.retries += u64::from(record.attempts.saturating_sub(1));
+        totals.coercions += u64::from(record.coerced);
+        if history.records.len() == TELEMETRY_HISTORY {
+            history.records.pop_front();
+        }
+        history.records.push_back(record.clone());
+    }
+    let hook = hook().read().unwrap_or_else(|e| e.into_inner()).clone();
+    if let Some(hook) = hook {
+        hook(&record);
+    }
+}
+
+/// Name and fingerprint of `T`'s schema, computed once per type.
+fn schema_identity<T: SchemaType>() -> (String, String) {
+    static CACHE: OnceLock<Mutex<HashMap<&'static str, (String, String)>>> = OnceLock::new();
+    let key = std::any::type_name::<T>();
+    let mut cache = CACHE
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    cache
+        .entry(key)
+        .or_insert_with(|| {
+            let schema = T::schema();
+            let name = T::schema_name().unwrap_or_else(|| key.to_string());
+            (name, schema.fingerprint().hash)
+        })
+        .clone()
+}
+
+#[derive(Clone, Copy, Default)]
+struct Counts {
+    attempts: u32,
+    validation_failures: u32,
+    coerced: bool,
+}
+
+tokio::task_local! {
+    /// Counts for the structured call the current task is making.
+    static COUNTS: Cell<Counts>;
+}
+
+/// Note that the retry engine is sending a request.
+pub(crate) fn note_attempt() {
+    let _ = COUNTS.try_with(|c| {
+        let mut counts = c.get();
+        counts.attempts += 1;
+        c.set(counts);
+    });
+}
+
+/// Note that a reply failed to parse or validate.
+pub(crate) fn note_validation_failure() {
+    let _ = COUNTS.try_with(|c| {
+        let mut counts = c.get();
+        counts.validation_failures += 1;
+        c.set(counts);
+    });
+}
+
+/// Note that a reply was accepted after its type mismatches were coerced.
+pub(crate) fn note_coercion() {
+    let _ = COUNTS.try_with(|c| {
+        let mut counts = c.get();
+        counts.coerced = true;
+        c.set(counts);
+    });
+}
+
+/// Run one structured call for `T` and record its [`ExtractionRecord`].
+pub(crate) async fn observe<T, F>(call: F) -> Result<MaterializeInternalOutput<T>>
+where
+    T: SchemaType,
+    F: Future<Output = Result<MaterializeInternalOutput<T>>>,
+{
+    let (result, counts) = COUNTS
+        .scope(Cell::default(), async {
+            let result = call.await;
+            (result, COUNTS.with(Cell::get))
+        })
+        .await;
+    let (schema_name, fingerprint) = schema_identity::<T>();
+    record(ExtractionRecord {
+        schema_name,
+        fingerprint,
+        at: SystemTime::now(),
+        attempts: counts.attempts,
+        validation_failures: counts.validation_failures,
+        coerced: counts.coerced && result.is_ok(),
+        succeeded: result.is_ok(),
+    });
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(outcomes: impl IntoIterator<Item = bool>) -> History {
+        let mut history = History::default();
+        for valid in outcomes {
+            history.records.push_back(ExtractionRecord {
+                schema_name: "Invoice".into(),
+                fingerprint: "f".into(),
+                at: SystemTime::now(),
+                attempts: if valid { 1 } else { 2 },
+                validation_failures: u32::from(!valid),
+                coerced: false,
+                succeeded: true,
+            });
+        }
+        history
+    }
+
+    #[test]
+    fn drift_needs_two_full_windows() {
+        assert_eq!(drift_of(&history(vec![true; 2 * DRIFT_WINDOW - 1])), None);
+        let steady = drift_of(&history(vec![true; 2 * DRIFT_WINDOW])).unwrap();
+        assert!(!steady.drifted);
+        assert_eq!(steady.recent.mean_attempts, 1.0);
+    }
+
+    #[test]
+    fn rising_validation_failures_are_drift() {
+        // Baseline: all valid. Recent window: one call in five re-asked.
+        let recent = (0..DRIFT_WINDOW).map(|i| i % 5 != 0);
+        let outcomes = std::iter::repeat_n(true, 100).chain(recent);
+        let drift = drift_of(&history(outcomes)).unwrap();
+        assert_eq!(drift.baseline.calls, 100);
+        assert_eq!(drift.recent.validation_failure_rate, 0.2);
+        assert!(drift.drifted);
+    }
+}
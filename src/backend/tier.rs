@@ -0,0 +1,211 @@
+//! Latency-tier model aliases.
+//!
+//! Concrete model identifiers go stale every few months. [`ModelTier`] names the
+//! trade-off instead — [`Fast`](ModelTier::Fast), [`Balanced`](ModelTier::Balanced),
+//! [`Best`](ModelTier::Best) — and resolves it per provider to the currently
+//! recommended model, so upgrading rstructor upgrades the models too. Every
+//! client's `model(..)` builder accepts a tier directly.
+//!
+//! The built-in mapping can be overridden process-wide with
+//! [`ModelTier::set_override`], e.g. from configuration at startup.
+//!
+//! ```
+//! # #[cfg(feature = "anthropic")]
+//! # fn main() -> rstructor::Result<()> {
+//! use rstructor::{AnthropicClient, AnthropicModel, ModelTier};
+//!
+//! let client = AnthropicClient::new("key")?.model(ModelTier::Fast);
+//! assert_eq!(
+//!     ModelTier::Fast.resolve(rstructor::Provider::Anthropic).name,
+//!     AnthropicModel::ClaudeHaiku45.as_str()
+//! );
+//! # let _ = client;
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "anthropic"))]
+//! # fn main() {}
+//! ```
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, RwLock};
+
+use crate::backend::{ModelId, Provider};
+
+/// A latency/quality trade-off that resolves to a concrete model per provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ModelTier {
+    /// Lowest latency and cost; good for simple, high-volume extraction.
+    Fast,
+    /// The provider's recommended general-purpose model.
+    Balanced,
+    /// Most capable model, for hard extractions where quality beats latency.
+    Best,
+}
+
+static OVERRIDES: LazyLock<RwLock<HashMap<(Provider, ModelTier), String>>> =
+    LazyLock::new(Default::default);
+
+impl ModelTier {
+    /// The model this tier currently maps to for `provider`: an override if one
+    /// is set, otherwise the built-in recommendation.
+    pub fn resolve(self, provider: Provider) -> ModelId {
+        let overridden = OVERRIDES.read().unwrap().get(&(provider, self)).cloned();
+        let name = overridden.unwrap_or_else(|| self.default_model(provider));
+        ModelId::new(provider, name)
+    }
+
+    /// Map this tier to `model` for `provider` for the rest of the process.
+    pub fn set_override(self, provider: Provider, model: impl Into<String>) {
+        OVERRIDES
+            .write()
+            .unwrap()
+            .insert((provider, self), model.into());
+    }
+
+    /// Remove every override set with [`set_override`](Self::set_override).
+    pub fn clear_overrides() {
+        OVERRIDES.write().unwrap().clear();
+    }
+
+    fn default_model(self, provider: Provider) -> String {
+        use ModelTier::{Balanced, Best, Fast};
+        match provider {
+            #[cfg(feature = "openai")]
+            Provider::OpenAI => {
+                use crate::backend::openai::Model;
+                match self {
+                    Fast => Model::Gpt54Mini,
+                    Balanced => Model::Gpt55,
+                    Best => Model::Gpt55Pro,
+                }
+                .as_str()
+                .to_string()
+            }
+            #[cfg(feature = "anthropic")]
+            Provider::Anthropic => {
+                use crate::backend::anthropic::AnthropicModel as Model;
+                match self {
+                    Fast => Model::ClaudeHaiku45,
+                    Balanced => Model::ClaudeSonnet46,
+                    Best => Model::ClaudeOpus48,
+                }
+                .as_str()
+                .to_string()
+            }
+            #[cfg(feature = "grok")]
+            Provider::Grok => {
+                use crate::backend::grok::Model;
+                match self {
+                    Fast => Model::Grok420NonReasoning,
+                    Balanced => Model::Grok43,
+                    Best => Model::Grok420Reasoning,
+                }
+                .as_str()
+                .to_string()
+            }
+            #[cfg(feature = "gemini")]
+            Provider::Gemini => {
+                use crate::backend::gemini::Model;
+                match self {
+                    Fast => Model::Gemini31FlashLite,
+                    Balanced => Model::Gemini35Flash,
+                    Best => Model::Gemini31ProPreview,
+                }
+                .as_str()
+                .to_string()
+            }
+            // Azure deployments serve OpenAI models
+            #[cfg(feature = "azure")]
+            Provider::AzureOpenAI => self.default_model(Provider::OpenAI),
+            #[cfg(feature = "ollama")]
+            Provider::Ollama => {
+                use crate::backend::ollama::Model;
+                match self {
+                    Fast => Model::Llama32,
+                    Balanced => Model::Qwen3,
+                    Best => Model::Llama33,
+                }
+                .as_str()
+                .to_string()
+            }
+            #[cfg(feature = "bedrock")]
+            Provider::Bedrock => {
+                use crate::backend::bedrock::Model;
+                match self {
+                    Fast => Model::ClaudeHaiku45,
+                    Balanced => Model::ClaudeSonnet45,
+                    Best => Model::ClaudeOpus41,
+                }
+                .as_str()
+                .to_string()
+            }
+            // Vertex AI serves the Gemini models
+            #[cfg(feature = "vertex")]
+            Provider::VertexAI => self.default_model(Provider::Gemini),
+            #[cfg(feature = "groq")]
+            Provider::Groq => {
+                use crate::backend::groq::Model;
+                match self {
+                    Fast => Model::Llama318bInstant,
+                    Balanced => Model::Llama3370bVersatile,
+                    Best => Model::GptOss120b,
+                }
+                .as_str()
+                .to_string()
+            }
+            #[cfg(feature = "together")]
+            Provider::Together => {
+                use crate::backend::together::Model;
+                match self {
+                    Fast => Model::Llama318bInstructTurbo,
+                    Balanced => Model::Llama3370bInstructTurbo,
+                    Best => Model::DeepSeekV3,
+                }
+                .as_str()
+                .to_string()
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "openai"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tiers_resolve_to_distinct_models() {
+        let fast = ModelTier::Fast.resolve(Provider::OpenAI);
+        let best = ModelTier::Best.resolve(Provider::OpenAI);
+        assert_eq!(fast.provider, Provider::OpenAI);
+        assert_ne!(fast.name, best.name);
+    }
+
+    #[test]
+    fn override_replaces_and_clear_restores_default() {
+        let default = ModelTier::Balanced.resolve(Provider::OpenAI);
+        ModelTier::Balanced.set_override(Provider::OpenAI, "my-finetune");
+        assert_eq!(
+            ModelTier::Balanced.resolve(Provider::OpenAI).name,
+            "my-finetune"
+        );
+        ModelTier::clear_overrides();
+        assert_eq!(ModelTier::Balanced.resolve(Provider::OpenAI), default);
+    }
+
+    #[cfg(all(feature = "anthropic", feature = "grok", feature = "gemini"))]
+    #[test]
+    fn tier_defaults_have_prices() {
+        use crate::backend::pricing::ModelPricing;
+        for provider in [
+            Provider::OpenAI,
+            Provider::Anthropic,
+            Provider::Grok,
+            Provider::Gemini,
+        ] {
+            for tier in [ModelTier::Fast, ModelTier::Balanced, ModelTier::Best] {
+                let model = tier.default_model(provider);
+                assert!(ModelPricing::for_model(&model).is_some(), "{model}");
+            }
+        }
+    }
+}
@@ -0,0 +1,40 @@
+//! An executor-agnostic timer for the test doubles.
+
+use std::sync::{Arc, Mutex};
+use std::task::{Poll, Waker};
+use std::time::Duration;
+
+/// Resolve after `duration`. A helper thread does the waiting, so this works
+/// under any executor (or none) without pulling in a timer dependency.
+pub(crate) async fn sleep(duration: Duration) {
+    if duration.is_zero() {
+        return;
+    }
+    struct Timer {
+        done: bool,
+        waker: Option<Waker>,
+    }
+    let timer = Arc::new(Mutex::new(Timer {
+        done: false,
+        waker: None,
+    }));
+    let shared = timer.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(duration);
+        let mut timer = shared.lock().unwrap();
+        timer.done = true;
+        if let Some(waker) = timer.waker.take() {
+            waker.wake();
+        }
+    });
+    std::future::poll_fn(|cx| {
+        let mut timer = timer.lock().unwrap();
+        if timer.done {
+            Poll::Ready(())
+        } else {
+            timer.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    })
+    .await
+}
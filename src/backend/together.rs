@@ -0,0 +1,80 @@
+use crate::backend::ModelInfo;
+use crate::backend::model_macro::define_model_enum;
+use crate::backend::openai_hosted::define_hosted_client;
+
+define_model_enum! {
+    /// Models served by Together.ai
+    ///
+    /// For the latest available models and their identifiers, check the
+    /// [Together.ai Models Documentation](https://docs.together.ai/docs/serverless-models).
+    /// Any other name can be given as a string or through the `Custom` variant:
+    ///
+    /// ```rust
+    /// use rstructor::TogetherModel;
+    ///
+    /// let model = TogetherModel::from_string("mistralai/Mixtral-8x7B-Instruct-v0.1");
+    /// assert!(matches!(model, TogetherModel::Custom(_)));
+    /// ```
+    pub enum Model {
+        /// Llama 3.3 70B Instruct Turbo (general-purpose model)
+        Llama3370bInstructTurbo => "meta-llama/Llama-3.3-70B-Instruct-Turbo",
+        /// Llama 3.1 8B Instruct Turbo (fast, low-cost model)
+        Llama318bInstructTurbo => "meta-llama/Meta-Llama-3.1-8B-Instruct-Turbo",
+        /// Llama 4 Maverick 17B 128E (multimodal mixture-of-experts model)
+        Llama4Maverick => "meta-llama/Llama-4-Maverick-17B-128E-Instruct-FP8",
+        /// DeepSeek V3 (large mixture-of-experts chat model)
+        DeepSeekV3 => "deepseek-ai/DeepSeek-V3",
+        /// DeepSeek R1 (reasoning model)
+        DeepSeekR1 => "deepseek-ai/DeepSeek-R1",
+        /// Qwen 2.5 72B Instruct Turbo (Alibaba's general-purpose model)
+        Qwen2572bInstructTurbo => "Qwen/Qwen2.5-72B-Instruct-Turbo",
+        /// GPT-OSS 120B (OpenAI's open-weight reasoning model)
+        GptOss120b => "openai/gpt-oss-120b",
+    }
+}
+
+define_hosted_client! {
+    /// Together.ai client for generating completions.
+    ///
+    /// Together's OpenAI-compatible API constrains output only with
+    /// `response_format: {"type": "json_object"}` and needs the schema spelled
+    /// out in the prompt, which this client sends as a system message. Reads
+    /// `TOGETHER_API_KEY` in [`from_env`](Self::from_env).
+    ///
+    /// Cheap to clone and `Send + Sync`; see [sharing clients](crate::LLMClient#sharing-clients).
+    ///
+    /// ```no_run
+    /// # use rstructor::{TogetherClient, TogetherModel};
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = TogetherClient::from_env()?.model(TogetherModel::DeepSeekV3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    client: TogetherClient,
+    model_type: Model,
+    default_model: Model::Llama3370bInstructTurbo,
+    provider: Together,
+    provider_name: "Together",
+    api_key_env: "TOGETHER_API_KEY",
+    base_url: "https://api.together.xyz/v1",
+    model_info: chat_model,
+}
+
+/// A chat model from Together's `/models` list, which also lists image,
+/// embedding and other model types.
+fn chat_model(model: &serde_json::Value) -> Option<ModelInfo> {
+    if model.get("type")?.as_str()? != "chat" {
+        return None;
+    }
+    Some(ModelInfo {
+        id: model.get("id")?.as_str()?.to_string(),
+        name: model
+            .get("display_name")
+            .and_then(|name| name.as_str())
+            .map(str::to_string),
+        description: model
+            .get("organization")
+            .and_then(|org| org.as_str())
+            .map(|org| format!("By {org}")),
+    })
+}
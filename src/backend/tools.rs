@@ -3,7 +3,9 @@
 //!
 //! Define tools whose argument types derive [`Instructor`](crate::Instructor) (so
 //! their JSON Schema is generated for you), collect them in a [`Toolbox`], and run
-//! the agentic loop with a client's `with_tools(...).run(prompt)`.
+//! the agentic loop with a client's `with_tools(...).run(prompt)`. Tools built
+//! with [`TypedFnTool`] also declare the type they return, so results are
+//! validated before being fed back to the model.
 //!
 //! This module is only compiled with the `tools` feature.
 
@@ -16,7 +18,7 @@ use serde_json::Value;
 
 use crate::error::{RStructorError, Result};
 use crate::model::Instructor;
-use crate::schema::SchemaType;
+use crate::schema::{Schema, SchemaType};
 
 /// A typed tool the model can call.
 ///
@@ -37,6 +39,12 @@ pub trait Tool: Send + Sync {
     /// Execute the tool with deserialized arguments, returning a JSON result that
     /// is fed back to the model.
     async fn invoke(&self, args: Self::Args) -> Result<Value>;
+
+    /// The JSON Schema of the tool's result, if it declares one. `None` (the
+    /// default) means the result is free-form JSON.
+    fn result_schema(&self) -> Option<Schema> {
+        None
+    }
 }
 
 /// Object-safe, type-erased view of a [`Tool`], used to store heterogeneous tools
@@ -48,12 +56,18 @@ pub trait DynTool: Send + Sync {
     /// The tool description.
     fn description(&self) -> String;
     /// The JSON Schema for the tool's arguments (strict form: `additionalProperties:
-    /// false`), as used by OpenAI/Grok/Anthropic.
+    /// false`), as used by OpenAI/Grok/Anthropic. Property names that
+    /// function-calling APIs reject are replaced with safe ones (see
+    /// [`Schema::with_safe_property_names`](crate::Schema::with_safe_property_names)).
     fn parameters_schema(&self) -> Value;
-    /// The argument schema with Gemini-unsupported keywords stripped.
-    fn parameters_schema_gemini(&self) -> Value;
-    /// Invoke the tool with raw JSON arguments (deserialized into `Args`).
+    /// The argument schema with Gemini-unsupported keywords stripped, or an
+    /// error if it nests deeper than Gemini allows.
+    fn parameters_schema_gemini(&self) -> Result<Value>;
+    /// Invoke the tool with raw JSON arguments (deserialized into `Args` after
+    /// restoring any renamed property names).
     async fn invoke_json(&self, args: Value) -> Result<Value>;
+    /// The JSON Schema of the tool's result, if it declares one.
+    fn result_schema(&self) -> Option<Value>;
 }
 
 #[async_trait]
@@ -67,18 +81,26 @@ impl<T: Tool> DynTool for T {
     }
 
     fn parameters_schema(&self) -> Value {
-        crate::backend::utils::prepare_strict_schema(&<T::Args as SchemaType>::schema())
+        let (schema, _) = <T::Args as SchemaType>::schema().with_safe_property_names();
+        crate::backend::utils::prepare_strict_schema(&schema)
     }
 
-    fn parameters_schema_gemini(&self) -> Value {
-        crate::backend::utils::prepare_gemini_schema(&<T::Args as SchemaType>::schema())
+    fn parameters_schema_gemini(&self) -> Result<Value> {
+        let (schema, _) = <T::Args as SchemaType>::schema().with_safe_property_names();
+        crate::backend::utils::prepare_gemini_schema(&schema)
     }
 
     async fn invoke_json(&self, args: Value) -> Result<Value> {
-        let typed: T::Args = serde_json::from_value(args)
+        // Argument names were made provider-safe in the schema; map them back
+        let (_, renames) = <T::Args as SchemaType>::schema().with_safe_property_names();
+        let typed: T::Args = serde_json::from_value(renames.restore(args))
             .map_err(|e| RStructorError::SerializationError(e.to_string()))?;
         self.invoke(typed).await
     }
+
+    fn result_schema(&self) -> Option<Value> {
+        Tool::result_schema(self).map(|schema| schema.to_json())
+    }
 }
 
 /// A [`Tool`] built from a closure.
@@ -140,6 +162,83 @@ where
     }
 }
 
+/// A [`Tool`] built from a closure that returns a typed result.
+///
+/// The result type `R` derives [`Instructor`](crate::Instructor) like the
+/// arguments do. Each result runs through `R::validate` and is serialized with
+/// serde before it is fed back to the model, and its schema is reported by
+/// [`DynTool::result_schema`]. A result that fails validation reaches the model
+/// as an `{"error": ...}` payload, exactly like an error returned by the closure.
+///
+/// ```no_run
+/// # use rstructor::{Instructor, Toolbox, TypedFnTool};
+/// # use serde::{Serialize, Deserialize};
+/// #[derive(Instructor, Serialize, Deserialize)]
+/// struct WeatherArgs {
+///     #[llm(description = "City name")]
+///     city: String,
+/// }
+///
+/// #[derive(Instructor, Serialize, Deserialize)]
+/// struct Weather {
+///     #[llm(description = "Temperature in degrees Fahrenheit")]
+///     temp_f: f64,
+/// }
+///
+/// let tool = TypedFnTool::new("get_weather", "Get the current weather for a city", |args: WeatherArgs| async move {
+///     Ok(Weather { temp_f: 72.0 })
+/// });
+/// let toolbox = Toolbox::new().with(tool);
+/// ```
+pub struct TypedFnTool<A, R, F> {
+    name: String,
+    description: String,
+    func: F,
+    _marker: PhantomData<fn() -> (A, R)>,
+}
+
+impl<A, R, F> TypedFnTool<A, R, F> {
+    /// Create a tool from a name, description, and an async closure from the
+    /// argument type to the result type.
+    pub fn new(name: impl Into<String>, description: impl Into<String>, func: F) -> Self {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            func,
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<A, R, F, Fut> Tool for TypedFnTool<A, R, F>
+where
+    A: Instructor + DeserializeOwned + Send + 'static,
+    R: Instructor + Send + 'static,
+    F: Fn(A) -> Fut + Send + Sync,
+    Fut: Future<Output = Result<R>> + Send,
+{
+    type Args = A;
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn description(&self) -> String {
+        self.description.clone()
+    }
+
+    async fn invoke(&self, args: A) -> Result<Value> {
+        let result = (self.func)(args).await?;
+        result.validate()?;
+        serde_json::to_value(&result).map_err(|e| RStructorError::SerializationError(e.to_string()))
+    }
+
+    fn result_schema(&self) -> Option<Schema> {
+        Some(R::schema())
+    }
+}
+
 /// A collection of tools made available to the model.
 #[derive(Default)]
 pub struct Toolbox {
@@ -210,6 +309,21 @@ impl Toolbox {
             .collect()
     }
 
+    /// Render the tools as legacy OpenAI `functions` JSON.
+    #[cfg(any(feature = "openai", feature = "grok"))]
+    fn openai_functions_json(&self) -> Vec<Value> {
+        self.tools
+            .iter()
+            .map(|t| {
+                serde_json::json!({
+                    "name": t.name(),
+                    "description": t.description(),
+                    "parameters": t.parameters_schema(),
+                })
+            })
+            .collect()
+    }
+
     /// Render the tools as Anthropic `tools` JSON.
     #[cfg(feature = "anthropic")]
     fn anthropic_tools_json(&self) -> Vec<Value> {
@@ -227,22 +341,47 @@ impl Toolbox {
 
     /// Render the tools as Gemini `tools` JSON (a single `functionDeclarations`).
     #[cfg(feature = "gemini")]
-    fn gemini_tools_json(&self) -> Vec<Value> {
+    fn gemini_tools_json(&self) -> Result<Vec<Value>> {
         if self.tools.is_empty() {
-            return Vec::new();
+            return Ok(Vec::new());
         }
-        let declarations: Vec<Value> = self
+        let declarations = self
             .tools
             .iter()
             .map(|t| {
-                serde_json::json!({
+                Ok(serde_json::json!({
                     "name": t.name(),
                     "description": t.description(),
-                    "parameters": t.parameters_schema_gemini(),
-                })
+                    "parameters": t.parameters_schema_gemini()?,
+                }))
             })
-            .collect();
-        vec![serde_json::json!({ "functionDeclarations": declarations })]
+            .collect::<Result<Vec<Value>>>()?;
+        Ok(vec![
+            serde_json::json!({ "functionDeclarations": declarations }),
+        ])
+    }
+}
+
+/// How tools are offered to an OpenAI-compatible chat endpoint (OpenAI and
+/// Grok), set with the client's `tool_mode(..)`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ToolMode {
+    /// `tools: [{"type": "function", ..}]` with `tool_choice`, answered with
+    /// `tool` messages. What current APIs and gateways expect.
+    #[default]
+    Tools,
+    /// The deprecated `functions` and `function_call` fields, answered with
+    /// `function` messages, for endpoints that predate `tools`. The model can
+    /// call one function per turn.
+    LegacyFunctions,
+}
+
+/// The system prompt of a tool run: the client's configured system prompt,
+/// then the request's own context, separated by a blank line.
+pub(crate) fn tool_run_system(configured: Option<&str>, call: Option<&str>) -> Option<String> {
+    match (configured, call) {
+        (Some(configured), Some(call)) => Some(format!("{configured}\n\n{call}")),
+        (configured, call) => configured.or(call).map(str::to_string),
     }
 }
 
@@ -257,6 +396,9 @@ pub(crate) async fn run_openai_compatible_tools(
     client: &reqwest::Client,
     url: &str,
     api_key: &str,
+    rate_limiter: Option<&crate::backend::RateLimiter>,
+    max_request_bytes: Option<usize>,
+    tool_mode: ToolMode,
     provider: &str,
     model: &str,
     temperature: f32,
@@ -268,11 +410,14 @@ pub(crate) async fn run_openai_compatible_tools(
     toolbox: &Toolbox,
     max_iterations: usize,
 ) -> Result<String> {
-    use crate::backend::{check_response_status, handle_http_error};
+    use crate::backend::{check_response_status, send_limited};
     use serde_json::json;
     use tracing::{debug, warn};
 
-    let tools_json = toolbox.openai_tools_json();
+    let tools_json = match tool_mode {
+        ToolMode::Tools => toolbox.openai_tools_json(),
+        ToolMode::LegacyFunctions => toolbox.openai_functions_json(),
+    };
     let mut messages: Vec<Value> = Vec::new();
     if let Some(system) = system {
         messages.push(json!({ "role": "system", "content": system }));
@@ -291,8 +436,16 @@ pub(crate) async fn run_openai_compatible_tools(
             "temperature": temperature,
         });
         if !tools_json.is_empty() {
-            body["tools"] = json!(tools_json);
-            body["tool_choice"] = json!("auto");
+            match tool_mode {
+                ToolMode::Tools => {
+                    body["tools"] = json!(tools_json);
+                    body["tool_choice"] = json!("auto");
+                }
+                ToolMode::LegacyFunctions => {
+                    body["functions"] = json!(tools_json);
+                    body["function_call"] = json!("auto");
+                }
+            }
         }
         if let Some(mt) = max_tokens {
             body["max_tokens"] = json!(mt);
@@ -301,14 +454,17 @@ pub(crate) async fn run_openai_compatible_tools(
             body["reasoning_effort"] = json!(effort);
         }
 
-        let response = client
-            .post(url)
-            .header("Authorization", format!("Bearer {api_key}"))
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| handle_http_error(e, provider))?;
+        let response = send_limited(
+            provider,
+            rate_limiter,
+            max_request_bytes,
+            client
+                .post(url)
+                .header("Authorization", format!("Bearer {api_key}"))
+                .header("Content-Type", "application/json")
+                .json(&body),
+        )
+        .await?;
         let response = check_response_status(response, provider).await?;
         let payload: Value = response.json().await.map_err(RStructorError::from)?;
 
@@ -326,10 +482,18 @@ pub(crate) async fn run_openai_compatible_tools(
             })?
             .clone();
 
-        let tool_calls = message
-            .get("tool_calls")
-            .and_then(Value::as_array)
-            .filter(|calls| !calls.is_empty());
+        // A legacy `function_call` is treated as a single id-less tool call
+        let tool_calls = match tool_mode {
+            ToolMode::Tools => message
+                .get("tool_calls")
+                .and_then(Value::as_array)
+                .filter(|calls| !calls.is_empty())
+                .map(|calls| merge_tool_call_fragments(calls)),
+            ToolMode::LegacyFunctions => message
+                .get("function_call")
+                .filter(|call| call.is_object())
+                .map(|call| vec![json!({ "function": call })]),
+        };
 
         let Some(tool_calls) = tool_calls else {
             // No tool calls: the model produced its final answer.
@@ -342,20 +506,25 @@ pub(crate) async fn run_openai_compatible_tools(
             return Ok(content);
         };
 
-        // Record the assistant's tool-call message, then execute each call.
-        messages.push(message.clone());
-        for call in tool_calls {
+        // Record the assistant's tool-call message (with fragments merged, so
+        // every call id is answered exactly once), then execute each call.
+        let mut message = message;
+        if tool_mode == ToolMode::Tools {
+            message["tool_calls"] = Value::Array(tool_calls.clone());
+        }
+        messages.push(message);
+        for call in &tool_calls {
             let call_id = call.get("id").and_then(Value::as_str).unwrap_or_default();
             let function = call.get("function");
             let name = function
                 .and_then(|f| f.get("name"))
                 .and_then(Value::as_str)
                 .unwrap_or_default();
-            let args_str = function
+            let args = function
                 .and_then(|f| f.get("arguments"))
                 .and_then(Value::as_str)
-                .unwrap_or("{}");
-            let args: Value = serde_json::from_str(args_str).unwrap_or_else(|_| json!({}));
+                .and_then(parse_tool_arguments)
+                .unwrap_or_else(|| json!({}));
 
             debug!(tool = name, "Model requested tool call");
             let result = match toolbox.get(name) {
@@ -369,11 +538,19 @@ pub(crate) async fn run_openai_compatible_tools(
                 }
             };
 
-            messages.push(json!({
-                "role": "tool",
-                "tool_call_id": call_id,
-                "content": serde_json::to_string(&result).unwrap_or_default(),
-            }));
+            let content = serde_json::to_string(&result).unwrap_or_default();
+            messages.push(match tool_mode {
+                ToolMode::Tools => json!({
+                    "role": "tool",
+                    "tool_call_id": call_id,
+                    "content": content,
+                }),
+                ToolMode::LegacyFunctions => json!({
+                    "role": "function",
+                    "name": name,
+                    "content": content,
+                }),
+            });
         }
     }
 
@@ -382,6 +559,88 @@ pub(crate) async fn run_openai_compatible_tools(
     )))
 }
 
+/// Merge OpenAI-style `tool_calls` entries that are fragments of one call.
+///
+/// Providers occasionally split a call's `arguments` across several entries:
+/// entries sharing an `index` or `id` are one call, as is an entry with
+/// neither `id` nor function `name` (it continues the previous call). The
+/// argument strings are concatenated in order, then normalized with
+/// [`parse_tool_arguments`].
+#[cfg(any(feature = "openai", feature = "grok"))]
+fn merge_tool_call_fragments(calls: &[Value]) -> Vec<Value> {
+    fn text(value: Option<&Value>) -> &str {
+        value.and_then(Value::as_str).unwrap_or_default()
+    }
+
+    let mut merged: Vec<Value> = Vec::new();
+    for call in calls {
+        let id = text(call.get("id"));
+        let function = call.get("function");
+        let name = text(function.and_then(|f| f.get("name")));
+        let arguments = text(function.and_then(|f| f.get("arguments")));
+
+        let same_call = |earlier: &Value| match call.get("index") {
+            Some(index) => earlier.get("index") == Some(index),
+            None => !id.is_empty() && text(earlier.get("id")) == id,
+        };
+        let target = match merged.iter().position(same_call) {
+            Some(position) => Some(position),
+            None if id.is_empty() && name.is_empty() => merged.len().checked_sub(1),
+            None => None,
+        };
+        let Some(target) = target else {
+            merged.push(call.clone());
+            continue;
+        };
+
+        let earlier = &mut merged[target];
+        if text(earlier.get("id")).is_empty() && !id.is_empty() {
+            earlier["id"] = Value::from(id);
+        }
+        if !earlier.get("function").is_some_and(Value::is_object) {
+            earlier["function"] = serde_json::json!({});
+        }
+        let earlier_function = &mut earlier["function"];
+        if text(earlier_function.get("name")).is_empty() && !name.is_empty() {
+            earlier_function["name"] = Value::from(name);
+        }
+        let joined = format!("{}{arguments}", text(earlier_function.get("arguments")));
+        earlier_function["arguments"] = Value::from(joined);
+    }
+
+    for call in &mut merged {
+        let normalized = call
+            .get("function")
+            .and_then(|f| f.get("arguments"))
+            .and_then(Value::as_str)
+            .and_then(parse_tool_arguments);
+        if let Some(arguments) = normalized {
+            call["function"]["arguments"] = Value::from(arguments.to_string());
+        }
+    }
+    merged
+}
+
+/// Parse tool-call arguments leniently: duplicate keys keep their last
+/// value, consecutive JSON objects (`{"a":1}{"b":2}`, as left by merged
+/// fragments) are combined key by key, and anything after the last complete
+/// value is ignored. `None` if no value parses.
+#[cfg(any(feature = "openai", feature = "grok"))]
+fn parse_tool_arguments(arguments: &str) -> Option<Value> {
+    let mut values = serde_json::Deserializer::from_str(arguments)
+        .into_iter::<Value>()
+        .map_while(|value| value.ok());
+    let first = values.next()?;
+    let Value::Object(mut combined) = first else {
+        return Some(first);
+    };
+    for value in values {
+        let Value::Object(object) = value else { break };
+        combined.extend(object);
+    }
+    Some(Value::Object(combined))
+}
+
 /// Error returned when a tool loop exhausts its iteration budget.
 #[cfg(any(feature = "anthropic", feature = "gemini"))]
 fn loop_exhausted(max_iterations: usize) -> RStructorError {
@@ -397,6 +656,8 @@ pub(crate) async fn run_anthropic_tools(
     client: &reqwest::Client,
     base_url: &str,
     api_key: &str,
+    rate_limiter: Option<&crate::backend::RateLimiter>,
+    max_request_bytes: Option<usize>,
     model: &str,
     temperature: f32,
     max_tokens: u32,
@@ -406,7 +667,7 @@ pub(crate) async fn run_anthropic_tools(
     toolbox: &Toolbox,
     max_iterations: usize,
 ) -> Result<String> {
-    use crate::backend::{check_response_status, handle_http_error};
+    use crate::backend::{check_response_status, send_limited};
     use serde_json::json;
     use tracing::debug;
 
@@ -432,15 +693,18 @@ pub(crate) async fn run_anthropic_tools(
             body["tools"] = json!(tools_json);
         }
 
-        let response = client
-            .post(&url)
-            .header("x-api-key", api_key)
-            .header("anthropic-version", "2023-06-01")
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| handle_http_error(e, "Anthropic"))?;
+        let response = send_limited(
+            "Anthropic",
+            rate_limiter,
+            max_request_bytes,
+            client
+                .post(&url)
+                .header("x-api-key", api_key)
+                .header("anthropic-version", "2023-06-01")
+                .header("Content-Type", "application/json")
+                .json(&body),
+        )
+        .await?;
         let response = check_response_status(response, "Anthropic").await?;
         let payload: Value = response.json().await.map_err(RStructorError::from)?;
 
@@ -501,6 +765,8 @@ pub(crate) async fn run_gemini_tools(
     client: &reqwest::Client,
     base_url: &str,
     api_key: &str,
+    rate_limiter: Option<&crate::backend::RateLimiter>,
+    max_request_bytes: Option<usize>,
     model: &str,
     temperature: f32,
     max_tokens: Option<u32>,
@@ -510,11 +776,11 @@ pub(crate) async fn run_gemini_tools(
     toolbox: &Toolbox,
     max_iterations: usize,
 ) -> Result<String> {
-    use crate::backend::{check_response_status, handle_http_error};
+    use crate::backend::{check_response_status, send_limited};
     use serde_json::json;
     use tracing::debug;
 
-    let tools_json = toolbox.gemini_tools_json();
+    let tools_json = toolbox.gemini_tools_json()?;
     let url = format!("{base_url}/models/{model}:generateContent");
     // Attach any media to the initial user turn, mirroring the materialize path:
     // inline base64 data becomes `inlineData`, URI references become `fileData`.
@@ -545,14 +811,17 @@ pub(crate) async fn run_gemini_tools(
             body["tools"] = json!(tools_json);
         }
 
-        let response = client
-            .post(&url)
-            .query(&[("key", api_key)])
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| handle_http_error(e, "Gemini"))?;
+        let response = send_limited(
+            "Gemini",
+            rate_limiter,
+            max_request_bytes,
+            client
+                .post(&url)
+                .query(&[("key", api_key)])
+                .header("Content-Type", "application/json")
+                .json(&body),
+        )
+        .await?;
         let response = check_response_status(response, "Gemini").await?;
         let payload: Value = response.json().await.map_err(RStructorError::from)?;
 
@@ -730,6 +999,52 @@ mod tests {
         assert_eq!(toolbox.tool_names(), vec!["first", "second", "third"]);
     }
 
+    // ---- TypedFnTool result schema / validation ----
+
+    #[derive(crate::Instructor, Serialize, Deserialize)]
+    #[llm(validate = "validate_sum")]
+    struct Sum {
+        #[llm(description = "The sum of the addends")]
+        sum: i64,
+    }
+
+    fn validate_sum(sum: &Sum) -> Result<()> {
+        if sum.sum < 0 {
+            return Err(RStructorError::ValidationError(
+                "sum must not be negative".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn typed_add_tool() -> impl DynTool {
+        TypedFnTool::new("add", "Add two integers", |args: AddArgs| {
+            std::future::ready(Ok(Sum {
+                sum: args.a + args.b,
+            }))
+        })
+    }
+
+    #[tokio::test]
+    async fn typed_tool_serializes_result_and_reports_its_schema() {
+        let tool = typed_add_tool();
+        let result = tool.invoke_json(json!({ "a": 2, "b": 3 })).await.unwrap();
+        assert_eq!(result, json!({ "sum": 5 }));
+
+        let schema = tool.result_schema().expect("typed tool declares a result");
+        assert_eq!(schema["properties"]["sum"]["type"], "integer");
+        assert!(DynTool::result_schema(&add_tool()).is_none());
+    }
+
+    #[tokio::test]
+    async fn typed_tool_result_failing_validation_is_an_error() {
+        let err = typed_add_tool()
+            .invoke_json(json!({ "a": 2, "b": -5 }))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("sum must not be negative"));
+    }
+
     // ---- openai_tools_json render shape ----
 
     #[cfg(any(feature = "openai", feature = "grok"))]
@@ -796,14 +1111,14 @@ mod tests {
     #[test]
     fn gemini_tools_json_empty_returns_empty_vec() {
         let toolbox = Toolbox::new();
-        assert!(toolbox.gemini_tools_json().is_empty());
+        assert!(toolbox.gemini_tools_json().unwrap().is_empty());
     }
 
     #[cfg(feature = "gemini")]
     #[test]
     fn gemini_tools_json_wraps_declarations() {
         let toolbox = Toolbox::new().with(add_tool());
-        let rendered = toolbox.gemini_tools_json();
+        let rendered = toolbox.gemini_tools_json().unwrap();
         // Populated toolbox yields a single wrapper object.
         assert_eq!(rendered.len(), 1);
 
@@ -827,4 +1142,66 @@ mod tests {
             "title should be stripped from Gemini schema"
         );
     }
+
+    #[cfg(any(feature = "openai", feature = "grok"))]
+    #[test]
+    fn tool_arguments_tolerate_duplicates_concatenation_and_garbage() {
+        let parse = |s: &str| parse_tool_arguments(s);
+        assert_eq!(
+            parse(r#"{"a":1,"b":2,"a":3}"#),
+            Some(json!({ "a": 3, "b": 2 }))
+        );
+        assert_eq!(
+            parse(r#"{"a":1}{"b":2,"a":5}"#),
+            Some(json!({ "a": 5, "b": 2 }))
+        );
+        assert_eq!(parse(r#"{"a":1,"b":2}}"#), Some(json!({ "a": 1, "b": 2 })));
+        assert_eq!(
+            parse("{\"a\":1,\"b\":2}\n<|call_end|>"),
+            Some(json!({ "a": 1, "b": 2 }))
+        );
+        assert_eq!(parse("not json"), None);
+        assert_eq!(parse(""), None);
+    }
+
+    #[cfg(any(feature = "openai", feature = "grok"))]
+    #[test]
+    fn tool_call_fragments_are_merged_per_call() {
+        let call = |id: &str, name: &str, args: &str| json!({ "id": id, "type": "function", "function": { "name": name, "arguments": args } });
+        // Same id, arguments split mid-object; then an anonymous continuation
+        // of a second call; then a separate call with its own id.
+        let merged = merge_tool_call_fragments(&[
+            call("c1", "add", r#"{"a":2,"#),
+            call("c1", "add", r#""b":3}"#),
+            call("c2", "echo", r#"{"a":1,"#),
+            call("", "", r#""b":1,"a":7}"#),
+            call("c3", "add", r#"{"a":1,"b":1}"#),
+        ]);
+        assert_eq!(merged.len(), 3);
+        assert_eq!(merged[0]["id"], "c1");
+        assert_eq!(merged[0]["function"]["arguments"], r#"{"a":2,"b":3}"#);
+        assert_eq!(merged[1]["function"]["name"], "echo");
+        assert_eq!(merged[1]["function"]["arguments"], r#"{"a":7,"b":1}"#);
+        assert_eq!(merged[2]["id"], "c3");
+
+        // Streaming-style fragments keyed by `index`, name and id only on the first.
+        let merged = merge_tool_call_fragments(&[
+            json!({ "index": 0, "id": "c1", "function": { "name": "add", "arguments": "{\"a\"" } }),
+            json!({ "index": 0, "function": { "arguments": ":2,\"b\":3}" } }),
+        ]);
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0]["function"]["name"], "add");
+        assert_eq!(merged[0]["function"]["arguments"], r#"{"a":2,"b":3}"#);
+    }
+
+    #[test]
+    fn tool_run_system_puts_the_configured_prompt_first() {
+        assert_eq!(tool_run_system(None, None), None);
+        assert_eq!(tool_run_system(Some("a"), None).as_deref(), Some("a"));
+        assert_eq!(tool_run_system(None, Some("b")).as_deref(), Some("b"));
+        assert_eq!(
+            tool_run_system(Some("a"), Some("b")).as_deref(),
+            Some("a\n\nb")
+        );
+    }
 }
@@ -1,3 +1,7 @@
+use serde_json::{Map, Value};
+
+use crate::backend::ChatMessage;
+
 /// Token usage information from an LLM API call.
 ///
 /// This struct contains the token counts returned by LLM providers,
@@ -49,6 +53,20 @@ impl TokenUsage {
     pub fn total_tokens(&self) -> u64 {
         self.input_tokens + self.output_tokens
     }
+
+    /// The sum of `total` and `other`, named after `other`'s model (the later
+    /// request's, when summing attempts in order).
+    #[cfg(feature = "_client")]
+    pub(crate) fn accumulate(total: Option<Self>, other: Option<&Self>) -> Option<Self> {
+        match (total, other) {
+            (Some(total), Some(other)) => Some(Self {
+                model: other.model.clone(),
+                input_tokens: total.input_tokens + other.input_tokens,
+                output_tokens: total.output_tokens + other.output_tokens,
+            }),
+            (total, other) => total.or_else(|| other.cloned()),
+        }
+    }
 }
 
 /// Result of a materialize call, containing both the data and optional usage information.
@@ -85,17 +103,101 @@ pub struct MaterializeResult<T> {
     pub data: T,
     /// Token usage information (if available from the provider)
     pub usage: Option<TokenUsage>,
+    /// Token usage summed over every attempt of the call, including replies
+    /// that failed validation and were re-asked. Equal to `usage` when the
+    /// first reply was accepted; `None` if no attempt reported usage.
+    pub total_usage: Option<TokenUsage>,
+    /// The final conversation sent to and received from the model.
+    ///
+    /// Starts with the original user prompt, includes every failed attempt
+    /// together with the validation feedback that was sent back (the re-ask
+    /// turns), and ends with the accepted assistant reply. Persist it to keep a
+    /// full audit trail of an extraction, or to build fine-tuning datasets.
+    ///
+    /// Empty when the client does not track conversation history.
+    pub conversation: Vec<ChatMessage>,
+    /// Fields the model returned that the schema does not declare, keyed by
+    /// JSON Pointer (e.g. `/address/zip`).
+    ///
+    /// Only filled when the client was configured with
+    /// `capture_unknown_fields(true)`; otherwise these fields are dropped during
+    /// deserialization and the map is empty. See [`Schema::unknown_fields`](crate::Schema::unknown_fields).
+    pub extra_fields: Map<String, Value>,
+    /// The model's rationale for its answer, from the
+    /// [`EXPLANATION_FIELD`](crate::EXPLANATION_FIELD) property that clients
+    /// configured with `explain(true)` add to the schema.
+    ///
+    /// `None` when the option is off or the model left the field out.
+    pub explanation: Option<String>,
 }
 
 impl<T> MaterializeResult<T> {
     /// Create a new MaterializeResult with data and usage
     pub fn new(data: T, usage: Option<TokenUsage>) -> Self {
-        Self { data, usage }
+        Self {
+            data,
+            total_usage: usage.clone(),
+            usage,
+            conversation: Vec::new(),
+            extra_fields: Map::new(),
+            explanation: None,
+        }
     }
 
     /// Create a MaterializeResult with just data (no usage info)
     pub fn from_data(data: T) -> Self {
-        Self { data, usage: None }
+        Self::new(data, None)
+    }
+
+    /// Attach the conversation that produced this result.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rstructor::{ChatMessage, MaterializeResult};
+    ///
+    /// let result = MaterializeResult::from_data(42).with_conversation(vec![
+    ///     ChatMessage::user("Pick a number"),
+    ///     ChatMessage::assistant("42"),
+    /// ]);
+    /// assert_eq!(result.conversation.len(), 2);
+    /// assert_eq!(result.attempts(), 1);
+    /// ```
+    #[must_use]
+    pub fn with_conversation(mut self, conversation: Vec<ChatMessage>) -> Self {
+        self.conversation = conversation;
+        self
+    }
+
+    /// Attach the token usage summed over every attempt of the call.
+    #[must_use]
+    pub fn with_total_usage(mut self, total_usage: Option<TokenUsage>) -> Self {
+        self.total_usage = total_usage;
+        self
+    }
+
+    /// Attach the undeclared fields found in the model's output.
+    #[must_use]
+    pub fn with_extra_fields(mut self, extra_fields: Map<String, Value>) -> Self {
+        self.extra_fields = extra_fields;
+        self
+    }
+
+    /// Attach the model's rationale for its answer.
+    #[must_use]
+    pub fn with_explanation(mut self, explanation: Option<String>) -> Self {
+        self.explanation = explanation;
+        self
+    }
+
+    /// Number of model replies in [`conversation`](Self::conversation) — i.e.
+    /// how many attempts it took to get a valid response. Returns 0 when no
+    /// conversation was recorded.
+    pub fn attempts(&self) -> usize {
+        self.conversation
+            .iter()
+            .filter(|m| m.role == crate::backend::ChatRole::Assistant)
+            .count()
     }
 
     /// Map the data to a new type
@@ -103,6 +205,10 @@ impl<T> MaterializeResult<T> {
         MaterializeResult {
             data: f(self.data),
             usage: self.usage,
+            total_usage: self.total_usage,
+            conversation: self.conversation,
+            extra_fields: self.extra_fields,
+            explanation: self.explanation,
         }
     }
 }
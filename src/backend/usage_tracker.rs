@@ -0,0 +1,190 @@
+//! Token and request counts that include every retry.
+//!
+//! [`MaterializeResult::usage`](crate::MaterializeResult::usage) covers only
+//! the accepted reply, so a call that took three re-asks looks as cheap as one
+//! that succeeded first time. A [`UsageTracker`] is fed by the retry engine
+//! after every request it sends, failed or not. Every client owns one, read
+//! with `client.usage_snapshot()`; a tracker can also be shared between
+//! clients with `.usage_tracker(tracker)`, or cover the calls made inside
+//! [`UsageTracker::scope`] whichever client makes them.
+//!
+//! ```no_run
+//! # #[cfg(feature = "openai")]
+//! # async fn run() -> rstructor::Result<()> {
+//! use rstructor::{Instructor, LLMClient, OpenAIClient, UsageTracker};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Instructor, Serialize, Deserialize)]
+//! struct Summary { text: String }
+//!
+//! let client = OpenAIClient::from_env()?;
+//! let per_job = UsageTracker::new();
+//! let summary: Summary = per_job.scope(client.materialize("Summarize: ...")).await?;
+//!
+//! let job = per_job.snapshot();
+//! println!("{} tokens over {} requests", job.total_tokens(), job.attempts);
+//! println!("{} tokens since startup", client.usage_snapshot().total_tokens());
+//! # let _ = summary;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::backend::TokenUsage;
+
+/// Counters of the requests sent for structured calls and the tokens they
+/// used, including re-asks and retries after transient errors.
+///
+/// Clones share the same counters.
+#[derive(Debug, Clone, Default)]
+pub struct UsageTracker {
+    state: Arc<TrackerState>,
+}
+
+#[derive(Debug, Default)]
+struct TrackerState {
+    attempts: AtomicU64,
+    input_tokens: AtomicU64,
+    output_tokens: AtomicU64,
+}
+
+/// The counts of a [`UsageTracker`] at one point in time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UsageSnapshot {
+    /// Requests sent, including the ones whose reply was rejected or that
+    /// failed with an API error.
+    pub attempts: u64,
+    /// Input tokens across all requests that reported usage.
+    pub input_tokens: u64,
+    /// Output tokens across all requests that reported usage.
+    pub output_tokens: u64,
+}
+
+impl UsageSnapshot {
+    /// Total tokens (input + output).
+    #[must_use]
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens + self.output_tokens
+    }
+}
+
+tokio::task_local! {
+    /// Trackers covering the current task's requests, outermost first.
+    static TRACKERS: Vec<UsageTracker>;
+}
+
+impl UsageTracker {
+    /// A tracker with all counts at zero.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The counts so far.
+    ///
+    /// ```
+    /// use rstructor::UsageTracker;
+    ///
+    /// let tracker = UsageTracker::new();
+    /// assert_eq!(tracker.snapshot().attempts, 0);
+    /// assert_eq!(tracker.snapshot().total_tokens(), 0);
+    /// ```
+    #[must_use]
+    pub fn snapshot(&self) -> UsageSnapshot {
+        UsageSnapshot {
+            attempts: self.state.attempts.load(Ordering::Relaxed),
+            input_tokens: self.state.input_tokens.load(Ordering::Relaxed),
+            output_tokens: self.state.output_tokens.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Set all counts back to zero.
+    pub fn reset(&self) {
+        self.state.attempts.store(0, Ordering::Relaxed);
+        self.state.input_tokens.store(0, Ordering::Relaxed);
+        self.state.output_tokens.store(0, Ordering::Relaxed);
+    }
+
+    /// Run `future` with this tracker counting every structured request it
+    /// makes.
+    ///
+    /// Scopes nest, and a request counts towards every tracker in scope, so a
+    /// per-job tracker inside a per-tenant one updates both. The tracker is
+    /// carried by the current task, so calls inside a `tokio::spawn`ed task
+    /// are not covered unless the spawned future is wrapped in `scope` too.
+    pub async fn scope<F: Future>(&self, future: F) -> F::Output {
+        let mut trackers = TRACKERS.try_with(Clone::clone).unwrap_or_default();
+        if !trackers.iter().any(|t| Arc::ptr_eq(&t.state, &self.state)) {
+            trackers.push(self.clone());
+        }
+        TRACKERS.scope(trackers, future).await
+    }
+
+    fn record(&self, usage: Option<&TokenUsage>) {
+        self.state.attempts.fetch_add(1, Ordering::Relaxed);
+        if let Some(usage) = usage {
+            self.state
+                .input_tokens
+                .fetch_add(usage.input_tokens, Ordering::Relaxed);
+            self.state
+                .output_tokens
+                .fetch_add(usage.output_tokens, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Count one request, and the tokens it used if the provider reported them,
+/// towards every tracker in scope.
+pub(crate) fn note_attempt(usage: Option<&TokenUsage>) {
+    let _ = TRACKERS.try_with(|trackers| {
+        for tracker in trackers {
+            tracker.record(usage);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn requests_count_towards_every_tracker_in_scope() {
+        note_attempt(None);
+
+        let outer = UsageTracker::new();
+        let inner = UsageTracker::new();
+        outer
+            .scope(async {
+                note_attempt(None);
+                inner
+                    .scope(async { note_attempt(Some(&TokenUsage::new("m", 10, 5))) })
+                    .await;
+            })
+            .await;
+        assert_eq!(
+            outer.snapshot(),
+            UsageSnapshot {
+                attempts: 2,
+                input_tokens: 10,
+                output_tokens: 5
+            }
+        );
+        assert_eq!(inner.snapshot().attempts, 1);
+        assert_eq!(inner.snapshot().total_tokens(), 15);
+    }
+
+    #[tokio::test]
+    async fn a_tracker_scoped_twice_counts_once() {
+        let tracker = UsageTracker::new();
+        let clone = tracker.clone();
+        tracker
+            .scope(clone.scope(async { note_attempt(Some(&TokenUsage::new("m", 1, 1))) }))
+            .await;
+        assert_eq!(tracker.snapshot().attempts, 1);
+        tracker.reset();
+        assert_eq!(tracker.snapshot(), UsageSnapshot::default());
+    }
+}
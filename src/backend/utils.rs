@@ -1,12 +1,24 @@
+use crate::backend::best_effort;
+use crate::backend::budget::take_retry;
+use crate::backend::journal;
+use crate::backend::overflow::{ContextOverflow, generate_with_overflow_policy};
+use crate::backend::reply::{fault_injection_seam, parse_reply, validation_retry_feedback};
+use crate::backend::telemetry;
+use crate::backend::usage_tracker;
 use crate::backend::{
     ChatMessage, MaterializeInternalOutput, TokenUsage, ValidationFailureContext,
 };
-use crate::error::{ApiErrorKind, RStructorError, Result};
+use crate::error::{ApiErrorKind, ProviderError, RStructorError, Result};
 use crate::model::Instructor;
+use crate::parsing::{extract_json_from_markdown, find_json_candidates};
+use crate::schema::{
+    COUNTER_EXAMPLES_KEY, make_schema_nullable, split_explanation, unwrap_object_root,
+};
 use reqwest::Response;
 use serde::Serialize;
 use serde::de::DeserializeOwned;
 use serde_json::Value;
+use std::sync::{Arc, OnceLock};
 use std::time::Duration;
 use tokio::time::sleep;
 use tracing::{debug, error, info, trace, warn};
@@ -52,17 +64,53 @@ pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
 /// ([`DEFAULT_CONNECT_TIMEOUT`]). Falls back to `reqwest::Client::new()` if the
 /// builder fails (which should never happen with these options).
 pub fn build_http_client(timeout: Duration) -> reqwest::Client {
-    reqwest::Client::builder()
+    try_build_http_client(timeout).unwrap_or_else(|e| {
+        warn!(
+            error = %e,
+            "Failed to build reqwest client with timeout, using default client"
+        );
+        reqwest::Client::new()
+    })
+}
+
+/// Like [`build_http_client`], but reports a builder failure (e.g. a malformed
+/// `HTTPS_PROXY`) instead of falling back.
+pub(crate) fn try_build_http_client(timeout: Duration) -> Result<reqwest::Client> {
+    Ok(reqwest::Client::builder()
         .timeout(timeout)
         .connect_timeout(DEFAULT_CONNECT_TIMEOUT)
-        .build()
-        .unwrap_or_else(|e| {
-            warn!(
-                error = %e,
-                "Failed to build reqwest client with timeout, using default client"
-            );
-            reqwest::Client::new()
-        })
+        .build()?)
+}
+
+/// The HTTP client of a provider client, built on first use.
+///
+/// Builder methods that change HTTP settings call [`reset`](Self::reset) rather
+/// than rebuilding, so a chain of builder calls constructs the reqwest client at
+/// most once: in the client's `build()`, or lazily on the first request. Clones
+/// share the cell, and with it the connection pool.
+#[derive(Clone, Default)]
+pub(crate) struct HttpClientCell(Arc<OnceLock<reqwest::Client>>);
+
+impl HttpClientCell {
+    /// The client for `timeout`, building it if needed.
+    pub(crate) fn get(&self, timeout: Option<Duration>) -> &reqwest::Client {
+        self.0
+            .get_or_init(|| build_http_client(timeout.unwrap_or(DEFAULT_REQUEST_TIMEOUT)))
+    }
+
+    /// Build the client now, surfacing builder errors. A no-op if already built.
+    pub(crate) fn init(&self, timeout: Option<Duration>) -> Result<()> {
+        if self.0.get().is_none() {
+            let client = try_build_http_client(timeout.unwrap_or(DEFAULT_REQUEST_TIMEOUT))?;
+            let _ = self.0.set(client);
+        }
+        Ok(())
+    }
+
+    /// Discard the built client (if any) so the next use picks up new settings.
+    pub(crate) fn reset(&mut self) {
+        *self = Self::default();
+    }
 }
 
 /// Prepare a JSON schema for strict mode by recursively adding required fields
@@ -89,6 +137,7 @@ pub fn build_http_client(timeout: Duration) -> reqwest::Client {
 /// A new schema Value with strict mode requirements added to all objects
 pub fn prepare_strict_schema(schema: &crate::schema::Schema) -> Value {
     let mut schema_json = schema.to_json();
+    crate::schema::strip_translations(&mut schema_json);
     add_additional_properties_false(&mut schema_json);
     schema_json
 }
@@ -100,6 +149,17 @@ pub fn prepare_strict_schema(schema: &crate::schema::Schema) -> Value {
 /// 3. `required` array with all property keys (overriding any existing array)
 fn add_additional_properties_false(schema: &mut Value) {
     if let Some(obj) = schema.as_object_mut() {
+        // Counter-examples are prompt guidance, not part of the output schema
+        obj.remove(COUNTER_EXAMPLES_KEY);
+
+        // Described unit enums are a oneOf of consts; strict-mode APIs take anyOf
+        if !obj.contains_key("anyOf")
+            && const_one_of(obj).is_some()
+            && let Some(one_of) = obj.remove("oneOf")
+        {
+            obj.insert("anyOf".to_string(), one_of);
+        }
+
         // Check if this is an object type schema (the type may already be a
         // ["object", "null"] union if a parent marked this schema optional)
         let is_object_type = match obj.get("type") {
@@ -248,79 +308,26 @@ fn add_additional_properties_false(schema: &mut Value) {
     }
 }
 
-/// Returns true if a schema branch explicitly admits `null` via its `type` keyword.
-fn schema_branch_admits_null(branch: &Value) -> bool {
-    match branch.get("type") {
-        Some(Value::String(t)) => t == "null",
-        Some(Value::Array(types)) => types.iter().any(|t| t.as_str() == Some("null")),
-        _ => false,
-    }
-}
-
-/// Rewrite a property schema so that it also admits `null`.
-///
-/// Used for optional (`Option<T>`) fields under strict mode: strict structured
-/// outputs require every property to be listed in `required`, so optionality is
-/// expressed by allowing `null` instead. Handles the schema shapes this crate
-/// emits:
-///
-/// - `"type": "string"` (scalar) becomes `"type": ["string", "null"]`
-/// - `"type": [...]` (already a union) gets `"null"` appended if absent
-/// - an `"enum"` array gets `null` appended (JSON Schema `enum` constrains
-///   values independently of `type`)
-/// - `anyOf`/`oneOf` unions get a `{"type": "null"}` branch if none exists
-/// - a bare `$ref` (emitted for self-referential structs) is wrapped as
-///   `{"anyOf": [{"$ref": ...}, {"type": "null"}]}`
-///
-/// Schemas without any of these keywords (e.g. `{}`) already admit `null` and
-/// are left untouched.
-fn make_schema_nullable(schema: &mut Value) {
-    let Some(obj) = schema.as_object_mut() else {
-        return;
-    };
-
-    // Bare $ref: type/enum keywords cannot be reliably combined with $ref, so
-    // wrap the reference in an anyOf union with an explicit null branch.
-    if obj.contains_key("$ref") {
-        let original = Value::Object(std::mem::take(obj));
-        *schema = serde_json::json!({
-            "anyOf": [original, { "type": "null" }]
-        });
-        return;
-    }
-
-    // anyOf/oneOf unions: add a {"type": "null"} branch if no branch admits null.
-    for key in ["anyOf", "oneOf"] {
-        if let Some(branches) = obj.get_mut(key).and_then(|v| v.as_array_mut())
-            && !branches.iter().any(schema_branch_admits_null)
-        {
-            branches.push(serde_json::json!({ "type": "null" }));
+/// The `(value, description)` pairs of a `oneOf` made only of `const` branches,
+/// as the derive emits for unit enums with variant descriptions. A
+/// `{"type": "null"}` branch (added for optional fields) is skipped.
+fn const_one_of(obj: &serde_json::Map<String, Value>) -> Option<Vec<(Value, Option<String>)>> {
+    let mut values = Vec::new();
+    for branch in obj.get("oneOf")?.as_array()? {
+        let branch = branch.as_object()?;
+        if branch.len() == 1 && branch.get("type").and_then(Value::as_str) == Some("null") {
+            continue;
         }
-    }
-
-    // type keyword: scalar becomes a [type, "null"] union; existing unions get
-    // "null" appended if absent.
-    if let Some(type_value) = obj.get_mut("type") {
-        match type_value {
-            Value::String(t) => {
-                if t != "null" {
-                    *type_value = serde_json::json!([t.clone(), "null"]);
-                }
-            }
-            Value::Array(types) if !types.iter().any(|t| t.as_str() == Some("null")) => {
-                types.push(serde_json::json!("null"));
-            }
-            _ => {}
+        if branch.keys().any(|k| k != "const" && k != "description") {
+            return None;
         }
+        let description = branch
+            .get("description")
+            .and_then(Value::as_str)
+            .map(String::from);
+        values.push((branch.get("const")?.clone(), description));
     }
-
-    // enum constrains allowed values independently of type, so null must also be
-    // listed as an allowed enum value.
-    if let Some(values) = obj.get_mut("enum").and_then(|e| e.as_array_mut())
-        && !values.iter().any(|v| v.is_null())
-    {
-        values.push(Value::Null);
-    }
+    Some(values)
 }
 
 /// Information about adjacently tagged enum transformations for response conversion
@@ -451,11 +458,73 @@ pub fn transform_internally_to_adjacently_tagged(
     }
 }
 
+/// Maximum schema nesting depth sent to Gemini, which rejects very deep schemas.
+/// [`sanitize_gemini_schema`] refuses schemas that nest deeper.
+pub const GEMINI_MAX_SCHEMA_DEPTH: usize = 16;
+
+/// One change made to a schema by a provider sanitizer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaChange {
+    /// An unsupported keyword was removed.
+    Removed {
+        /// JSON Pointer of the subschema the keyword was removed from.
+        path: String,
+        /// The removed keyword.
+        keyword: String,
+    },
+    /// A construct was rewritten into a supported equivalent.
+    Transformed {
+        /// JSON Pointer of the rewritten subschema.
+        path: String,
+        /// What was done.
+        description: String,
+    },
+}
+
+/// The list of changes a provider sanitizer made to a schema, in the order they
+/// were made (which is deterministic for a given input schema).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchemaSanitizeReport {
+    /// Every change, in order.
+    pub changes: Vec<SchemaChange>,
+}
+
+impl SchemaSanitizeReport {
+    /// Whether the schema was sent unchanged.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// The `(path, keyword)` pairs that were removed.
+    pub fn removed(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.changes.iter().filter_map(|c| match c {
+            SchemaChange::Removed { path, keyword } => Some((path.as_str(), keyword.as_str())),
+            SchemaChange::Transformed { .. } => None,
+        })
+    }
+
+    fn remove(&mut self, obj: &mut serde_json::Map<String, Value>, path: &str, keyword: &str) {
+        if obj.remove(keyword).is_some() {
+            self.changes.push(SchemaChange::Removed {
+                path: path.to_string(),
+                keyword: keyword.to_string(),
+            });
+        }
+    }
+
+    fn transformed(&mut self, path: &str, description: impl Into<String>) {
+        self.changes.push(SchemaChange::Transformed {
+            path: path.to_string(),
+            description: description.into(),
+        });
+    }
+}
+
 /// Prepare a JSON schema for Gemini by stripping unsupported keywords.
 ///
 /// Gemini's structured outputs API doesn't support certain JSON Schema keywords like
 /// `examples`, `additionalProperties`, `title`, etc. This function recursively removes
-/// them from the schema.
+/// them from the schema. See [`sanitize_gemini_schema`] for the list of changes.
 ///
 /// # Arguments
 ///
@@ -463,49 +532,91 @@ pub fn transform_internally_to_adjacently_tagged(
 ///
 /// # Returns
 ///
-/// A new schema Value with unsupported keywords removed
-pub fn prepare_gemini_schema(schema: &crate::schema::Schema) -> Value {
+/// A new schema Value with unsupported keywords removed, or an error if the
+/// schema nests deeper than Gemini allows
+pub fn prepare_gemini_schema(schema: &crate::schema::Schema) -> Result<Value> {
+    let (schema_json, report) = sanitize_gemini_schema(schema)?;
+    if !report.is_empty() {
+        debug!(changes = ?report.changes, "Sanitized schema for Gemini");
+    }
+    Ok(schema_json)
+}
+
+/// Sanitize a schema for Gemini's `response_schema` and report what changed.
+///
+/// `$ref`s are inlined; `examples`, `title`, `default`, `$schema`, `$id` and
+/// boolean `additionalProperties` are removed; and maps and tuples are rewritten
+/// into shapes Gemini accepts. The transformation is deterministic.
+///
+/// # Errors
+///
+/// [`RStructorError::Unsupported`] if the sanitized schema nests deeper than
+/// [`GEMINI_MAX_SCHEMA_DEPTH`]. Cutting the nesting off would leave an object
+/// without properties, which Gemini rejects and no reply could satisfy.
+///
+/// ```
+/// use rstructor::{Schema, sanitize_gemini_schema};
+/// use serde_json::json;
+///
+/// let schema = Schema::new(json!({
+///     "type": "object",
+///     "title": "Movie",
+///     "properties": { "title": { "type": "string", "examples": ["Inception"] } },
+///     "additionalProperties": false
+/// }));
+/// let (gemini, report) = sanitize_gemini_schema(&schema).unwrap();
+/// assert!(gemini.get("additionalProperties").is_none());
+/// let removed: Vec<_> = report.removed().collect();
+/// assert!(removed.contains(&("", "title")));
+/// assert!(removed.contains(&("/properties/title", "examples")));
+/// ```
+pub fn sanitize_gemini_schema(
+    schema: &crate::schema::Schema,
+) -> Result<(Value, SchemaSanitizeReport)> {
     let mut schema_json = schema.to_json();
-    strip_gemini_unsupported_keywords(&mut schema_json);
-    schema_json
+    crate::schema::strip_translations(&mut schema_json);
+    let mut report = SchemaSanitizeReport::default();
+    strip_gemini_unsupported_keywords(&mut schema_json, &mut report);
+    if let Some(path) = gemini_depth_violation(&schema_json, "", 0) {
+        return Err(RStructorError::Unsupported(format!(
+            "schema nests deeper than Gemini's limit of {GEMINI_MAX_SCHEMA_DEPTH} levels \
+             (at `{path}`); flatten the type or use a provider without this limit"
+        )));
+    }
+    Ok((schema_json, report))
 }
 
 /// Recursively removes keywords unsupported by Gemini's structured outputs.
-fn strip_gemini_unsupported_keywords(schema: &mut Value) {
+fn strip_gemini_unsupported_keywords(schema: &mut Value, report: &mut SchemaSanitizeReport) {
     // First, resolve any $ref references by inlining definitions
-    resolve_refs_for_gemini(schema);
+    if resolve_refs_for_gemini(schema) {
+        report.transformed("", "inlined $ref definitions");
+    }
 
-    strip_gemini_unsupported_keywords_recursive(schema);
+    strip_gemini_unsupported_keywords_recursive(schema, "", report);
 }
 
 /// Resolves $ref references by inlining definitions for Gemini compatibility.
-/// This handles recursive schemas by inlining to a limited depth.
-fn resolve_refs_for_gemini(schema: &mut Value) {
-    // Extract $defs if present
-    let defs = if let Some(obj) = schema.as_object_mut() {
-        obj.remove("$defs").or_else(|| obj.remove("definitions"))
-    } else {
-        None
-    };
-
+/// Acyclic references are expanded fully by [`Schema::inline`](crate::schema::Schema::inline);
+/// the references it keeps for recursive types are inlined to a limited depth.
+///
+/// Returns whether the schema had definitions to inline.
+fn resolve_refs_for_gemini(schema: &mut Value) -> bool {
+    let has_defs = schema
+        .as_object()
+        .is_some_and(|obj| obj.contains_key("$defs") || obj.contains_key("definitions"));
+    if !has_defs {
+        return false;
+    }
+    *schema = crate::schema::Schema::new(schema.take()).inline().schema;
+
+    let defs = schema
+        .as_object_mut()
+        .and_then(|obj| obj.remove("$defs").or_else(|| obj.remove("definitions")));
     if let Some(defs) = defs {
-        // If schema has $ref at root, replace with the referenced definition
-        if let Some(obj) = schema.as_object_mut()
-            && let Some(ref_value) = obj.remove("$ref")
-            && let Some(ref_str) = ref_value.as_str()
-            && let Some(def_name) = ref_str
-                .strip_prefix("#/$defs/")
-                .or_else(|| ref_str.strip_prefix("#/definitions/"))
-            && let Some(defs_obj) = defs.as_object()
-            && let Some(definition) = defs_obj.get(def_name)
-        {
-            // Replace schema with the definition
-            *schema = definition.clone();
-
-            // Recursively inline refs in the new schema (with depth limit)
-            inline_refs_recursive(schema, &defs, 3);
-        }
+        inline_refs_recursive(schema, &defs, 3);
     }
+    true
 }
 
 /// Recursively inlines $ref references with a depth limit to prevent infinite recursion.
@@ -697,7 +808,7 @@ fn transform_adjacently_tagged_to_internally_tagged(
         }
 
         // Remove the content field itself
-        properties.remove(content_key);
+        properties.shift_remove(content_key);
     }
 
     // Update required array
@@ -772,23 +883,33 @@ fn normalize_adjacently_tagged_variants(variants: &mut Vec<Value>) {
 }
 
 /// Internal function that strips unsupported keywords after refs are resolved.
-fn strip_gemini_unsupported_keywords_recursive(schema: &mut Value) {
+fn strip_gemini_unsupported_keywords_recursive(
+    schema: &mut Value,
+    path: &str,
+    report: &mut SchemaSanitizeReport,
+) {
     if let Some(obj) = schema.as_object_mut() {
         // Remove unsupported keywords
-        obj.remove("examples");
-        obj.remove("title");
-        obj.remove("$schema");
-        obj.remove("$id");
-        obj.remove("default");
-        obj.remove("$defs");
-        obj.remove("definitions");
-        obj.remove("$ref"); // Should be resolved by now, but remove if any remain
+        // ($ref should be resolved by now, but remove any that remain)
+        for keyword in [
+            "examples",
+            "title",
+            "$schema",
+            "$id",
+            "default",
+            "$defs",
+            "definitions",
+            "$ref",
+            COUNTER_EXAMPLES_KEY,
+        ] {
+            report.remove(obj, path, keyword);
+        }
 
         // Handle additionalProperties: remove if boolean, keep if it's a schema for maps
         if let Some(additional) = obj.get("additionalProperties")
             && additional.is_boolean()
         {
-            obj.remove("additionalProperties");
+            report.remove(obj, path, "additionalProperties");
         }
 
         // For object types with additionalProperties but no properties, this is a Map type
@@ -870,23 +991,28 @@ fn strip_gemini_unsupported_keywords_recursive(schema: &mut Value) {
                 )
             };
             obj.insert("description".to_string(), Value::String(map_desc));
+            report.transformed(path, "map converted to placeholder properties");
         }
 
         // Strip x-enum-keys extension (consumed above for maps, not needed in final schema)
-        obj.remove("x-enum-keys");
+        report.remove(obj, path, "x-enum-keys");
 
         // Recursively process nested schemas
         if let Some(properties) = obj.get_mut("properties")
             && let Some(props_obj) = properties.as_object_mut()
         {
-            for prop_schema in props_obj.values_mut() {
-                strip_gemini_unsupported_keywords_recursive(prop_schema);
+            for (name, prop_schema) in props_obj.iter_mut() {
+                strip_gemini_unsupported_keywords_recursive(
+                    prop_schema,
+                    &format!("{path}/properties/{name}"),
+                    report,
+                );
             }
         }
 
         // Process 'items' for arrays
         if let Some(items) = obj.get_mut("items") {
-            strip_gemini_unsupported_keywords_recursive(items);
+            strip_gemini_unsupported_keywords_recursive(items, &format!("{path}/items"), report);
         }
 
         // Handle tuples (prefixItems) - Gemini doesn't support prefixItems
@@ -897,9 +1023,14 @@ fn strip_gemini_unsupported_keywords_recursive(schema: &mut Value) {
             // Recursively process each item schema
             let mut processed_items: Vec<Value> = arr
                 .iter()
-                .map(|item| {
+                .enumerate()
+                .map(|(i, item)| {
                     let mut item_clone = item.clone();
-                    strip_gemini_unsupported_keywords_recursive(&mut item_clone);
+                    strip_gemini_unsupported_keywords_recursive(
+                        &mut item_clone,
+                        &format!("{path}/prefixItems/{i}"),
+                        report,
+                    );
                     item_clone
                 })
                 .collect();
@@ -941,14 +1072,46 @@ fn strip_gemini_unsupported_keywords_recursive(schema: &mut Value) {
                     existing_desc, tuple_len
                 )),
             );
+            report.transformed(path, "tuple (prefixItems) converted to array");
+        }
+
+        // Described unit enums (a oneOf of consts) become a plain enum, with the
+        // value meanings moved into the description
+        if let Some(values) = const_one_of(obj) {
+            obj.remove("oneOf");
+            let mut description = obj
+                .get("description")
+                .and_then(Value::as_str)
+                .map(|d| format!("{d}\n"))
+                .unwrap_or_default();
+            description.push_str("Allowed values:");
+            for (value, meaning) in &values {
+                let label = value
+                    .as_str()
+                    .map_or_else(|| value.to_string(), String::from);
+                match meaning {
+                    Some(meaning) => description.push_str(&format!("\n- {label}: {meaning}")),
+                    None => description.push_str(&format!("\n- {label}")),
+                }
+            }
+            obj.insert("description".to_string(), Value::String(description));
+            obj.insert(
+                "enum".to_string(),
+                Value::Array(values.into_iter().map(|(value, _)| value).collect()),
+            );
+            report.transformed(path, "oneOf of const values converted to enum");
         }
 
         // Process 'allOf' array
         if let Some(all_of) = obj.get_mut("allOf")
             && let Some(arr) = all_of.as_array_mut()
         {
-            for item in arr.iter_mut() {
-                strip_gemini_unsupported_keywords_recursive(item);
+            for (i, item) in arr.iter_mut().enumerate() {
+                strip_gemini_unsupported_keywords_recursive(
+                    item,
+                    &format!("{path}/allOf/{i}"),
+                    report,
+                );
             }
         }
 
@@ -958,8 +1121,12 @@ fn strip_gemini_unsupported_keywords_recursive(schema: &mut Value) {
                 && let Some(variants) = disjunction.as_array_mut()
             {
                 normalize_adjacently_tagged_variants(variants);
-                for variant in variants.iter_mut() {
-                    strip_gemini_unsupported_keywords_recursive(variant);
+                for (i, variant) in variants.iter_mut().enumerate() {
+                    strip_gemini_unsupported_keywords_recursive(
+                        variant,
+                        &format!("{path}/{key}/{i}"),
+                        report,
+                    );
                 }
             }
         }
@@ -968,11 +1135,50 @@ fn strip_gemini_unsupported_keywords_recursive(schema: &mut Value) {
         if let Some(additional) = obj.get_mut("additionalProperties")
             && additional.is_object()
         {
-            strip_gemini_unsupported_keywords_recursive(additional);
+            strip_gemini_unsupported_keywords_recursive(
+                additional,
+                &format!("{path}/additionalProperties"),
+                report,
+            );
         }
     }
 }
 
+/// The path of the first subschema at [`GEMINI_MAX_SCHEMA_DEPTH`] that still
+/// nests further subschemas, if any.
+fn gemini_depth_violation(schema: &Value, path: &str, depth: usize) -> Option<String> {
+    let obj = schema.as_object()?;
+    let mut children: Vec<(String, &Value)> = Vec::new();
+    if let Some(props) = obj.get("properties").and_then(Value::as_object) {
+        children.extend(
+            props
+                .iter()
+                .map(|(name, child)| (format!("{path}/properties/{name}"), child)),
+        );
+    }
+    for key in ["items", "additionalProperties"] {
+        if let Some(child) = obj.get(key).filter(|child| child.is_object()) {
+            children.push((format!("{path}/{key}"), child));
+        }
+    }
+    for key in ["anyOf", "oneOf", "allOf"] {
+        if let Some(variants) = obj.get(key).and_then(Value::as_array) {
+            children.extend(
+                variants
+                    .iter()
+                    .enumerate()
+                    .map(|(i, child)| (format!("{path}/{key}/{i}"), child)),
+            );
+        }
+    }
+    if depth >= GEMINI_MAX_SCHEMA_DEPTH && !children.is_empty() {
+        return Some(path.to_string());
+    }
+    children
+        .into_iter()
+        .find_map(|(child_path, child)| gemini_depth_violation(child, &child_path, depth + 1))
+}
+
 /// JSON Schema format specification for structured outputs.
 ///
 /// This struct is used by OpenAI and Grok (and potentially other OpenAI-compatible APIs)
@@ -1000,6 +1206,9 @@ pub enum ResponseFormat {
         /// The JSON schema specification
         json_schema: JsonSchemaFormat,
     },
+    /// JSON mode: the reply is a JSON object, but not constrained to a schema
+    #[serde(rename = "json_object")]
+    JsonObject,
 }
 
 impl ResponseFormat {
@@ -1013,7 +1222,7 @@ impl ResponseFormat {
     pub fn json_schema(name: String, schema: Value, description: Option<String>) -> Self {
         ResponseFormat::JsonSchema {
             json_schema: JsonSchemaFormat {
-                name,
+                name: response_format_name(&name),
                 description,
                 schema,
                 strict: true,
@@ -1022,80 +1231,44 @@ impl ResponseFormat {
     }
 }
 
-/// Parse a raw JSON response and validate it against the Instructor trait.
-///
-/// This function handles:
-/// 1. JSON parsing with detailed error messages
-/// 2. Custom validation via the Instructor trait
-///
-/// # Arguments
-///
-/// * `raw_response` - The raw JSON string from the LLM
-///
-/// # Returns
-///
-/// The parsed and validated data, or an error with validation context
-pub fn parse_and_validate_response<T>(
-    raw_response: &str,
-) -> std::result::Result<T, (RStructorError, Option<ValidationFailureContext>)>
-where
-    T: Instructor + DeserializeOwned,
-{
-    // Parse the JSON content into our target type
-    let result: T = match serde_json::from_str(raw_response) {
-        Ok(parsed) => parsed,
-        Err(e) => {
-            let error_msg = format!(
-                "Failed to parse response as JSON: {}\nPartial JSON: {}",
-                e, raw_response
-            );
-            error!(
-                error = %e,
-                content = %raw_response,
-                "JSON parsing error"
-            );
-            return Err((
-                RStructorError::ValidationError(error_msg.clone()),
-                Some(ValidationFailureContext::new(
-                    error_msg,
-                    raw_response.to_string(),
-                )),
-            ));
-        }
-    };
-
-    // Apply any custom validation (business logic beyond schema)
-    if let Err(e) = result.validate() {
-        error!(error = ?e, "Custom validation failed");
-        let error_msg = e.to_string();
-        return Err((
-            e,
-            Some(ValidationFailureContext::new(
-                error_msg,
-                raw_response.to_string(),
-            )),
-        ));
+/// `name` restricted to what `response_format` accepts (`[A-Za-z0-9_-]`, at
+/// most 64 characters): `Vec<Person>` becomes `Vec_Person`.
+fn response_format_name(name: &str) -> String {
+    let name = name
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_");
+    match name.char_indices().nth(64) {
+        Some((end, _)) => name[..end].to_string(),
+        None if name.is_empty() => "output".to_string(),
+        None => name,
     }
-
-    Ok(result)
 }
 
 /// Helper to create a successful MaterializeInternalOutput from parsed data.
 ///
 /// This is a convenience function that combines parsing, validation, and
-/// output construction in one step.
+/// output construction in one step. Parsing is done by
+/// [`parse_reply`](crate::backend::reply::parse_reply), which `MockClient`
+/// shares, so truncated output is reported as
+/// [`ApiErrorKind::UnexpectedResponse`] and re-asked like any other rejected
+/// reply.
 ///
 /// # Arguments
 ///
 /// * `raw_response` - The raw JSON string from the LLM
 /// * `usage` - Optional token usage information
+/// * `provider_name` - Provider name used in error messages
 ///
 /// # Returns
 ///
 /// A MaterializeInternalOutput with the parsed data, or an error
+#[allow(clippy::result_large_err)]
 pub fn parse_validate_and_create_output<T>(
     raw_response: String,
     usage: Option<TokenUsage>,
+    provider_name: &str,
 ) -> std::result::Result<
     MaterializeInternalOutput<T>,
     (RStructorError, Option<ValidationFailureContext>),
@@ -1103,11 +1276,33 @@ pub fn parse_validate_and_create_output<T>(
 where
     T: Instructor + DeserializeOwned,
 {
-    let result = parse_and_validate_response::<T>(&raw_response)?;
+    let raw_response = fault_injection_seam(raw_response);
+    let result = parse_reply::<T>(&raw_response, provider_name)
+        .map_err(|(err, ctx)| (err, ctx.map(|ctx| ctx.with_usage(usage.clone()))))?;
     info!("Successfully generated and validated structured data");
     Ok(MaterializeInternalOutput::new(result, raw_response, usage))
 }
 
+/// Deserialize a reply the way [`parse_validate_and_create_output`] does, but
+/// without validating it. `None` if it does not deserialize into `T`.
+pub(crate) fn parse_unvalidated<T>(raw_response: &str) -> Option<T>
+where
+    T: Instructor + DeserializeOwned,
+{
+    let schema = T::schema();
+    let stripped = split_explanation(&schema, raw_response).map(|(json, _)| json);
+    let reply = stripped.as_deref().unwrap_or(raw_response);
+    let unwrapped = unwrap_object_root(&schema, reply);
+    let reply = unwrapped.as_deref().unwrap_or(reply);
+    serde_json::from_str(extract_json_from_markdown(reply))
+        .ok()
+        .or_else(|| {
+            find_json_candidates(reply)
+                .into_iter()
+                .find_map(|candidate| serde_json::from_str(candidate).ok())
+        })
+}
+
 /// Convert a reqwest error to a RStructorError, handling timeout errors specially.
 ///
 /// Request timeouts become [`RStructorError::Timeout`]. Other transport errors
@@ -1123,8 +1318,14 @@ pub fn handle_http_error(e: reqwest::Error, provider_name: &str) -> RStructorErr
     }
 }
 
+/// Wait before retry `attempt + 1` after an overload without a
+/// `retry-after`: 2s, doubling per attempt, capped at 30s.
+fn overload_backoff(attempt: usize) -> Duration {
+    Duration::from_secs(2_u64 << attempt.min(4)).min(Duration::from_secs(30))
+}
+
 /// Parse retry-after header value to Duration.
-fn parse_retry_after(value: &str) -> Option<Duration> {
+pub(crate) fn parse_retry_after(value: &str) -> Option<Duration> {
     // Try parsing as seconds (most common)
     if let Ok(secs) = value.parse::<u64>() {
         return Some(Duration::from_secs(secs));
@@ -1143,6 +1344,14 @@ fn classify_api_error(
     let code = status.as_u16();
     let error_lower = error_text.to_lowercase();
 
+    let provider_error = ProviderError::parse(error_text).map(Box::new);
+    // Prefer the provider's own message over the raw JSON envelope.
+    let message = provider_error
+        .as_ref()
+        .map(|e| e.message.as_str())
+        .filter(|m| !m.is_empty())
+        .unwrap_or(error_text);
+
     match code {
         // Authentication errors
         401 => ApiErrorKind::AuthenticationFailed,
@@ -1165,14 +1374,40 @@ fn classify_api_error(
             } else {
                 ApiErrorKind::Other {
                     code,
-                    message: error_text.to_string(),
+                    message: message.to_string(),
+                    provider_error,
                 }
             }
         }
 
-        // Bad request
-        400 => ApiErrorKind::BadRequest {
-            details: truncate_message(error_text, 200),
+        // Prompt larger than the context window (a 400 on every provider)
+        400 | 413 | 422
+            if provider_error
+                .as_ref()
+                .is_some_and(|e| e.is_context_length_exceeded()) =>
+        {
+            ApiErrorKind::ContextLengthExceeded {
+                details: truncate_message(message, 300),
+                provider_error,
+            }
+        }
+
+        // Bad request / unprocessable entity
+        400 | 422 => ApiErrorKind::BadRequest {
+            details: truncate_message(
+                &describe_field_errors(error_text).unwrap_or_else(|| message.to_string()),
+                200,
+            ),
+            provider_error,
+        },
+
+        // Request timeout
+        408 => ApiErrorKind::RequestTimeout,
+
+        // Conflict (concurrent request or batch state change)
+        409 => ApiErrorKind::Conflict {
+            details: truncate_message(message, 200),
+            provider_error,
         },
 
         // Payload too large
@@ -1181,6 +1416,13 @@ fn classify_api_error(
         // Rate limited
         429 => ApiErrorKind::RateLimited { retry_after },
 
+        // Provider-wide overload (Anthropic 529 `overloaded_error`), kept apart
+        // from server errors so it can be told apart from bugs
+        529 => ApiErrorKind::Overloaded { retry_after },
+        500..=599 if provider_error.as_ref().is_some_and(|e| e.is_overloaded()) => {
+            ApiErrorKind::Overloaded { retry_after }
+        }
+
         // Server errors
         500 | 502 => ApiErrorKind::ServerError { code },
 
@@ -1193,11 +1435,80 @@ fn classify_api_error(
         // Other errors
         _ => ApiErrorKind::Other {
             code,
-            message: truncate_message(error_text, 500),
+            message: truncate_message(message, 500),
+            provider_error,
         },
     }
 }
 
+/// Render the request fields a structured error body rejects as
+/// `field: message` pairs joined by `; `.
+///
+/// Understands OpenAI-style `error.param`, Gemini `fieldViolations`, and
+/// FastAPI/pydantic `detail[].loc` arrays (served by many OpenAI-compatible
+/// gateways). Returns `None` when the body names no field.
+fn describe_field_errors(error_text: &str) -> Option<String> {
+    let body: Value = serde_json::from_str(error_text).ok()?;
+    let mut fields: Vec<(String, String)> = Vec::new();
+
+    // FastAPI / pydantic: {"detail": [{"loc": ["body", "temperature"], "msg": ".."}]}
+    if let Some(detail) = body.get("detail").and_then(Value::as_array) {
+        for item in detail {
+            let Some(loc) = item.get("loc").and_then(Value::as_array) else {
+                continue;
+            };
+            let path = loc
+                .iter()
+                .skip_while(|seg| seg.as_str() == Some("body"))
+                .map(|seg| match seg {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(".");
+            let msg = item.get("msg").and_then(Value::as_str).unwrap_or("invalid");
+            fields.push((path, msg.to_string()));
+        }
+    }
+
+    if let Some(error) = body.get("error") {
+        let message = error.get("message").and_then(Value::as_str);
+        // OpenAI / Grok: {"error": {"message": "..", "param": "temperature"}}
+        if let Some(param) = error.get("param").and_then(Value::as_str) {
+            fields.push((param.to_string(), message.unwrap_or("invalid").to_string()));
+        }
+        // Gemini: {"error": {"details": [{"fieldViolations": [{"field": .., "description": ..}]}]}}
+        for violation in error
+            .get("details")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|d| d.get("fieldViolations").and_then(Value::as_array))
+            .flatten()
+        {
+            if let Some(field) = violation.get("field").and_then(Value::as_str) {
+                let description = violation
+                    .get("description")
+                    .and_then(Value::as_str)
+                    .or(message)
+                    .unwrap_or("invalid");
+                fields.push((field.to_string(), description.to_string()));
+            }
+        }
+    }
+
+    if fields.is_empty() {
+        return None;
+    }
+    Some(
+        fields
+            .iter()
+            .map(|(field, msg)| format!("{field}: {msg}"))
+            .collect::<Vec<_>>()
+            .join("; "),
+    )
+}
+
 /// Extract model name from error message if present.
 fn extract_model_from_error(error_text: &str) -> Option<String> {
     // Look for quoted model names like 'gpt-4' or "gpt-4"
@@ -1279,22 +1590,6 @@ pub async fn check_response_status(response: Response, provider_name: &str) -> R
     Ok(response)
 }
 
-/// Builds the user-role feedback message sent back to the LLM when a response
-/// fails schema or custom validation (the re-ask prompt).
-///
-/// The array guidance deliberately avoids any minimum-count instruction.
-/// Telling the model to include "at least N" items induces fabrication: a
-/// model that correctly extracted one item (or zero) from the source material
-/// would be instructed to invent more. Instead, the prompt requires arrays to
-/// contain only entries actually supported by the input — a single-item or
-/// empty array is valid when that is all the data supports.
-fn validation_retry_feedback(error_message: &str) -> String {
-    format!(
-        "Your previous response contained validation errors. Please provide a complete, valid JSON response that includes ALL required fields and follows the schema exactly.\n\nError details:\n{}\n\nPlease fix the issues in your response. Make sure to:\n1. Include ALL required fields exactly as specified in the schema\n2. For enum fields, use EXACTLY one of the allowed values from the description\n3. CRITICAL: For arrays where items.type = 'object':\n   - You MUST provide an array of OBJECTS, not strings or primitive values\n   - Each object must be a complete JSON object with all its required fields\n4. Arrays must contain ONLY items actually supported by the input: an array may have a single item or be empty if that is what the data supports — NEVER invent entries to pad an array\n5. Verify all nested objects have their complete structure\n6. Follow ALL type specifications (string, number, boolean, array, object)",
-        error_message
-    )
-}
-
 /// Helper function to execute generation with retry logic using conversation history.
 ///
 /// This function maintains a conversation history across retry attempts, which enables:
@@ -1316,12 +1611,15 @@ fn validation_retry_feedback(error_message: &str) -> String {
 /// * `generate_fn` - Function that takes a conversation history and returns the result plus raw response
 /// * `prompt` - The initial user prompt
 /// * `max_retries` - Maximum number of retry attempts (None or 0 means no retries)
+/// * `overflow` - What to do if the prompt exceeds the model's context window
 pub async fn generate_with_retry_with_history<F, Fut, T>(
     generate_fn: F,
     prompt: &str,
     max_retries: Option<usize>,
+    overflow: &ContextOverflow,
 ) -> Result<MaterializeInternalOutput<T>>
 where
+    T: crate::schema::SchemaType,
     F: FnMut(Vec<ChatMessage>) -> Fut,
     Fut: std::future::Future<
             Output = std::result::Result<
@@ -1330,14 +1628,85 @@ where
             >,
         >,
 {
-    generate_with_retry_with_initial_messages(
+    telemetry::observe(generate_with_overflow_policy(
         generate_fn,
         vec![ChatMessage::user(prompt)],
         max_retries,
-    )
+        overflow,
+    ))
     .await
 }
 
+/// Like [`generate_with_retry_with_history`], but continuing an existing
+/// conversation instead of starting from a single prompt.
+pub async fn generate_with_retry_with_conversation<F, Fut, T>(
+    generate_fn: F,
+    messages: &[ChatMessage],
+    max_retries: Option<usize>,
+    overflow: &ContextOverflow,
+) -> Result<MaterializeInternalOutput<T>>
+where
+    T: crate::schema::SchemaType,
+    F: FnMut(Vec<ChatMessage>) -> Fut,
+    Fut: std::future::Future<
+            Output = std::result::Result<
+                MaterializeInternalOutput<T>,
+                (RStructorError, Option<ValidationFailureContext>),
+            >,
+        >,
+{
+    telemetry::observe(generate_with_overflow_policy(
+        generate_fn,
+        messages.to_vec(),
+        max_retries,
+        overflow,
+    ))
+    .await
+}
+
+/// Header carrying the idempotency key of a structured-output request.
+#[cfg(any(feature = "openai", feature = "anthropic", feature = "grok"))]
+const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+tokio::task_local! {
+    /// Idempotency key of the request the retry engine is currently sending.
+    static IDEMPOTENCY_KEY: String;
+}
+
+/// A fresh, random idempotency key.
+fn new_idempotency_key() -> String {
+    use std::hash::{BuildHasher, Hasher};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let seed = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos());
+    // `RandomState` is seeded from OS randomness, so each half is unpredictable.
+    let half = |salt: u64| {
+        let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+        hasher.write_u64(seed);
+        hasher.write_u128(nanos);
+        hasher.write_u64(salt);
+        hasher.finish()
+    };
+    format!("rstructor-{:016x}{:016x}", half(0), half(1))
+}
+
+/// Attach the retry engine's idempotency key to `request`, if one is in scope.
+///
+/// Providers that honor the header (OpenAI-style `Idempotency-Key`) then return
+/// the original result for a replayed request instead of processing — and
+/// billing — it twice when a response was lost in transit.
+#[cfg(any(feature = "openai", feature = "anthropic", feature = "grok"))]
+pub(crate) fn with_idempotency_key(request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+    match IDEMPOTENCY_KEY.try_with(Clone::clone) {
+        Ok(key) => request.header(IDEMPOTENCY_KEY_HEADER, key),
+        Err(_) => request,
+    }
+}
+
 /// Helper function to execute generation with retry logic using a custom initial
 /// conversation history.
 ///
@@ -1359,13 +1728,39 @@ where
 {
     let Some(max_retries) = max_retries.filter(|&n| n > 0) else {
         // No retries configured - just run once with the provided initial messages
-        return generate_fn(initial_messages).await.map_err(|(err, _)| err);
+        telemetry::note_attempt();
+        let (result, sent) = journal::attempt(
+            IDEMPOTENCY_KEY.scope(new_idempotency_key(), generate_fn(initial_messages.clone())),
+        )
+        .await;
+        return match result {
+            Ok(output) => {
+                usage_tracker::note_attempt(output.usage.as_ref());
+                sent.finish(output.usage.as_ref());
+                Ok(output.with_conversation(initial_messages))
+            }
+            Err((err, ctx)) => {
+                let usage = ctx.as_ref().and_then(|ctx| ctx.usage.as_ref());
+                usage_tracker::note_attempt(usage);
+                sent.finish(usage);
+                if let Some(ctx) = &ctx {
+                    telemetry::note_validation_failure();
+                    best_effort::note_failed_reply(ctx);
+                }
+                Err(err)
+            }
+        };
     };
 
     let max_attempts = max_retries + 1; // +1 for initial attempt
 
     // Initialize conversation history with the provided starting messages.
     let mut messages = initial_messages;
+    // One key per distinct request: kept when a transient failure resends the
+    // same messages, replaced when a re-ask changes them.
+    let mut idempotency_key = new_idempotency_key();
+    // Usage of the replies rejected so far
+    let mut spent: Option<TokenUsage> = None;
 
     trace!(
         "Starting structured generation with conversation history: max_attempts={}",
@@ -1382,8 +1777,16 @@ where
         );
 
         // Attempt to generate structured data
-        match generate_fn(messages.clone()).await {
-            Ok(result) => {
+        telemetry::note_attempt();
+        let (attempt_result, sent) = journal::attempt(
+            IDEMPOTENCY_KEY.scope(idempotency_key.clone(), generate_fn(messages.clone())),
+        )
+        .await;
+        match attempt_result {
+            Ok(mut result) => {
+                usage_tracker::note_attempt(result.usage.as_ref());
+                sent.finish(result.usage.as_ref());
+                result.total_usage = TokenUsage::accumulate(spent, result.usage.as_ref());
                 if attempt > 0 {
                     info!(
                         attempts_used = attempt + 1,
@@ -1393,10 +1796,18 @@ where
                 } else {
                     debug!("Successfully generated on first attempt");
                 }
-                return Ok(result);
+                return Ok(result.with_conversation(messages));
             }
             Err((err, validation_ctx)) => {
                 let is_last_attempt = attempt >= max_attempts - 1;
+                let usage = validation_ctx.as_ref().and_then(|ctx| ctx.usage.as_ref());
+                usage_tracker::note_attempt(usage);
+                sent.finish(usage);
+                spent = TokenUsage::accumulate(spent, usage);
+                if let Some(ctx) = &validation_ctx {
+                    telemetry::note_validation_failure();
+                    best_effort::note_failed_reply(ctx);
+                }
 
                 // A validation failure — whether a schema/parse error or a custom
                 // validator returning ANY error variant — carries a
@@ -1404,8 +1815,11 @@ where
                 // feedback whenever that context is present, rather than keying off
                 // a specific error variant (a validator that returns, say, a
                 // `SchemaError` should still trigger a retry).
+                let retry_allowed = !is_last_attempt
+                    && (validation_ctx.is_some() || err.is_retryable())
+                    && take_retry_or_log(&err);
                 if let Some(ctx) = validation_ctx {
-                    if !is_last_attempt {
+                    if retry_allowed {
                         warn!(
                             attempt = attempt + 1,
                             error = %ctx.error_message,
@@ -1419,6 +1833,7 @@ where
                         // Add user message with error feedback
                         let error_feedback = validation_retry_feedback(&ctx.error_message);
                         messages.push(ChatMessage::user(error_feedback));
+                        idempotency_key = new_idempotency_key();
 
                         debug!(
                             history_len = messages.len(),
@@ -1428,7 +1843,7 @@ where
                         // Wait briefly before retrying
                         sleep(Duration::from_millis(500)).await;
                         continue;
-                    } else {
+                    } else if is_last_attempt {
                         error!(
                             attempts = max_attempts,
                             error = %ctx.error_message,
@@ -1437,8 +1852,13 @@ where
                     }
                 }
                 // Handle retryable API errors (rate limits, transient failures)
-                else if err.is_retryable() && !is_last_attempt {
-                    let delay = err.retry_delay().unwrap_or(Duration::from_secs(1));
+                else if retry_allowed {
+                    let delay = match err.api_error_kind() {
+                        Some(ApiErrorKind::Overloaded { retry_after: None }) => {
+                            overload_backoff(attempt)
+                        }
+                        _ => err.retry_delay().unwrap_or(Duration::from_secs(1)),
+                    };
                     warn!(
                         attempt = attempt + 1,
                         error = ?err,
@@ -1450,14 +1870,14 @@ where
                     sleep(delay).await;
                     continue;
                 }
-                // Non-retryable errors or last attempt
+                // Non-retryable errors, last attempt, or retry budget spent
                 else if is_last_attempt {
                     error!(
                         attempts = max_attempts,
                         error = ?err,
                         "Failed after maximum retry attempts"
                     );
-                } else {
+                } else if !err.is_retryable() {
                     error!(
                         error = ?err,
                         "Non-retryable error occurred during generation"
@@ -1473,6 +1893,16 @@ where
     unreachable!()
 }
 
+/// Take a retry from the [`RetryBudget`](crate::RetryBudget) in scope, logging
+/// when the budget stops a retry of `err`.
+pub(crate) fn take_retry_or_log(err: &RStructorError) -> bool {
+    let allowed = take_retry();
+    if !allowed {
+        warn!(error = %err, "Retry budget exhausted; not retrying");
+    }
+    allowed
+}
+
 /// Helper for provider implementations of `materialize_with_media`.
 ///
 /// Builds an initial media-bearing user message and runs the shared retry/history flow.
@@ -1481,8 +1911,10 @@ pub async fn materialize_with_media_with_retry<F, Fut, T>(
     prompt: &str,
     media: &[crate::backend::client::MediaFile],
     max_retries: Option<usize>,
+    overflow: &ContextOverflow,
 ) -> Result<T>
 where
+    T: crate::schema::SchemaType,
     F: FnMut(Vec<ChatMessage>) -> Fut,
     Fut: std::future::Future<
             Output = std::result::Result<
@@ -1492,25 +1924,192 @@ where
         >,
 {
     let initial_messages = vec![ChatMessage::user_with_media(prompt, media.to_vec())];
-    let output =
-        generate_with_retry_with_initial_messages(generate_fn, initial_messages, max_retries)
-            .await?;
+    let output = telemetry::observe(generate_with_overflow_policy(
+        generate_fn,
+        initial_messages,
+        max_retries,
+        overflow,
+    ))
+    .await?;
     Ok(output.data)
 }
 
 /// Macro to generate standard builder methods for LLM clients.
 ///
 /// This macro generates `model()`, `temperature()`, `max_tokens()`, and `timeout()` methods
-/// that are identical across all LLM client implementations.
+/// that are identical across all LLM client implementations. Pass
+/// `api_key_required: false` for providers whose API key is optional, so that
+/// `build()` accepts an empty one, and `key_provider` for a client whose
+/// per-call keys are looked up under a name other than `provider_name`.
 #[macro_export]
 macro_rules! impl_client_builder_methods {
     (
         client_type: $client:ty,
         config_type: $config:ty,
         model_type: $model:ty,
-        provider_name: $provider:expr
+        provider_name: $provider:expr,
+        max_temperature: $max_temperature:expr
+    ) => {
+        $crate::impl_client_builder_methods! {
+            client_type: $client,
+            config_type: $config,
+            model_type: $model,
+            provider_name: $provider,
+            max_temperature: $max_temperature,
+            api_key_required: true
+        }
+    };
+    (
+        client_type: $client:ty,
+        config_type: $config:ty,
+        model_type: $model:ty,
+        provider_name: $provider:expr,
+        max_temperature: $max_temperature:expr,
+        api_key_required: $api_key_required:expr
+    ) => {
+        $crate::impl_client_builder_methods! {
+            client_type: $client,
+            config_type: $config,
+            model_type: $model,
+            provider_name: $provider,
+            max_temperature: $max_temperature,
+            api_key_required: $api_key_required,
+            key_provider: |_: &$config| $provider
+        }
+    };
+    (
+        client_type: $client:ty,
+        config_type: $config:ty,
+        model_type: $model:ty,
+        provider_name: $provider:expr,
+        max_temperature: $max_temperature:expr,
+        api_key_required: $api_key_required:expr,
+        key_provider: $key_provider:expr
     ) => {
         impl $client {
+            /// The shared HTTP client, built with the configured timeout on first use.
+            fn http(&self) -> &reqwest::Client {
+                self.client.get(self.config.timeout)
+            }
+
+            /// The key to authenticate this request with: a per-call key for
+            /// this client's provider set via
+            /// [`scoped_api_key`](crate::scoped_api_key) if one is in scope,
+            /// otherwise the configured key.
+            fn api_key_for_call(&self) -> String {
+                let key_provider: fn(&$config) -> &'static str = $key_provider;
+                $crate::backend::api_key_override(key_provider(&self.config))
+                    .unwrap_or_else(|| self.config.api_key.clone())
+            }
+
+            /// `T`'s schema with its descriptions in the configured `schema_locale`.
+            fn localized_schema<T: $crate::SchemaType>(&self) -> $crate::Schema {
+                let schema = T::schema();
+                let Some(locale) = &self.config.schema_locale else {
+                    return schema;
+                };
+                match &self.config.description_catalog {
+                    Some(catalog) => schema.localized_with(locale, catalog),
+                    None => schema.localized(locale),
+                }
+            }
+
+            /// The schema requested for `T`: an array or scalar root wrapped in an object
+            /// (see [`Schema::with_object_root`](crate::Schema::with_object_root)),
+            /// plus the rationale property when `explain` is set.
+            fn output_schema<T: $crate::SchemaType>(&self) -> $crate::Schema {
+                let schema = self.localized_schema::<T>().with_object_root();
+                if self.config.explain {
+                    schema.with_explanation()
+                } else {
+                    schema
+                }
+            }
+
+            /// Validate the full configuration and construct the HTTP client.
+            ///
+            /// Builder methods never fail, so a typo such as `.temperature(20.0)` or
+            /// a `base_url` without a scheme otherwise only surfaces on the first
+            /// request. `build()` checks everything at once and builds the reqwest
+            /// client exactly once, with the final timeout and any `HTTP(S)_PROXY`
+            /// settings from the environment. It is optional: an unbuilt client
+            /// builds its HTTP client lazily on the first request.
+            ///
+            /// # Errors
+            ///
+            /// Returns [`RStructorError::ConfigError`](crate::RStructorError::ConfigError)
+            /// for an empty API key or model name, a temperature outside the
+            /// provider's range, `max_tokens` above the model's known output limit,
+            /// a zero timeout, a non-HTTP(S) `base_url`, or a zero rate limit budget; or
+            /// [`RStructorError::HttpError`](crate::RStructorError::HttpError) if the
+            /// HTTP client cannot be constructed.
+            ///
+            /// # Examples
+            ///
+            /// ```no_run
+            /// # use rstructor::OpenAIClient;
+            /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+            /// let client = OpenAIClient::new("api-key")?
+            ///     .temperature(0.2)
+            ///     .timeout(std::time::Duration::from_secs(30))
+            ///     .build()?;
+            /// # Ok(())
+            /// # }
+            /// ```
+            pub fn build(self) -> $crate::Result<Self> {
+                let config = &self.config;
+                let invalid = |message: String| {
+                    Err($crate::RStructorError::ConfigError(format!(
+                        "{}: {}",
+                        $provider, message
+                    )))
+                };
+                let api_key_required: bool = $api_key_required;
+                if api_key_required && config.api_key.trim().is_empty() {
+                    return invalid("API key is empty".to_string());
+                }
+                let model = config.model.as_str();
+                if model.trim().is_empty() {
+                    return invalid("model name is empty".to_string());
+                }
+                let max_temperature: f32 = $max_temperature;
+                if !(0.0..=max_temperature).contains(&config.temperature) {
+                    return invalid(format!(
+                        "temperature {} is outside 0.0..={}",
+                        config.temperature, max_temperature
+                    ));
+                }
+                if let Some(max_tokens) = config.max_tokens
+                    && let Some(limit) = self.capabilities().max_output_tokens
+                    && max_tokens > limit
+                {
+                    return invalid(format!(
+                        "max_tokens {max_tokens} exceeds {model}'s output limit of {limit}"
+                    ));
+                }
+                if config.timeout == Some(std::time::Duration::ZERO) {
+                    return invalid("timeout must be greater than zero".to_string());
+                }
+                if let Some(url) = &config.base_url
+                    && !(url.starts_with("http://") || url.starts_with("https://"))
+                {
+                    return invalid(format!("base_url {url:?} must start with http:// or https://"));
+                }
+                if let Some(limiter) = &config.rate_limiter
+                    && !limiter.is_satisfiable()
+                {
+                    return invalid("rate limit budgets must be non-zero".to_string());
+                }
+                self.client.init(config.timeout)?;
+                Ok(self)
+            }
+
+            /// Capabilities of the configured model (context window, output limit,
+            /// media and thinking support).
+            pub fn capabilities(&self) -> $crate::backend::ProviderCapabilities {
+                $crate::backend::ProviderCapabilities::for_model(&self.model_id())
+            }
+
             /// Set the model to use. Accepts either a Model enum variant or a string.
             ///
             /// When a string is provided, it will be converted to a Model enum. If the string
@@ -1525,7 +2124,31 @@ macro_rules! impl_client_builder_methods {
                     new_model = ?model,
                     "Setting {} model", $provider
                 );
-                self.config.model = model;
+                std::sync::Arc::make_mut(&mut self.config).model = model;
+                $crate::backend::warn_if_deprecated(&self.model_id());
+                self
+            }
+
+            /// The configured model, qualified by provider.
+            pub fn model_id(&self) -> $crate::backend::ModelId {
+                (&self.config.model).into()
+            }
+
+            /// Replace a known-deprecated model with its documented replacement.
+            ///
+            /// Does nothing if the configured model is not in the bundled
+            /// deprecation table.
+            pub fn upgrade_deprecated_model(mut self) -> Self {
+                let current = self.model_id();
+                let upgraded = current.upgraded();
+                if upgraded != current {
+                    tracing::info!(
+                        from = %current.name,
+                        to = %upgraded.name,
+                        "Upgrading deprecated {} model", $provider
+                    );
+                    std::sync::Arc::make_mut(&mut self.config).model = upgraded.name.into();
+                }
                 self
             }
 
@@ -1537,7 +2160,7 @@ macro_rules! impl_client_builder_methods {
                     new_temp = temp,
                     "Setting temperature"
                 );
-                self.config.temperature = temp;
+                std::sync::Arc::make_mut(&mut self.config).temperature = temp;
                 self
             }
 
@@ -1550,67 +2173,326 @@ macro_rules! impl_client_builder_methods {
                     "Setting max_tokens"
                 );
                 // Ensure max_tokens is at least 1 to avoid API errors
-                self.config.max_tokens = Some(max.max(1));
+                std::sync::Arc::make_mut(&mut self.config).max_tokens = Some(max.max(1));
+                self
+            }
+
+            /// Set the timeout for HTTP requests.
+            ///
+            /// This sets the total timeout for each HTTP request made by the
+            /// client (connection + response). The connect phase additionally
+            /// keeps the default connect timeout of 30 seconds
+            /// ([`DEFAULT_CONNECT_TIMEOUT`](crate::DEFAULT_CONNECT_TIMEOUT)).
+            ///
+            /// If not called, requests use the default timeout of 5 minutes
+            /// ([`DEFAULT_REQUEST_TIMEOUT`](crate::DEFAULT_REQUEST_TIMEOUT)).
+            ///
+            /// # Arguments
+            ///
+            /// * `timeout` - Timeout duration (e.g., `Duration::from_secs(30)` for 30 seconds)
+            #[tracing::instrument(skip(self))]
+            pub fn timeout(mut self, timeout: std::time::Duration) -> Self {
+                tracing::debug!(
+                    previous_timeout = ?self.config.timeout,
+                    new_timeout = ?timeout,
+                    "Setting timeout"
+                );
+                std::sync::Arc::make_mut(&mut self.config).timeout = Some(timeout);
+
+                // The HTTP client is rebuilt with the new timeout on build() or first use
+                self.client.reset();
+
+                self
+            }
+
+            /// Set the maximum number of retry attempts for validation errors.
+            ///
+            /// When `materialize` encounters a validation error, it will automatically
+            /// retry up to this many times, including the validation error message in subsequent attempts.
+            ///
+            /// The default is 3 retries. Use `.no_retries()` to disable retries entirely.
+            ///
+            /// # Arguments
+            ///
+            /// * `max_retries` - Maximum number of retry attempts (0 = no retries, only single attempt)
+            ///
+            /// # Examples
+            ///
+            /// ```no_run
+            /// # use rstructor::OpenAIClient;
+            /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+            /// let client = OpenAIClient::new("api-key")?
+            ///     .max_retries(5);  // Increase to 5 retries (default is 3)
+            /// # Ok(())
+            /// # }
+            /// ```
+            #[tracing::instrument(skip(self))]
+            pub fn max_retries(mut self, max_retries: usize) -> Self {
+                tracing::debug!(
+                    previous_max_retries = ?self.config.max_retries,
+                    new_max_retries = max_retries,
+                    "Setting max_retries"
+                );
+                std::sync::Arc::make_mut(&mut self.config).max_retries = Some(max_retries);
+                self
+            }
+
+            /// Report fields the model returned but the schema does not declare.
+            ///
+            /// Serde drops undeclared fields during deserialization. With this
+            /// enabled, `materialize_with_metadata` also returns them in
+            /// [`MaterializeResult::extra_fields`](crate::MaterializeResult::extra_fields),
+            /// keyed by JSON Pointer — handy while iterating on a schema to see
+            /// what else the model volunteers. Off by default.
+            ///
+            /// # Examples
+            ///
+            /// ```no_run
+            /// # use rstructor::OpenAIClient;
+            /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+            /// let client = OpenAIClient::new("api-key")?.capture_unknown_fields(true);
+            /// # Ok(())
+            /// # }
+            /// ```
+            #[tracing::instrument(skip(self))]
+            pub fn capture_unknown_fields(mut self, enabled: bool) -> Self {
+                std::sync::Arc::make_mut(&mut self.config).capture_unknown_fields = enabled;
+                self
+            }
+
+            /// Ask the model to explain its answer without adding a field to the
+            /// domain type.
+            ///
+            /// Structured requests get a required
+            /// [`EXPLANATION_FIELD`](crate::EXPLANATION_FIELD) string property
+            /// ahead of the type's own fields, so the model reasons before it
+            /// answers. The property is stripped before deserialization, and
+            /// `materialize_with_metadata` returns its text in
+            /// [`MaterializeResult::explanation`](crate::MaterializeResult::explanation).
+            /// Costs the output tokens of the rationale. Off by default; streaming
+            /// calls are unaffected.
+            ///
+            /// # Examples
+            ///
+            /// ```no_run
+            /// # use rstructor::OpenAIClient;
+            /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+            /// let client = OpenAIClient::new("api-key")?.explain(true);
+            /// # Ok(())
+            /// # }
+            /// ```
+            #[tracing::instrument(skip(self))]
+            pub fn explain(mut self, enabled: bool) -> Self {
+                std::sync::Arc::make_mut(&mut self.config).explain = enabled;
+                self
+            }
+
+            /// Choose what structured requests do when the prompt exceeds the
+            /// model's context window.
+            ///
+            /// By default the [`ContextLengthExceeded`](crate::ApiErrorKind::ContextLengthExceeded)
+            /// error is returned. [`ContextOverflow::Truncate`](crate::ContextOverflow::Truncate)
+            /// retries with the longest user message shortened, and
+            /// [`ContextOverflow::Escalate`](crate::ContextOverflow::Escalate) retries once
+            /// on a larger-context model.
+            ///
+            /// # Examples
+            ///
+            /// ```no_run
+            /// # use rstructor::{ContextOverflow, OpenAIClient};
+            /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+            /// let client = OpenAIClient::new("api-key")?
+            ///     .on_context_overflow(ContextOverflow::Truncate);
+            /// # Ok(())
+            /// # }
+            /// ```
+            #[tracing::instrument(skip(self))]
+            pub fn on_context_overflow(mut self, policy: $crate::ContextOverflow) -> Self {
+                std::sync::Arc::make_mut(&mut self.config).context_overflow = policy;
+                self
+            }
+
+            /// Send `prompt` as the system prompt of every request, for domain
+            /// instructions such as "You are a medical coder".
+            ///
+            /// It goes out in the provider's system role (a `system` message,
+            /// Anthropic's `system` field, Gemini's `systemInstruction`) rather
+            /// than being prepended to the user prompt. Context attached with
+            /// [`Request::system`](crate::Request::system) is added after it for
+            /// tool runs.
+            ///
+            /// # Examples
+            ///
+            /// ```no_run
+            /// # use rstructor::OpenAIClient;
+            /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+            /// let client = OpenAIClient::new("api-key")?
+            ///     .system_prompt("You are a medical coder. Use ICD-10 codes.");
+            /// # Ok(())
+            /// # }
+            /// ```
+            #[tracing::instrument(skip(self, prompt))]
+            pub fn system_prompt(mut self, prompt: impl Into<String>) -> Self {
+                std::sync::Arc::make_mut(&mut self.config).system_prompt = Some(prompt.into());
+                self
+            }
+
+            /// Send schema descriptions in `locale`, e.g. the language of the
+            /// documents being extracted from.
+            ///
+            /// Translations come from `#[llm(description(en = "..", de = ".."))]`
+            /// and from [`description_catalog`](Self::description_catalog). A
+            /// regional locale such as `de-AT` falls back to `de`, and
+            /// descriptions without a translation are sent as written.
+            ///
+            /// # Examples
+            ///
+            /// ```no_run
+            /// # use rstructor::OpenAIClient;
+            /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+            /// let client = OpenAIClient::new("api-key")?;
+            /// let german = client.clone().schema_locale("de");
+            /// # Ok(())
+            /// # }
+            /// ```
+            #[tracing::instrument(skip(self, locale))]
+            pub fn schema_locale(mut self, locale: impl Into<String>) -> Self {
+                std::sync::Arc::make_mut(&mut self.config).schema_locale = Some(locale.into());
                 self
             }
 
-            /// Set the timeout for HTTP requests.
+            /// Translate schema descriptions from `catalog` when a
+            /// [`schema_locale`](Self::schema_locale) is set. Its entries take
+            /// precedence over those recorded by the derive.
             ///
-            /// This sets the total timeout for each HTTP request made by the
-            /// client (connection + response). The connect phase additionally
-            /// keeps the default connect timeout of 30 seconds
-            /// ([`DEFAULT_CONNECT_TIMEOUT`](crate::DEFAULT_CONNECT_TIMEOUT)).
+            /// # Examples
             ///
-            /// If not called, requests use the default timeout of 5 minutes
-            /// ([`DEFAULT_REQUEST_TIMEOUT`](crate::DEFAULT_REQUEST_TIMEOUT)).
+            /// ```no_run
+            /// # use rstructor::{DescriptionCatalog, OpenAIClient};
+            /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+            /// let catalog = DescriptionCatalog::from_json(&serde_json::from_str(
+            ///     &std::fs::read_to_string("descriptions.json")?,
+            /// )?)?;
+            /// let client = OpenAIClient::new("api-key")?
+            ///     .description_catalog(catalog)
+            ///     .schema_locale("fr");
+            /// # Ok(())
+            /// # }
+            /// ```
+            #[tracing::instrument(skip(self, catalog))]
+            pub fn description_catalog(mut self, catalog: $crate::DescriptionCatalog) -> Self {
+                std::sync::Arc::make_mut(&mut self.config).description_catalog =
+                    Some(std::sync::Arc::new(catalog));
+                self
+            }
+
+            /// Keep this client under a request and a token budget, delaying
+            /// requests until the budgets have room. Use
+            /// [`rate_limiter`](Self::rate_limiter) to share one limiter
+            /// between clients.
             ///
-            /// # Arguments
+            /// # Examples
             ///
-            /// * `timeout` - Timeout duration (e.g., `Duration::from_secs(30)` for 30 seconds)
+            /// ```no_run
+            /// # use rstructor::{OpenAIClient, Requests, Tokens};
+            /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+            /// let client = OpenAIClient::new("api-key")?
+            ///     .rate_limit(Requests::per_minute(60), Tokens::per_minute(90_000));
+            /// # Ok(())
+            /// # }
+            /// ```
             #[tracing::instrument(skip(self))]
-            pub fn timeout(mut self, timeout: std::time::Duration) -> Self {
-                tracing::debug!(
-                    previous_timeout = ?self.config.timeout,
-                    new_timeout = ?timeout,
-                    "Setting timeout"
-                );
-                self.config.timeout = Some(timeout);
-
-                // Rebuild reqwest client with the new timeout immediately
-                self.client = $crate::backend::utils::build_http_client(timeout);
+            pub fn rate_limit(self, requests: $crate::Requests, tokens: $crate::Tokens) -> Self {
+                self.rate_limiter(std::sync::Arc::new(
+                    $crate::RateLimiter::new().requests(requests).tokens(tokens),
+                ))
+            }
 
+            /// Send requests through `limiter`, which may be shared with other
+            /// clients drawing on the same provider account. A `retry-after`
+            /// received by any of them holds back all of them.
+            ///
+            /// # Examples
+            ///
+            /// ```no_run
+            /// # use std::sync::Arc;
+            /// # use rstructor::{OpenAIClient, RateLimiter, Requests};
+            /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+            /// let limiter = Arc::new(RateLimiter::new().requests(Requests::per_minute(500)));
+            /// let mini = OpenAIClient::new("api-key")?.model("gpt-4o-mini").rate_limiter(limiter.clone());
+            /// let full = OpenAIClient::new("api-key")?.model("gpt-4o").rate_limiter(limiter);
+            /// # Ok(())
+            /// # }
+            /// ```
+            #[tracing::instrument(skip(self, limiter))]
+            pub fn rate_limiter(mut self, limiter: std::sync::Arc<$crate::RateLimiter>) -> Self {
+                std::sync::Arc::make_mut(&mut self.config).rate_limiter = Some(limiter);
                 self
             }
 
-            /// Set the maximum number of retry attempts for validation errors.
-            ///
-            /// When `materialize` encounters a validation error, it will automatically
-            /// retry up to this many times, including the validation error message in subsequent attempts.
+            /// Count this client's requests and tokens in `tracker` instead of
+            /// the client's own, e.g. to total the usage of several clients.
             ///
-            /// The default is 3 retries. Use `.no_retries()` to disable retries entirely.
+            /// # Examples
             ///
-            /// # Arguments
+            /// ```no_run
+            /// # use rstructor::{OpenAIClient, UsageTracker};
+            /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+            /// let tracker = UsageTracker::new();
+            /// let mini = OpenAIClient::new("api-key")?.model("gpt-4o-mini").usage_tracker(tracker.clone());
+            /// let full = OpenAIClient::new("api-key")?.model("gpt-4o").usage_tracker(tracker.clone());
+            /// // ... later
+            /// println!("{} tokens", tracker.snapshot().total_tokens());
+            /// # Ok(())
+            /// # }
+            /// ```
+            #[tracing::instrument(skip(self, tracker))]
+            pub fn usage_tracker(mut self, tracker: $crate::UsageTracker) -> Self {
+                std::sync::Arc::make_mut(&mut self.config).usage_tracker = tracker;
+                self
+            }
+
+            /// Requests sent and tokens used by this client's structured calls
+            /// so far, counting every retry. Clones of the client share the
+            /// same counts.
+            #[must_use]
+            pub fn usage_snapshot(&self) -> $crate::UsageSnapshot {
+                self.config.usage_tracker.snapshot()
+            }
+
+            /// Refuse to send request bodies larger than `bytes`, failing with
+            /// [`RStructorError::RequestTooLarge`]($crate::RStructorError::RequestTooLarge)
+            /// (which names whether the prompt, the schema or attached media
+            /// takes up most of it) instead of uploading the body only to get a
+            /// 413 back.
             ///
-            /// * `max_retries` - Maximum number of retry attempts (0 = no retries, only single attempt)
+            /// Defaults to the provider's published limit: 50 MB for OpenAI,
+            /// 32 MB for Anthropic and 20 MB for Gemini. Other providers publish
+            /// none, so their clients check nothing unless a limit is set here.
+            /// [`without_request_size_limit`](Self::without_request_size_limit)
+            /// turns the check off.
             ///
             /// # Examples
             ///
             /// ```no_run
             /// # use rstructor::OpenAIClient;
             /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
-            /// let client = OpenAIClient::new("api-key")?
-            ///     .max_retries(5);  // Increase to 5 retries (default is 3)
+            /// // A proxy in front of the API accepts at most 10 MB
+            /// let client = OpenAIClient::new("api-key")?.max_request_bytes(10_000_000);
             /// # Ok(())
             /// # }
             /// ```
             #[tracing::instrument(skip(self))]
-            pub fn max_retries(mut self, max_retries: usize) -> Self {
-                tracing::debug!(
-                    previous_max_retries = ?self.config.max_retries,
-                    new_max_retries = max_retries,
-                    "Setting max_retries"
-                );
-                self.config.max_retries = Some(max_retries);
+            pub fn max_request_bytes(mut self, bytes: usize) -> Self {
+                std::sync::Arc::make_mut(&mut self.config).max_request_bytes = Some(bytes);
+                self
+            }
+
+            /// Send request bodies of any size, leaving the provider to refuse
+            /// ones that are too large.
+            #[tracing::instrument(skip(self))]
+            pub fn without_request_size_limit(mut self) -> Self {
+                std::sync::Arc::make_mut(&mut self.config).max_request_bytes = None;
                 self
             }
 
@@ -1635,7 +2517,7 @@ macro_rules! impl_client_builder_methods {
                     previous_max_retries = ?self.config.max_retries,
                     "Disabling retries"
                 );
-                self.config.max_retries = Some(0);
+                std::sync::Arc::make_mut(&mut self.config).max_retries = Some(0);
                 self
             }
         }
@@ -1645,6 +2527,19 @@ macro_rules! impl_client_builder_methods {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::backend::reply::parse_and_validate_response;
+
+    #[test]
+    fn test_response_format_name() {
+        assert_eq!(response_format_name("Person"), "Person");
+        assert_eq!(response_format_name("Vec<Person>"), "Vec_Person");
+        assert_eq!(
+            response_format_name("HashMap<String, Vec<u8>>"),
+            "HashMap_String_Vec_u8"
+        );
+        assert_eq!(response_format_name("<>"), "output");
+        assert_eq!(response_format_name(&"a".repeat(80)).len(), 64);
+    }
 
     #[test]
     fn test_add_additional_properties_simple_object() {
@@ -1943,6 +2838,79 @@ mod tests {
         }
     }
 
+    fn described_enum_property() -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "level": {
+                    "type": "string",
+                    "description": "Severity",
+                    "oneOf": [
+                        { "const": "low", "description": "Cosmetic" },
+                        { "const": "high" }
+                    ]
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn test_strict_mode_const_one_of_becomes_any_of() {
+        let mut schema = described_enum_property();
+        add_additional_properties_false(&mut schema);
+        let level = &schema["properties"]["level"];
+        assert!(level.get("oneOf").is_none());
+        // The optional field's null branch is kept alongside the consts
+        assert_eq!(
+            level["anyOf"],
+            serde_json::json!([
+                { "const": "low", "description": "Cosmetic" },
+                { "const": "high" },
+                { "type": "null" }
+            ])
+        );
+    }
+
+    #[test]
+    fn test_gemini_const_one_of_becomes_enum_with_meanings() {
+        let schema = crate::schema::Schema::new(described_enum_property());
+        let (gemini, report) = sanitize_gemini_schema(&schema).unwrap();
+        let level = &gemini["properties"]["level"];
+        assert!(level.get("oneOf").is_none());
+        assert_eq!(level["enum"], serde_json::json!(["low", "high"]));
+        assert_eq!(
+            level["description"],
+            "Severity\nAllowed values:\n- low: Cosmetic\n- high"
+        );
+        assert!(report.changes.contains(&SchemaChange::Transformed {
+            path: "/properties/level".to_string(),
+            description: "oneOf of const values converted to enum".to_string(),
+        }));
+    }
+
+    #[test]
+    fn test_counter_examples_are_not_sent_as_schema() {
+        let mut schema = serde_json::json!({
+            "type": "object",
+            "x-counter-examples": [{ "a": "N/A" }],
+            "properties": {
+                "inner": { "type": "object", "x-counter-examples": ["?"], "properties": {} }
+            }
+        });
+        let gemini = sanitize_gemini_schema(&crate::schema::Schema::new(schema.clone()))
+            .unwrap()
+            .0;
+        add_additional_properties_false(&mut schema);
+        for prepared in [schema, gemini] {
+            assert!(prepared.get(COUNTER_EXAMPLES_KEY).is_none());
+            assert!(
+                prepared["properties"]["inner"]
+                    .get(COUNTER_EXAMPLES_KEY)
+                    .is_none()
+            );
+        }
+    }
+
     #[test]
     fn test_strict_mode_optional_nested_object_and_array() {
         // Optionality is resolved per object level: the outer object's optional
@@ -2318,7 +3286,7 @@ mod tests {
             }]
         }));
 
-        let gemini_schema = prepare_gemini_schema(&schema);
+        let gemini_schema = prepare_gemini_schema(&schema).unwrap();
 
         // Verify examples is stripped
         assert!(
@@ -2368,7 +3336,7 @@ mod tests {
             }]
         }));
 
-        let gemini_schema = prepare_gemini_schema(&schema);
+        let gemini_schema = prepare_gemini_schema(&schema).unwrap();
 
         // Verify examples is stripped at root
         assert!(
@@ -2395,7 +3363,7 @@ mod tests {
             "additionalProperties": false
         });
 
-        strip_gemini_unsupported_keywords(&mut schema_json);
+        strip_gemini_unsupported_keywords(&mut schema_json, &mut SchemaSanitizeReport::default());
 
         assert!(
             schema_json.get("additionalProperties").is_none(),
@@ -2417,7 +3385,7 @@ mod tests {
             }
         });
 
-        strip_gemini_unsupported_keywords(&mut schema_json);
+        strip_gemini_unsupported_keywords(&mut schema_json, &mut SchemaSanitizeReport::default());
 
         assert!(
             schema_json.get("$schema").is_none(),
@@ -2513,7 +3481,11 @@ mod tests {
             ]
         });
 
-        strip_gemini_unsupported_keywords_recursive(&mut schema);
+        strip_gemini_unsupported_keywords_recursive(
+            &mut schema,
+            "",
+            &mut SchemaSanitizeReport::default(),
+        );
 
         let first_props = schema["anyOf"][0]["properties"]
             .as_object()
@@ -2530,6 +3502,102 @@ mod tests {
         assert!(!first_required.contains(&serde_json::json!("data")));
     }
 
+    #[test]
+    fn test_gemini_sanitize_report_lists_changes() {
+        use crate::schema::Schema;
+
+        let schema = Schema::new(serde_json::json!({
+            "type": "object",
+            "title": "Config",
+            "properties": {
+                "name": { "type": "string", "default": "x", "examples": ["a"] },
+                "labels": {
+                    "type": "object",
+                    "additionalProperties": { "type": "string" }
+                }
+            },
+            "additionalProperties": false
+        }));
+        let (_, report) = sanitize_gemini_schema(&schema).unwrap();
+        let removed: Vec<_> = report.removed().collect();
+        assert_eq!(
+            removed,
+            vec![
+                ("", "title"),
+                ("", "additionalProperties"),
+                ("/properties/name", "examples"),
+                ("/properties/name", "default"),
+            ]
+        );
+        assert!(report.changes.contains(&SchemaChange::Transformed {
+            path: "/properties/labels".to_string(),
+            description: "map converted to placeholder properties".to_string(),
+        }));
+        assert_eq!(
+            sanitize_gemini_schema(&schema).unwrap().1,
+            report,
+            "deterministic"
+        );
+    }
+
+    #[test]
+    fn test_gemini_sanitize_report_empty_for_clean_schema() {
+        use crate::schema::Schema;
+
+        let schema = Schema::new(serde_json::json!({
+            "type": "object",
+            "properties": { "name": { "type": "string" } },
+            "required": ["name"]
+        }));
+        let (gemini, report) = sanitize_gemini_schema(&schema).unwrap();
+        assert!(report.is_empty());
+        assert_eq!(gemini, schema.to_json());
+    }
+
+    #[test]
+    fn test_gemini_deep_nesting_is_refused() {
+        use crate::schema::Schema;
+
+        let nested = |levels: usize| {
+            let mut schema = serde_json::json!({ "type": "string", "description": "Leaf" });
+            for level in 0..levels {
+                schema = if level % 2 == 0 {
+                    serde_json::json!({
+                        "type": "object",
+                        "description": "Level",
+                        "properties": { "child": schema },
+                        "required": ["child"]
+                    })
+                } else {
+                    serde_json::json!({ "type": "array", "items": schema })
+                };
+            }
+            Schema::new(schema)
+        };
+
+        // At the limit the schema goes through whole: every object keeps its
+        // properties, every array its items, and descriptions are untouched
+        let (gemini, _) = sanitize_gemini_schema(&nested(GEMINI_MAX_SCHEMA_DEPTH)).unwrap();
+        fn check(schema: &Value) {
+            match schema["type"].as_str() {
+                Some("object") => {
+                    assert_eq!(schema["description"], "Level");
+                    let props = schema["properties"].as_object().unwrap();
+                    assert!(!props.is_empty(), "property-less object: {schema}");
+                    props.values().for_each(check);
+                }
+                Some("array") => check(schema.get("items").expect("item-less array")),
+                _ => assert_eq!(schema["description"], "Leaf"),
+            }
+        }
+        check(&gemini);
+
+        // One level deeper is an error rather than a truncated schema
+        let err = sanitize_gemini_schema(&nested(GEMINI_MAX_SCHEMA_DEPTH + 5)).unwrap_err();
+        assert!(matches!(err, RStructorError::Unsupported(ref m) if m.contains("deeper")));
+        assert!(sanitize_gemini_schema(&nested(GEMINI_MAX_SCHEMA_DEPTH + 1)).is_err());
+    }
+
     #[test]
     fn test_gemini_map_with_x_enum_keys() {
         let mut schema = serde_json::json!({
@@ -2543,7 +3611,11 @@ mod tests {
                 }
             }
         });
-        strip_gemini_unsupported_keywords_recursive(&mut schema);
+        strip_gemini_unsupported_keywords_recursive(
+            &mut schema,
+            "",
+            &mut SchemaSanitizeReport::default(),
+        );
         let props = schema["properties"]["counts"]["properties"]
             .as_object()
             .unwrap();
@@ -2573,7 +3645,11 @@ mod tests {
                 }
             }
         });
-        strip_gemini_unsupported_keywords_recursive(&mut schema);
+        strip_gemini_unsupported_keywords_recursive(
+            &mut schema,
+            "",
+            &mut SchemaSanitizeReport::default(),
+        );
         let props = schema["properties"]["counts"]["properties"]
             .as_object()
             .unwrap();
@@ -2597,7 +3673,11 @@ mod tests {
                 }
             }
         });
-        strip_gemini_unsupported_keywords_recursive(&mut schema);
+        strip_gemini_unsupported_keywords_recursive(
+            &mut schema,
+            "",
+            &mut SchemaSanitizeReport::default(),
+        );
         assert!(
             schema["properties"]["name"].get("x-enum-keys").is_none(),
             "x-enum-keys should be stripped from non-map schemas"
@@ -2762,7 +3842,7 @@ mod tests {
     fn classify_api_error_400_bad_request() {
         let kind = classify_api_error(status(400), "malformed body", None, None);
         match kind {
-            ApiErrorKind::BadRequest { details } => assert_eq!(details, "malformed body"),
+            ApiErrorKind::BadRequest { details, .. } => assert_eq!(details, "malformed body"),
             other => panic!("expected BadRequest, got {:?}", other),
         }
     }
@@ -2822,6 +3902,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn classify_api_error_529_and_overloaded_bodies_are_overloaded() {
+        assert_eq!(
+            classify_api_error(status(529), "", Some(Duration::from_secs(3)), None),
+            ApiErrorKind::Overloaded {
+                retry_after: Some(Duration::from_secs(3))
+            }
+        );
+        let body =
+            r#"{"type": "error", "error": {"type": "overloaded_error", "message": "Overloaded"}}"#;
+        assert_eq!(
+            classify_api_error(status(500), body, None, None),
+            ApiErrorKind::Overloaded { retry_after: None }
+        );
+    }
+
+    #[test]
+    fn overload_backoff_doubles_up_to_a_cap() {
+        let delays: Vec<u64> = (0..7).map(|a| overload_backoff(a).as_secs()).collect();
+        assert_eq!(delays, [2, 4, 8, 16, 30, 30, 30]);
+    }
+
     #[test]
     fn classify_api_error_520_to_524_gateway_error_with_code() {
         for code in [520u16, 521, 522, 523, 524] {
@@ -2834,12 +3936,125 @@ mod tests {
         }
     }
 
+    fn bad_request_details(kind: ApiErrorKind) -> String {
+        match kind {
+            ApiErrorKind::BadRequest { details, .. } => details,
+            other => panic!("expected BadRequest, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn classify_api_error_408_and_409_are_retryable() {
+        let timeout = classify_api_error(status(408), "", None, None);
+        assert_eq!(timeout, ApiErrorKind::RequestTimeout);
+        assert!(timeout.is_retryable());
+
+        let body = r#"{"error": {"message": "Another request with this idempotency key is in progress", "type": "conflict"}}"#;
+        let conflict = classify_api_error(status(409), body, None, None);
+        match &conflict {
+            ApiErrorKind::Conflict { details, .. } => {
+                assert_eq!(
+                    details,
+                    "Another request with this idempotency key is in progress"
+                )
+            }
+            other => panic!("expected Conflict, got {:?}", other),
+        }
+        assert_eq!(
+            conflict.provider_error().unwrap().error_type.as_deref(),
+            Some("conflict")
+        );
+        assert!(conflict.is_retryable());
+    }
+
+    #[test]
+    fn classify_api_error_422_lists_offending_fields() {
+        let pydantic = r#"{"detail": [
+            {"loc": ["body", "temperature"], "msg": "Input should be less than or equal to 2", "type": "less_than_equal"},
+            {"loc": ["body", "messages", 0, "content"], "msg": "Field required", "type": "missing"}
+        ]}"#;
+        assert_eq!(
+            bad_request_details(classify_api_error(status(422), pydantic, None, None)),
+            "temperature: Input should be less than or equal to 2; messages.0.content: Field required"
+        );
+
+        // Unstructured 422 bodies are passed through like a 400.
+        assert_eq!(
+            classify_api_error(status(422), "unprocessable", None, None),
+            ApiErrorKind::BadRequest {
+                details: "unprocessable".into(),
+                provider_error: None,
+            }
+        );
+    }
+
+    #[test]
+    fn classify_api_error_400_names_openai_param_and_gemini_field() {
+        let openai = r#"{"error": {"message": "Unsupported value: 'temperature' must be 1", "type": "invalid_request_error", "param": "temperature", "code": "unsupported_value"}}"#;
+        assert_eq!(
+            bad_request_details(classify_api_error(status(400), openai, None, None)),
+            "temperature: Unsupported value: 'temperature' must be 1"
+        );
+
+        let gemini = r#"{"error": {"code": 400, "message": "Invalid JSON payload", "status": "INVALID_ARGUMENT",
+            "details": [{"@type": "type.googleapis.com/google.rpc.BadRequest",
+                "fieldViolations": [{"field": "generation_config.max_output_tokens", "description": "must be positive"}]}]}}"#;
+        assert_eq!(
+            bad_request_details(classify_api_error(status(400), gemini, None, None)),
+            "generation_config.max_output_tokens: must be positive"
+        );
+    }
+
+    #[test]
+    fn classify_api_error_attaches_provider_error_and_uses_its_message() {
+        let body = r#"{"error": {"message": "Invalid schema for response_format 'output'.",
+            "type": "invalid_request_error", "param": null, "code": "invalid_json_schema"}}"#;
+        let kind = classify_api_error(status(400), body, None, None);
+        assert_eq!(
+            kind.provider_error().unwrap().code.as_deref(),
+            Some("invalid_json_schema")
+        );
+        assert_eq!(
+            bad_request_details(kind),
+            "Invalid schema for response_format 'output'."
+        );
+
+        let anthropic =
+            r#"{"type": "error", "error": {"type": "overloaded_error", "message": "Overloaded"}}"#;
+        assert_eq!(
+            classify_api_error(status(529), anthropic, None, None),
+            ApiErrorKind::Overloaded { retry_after: None }
+        );
+    }
+
+    #[test]
+    fn classify_api_error_context_length_exceeded_for_each_provider() {
+        let bodies = [
+            r#"{"error": {"message": "This model's maximum context length is 128000 tokens.",
+                "type": "invalid_request_error", "param": "messages", "code": "context_length_exceeded"}}"#,
+            r#"{"type": "error", "error": {"type": "invalid_request_error",
+                "message": "prompt is too long: 210000 tokens > 200000 maximum"}}"#,
+            r#"{"error": {"code": 400, "status": "INVALID_ARGUMENT",
+                "message": "The input token count (1200000) exceeds the maximum number of tokens allowed (1048576)."}}"#,
+        ];
+        for body in bodies {
+            let kind = classify_api_error(status(400), body, None, None);
+            assert!(
+                matches!(kind, ApiErrorKind::ContextLengthExceeded { .. }),
+                "{body} classified as {kind:?}"
+            );
+            assert!(!kind.is_retryable());
+        }
+    }
+
     #[test]
     fn classify_api_error_out_of_range_codes_fall_into_other() {
         // 519 and 525 are just outside the 520..=524 gateway band; 418 is a teapot.
         for code in [418u16, 519, 525] {
             match classify_api_error(status(code), "weird", None, None) {
-                ApiErrorKind::Other { code: c, message } => {
+                ApiErrorKind::Other {
+                    code: c, message, ..
+                } => {
                     assert_eq!(c, code);
                     assert_eq!(message, "weird");
                 }
@@ -2851,7 +4066,7 @@ mod tests {
     #[test]
     fn classify_api_error_404_without_model_is_other() {
         match classify_api_error(status(404), "endpoint not found", None, None) {
-            ApiErrorKind::Other { code, message } => {
+            ApiErrorKind::Other { code, message, .. } => {
                 assert_eq!(code, 404);
                 assert_eq!(message, "endpoint not found");
             }
@@ -2864,7 +4079,7 @@ mod tests {
         // truncate_message keeps `max_len` bytes (200) then appends "..." -> 203 chars.
         let body = "x".repeat(300);
         match classify_api_error(status(400), &body, None, None) {
-            ApiErrorKind::BadRequest { details } => {
+            ApiErrorKind::BadRequest { details, .. } => {
                 assert_eq!(details.len(), 203);
                 assert!(details.ends_with("..."));
             }
@@ -3034,7 +4249,8 @@ mod tests {
         assert!(!ApiErrorKind::RequestTooLarge.is_retryable());
         assert!(
             !ApiErrorKind::BadRequest {
-                details: "x".into()
+                details: "x".into(),
+                provider_error: None,
             }
             .is_retryable()
         );
@@ -3048,7 +4264,8 @@ mod tests {
         assert!(
             !ApiErrorKind::Other {
                 code: 418,
-                message: "teapot".into()
+                message: "teapot".into(),
+                provider_error: None,
             }
             .is_retryable()
         );
@@ -3093,7 +4310,8 @@ mod tests {
         assert_eq!(
             ApiErrorKind::Other {
                 code: 418,
-                message: "teapot".into()
+                message: "teapot".into(),
+                provider_error: None,
             }
             .retry_delay(),
             None
@@ -3340,7 +4558,7 @@ mod tests {
                 }
             }
         });
-        strip_gemini_unsupported_keywords(&mut schema);
+        strip_gemini_unsupported_keywords(&mut schema, &mut SchemaSanitizeReport::default());
 
         assert_eq!(schema["type"], "object");
         assert_eq!(schema["properties"]["name"]["type"], "string");
@@ -3361,7 +4579,7 @@ mod tests {
                 }
             }
         });
-        strip_gemini_unsupported_keywords(&mut schema);
+        strip_gemini_unsupported_keywords(&mut schema, &mut SchemaSanitizeReport::default());
 
         assert_eq!(schema["type"], "object");
         assert_eq!(schema["properties"]["id"]["type"], "integer");
@@ -3392,7 +4610,7 @@ mod tests {
                 }
             }
         });
-        strip_gemini_unsupported_keywords(&mut schema);
+        strip_gemini_unsupported_keywords(&mut schema, &mut SchemaSanitizeReport::default());
 
         // No $ref or $defs should survive anywhere in the serialized schema.
         let serialized = serde_json::to_string(&schema).unwrap();
@@ -3459,7 +4677,11 @@ mod tests {
             "minItems": 2,
             "maxItems": 2
         });
-        strip_gemini_unsupported_keywords_recursive(&mut schema);
+        strip_gemini_unsupported_keywords_recursive(
+            &mut schema,
+            "",
+            &mut SchemaSanitizeReport::default(),
+        );
 
         assert_eq!(schema["items"]["type"], "integer");
         assert!(schema.get("prefixItems").is_none());
@@ -3486,7 +4708,11 @@ mod tests {
             "minItems": 3,
             "maxItems": 3
         });
-        strip_gemini_unsupported_keywords_recursive(&mut schema);
+        strip_gemini_unsupported_keywords_recursive(
+            &mut schema,
+            "",
+            &mut SchemaSanitizeReport::default(),
+        );
 
         let any_of = schema["items"]["anyOf"]
             .as_array()
@@ -3510,7 +4736,11 @@ mod tests {
                 { "type": "integer" }
             ]
         });
-        strip_gemini_unsupported_keywords_recursive(&mut schema);
+        strip_gemini_unsupported_keywords_recursive(
+            &mut schema,
+            "",
+            &mut SchemaSanitizeReport::default(),
+        );
 
         let any_of = schema["items"]["anyOf"]
             .as_array()
@@ -3544,7 +4774,7 @@ mod tests {
             serde_json::json!(false)
         );
 
-        strip_gemini_unsupported_keywords(&mut schema);
+        strip_gemini_unsupported_keywords(&mut schema, &mut SchemaSanitizeReport::default());
 
         // Boolean additionalProperties stripped at both levels.
         assert!(schema.get("additionalProperties").is_none());
@@ -3581,7 +4811,11 @@ mod tests {
             },
             "x-enum-keys": ["alpha"]
         });
-        strip_gemini_unsupported_keywords_recursive(&mut schema);
+        strip_gemini_unsupported_keywords_recursive(
+            &mut schema,
+            "",
+            &mut SchemaSanitizeReport::default(),
+        );
 
         let placeholder = &schema["properties"]["alpha"];
         assert_eq!(placeholder["type"], "object");
@@ -3626,7 +4860,11 @@ mod tests {
                 }
             ]
         });
-        strip_gemini_unsupported_keywords_recursive(&mut schema);
+        strip_gemini_unsupported_keywords_recursive(
+            &mut schema,
+            "",
+            &mut SchemaSanitizeReport::default(),
+        );
         let member = &schema["allOf"][0];
         assert!(member.get("title").is_none());
         assert!(member.get("examples").is_none());
@@ -3643,7 +4881,7 @@ mod tests {
                 "x": { "$ref": "#/$defs/Missing" }
             }
         });
-        strip_gemini_unsupported_keywords(&mut schema);
+        strip_gemini_unsupported_keywords(&mut schema, &mut SchemaSanitizeReport::default());
         assert!(
             schema["properties"]["x"].get("$ref").is_none(),
             "orphan $ref should be stripped"
@@ -3660,7 +4898,7 @@ mod tests {
                 "name": { "type": "string", "default": "anon" }
             }
         });
-        strip_gemini_unsupported_keywords(&mut schema);
+        strip_gemini_unsupported_keywords(&mut schema, &mut SchemaSanitizeReport::default());
         assert!(schema.get("$id").is_none(), "$id should be stripped");
         assert!(
             schema.get("default").is_none(),
@@ -3680,7 +4918,11 @@ mod tests {
             "type": "object",
             "additionalProperties": true
         });
-        strip_gemini_unsupported_keywords_recursive(&mut schema);
+        strip_gemini_unsupported_keywords_recursive(
+            &mut schema,
+            "",
+            &mut SchemaSanitizeReport::default(),
+        );
         assert!(schema.get("additionalProperties").is_none());
         assert!(
             schema.get("properties").is_none(),
@@ -3955,4 +5197,66 @@ mod tests {
         assert_eq!(value["json_schema"]["description"], "A movie");
         assert_eq!(value["json_schema"]["strict"], serde_json::json!(true));
     }
+
+    #[derive(Debug, serde::Deserialize, serde::Serialize)]
+    struct Title {
+        title: String,
+    }
+
+    impl crate::schema::SchemaType for Title {
+        fn schema() -> crate::schema::Schema {
+            crate::schema::Schema::new(serde_json::json!({"type": "object"}))
+        }
+    }
+
+    impl Instructor for Title {
+        fn validate(&self) -> Result<()> {
+            if self.title.is_empty() {
+                return Err(RStructorError::ValidationError("empty title".to_string()));
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn truncated_output_is_unexpected_response_with_reask_context() {
+        let (err, ctx) = parse_validate_and_create_output::<Title>(
+            r#"{"title": "Inc"#.to_string(),
+            None,
+            "OpenAI",
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.api_error_kind(),
+            Some(&ApiErrorKind::UnexpectedResponse {
+                details: "truncated output".to_string()
+            })
+        );
+        let ctx = ctx.expect("truncation must carry re-ask context");
+        assert!(ctx.error_message.contains("truncated"));
+    }
+
+    #[test]
+    fn prose_wrapped_json_is_extracted() {
+        let title: Title =
+            parse_and_validate_response(r#"Sure! Here it is: {"title": "Inception"} Enjoy."#)
+                .unwrap();
+        assert_eq!(title.title, "Inception");
+    }
+
+    #[test]
+    fn first_validating_candidate_is_selected() {
+        let raw = r#"Draft: {"title": ""} Final: {"title": "Inception"}"#;
+        let title: Title = parse_and_validate_response(raw).unwrap();
+        assert_eq!(title.title, "Inception");
+    }
+
+    #[test]
+    fn invalid_candidates_surface_the_validation_error() {
+        let (err, ctx) =
+            parse_and_validate_response::<Title>(r#"A: {"title": ""} B: {"other": 1}"#)
+                .unwrap_err();
+        assert!(err.to_string().contains("empty title"));
+        assert!(ctx.is_some());
+    }
 }
@@ -0,0 +1,713 @@
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, info, instrument, trace};
+
+use crate::backend::gemini::{
+    GenerateContentRequest, GenerateContentResponse, GenerationConfig, Model,
+    conversation_contents, structured_reply, text_reply, thinking_config,
+};
+use crate::backend::google_auth::TokenSource;
+use crate::backend::{
+    ChatMessage, ContextOverflow, DEFAULT_REQUEST_TIMEOUT, GenerateResult, HttpClientCell,
+    LLMClient, MaterializeInternalOutput, MaterializeResult, MediaFile, ModelInfo, RateLimiter,
+    ThinkingLevel, UsageTracker, ValidationFailureContext, check_response_status,
+    generate_with_retry_with_conversation, generate_with_retry_with_history,
+    materialize_with_media_with_retry, model_override, send_limited,
+};
+use crate::error::{ApiErrorKind, RStructorError, Result};
+use crate::model::Instructor;
+
+/// Location used by [`VertexAIClient::from_env`] when `GOOGLE_CLOUD_LOCATION`
+/// is unset; the global endpoint serves the newest Gemini models.
+pub const DEFAULT_VERTEX_LOCATION: &str = "global";
+
+/// Configuration for the Vertex AI client
+#[derive(Debug, Clone)]
+pub struct VertexAIConfig {
+    /// OAuth access token sent as a bearer token, e.g. from
+    /// `gcloud auth print-access-token`. Empty (the default) fetches tokens
+    /// from Application Default Credentials.
+    pub api_key: String,
+    /// Google Cloud project id the requests are billed to.
+    pub project: String,
+    /// Region such as "us-central1", or "global".
+    pub location: String,
+    /// The Gemini model to call.
+    pub model: Model,
+    pub temperature: f32,
+    pub max_tokens: Option<u32>,
+    /// Total timeout for each HTTP request.
+    /// Defaults to [`DEFAULT_REQUEST_TIMEOUT`](crate::DEFAULT_REQUEST_TIMEOUT) (5 minutes).
+    pub timeout: Option<Duration>,
+    pub max_retries: Option<usize>,
+    /// Collect fields the model returned but the schema does not declare into
+    /// [`MaterializeResult::extra_fields`](crate::MaterializeResult::extra_fields).
+    pub capture_unknown_fields: bool,
+    /// Ask for a rationale in a generated
+    /// [`EXPLANATION_FIELD`](crate::EXPLANATION_FIELD) property, returned in
+    /// [`MaterializeResult::explanation`](crate::MaterializeResult::explanation).
+    pub explain: bool,
+    /// What `materialize` does when the prompt exceeds the context window.
+    pub context_overflow: ContextOverflow,
+    /// System prompt sent with every request, as the provider's system
+    /// instruction rather than as part of the user prompt.
+    pub system_prompt: Option<String>,
+    /// Locale the schema's descriptions are sent in; see
+    /// [`Schema::localized`](crate::Schema::localized).
+    pub schema_locale: Option<String>,
+    /// Translations consulted before those recorded by the derive.
+    pub description_catalog: Option<Arc<crate::schema::DescriptionCatalog>>,
+    /// Request and token budgets this client's requests wait for, possibly
+    /// shared with other clients.
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    /// Counts the requests and tokens of this client's structured calls,
+    /// possibly shared with other clients.
+    pub usage_tracker: UsageTracker,
+    /// Largest request body this client sends; bigger requests fail with
+    /// [`RStructorError::RequestTooLarge`] before anything is uploaded.
+    /// `None` (the default: Vertex AI publishes no limit) sends any size.
+    pub max_request_bytes: Option<usize>,
+    /// Replaces the location's endpoint, e.g. a Private Service Connect
+    /// endpoint. The `/v1/projects/...` path is appended to it.
+    pub base_url: Option<String>,
+    /// Thinking level for Gemini 3.x models
+    pub thinking_level: Option<ThinkingLevel>,
+}
+
+/// Google Vertex AI client for Gemini models.
+///
+/// Calls the same `generateContent` API as [`GeminiClient`](crate::GeminiClient),
+/// but on a Google Cloud project's Vertex AI endpoint and with OAuth access
+/// tokens instead of an AI Studio API key, for organizations whose accounts
+/// can't issue one. Models are configured with [`GeminiModel`](crate::GeminiModel)
+/// values.
+///
+/// Tokens come from Application Default Credentials, looked up in order:
+/// the key file named by `GOOGLE_APPLICATION_CREDENTIALS` (a service account
+/// key or user credentials), the credentials saved by
+/// `gcloud auth application-default login`, and the metadata server of the
+/// Google Cloud VM, Cloud Run service or GKE pod the program runs on. Tokens
+/// are cached and refreshed shortly before they expire. Workload identity
+/// federation (`external_account`) credentials are not supported; pass a
+/// token from another source with [`with_access_token`](Self::with_access_token).
+///
+/// Cheap to clone and `Send + Sync`; see [sharing clients](crate::LLMClient#sharing-clients).
+#[derive(Clone)]
+pub struct VertexAIClient {
+    config: Arc<VertexAIConfig>,
+    client: HttpClientCell,
+    /// Application Default Credentials; `None` when a static access token is
+    /// configured.
+    credentials: Option<Arc<TokenSource>>,
+}
+
+impl VertexAIClient {
+    /// Create a client for `project` in `location` ("us-central1", "global",
+    /// ...) that authenticates with Application Default Credentials.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RStructorError::ConfigError`] if `project` is empty, or if
+    /// the credentials file can't be read or is of an unsupported type.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use rstructor::{GeminiModel, VertexAIClient};
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = VertexAIClient::new("my-project", "us-central1")?
+    ///     .model(GeminiModel::Gemini25Pro);
+    /// # let _ = client;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(name = "vertex_ai_client_new", skip(project, location), fields(model = ?Model::Gemini35Flash))]
+    pub fn new(project: impl Into<String>, location: impl Into<String>) -> Result<Self> {
+        let credentials = TokenSource::application_default()?;
+        info!("Creating new Vertex AI client with Application Default Credentials");
+        Self::with_auth(
+            project.into(),
+            location.into(),
+            String::new(),
+            Some(credentials),
+        )
+    }
+
+    /// Create a client for `project` in `location` that sends `access_token`
+    /// with every request, e.g. one from `gcloud auth print-access-token` or
+    /// your own token provider. Access tokens expire, typically after an
+    /// hour; replace the client's with [`access_token`](Self::access_token),
+    /// or pass one per call with [`scoped_api_key`](crate::scoped_api_key).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rstructor::VertexAIClient;
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = VertexAIClient::with_access_token("my-project", "global", "ya29.a0...")?;
+    /// # let _ = client;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(
+        name = "vertex_ai_client_with_access_token",
+        skip(project, location, access_token)
+    )]
+    pub fn with_access_token(
+        project: impl Into<String>,
+        location: impl Into<String>,
+        access_token: impl Into<String>,
+    ) -> Result<Self> {
+        let access_token = access_token.into();
+        if access_token.trim().is_empty() {
+            return Err(RStructorError::api_error(
+                "Vertex AI",
+                ApiErrorKind::AuthenticationFailed,
+            ));
+        }
+        info!("Creating new Vertex AI client with an access token");
+        trace!("Access token length: {}", access_token.len());
+        Self::with_auth(project.into(), location.into(), access_token, None)
+    }
+
+    fn with_auth(
+        project: String,
+        location: String,
+        api_key: String,
+        credentials: Option<TokenSource>,
+    ) -> Result<Self> {
+        if project.trim().is_empty() {
+            return Err(RStructorError::ConfigError(
+                "Vertex AI: the Google Cloud project is empty".to_string(),
+            ));
+        }
+        let config = VertexAIConfig {
+            api_key,
+            project,
+            location,
+            model: Model::Gemini35Flash, // Default to Gemini 3.5 Flash, as GeminiClient does
+            temperature: 0.0,
+            max_tokens: None,
+            timeout: Some(DEFAULT_REQUEST_TIMEOUT), // Default: 5-minute request timeout
+            max_retries: Some(3),                   // Default: 3 retries with error feedback
+            capture_unknown_fields: false,
+            explain: false,
+            context_overflow: ContextOverflow::default(),
+            system_prompt: None,
+            schema_locale: None,
+            description_catalog: None,
+            rate_limiter: None,
+            usage_tracker: UsageTracker::new(),
+            max_request_bytes: None,
+            base_url: None,
+            thinking_level: Some(ThinkingLevel::Low), // Default to Low thinking for Gemini 3.x
+        };
+
+        debug!(
+            project = %config.project,
+            location = %config.location,
+            "Vertex AI client created with default configuration"
+        );
+        Ok(Self {
+            config: Arc::new(config),
+            client: HttpClientCell::default(),
+            credentials: credentials.map(Arc::new),
+        })
+    }
+
+    /// Create a client from the standard Google Cloud environment variables.
+    ///
+    /// The project comes from `GOOGLE_CLOUD_PROJECT`, or else from the
+    /// credentials file, and the location from `GOOGLE_CLOUD_LOCATION`
+    /// (default [`DEFAULT_VERTEX_LOCATION`]). Requests authenticate with
+    /// Application Default Credentials, as with [`new`](Self::new).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RStructorError::ConfigError`] if no project is set, or if the
+    /// credentials file can't be read or is of an unsupported type.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use rstructor::VertexAIClient;
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = VertexAIClient::from_env()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(name = "vertex_ai_client_from_env")]
+    pub fn from_env() -> Result<Self> {
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+        let credentials = TokenSource::application_default()?;
+        let project = var("GOOGLE_CLOUD_PROJECT")
+            .or_else(|| credentials.project_id().map(str::to_string))
+            .ok_or_else(|| {
+                RStructorError::ConfigError(
+                    "Vertex AI: set GOOGLE_CLOUD_PROJECT to the project to call".to_string(),
+                )
+            })?;
+        let location =
+            var("GOOGLE_CLOUD_LOCATION").unwrap_or_else(|| DEFAULT_VERTEX_LOCATION.to_string());
+
+        info!("Creating new Vertex AI client from environment variables");
+        Self::with_auth(project, location, String::new(), Some(credentials))
+    }
+
+    // Builder methods are generated by the macro below
+
+    /// The location's endpoint, or the configured `base_url`.
+    fn endpoint(&self) -> String {
+        match &self.config.base_url {
+            Some(base_url) => base_url.trim_end_matches('/').to_string(),
+            None if self.config.location == "global" => {
+                "https://aiplatform.googleapis.com".to_string()
+            }
+            None => format!("https://{}-aiplatform.googleapis.com", self.config.location),
+        }
+    }
+
+    /// The `generateContent` URL for `model`.
+    fn generate_url(&self, model: &str) -> String {
+        format!(
+            "{}/v1/projects/{}/locations/{}/publishers/google/models/{model}:generateContent",
+            self.endpoint(),
+            self.config.project,
+            self.config.location
+        )
+    }
+
+    /// The bearer token for this request: a per-call or configured access
+    /// token, or else one from Application Default Credentials.
+    async fn bearer_token(&self) -> Result<String> {
+        let token = self.api_key_for_call();
+        if !token.is_empty() {
+            return Ok(token);
+        }
+        match &self.credentials {
+            Some(credentials) => credentials.token(self.http()).await,
+            None => Err(RStructorError::api_error(
+                "Vertex AI",
+                ApiErrorKind::AuthenticationFailed,
+            )),
+        }
+    }
+
+    /// POST a `generateContent` request for `model` and decode the reply.
+    async fn generate_content(
+        &self,
+        model: &str,
+        request: &GenerateContentRequest,
+    ) -> Result<GenerateContentResponse> {
+        let url = self.generate_url(model);
+        debug!(url = %url, "Sending request to Vertex AI");
+        let token = self.bearer_token().await?;
+        let response = send_limited(
+            "Vertex AI",
+            self.config.rate_limiter.as_deref(),
+            self.config.max_request_bytes,
+            self.http()
+                .post(&url)
+                .bearer_auth(token)
+                .header("Content-Type", "application/json")
+                .json(request),
+        )
+        .await?;
+
+        let response = check_response_status(response, "Vertex AI").await?;
+
+        debug!("Successfully received response from Vertex AI");
+        response.json().await.map_err(|e| {
+            error!(error = %e, "Failed to parse JSON response from Vertex AI");
+            RStructorError::from(e)
+        })
+    }
+
+    fn generation_config(&self, response_schema: Option<serde_json::Value>) -> GenerationConfig {
+        GenerationConfig {
+            temperature: self.config.temperature,
+            max_output_tokens: self.config.max_tokens,
+            response_mime_type: response_schema
+                .is_some()
+                .then(|| "application/json".to_string()),
+            response_schema,
+            thinking_config: thinking_config(
+                self.capabilities().thinking,
+                self.config.thinking_level,
+            ),
+        }
+    }
+
+    /// Internal implementation of materialize (without retry logic)
+    /// Accepts conversation history for multi-turn interactions.
+    /// Returns the data, raw response, and optional usage info.
+    async fn materialize_internal<T>(
+        &self,
+        messages: &[ChatMessage],
+    ) -> std::result::Result<
+        MaterializeInternalOutput<T>,
+        (RStructorError, Option<ValidationFailureContext>),
+    >
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        info!("Generating structured response with Vertex AI");
+
+        let schema = self.output_schema::<T>();
+        let (system_instruction, contents) =
+            conversation_contents(self.config.system_prompt.as_deref(), messages);
+        // Extract adjacently tagged enum info before transformation (for response conversion)
+        let adjacently_tagged_info =
+            crate::backend::utils::extract_adjacently_tagged_info(&schema.inline().to_json());
+        let gemini_schema =
+            crate::backend::utils::prepare_gemini_schema(&schema).map_err(|e| (e, None))?;
+        let request = GenerateContentRequest {
+            system_instruction,
+            contents,
+            generation_config: self.generation_config(Some(gemini_schema)),
+        };
+
+        let model = model_override().unwrap_or_else(|| self.config.model.as_str().to_string());
+        let completion = self
+            .generate_content(&model, &request)
+            .await
+            .map_err(|e| (e, None))?;
+        structured_reply(
+            completion,
+            &model,
+            adjacently_tagged_info.as_ref(),
+            "Vertex AI",
+        )
+    }
+
+    /// Internal implementation of raw text generation (no structured output).
+    async fn generate_internal(&self, messages: &[ChatMessage]) -> Result<GenerateResult> {
+        info!("Generating raw text response with Vertex AI");
+        let (system_instruction, contents) =
+            conversation_contents(self.config.system_prompt.as_deref(), messages);
+        let request = GenerateContentRequest {
+            system_instruction,
+            contents,
+            generation_config: self.generation_config(None),
+        };
+        let model = self.config.model.as_str();
+        let completion = self.generate_content(model, &request).await?;
+        text_reply(completion, model, "Vertex AI")
+    }
+}
+
+// Generate builder methods using macro
+crate::impl_client_builder_methods! {
+    client_type: VertexAIClient,
+    config_type: VertexAIConfig,
+    model_type: Model,
+    provider_name: "Vertex AI",
+    max_temperature: 2.0,
+    api_key_required: false
+}
+
+impl VertexAIClient {
+    /// Set the Google Cloud project requests are sent to and billed to.
+    #[tracing::instrument(skip(self, project))]
+    pub fn project(mut self, project: impl Into<String>) -> Self {
+        Arc::make_mut(&mut self.config).project = project.into();
+        self
+    }
+
+    /// Set the location, e.g. "europe-west4" or "global". Not every model is
+    /// served in every location.
+    #[tracing::instrument(skip(self, location))]
+    pub fn location(mut self, location: impl Into<String>) -> Self {
+        Arc::make_mut(&mut self.config).location = location.into();
+        self
+    }
+
+    /// Send `access_token` with every request instead of fetching tokens from
+    /// Application Default Credentials.
+    #[tracing::instrument(skip(self, access_token))]
+    pub fn access_token(mut self, access_token: impl Into<String>) -> Self {
+        Arc::make_mut(&mut self.config).api_key = access_token.into();
+        self
+    }
+
+    /// Send requests to `base_url` instead of the location's endpoint, e.g. a
+    /// Private Service Connect endpoint.
+    #[tracing::instrument(skip(self, base_url))]
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        let base_url_str = base_url.into();
+        tracing::debug!(
+            previous_base_url = ?self.config.base_url,
+            new_base_url = %base_url_str,
+            "Setting custom base URL"
+        );
+        Arc::make_mut(&mut self.config).base_url = Some(base_url_str);
+        self
+    }
+
+    /// Set the thinking level for Gemini 3.x models; see
+    /// [`GeminiClient::thinking_level`](crate::GeminiClient::thinking_level).
+    #[tracing::instrument(skip(self))]
+    pub fn thinking_level(mut self, level: ThinkingLevel) -> Self {
+        Arc::make_mut(&mut self.config).thinking_level = Some(level);
+        self
+    }
+}
+
+#[async_trait]
+impl LLMClient for VertexAIClient {
+    fn from_env() -> Result<Self> {
+        Self::from_env()
+    }
+
+    #[instrument(
+        name = "vertex_ai_materialize",
+        skip(self, prompt),
+        fields(
+            type_name = std::any::type_name::<T>(),
+            model = %self.config.model.as_str(),
+            prompt_len = prompt.len()
+        )
+    )]
+    async fn materialize<T>(&self, prompt: &str) -> Result<T>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        let output = self
+            .config
+            .usage_tracker
+            .scope(generate_with_retry_with_history(
+                |messages: Vec<ChatMessage>| {
+                    let this = self;
+                    async move { this.materialize_internal::<T>(&messages).await }
+                },
+                prompt,
+                self.config.max_retries,
+                &self.config.context_overflow,
+            ))
+            .await?;
+        Ok(output.data)
+    }
+
+    #[instrument(
+        name = "vertex_ai_materialize_with_media",
+        skip(self, prompt, media),
+        fields(
+            type_name = std::any::type_name::<T>(),
+            model = %self.config.model.as_str(),
+            prompt_len = prompt.len(),
+            media_len = media.len()
+        )
+    )]
+    async fn materialize_with_media<T>(&self, prompt: &str, media: &[MediaFile]) -> Result<T>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        self.config
+            .usage_tracker
+            .scope(materialize_with_media_with_retry(
+                |messages: Vec<ChatMessage>| {
+                    let this = self;
+                    async move { this.materialize_internal::<T>(&messages).await }
+                },
+                prompt,
+                media,
+                self.config.max_retries,
+                &self.config.context_overflow,
+            ))
+            .await
+    }
+
+    #[instrument(
+        name = "vertex_ai_materialize_with_metadata",
+        skip(self, prompt),
+        fields(
+            type_name = std::any::type_name::<T>(),
+            model = %self.config.model.as_str(),
+            prompt_len = prompt.len()
+        )
+    )]
+    async fn materialize_with_metadata<T>(&self, prompt: &str) -> Result<MaterializeResult<T>>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        let output = self
+            .config
+            .usage_tracker
+            .scope(generate_with_retry_with_history(
+                |messages: Vec<ChatMessage>| {
+                    let this = self;
+                    async move { this.materialize_internal::<T>(&messages).await }
+                },
+                prompt,
+                self.config.max_retries,
+                &self.config.context_overflow,
+            ))
+            .await?;
+        Ok(output.into_result(self.config.capture_unknown_fields))
+    }
+
+    #[instrument(
+        name = "vertex_ai_generate",
+        skip(self, prompt),
+        fields(
+            model = %self.config.model.as_str(),
+            prompt_len = prompt.len()
+        )
+    )]
+    async fn generate(&self, prompt: &str) -> Result<String> {
+        let result = self.generate_with_metadata(prompt).await?;
+        Ok(result.text)
+    }
+
+    #[instrument(
+        name = "vertex_ai_generate_with_media",
+        skip(self, prompt, media),
+        fields(
+            model = %self.config.model.as_str(),
+            prompt_len = prompt.len(),
+            media_len = media.len()
+        )
+    )]
+    async fn generate_with_media(&self, prompt: &str, media: &[MediaFile]) -> Result<String> {
+        let result = self
+            .generate_internal(&[ChatMessage::user_with_media(prompt, media.to_vec())])
+            .await?;
+        Ok(result.text)
+    }
+
+    #[instrument(
+        name = "vertex_ai_generate_with_metadata",
+        skip(self, prompt),
+        fields(
+            model = %self.config.model.as_str(),
+            prompt_len = prompt.len()
+        )
+    )]
+    async fn generate_with_metadata(&self, prompt: &str) -> Result<GenerateResult> {
+        self.generate_internal(&[ChatMessage::user(prompt)]).await
+    }
+
+    #[instrument(
+        name = "vertex_ai_materialize_conversation",
+        skip(self, messages),
+        fields(
+            type_name = std::any::type_name::<T>(),
+            model = %self.config.model.as_str(),
+            history_len = messages.len()
+        )
+    )]
+    async fn materialize_conversation<T>(
+        &self,
+        messages: &[ChatMessage],
+    ) -> Result<MaterializeResult<T>>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        let output = self
+            .config
+            .usage_tracker
+            .scope(generate_with_retry_with_conversation(
+                |messages: Vec<ChatMessage>| {
+                    let this = self;
+                    async move { this.materialize_internal::<T>(&messages).await }
+                },
+                messages,
+                self.config.max_retries,
+                &self.config.context_overflow,
+            ))
+            .await?;
+        Ok(output.into_result(self.config.capture_unknown_fields))
+    }
+
+    #[instrument(
+        name = "vertex_ai_generate_conversation",
+        skip(self, messages),
+        fields(model = %self.config.model.as_str(), history_len = messages.len())
+    )]
+    async fn generate_conversation(&self, messages: &[ChatMessage]) -> Result<GenerateResult> {
+        self.generate_internal(messages).await
+    }
+
+    /// List the Gemini models Google publishes on Vertex AI.
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        let url = format!("{}/v1beta1/publishers/google/models", self.endpoint());
+        debug!(url = %url, "Fetching available models from Vertex AI");
+
+        let response = send_limited(
+            "Vertex AI",
+            self.config.rate_limiter.as_deref(),
+            self.config.max_request_bytes,
+            self.http()
+                .get(&url)
+                .bearer_auth(self.bearer_token().await?)
+                // Bill the listing to the configured project, as user credentials require
+                .header("x-goog-user-project", &self.config.project),
+        )
+        .await?;
+
+        let response = check_response_status(response, "Vertex AI").await?;
+
+        let json: serde_json::Value = response.json().await.map_err(|e| {
+            error!(error = %e, "Failed to parse models response from Vertex AI");
+            e
+        })?;
+
+        let models = json
+            .get("publisherModels")
+            .and_then(|models| models.as_array())
+            .map(|models_array| {
+                models_array
+                    .iter()
+                    .filter_map(|model| {
+                        let name = model.get("name").and_then(|n| n.as_str())?;
+                        // "publishers/google/models/gemini-2.5-pro"
+                        let id = name.rsplit('/').next().unwrap_or(name);
+                        id.starts_with("gemini").then(|| ModelInfo {
+                            id: id.to_string(),
+                            name: None,
+                            description: None,
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        debug!(count = models.len(), "Fetched Vertex AI models");
+        Ok(models)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn urls_use_the_location_endpoint() {
+        let client = VertexAIClient::with_access_token("proj", "europe-west4", "t").unwrap();
+        assert_eq!(
+            client.generate_url("gemini-2.5-pro"),
+            "https://europe-west4-aiplatform.googleapis.com/v1/projects/proj/locations/europe-west4/publishers/google/models/gemini-2.5-pro:generateContent"
+        );
+        let client = client.location("global");
+        assert_eq!(
+            client.generate_url("gemini-2.5-pro"),
+            "https://aiplatform.googleapis.com/v1/projects/proj/locations/global/publishers/google/models/gemini-2.5-pro:generateContent"
+        );
+        let client = client.base_url("http://localhost:8080/");
+        assert!(
+            client
+                .generate_url("m")
+                .starts_with("http://localhost:8080/v1/projects/proj/")
+        );
+    }
+
+    #[test]
+    fn project_is_required() {
+        assert!(matches!(
+            VertexAIClient::with_access_token(" ", "global", "t"),
+            Err(RStructorError::ConfigError(_))
+        ));
+    }
+}
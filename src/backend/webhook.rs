@@ -0,0 +1,426 @@
+//! Usage-event webhooks for centralized spend tracking.
+//!
+//! [`WebhookClient`] wraps any [`LLMClient`] and reports every call — model,
+//! token usage, optional cost, latency and error — as a [`UsageEvent`] to an HTTP
+//! endpoint. Events are batched in a background task and POSTed as
+//! `{"events": [...]}`, with exponential-backoff retries; delivery never blocks
+//! or fails the LLM call itself.
+//!
+//! Wrapping a client with [`WebhookClient::from_env_url`] (or building it through
+//! [`LLMClient::from_env`]) reads the endpoint from `RSTRUCTOR_USAGE_WEBHOOK_URL`
+//! and the service name from `RSTRUCTOR_SERVICE_NAME`, so platform teams can turn
+//! reporting on per deployment without touching each service's code. When the
+//! URL is unset the wrapper is a pass-through.
+//!
+//! This module is only compiled with the `webhook` feature.
+//!
+//! ```no_run
+//! # async fn ex() -> rstructor::Result<()> {
+//! use rstructor::{LLMClient, OpenAIClient, WebhookClient};
+//!
+//! let client = WebhookClient::new(OpenAIClient::from_env()?, "https://spend.internal/rstructor")
+//!     .service("billing-api")
+//!     .pricing(1.25, 10.0);
+//! let summary = client.generate("Summarize our refund policy").await?;
+//! client.flush().await; // e.g. before shutdown
+//! # let _ = summary;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use tokio::sync::{mpsc, oneshot};
+use tracing::{debug, warn};
+
+use crate::backend::usage::{GenerateResult, MaterializeResult, TokenUsage};
+use crate::backend::{ChatMessage, LLMClient, MediaFile, ModelInfo, build_http_client};
+use crate::error::Result;
+use crate::model::Instructor;
+
+/// Environment variable holding the webhook URL read by [`WebhookClient::from_env_url`].
+pub const USAGE_WEBHOOK_URL_ENV: &str = "RSTRUCTOR_USAGE_WEBHOOK_URL";
+
+/// Environment variable holding the service name read by [`WebhookClient::from_env_url`].
+pub const SERVICE_NAME_ENV: &str = "RSTRUCTOR_SERVICE_NAME";
+
+const DEFAULT_BATCH_SIZE: usize = 50;
+const DEFAULT_FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+const DEFAULT_MAX_RETRIES: u32 = 3;
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+const INITIAL_BACKOFF: Duration = Duration::from_millis(250);
+
+/// Which client method produced a [`UsageEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageOperation {
+    /// `materialize*`: structured output.
+    Materialize,
+    /// `generate*`: free-form text.
+    Generate,
+}
+
+/// One LLM call, as reported to the webhook.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UsageEvent {
+    /// When the call finished, in milliseconds since the Unix epoch.
+    pub timestamp_ms: u64,
+    /// The configured service name, if any.
+    pub service: Option<String>,
+    /// Which client method was called.
+    pub operation: UsageOperation,
+    /// The schema name for structured calls.
+    pub schema: Option<String>,
+    /// The model that answered, when the provider reported usage.
+    pub model: Option<String>,
+    /// Prompt tokens (`0` when usage is unavailable).
+    pub input_tokens: u64,
+    /// Completion tokens (`0` when usage is unavailable).
+    pub output_tokens: u64,
+    /// Estimated cost in USD, when [`WebhookClient::pricing`] is set and usage is known.
+    pub cost_usd: Option<f64>,
+    /// Wall-clock duration of the call, including retries.
+    pub duration_ms: u64,
+    /// The error message if the call failed.
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct WebhookConfig {
+    url: Option<String>,
+    service: Option<String>,
+    batch_size: usize,
+    flush_interval: Duration,
+    max_retries: u32,
+    /// USD per million (input, output) tokens.
+    pricing: Option<(f64, f64)>,
+}
+
+enum Message {
+    Event(UsageEvent),
+    Flush(oneshot::Sender<()>),
+}
+
+#[derive(Default)]
+struct Emitter {
+    sender: OnceLock<mpsc::UnboundedSender<Message>>,
+}
+
+/// An [`LLMClient`] that reports usage events for every call to a webhook.
+///
+/// Results and errors from the wrapped client are returned unchanged. Plain
+/// `materialize`/`generate` calls are forwarded to the `*_with_metadata`
+/// methods so token usage is available; media calls report no usage.
+///
+/// `WebhookClient` is `Clone`; clones share one background sender. Events still
+/// buffered when the Tokio runtime shuts down are lost, so call
+/// [`flush`](Self::flush) before exiting.
+#[derive(Clone)]
+pub struct WebhookClient<C> {
+    inner: C,
+    config: Arc<WebhookConfig>,
+    emitter: Arc<Emitter>,
+}
+
+impl<C> WebhookClient<C> {
+    /// Wrap `inner`, reporting events to `url`.
+    pub fn new(inner: C, url: impl Into<String>) -> Self {
+        Self::with_url(inner, Some(url.into()))
+    }
+
+    /// Wrap `inner`, reading the URL from `RSTRUCTOR_USAGE_WEBHOOK_URL` and the
+    /// service name from `RSTRUCTOR_SERVICE_NAME`. Reporting is disabled when
+    /// the URL is unset.
+    pub fn from_env_url(inner: C) -> Self {
+        let url = std::env::var(USAGE_WEBHOOK_URL_ENV)
+            .ok()
+            .filter(|u| !u.is_empty());
+        let mut client = Self::with_url(inner, url);
+        Arc::make_mut(&mut client.config).service = std::env::var(SERVICE_NAME_ENV).ok();
+        client
+    }
+
+    fn with_url(inner: C, url: Option<String>) -> Self {
+        Self {
+            inner,
+            config: Arc::new(WebhookConfig {
+                url,
+                service: None,
+                batch_size: DEFAULT_BATCH_SIZE,
+                flush_interval: DEFAULT_FLUSH_INTERVAL,
+                max_retries: DEFAULT_MAX_RETRIES,
+                pricing: None,
+            }),
+            emitter: Arc::new(Emitter::default()),
+        }
+    }
+
+    /// Name of the calling service, included in every event.
+    #[must_use]
+    pub fn service(mut self, name: impl Into<String>) -> Self {
+        Arc::make_mut(&mut self.config).service = Some(name.into());
+        self
+    }
+
+    /// Send a batch once this many events are buffered (default 50).
+    #[must_use]
+    pub fn batch_size(mut self, n: usize) -> Self {
+        Arc::make_mut(&mut self.config).batch_size = n.max(1);
+        self
+    }
+
+    /// Send buffered events at least this often (default 5 seconds).
+    #[must_use]
+    pub fn flush_interval(mut self, interval: Duration) -> Self {
+        Arc::make_mut(&mut self.config).flush_interval = interval;
+        self
+    }
+
+    /// Retry a failed delivery this many times with exponential backoff (default 3).
+    #[must_use]
+    pub fn max_retries(mut self, n: u32) -> Self {
+        Arc::make_mut(&mut self.config).max_retries = n;
+        self
+    }
+
+    /// Price in USD per million input and output tokens, used to fill
+    /// [`UsageEvent::cost_usd`].
+    #[must_use]
+    pub fn pricing(mut self, input_per_mtok: f64, output_per_mtok: f64) -> Self {
+        Arc::make_mut(&mut self.config).pricing = Some((input_per_mtok, output_per_mtok));
+        self
+    }
+
+    /// The wrapped client.
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// Deliver every buffered event now and wait for the attempt to finish.
+    pub async fn flush(&self) {
+        if let Some(sender) = self.emitter.sender.get() {
+            let (done, wait) = oneshot::channel();
+            if sender.send(Message::Flush(done)).is_ok() {
+                let _ = wait.await;
+            }
+        }
+    }
+
+    fn report(
+        &self,
+        operation: UsageOperation,
+        schema: Option<String>,
+        usage: Option<&TokenUsage>,
+        started: Instant,
+        error: Option<String>,
+    ) {
+        let Some(url) = &self.config.url else {
+            return;
+        };
+        let event = UsageEvent {
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_millis() as u64),
+            service: self.config.service.clone(),
+            operation,
+            schema,
+            model: usage.map(|u| u.model.clone()),
+            input_tokens: usage.map_or(0, |u| u.input_tokens),
+            output_tokens: usage.map_or(0, |u| u.output_tokens),
+            cost_usd: self.config.pricing.zip(usage).map(|((input, output), u)| {
+                (u.input_tokens as f64 * input + u.output_tokens as f64 * output) / 1_000_000.0
+            }),
+            duration_ms: started.elapsed().as_millis() as u64,
+            error,
+        };
+        let sender = self.emitter.sender.get_or_init(|| {
+            let (sender, receiver) = mpsc::unbounded_channel();
+            tokio::spawn(run_worker(url.clone(), Arc::clone(&self.config), receiver));
+            sender
+        });
+        let _ = sender.send(Message::Event(event));
+    }
+
+    fn report_result<T>(
+        &self,
+        operation: UsageOperation,
+        schema: Option<String>,
+        started: Instant,
+        result: &Result<T>,
+        usage: impl FnOnce(&T) -> Option<&TokenUsage>,
+    ) {
+        match result {
+            Ok(value) => self.report(operation, schema, usage(value), started, None),
+            Err(e) => self.report(operation, schema, None, started, Some(e.to_string())),
+        }
+    }
+}
+
+fn schema_key<T: Instructor>() -> Option<String> {
+    Some(T::schema_name().unwrap_or_else(|| std::any::type_name::<T>().to_string()))
+}
+
+async fn run_worker(
+    url: String,
+    config: Arc<WebhookConfig>,
+    mut receiver: mpsc::UnboundedReceiver<Message>,
+) {
+    let http = build_http_client(DELIVERY_TIMEOUT);
+    let mut buffer = Vec::new();
+    let mut ticker = tokio::time::interval(config.flush_interval);
+    loop {
+        tokio::select! {
+            message = receiver.recv() => match message {
+                Some(Message::Event(event)) => {
+                    buffer.push(event);
+                    if buffer.len() >= config.batch_size {
+                        deliver(&http, &url, &mut buffer, config.max_retries).await;
+                    }
+                }
+                Some(Message::Flush(done)) => {
+                    deliver(&http, &url, &mut buffer, config.max_retries).await;
+                    let _ = done.send(());
+                }
+                None => {
+                    deliver(&http, &url, &mut buffer, config.max_retries).await;
+                    return;
+                }
+            },
+            _ = ticker.tick() => deliver(&http, &url, &mut buffer, config.max_retries).await,
+        }
+    }
+}
+
+/// POST the buffered events, retrying with exponential backoff. The batch is
+/// dropped (with a warning) once retries are exhausted.
+async fn deliver(http: &reqwest::Client, url: &str, buffer: &mut Vec<UsageEvent>, retries: u32) {
+    if buffer.is_empty() {
+        return;
+    }
+    let events = std::mem::take(buffer);
+    let body = serde_json::json!({ "events": events });
+    let mut backoff = INITIAL_BACKOFF;
+    for attempt in 0..=retries {
+        match http.post(url).json(&body).send().await {
+            Ok(response) if response.status().is_success() => {
+                debug!(events = events.len(), "Delivered usage events");
+                return;
+            }
+            Ok(response) => {
+                warn!(status = %response.status(), attempt, "Usage webhook rejected events");
+            }
+            Err(e) => warn!(error = %e, attempt, "Usage webhook delivery failed"),
+        }
+        if attempt < retries {
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+        }
+    }
+    warn!(events = events.len(), "Dropping usage events after retries");
+}
+
+#[async_trait]
+impl<C> LLMClient for WebhookClient<C>
+where
+    C: LLMClient + Send + Sync,
+{
+    async fn materialize<T>(&self, prompt: &str) -> Result<T>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        self.materialize_with_metadata::<T>(prompt)
+            .await
+            .map(|result| result.data)
+    }
+
+    async fn materialize_with_media<T>(&self, prompt: &str, media: &[MediaFile]) -> Result<T>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        let started = Instant::now();
+        let result = self.inner.materialize_with_media::<T>(prompt, media).await;
+        let schema = schema_key::<T>();
+        self.report_result(
+            UsageOperation::Materialize,
+            schema,
+            started,
+            &result,
+            |_| None,
+        );
+        result
+    }
+
+    async fn materialize_with_metadata<T>(&self, prompt: &str) -> Result<MaterializeResult<T>>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        let started = Instant::now();
+        let result = self.inner.materialize_with_metadata::<T>(prompt).await;
+        let schema = schema_key::<T>();
+        self.report_result(UsageOperation::Materialize, schema, started, &result, |r| {
+            r.usage.as_ref()
+        });
+        result
+    }
+
+    async fn materialize_conversation<T>(
+        &self,
+        messages: &[ChatMessage],
+    ) -> Result<MaterializeResult<T>>
+    where
+        T: Instructor + DeserializeOwned + Send + 'static,
+    {
+        let started = Instant::now();
+        let result = self.inner.materialize_conversation::<T>(messages).await;
+        let schema = schema_key::<T>();
+        self.report_result(UsageOperation::Materialize, schema, started, &result, |r| {
+            r.usage.as_ref()
+        });
+        result
+    }
+
+    async fn generate(&self, prompt: &str) -> Result<String> {
+        self.generate_with_metadata(prompt)
+            .await
+            .map(|result| result.text)
+    }
+
+    async fn generate_with_media(&self, prompt: &str, media: &[MediaFile]) -> Result<String> {
+        let started = Instant::now();
+        let result = self.inner.generate_with_media(prompt, media).await;
+        self.report_result(UsageOperation::Generate, None, started, &result, |_| None);
+        result
+    }
+
+    async fn generate_with_metadata(&self, prompt: &str) -> Result<GenerateResult> {
+        let started = Instant::now();
+        let result = self.inner.generate_with_metadata(prompt).await;
+        self.report_result(UsageOperation::Generate, None, started, &result, |r| {
+            r.usage.as_ref()
+        });
+        result
+    }
+
+    async fn generate_conversation(&self, messages: &[ChatMessage]) -> Result<GenerateResult> {
+        let started = Instant::now();
+        let result = self.inner.generate_conversation(messages).await;
+        self.report_result(UsageOperation::Generate, None, started, &result, |r| {
+            r.usage.as_ref()
+        });
+        result
+    }
+
+    /// Build the wrapped client from its environment and configure the webhook
+    /// from `RSTRUCTOR_USAGE_WEBHOOK_URL` / `RSTRUCTOR_SERVICE_NAME`.
+    fn from_env() -> Result<Self> {
+        Ok(Self::from_env_url(C::from_env()?))
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        self.inner.list_models().await
+    }
+}
@@ -0,0 +1,268 @@
+//! Structured-output conformance suite for any [`LLMClient`].
+//!
+//! Before switching to a new model or an OpenAI-compatible endpoint, it helps to
+//! know whether it can reliably fill the schema shapes your application uses.
+//! [`run`] asks the client for one small, unambiguous extraction per [`Shape`]
+//! (nested objects, enums, tagged unions, arrays of objects, optional fields,
+//! maps), checks both that the response materializes and that the extracted
+//! values are right, and returns a [`ConformanceReport`].
+//!
+//! Each case is a real request, so running the suite against a paid API costs
+//! one call per case (plus any re-asks).
+//!
+//! ```no_run
+//! # async fn ex() -> rstructor::Result<()> {
+//! use rstructor::{LLMClient, OpenAIClient};
+//!
+//! let client = OpenAIClient::from_env()?;
+//! let report = rstructor::conformance::run(&client).await;
+//! for case in &report.results {
+//!     println!("{:?}: {}", case.shape, if case.passed() { "ok" } else { "FAIL" });
+//! }
+//! println!("pass rate: {:.0}%", report.pass_rate() * 100.0);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::backend::LLMClient;
+use crate::model::Instructor;
+
+/// A schema shape exercised by the conformance suite.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Shape {
+    /// An object containing another object.
+    Nested,
+    /// A unit-variant enum field.
+    Enum,
+    /// An adjacently tagged enum whose variants carry different fields.
+    Union,
+    /// An array whose items are objects.
+    ArrayOfObjects,
+    /// Optional fields, one present and one absent in the input.
+    Optional,
+    /// A string-keyed map.
+    Map,
+}
+
+impl Shape {
+    /// Every shape, in the order [`run`] executes them.
+    pub const ALL: [Shape; 6] = [
+        Shape::Nested,
+        Shape::Enum,
+        Shape::Union,
+        Shape::ArrayOfObjects,
+        Shape::Optional,
+        Shape::Map,
+    ];
+}
+
+/// Outcome of one conformance case.
+#[derive(Debug, Clone)]
+pub struct CaseResult {
+    /// The shape that was tested.
+    pub shape: Shape,
+    /// Why the case failed (API/validation error or a wrong value); `None` on success.
+    pub error: Option<String>,
+    /// Wall-clock time of the request, including re-asks.
+    pub duration: Duration,
+}
+
+impl CaseResult {
+    /// Whether the case passed.
+    pub fn passed(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Results of a conformance run, one [`CaseResult`] per [`Shape`].
+#[derive(Debug, Clone, Default)]
+pub struct ConformanceReport {
+    /// Per-case results, in execution order.
+    pub results: Vec<CaseResult>,
+}
+
+impl ConformanceReport {
+    /// Number of passing cases.
+    pub fn passed(&self) -> usize {
+        self.results.iter().filter(|r| r.passed()).count()
+    }
+
+    /// The failing cases.
+    pub fn failures(&self) -> impl Iterator<Item = &CaseResult> {
+        self.results.iter().filter(|r| !r.passed())
+    }
+
+    /// Whether every case passed.
+    pub fn all_passed(&self) -> bool {
+        self.passed() == self.results.len()
+    }
+
+    /// Fraction of cases that passed (`0.0` for an empty report).
+    pub fn pass_rate(&self) -> f64 {
+        if self.results.is_empty() {
+            return 0.0;
+        }
+        self.passed() as f64 / self.results.len() as f64
+    }
+}
+
+/// Run every [`Shape`] against `client` and report pass/fail per case.
+pub async fn run<C: LLMClient + Sync>(client: &C) -> ConformanceReport {
+    run_shapes(client, &Shape::ALL).await
+}
+
+/// Run only the given `shapes` against `client`.
+pub async fn run_shapes<C: LLMClient + Sync>(client: &C, shapes: &[Shape]) -> ConformanceReport {
+    let mut results = Vec::with_capacity(shapes.len());
+    for &shape in shapes {
+        let start = Instant::now();
+        let outcome = run_case(client, shape).await;
+        results.push(CaseResult {
+            shape,
+            error: outcome.err(),
+            duration: start.elapsed(),
+        });
+    }
+    ConformanceReport { results }
+}
+
+type CaseOutcome = std::result::Result<(), String>;
+
+async fn run_case<C: LLMClient + Sync>(client: &C, shape: Shape) -> CaseOutcome {
+    match shape {
+        Shape::Nested => {
+            let p: Person =
+                extract(client, "Ada Lovelace lives in London, United Kingdom.").await?;
+            expect(p.name.contains("Ada"), "name", &p.name)?;
+            expect(p.address.city == "London", "address.city", &p.address.city)
+        }
+        Shape::Enum => {
+            let r: Review = extract(
+                client,
+                "Classify the sentiment of this review: \"Absolutely loved it, best purchase I have made all year.\"",
+            )
+            .await?;
+            expect(
+                r.sentiment == Sentiment::Positive,
+                "sentiment",
+                &r.sentiment,
+            )
+        }
+        Shape::Union => {
+            let d: Drawing =
+                extract(client, "Draw a rectangle 3 units wide and 4 units tall.").await?;
+            let ok = matches!(d.shape, Figure::Rectangle { width, height } if width == 3.0 && height == 4.0);
+            expect(ok, "shape", &d.shape)
+        }
+        Shape::ArrayOfObjects => {
+            let o: Order = extract(client, "I'd like 2 apples and 3 pears, please.").await?;
+            let mut quantities: Vec<u32> = o.items.iter().map(|i| i.quantity).collect();
+            quantities.sort_unstable();
+            expect(quantities == [2, 3], "items", &o.items)
+        }
+        Shape::Optional => {
+            let c: Contact = extract(
+                client,
+                "Contact: Bob, email bob@example.com. He did not give a phone number.",
+            )
+            .await?;
+            expect(
+                c.email.as_deref() == Some("bob@example.com"),
+                "email",
+                &c.email,
+            )?;
+            expect(c.phone.is_none(), "phone", &c.phone)
+        }
+        Shape::Map => {
+            let i: Inventory = extract(
+                client,
+                "The warehouse holds 5 chairs and 2 tables. Key the counts by item name.",
+            )
+            .await?;
+            let mut counts: Vec<u32> = i.counts.values().copied().collect();
+            counts.sort_unstable();
+            expect(counts == [2, 5], "counts", &i.counts)
+        }
+    }
+}
+
+async fn extract<C, T>(client: &C, prompt: &str) -> std::result::Result<T, String>
+where
+    C: LLMClient + Sync,
+    T: Instructor + Send + 'static,
+{
+    client.materialize(prompt).await.map_err(|e| e.to_string())
+}
+
+fn expect(ok: bool, field: &str, actual: &impl std::fmt::Debug) -> CaseOutcome {
+    if ok {
+        Ok(())
+    } else {
+        Err(format!("unexpected {field}: {actual:?}"))
+    }
+}
+
+#[derive(crate::Instructor, Serialize, Deserialize, Debug)]
+struct Address {
+    city: String,
+    country: String,
+}
+
+#[derive(crate::Instructor, Serialize, Deserialize, Debug)]
+struct Person {
+    name: String,
+    address: Address,
+}
+
+#[derive(crate::Instructor, Serialize, Deserialize, Debug, PartialEq)]
+enum Sentiment {
+    Positive,
+    Negative,
+    Neutral,
+}
+
+#[derive(crate::Instructor, Serialize, Deserialize, Debug)]
+struct Review {
+    sentiment: Sentiment,
+}
+
+#[derive(crate::Instructor, Serialize, Deserialize, Debug)]
+#[serde(tag = "kind", content = "data")]
+enum Figure {
+    Circle { radius: f64 },
+    Rectangle { width: f64, height: f64 },
+}
+
+#[derive(crate::Instructor, Serialize, Deserialize, Debug)]
+struct Drawing {
+    shape: Figure,
+}
+
+#[derive(crate::Instructor, Serialize, Deserialize, Debug)]
+struct LineItem {
+    product: String,
+    quantity: u32,
+}
+
+#[derive(crate::Instructor, Serialize, Deserialize, Debug)]
+struct Order {
+    items: Vec<LineItem>,
+}
+
+#[derive(crate::Instructor, Serialize, Deserialize, Debug)]
+struct Contact {
+    name: String,
+    email: Option<String>,
+    phone: Option<String>,
+}
+
+#[derive(crate::Instructor, Serialize, Deserialize, Debug)]
+struct Inventory {
+    #[llm(description = "Item name to count")]
+    counts: HashMap<String, u32>,
+}
@@ -0,0 +1,273 @@
+//! Prompt assembly from typed context sources.
+//!
+//! Retrieval-augmented extraction usually means: gather snippets from a few
+//! places (a vector store, a database row, the current document), keep the most
+//! relevant ones that fit the model's context window, and put them ahead of the
+//! question. [`ContextSource`] abstracts "somewhere snippets come from" and
+//! [`PromptAssembler`] does the ranking, packing, and truncation.
+//!
+//! Token counts are estimated with [`estimate_tokens`] (about four characters per
+//! token), which is deliberately conservative rather than tokenizer-exact.
+//!
+//! ```
+//! # #[tokio::main]
+//! # async fn main() -> rstructor::Result<()> {
+//! use rstructor::context::{PromptAssembler, Snippet};
+//!
+//! let notes = vec![
+//!     Snippet::new("release", "Inception was released in 2010.").score(0.9),
+//!     Snippet::new("trivia", "The spinning top is never shown to fall.").score(0.2),
+//! ];
+//! let prompt = PromptAssembler::new(200)
+//!     .source(notes)
+//!     .assemble("When was Inception released?")
+//!     .await?;
+//! assert!(prompt.starts_with(r#"<context label="release">"#));
+//! assert!(prompt.ends_with("When was Inception released?"));
+//! # Ok(())
+//! # }
+//! ```
+
+use std::cmp::Ordering;
+
+use async_trait::async_trait;
+
+use crate::error::Result;
+
+/// Default minimum size, in estimated tokens, of a truncated snippet. Smaller
+/// leftovers are skipped rather than included as a fragment.
+pub const DEFAULT_MIN_TRUNCATED_TOKENS: usize = 32;
+
+/// Marker appended to a snippet that was cut to fit the budget.
+const TRUNCATION_MARKER: &str = "…";
+
+/// Rough token count for `text`: one token per four characters, rounded up.
+///
+/// ```
+/// assert_eq!(rstructor::context::estimate_tokens("abcdefgh"), 2);
+/// assert_eq!(rstructor::context::estimate_tokens("abcde"), 2);
+/// ```
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// A labeled piece of context text with a relevance score.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snippet {
+    /// Where the text came from (a document title, table name, URL, ...).
+    pub label: String,
+    /// The context text itself.
+    pub text: String,
+    /// Relevance; higher scores are packed first. Defaults to `0.0`.
+    pub score: f32,
+}
+
+impl Snippet {
+    /// Create a snippet with a score of `0.0`.
+    pub fn new(label: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            text: text.into(),
+            score: 0.0,
+        }
+    }
+
+    /// Set the relevance score.
+    #[must_use]
+    pub fn score(mut self, score: f32) -> Self {
+        self.score = score;
+        self
+    }
+
+    fn render(&self, text: &str) -> String {
+        format!("<context label=\"{}\">\n{}\n</context>", self.label, text)
+    }
+}
+
+/// Somewhere context snippets come from: a vector store, a database, a file.
+///
+/// Implementations receive the user query so they can retrieve by relevance.
+#[async_trait]
+pub trait ContextSource: Send + Sync {
+    /// Return snippets relevant to `query`.
+    async fn snippets(&self, query: &str) -> Result<Vec<Snippet>>;
+}
+
+/// A fixed list of snippets, returned regardless of the query.
+#[async_trait]
+impl ContextSource for Vec<Snippet> {
+    async fn snippets(&self, _query: &str) -> Result<Vec<Snippet>> {
+        Ok(self.clone())
+    }
+}
+
+/// Packs snippets from several [`ContextSource`]s into a prompt that fits a
+/// token budget, ahead of the user query.
+///
+/// Snippets from all sources are ranked by score (ties keep source order). Each
+/// one that fits is included whole; the first that does not fit is truncated to
+/// the remaining budget if at least [`min_truncated_tokens`](Self::min_truncated_tokens)
+/// remain, which ends packing. The query is always included and counts toward
+/// the budget.
+pub struct PromptAssembler {
+    token_budget: usize,
+    min_truncated_tokens: usize,
+    sources: Vec<Box<dyn ContextSource>>,
+}
+
+impl PromptAssembler {
+    /// Create an assembler for prompts of at most `token_budget` estimated tokens.
+    pub fn new(token_budget: usize) -> Self {
+        Self {
+            token_budget,
+            min_truncated_tokens: DEFAULT_MIN_TRUNCATED_TOKENS,
+            sources: Vec::new(),
+        }
+    }
+
+    /// Add a context source.
+    #[must_use]
+    pub fn source(mut self, source: impl ContextSource + 'static) -> Self {
+        self.sources.push(Box::new(source));
+        self
+    }
+
+    /// Smallest truncated snippet worth including, in estimated tokens
+    /// (default [`DEFAULT_MIN_TRUNCATED_TOKENS`]).
+    #[must_use]
+    pub fn min_truncated_tokens(mut self, tokens: usize) -> Self {
+        self.min_truncated_tokens = tokens;
+        self
+    }
+
+    /// Fetch snippets from every source and build the prompt for `query`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error reported by a source.
+    pub async fn assemble(&self, query: &str) -> Result<String> {
+        let mut snippets = Vec::new();
+        for source in &self.sources {
+            snippets.extend(source.snippets(query).await?);
+        }
+        Ok(self.pack(query, snippets))
+    }
+
+    /// Build the prompt for `query` from already-fetched `snippets`.
+    pub fn pack(&self, query: &str, mut snippets: Vec<Snippet>) -> String {
+        snippets.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal));
+
+        let mut remaining = self.token_budget.saturating_sub(estimate_tokens(query));
+        let mut blocks = Vec::new();
+        for snippet in &snippets {
+            let block = snippet.render(&snippet.text);
+            // Each block is followed by a blank-line separator.
+            let cost = estimate_tokens(&block) + 1;
+            if cost <= remaining {
+                remaining -= cost;
+                blocks.push(block);
+                continue;
+            }
+            if remaining >= self.min_truncated_tokens {
+                let overhead = estimate_tokens(&snippet.render(TRUNCATION_MARKER)) + 1;
+                let keep_chars = remaining.saturating_sub(overhead) * 4;
+                if keep_chars > 0 {
+                    let cut: String = snippet.text.chars().take(keep_chars).collect();
+                    blocks.push(snippet.render(&format!("{cut}{TRUNCATION_MARKER}")));
+                }
+                break;
+            }
+        }
+
+        blocks.push(query.to_string());
+        blocks.join("\n\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(tokens: usize) -> String {
+        "abcd".repeat(tokens)
+    }
+
+    #[test]
+    fn snippets_are_ranked_by_score() {
+        let prompt = PromptAssembler::new(1_000).pack(
+            "q",
+            vec![
+                Snippet::new("low", "second").score(0.1),
+                Snippet::new("high", "first").score(0.9),
+            ],
+        );
+        let high = prompt.find("first").unwrap();
+        let low = prompt.find("second").unwrap();
+        assert!(high < low);
+        assert!(prompt.ends_with("\n\nq"));
+    }
+
+    #[test]
+    fn prompt_stays_within_budget_and_truncates_the_overflowing_snippet() {
+        let assembler = PromptAssembler::new(100);
+        let prompt = assembler.pack(
+            "query",
+            vec![
+                Snippet::new("a", text(40)).score(1.0),
+                Snippet::new("b", text(200)).score(0.5),
+                Snippet::new("c", "never reached").score(0.1),
+            ],
+        );
+        assert!(estimate_tokens(&prompt) <= 100);
+        assert!(prompt.contains(&text(40)));
+        assert!(prompt.contains(TRUNCATION_MARKER));
+        assert!(!prompt.contains("never reached"));
+    }
+
+    #[test]
+    fn small_leftover_skips_to_snippets_that_fit() {
+        let assembler = PromptAssembler::new(40).min_truncated_tokens(50);
+        let prompt = assembler.pack(
+            "q",
+            vec![
+                Snippet::new("big", text(100)).score(1.0),
+                Snippet::new("small", "fits").score(0.5),
+            ],
+        );
+        assert!(!prompt.contains("label=\"big\""));
+        assert!(prompt.contains("fits"));
+    }
+
+    #[test]
+    fn query_is_kept_even_when_over_budget() {
+        let prompt = PromptAssembler::new(1).pack("a long query", vec![Snippet::new("x", "y")]);
+        assert_eq!(prompt, "a long query");
+    }
+
+    struct Failing;
+
+    #[async_trait]
+    impl ContextSource for Failing {
+        async fn snippets(&self, _query: &str) -> Result<Vec<Snippet>> {
+            Err(crate::RStructorError::Unsupported("offline".into()))
+        }
+    }
+
+    #[tokio::test]
+    async fn assemble_merges_sources_and_propagates_errors() {
+        let prompt = PromptAssembler::new(1_000)
+            .source(vec![Snippet::new("one", "alpha")])
+            .source(vec![Snippet::new("two", "beta").score(1.0)])
+            .assemble("q")
+            .await
+            .unwrap();
+        assert!(prompt.find("beta").unwrap() < prompt.find("alpha").unwrap());
+
+        let err = PromptAssembler::new(1_000)
+            .source(Failing)
+            .assemble("q")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, crate::RStructorError::Unsupported(_)));
+    }
+}
@@ -1,6 +1,10 @@
 use std::time::Duration;
 use thiserror::Error;
 
+mod provider;
+
+pub use provider::ProviderError;
+
 /// Classification of API errors for better handling and retry logic.
 ///
 /// This enum categorizes HTTP errors from LLM providers into actionable types,
@@ -61,6 +65,16 @@ pub enum ApiErrorKind {
     /// The API service is temporarily down. This is usually transient.
     ServiceUnavailable,
 
+    /// Provider overloaded (Anthropic HTTP 529 `overloaded_error`)
+    ///
+    /// The provider is shedding load across all customers. This is not a bug on
+    /// either side and clears on its own; retries back off exponentially unless
+    /// the provider says how long to wait.
+    Overloaded {
+        /// How long to wait before retrying (if provided by the API)
+        retry_after: Option<Duration>,
+    },
+
     /// Gateway/proxy error (HTTP 520-524, Cloudflare errors)
     ///
     /// An error occurred at the gateway level. Usually transient.
@@ -84,12 +98,46 @@ pub enum ApiErrorKind {
     /// The request payload (usually the prompt) is too large.
     RequestTooLarge,
 
-    /// Invalid request (HTTP 400)
+    /// Request timeout (HTTP 408)
+    ///
+    /// The server gave up waiting for the request to arrive. Usually transient.
+    RequestTimeout,
+
+    /// Conflict with the current state of a resource (HTTP 409)
+    ///
+    /// Typically a concurrent request holding the same idempotency key, or a
+    /// batch/job that changed state underneath the request. Retrying once the
+    /// other operation settles usually succeeds.
+    Conflict {
+        /// Details from the provider's error body
+        details: String,
+        /// The provider's structured error body, if it sent one
+        provider_error: Option<Box<ProviderError>>,
+    },
+
+    /// The prompt does not fit in the model's context window
+    ///
+    /// Reported as a 400 by most providers; recognised from the provider's
+    /// error code or message (see [`ProviderError::is_context_length_exceeded`]).
+    /// Resending the same prompt cannot succeed, but clients can be configured
+    /// to truncate it or switch models with `on_context_overflow(..)`.
+    ContextLengthExceeded {
+        /// The provider's message (usually including the token counts)
+        details: String,
+        /// The provider's structured error body, if it sent one
+        provider_error: Option<Box<ProviderError>>,
+    },
+
+    /// Invalid request (HTTP 400, 422)
     ///
-    /// The request was malformed or contained invalid parameters.
+    /// The request was malformed or contained invalid parameters. When the
+    /// provider reports which request fields were rejected, `details` lists
+    /// them as `field: message` pairs.
     BadRequest {
         /// Details about what was invalid
         details: String,
+        /// The provider's structured error body, if it sent one
+        provider_error: Option<Box<ProviderError>>,
     },
 
     /// Server error (HTTP 500, 502)
@@ -106,6 +154,8 @@ pub enum ApiErrorKind {
         code: u16,
         /// The error message from the API
         message: String,
+        /// The provider's structured error body, if it sent one
+        provider_error: Option<Box<ProviderError>>,
     },
 
     /// Unexpected response format from API
@@ -140,23 +190,58 @@ impl ApiErrorKind {
             self,
             ApiErrorKind::RateLimited { .. }
                 | ApiErrorKind::ServiceUnavailable
+                | ApiErrorKind::Overloaded { .. }
                 | ApiErrorKind::GatewayError { .. }
                 | ApiErrorKind::ServerError { .. }
+                | ApiErrorKind::RequestTimeout
+                | ApiErrorKind::Conflict { .. }
         )
     }
 
     /// Returns the suggested wait duration for retryable errors.
     ///
-    /// For rate-limited errors, returns the `retry_after` duration if available.
-    /// For other retryable errors, returns a sensible default.
+    /// For rate-limited and overloaded errors, returns the `retry_after`
+    /// duration if available. For other retryable errors, returns a sensible
+    /// default (for overloads, the first step of an exponential backoff).
     pub fn retry_delay(&self) -> Option<Duration> {
         match self {
             ApiErrorKind::RateLimited { retry_after } => {
                 Some(retry_after.unwrap_or(Duration::from_secs(5)))
             }
             ApiErrorKind::ServiceUnavailable => Some(Duration::from_secs(2)),
+            ApiErrorKind::Overloaded { retry_after } => {
+                Some(retry_after.unwrap_or(Duration::from_secs(2)))
+            }
             ApiErrorKind::GatewayError { .. } => Some(Duration::from_secs(1)),
             ApiErrorKind::ServerError { .. } => Some(Duration::from_secs(2)),
+            ApiErrorKind::RequestTimeout => Some(Duration::from_secs(1)),
+            ApiErrorKind::Conflict { .. } => Some(Duration::from_secs(2)),
+            _ => None,
+        }
+    }
+
+    /// The provider's structured error body, for kinds classified from one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rstructor::{ApiErrorKind, ProviderError};
+    ///
+    /// let kind = ApiErrorKind::BadRequest {
+    ///     details: "context too long".into(),
+    ///     provider_error: Some(Box::new(ProviderError {
+    ///         code: Some("context_length_exceeded".into()),
+    ///         ..Default::default()
+    ///     })),
+    /// };
+    /// assert!(kind.provider_error().is_some_and(|e| e.is_context_length_exceeded()));
+    /// ```
+    pub fn provider_error(&self) -> Option<&ProviderError> {
+        match self {
+            ApiErrorKind::BadRequest { provider_error, .. }
+            | ApiErrorKind::Conflict { provider_error, .. }
+            | ApiErrorKind::ContextLengthExceeded { provider_error, .. }
+            | ApiErrorKind::Other { provider_error, .. } => provider_error.as_deref(),
             _ => None,
         }
     }
@@ -187,6 +272,12 @@ impl ApiErrorKind {
                     provider_name
                 )
             }
+            ApiErrorKind::Overloaded { .. } => {
+                format!(
+                    "{} is overloaded. This is transient - please retry with backoff.",
+                    provider_name
+                )
+            }
             ApiErrorKind::GatewayError { code } => {
                 format!(
                     "Gateway error ({}). This is usually transient - please retry.",
@@ -206,7 +297,25 @@ impl ApiErrorKind {
             ApiErrorKind::RequestTooLarge => {
                 "Request too large. Try reducing the prompt length or max_tokens.".to_string()
             }
-            ApiErrorKind::BadRequest { details } => {
+            ApiErrorKind::RequestTimeout => {
+                format!(
+                    "{} timed out waiting for the request. This is usually transient - please retry.",
+                    provider_name
+                )
+            }
+            ApiErrorKind::Conflict { details, .. } => {
+                format!(
+                    "Request conflicts with a concurrent operation: {}. Retry once it has finished.",
+                    details
+                )
+            }
+            ApiErrorKind::ContextLengthExceeded { details, .. } => {
+                format!(
+                    "Prompt exceeds the model's context window: {}. Shorten the input or configure on_context_overflow().",
+                    details
+                )
+            }
+            ApiErrorKind::BadRequest { details, .. } => {
                 format!("Invalid request: {}", details)
             }
             ApiErrorKind::ServerError { code } => {
@@ -215,7 +324,7 @@ impl ApiErrorKind {
                     provider_name, code
                 )
             }
-            ApiErrorKind::Other { code, message } => {
+            ApiErrorKind::Other { code, message, .. } => {
                 format!("{} API error ({}): {}", provider_name, code, message)
             }
             ApiErrorKind::UnexpectedResponse { details } => {
@@ -240,13 +349,27 @@ impl std::fmt::Display for ApiErrorKind {
             }
             ApiErrorKind::InvalidModel { model, .. } => write!(f, "Invalid model: {}", model),
             ApiErrorKind::ServiceUnavailable => write!(f, "Service unavailable"),
+            ApiErrorKind::Overloaded { retry_after } => {
+                write!(f, "Overloaded")?;
+                if let Some(d) = retry_after {
+                    write!(f, " (retry after {}s)", d.as_secs())?;
+                }
+                Ok(())
+            }
             ApiErrorKind::GatewayError { code } => write!(f, "Gateway error ({})", code),
             ApiErrorKind::AuthenticationFailed => write!(f, "Authentication failed"),
             ApiErrorKind::PermissionDenied => write!(f, "Permission denied"),
             ApiErrorKind::RequestTooLarge => write!(f, "Request too large"),
-            ApiErrorKind::BadRequest { details } => write!(f, "Bad request: {}", details),
+            ApiErrorKind::RequestTimeout => write!(f, "Request timeout"),
+            ApiErrorKind::Conflict { details, .. } => write!(f, "Conflict: {}", details),
+            ApiErrorKind::ContextLengthExceeded { details, .. } => {
+                write!(f, "Context length exceeded: {}", details)
+            }
+            ApiErrorKind::BadRequest { details, .. } => write!(f, "Bad request: {}", details),
             ApiErrorKind::ServerError { code } => write!(f, "Server error ({})", code),
-            ApiErrorKind::Other { code, message } => write!(f, "API error ({}): {}", code, message),
+            ApiErrorKind::Other { code, message, .. } => {
+                write!(f, "API error ({}): {}", code, message)
+            }
             ApiErrorKind::UnexpectedResponse { details } => {
                 write!(f, "Unexpected response: {}", details)
             }
@@ -254,6 +377,28 @@ impl std::fmt::Display for ApiErrorKind {
     }
 }
 
+/// The part of a request that takes up most of its size, reported by
+/// [`RStructorError::RequestTooLarge`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PayloadComponent {
+    /// The prompt, system prompt and conversation history
+    Prompt,
+    /// The output schema and tool definitions
+    Schema,
+    /// Attached images, PDFs and other base64-encoded media
+    Media,
+}
+
+impl std::fmt::Display for PayloadComponent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            PayloadComponent::Prompt => "prompt",
+            PayloadComponent::Schema => "schema",
+            PayloadComponent::Media => "media",
+        })
+    }
+}
+
 /// Error types for the rstructor library.
 ///
 /// This enum defines the various error types that can occur within the rstructor library.
@@ -317,6 +462,28 @@ pub enum RStructorError {
     #[error("Unsupported operation: {0}")]
     Unsupported(String),
 
+    /// A client was configured with invalid settings (reported by `build()`)
+    #[error("Invalid configuration: {0}")]
+    ConfigError(String),
+
+    /// A request was over the provider's size limit (see
+    /// `max_request_bytes(..)`), so it was never sent
+    #[error(
+        "Request to {provider} is {bytes} bytes, over the {limit}-byte limit; {component} takes up {component_bytes} bytes of it"
+    )]
+    RequestTooLarge {
+        /// The provider the request was for
+        provider: String,
+        /// Size of the serialized request body
+        bytes: usize,
+        /// The client's `max_request_bytes`
+        limit: usize,
+        /// The largest part of the request
+        component: PayloadComponent,
+        /// How many of `bytes` that part takes up
+        component_bytes: usize,
+    },
+
     /// HTTP client error (from reqwest)
     #[cfg(feature = "_client")]
     #[error("HTTP client error: {0}")]
@@ -358,6 +525,13 @@ impl RStructorError {
         }
     }
 
+    /// Returns the provider's structured error body, if this API error has one.
+    ///
+    /// Shorthand for `api_error_kind().and_then(ApiErrorKind::provider_error)`.
+    pub fn provider_error(&self) -> Option<&ProviderError> {
+        self.api_error_kind().and_then(ApiErrorKind::provider_error)
+    }
+
     /// Returns whether this error is potentially retryable.
     ///
     /// Retryable errors include:
@@ -433,6 +607,22 @@ impl PartialEq for RStructorError {
             (Self::SchemaError(a), Self::SchemaError(b)) => a == b,
             (Self::SerializationError(a), Self::SerializationError(b)) => a == b,
             (Self::Unsupported(a), Self::Unsupported(b)) => a == b,
+            (
+                Self::RequestTooLarge {
+                    provider: p1,
+                    bytes: b1,
+                    limit: l1,
+                    component: c1,
+                    component_bytes: cb1,
+                },
+                Self::RequestTooLarge {
+                    provider: p2,
+                    bytes: b2,
+                    limit: l2,
+                    component: c2,
+                    component_bytes: cb2,
+                },
+            ) => p1 == p2 && b1 == b2 && l1 == l2 && c1 == c2 && cb1 == cb2,
             (Self::Timeout, Self::Timeout) => true,
             // HttpError and JsonError don't implement PartialEq, so we always return false
             #[cfg(feature = "_client")]
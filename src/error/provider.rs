@@ -0,0 +1,210 @@
+use serde_json::Value;
+
+/// The structured error body a provider returned alongside a failing status.
+///
+/// OpenAI, Grok, Anthropic and Gemini all answer errors with a JSON
+/// `{"error": {...}}` envelope; this is that envelope's fields, normalised.
+/// It is attached to [`ApiErrorKind`](crate::ApiErrorKind) variants built from
+/// a response body so callers can branch on the provider's own `type`/`code`
+/// instead of matching on message text.
+///
+/// | Field        | OpenAI / Grok   | Anthropic       | Gemini                  |
+/// |--------------|-----------------|-----------------|-------------------------|
+/// | `error_type` | `error.type`    | `error.type`    | `error.status`          |
+/// | `code`       | `error.code`    | —               | `error.code` (numeric)  |
+/// | `param`      | `error.param`   | —               | first `fieldViolations` |
+/// | `message`    | `error.message` | `error.message` | `error.message`         |
+///
+/// # Example
+///
+/// ```
+/// use rstructor::ProviderError;
+///
+/// let body = r#"{"error": {"message": "This model's maximum context length is 128000 tokens.",
+///     "type": "invalid_request_error", "param": "messages", "code": "context_length_exceeded"}}"#;
+/// let err = ProviderError::parse(body).unwrap();
+/// assert_eq!(err.code.as_deref(), Some("context_length_exceeded"));
+/// assert_eq!(err.param.as_deref(), Some("messages"));
+/// assert!(err.is_context_length_exceeded());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ProviderError {
+    /// The provider's error category (e.g. `invalid_request_error`,
+    /// `overloaded_error`, `INVALID_ARGUMENT`)
+    pub error_type: Option<String>,
+    /// A machine-readable error code (e.g. `context_length_exceeded`)
+    pub code: Option<String>,
+    /// The request field the provider rejected, if it named one
+    pub param: Option<String>,
+    /// The human-readable message
+    pub message: String,
+}
+
+impl ProviderError {
+    /// Parse a provider error body. Returns `None` if `body` is not JSON or
+    /// has neither an `error` object nor a top-level `message`.
+    pub fn parse(body: &str) -> Option<Self> {
+        let body: Value = serde_json::from_str(body).ok()?;
+        let error = match body.get("error") {
+            Some(error @ Value::Object(_)) => error,
+            _ if body.get("message").is_some() => &body,
+            _ => return None,
+        };
+        let text = |key: &str| match error.get(key) {
+            Some(Value::String(s)) => Some(s.clone()),
+            Some(Value::Number(n)) => Some(n.to_string()),
+            _ => None,
+        };
+        let field_violation = error
+            .get("details")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|d| d.get("fieldViolations").and_then(Value::as_array))
+            .flatten()
+            .find_map(|v| v.get("field").and_then(Value::as_str))
+            .map(str::to_string);
+
+        Some(Self {
+            error_type: text("type").or_else(|| text("status")),
+            code: text("code"),
+            param: text("param").or(field_violation),
+            message: text("message").unwrap_or_default(),
+        })
+    }
+
+    /// Whether the prompt exceeded the model's context window.
+    ///
+    /// Matches OpenAI's `context_length_exceeded` code as well as the message
+    /// wording Anthropic and Gemini use for the same condition.
+    pub fn is_context_length_exceeded(&self) -> bool {
+        if self.code.as_deref() == Some("context_length_exceeded") {
+            return true;
+        }
+        let message = self.message.to_lowercase();
+        message.contains("prompt is too long")
+            || message.contains("maximum context length")
+            || message.contains("exceeds the maximum number of tokens")
+    }
+
+    /// Whether the provider is overloaded (Anthropic's `overloaded_error`).
+    pub fn is_overloaded(&self) -> bool {
+        self.error_type.as_deref() == Some("overloaded_error")
+    }
+
+    /// Whether an OpenAI-compatible endpoint rejected the `json_schema`
+    /// response format type itself (many vLLM and Together deployments only
+    /// take `json_object`).
+    ///
+    /// Errors about the schema sent in that format, such as OpenAI's
+    /// `Invalid schema for response_format ..` or an invalid
+    /// `response_format.json_schema.name`, also name the response format but
+    /// are not matched: the endpoint supports `json_schema`, and the schema is
+    /// the caller's to fix.
+    pub fn is_json_schema_unsupported(&self) -> bool {
+        let message = self.message.to_lowercase();
+        let param = self.param.as_deref().unwrap_or_default();
+        if param.starts_with("response_format.json_schema")
+            || message.contains("invalid schema")
+            || message.contains("response_format.json_schema")
+        {
+            return false;
+        }
+        if !message.contains("json_schema") {
+            return false;
+        }
+        param == "response_format.type"
+            || ((param == "response_format"
+                || message.contains("response_format")
+                || message.contains("response format"))
+                && (message.contains("not supported") || message.contains("unsupported")))
+    }
+}
+
+impl std::fmt::Display for ProviderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(code) = self.code.as_ref().or(self.error_type.as_ref()) {
+            write!(f, "[{}] ", code)?;
+        }
+        f.write_str(&self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_anthropic_error_envelope() {
+        let body = r#"{"type": "error", "error": {"type": "invalid_request_error",
+            "message": "prompt is too long: 210000 tokens > 200000 maximum"}}"#;
+        let err = ProviderError::parse(body).unwrap();
+        assert_eq!(err.error_type.as_deref(), Some("invalid_request_error"));
+        assert_eq!(err.code, None);
+        assert!(err.is_context_length_exceeded());
+    }
+
+    #[test]
+    fn parses_gemini_status_code_and_field_violation() {
+        let body = r#"{"error": {"code": 400, "message": "Invalid value", "status": "INVALID_ARGUMENT",
+            "details": [{"fieldViolations": [{"field": "contents[0].parts", "description": "empty"}]}]}}"#;
+        let err = ProviderError::parse(body).unwrap();
+        assert_eq!(err.error_type.as_deref(), Some("INVALID_ARGUMENT"));
+        assert_eq!(err.code.as_deref(), Some("400"));
+        assert_eq!(err.param.as_deref(), Some("contents[0].parts"));
+        assert_eq!(err.to_string(), "[400] Invalid value");
+        assert!(!err.is_context_length_exceeded());
+    }
+
+    #[test]
+    fn recognises_a_rejected_json_schema_response_format() {
+        let body = r#"{"error": {"message": "response_format json_schema is not supported for this model",
+            "type": "invalid_request_error", "param": "response_format"}}"#;
+        assert!(
+            ProviderError::parse(body)
+                .unwrap()
+                .is_json_schema_unsupported()
+        );
+        let body = r#"{"error": {"message": "Invalid value: 'json_schema'. Supported values are: 'text' and 'json_object'.",
+            "type": "invalid_request_error", "param": "response_format.type", "code": "invalid_value"}}"#;
+        assert!(
+            ProviderError::parse(body)
+                .unwrap()
+                .is_json_schema_unsupported()
+        );
+        let body =
+            r#"{"error": {"message": "Invalid value for 'temperature'", "param": "temperature"}}"#;
+        assert!(
+            !ProviderError::parse(body)
+                .unwrap()
+                .is_json_schema_unsupported()
+        );
+    }
+
+    #[test]
+    fn errors_in_the_callers_schema_are_not_a_rejected_format() {
+        // OpenAI's reply to a `json_schema` request whose schema name has a space.
+        let body = r#"{"error": {"message": "Invalid 'response_format.json_schema.name': string does not match pattern. Expected a string that matches the pattern '^[a-zA-Z0-9_-]+$'.",
+            "type": "invalid_request_error", "param": "response_format.json_schema.name", "code": "invalid_value"}}"#;
+        assert!(
+            !ProviderError::parse(body)
+                .unwrap()
+                .is_json_schema_unsupported()
+        );
+        // OpenAI's reply to a strict schema with an open object.
+        let body = r#"{"error": {"message": "Invalid schema for response_format 'Movie': In context=(), 'additionalProperties' is required to be supplied and to be false.",
+            "type": "invalid_request_error", "param": "response_format", "code": null}}"#;
+        assert!(
+            !ProviderError::parse(body)
+                .unwrap()
+                .is_json_schema_unsupported()
+        );
+    }
+
+    #[test]
+    fn non_json_and_unrelated_json_are_not_provider_errors() {
+        assert_eq!(ProviderError::parse("upstream connect error"), None);
+        assert_eq!(ProviderError::parse(r#"{"detail": "nope"}"#), None);
+        assert_eq!(ProviderError::parse(r#"{"error": "flat string"}"#), None);
+    }
+}
@@ -0,0 +1,253 @@
+//! Fine-tuning dataset export.
+//!
+//! Successful extractions are exactly the data a cheaper model needs to learn a
+//! schema: a prompt, the JSON Schema it was asked to follow, and a final output
+//! that passed validation. This module turns those triples into the JSONL
+//! formats accepted by the OpenAI and Anthropic fine-tuning pipelines.
+//!
+//! ```
+//! use rstructor::{FineTuneExample, FineTuneFormat, Instructor, MaterializeResult};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Instructor, Serialize, Deserialize)]
+//! struct Movie {
+//!     title: String,
+//!     year: u16,
+//! }
+//!
+//! # fn main() -> rstructor::Result<()> {
+//! // Typically the output of `client.materialize_with_metadata::<Movie>(prompt)`.
+//! let result = MaterializeResult::from_data(Movie { title: "Inception".into(), year: 2010 });
+//!
+//! let example = FineTuneExample::from_result("Describe Inception", &result)?;
+//! let jsonl = rstructor::finetune::to_jsonl(&[example], FineTuneFormat::OpenAI)?;
+//! assert!(jsonl.contains(r#""role":"assistant""#));
+//! # Ok(())
+//! # }
+//! ```
+
+use std::io::Write;
+
+use serde_json::{Value, json};
+
+use crate::backend::MaterializeResult;
+use crate::error::{RStructorError, Result};
+use crate::model::Instructor;
+use crate::schema::Schema;
+
+/// Target fine-tuning format for [`to_jsonl`] / [`write_jsonl`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FineTuneFormat {
+    /// OpenAI chat fine-tuning: `{"messages": [system, user, assistant]}`.
+    OpenAI,
+    /// Anthropic (Claude) fine-tuning: `{"system": ..., "messages": [user, assistant]}`.
+    Anthropic,
+}
+
+/// One training example: a prompt, the schema it targeted, and the final valid output.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FineTuneExample {
+    /// Optional system instructions, placed ahead of the schema instructions.
+    pub system: Option<String>,
+    /// The user prompt that produced the output.
+    pub prompt: String,
+    /// The JSON Schema the output conforms to.
+    pub schema: Value,
+    /// The final, validated output.
+    pub output: Value,
+}
+
+impl FineTuneExample {
+    /// Build an example from a prompt and a validated value of type `T`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RStructorError::SerializationError`] if `output` cannot be
+    /// serialized to JSON.
+    pub fn new<T: Instructor>(prompt: impl Into<String>, output: &T) -> Result<Self> {
+        let output = serde_json::to_value(output)
+            .map_err(|e| RStructorError::SerializationError(e.to_string()))?;
+        Ok(Self {
+            system: None,
+            prompt: prompt.into(),
+            schema: T::schema().to_json(),
+            output,
+        })
+    }
+
+    /// Build an example from the result of
+    /// [`materialize_with_metadata`](crate::LLMClient::materialize_with_metadata).
+    ///
+    /// Only the original prompt and the accepted output are kept; failed attempts
+    /// recorded in [`MaterializeResult::conversation`] are deliberately dropped so
+    /// the model is trained on the correct answer alone.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RStructorError::SerializationError`] if the data cannot be
+    /// serialized to JSON.
+    pub fn from_result<T: Instructor>(
+        prompt: impl Into<String>,
+        result: &MaterializeResult<T>,
+    ) -> Result<Self> {
+        Self::new(prompt, &result.data)
+    }
+
+    /// Set system instructions for this example.
+    #[must_use]
+    pub fn system(mut self, system: impl Into<String>) -> Self {
+        self.system = Some(system.into());
+        self
+    }
+
+    /// The system prompt: any custom instructions followed by the same schema
+    /// instructions [`Schema::prompt_instructions`] gives a live model.
+    fn system_prompt(&self) -> String {
+        let schema_instructions = Schema::new(self.schema.clone()).prompt_instructions();
+        match &self.system {
+            Some(system) => format!("{system}\n\n{schema_instructions}"),
+            None => schema_instructions,
+        }
+    }
+
+    /// Render this example as a single fine-tuning record in `format`.
+    ///
+    /// ```
+    /// use rstructor::{FineTuneExample, FineTuneFormat};
+    /// use serde_json::json;
+    ///
+    /// let example = FineTuneExample {
+    ///     system: None,
+    ///     prompt: "Pick a number".into(),
+    ///     schema: json!({"type": "object"}),
+    ///     output: json!({"n": 7}),
+    /// };
+    /// let record = example.to_record(FineTuneFormat::Anthropic);
+    /// assert_eq!(record["messages"][1]["content"], r#"{"n":7}"#);
+    /// ```
+    pub fn to_record(&self, format: FineTuneFormat) -> Value {
+        let system = self.system_prompt();
+        let answer = self.output.to_string();
+        match format {
+            FineTuneFormat::OpenAI => json!({
+                "messages": [
+                    { "role": "system", "content": system },
+                    { "role": "user", "content": self.prompt },
+                    { "role": "assistant", "content": answer },
+                ]
+            }),
+            FineTuneFormat::Anthropic => json!({
+                "system": system,
+                "messages": [
+                    { "role": "user", "content": self.prompt },
+                    { "role": "assistant", "content": answer },
+                ]
+            }),
+        }
+    }
+}
+
+/// Render `examples` as a JSONL string (one record per line) in `format`.
+///
+/// # Errors
+///
+/// Returns an error if a record cannot be serialized.
+pub fn to_jsonl(examples: &[FineTuneExample], format: FineTuneFormat) -> Result<String> {
+    let mut buf = Vec::new();
+    write_jsonl(&mut buf, examples, format)?;
+    String::from_utf8(buf).map_err(|e| RStructorError::SerializationError(e.to_string()))
+}
+
+/// Write `examples` as JSONL (one record per line) in `format` to `writer`.
+///
+/// # Errors
+///
+/// Returns [`RStructorError::SerializationError`] if writing fails.
+pub fn write_jsonl<W: Write>(
+    mut writer: W,
+    examples: &[FineTuneExample],
+    format: FineTuneFormat,
+) -> Result<()> {
+    for example in examples {
+        serde_json::to_writer(&mut writer, &example.to_record(format))?;
+        writer
+            .write_all(b"\n")
+            .map_err(|e| RStructorError::SerializationError(e.to_string()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Instructor;
+    use crate::schema::SchemaType;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Instructor, Serialize, Deserialize)]
+    struct Movie {
+        title: String,
+        year: u16,
+    }
+
+    fn example() -> FineTuneExample {
+        FineTuneExample::new(
+            "Describe Inception",
+            &Movie {
+                title: "Inception".into(),
+                year: 2010,
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn openai_record_has_system_user_assistant() {
+        let record = example().to_record(FineTuneFormat::OpenAI);
+        let messages = record["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[0]["role"], "system");
+        assert!(
+            messages[0]["content"]
+                .as_str()
+                .unwrap()
+                .contains(r#""title""#)
+        );
+        assert_eq!(messages[1]["content"], "Describe Inception");
+        let answer: Value = serde_json::from_str(messages[2]["content"].as_str().unwrap()).unwrap();
+        assert_eq!(answer, json!({"title": "Inception", "year": 2010}));
+    }
+
+    #[test]
+    fn system_prompt_matches_live_schema_instructions() {
+        let record = example()
+            .system("Be terse.")
+            .to_record(FineTuneFormat::OpenAI);
+        assert_eq!(
+            record["messages"][0]["content"],
+            format!("Be terse.\n\n{}", Movie::prompt_instructions())
+        );
+    }
+
+    #[test]
+    fn anthropic_record_uses_top_level_system() {
+        let record = example()
+            .system("Be terse.")
+            .to_record(FineTuneFormat::Anthropic);
+        assert!(record["system"].as_str().unwrap().starts_with("Be terse."));
+        let messages = record["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0]["role"], "user");
+        assert_eq!(messages[1]["role"], "assistant");
+    }
+
+    #[test]
+    fn jsonl_has_one_line_per_example() {
+        let jsonl = to_jsonl(&[example(), example()], FineTuneFormat::OpenAI).unwrap();
+        let lines: Vec<_> = jsonl.lines().collect();
+        assert_eq!(lines.len(), 2);
+        for line in lines {
+            serde_json::from_str::<Value>(line).unwrap();
+        }
+    }
+}
@@ -8,7 +8,7 @@
 //!
 //! Key features:
 //! - Derive macro for automatic JSON Schema generation
-//! - Built-in clients for OpenAI, Anthropic, Google Gemini, and xAI Grok
+//! - Built-in clients for OpenAI, Anthropic, Google Gemini, xAI Grok, Azure OpenAI, AWS Bedrock, Google Vertex AI, and local Ollama models
 //! - Validation of responses against schemas
 //! - Type-safe conversion from LLM outputs to Rust structs and enums
 //! - Customizable client configurations
@@ -41,21 +41,39 @@
 //!     Ok(())
 //! }
 //! ```
+//!
+//! `use rstructor::prelude::*;` brings in the derive, the traits, every enabled
+//! client with its model enum, and the common option and error types.
 // Let the crate refer to itself as `rstructor` so `#[derive(Instructor)]` — which
 // emits absolute `::rstructor::…` paths — works in the crate's own unit tests.
 extern crate self as rstructor;
 
 mod backend;
+#[cfg(feature = "derive")]
+pub mod conformance;
+pub mod context;
 pub mod error;
+pub mod finetune;
 #[cfg(feature = "logging")]
 pub mod logging;
 pub mod model;
+pub mod parsing;
+pub mod prelude;
 pub mod schema;
+#[cfg(feature = "mock")]
+pub mod testing;
 
 // Re-exports for convenience
-pub use error::{ApiErrorKind, RStructorError, Result};
+pub use error::{ApiErrorKind, PayloadComponent, ProviderError, RStructorError, Result};
+pub use finetune::{FineTuneExample, FineTuneFormat};
 pub use model::Instructor;
-pub use schema::{CustomTypeSchema, Schema, SchemaBuilder, SchemaType};
+pub use schema::{
+    CustomTypeSchema, DescriptionCatalog, EXPLANATION_FIELD, PropertyNameIssue, PropertyRenames,
+    SCHEMA_GENERATION_CHANGES, SCHEMA_GENERATION_VERSION, Schema, SchemaBuilder, SchemaDraft,
+    SchemaFingerprint, SchemaType,
+};
+#[cfg(feature = "registry")]
+pub use schema::{RegisteredSchema, SchemaRegistry};
 
 #[cfg(feature = "openai")]
 pub use backend::openai::{Model as OpenAIModel, OpenAIClient};
@@ -63,12 +81,29 @@ pub use backend::openai::{Model as OpenAIModel, OpenAIClient};
 #[cfg(feature = "anthropic")]
 pub use backend::anthropic::{AnthropicClient, AnthropicModel};
 
+#[cfg(feature = "azure")]
+pub use backend::azure::{AzureOpenAIClient, DEFAULT_AZURE_API_VERSION};
+
+#[cfg(feature = "bedrock")]
+pub use backend::bedrock::{AwsCredentials, BedrockClient, Model as BedrockModel};
+#[cfg(feature = "vertex")]
+pub use backend::vertex::{DEFAULT_VERTEX_LOCATION, VertexAIClient};
+
 #[cfg(feature = "gemini")]
 pub use backend::gemini::{GeminiClient, Model as GeminiModel};
 
 #[cfg(feature = "grok")]
 pub use backend::grok::{GrokClient, Model as GrokModel};
 
+#[cfg(feature = "groq")]
+pub use backend::groq::{GroqClient, Model as GroqModel};
+
+#[cfg(feature = "together")]
+pub use backend::together::{Model as TogetherModel, TogetherClient};
+
+#[cfg(feature = "ollama")]
+pub use backend::ollama::{Model as OllamaModel, OllamaClient};
+
 #[cfg(feature = "derive")]
 pub use rstructor_derive::Instructor;
 
@@ -76,15 +111,68 @@ pub use backend::LLMClient;
 pub use backend::ModelInfo;
 pub use backend::ThinkingLevel;
 #[cfg(feature = "_client")]
-pub use backend::{AnyClient, Provider, Request, RequestExt};
 pub use backend::{
-    ChatMessage, ChatRole, GenerateResult, MaterializeResult, MediaFile, TokenUsage,
+    AdaptiveConcurrency, ConcurrencyMetrics, Priority, clear_concurrency_limit, concurrency_limit,
+    concurrency_metrics, scoped_api_key, scoped_priority, set_adaptive_concurrency_limit,
+    set_concurrency_limit,
+};
+#[cfg(feature = "_client")]
+pub use backend::{
+    AnyClient, BestEffort, ContextOverflow, DocId, Document, DynLLMClient, DynMaterializeExt,
+    Provider, RateLimiter, Request, RequestExt, Requests, RetryBudget, Tokens, UsageSnapshot,
+    UsageTracker, ValueValidator, client_from_str,
+};
+pub use backend::{
+    ChatMessage, ChatRole, Conversation, GenerateResult, MaterializeResult, MediaFile, TokenUsage,
+};
+pub use backend::{
+    CostEstimate, LITELLM_PRICING_URL, ModelPricing, load_litellm_pricing, set_model_pricing,
 };
 #[cfg(feature = "_client")]
 pub use backend::{DEFAULT_CONNECT_TIMEOUT, DEFAULT_REQUEST_TIMEOUT};
+pub use backend::{DEFAULT_DISTILL_SAMPLE_EVERY, DistillClient, DistillStats};
+pub use backend::{
+    DEFAULT_MAX_EXAMPLES, DEFAULT_REVIEW_THRESHOLD, LabelOutcome, Labeler, ReviewItem, ReviewQueue,
+    ReviewReason,
+};
+#[cfg(feature = "_client")]
+pub use backend::{
+    DEFAULT_SWEEP_SAMPLES, SweepGrid, SweepReport, SweepResult, SweepSettings, sweep,
+};
+#[cfg(feature = "_client")]
+pub use backend::{
+    DRIFT_THRESHOLD, DRIFT_WINDOW, ExtractionRecord, SchemaDrift, SchemaTelemetry,
+    TELEMETRY_HISTORY, WindowStats, clear_telemetry_hook, drift_report, reset_telemetry,
+    schema_telemetry, set_telemetry_hook,
+};
+#[cfg(feature = "_client")]
+pub use backend::{
+    Deprecation, ModelId, ModelTier, OutputStrategy, ProviderCapabilities, check_model_listed,
+};
 #[cfg(feature = "tools")]
-pub use backend::{DynTool, FnTool, Tool, ToolRunner, Toolbox};
+pub use backend::{DynTool, FnTool, Tool, ToolMode, ToolRunner, Toolbox, TypedFnTool};
+#[cfg(feature = "test-util")]
+pub use backend::{FaultInjectingClient, InjectedFaults};
+#[cfg(feature = "_client")]
+pub use backend::{Fixture, FixtureRequest, FixtureResponse};
+#[cfg(feature = "_client")]
+pub use backend::{
+    GEMINI_MAX_SCHEMA_DEPTH, SchemaChange, SchemaSanitizeReport, sanitize_gemini_schema,
+};
 #[cfg(feature = "streaming")]
-pub use backend::{ItemStream, ObjectStream, StreamedObject, TextStream};
+pub use backend::{ItemStream, ObjectStream, Partial, StreamedObject, TextStream};
+#[cfg(feature = "_client")]
+pub use backend::{
+    JournalEntry, RequestJournal, clear_request_journal, request_journal, set_request_journal,
+};
+pub use backend::{MaterializeExt, Scored};
 #[cfg(feature = "mock")]
 pub use backend::{MockClient, MockRequestView, MockResponse, RecordedRequest, RequestKind};
+#[cfg(all(feature = "_client", feature = "test-util"))]
+pub use backend::{
+    RECORD_FIXTURES_ENV, fixture_recording_dir, record_fixtures, stop_recording_fixtures,
+};
+#[cfg(feature = "webhook")]
+pub use backend::{UsageEvent, UsageOperation, WebhookClient};
+#[cfg(feature = "_client")]
+pub use backend::{refresh_pricing, refresh_pricing_from};
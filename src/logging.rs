@@ -2,9 +2,34 @@
 //!
 //! This module provides utilities for configuring and working with logging
 //! through the `tracing` crate.
+//!
+//! It also keeps an in-process latency histogram of `materialize` calls per
+//! provider and model, for quick performance triage without a metrics
+//! backend. [`init_logging`] and [`init_logging_with_filter`] install it;
+//! add [`latency_layer`] yourself when building your own subscriber. Read it
+//! with [`latency_snapshot`], whose `Display` output is a small table:
+//!
+//! ```no_run
+//! use rstructor::logging::{LogLevel, init_logging, latency_snapshot};
+//!
+//! init_logging(LogLevel::Warn);
+//! // ... run some extractions ...
+//! eprintln!("{}", latency_snapshot());
+//! ```
 
-use tracing::Level;
-use tracing_subscriber::{EnvFilter, fmt, layer::SubscriberExt, util::SubscriberInitExt};
+use std::collections::BTreeMap;
+use std::fmt as std_fmt;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Level, Metadata, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{
+    EnvFilter, filter::filter_fn, fmt, layer::SubscriberExt, util::SubscriberInitExt,
+};
 
 /// Log levels supported by rstructor.
 ///
@@ -70,10 +95,12 @@ pub fn init_logging(level: LogLevel) {
         EnvFilter::new(format!("rstructor={}", level.to_tracing_level()))
     });
 
-    // Create a subscriber with a custom filter and formatter
+    // Create a subscriber with a custom filter and formatter. The filter
+    // applies to the formatter only, so the latency histogram still sees
+    // materialize spans below the chosen level.
     tracing_subscriber::registry()
-        .with(fmt::layer().with_target(true))
-        .with(env_filter)
+        .with(fmt::layer().with_target(true).with_filter(env_filter))
+        .with(latency_layer())
         .init();
 
     // Log initialization
@@ -99,8 +126,8 @@ pub fn init_logging_with_filter(filter: &str) {
     });
 
     tracing_subscriber::registry()
-        .with(fmt::layer().with_target(true))
-        .with(env_filter)
+        .with(fmt::layer().with_target(true).with_filter(env_filter))
+        .with(latency_layer())
         .init();
 
     tracing::info!(
@@ -108,3 +135,344 @@ pub fn init_logging_with_filter(filter: &str) {
         filter
     );
 }
+
+/// Finest resolution of the latency histogram: each power-of-two range of
+/// microseconds is split into this many buckets, so a reported percentile is
+/// within 1/8 (12.5%) of the true value.
+const SUB_BUCKETS: u64 = 8;
+
+/// Latency distribution of the `materialize` calls made with one model.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LatencyStats {
+    /// Provider prefix of the client, e.g. `openai` or `azure_openai`.
+    pub provider: String,
+    /// Model name (for Azure OpenAI, the deployment).
+    pub model: String,
+    /// Calls recorded, successful or not.
+    pub count: u64,
+    /// Median latency.
+    pub p50: Duration,
+    /// 95th percentile latency.
+    pub p95: Duration,
+    /// 99th percentile latency.
+    pub p99: Duration,
+    /// Slowest call.
+    pub max: Duration,
+}
+
+/// Latency of every provider/model recorded so far, from [`latency_snapshot`].
+///
+/// Displays as a table with one row per model, slowest p95 first.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LatencySnapshot {
+    /// One entry per provider/model.
+    pub entries: Vec<LatencyStats>,
+}
+
+impl std_fmt::Display for LatencySnapshot {
+    fn fmt(&self, f: &mut std_fmt::Formatter<'_>) -> std_fmt::Result {
+        if self.entries.is_empty() {
+            return writeln!(f, "no materialize calls recorded");
+        }
+        let model_width = self
+            .entries
+            .iter()
+            .map(|e| e.provider.len() + 1 + e.model.len())
+            .max()
+            .unwrap_or(0)
+            .max("model".len());
+        writeln!(
+            f,
+            "{:<model_width$}  {:>6}  {:>9}  {:>9}  {:>9}  {:>9}",
+            "model", "calls", "p50", "p95", "p99", "max"
+        )?;
+        for e in &self.entries {
+            writeln!(
+                f,
+                "{:<model_width$}  {:>6}  {:>9}  {:>9}  {:>9}  {:>9}",
+                format!("{}/{}", e.provider, e.model),
+                e.count,
+                format_duration(e.p50),
+                format_duration(e.p95),
+                format_duration(e.p99),
+                format_duration(e.max),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn format_duration(d: Duration) -> String {
+    if d >= Duration::from_secs(1) {
+        format!("{:.2}s", d.as_secs_f64())
+    } else {
+        format!("{:.1}ms", d.as_secs_f64() * 1000.0)
+    }
+}
+
+/// Log-linear histogram of durations in microseconds.
+#[derive(Debug, Default)]
+struct Histogram {
+    counts: Vec<u64>,
+    total: u64,
+    max_micros: u64,
+}
+
+impl Histogram {
+    fn bucket(micros: u64) -> usize {
+        if micros < SUB_BUCKETS {
+            return micros as usize;
+        }
+        let exp = u64::from(63 - micros.leading_zeros());
+        let shift = exp - SUB_BUCKETS.trailing_zeros() as u64;
+        let sub = (micros >> shift) & (SUB_BUCKETS - 1);
+        ((shift + 1) * SUB_BUCKETS + sub) as usize
+    }
+
+    /// Largest value that falls in `bucket`.
+    fn upper_bound(bucket: usize) -> u64 {
+        let bucket = bucket as u64;
+        if bucket < SUB_BUCKETS {
+            return bucket;
+        }
+        let shift = bucket / SUB_BUCKETS - 1;
+        let sub = bucket % SUB_BUCKETS;
+        ((SUB_BUCKETS + sub + 1) << shift) - 1
+    }
+
+    fn record(&mut self, latency: Duration) {
+        let micros = u64::try_from(latency.as_micros()).unwrap_or(u64::MAX);
+        let bucket = Self::bucket(micros);
+        if self.counts.len() <= bucket {
+            self.counts.resize(bucket + 1, 0);
+        }
+        self.counts[bucket] += 1;
+        self.total += 1;
+        self.max_micros = self.max_micros.max(micros);
+    }
+
+    fn percentile(&self, q: f64) -> Duration {
+        let rank = ((q * self.total as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (bucket, count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                let micros = Self::upper_bound(bucket).min(self.max_micros);
+                return Duration::from_micros(micros);
+            }
+        }
+        Duration::from_micros(self.max_micros)
+    }
+}
+
+type HistogramKey = (String, String);
+
+fn histograms() -> &'static Mutex<BTreeMap<HistogramKey, Histogram>> {
+    static HISTOGRAMS: OnceLock<Mutex<BTreeMap<HistogramKey, Histogram>>> = OnceLock::new();
+    HISTOGRAMS.get_or_init(Default::default)
+}
+
+fn record_latency(provider: &str, model: &str, latency: Duration) {
+    histograms()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry((provider.to_string(), model.to_string()))
+        .or_default()
+        .record(latency);
+}
+
+/// Latency percentiles of the `materialize` calls recorded so far, per
+/// provider and model.
+///
+/// Only calls made while [`latency_layer`] is installed (it is by
+/// [`init_logging`]) are counted. Each call is timed end to end, including
+/// retries and time spent waiting for a concurrency slot.
+pub fn latency_snapshot() -> LatencySnapshot {
+    let histograms = histograms().lock().unwrap_or_else(|e| e.into_inner());
+    let mut entries: Vec<LatencyStats> = histograms
+        .iter()
+        .map(|((provider, model), h)| LatencyStats {
+            provider: provider.clone(),
+            model: model.clone(),
+            count: h.total,
+            p50: h.percentile(0.50),
+            p95: h.percentile(0.95),
+            p99: h.percentile(0.99),
+            max: Duration::from_micros(h.max_micros),
+        })
+        .collect();
+    entries.sort_by_key(|e| std::cmp::Reverse(e.p95));
+    LatencySnapshot { entries }
+}
+
+/// Forget every latency recorded so far.
+pub fn reset_latency() {
+    histograms()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clear();
+}
+
+/// A [`Layer`] that feeds [`latency_snapshot`] from the spans rstructor
+/// clients open around each `materialize` call.
+///
+/// Only needed when you build your own subscriber; [`init_logging`] adds it.
+/// It carries its own filter, so it keeps recording whatever level the rest
+/// of the subscriber logs at.
+///
+/// ```no_run
+/// use rstructor::logging::latency_layer;
+/// use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt};
+///
+/// tracing_subscriber::registry()
+///     .with(fmt::layer())
+///     .with(latency_layer())
+///     .init();
+/// ```
+pub fn latency_layer<S>() -> impl Layer<S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    LatencyLayer.with_filter(filter_fn(|meta: &Metadata<'_>| {
+        meta.is_span() && materialize_provider(meta.name()).is_some()
+    }))
+}
+
+/// The provider prefix of a client's `materialize` span name, such as
+/// `openai` for `openai_materialize_with_media`.
+fn materialize_provider(span_name: &str) -> Option<&str> {
+    span_name
+        .split_once("_materialize")
+        .map(|(provider, _)| provider)
+        .filter(|provider| !provider.is_empty())
+}
+
+struct LatencyLayer;
+
+/// Stored in a `materialize` span's extensions while it is open.
+struct Timing {
+    provider: &'static str,
+    model: String,
+    started: Instant,
+}
+
+/// Picks the model out of a span's fields; Azure OpenAI spans name the
+/// deployment instead.
+#[derive(Default)]
+struct ModelField(Option<String>);
+
+impl Visit for ModelField {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if matches!(field.name(), "model" | "deployment") {
+            self.0 = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std_fmt::Debug) {
+        if matches!(field.name(), "model" | "deployment") {
+            self.0 = Some(format!("{:?}", value));
+        }
+    }
+}
+
+impl<S> Layer<S> for LatencyLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(provider) = materialize_provider(attrs.metadata().name()) else {
+            return;
+        };
+        let mut model = ModelField::default();
+        attrs.record(&mut model);
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(Timing {
+                provider,
+                model: model.0.unwrap_or_else(|| "unknown".to_string()),
+                started: Instant::now(),
+            });
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(&id)
+            && let Some(timing) = span.extensions().get::<Timing>()
+        {
+            record_latency(timing.provider, &timing.model, timing.started.elapsed());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buckets_are_contiguous_and_within_an_eighth() {
+        let mut previous = 0;
+        for micros in 0..100_000u64 {
+            let bucket = Histogram::bucket(micros);
+            assert!(bucket == previous || bucket == previous + 1, "at {micros}");
+            previous = bucket;
+            let upper = Histogram::upper_bound(bucket);
+            assert!(
+                upper >= micros && upper - micros <= micros / 8,
+                "at {micros}"
+            );
+        }
+    }
+
+    #[test]
+    fn percentiles_follow_the_recorded_distribution() {
+        let mut histogram = Histogram::default();
+        for ms in 1..=100 {
+            histogram.record(Duration::from_millis(ms));
+        }
+        let close = |got: Duration, want_ms: u64| {
+            let got = got.as_secs_f64() * 1000.0;
+            assert!(
+                got >= want_ms as f64 && got <= want_ms as f64 * 1.125,
+                "{got} vs {want_ms}"
+            );
+        };
+        close(histogram.percentile(0.50), 50);
+        close(histogram.percentile(0.95), 95);
+        close(histogram.percentile(0.99), 99);
+        assert_eq!(histogram.percentile(1.0), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn span_names_map_to_providers() {
+        assert_eq!(materialize_provider("openai_materialize"), Some("openai"));
+        assert_eq!(
+            materialize_provider("azure_openai_materialize_with_media"),
+            Some("azure_openai")
+        );
+        assert_eq!(materialize_provider("openai_generate"), None);
+    }
+
+    #[test]
+    fn layer_times_materialize_spans() {
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let subscriber = tracing_subscriber::registry().with(latency_layer());
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("gemini_materialize", model = "layer-test");
+            let entered = span.enter();
+            std::thread::sleep(Duration::from_millis(5));
+            drop(entered);
+            drop(span);
+            tracing::info_span!("gemini_generate", model = "layer-test").in_scope(|| {});
+        });
+
+        let snapshot = latency_snapshot();
+        let stats = snapshot
+            .entries
+            .iter()
+            .find(|e| e.model == "layer-test")
+            .expect("materialize span recorded");
+        assert_eq!((stats.provider.as_str(), stats.count), ("gemini", 1));
+        assert!(stats.max >= Duration::from_millis(5));
+        assert!(snapshot.to_string().contains("gemini/layer-test"));
+    }
+}
@@ -1,7 +1,7 @@
 use serde::Serialize;
 use serde::de::DeserializeOwned;
 
-use crate::error::Result;
+use crate::error::{RStructorError, Result};
 use crate::schema::SchemaType;
 
 /// The `Instructor` trait combines JSON schema generation, serialization, and validation.
@@ -103,9 +103,19 @@ pub trait Instructor: SchemaType + DeserializeOwned + Serialize {
     /// fed back to the model. The default implementation returns `Ok(())`.
     ///
     /// The derive-generated implementation automatically recurses into nested
-    /// `Instructor` fields — directly, and through `Option`, `Vec`, `Box`, and
-    /// string-keyed maps — before running this type's own validator, so validating
-    /// a parent validates its entire tree.
+    /// `Instructor` fields — directly, and through `Option`, `Vec`, `Box`, sets,
+    /// and string-keyed maps — before running this type's own validator, so validating
+    /// a parent validates its entire tree. A nested failure names where it
+    /// happened: a child's `"must be positive"` surfaces from the parent as
+    /// ``"`items[2].price`: must be positive"``, and the field of a bounds
+    /// error is extended the same way (``"`items[2].price` must be at least
+    /// 0, got -1"``). It also enforces the numeric bounds
+    /// of `#[llm(minimum = .., maximum = .., exclusive_minimum = ..,
+    /// exclusive_maximum = .., multiple_of = ..)]` struct fields and the
+    /// `#[llm(min_length = .., max_length = .., pattern = .., format = ..)]`
+    /// string constraints and `#[llm(min_items = .., max_items = ..,
+    /// unique_items)]` array constraints, since strict structured-output modes
+    /// drop those keywords from the schema.
     ///
     /// To add custom validation, use the `#[llm(validate = "path")]` container
     /// attribute — the derive macro wires your function into this trait method:
@@ -149,9 +159,23 @@ pub trait Instructor: SchemaType + DeserializeOwned + Serialize {
 
 // Container implementations so that validation recurses into nested values.
 // `#[derive(Instructor)]` validates each field via these impls, so a `Vec`,
-// `Option`, `Box`, or string-keyed map of validating types is validated
+// `Option`, `Box`, set, or string-keyed map of validating types is validated
 // element-by-element as part of its parent.
 
+// Scalars carry no validation of their own; implementing `Instructor` for
+// them makes `materialize::<bool>(..)` and friends (and `Vec<String>`) valid
+// targets. Their root schema is wrapped in an object for providers that need
+// one (see `Schema::with_object_root`).
+macro_rules! impl_scalar_instructor {
+    ($($ty:ty),+) => {
+        $(impl Instructor for $ty {})+
+    };
+}
+
+impl_scalar_instructor!(
+    String, bool, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64
+);
+
 impl<T: Instructor> Instructor for Option<T> {
     fn validate(&self) -> Result<()> {
         match self {
@@ -163,8 +187,10 @@ impl<T: Instructor> Instructor for Option<T> {
 
 impl<T: Instructor> Instructor for Vec<T> {
     fn validate(&self) -> Result<()> {
-        for value in self {
-            value.validate()?;
+        for (index, value) in self.iter().enumerate() {
+            value
+                .validate()
+                .map_err(|e| at_path(e, &format!("[{index}]")))?;
         }
         Ok(())
     }
@@ -176,15 +202,75 @@ impl<T: Instructor> Instructor for Box<T> {
     }
 }
 
-impl<V: Instructor> Instructor for std::collections::HashMap<String, V> {
+impl<V: Instructor, S> Instructor for std::collections::HashMap<String, V, S>
+where
+    S: std::hash::BuildHasher + Default,
+{
+    fn validate(&self) -> Result<()> {
+        for (key, value) in self {
+            value
+                .validate()
+                .map_err(|e| at_path(e, &format!("[{key:?}]")))?;
+        }
+        Ok(())
+    }
+}
+
+impl<V: Instructor> Instructor for std::collections::BTreeMap<String, V> {
+    fn validate(&self) -> Result<()> {
+        for (key, value) in self {
+            value
+                .validate()
+                .map_err(|e| at_path(e, &format!("[{key:?}]")))?;
+        }
+        Ok(())
+    }
+}
+
+// Set elements have no stable position to report, so their errors pass
+// through unqualified.
+impl<T: Instructor + Eq + std::hash::Hash> Instructor for std::collections::HashSet<T> {
     fn validate(&self) -> Result<()> {
-        for value in self.values() {
+        for value in self {
+            value.validate()?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Instructor + Ord> Instructor for std::collections::BTreeSet<T> {
+    fn validate(&self) -> Result<()> {
+        for value in self {
             value.validate()?;
         }
         Ok(())
     }
 }
 
+/// Qualify a nested validation error with the `segment` (a field name,
+/// `[index]` or `["key"]`) it was found under.
+///
+/// A message that already starts with a backquoted path, as bounds errors and
+/// errors qualified further down do, has the segment prepended to that path;
+/// any other message gets a ``"`segment`: "`` prefix. Errors other than
+/// `ValidationError` are returned unchanged.
+fn at_path(err: RStructorError, segment: &str) -> RStructorError {
+    let RStructorError::ValidationError(message) = err else {
+        return err;
+    };
+    let qualified = match message
+        .strip_prefix('`')
+        .and_then(|rest| rest.split_once('`'))
+    {
+        Some((path, rest)) => {
+            let separator = if path.starts_with('[') { "" } else { "." };
+            format!("`{segment}{separator}{path}`{rest}")
+        }
+        None => format!("`{segment}`: {message}"),
+    };
+    RStructorError::ValidationError(qualified)
+}
+
 /// Internal helpers used by `#[derive(Instructor)]`. Not part of the public API
 /// and exempt from semver guarantees.
 #[doc(hidden)]
@@ -196,8 +282,9 @@ pub mod __private {
     /// **iff** its type implements [`Instructor`], and otherwise do nothing —
     /// without the derive macro having to know which field types are `Instructor`.
     ///
-    /// `#[derive(Instructor)]` emits `Probe(&self.field).rstructor_probe()?` for
-    /// each field. When the field's type implements `Instructor`, the inherent
+    /// `#[derive(Instructor)]` emits `Probe(&self.field).rstructor_probe("field")?`
+    /// for each field, where the name qualifies a nested error's path (an empty
+    /// name, used for newtype fields, leaves it as is). When the field's type implements `Instructor`, the inherent
     /// method below is selected (inherent methods take priority over trait
     /// methods); otherwise method resolution falls back to the [`ProbeFallback`]
     /// trait, which is a no-op.
@@ -205,11 +292,11 @@ pub mod __private {
 
     /// Fallback for non-`Instructor` field types (e.g. `String`, `u32`).
     pub trait ProbeFallback {
-        fn rstructor_probe(&self) -> Result<()>;
+        fn rstructor_probe(&self, field: &str) -> Result<()>;
     }
 
     impl<T> ProbeFallback for Probe<'_, T> {
-        fn rstructor_probe(&self) -> Result<()> {
+        fn rstructor_probe(&self, _field: &str) -> Result<()> {
             Ok(())
         }
     }
@@ -217,10 +304,364 @@ pub mod __private {
     impl<T: Instructor> Probe<'_, T> {
         /// Validate the wrapped value (selected over the trait method when
         /// `T: Instructor`).
-        pub fn rstructor_probe(&self) -> Result<()> {
-            self.0.validate()
+        pub fn rstructor_probe(&self, field: &str) -> Result<()> {
+            match self.0.validate() {
+                Err(e) if !field.is_empty() => Err(super::at_path(e, field)),
+                result => result,
+            }
+        }
+    }
+
+    /// Field types `#[llm(minimum = ..)]`-style bounds can be checked on.
+    pub trait NumericField {
+        /// The value to check, or `None` for an absent optional value.
+        fn rstructor_number(&self) -> Option<f64>;
+    }
+
+    macro_rules! numeric_field {
+        ($($ty:ty),*) => {$(
+            impl NumericField for $ty {
+                fn rstructor_number(&self) -> Option<f64> {
+                    Some(*self as f64)
+                }
+            }
+        )*};
+    }
+
+    numeric_field!(
+        i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64
+    );
+
+    impl<T: NumericField> NumericField for Option<T> {
+        fn rstructor_number(&self) -> Option<f64> {
+            self.as_ref().and_then(NumericField::rstructor_number)
+        }
+    }
+
+    /// Bounds declared on a field, as in JSON Schema.
+    pub struct NumericBounds {
+        pub minimum: Option<f64>,
+        pub maximum: Option<f64>,
+        pub exclusive_minimum: Option<f64>,
+        pub exclusive_maximum: Option<f64>,
+        pub multiple_of: Option<f64>,
+    }
+
+    /// Check `value` of the field named `field` against `bounds`.
+    pub fn check_numeric<T: NumericField>(
+        field: &str,
+        value: &T,
+        bounds: &NumericBounds,
+    ) -> Result<()> {
+        let Some(value) = value.rstructor_number() else {
+            return Ok(());
+        };
+        let violation = match *bounds {
+            NumericBounds {
+                minimum: Some(min), ..
+            } if value < min => Some(format!("at least {min}")),
+            NumericBounds {
+                maximum: Some(max), ..
+            } if value > max => Some(format!("at most {max}")),
+            NumericBounds {
+                exclusive_minimum: Some(min),
+                ..
+            } if value <= min => Some(format!("greater than {min}")),
+            NumericBounds {
+                exclusive_maximum: Some(max),
+                ..
+            } if value >= max => Some(format!("less than {max}")),
+            // Tolerate float rounding, e.g. 0.3 as a multiple of 0.1
+            NumericBounds {
+                multiple_of: Some(step),
+                ..
+            } if ((value / step) - (value / step).round()).abs() > 1e-9 => {
+                Some(format!("a multiple of {step}"))
+            }
+            _ => None,
+        };
+        match violation {
+            Some(expected) => Err(crate::error::RStructorError::ValidationError(format!(
+                "`{field}` must be {expected}, got {value}"
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Field types `#[llm(min_items = ..)]`-style constraints can be checked on.
+    pub trait ArrayField {
+        /// The number of items, or `None` for an absent optional value.
+        fn rstructor_len(&self) -> Option<usize>;
+
+        /// Index of the first item equal to an earlier one (compared by
+        /// serialized value), if any.
+        fn rstructor_duplicate(&self) -> Option<usize>;
+    }
+
+    impl<T: serde::Serialize> ArrayField for Vec<T> {
+        fn rstructor_len(&self) -> Option<usize> {
+            Some(self.len())
+        }
+
+        fn rstructor_duplicate(&self) -> Option<usize> {
+            let mut seen = Vec::with_capacity(self.len());
+            for (index, item) in self.iter().enumerate() {
+                let value = serde_json::to_value(item).ok()?;
+                if seen.contains(&value) {
+                    return Some(index);
+                }
+                seen.push(value);
+            }
+            None
         }
     }
+
+    impl<T> ArrayField for std::collections::HashSet<T> {
+        fn rstructor_len(&self) -> Option<usize> {
+            Some(self.len())
+        }
+
+        fn rstructor_duplicate(&self) -> Option<usize> {
+            None
+        }
+    }
+
+    impl<T> ArrayField for std::collections::BTreeSet<T> {
+        fn rstructor_len(&self) -> Option<usize> {
+            Some(self.len())
+        }
+
+        fn rstructor_duplicate(&self) -> Option<usize> {
+            None
+        }
+    }
+
+    impl<T: ArrayField> ArrayField for Option<T> {
+        fn rstructor_len(&self) -> Option<usize> {
+            self.as_ref().and_then(ArrayField::rstructor_len)
+        }
+
+        fn rstructor_duplicate(&self) -> Option<usize> {
+            self.as_ref().and_then(ArrayField::rstructor_duplicate)
+        }
+    }
+
+    /// Array constraints declared on a field, as in JSON Schema.
+    pub struct ArrayBounds {
+        pub min_items: Option<usize>,
+        pub max_items: Option<usize>,
+        pub unique_items: bool,
+    }
+
+    /// Check `value` of the field named `field` against `bounds`.
+    pub fn check_array<T: ArrayField>(field: &str, value: &T, bounds: &ArrayBounds) -> Result<()> {
+        let Some(len) = value.rstructor_len() else {
+            return Ok(());
+        };
+        let violation = match *bounds {
+            ArrayBounds {
+                min_items: Some(min),
+                ..
+            } if len < min => format!("have at least {min} items, got {len}"),
+            ArrayBounds {
+                max_items: Some(max),
+                ..
+            } if len > max => format!("have at most {max} items, got {len}"),
+            ArrayBounds {
+                unique_items: true, ..
+            } => match value.rstructor_duplicate() {
+                Some(index) => {
+                    format!("have unique items, but item {index} repeats an earlier one")
+                }
+                None => return Ok(()),
+            },
+            _ => return Ok(()),
+        };
+        Err(crate::error::RStructorError::ValidationError(format!(
+            "`{field}` must {violation}"
+        )))
+    }
+
+    /// Field types `#[llm(min_length = ..)]`-style constraints can be checked on.
+    #[cfg(feature = "derive")]
+    pub trait StringField {
+        /// The text to check, or `None` for an absent optional value.
+        fn rstructor_str(&self) -> Option<&str>;
+    }
+
+    #[cfg(feature = "derive")]
+    impl StringField for String {
+        fn rstructor_str(&self) -> Option<&str> {
+            Some(self)
+        }
+    }
+
+    #[cfg(feature = "derive")]
+    impl<T: StringField> StringField for Option<T> {
+        fn rstructor_str(&self) -> Option<&str> {
+            self.as_ref().and_then(StringField::rstructor_str)
+        }
+    }
+
+    #[cfg(feature = "derive")]
+    impl<T: StringField> StringField for Box<T> {
+        fn rstructor_str(&self) -> Option<&str> {
+            (**self).rstructor_str()
+        }
+    }
+
+    /// String constraints declared on a field, as in JSON Schema.
+    #[cfg(feature = "derive")]
+    pub struct StringBounds {
+        pub min_length: Option<usize>,
+        pub max_length: Option<usize>,
+        pub pattern: Option<&'static str>,
+        pub format: Option<&'static str>,
+    }
+
+    /// Compiled `pattern`s, built once per distinct pattern.
+    #[cfg(feature = "derive")]
+    fn regex(pattern: &'static str) -> std::result::Result<regex::Regex, regex::Error> {
+        use std::collections::HashMap;
+        use std::sync::{Mutex, OnceLock};
+
+        static CACHE: OnceLock<Mutex<HashMap<&'static str, regex::Regex>>> = OnceLock::new();
+        let mut cache = CACHE
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if let Some(regex) = cache.get(pattern) {
+            return Ok(regex.clone());
+        }
+        let regex = regex::Regex::new(pattern)?;
+        cache.insert(pattern, regex.clone());
+        Ok(regex)
+    }
+
+    /// Whether `value` is a valid instance of the JSON Schema `format`, or
+    /// `None` for formats that are not checked (they stay a schema hint).
+    #[cfg(feature = "derive")]
+    fn conforms_to_format(format: &str, value: &str) -> Option<bool> {
+        let pattern = match format {
+            "ipv4" => return Some(value.parse::<std::net::Ipv4Addr>().is_ok()),
+            "ipv6" => return Some(value.parse::<std::net::Ipv6Addr>().is_ok()),
+            "email" => r"^[^@\s]+@[^@\s.]+(\.[^@\s.]+)+$",
+            "uri" | "url" => r"^[A-Za-z][A-Za-z0-9+.-]*:\S+$",
+            "uuid" => {
+                r"^[0-9A-Fa-f]{8}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{12}$"
+            }
+            "hostname" => {
+                r"^[A-Za-z0-9]([A-Za-z0-9-]{0,61}[A-Za-z0-9])?(\.[A-Za-z0-9]([A-Za-z0-9-]{0,61}[A-Za-z0-9])?)*$"
+            }
+            "date" => r"^\d{4}-(0[1-9]|1[0-2])-(0[1-9]|[12]\d|3[01])$",
+            "time" => {
+                r"^([01]\d|2[0-3]):[0-5]\d:([0-5]\d|60)(\.\d+)?([Zz]|[+-]([01]\d|2[0-3]):[0-5]\d)?$"
+            }
+            "date-time" => {
+                r"^\d{4}-(0[1-9]|1[0-2])-(0[1-9]|[12]\d|3[01])[Tt ]([01]\d|2[0-3]):[0-5]\d:([0-5]\d|60)(\.\d+)?([Zz]|[+-]([01]\d|2[0-3]):[0-5]\d)$"
+            }
+            _ => return None,
+        };
+        Some(regex(pattern).ok()?.is_match(value))
+    }
+
+    /// Check `value` of the field named `field` against `bounds`. Lengths
+    /// count characters, as JSON Schema does.
+    #[cfg(feature = "derive")]
+    pub fn check_string<T: StringField>(
+        field: &str,
+        value: &T,
+        bounds: &StringBounds,
+    ) -> Result<()> {
+        let Some(value) = value.rstructor_str() else {
+            return Ok(());
+        };
+        let length = value.chars().count();
+        let violation = match *bounds {
+            StringBounds {
+                min_length: Some(min),
+                ..
+            } if length < min => Some(format!("at least {min} characters long")),
+            StringBounds {
+                max_length: Some(max),
+                ..
+            } if length > max => Some(format!("at most {max} characters long")),
+            StringBounds {
+                pattern: Some(pattern),
+                ..
+            } => match regex(pattern) {
+                Ok(regex) if !regex.is_match(value) => {
+                    Some(format!("a match for the pattern `{pattern}`"))
+                }
+                Ok(_) => None,
+                Err(err) => {
+                    return Err(crate::error::RStructorError::SchemaError(format!(
+                        "invalid pattern on `{field}`: {err}"
+                    )));
+                }
+            },
+            _ => None,
+        };
+        let violation = violation.or_else(|| {
+            let format = bounds.format?;
+            (conforms_to_format(format, value) == Some(false)).then(|| format!("a valid {format}"))
+        });
+        match violation {
+            Some(expected) => Err(crate::error::RStructorError::ValidationError(format!(
+                "`{field}` must be {expected}, got {value:?}"
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Check that `value` of the field named `field` is written in `language`,
+    /// an ISO 639-3 code or English language name. Text whose language can't
+    /// be told reliably passes.
+    #[cfg(feature = "language")]
+    pub fn check_language<T: StringField>(field: &str, value: &T, language: &str) -> Result<()> {
+        let Some(value) = value.rstructor_str() else {
+            return Ok(());
+        };
+        let expected = whatlang::Lang::from_code(language.to_ascii_lowercase())
+            .or_else(|| {
+                whatlang::Lang::all()
+                    .iter()
+                    .copied()
+                    .find(|lang| lang.eng_name().eq_ignore_ascii_case(language))
+            })
+            .ok_or_else(|| {
+                crate::error::RStructorError::SchemaError(format!(
+                    "unknown language `{language}` on `{field}`"
+                ))
+            })?;
+        match whatlang::detect(value) {
+            Some(info) if info.is_reliable() && info.lang() != expected => {
+                Err(crate::error::RStructorError::ValidationError(format!(
+                    "`{field}` must be written in {}, but it reads as {}",
+                    expected.eng_name(),
+                    info.lang().eng_name()
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Evaluated only where `#[llm(language = ..)]` is used, to name the
+    /// missing feature instead of the missing function.
+    #[cfg(all(feature = "derive", not(feature = "language")))]
+    struct LanguageFeature<T>(std::marker::PhantomData<T>);
+
+    #[cfg(all(feature = "derive", not(feature = "language")))]
+    impl<T> LanguageFeature<T> {
+        const REQUIRED: () =
+            panic!("`#[llm(language = ..)]` needs the `language` feature of rstructor");
+    }
+
+    #[cfg(all(feature = "derive", not(feature = "language")))]
+    pub fn check_language<T: StringField>(_field: &str, _value: &T, _language: &str) -> Result<()> {
+        let () = LanguageFeature::<T>::REQUIRED;
+        Ok(())
+    }
 }
 
 /// Helper trait to mark a type as implementing custom validation.
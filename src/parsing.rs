@@ -0,0 +1,496 @@
+//! Utilities for turning raw model text into JSON.
+//!
+//! These are the helpers `materialize` relies on internally, exposed for code that
+//! post-processes raw [`generate`](crate::LLMClient::generate) output itself:
+//!
+//! - [`extract_json_from_markdown`] pulls JSON out of a Markdown code fence or
+//!   surrounding prose; [`find_json_candidates`] lists every embedded value.
+//! - [`is_truncated_json`] detects a document cut off by the output token limit.
+//! - [`complete_json`] / [`repair_json`] close a truncated JSON prefix.
+//!
+//! ```
+//! use rstructor::parsing::{complete_json, extract_json_from_markdown, is_truncated_json};
+//! use serde_json::json;
+//!
+//! let raw = "Here you go:\n```json\n{\"title\": \"Incep\n```";
+//! let json = extract_json_from_markdown(raw);
+//! assert!(is_truncated_json(json));
+//! assert_eq!(complete_json(json), Some(json!({"title": "Incep"})));
+//! ```
+
+use serde_json::Value;
+
+/// Return the JSON inside `text`: the whole trimmed text when it is already a
+/// JSON object/array, otherwise the body of the first Markdown code fence, the
+/// first balanced JSON object/array embedded in surrounding prose, or the whole
+/// trimmed text when neither is found.
+///
+/// Valid JSON is checked first so fences inside its string values are left
+/// alone. The fence's language tag (e.g. ` ```json `) is skipped. An
+/// unterminated fence, typical of truncated output, yields everything after the
+/// opening fence. Unfenced text that starts with `{` or `[` is returned as-is
+/// (trimmed) so truncated documents reach [`is_truncated_json`] intact. When
+/// prose contains several candidates, use [`find_json_candidates`] to inspect
+/// all of them.
+///
+/// ```
+/// use rstructor::parsing::extract_json_from_markdown;
+///
+/// let raw = "Sure!\n```json\n{\"title\": \"Inception\"}\n```\nAnything else?";
+/// assert_eq!(extract_json_from_markdown(raw), r#"{"title": "Inception"}"#);
+/// assert_eq!(extract_json_from_markdown("  [1, 2]\n"), "[1, 2]");
+///
+/// let prose = r#"The answer is {"title": "Inception"}, released in 2010."#;
+/// assert_eq!(extract_json_from_markdown(prose), r#"{"title": "Inception"}"#);
+/// ```
+pub fn extract_json_from_markdown(text: &str) -> &str {
+    let trimmed = text.trim();
+    let starts_json = trimmed.starts_with('{') || trimmed.starts_with('[');
+    if starts_json && serde_json::from_str::<Value>(trimmed).is_ok() {
+        return trimmed;
+    }
+    if let Some(open) = trimmed.find("```") {
+        let body = trimmed[open + 3..]
+            .trim_start_matches(|c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        let body = match body.find("```") {
+            Some(close) => &body[..close],
+            None => body,
+        };
+        return body.trim();
+    }
+    if starts_json {
+        return trimmed;
+    }
+    find_json_candidates(trimmed)
+        .into_iter()
+        .next()
+        .unwrap_or(trimmed)
+}
+
+/// Every balanced JSON object or array embedded in `text`, in order of appearance.
+///
+/// Only spans that parse as JSON are returned, so bracketed prose such as
+/// `[sic]` is skipped. Nested values are not reported separately from the value
+/// that contains them.
+///
+/// ```
+/// use rstructor::parsing::find_json_candidates;
+///
+/// let raw = r#"Either {"n": 1} or {"n": 2} [sic] works."#;
+/// assert_eq!(find_json_candidates(raw), vec![r#"{"n": 1}"#, r#"{"n": 2}"#]);
+/// ```
+pub fn find_json_candidates(text: &str) -> Vec<&str> {
+    let mut candidates = Vec::new();
+    let mut search_from = 0;
+    while let Some(offset) = text[search_from..].find(['{', '[']) {
+        let start = search_from + offset;
+        match balanced_span_end(&text[start..]) {
+            Some(len) if serde_json::from_str::<Value>(&text[start..start + len]).is_ok() => {
+                candidates.push(&text[start..start + len]);
+                search_from = start + len;
+            }
+            // Not valid JSON here; a value may still start inside it.
+            _ => search_from = start + 1,
+        }
+    }
+    candidates
+}
+
+/// Byte length of the balanced object/array starting at the beginning of `s`,
+/// or `None` if it is never closed or a closer does not match its opener.
+fn balanced_span_end(s: &str) -> Option<usize> {
+    let mut stack: Vec<char> = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in s.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' | '[' => stack.push(c),
+            '}' | ']' => {
+                let opener = if c == '}' { '{' } else { '[' };
+                if stack.pop() != Some(opener) {
+                    return None;
+                }
+                if stack.is_empty() {
+                    return Some(i + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Returns true if `raw` starts a JSON object or array that is cut off before
+/// it is complete: it ends with unclosed braces/brackets or inside a string.
+///
+/// This is the signature of output that hit the provider's token limit.
+/// Malformed-but-complete JSON (e.g. an extra closing brace) and non-JSON text
+/// are not considered truncated.
+///
+/// ```
+/// use rstructor::parsing::is_truncated_json;
+///
+/// assert!(is_truncated_json(r#"{"title": "Incep"#));
+/// assert!(!is_truncated_json(r#"{"title": "Inception"}"#));
+/// ```
+pub fn is_truncated_json(raw: &str) -> bool {
+    let trimmed = raw.trim();
+    if !(trimmed.starts_with('{') || trimmed.starts_with('[')) {
+        return false;
+    }
+
+    let mut depth: usize = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in trimmed.chars() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' | '[' => depth += 1,
+            '}' | ']' => match depth.checked_sub(1) {
+                Some(d) => depth = d,
+                // More closers than openers: malformed, not truncated.
+                None => return false,
+            },
+            _ => {}
+        }
+    }
+    in_string || depth > 0
+}
+
+/// Repair a possibly-truncated JSON prefix into a parseable JSON value.
+///
+/// Returns `Some(value)` only when the repaired text actually parses, so callers
+/// never see invalid JSON; when the prefix is too incomplete to safely complete
+/// (e.g. a half-written number) it returns `None` and the caller simply waits for
+/// more input. Streaming uses this to emit progressive snapshots of structured
+/// output; the authoritative final parse always uses the raw buffer.
+///
+/// ```
+/// use rstructor::parsing::complete_json;
+/// use serde_json::json;
+///
+/// assert_eq!(complete_json(r#"{"name": "Ali"#), Some(json!({"name": "Ali"})));
+/// assert_eq!(complete_json(r#"{"n": 12."#), None);
+/// ```
+pub fn complete_json(s: &str) -> Option<Value> {
+    let repaired = repair_json(s)?;
+    serde_json::from_str(&repaired).ok()
+}
+
+/// Best-effort completion of a truncated JSON prefix: close an open string, drop a
+/// dangling key/comma, and close any open objects/arrays. The result is validated
+/// by [`complete_json`] before use, so imperfect repairs are simply discarded.
+///
+/// Returns `None` for empty input or mismatched closers. The returned text is
+/// not guaranteed to parse; use [`complete_json`] when you need a value.
+///
+/// ```
+/// use rstructor::parsing::repair_json;
+///
+/// assert_eq!(repair_json(r#"{"items": [1, 2,"#).as_deref(), Some(r#"{"items": [1, 2]}"#));
+/// ```
+pub fn repair_json(s: &str) -> Option<String> {
+    let s = s.trim();
+    if s.is_empty() {
+        return None;
+    }
+
+    let mut out = String::with_capacity(s.len() + 8);
+    let mut stack: Vec<char> = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for c in s.chars() {
+        if in_string {
+            out.push(c);
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+        } else {
+            match c {
+                '"' => {
+                    in_string = true;
+                    out.push(c);
+                }
+                '{' => {
+                    stack.push('{');
+                    out.push(c);
+                }
+                '[' => {
+                    stack.push('[');
+                    out.push(c);
+                }
+                '}' => {
+                    if stack.pop() != Some('{') {
+                        return None;
+                    }
+                    out.push(c);
+                }
+                ']' => {
+                    if stack.pop() != Some('[') {
+                        return None;
+                    }
+                    out.push(c);
+                }
+                _ => out.push(c),
+            }
+        }
+    }
+
+    // A trailing incomplete escape (`...\`) inside a string: drop the backslash.
+    if in_string && escaped {
+        out.pop();
+    }
+    // Close an open string.
+    if in_string {
+        out.push('"');
+    }
+
+    // Trim trailing structural debris that can't be completed: a dangling comma,
+    // or a dangling object key (`"key":` with no value yet).
+    loop {
+        let trimmed_len = out.trim_end().len();
+        out.truncate(trimmed_len);
+        if out.ends_with(',') {
+            out.pop();
+            continue;
+        }
+        if out.ends_with(':') {
+            // Drop the dangling `"key":` back to the previous `{` or `,`.
+            if let Some(cut) = out.rfind(['{', ',']) {
+                out.truncate(cut + 1);
+            } else {
+                return None;
+            }
+            continue;
+        }
+        break;
+    }
+
+    // Close any still-open containers, innermost first.
+    for &opener in stack.iter().rev() {
+        out.push(if opener == '{' { '}' } else { ']' });
+    }
+
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn extract_json_returns_fenced_body() {
+        assert_eq!(
+            extract_json_from_markdown("```json\n{\"a\": 1}\n```"),
+            r#"{"a": 1}"#
+        );
+        assert_eq!(extract_json_from_markdown("```\n[1]\n```"), "[1]");
+        assert_eq!(
+            extract_json_from_markdown(r#"```json{"a": 1}```"#),
+            r#"{"a": 1}"#
+        );
+    }
+
+    #[test]
+    fn extract_json_ignores_surrounding_prose_and_later_fences() {
+        let raw = "Result:\n```json\n{\"a\": 1}\n```\nAlso:\n```\nnot this\n```";
+        assert_eq!(extract_json_from_markdown(raw), r#"{"a": 1}"#);
+    }
+
+    #[test]
+    fn extract_json_without_fence_or_with_unterminated_fence() {
+        assert_eq!(extract_json_from_markdown("  {\"a\": 1} "), r#"{"a": 1}"#);
+        assert_eq!(
+            extract_json_from_markdown("```json\n{\"a\": \"tru"),
+            r#"{"a": "tru"#
+        );
+    }
+
+    #[test]
+    fn extract_json_keeps_fences_inside_string_values() {
+        let raw = r#"{"code": "```rust\nfn main() {}\n```"}"#;
+        assert_eq!(extract_json_from_markdown(raw), raw);
+        let raw = r#"["```", "```"]"#;
+        assert_eq!(extract_json_from_markdown(raw), raw);
+    }
+
+    #[test]
+    fn extract_json_finds_value_in_prose() {
+        assert_eq!(
+            extract_json_from_markdown(r#"Here: {"a": {"b": [1, 2]}} done"#),
+            r#"{"a": {"b": [1, 2]}}"#
+        );
+        assert_eq!(
+            extract_json_from_markdown(r#"Note [sic]: {"a": "}"} ok"#),
+            r#"{"a": "}"}"#
+        );
+        assert_eq!(extract_json_from_markdown("no json here"), "no json here");
+    }
+
+    #[test]
+    fn candidates_skip_invalid_and_recover_nested() {
+        assert_eq!(
+            find_json_candidates(r#"{oops {"a": 1}} then [1, 2]"#),
+            vec![r#"{"a": 1}"#, "[1, 2]"]
+        );
+        assert!(find_json_candidates(r#"{"a": "unterminated"#).is_empty());
+        assert!(find_json_candidates(r#"{"a": 1]"#).is_empty());
+    }
+
+    #[test]
+    fn truncated_json_detects_unclosed_containers_and_strings() {
+        assert!(is_truncated_json(r#"{"title": "Inception", "year": 20"#));
+        assert!(is_truncated_json(r#"{"items": [{"n": 1}, {"n": 2"#));
+        assert!(is_truncated_json(r#"{"title": "Incep"#));
+        assert!(is_truncated_json(r#"  [1, 2"#));
+    }
+
+    #[test]
+    fn truncated_json_ignores_complete_malformed_and_non_json() {
+        assert!(!is_truncated_json(r#"{"title": "Inception"}"#));
+        assert!(!is_truncated_json(r#"{"a": "braces { and [ in strings"}"#));
+        assert!(!is_truncated_json(r#"{"a": "escaped \" quote"}"#));
+        assert!(!is_truncated_json(r#"{"a": 1}}"#));
+        assert!(!is_truncated_json("plain prose"));
+        assert!(!is_truncated_json(""));
+    }
+
+    #[test]
+    fn complete_json_closes_open_string_and_object() {
+        assert_eq!(
+            complete_json(r#"{"name": "Ali"#).unwrap(),
+            json!({"name": "Ali"})
+        );
+    }
+
+    #[test]
+    fn complete_json_drops_dangling_key_and_comma() {
+        assert_eq!(complete_json(r#"{"a": 1, "b":"#).unwrap(), json!({"a": 1}));
+        assert_eq!(complete_json(r#"{"a": 1, "#).unwrap(), json!({"a": 1}));
+        assert_eq!(complete_json(r#"{"a": 1,"#).unwrap(), json!({"a": 1}));
+    }
+
+    #[test]
+    fn complete_json_closes_nested_and_arrays() {
+        assert_eq!(
+            complete_json(r#"{"items":[{"x":1},{"x":2"#).unwrap(),
+            json!({"items":[{"x":1},{"x":2}]})
+        );
+        assert_eq!(complete_json(r#"[1, 2, 3"#).unwrap(), json!([1, 2, 3]));
+        assert_eq!(complete_json(r#"[1, 2, "#).unwrap(), json!([1, 2]));
+    }
+
+    #[test]
+    fn complete_json_skips_incomplete_primitive() {
+        // A half-written number/keyword can't be safely completed → None.
+        assert!(complete_json(r#"{"a": tr"#).is_none());
+        assert!(complete_json(r#"{"a": 12."#).is_none());
+        assert!(complete_json("").is_none());
+    }
+
+    #[test]
+    fn complete_json_handles_escapes() {
+        assert_eq!(
+            complete_json(r#"{"s": "line\"#).unwrap(),
+            json!({"s": "line"})
+        );
+        assert_eq!(
+            complete_json(r#"{"s": "a\nb"#).unwrap(),
+            json!({"s": "a\nb"})
+        );
+    }
+
+    #[test]
+    fn complete_json_progressive_prefixes_converge() {
+        let full = r#"{"name":"Alice","age":30,"tags":["x","y"]}"#;
+        // Every prefix either yields None or a valid JSON value, and the full
+        // string yields the exact object.
+        for i in 1..=full.len() {
+            if let Some(v) = complete_json(&full[..i]) {
+                assert!(v.is_object() || v.is_array());
+            }
+        }
+        assert_eq!(
+            complete_json(full).unwrap(),
+            json!({"name":"Alice","age":30,"tags":["x","y"]})
+        );
+    }
+
+    #[test]
+    fn complete_json_rejects_truncated_unicode_escape() {
+        // The braces/quotes are balanced so repair produces a string, but the
+        // truncated `\u00` escape makes it invalid JSON → None.
+        assert!(complete_json(r#"{"s":"\u00"#).is_none());
+    }
+
+    #[test]
+    fn complete_json_rejects_unbalanced_or_extra_closers() {
+        // Extra `}` and a mismatched `]` can't be repaired.
+        assert!(complete_json(r#"{"a":1}}"#).is_none());
+        assert!(complete_json(r#"{"a":1]"#).is_none());
+    }
+
+    #[test]
+    fn complete_json_handles_odd_and_even_trailing_backslashes() {
+        // Even backslashes: `a\\` is a complete escaped backslash; the value is a
+        // single backslash. (`json!({"s": "a\\"})` is the string `a\`.)
+        assert_eq!(complete_json(r#"{"s":"a\\"#).unwrap(), json!({"s": "a\\"}));
+        // Odd backslashes: the dangling final `\` is an incomplete escape and is
+        // dropped, leaving the same completed value.
+        assert_eq!(complete_json(r#"{"s":"a\\\"#).unwrap(), json!({"s": "a\\"}));
+    }
+
+    #[test]
+    fn complete_json_rejects_dangling_minus_but_allows_negative_exponent() {
+        assert!(complete_json(r#"{"a":-"#).is_none());
+        assert_eq!(
+            complete_json(r#"{"a":-1.2e10"#).unwrap(),
+            json!({"a": -1.2e10})
+        );
+    }
+
+    #[test]
+    fn complete_json_rejects_dangling_colon_without_container() {
+        // A dangling key/colon with no surrounding `{`/`,` to cut back to → None.
+        assert!(complete_json(r#""key":"#).is_none());
+        assert!(complete_json("x:").is_none());
+    }
+
+    #[test]
+    fn complete_json_passes_top_level_scalars_through() {
+        assert_eq!(complete_json("42").unwrap(), json!(42));
+        assert_eq!(complete_json("true").unwrap(), json!(true));
+        assert_eq!(complete_json(r#""hello"#).unwrap(), json!("hello"));
+        assert_eq!(complete_json("[1,2,3]").unwrap(), json!([1, 2, 3]));
+    }
+
+    #[test]
+    fn complete_json_trims_trailing_whitespace_and_comma() {
+        assert_eq!(complete_json("{\"a\":1,  \n  ").unwrap(), json!({"a": 1}));
+    }
+}
@@ -0,0 +1,73 @@
+//! The items most programs need, in one import.
+//!
+//! ```no_run
+//! use rstructor::prelude::*;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Instructor, Serialize, Deserialize, Debug)]
+//! struct Movie {
+//!     title: String,
+//!     year: u16,
+//! }
+//!
+//! # async fn run() -> Result<(), RStructorError> {
+//! let client = OpenAIClient::from_env()?
+//!     .model(OpenAIModel::Gpt41Mini)
+//!     .thinking_level(ThinkingLevel::Low);
+//! let movie: Movie = client.materialize("Alien (1979)").await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! `rstructor::Result` is deliberately left out so the glob import does not
+//! shadow `std::result::Result`.
+
+pub use crate::{
+    ApiErrorKind, ChatMessage, ChatRole, Conversation, GenerateResult, Instructor, LLMClient,
+    MaterializeExt, MaterializeResult, MediaFile, RStructorError, Schema, SchemaType,
+    ThinkingLevel, TokenUsage,
+};
+
+#[cfg(feature = "_client")]
+pub use crate::{
+    AnyClient, ContextOverflow, DynLLMClient, DynMaterializeExt, Priority, Provider, RequestExt,
+    RetryBudget,
+};
+
+#[cfg(feature = "openai")]
+pub use crate::{OpenAIClient, OpenAIModel};
+
+#[cfg(feature = "anthropic")]
+pub use crate::{AnthropicClient, AnthropicModel};
+
+#[cfg(feature = "azure")]
+pub use crate::AzureOpenAIClient;
+
+#[cfg(feature = "vertex")]
+pub use crate::VertexAIClient;
+#[cfg(feature = "bedrock")]
+pub use crate::{BedrockClient, BedrockModel};
+
+#[cfg(feature = "gemini")]
+pub use crate::{GeminiClient, GeminiModel};
+
+#[cfg(feature = "grok")]
+pub use crate::{GrokClient, GrokModel};
+
+#[cfg(feature = "groq")]
+pub use crate::{GroqClient, GroqModel};
+
+#[cfg(feature = "together")]
+pub use crate::{TogetherClient, TogetherModel};
+
+#[cfg(feature = "ollama")]
+pub use crate::{OllamaClient, OllamaModel};
+
+#[cfg(feature = "streaming")]
+pub use crate::Partial;
+
+#[cfg(feature = "tools")]
+pub use crate::{Tool, Toolbox};
+
+#[cfg(feature = "mock")]
+pub use crate::{MockClient, MockResponse};
@@ -0,0 +1,201 @@
+//! Repairing scalar type mismatches in a reply before re-asking the model.
+//!
+//! Models often return `"42"` for an integer field, `42` for a string field, or
+//! a single object where the schema asks for a list. Each of these fails
+//! deserialization even though the intended value is unambiguous, and a retry
+//! costs a full round trip. [`Schema::coerce`] rewrites such values in place,
+//! guided by the types the schema declares, so the reply can be deserialized
+//! without asking again.
+
+use serde_json::{Number, Value};
+
+use super::Schema;
+use super::unknown::flatten_branches;
+
+/// Nesting depth beyond which the walk stops (guards recursive `$ref`s).
+const MAX_DEPTH: usize = 64;
+
+impl Schema {
+    /// Rewrite values in `instance` whose JSON type the schema does not allow,
+    /// where one allowed type holds the same value unambiguously. Returns the
+    /// number of values rewritten.
+    ///
+    /// Three repairs are made:
+    ///
+    /// - a numeric string (`"42"`, `" 3.5 "`) where a number or integer is
+    ///   expected becomes that number; `"3.5"` is left alone for an integer;
+    /// - a number where a string is expected becomes its decimal text;
+    /// - a single value where an array is expected becomes a one-element array.
+    ///
+    /// Values whose type the schema already allows are never changed, and
+    /// `$ref`s and `anyOf`/`oneOf`/`allOf` branches are followed to find the
+    /// allowed types.
+    ///
+    /// ```
+    /// use rstructor::Schema;
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::new(json!({
+    ///     "type": "object",
+    ///     "properties": {
+    ///         "year": { "type": "integer" },
+    ///         "isbn": { "type": "string" },
+    ///         "tags": { "type": "array", "items": { "type": "string" } }
+    ///     }
+    /// }));
+    /// let mut reply = json!({ "year": "1984", "isbn": 9780451524935_u64, "tags": "dystopia" });
+    /// assert_eq!(schema.coerce(&mut reply), 3);
+    /// assert_eq!(
+    ///     reply,
+    ///     json!({ "year": 1984, "isbn": "9780451524935", "tags": ["dystopia"] })
+    /// );
+    /// ```
+    pub fn coerce(&self, instance: &mut Value) -> usize {
+        coerce(&self.schema, &self.schema, instance, 0)
+    }
+}
+
+fn coerce(root: &Value, schema: &Value, instance: &mut Value, depth: usize) -> usize {
+    if depth > MAX_DEPTH {
+        return 0;
+    }
+    let mut branches = Vec::new();
+    flatten_branches(root, schema, 0, &mut branches);
+    let allowed: Vec<&str> = branches
+        .iter()
+        .flat_map(|branch| match branch.get("type") {
+            Some(Value::String(name)) => vec![name.as_str()],
+            Some(Value::Array(names)) => names.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        })
+        .collect();
+
+    let mut coerced = 0;
+    if !allowed.is_empty() && !allowed.iter().any(|name| has_type(instance, name)) {
+        if let Some(value) = coerce_scalar(instance, &allowed) {
+            *instance = value;
+            coerced += 1;
+        } else if allowed.contains(&"array") && !instance.is_null() {
+            *instance = Value::Array(vec![instance.take()]);
+            coerced += 1;
+        }
+    }
+
+    match instance {
+        Value::Object(object) => {
+            let value_schema = branches
+                .iter()
+                .find_map(|b| b.get("additionalProperties").filter(|s| s.is_object()));
+            for (key, value) in object {
+                let declared = branches
+                    .iter()
+                    .find_map(|b| b.get("properties").and_then(|p| p.get(key)))
+                    .or(value_schema);
+                if let Some(sub) = declared {
+                    coerced += coerce(root, sub, value, depth + 1);
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(item_schema) = branches
+                .iter()
+                .find_map(|b| b.get("items").filter(|s| s.is_object()))
+            {
+                for item in items {
+                    coerced += coerce(root, item_schema, item, depth + 1);
+                }
+            }
+        }
+        _ => {}
+    }
+    coerced
+}
+
+/// Whether `instance` is of the JSON Schema type `name`.
+fn has_type(instance: &Value, name: &str) -> bool {
+    match name {
+        "null" => instance.is_null(),
+        "boolean" => instance.is_boolean(),
+        "string" => instance.is_string(),
+        "object" => instance.is_object(),
+        "array" => instance.is_array(),
+        "number" => instance.is_number(),
+        "integer" => instance.is_i64() || instance.is_u64(),
+        _ => false,
+    }
+}
+
+/// `instance` as a number or string of one of the `allowed` types, if it
+/// converts losslessly.
+fn coerce_scalar(instance: &Value, allowed: &[&str]) -> Option<Value> {
+    match instance {
+        Value::String(text) => {
+            let text = text.trim();
+            if allowed.contains(&"integer") {
+                if let Ok(n) = text.parse::<i64>() {
+                    return Some(Value::from(n));
+                }
+                if let Ok(n) = text.parse::<u64>() {
+                    return Some(Value::from(n));
+                }
+            }
+            if allowed.contains(&"number") {
+                return text
+                    .parse::<f64>()
+                    .ok()
+                    .and_then(Number::from_f64)
+                    .map(Value::Number);
+            }
+            None
+        }
+        Value::Number(n) if allowed.contains(&"string") => Some(Value::String(n.to_string())),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn follows_refs_and_array_items() {
+        let schema = Schema::new(json!({
+            "type": "object",
+            "properties": {
+                "books": { "type": "array", "items": { "$ref": "#/$defs/Book" } }
+            },
+            "$defs": {
+                "Book": {
+                    "type": "object",
+                    "properties": {
+                        "pages": { "type": "integer" },
+                        "price": { "type": ["number", "null"] }
+                    }
+                }
+            }
+        }));
+        let mut reply = json!({ "books": { "pages": "310", "price": "12.5" } });
+        assert_eq!(schema.coerce(&mut reply), 3);
+        assert_eq!(reply, json!({ "books": [{ "pages": 310, "price": 12.5 }] }));
+    }
+
+    #[test]
+    fn leaves_ambiguous_and_allowed_values_alone() {
+        let schema = Schema::new(json!({
+            "type": "object",
+            "properties": {
+                "count": { "type": "integer" },
+                "label": { "anyOf": [{ "type": "string" }, { "type": "integer" }] },
+                "tags": { "type": "array", "items": { "type": "string" } }
+            }
+        }));
+        let mut reply = json!({ "count": "about 3", "label": 7, "tags": null });
+        let before = reply.clone();
+        assert_eq!(schema.coerce(&mut reply), 0);
+        assert_eq!(reply, before);
+
+        let mut fraction = json!({ "count": "3.5" });
+        assert_eq!(schema.coerce(&mut fraction), 0);
+    }
+}
@@ -0,0 +1,220 @@
+//! Emitting schemas for a specific JSON Schema draft.
+//!
+//! Derived schemas use 2020-12 keywords (`$defs`, `prefixItems`) without a
+//! `$schema` marker, which is what the LLM providers accept. Validators and
+//! tooling outside the request path often need the draft spelled out — and
+//! draft-07 validators silently ignore `$defs` and `prefixItems`, so a schema
+//! that looks fine validates nothing. [`Schema::for_draft`] rewrites the
+//! draft-specific keywords and adds the matching `$schema` URI.
+
+use serde_json::{Map, Value};
+
+use super::Schema;
+
+/// A JSON Schema draft to emit with [`Schema::for_draft`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SchemaDraft {
+    /// Draft-07: `definitions` and array-form `items` for tuples.
+    Draft07,
+    /// Draft 2020-12: `$defs` and `prefixItems` (what derived schemas use).
+    #[default]
+    Draft2020_12,
+}
+
+impl SchemaDraft {
+    /// The meta-schema URI written to `$schema`.
+    pub fn uri(self) -> &'static str {
+        match self {
+            SchemaDraft::Draft07 => "http://json-schema.org/draft-07/schema#",
+            SchemaDraft::Draft2020_12 => "https://json-schema.org/draft/2020-12/schema",
+        }
+    }
+
+    fn defs_keyword(self) -> &'static str {
+        match self {
+            SchemaDraft::Draft07 => "definitions",
+            SchemaDraft::Draft2020_12 => "$defs",
+        }
+    }
+}
+
+impl Schema {
+    /// This schema expressed in `draft`, with a `$schema` marker at the root.
+    ///
+    /// Converts in either direction between draft-07 and 2020-12:
+    /// `$defs` ↔ `definitions` (and the `$ref`s pointing into them), and
+    /// `prefixItems` + `items` ↔ array-form `items` + `additionalItems`.
+    /// Keywords both drafts share, such as `const` and `examples`, are kept.
+    ///
+    /// ```
+    /// use rstructor::{Schema, SchemaDraft};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::new(json!({
+    ///     "type": "object",
+    ///     "properties": { "next": { "$ref": "#/$defs/Node" } },
+    ///     "$defs": { "Node": { "type": "object" } }
+    /// }));
+    /// let draft7 = schema.for_draft(SchemaDraft::Draft07).to_json();
+    /// assert_eq!(draft7["$schema"], "http://json-schema.org/draft-07/schema#");
+    /// assert_eq!(draft7["properties"]["next"]["$ref"], "#/definitions/Node");
+    /// assert!(draft7["definitions"]["Node"].is_object());
+    /// ```
+    pub fn for_draft(&self, draft: SchemaDraft) -> Schema {
+        let mut converted = convert(&self.schema, draft);
+        if let Value::Object(root) = &mut converted {
+            let mut with_marker = Map::with_capacity(root.len() + 1);
+            with_marker.insert("$schema".to_string(), Value::from(draft.uri()));
+            for (key, value) in std::mem::take(root) {
+                if key != "$schema" {
+                    with_marker.insert(key, value);
+                }
+            }
+            *root = with_marker;
+        }
+        Schema::new(converted)
+    }
+}
+
+/// Keywords whose values are instance data rather than subschemas.
+pub(super) const DATA_KEYWORDS: &[&str] = &["const", "default", "enum", "example", "examples"];
+
+/// Keywords whose values map arbitrary names (never keywords) to subschemas.
+const NAMED_SCHEMA_KEYWORDS: &[&str] = &[
+    "properties",
+    "patternProperties",
+    "dependentSchemas",
+    "$defs",
+    "definitions",
+];
+
+fn convert(value: &Value, draft: SchemaDraft) -> Value {
+    match value {
+        Value::Object(obj) => {
+            let mut out = Map::with_capacity(obj.len());
+            for (key, child) in obj {
+                let converted = match (key.as_str(), child) {
+                    ("$ref", Value::String(reference)) => {
+                        Value::from(convert_ref(reference, draft))
+                    }
+                    (k, _) if DATA_KEYWORDS.contains(&k) => child.clone(),
+                    (k, Value::Object(named)) if NAMED_SCHEMA_KEYWORDS.contains(&k) => {
+                        Value::Object(
+                            named
+                                .iter()
+                                .map(|(name, schema)| (name.clone(), convert(schema, draft)))
+                                .collect(),
+                        )
+                    }
+                    _ => convert(child, draft),
+                };
+                let key = match key.as_str() {
+                    "$defs" | "definitions" => draft.defs_keyword().to_string(),
+                    _ => key.clone(),
+                };
+                out.insert(key, converted);
+            }
+            convert_tuple_keywords(&mut out, draft);
+            Value::Object(out)
+        }
+        Value::Array(items) => Value::Array(items.iter().map(|v| convert(v, draft)).collect()),
+        other => other.clone(),
+    }
+}
+
+fn convert_ref(reference: &str, draft: SchemaDraft) -> String {
+    for prefix in ["#/$defs/", "#/definitions/"] {
+        if let Some(name) = reference.strip_prefix(prefix) {
+            return format!("#/{}/{}", draft.defs_keyword(), name);
+        }
+    }
+    reference.to_string()
+}
+
+/// Rename tuple keywords in place, keeping the key's position.
+fn convert_tuple_keywords(obj: &mut Map<String, Value>, draft: SchemaDraft) {
+    let renames: &[(&str, &str)] = match draft {
+        SchemaDraft::Draft07 if obj.contains_key("prefixItems") => {
+            &[("items", "additionalItems"), ("prefixItems", "items")]
+        }
+        SchemaDraft::Draft2020_12 if obj.get("items").is_some_and(Value::is_array) => {
+            &[("items", "prefixItems"), ("additionalItems", "items")]
+        }
+        _ => return,
+    };
+    let renamed = std::mem::take(obj).into_iter().map(|(key, value)| {
+        let key = renames
+            .iter()
+            .find(|(from, _)| *from == key)
+            .map_or(key, |(_, to)| to.to_string());
+        (key, value)
+    });
+    *obj = renamed.collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn tuples_round_trip_between_drafts() {
+        let schema = Schema::new(json!({
+            "type": "array",
+            "prefixItems": [{ "type": "integer" }, { "const": "x" }],
+            "items": false,
+            "minItems": 2
+        }));
+        let draft7 = schema.for_draft(SchemaDraft::Draft07).to_json();
+        assert_eq!(
+            draft7,
+            json!({
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "type": "array",
+                "items": [{ "type": "integer" }, { "const": "x" }],
+                "additionalItems": false,
+                "minItems": 2
+            })
+        );
+
+        let back = Schema::new(draft7)
+            .for_draft(SchemaDraft::Draft2020_12)
+            .to_json();
+        assert_eq!(back["$schema"], SchemaDraft::Draft2020_12.uri());
+        assert_eq!(back["prefixItems"], schema.to_json()["prefixItems"]);
+        assert_eq!(back["items"], json!(false));
+    }
+
+    #[test]
+    fn property_names_and_examples_are_not_rewritten() {
+        let schema = Schema::new(json!({
+            "type": "object",
+            "properties": {
+                "items": { "type": "string" },
+                "definitions": { "type": "string" }
+            },
+            "examples": [{ "items": "a", "definitions": "b" }]
+        }));
+        let json = schema.for_draft(SchemaDraft::Draft07).to_json();
+        assert_eq!(
+            json["properties"],
+            json!({ "items": { "type": "string" }, "definitions": { "type": "string" } })
+        );
+        assert_eq!(json["examples"], schema.to_json()["examples"]);
+    }
+
+    #[test]
+    fn existing_marker_is_replaced_and_list_items_untouched() {
+        let schema = Schema::new(json!({
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "type": "array",
+            "items": { "type": "string" },
+            "examples": [["a"]]
+        }));
+        let json = schema.for_draft(SchemaDraft::Draft07).to_json();
+        assert_eq!(json["$schema"], SchemaDraft::Draft07.uri());
+        assert_eq!(json["items"], json!({ "type": "string" }));
+        assert!(json.get("additionalItems").is_none());
+        assert_eq!(json["examples"], json!([["a"]]));
+    }
+}
@@ -0,0 +1,238 @@
+//! Assembling a concrete example instance from a schema's `example` keywords.
+
+use serde_json::{Map, Value};
+
+use super::Schema;
+
+/// Schema extension keyword holding `#[llm(counter_example = ...)]` values.
+pub(crate) const COUNTER_EXAMPLES_KEY: &str = "x-counter-examples";
+
+/// How many `$ref` hops to follow before giving up (guards recursive types).
+const MAX_REF_DEPTH: usize = 8;
+
+impl Schema {
+    /// Instructions that spell this schema out in the prompt, for output
+    /// strategies (such as JSON mode) that cannot pass it to the provider.
+    ///
+    /// Renders the JSON Schema, then an example instance assembled from its
+    /// `example`/`examples` keywords when every required field has one, then
+    /// each container-level counter-example as "Do NOT return output like
+    /// this" guidance.
+    ///
+    /// ```
+    /// use rstructor::Schema;
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::new(json!({
+    ///     "type": "object",
+    ///     "properties": { "city": { "type": "string", "example": "Lisbon" } },
+    ///     "required": ["city"],
+    ///     "x-counter-examples": [{ "city": "Lisbon, Portugal" }]
+    /// }));
+    /// let instructions = schema.prompt_instructions();
+    /// assert!(instructions.starts_with("Respond with a single JSON object that matches this JSON Schema:"));
+    /// assert!(instructions.contains("Example of a valid response:\n{\n  \"city\": \"Lisbon\"\n}"));
+    /// assert!(instructions.contains("Do NOT return output like this:"));
+    /// ```
+    pub fn prompt_instructions(&self) -> String {
+        let pretty = |value: &Value| serde_json::to_string_pretty(value).unwrap_or_default();
+        let mut instructions = format!(
+            "Respond with a single JSON object that matches this JSON Schema:\n{}",
+            pretty(&self.schema)
+        );
+        if let Some(example) = example_from_schema(&self.schema) {
+            instructions.push_str(&format!(
+                "\n\nExample of a valid response:\n{}",
+                pretty(&example)
+            ));
+        }
+        for counter_example in counter_examples_from_schema(&self.schema) {
+            instructions.push_str(&format!(
+                "\n\nDo NOT return output like this:\n{}",
+                pretty(&counter_example)
+            ));
+        }
+        instructions
+    }
+}
+
+/// Build an example instance of `schema` from its `example`/`examples` keywords.
+///
+/// A schema-level example wins. Otherwise objects are assembled property by
+/// property, arrays from an example of their items, and `enum`/`const` schemas
+/// use their (first) allowed value. Optional properties without an example are
+/// left out; `None` is returned if any required property has none, since a
+/// partial instance would teach the model to drop fields.
+pub(crate) fn example_from_schema(schema: &Value) -> Option<Value> {
+    example_at(schema, schema, 0)
+}
+
+fn example_at(root: &Value, schema: &Value, ref_depth: usize) -> Option<Value> {
+    let obj = schema.as_object()?;
+
+    if let Some(example) = obj.get("example") {
+        return Some(example.clone());
+    }
+    if let Some(example) = obj.get("examples").and_then(|e| e.as_array()?.first()) {
+        return Some(example.clone());
+    }
+    if let Some(value) = obj.get("const") {
+        return Some(value.clone());
+    }
+    if let Some(value) = obj.get("enum").and_then(|e| e.as_array()?.first()) {
+        return Some(value.clone());
+    }
+    if let Some(reference) = obj.get("$ref").and_then(Value::as_str) {
+        if ref_depth >= MAX_REF_DEPTH {
+            return None;
+        }
+        return example_at(root, resolve_ref(root, reference)?, ref_depth + 1);
+    }
+    if let Some(branches) = obj
+        .get("anyOf")
+        .or_else(|| obj.get("oneOf"))
+        .and_then(Value::as_array)
+    {
+        return branches
+            .iter()
+            .find_map(|branch| example_at(root, branch, ref_depth));
+    }
+    if let Some(Value::Object(properties)) = obj.get("properties") {
+        return object_example(root, obj, properties, ref_depth);
+    }
+    if let Some(items) = obj.get("items") {
+        return Some(Value::Array(vec![example_at(root, items, ref_depth)?]));
+    }
+    None
+}
+
+fn object_example(
+    root: &Value,
+    obj: &Map<String, Value>,
+    properties: &Map<String, Value>,
+    ref_depth: usize,
+) -> Option<Value> {
+    let required = |name: &str| {
+        obj.get("required")
+            .and_then(Value::as_array)
+            .is_some_and(|r| r.iter().any(|v| v.as_str() == Some(name)))
+    };
+
+    let mut instance = Map::new();
+    for (name, property) in properties {
+        match example_at(root, property, ref_depth) {
+            Some(value) => {
+                instance.insert(name.clone(), value);
+            }
+            None if required(name) => return None,
+            None => {}
+        }
+    }
+    Some(Value::Object(instance))
+}
+
+/// The container-level counter-examples (`x-counter-examples`) of `schema`,
+/// following a root `$ref` as emitted for recursive types.
+pub(crate) fn counter_examples_from_schema(schema: &Value) -> Vec<Value> {
+    let container = match schema.get("$ref").and_then(Value::as_str) {
+        Some(reference) => resolve_ref(schema, reference).unwrap_or(schema),
+        None => schema,
+    };
+    container
+        .get(COUNTER_EXAMPLES_KEY)
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Resolve a local `#/...` JSON Pointer reference against the root schema.
+fn resolve_ref<'a>(root: &'a Value, reference: &str) -> Option<&'a Value> {
+    root.pointer(reference.strip_prefix('#')?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn assembles_object_from_property_examples() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string", "example": "Ada" },
+                "tags": { "type": "array", "items": { "type": "string", "examples": ["math"] } },
+                "level": { "type": "string", "enum": ["junior", "senior"] },
+                "nickname": { "type": "string" }
+            },
+            "required": ["name", "tags", "level"]
+        });
+        assert_eq!(
+            example_from_schema(&schema),
+            Some(json!({ "name": "Ada", "tags": ["math"], "level": "junior" }))
+        );
+    }
+
+    #[test]
+    fn missing_required_example_yields_none() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string", "example": "Ada" },
+                "age": { "type": "integer" }
+            },
+            "required": ["name", "age"]
+        });
+        assert_eq!(example_from_schema(&schema), None);
+    }
+
+    #[test]
+    fn container_example_wins() {
+        let schema = json!({
+            "type": "object",
+            "examples": [{ "name": "Grace" }],
+            "properties": { "name": { "type": "string", "example": "Ada" } },
+            "required": ["name"]
+        });
+        assert_eq!(
+            example_from_schema(&schema),
+            Some(json!({ "name": "Grace" }))
+        );
+    }
+
+    #[test]
+    fn counter_examples_are_read_through_root_ref() {
+        let schema = json!({
+            "$ref": "#/$defs/Node",
+            "$defs": { "Node": { "type": "object", "x-counter-examples": ["nope"] } }
+        });
+        assert_eq!(counter_examples_from_schema(&schema), vec![json!("nope")]);
+        assert!(counter_examples_from_schema(&json!({ "type": "object" })).is_empty());
+    }
+
+    #[test]
+    fn refs_are_followed_with_a_depth_limit() {
+        let schema = json!({
+            "$ref": "#/$defs/Node",
+            "$defs": {
+                "Node": {
+                    "type": "object",
+                    "properties": {
+                        "value": { "type": "integer", "example": 1 },
+                        "next": { "$ref": "#/$defs/Node" }
+                    },
+                    "required": ["value"]
+                }
+            }
+        });
+        let example = example_from_schema(&schema).unwrap();
+        assert_eq!(example["value"], 1);
+        let mut depth = 0;
+        let mut node = &example;
+        while let Some(next) = node.get("next") {
+            node = next;
+            depth += 1;
+        }
+        assert!(depth < MAX_REF_DEPTH);
+    }
+}
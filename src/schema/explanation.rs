@@ -0,0 +1,133 @@
+//! A generated rationale property kept off the domain type.
+//!
+//! Models extract more accurately when they can reason before answering, and
+//! in structured output the only place to do that is inside the JSON.
+//! [`Schema::with_explanation`] prepends an [`EXPLANATION_FIELD`] string
+//! property for that reasoning; the field is removed from the reply before
+//! deserialization and surfaced as
+//! [`MaterializeResult::explanation`](crate::MaterializeResult::explanation).
+
+use serde_json::{Value, json};
+
+use super::Schema;
+
+/// Name of the rationale property added by [`Schema::with_explanation`].
+pub const EXPLANATION_FIELD: &str = "_reasoning";
+
+impl Schema {
+    /// This schema with a required [`EXPLANATION_FIELD`] string property
+    /// placed first, so the model writes its rationale before the answer.
+    ///
+    /// Schemas whose root is not an object with `properties`, or that already
+    /// declare the field, are returned unchanged.
+    ///
+    /// ```
+    /// use rstructor::{EXPLANATION_FIELD, Schema};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::new(json!({
+    ///     "type": "object",
+    ///     "properties": { "total": { "type": "number" } },
+    ///     "required": ["total"]
+    /// }))
+    /// .with_explanation();
+    /// let json = schema.to_json();
+    /// assert_eq!(json["properties"].as_object().unwrap().keys().next().unwrap(), EXPLANATION_FIELD);
+    /// assert_eq!(json["required"], json!([EXPLANATION_FIELD, "total"]));
+    /// ```
+    #[must_use]
+    pub fn with_explanation(&self) -> Schema {
+        let mut schema = self.schema.clone();
+        let Some(root) = schema.as_object_mut() else {
+            return self.clone();
+        };
+        let Some(properties) = root.get("properties").and_then(Value::as_object) else {
+            return self.clone();
+        };
+        if properties.contains_key(EXPLANATION_FIELD) {
+            return self.clone();
+        }
+
+        let mut with_field = serde_json::Map::with_capacity(properties.len() + 1);
+        with_field.insert(
+            EXPLANATION_FIELD.to_string(),
+            json!({
+                "type": "string",
+                "description": "Brief reasoning for the answer, written before the other fields"
+            }),
+        );
+        with_field.extend(properties.clone());
+        root.insert("properties".to_string(), Value::Object(with_field));
+
+        let mut required = vec![json!(EXPLANATION_FIELD)];
+        if let Some(Value::Array(existing)) = root.get("required") {
+            required.extend(existing.iter().cloned());
+        }
+        root.insert("required".to_string(), Value::Array(required));
+        Schema::new(schema)
+    }
+
+    #[cfg(any(feature = "_client", feature = "mock"))]
+    fn declares_explanation(&self) -> bool {
+        self.schema
+            .get("properties")
+            .and_then(Value::as_object)
+            .is_some_and(|properties| properties.contains_key(EXPLANATION_FIELD))
+    }
+}
+
+/// Split the rationale out of a raw model reply (which may be wrapped in a
+/// markdown fence): the reply's JSON without the field, and the field's text.
+///
+/// `None` if the reply holds no such field, or if `schema` declares it (then
+/// it belongs to the domain type).
+#[cfg(any(feature = "_client", feature = "mock"))]
+pub(crate) fn split_explanation(schema: &Schema, reply: &str) -> Option<(String, String)> {
+    let json = crate::parsing::extract_json_from_markdown(reply);
+    if !json.contains(EXPLANATION_FIELD) || schema.declares_explanation() {
+        return None;
+    }
+    let mut value: Value = serde_json::from_str(json).ok()?;
+    let explanation = match value.as_object_mut()?.remove(EXPLANATION_FIELD)? {
+        Value::String(text) => text,
+        other => other.to_string(),
+    };
+    Some((value.to_string(), explanation))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn non_object_and_declaring_schemas_are_unchanged() {
+        let list = Schema::new(json!({ "type": "array", "items": { "type": "string" } }));
+        assert_eq!(list.with_explanation().to_json(), list.to_json());
+
+        let declared = Schema::new(json!({
+            "type": "object",
+            "properties": { "_reasoning": { "type": "integer" } }
+        }));
+        assert_eq!(declared.with_explanation().to_json(), declared.to_json());
+    }
+
+    #[cfg(any(feature = "_client", feature = "mock"))]
+    #[test]
+    fn splits_explanation_from_fenced_reply() {
+        let schema = Schema::new(json!({
+            "type": "object",
+            "properties": { "total": { "type": "number" } }
+        }));
+        let reply = "```json\n{\"_reasoning\": \"Sum of lines\", \"total\": 12.5}\n```";
+        let (json, explanation) = split_explanation(&schema, reply).unwrap();
+        assert_eq!(explanation, "Sum of lines");
+        assert_eq!(json, r#"{"total":12.5}"#);
+        assert_eq!(split_explanation(&schema, r#"{"total": 1}"#), None);
+
+        let declared = Schema::new(json!({
+            "type": "object",
+            "properties": { "_reasoning": { "type": "integer" } }
+        }));
+        assert_eq!(split_explanation(&declared, r#"{"_reasoning": 3}"#), None);
+    }
+}
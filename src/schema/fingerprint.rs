@@ -0,0 +1,131 @@
+//! Fingerprints that tell schema changes apart by cause.
+//!
+//! A schema snapshot or cache keyed by a hash of the schema can't tell
+//! whether a type changed or the crate started generating different JSON for
+//! the same type. [`Schema::fingerprint`] pairs the hash with
+//! [`SCHEMA_GENERATION_VERSION`], which is bumped in any release that changes
+//! the derived (or built-in) schema of an unchanged type, and
+//! [`SCHEMA_GENERATION_CHANGES`] lists what each bump changed.
+
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use super::Schema;
+
+/// Version of the schema generator: bumped whenever `#[derive(Instructor)]`
+/// or a built-in [`SchemaType`](super::SchemaType) impl emits a different
+/// schema for an unchanged type.
+pub const SCHEMA_GENERATION_VERSION: u32 = 2;
+
+/// What changed in each [`SCHEMA_GENERATION_VERSION`], oldest first.
+///
+/// The last entry's version is always [`SCHEMA_GENERATION_VERSION`].
+pub const SCHEMA_GENERATION_CHANGES: &[(u32, &str)] = &[
+    (
+        1,
+        "First tracked version: 2020-12 keywords, shared and recursive types under `$defs`",
+    ),
+    (
+        2,
+        "`Option` array items, map values and tuple elements admit `null`",
+    ),
+];
+
+/// A schema's content hash and the generator version that produced it,
+/// from [`Schema::fingerprint`].
+///
+/// Displays as `v{generation}:{hash}`, e.g. `v1:af63dc4c8601ec8c`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SchemaFingerprint {
+    /// [`SCHEMA_GENERATION_VERSION`] of the crate that computed it.
+    pub generation: u32,
+    /// FNV-1a hash of the [canonical](Schema::to_canonical_string) schema,
+    /// as 16 hex digits.
+    pub hash: String,
+}
+
+impl SchemaFingerprint {
+    /// Whether the schema is the same, ignoring the generator version.
+    pub fn same_schema(&self, other: &Self) -> bool {
+        self.hash == other.hash
+    }
+
+    /// Whether it was computed by the running crate's generator, i.e.
+    /// a mismatch against a fresh fingerprint comes from the type itself.
+    pub fn is_current_generation(&self) -> bool {
+        self.generation == SCHEMA_GENERATION_VERSION
+    }
+}
+
+impl Display for SchemaFingerprint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "v{}:{}", self.generation, self.hash)
+    }
+}
+
+impl Schema {
+    /// Fingerprint the schema for caches and snapshot tests.
+    ///
+    /// The hash covers the canonical form, so it is stable across processes,
+    /// releases and property reorders; the generation says which generator
+    /// version produced the schema.
+    ///
+    /// ```
+    /// use rstructor::{SCHEMA_GENERATION_VERSION, Schema};
+    /// use serde_json::json;
+    ///
+    /// let fingerprint = Schema::new(json!({ "type": "string" })).fingerprint();
+    /// assert_eq!(fingerprint.generation, SCHEMA_GENERATION_VERSION);
+    /// assert!(fingerprint.to_string().starts_with("v2:"));
+    /// ```
+    pub fn fingerprint(&self) -> SchemaFingerprint {
+        SchemaFingerprint {
+            generation: SCHEMA_GENERATION_VERSION,
+            hash: fnv1a(&self.to_canonical_string()),
+        }
+    }
+}
+
+/// FNV-1a over `text`: stable across processes and releases, unlike `std`'s
+/// hasher.
+fn fnv1a(text: &str) -> String {
+    let hash = text.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    format!("{hash:016x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn hash_is_stable() {
+        assert_eq!(fnv1a(""), "cbf29ce484222325");
+        assert_eq!(fnv1a("a"), "af63dc4c8601ec8c");
+    }
+
+    #[test]
+    fn changelog_ends_at_the_current_version() {
+        assert_eq!(
+            SCHEMA_GENERATION_CHANGES.last().map(|(v, _)| *v),
+            Some(SCHEMA_GENERATION_VERSION)
+        );
+    }
+
+    #[test]
+    fn generator_bumps_are_told_apart_from_schema_changes() {
+        let schema = Schema::new(json!({ "type": "object", "properties": { "a": {}, "b": {} } }));
+        let current = schema.fingerprint();
+        let reordered =
+            Schema::new(json!({ "properties": { "b": {}, "a": {} }, "type": "object" }));
+        assert_eq!(current, reordered.fingerprint());
+
+        let stored = SchemaFingerprint {
+            generation: SCHEMA_GENERATION_VERSION - 1,
+            ..current.clone()
+        };
+        assert!(stored.same_schema(&current) && !stored.is_current_generation());
+        assert_ne!(stored, current);
+    }
+}
@@ -0,0 +1,290 @@
+//! Schema descriptions in more than one language.
+//!
+//! Extraction works best when the schema's descriptions are in the language
+//! of the document. `#[llm(description(en = "..", de = ".."))]` puts the first
+//! text in the schema and records the others under a
+//! [`TRANSLATIONS_KEY`] extension keyword, keyed by that text.
+//! [`Schema::localized`] swaps the translations in; a [`DescriptionCatalog`]
+//! supplies them from outside the code, e.g. from a JSON file maintained by
+//! translators. Backends strip the extension keyword before sending a schema.
+
+use std::collections::HashMap;
+
+use serde_json::{Map, Value};
+
+use super::Schema;
+use crate::error::{RStructorError, Result};
+
+/// Extension keyword holding a derived schema's translated descriptions, as
+/// `{locale: {default text: translation}}`.
+pub(crate) const TRANSLATIONS_KEY: &str = "x-descriptions";
+
+/// Keywords whose values are data rather than subschemas, left untouched.
+const DATA_KEYWORDS: [&str; 5] = ["const", "default", "enum", "examples", TRANSLATIONS_KEY];
+
+/// Keywords mapping names to subschemas, whose keys are not keywords.
+const SCHEMA_MAPS: [&str; 5] = [
+    "properties",
+    "patternProperties",
+    "$defs",
+    "definitions",
+    "dependentSchemas",
+];
+
+/// Translated schema descriptions per locale, keyed by the default text.
+///
+/// ```
+/// use rstructor::{DescriptionCatalog, Schema};
+/// use serde_json::json;
+///
+/// let catalog = DescriptionCatalog::from_json(&json!({
+///     "de": { "Total amount due": "Fälliger Gesamtbetrag" }
+/// }))
+/// .unwrap();
+/// let schema = Schema::new(json!({
+///     "type": "object",
+///     "properties": {
+///         "total": { "type": "number", "description": "Total amount due" }
+///     }
+/// }));
+///
+/// let german = schema.localized_with("de-AT", &catalog);
+/// assert_eq!(german.to_json()["properties"]["total"]["description"], "Fälliger Gesamtbetrag");
+/// assert_eq!(schema.localized_with("fr", &catalog).to_json(), schema.to_json());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DescriptionCatalog {
+    locales: HashMap<String, HashMap<String, String>>,
+}
+
+impl DescriptionCatalog {
+    /// An empty catalog.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the `locale` translation of the description `source`.
+    #[must_use]
+    pub fn translation(
+        mut self,
+        locale: impl Into<String>,
+        source: impl Into<String>,
+        translation: impl Into<String>,
+    ) -> Self {
+        self.insert(locale, source, translation);
+        self
+    }
+
+    /// Add the `locale` translation of the description `source`, replacing
+    /// any earlier one.
+    pub fn insert(
+        &mut self,
+        locale: impl Into<String>,
+        source: impl Into<String>,
+        translation: impl Into<String>,
+    ) {
+        self.locales
+            .entry(locale.into())
+            .or_default()
+            .insert(source.into(), translation.into());
+    }
+
+    /// Read a catalog of the form `{"de": {"default text": "Übersetzung"}}`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RStructorError::SchemaError`] if `value` is not an object of
+    /// objects of strings.
+    pub fn from_json(value: &Value) -> Result<Self> {
+        let invalid = || {
+            RStructorError::SchemaError(
+                "a description catalog must map locales to objects of default text to translation"
+                    .to_string(),
+            )
+        };
+        let mut catalog = Self::new();
+        for (locale, entries) in value.as_object().ok_or_else(invalid)? {
+            for (source, translation) in entries.as_object().ok_or_else(invalid)? {
+                catalog.insert(locale, source, translation.as_str().ok_or_else(invalid)?);
+            }
+        }
+        Ok(catalog)
+    }
+
+    /// Add every translation in `other`, replacing this catalog's on conflict.
+    pub fn extend(&mut self, other: &DescriptionCatalog) {
+        for (locale, entries) in &other.locales {
+            for (source, translation) in entries {
+                self.insert(locale, source, translation);
+            }
+        }
+    }
+
+    /// The `locale` translation of `source`. A regional locale such as
+    /// `de-AT` falls back to its language, `de`.
+    pub fn get(&self, locale: &str, source: &str) -> Option<&str> {
+        self.entries(locale)?.get(source).map(String::as_str)
+    }
+
+    /// Locales with at least one translation.
+    pub fn locales(&self) -> impl Iterator<Item = &str> {
+        self.locales.keys().map(String::as_str)
+    }
+
+    /// Whether there are no translations.
+    pub fn is_empty(&self) -> bool {
+        self.locales.values().all(HashMap::is_empty)
+    }
+
+    fn entries(&self, locale: &str) -> Option<&HashMap<String, String>> {
+        let locale = locale.replace('_', "-");
+        self.locales.get(&locale).or_else(|| {
+            let (language, _) = locale.split_once('-')?;
+            self.locales.get(language)
+        })
+    }
+}
+
+impl Schema {
+    /// This schema with its descriptions in `locale`, using the translations
+    /// recorded by `#[llm(description(..))]`. Descriptions without a
+    /// translation keep their default text, and the recorded translations
+    /// are dropped from the result.
+    ///
+    /// ```
+    /// use rstructor::{Instructor, SchemaType};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Instructor, Serialize, Deserialize)]
+    /// #[llm(description(en = "An invoice", de = "Eine Rechnung"))]
+    /// struct Invoice {
+    ///     #[llm(description(en = "Total amount due", de = "Fälliger Gesamtbetrag"))]
+    ///     total: f64,
+    /// }
+    ///
+    /// let german = Invoice::schema().localized("de").to_json();
+    /// assert_eq!(german["description"], "Eine Rechnung");
+    /// assert_eq!(german["properties"]["total"]["description"], "Fälliger Gesamtbetrag");
+    /// ```
+    #[must_use]
+    pub fn localized(&self, locale: &str) -> Schema {
+        self.localized_with(locale, &DescriptionCatalog::new())
+    }
+
+    /// Like [`localized`](Self::localized), with `catalog` consulted first.
+    #[must_use]
+    pub fn localized_with(&self, locale: &str, catalog: &DescriptionCatalog) -> Schema {
+        let mut merged = self.translations();
+        merged.extend(catalog);
+        let mut schema = self.schema.clone();
+        if let Some(entries) = merged.entries(locale) {
+            walk_descriptions(&mut schema, &mut |description| {
+                if let Some(translation) = translate(entries, description) {
+                    *description = translation;
+                }
+            });
+        }
+        strip_translations(&mut schema);
+        Schema::new(schema)
+    }
+
+    /// The translations recorded in this schema by
+    /// `#[llm(description(..))]`, including those of nested types.
+    pub fn translations(&self) -> DescriptionCatalog {
+        let mut catalog = DescriptionCatalog::new();
+        collect_translations(&self.schema, &mut catalog);
+        catalog
+    }
+}
+
+/// Called by `#[derive(Instructor)]` to record `(locale, default text,
+/// translation)` entries on a built schema.
+pub(crate) fn with_translations(schema: Schema, translations: &[(&str, &str, &str)]) -> Schema {
+    let mut json = schema.to_json();
+    let Some(root) = json.as_object_mut() else {
+        return schema;
+    };
+    let recorded = root
+        .entry(TRANSLATIONS_KEY)
+        .or_insert_with(|| Value::Object(Map::new()));
+    let Some(recorded) = recorded.as_object_mut() else {
+        return schema;
+    };
+    for (locale, source, translation) in translations {
+        if let Some(entries) = recorded
+            .entry(*locale)
+            .or_insert_with(|| Value::Object(Map::new()))
+            .as_object_mut()
+        {
+            entries.insert(source.to_string(), Value::from(*translation));
+        }
+    }
+    Schema::new(json)
+}
+
+/// Remove every recorded translation from `schema`.
+pub(crate) fn strip_translations(schema: &mut Value) {
+    visit_schemas(schema, &mut |object| {
+        object.remove(TRANSLATIONS_KEY);
+    });
+}
+
+/// The translation of a description: the whole text, or the leading default
+/// text when the derive appended a hint to it (`"Text. Keys: .."`).
+fn translate(entries: &HashMap<String, String>, description: &str) -> Option<String> {
+    if let Some(translation) = entries.get(description) {
+        return Some(translation.clone());
+    }
+    let (source, rest) = description.split_once(". ")?;
+    entries
+        .get(source)
+        .map(|translation| format!("{translation}. {rest}"))
+}
+
+fn collect_translations(schema: &Value, catalog: &mut DescriptionCatalog) {
+    let mut schema = schema.clone();
+    visit_schemas(&mut schema, &mut |object| {
+        if let Some(recorded) = object.get(TRANSLATIONS_KEY)
+            && let Ok(recorded) = DescriptionCatalog::from_json(recorded)
+        {
+            catalog.extend(&recorded);
+        }
+    });
+}
+
+fn walk_descriptions(schema: &mut Value, f: &mut impl FnMut(&mut String)) {
+    visit_schemas(schema, &mut |object| {
+        if let Some(Value::String(description)) = object.get_mut("description") {
+            f(description);
+        }
+    });
+}
+
+/// Call `f` on every schema object in `schema`, outermost first.
+fn visit_schemas(schema: &mut Value, f: &mut impl FnMut(&mut Map<String, Value>)) {
+    match schema {
+        Value::Object(object) => {
+            f(object);
+            for (keyword, value) in object.iter_mut() {
+                if DATA_KEYWORDS.contains(&keyword.as_str()) {
+                    continue;
+                }
+                if SCHEMA_MAPS.contains(&keyword.as_str())
+                    && let Value::Object(subschemas) = value
+                {
+                    for subschema in subschemas.values_mut() {
+                        visit_schemas(subschema, f);
+                    }
+                } else {
+                    visit_schemas(value, f);
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                visit_schemas(item, f);
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,462 @@
+//! Rendering schemas as Markdown reference documentation.
+//!
+//! Product docs and data contracts tend to drift from the types they describe.
+//! [`Schema::to_markdown`] renders them from the same schema the model is given,
+//! so documentation can be regenerated (or snapshot-tested) on every build.
+
+use std::collections::{HashSet, VecDeque};
+
+use serde_json::Value;
+
+use super::Schema;
+
+/// Nesting depth beyond which type labels stop descending (guards recursive `$ref`s).
+const MAX_DEPTH: usize = 32;
+
+impl Schema {
+    /// Render this schema as Markdown: a heading and description, then a table
+    /// of fields with their type, whether they are required, description,
+    /// examples and constraints.
+    ///
+    /// Nested objects (inline, behind `$ref`, inside arrays, maps or enum
+    /// variants) get their own section, linked from the type column. Sections
+    /// are named after the nested schema's `title` and rendered once even when
+    /// the type appears in several places.
+    ///
+    /// ```
+    /// use rstructor::Schema;
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::new(json!({
+    ///     "title": "Person",
+    ///     "type": "object",
+    ///     "properties": {
+    ///         "name": { "type": "string", "description": "Full name", "examples": ["Ada"] },
+    ///         "age": { "type": "integer", "minimum": 0 }
+    ///     },
+    ///     "required": ["name"]
+    /// }));
+    /// let markdown = schema.to_markdown();
+    /// assert!(markdown.starts_with("# Person\n"));
+    /// assert!(markdown.contains("| `name` | string | yes | Full name | `\"Ada\"` |  |"));
+    /// assert!(markdown.contains("| `age` | integer | no |  |  | minimum 0 |"));
+    /// ```
+    pub fn to_markdown(&self) -> String {
+        let root = &self.schema;
+        let mut renderer = Renderer {
+            root,
+            queue: VecDeque::new(),
+            seen: HashSet::new(),
+        };
+        let title = root
+            .get("title")
+            .and_then(Value::as_str)
+            .unwrap_or("Schema")
+            .to_string();
+        renderer.seen.insert(title.clone());
+
+        let mut out = String::new();
+        renderer.section(&mut out, 1, &title, root);
+        while let Some((heading, schema)) = renderer.queue.pop_front() {
+            out.push('\n');
+            renderer.section(&mut out, 2, &heading, schema);
+        }
+        out
+    }
+}
+
+struct Renderer<'a> {
+    root: &'a Value,
+    /// Nested object sections still to render, in discovery order.
+    queue: VecDeque<(String, &'a Value)>,
+    /// Headings already rendered or queued.
+    seen: HashSet<String>,
+}
+
+impl<'a> Renderer<'a> {
+    fn section(&mut self, out: &mut String, level: usize, heading: &str, schema: &'a Value) {
+        let schema = self.resolve(schema);
+        out.push_str(&format!("{} {}\n", "#".repeat(level), heading));
+        if let Some(description) = schema.get("description").and_then(Value::as_str) {
+            out.push_str(&format!("\n{}\n", description.trim()));
+        }
+
+        let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+            // Not an object: describe the value itself.
+            let label = self.type_label(schema, heading, 0);
+            out.push_str(&format!("\n**Type:** {}\n", label));
+            let constraints = constraints(schema);
+            if !constraints.is_empty() {
+                out.push_str(&format!("\n**Constraints:** {}\n", constraints));
+            }
+            return;
+        };
+        let required: HashSet<&str> = schema
+            .get("required")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .collect();
+
+        out.push_str("\n| Field | Type | Required | Description | Examples | Constraints |\n");
+        out.push_str("|---|---|---|---|---|---|\n");
+        for (name, property) in properties {
+            let path = format!("{heading}.{name}");
+            let label = self.type_label(property, &path, 0);
+            // Keywords written next to a `$ref` describe this field and take
+            // precedence over the shared definition's.
+            let target = self.resolve(property);
+            let either = |read: fn(&Value) -> String| {
+                let own = read(property);
+                if own.is_empty() { read(target) } else { own }
+            };
+            let row = [
+                format!("`{}`", name),
+                label,
+                if required.contains(name.as_str()) {
+                    "yes"
+                } else {
+                    "no"
+                }
+                .to_string(),
+                either(description),
+                either(examples),
+                either(constraints),
+            ];
+            let cells: Vec<String> = row.iter().map(|cell| escape_cell(cell)).collect();
+            out.push_str(&format!("| {} |\n", cells.join(" | ")));
+        }
+    }
+
+    /// Follow a local `$ref` (`#/...`) to its target.
+    fn resolve(&self, schema: &'a Value) -> &'a Value {
+        let mut schema = schema;
+        for _ in 0..MAX_DEPTH {
+            match schema
+                .get("$ref")
+                .and_then(Value::as_str)
+                .and_then(|r| r.strip_prefix('#'))
+                .and_then(|pointer| self.root.pointer(pointer))
+            {
+                Some(target) => schema = target,
+                None => break,
+            }
+        }
+        schema
+    }
+
+    /// A short type description, queueing a section for any nested object.
+    /// `path` names the section when the object has no title.
+    fn type_label(&mut self, schema: &'a Value, path: &str, depth: usize) -> String {
+        if depth > MAX_DEPTH {
+            return "any".to_string();
+        }
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let name = reference.rsplit('/').next().unwrap_or(reference);
+            return self.type_label(self.resolve(schema), name, depth + 1);
+        }
+        // A described enum is one type with listed values, not a union
+        let described_enum = const_branches(schema);
+        for keyword in ["anyOf", "oneOf"] {
+            if described_enum.is_some() {
+                break;
+            }
+            if let Some(branches) = schema.get(keyword).and_then(Value::as_array) {
+                return branches
+                    .iter()
+                    .enumerate()
+                    .map(|(i, branch)| {
+                        self.type_label(branch, &format!("{path} (option {})", i + 1), depth + 1)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" or ");
+            }
+        }
+        match schema.get("type") {
+            Some(Value::Array(types)) => types
+                .iter()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>()
+                .join(" or "),
+            Some(Value::String(t)) if t == "array" => match schema.get("items") {
+                Some(items) => format!(
+                    "array of {}",
+                    self.type_label(items, &format!("{path}[]"), depth + 1)
+                ),
+                None => "array".to_string(),
+            },
+            Some(Value::String(t)) if t == "object" => {
+                if schema.get("properties").is_some() {
+                    let heading = schema
+                        .get("title")
+                        .and_then(Value::as_str)
+                        .unwrap_or(path)
+                        .to_string();
+                    if self.seen.insert(heading.clone()) {
+                        self.queue.push_back((heading.clone(), schema));
+                    }
+                    format!("[{}](#{})", heading, anchor(&heading))
+                } else if let Some(values) =
+                    schema.get("additionalProperties").filter(|v| v.is_object())
+                {
+                    format!(
+                        "map of {}",
+                        self.type_label(values, &format!("{path}{{}}"), depth + 1)
+                    )
+                } else {
+                    "object".to_string()
+                }
+            }
+            Some(Value::String(t)) => t.clone(),
+            _ if schema.get("const").is_some() => "const".to_string(),
+            _ if described_enum.is_some() => "enum".to_string(),
+            _ => "any".to_string(),
+        }
+    }
+}
+
+/// The schema's `description`, or an empty string.
+fn description(schema: &Value) -> String {
+    schema
+        .get("description")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Examples from `examples` and `example`, as inline JSON code.
+fn examples(schema: &Value) -> String {
+    let values: Vec<&Value> = match (schema.get("examples"), schema.get("example")) {
+        (Some(Value::Array(values)), _) => values.iter().collect(),
+        (_, Some(value)) => vec![value],
+        _ => Vec::new(),
+    };
+    values
+        .iter()
+        .map(|v| format!("`{}`", v))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Validation keywords in plain words, e.g. `minimum 0, format: email`.
+fn constraints(schema: &Value) -> String {
+    let mut parts = Vec::new();
+    let number = |key: &str| schema.get(key).filter(|v| v.is_number());
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        let values: Vec<String> = values.iter().map(|v| format!("`{}`", v)).collect();
+        parts.push(format!("one of {}", values.join(", ")));
+    }
+    if let Some(values) = const_branches(schema) {
+        let values: Vec<String> = values
+            .iter()
+            .map(|(value, description)| match description {
+                Some(description) => format!("`{}` ({})", value, description),
+                None => format!("`{}`", value),
+            })
+            .collect();
+        parts.push(format!("one of {}", values.join(", ")));
+    }
+    if let Some(value) = schema.get("const") {
+        parts.push(format!("always `{}`", value));
+    }
+    if let Some(format) = schema.get("format").and_then(Value::as_str) {
+        parts.push(format!("format: {}", format));
+    }
+    for (key, label) in [
+        ("minimum", "minimum"),
+        ("maximum", "maximum"),
+        ("exclusiveMinimum", "greater than"),
+        ("exclusiveMaximum", "less than"),
+        ("multipleOf", "multiple of"),
+        ("minLength", "min length"),
+        ("maxLength", "max length"),
+        ("minItems", "min items"),
+        ("maxItems", "max items"),
+        ("minProperties", "min entries"),
+        ("maxProperties", "max entries"),
+    ] {
+        if let Some(value) = number(key) {
+            parts.push(format!("{} {}", label, value));
+        }
+    }
+    if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+        parts.push(format!("pattern `{}`", pattern));
+    }
+    if schema.get("uniqueItems") == Some(&Value::Bool(true)) {
+        parts.push("unique items".to_string());
+    }
+    if let Some(items) = schema.get("items") {
+        let item_constraints = constraints(items);
+        if !item_constraints.is_empty() {
+            parts.push(format!("each item: {}", item_constraints));
+        }
+    }
+    if let Some(default) = schema.get("default") {
+        parts.push(format!("default `{}`", default));
+    }
+    parts.join(", ")
+}
+
+/// The values of an enum written as a `oneOf`/`anyOf` of `const` branches (how
+/// variant descriptions are emitted), each with its description. A `null`
+/// branch is allowed alongside them.
+fn const_branches(schema: &Value) -> Option<Vec<(&Value, Option<&str>)>> {
+    let branches = ["oneOf", "anyOf"]
+        .iter()
+        .find_map(|key| schema.get(*key).and_then(Value::as_array))?;
+    let mut values = Vec::new();
+    for branch in branches {
+        match branch.get("const") {
+            Some(value) => values.push((value, branch.get("description").and_then(Value::as_str))),
+            None if branch.get("type").and_then(Value::as_str) == Some("null") => {}
+            None => return None,
+        }
+    }
+    (!values.is_empty()).then_some(values)
+}
+
+/// Make `text` safe inside a table cell.
+fn escape_cell(text: &str) -> String {
+    text.trim()
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
+
+/// The anchor GitHub-flavoured Markdown generates for a heading.
+fn anchor(heading: &str) -> String {
+    heading
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn nested_objects_get_one_linked_section_each() {
+        let address = json!({
+            "type": "object",
+            "title": "Address",
+            "properties": { "city": { "type": "string", "example": "London" } },
+            "required": ["city"]
+        });
+        let schema = Schema::new(json!({
+            "type": "object",
+            "title": "Customer",
+            "description": "A customer record",
+            "properties": {
+                "home": address,
+                "previous": { "type": "array", "items": address, "maxItems": 5 },
+                "status": { "type": "string", "enum": ["Active", "Inactive"] },
+                "scores": { "type": "object", "additionalProperties": { "type": "integer" } }
+            },
+            "required": ["home", "status"]
+        }));
+        let markdown = schema.to_markdown();
+        assert_eq!(
+            markdown,
+            "# Customer\n\
+             \n\
+             A customer record\n\
+             \n\
+             | Field | Type | Required | Description | Examples | Constraints |\n\
+             |---|---|---|---|---|---|\n\
+             | `home` | [Address](#address) | yes |  |  |  |\n\
+             | `previous` | array of [Address](#address) | no |  |  | max items 5 |\n\
+             | `status` | string | yes |  |  | one of `\"Active\"`, `\"Inactive\"` |\n\
+             | `scores` | map of integer | no |  |  |  |\n\
+             \n\
+             ## Address\n\
+             \n\
+             | Field | Type | Required | Description | Examples | Constraints |\n\
+             |---|---|---|---|---|---|\n\
+             | `city` | string | yes |  | `\"London\"` |  |\n"
+        );
+    }
+
+    #[test]
+    fn refs_unions_and_untitled_objects_are_followed() {
+        let schema = Schema::new(json!({
+            "type": "object",
+            "title": "Node",
+            "properties": {
+                "child": { "anyOf": [{ "$ref": "#" }, { "type": "null" }] },
+                "meta": { "type": "object", "properties": { "note": { "type": ["string", "null"] } } }
+            }
+        }));
+        let markdown = schema.to_markdown();
+        assert!(markdown.contains("| `child` | [Node](#node) or null | no |"));
+        assert!(markdown.contains("| `meta` | [Node.meta](#nodemeta) | no |"));
+        assert!(markdown.contains("## Node.meta\n"));
+        assert!(markdown.contains("| `note` | string or null | no |"));
+        // The root is not rendered a second time for the self-reference.
+        assert_eq!(markdown.matches("# Node\n").count(), 1);
+    }
+
+    #[test]
+    fn field_keywords_next_to_a_ref_take_precedence() {
+        let schema = Schema::new(json!({
+            "type": "object",
+            "title": "Customer",
+            "properties": {
+                "home": { "$ref": "#/$defs/Addr", "description": "Home address" },
+                "work": { "$ref": "#/$defs/Addr" }
+            },
+            "$defs": {
+                "Addr": {
+                    "type": "object",
+                    "description": "A postal address",
+                    "properties": { "city": { "type": "string" } }
+                }
+            }
+        }));
+        let markdown = schema.to_markdown();
+        assert!(markdown.contains("| `home` | [Addr](#addr) | no | Home address |  |  |"));
+        assert!(markdown.contains("| `work` | [Addr](#addr) | no | A postal address |  |  |"));
+    }
+
+    #[test]
+    fn described_enums_list_their_values() {
+        let schema = Schema::new(json!({
+            "type": "object",
+            "properties": {
+                "color": {
+                    "type": "string",
+                    "oneOf": [
+                        { "const": "Red", "description": "Warm" },
+                        { "const": "Blue" }
+                    ]
+                }
+            },
+            "required": ["color"]
+        }));
+        let markdown = schema.to_markdown();
+        assert!(
+            markdown
+                .contains("| `color` | string | yes |  |  | one of `\"Red\"` (Warm), `\"Blue\"` |")
+        );
+    }
+
+    #[test]
+    fn cells_escape_pipes_and_newlines() {
+        let schema = Schema::new(json!({
+            "type": "object",
+            "properties": {
+                "a": { "type": "string", "description": "x | y\nz", "pattern": "^(a|b)$" }
+            }
+        }));
+        let markdown = schema.to_markdown();
+        assert!(markdown.contains(r"| x \| y<br>z |"));
+        assert!(markdown.contains(r"pattern `^(a\|b)$`"));
+    }
+}
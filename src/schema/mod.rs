@@ -1,8 +1,43 @@
 mod builder;
+mod coerce;
 mod custom_type;
+mod draft;
+mod example;
+mod explanation;
+mod fingerprint;
+mod locale;
+mod markdown;
+mod names;
+mod object_root;
+#[cfg(feature = "_client")]
+pub(crate) use example::COUNTER_EXAMPLES_KEY;
 mod primitives;
+mod recursion;
+mod refs;
+pub(crate) use refs::share_definitions;
+#[cfg(feature = "registry")]
+mod registry;
+mod strict;
+mod unknown;
 pub use builder::SchemaBuilder;
 pub use custom_type::CustomTypeSchema;
+pub use draft::SchemaDraft;
+pub use explanation::EXPLANATION_FIELD;
+#[cfg(any(feature = "_client", feature = "mock"))]
+pub(crate) use explanation::split_explanation;
+pub use fingerprint::{SCHEMA_GENERATION_CHANGES, SCHEMA_GENERATION_VERSION, SchemaFingerprint};
+pub use locale::DescriptionCatalog;
+#[cfg(feature = "_client")]
+pub(crate) use locale::strip_translations;
+pub use names::{PropertyNameIssue, PropertyRenames};
+#[cfg(any(feature = "_client", feature = "mock"))]
+pub(crate) use object_root::{unwrap_object_root, unwrapped_root_schema};
+#[cfg(feature = "registry")]
+pub use registry::{RegisteredSchema, SchemaRegistry};
+#[cfg(feature = "_client")]
+pub(crate) use strict::make_schema_nullable;
+#[cfg(any(feature = "_client", feature = "mock"))]
+pub(crate) use unknown::unknown_fields_in_reply;
 
 use crate::error::Result;
 use serde_json::Value;
@@ -82,28 +117,26 @@ impl Schema {
         Self { schema }
     }
 
-    /// Return a reference to the raw unenhanced schema
+    /// Return a reference to the underlying JSON schema
     ///
     /// This method exists for backward compatibility with code expecting a reference.
-    /// Most internal code should use to_enhanced_json() instead.
     pub fn original_schema(&self) -> &Value {
         &self.schema
     }
 
     /// Get the JSON representation of this schema
     ///
-    /// Returns the schema as-is without enhancement to prevent stack overflow
-    /// with complex nested structures. The derive macro should generate complete
-    /// schemas that don't need runtime enhancement.
+    /// Returns the schema as-is. Derived schemas are complete at derive time:
+    /// nested struct fields and collection items embed the field type's own
+    /// [`SchemaType::schema`], so nothing is filled in at runtime. Recursive
+    /// and repeated nested types are embedded as `$ref`s into the root
+    /// `$defs`; see [`inline`](Self::inline) to expand them.
     pub fn to_json(&self) -> Value {
-        // Return schema directly without enhancement to prevent stack overflow
-        // The derive macro should generate complete schemas
         self.schema.clone()
     }
 
     // Format the schema as a pretty-printed JSON string
     pub fn to_pretty_json(&self) -> String {
-        // Get the schema with array enhancements
         let schema_json = self.to_json();
         // CRITICAL: Use serde_json directly to avoid recursion - never call self.schema.to_string()
         // which would use Display impl and cause infinite recursion
@@ -112,12 +145,53 @@ impl Schema {
         })
     }
 
+    /// Render the schema in canonical form: object keys sorted at every level,
+    /// two-space indentation, no trailing newline.
+    ///
+    /// Property order in [`to_json`](Self::to_json) follows declaration order
+    /// (and `#[llm(order = n)]`) because that is what models see. The canonical
+    /// form ignores ordering, so it stays byte-identical across field reorders
+    /// and is suitable for hashing, snapshot tests, and diffs. Array order
+    /// (e.g. `required`, `enum`) is preserved.
+    ///
+    /// ```
+    /// use rstructor::Schema;
+    /// use serde_json::json;
+    ///
+    /// let a = Schema::new(json!({ "type": "object", "properties": { "b": {}, "a": {} } }));
+    /// let b = Schema::new(json!({ "properties": { "a": {}, "b": {} }, "type": "object" }));
+    /// assert_eq!(a.to_canonical_string(), b.to_canonical_string());
+    /// assert!(a.to_canonical_string().starts_with("{\n  \"properties\""));
+    /// ```
+    pub fn to_canonical_string(&self) -> String {
+        serde_json::to_string_pretty(&canonicalize(&self.schema))
+            .expect("serializing a JSON value cannot fail")
+    }
+
     /// Create a schema builder for an object type
     pub fn builder() -> SchemaBuilder {
         SchemaBuilder::object()
     }
 }
 
+/// `value` with the keys of every object sorted.
+fn canonicalize(value: &Value) -> Value {
+    match value {
+        Value::Object(obj) => {
+            let mut entries: Vec<_> = obj.iter().collect();
+            entries.sort_unstable_by_key(|(k, _)| *k);
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k.clone(), canonicalize(v)))
+                    .collect(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.iter().map(canonicalize).collect()),
+        other => other.clone(),
+    }
+}
+
 // Display implementation for Schema
 // NOTE: This can cause stack overflow with very complex schemas.
 // Prefer using serde_json::to_string_pretty(&schema.to_json()) directly
@@ -212,12 +286,116 @@ pub trait SchemaType {
     fn schema_name() -> Option<String> {
         None
     }
+
+    /// A complete example instance of this type, if the schema provides one.
+    ///
+    /// Assembled from the schema's `example`/`examples` keywords (for derived
+    /// types, the `#[llm(example = ...)]` and `#[llm(examples = [...])]`
+    /// attributes): a container-level example is used as-is, otherwise one is
+    /// built field by field, recursing into nested types, arrays and enums.
+    /// Returns `None` when a required field has no example.
+    ///
+    /// ```
+    /// use rstructor::{Schema, SchemaType};
+    /// use serde_json::json;
+    ///
+    /// struct Person;
+    ///
+    /// impl SchemaType for Person {
+    ///     fn schema() -> Schema {
+    ///         Schema::new(json!({
+    ///             "type": "object",
+    ///             "properties": {
+    ///                 "name": { "type": "string", "example": "Ada" },
+    ///                 "age": { "type": "integer", "examples": [36, 42] }
+    ///             },
+    ///             "required": ["name", "age"]
+    ///         }))
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(Person::example_value(), Some(json!({ "name": "Ada", "age": 36 })));
+    /// ```
+    fn example_value() -> Option<Value> {
+        example::example_from_schema(&Self::schema().to_json())
+    }
+
+    /// Schema instructions for providers without native structured outputs.
+    ///
+    /// [`Schema::prompt_instructions`] for this type's schema: the JSON Schema,
+    /// a concrete example object when [`example_value`](Self::example_value)
+    /// returns one (examples markedly improve how reliably small models follow
+    /// a schema given only in the prompt), and any
+    /// `#[llm(counter_example = ...)]` values as "Do NOT return output like
+    /// this" guidance.
+    fn prompt_instructions() -> String {
+        Self::schema().prompt_instructions()
+    }
+}
+
+/// Registers a derived type in the [`SchemaRegistry`]; emitted by
+/// `#[derive(Instructor)]` for non-generic types.
+#[cfg(feature = "registry")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __register_schema {
+    ($name:ident) => {
+        $crate::schema::__private::inventory::submit! {
+            $crate::schema::RegisteredSchema::of::<$name>(::core::stringify!($name))
+        }
+    };
+}
+
+/// Without the `registry` feature, derived types are not registered.
+#[cfg(not(feature = "registry"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __register_schema {
+    ($name:ident) => {};
 }
 
 /// Internal helpers used by `#[derive(Instructor)]`. Not part of the public API
 /// and exempt from semver guarantees.
 #[doc(hidden)]
 pub mod __private {
+    pub use super::recursion::build_schema;
+    #[cfg(feature = "registry")]
+    pub use inventory;
+
+    /// Record `(locale, default text, translation)` entries from
+    /// `#[llm(description(..))]` on a built schema.
+    pub fn with_translations(
+        schema: super::Schema,
+        translations: &[(&str, &str, &str)],
+    ) -> super::Schema {
+        super::locale::with_translations(schema, translations)
+    }
+
+    /// Merge the keywords of `#[llm(extra_schema = "..")]` (a JSON object,
+    /// checked by the derive) into a schema object, replacing generated
+    /// keywords of the same name.
+    pub fn merge_extra_schema(target: &mut serde_json::Map<String, Value>, extra: &str) {
+        if let Ok(Value::Object(extra)) = serde_json::from_str::<Value>(extra) {
+            target.extend(extra);
+        }
+    }
+
+    /// Make a schema also admit `null`, for an `Option` below field level (an
+    /// array item, map value or tuple element), where no `required` list can
+    /// express that it may be absent.
+    pub fn nullable(mut schema: Value) -> Value {
+        super::strict::make_schema_nullable(&mut schema);
+        schema
+    }
+
+    /// [`merge_extra_schema`] into the root of a built schema.
+    pub fn with_extra_schema(mut schema: super::Schema, extra: &str) -> super::Schema {
+        if let Value::Object(root) = &mut schema.schema {
+            merge_extra_schema(root, extra);
+        }
+        schema
+    }
+
     use super::SchemaType;
     use serde_json::Value;
     use std::marker::PhantomData;
@@ -0,0 +1,507 @@
+//! Auditing and rewriting property names providers may reject.
+//!
+//! Function-calling APIs validate parameter names more strictly than JSON
+//! Schema does: spaces, punctuation and non-ASCII characters (easily introduced
+//! by `#[serde(rename = "...")]`) can get a tool definition rejected.
+//! [`Schema::check_property_names`] lists the offending names, and
+//! [`Schema::with_safe_property_names`] rewrites them together with a
+//! [`PropertyRenames`] map that turns the model's output back into the original
+//! names before deserialization.
+
+use std::collections::{HashMap, HashSet};
+
+use serde_json::{Map, Value};
+
+use super::Schema;
+
+/// Longest property name accepted by every supported provider.
+const MAX_NAME_LEN: usize = 64;
+
+/// Instance-path segment standing for "any array element".
+const ANY_ITEM: &str = "/*";
+
+/// A property name that function-calling APIs may reject.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertyNameIssue {
+    /// JSON Pointer of the property's schema (e.g. `/properties/first name`).
+    pub pointer: String,
+    /// The property name as written in the schema.
+    pub name: String,
+    /// The replacement used by [`Schema::with_safe_property_names`].
+    pub safe_name: String,
+}
+
+/// Maps provider-safe property names back to the schema's original names.
+///
+/// Returned by [`Schema::with_safe_property_names`]; apply [`restore`](Self::restore)
+/// to the model's output before deserializing it.
+#[derive(Debug, Clone, Default)]
+pub struct PropertyRenames {
+    /// `(location of the owning object, safe name) -> original name`
+    renames: HashMap<(Location, String), String>,
+    /// `location of a $ref -> scope of the definition it points to`
+    refs: HashMap<Location, Vec<String>>,
+}
+
+/// An instance path within a scope: `""` for the root schema, or the JSON
+/// Pointer of a `$defs` entry (e.g. `/$defs/Address`) for instances described
+/// by that definition.
+type Location = (String, String);
+
+impl PropertyRenames {
+    /// Whether no property was renamed.
+    pub fn is_empty(&self) -> bool {
+        self.renames.is_empty()
+    }
+
+    /// Rename the safe property names in `value` back to the originals.
+    pub fn restore(&self, mut value: Value) -> Value {
+        if !self.is_empty() {
+            let root = self.follow_refs(vec![(String::new(), String::new())]);
+            self.restore_at(&mut value, &root);
+        }
+        value
+    }
+
+    /// `value` sits at every location in `at` (several when union branches or
+    /// `$ref`s describe the same instance).
+    fn restore_at(&self, value: &mut Value, at: &[Location]) {
+        match value {
+            Value::Object(obj) => {
+                let entries = std::mem::take(obj);
+                for (key, mut child) in entries {
+                    let key = at
+                        .iter()
+                        .find_map(|location| self.renames.get(&(location.clone(), key.clone())))
+                        .cloned()
+                        .unwrap_or(key);
+                    let segment = format!("/{}", escape(&key));
+                    self.restore_at(&mut child, &self.descend(at, &segment));
+                    obj.insert(key, child);
+                }
+            }
+            Value::Array(items) => {
+                let at = self.descend(at, ANY_ITEM);
+                for item in items {
+                    self.restore_at(item, &at);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn descend(&self, at: &[Location], segment: &str) -> Vec<Location> {
+        self.follow_refs(
+            at.iter()
+                .map(|(scope, path)| (scope.clone(), format!("{path}{segment}")))
+                .collect(),
+        )
+    }
+
+    /// Add the root of every definition the locations `$ref`, transitively.
+    fn follow_refs(&self, mut at: Vec<Location>) -> Vec<Location> {
+        let mut i = 0;
+        while i < at.len() {
+            for scope in self.refs.get(&at[i]).into_iter().flatten() {
+                let target = (scope.clone(), String::new());
+                if !at.contains(&target) {
+                    at.push(target);
+                }
+            }
+            i += 1;
+        }
+        at
+    }
+}
+
+impl Schema {
+    /// List property names that function-calling APIs may reject.
+    ///
+    /// A name is safe when it is 1–64 characters of ASCII letters, digits, `_`
+    /// or `-`. Nested objects, array items, union branches and `$defs` entries
+    /// are checked (each definition once, however many `$ref`s point to it);
+    /// map values are not.
+    ///
+    /// ```
+    /// use rstructor::Schema;
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::new(json!({
+    ///     "type": "object",
+    ///     "properties": { "first name": { "type": "string" }, "age": { "type": "integer" } }
+    /// }));
+    /// let issues = schema.check_property_names();
+    /// assert_eq!(issues.len(), 1);
+    /// assert_eq!(issues[0].name, "first name");
+    /// assert_eq!(issues[0].safe_name, "first_name");
+    /// ```
+    pub fn check_property_names(&self) -> Vec<PropertyNameIssue> {
+        let mut schema = self.to_json();
+        let mut walker = Walker::default();
+        walker.walk_root(&mut schema);
+        walker.issues
+    }
+
+    /// Rewrite unsafe property names (see [`check_property_names`](Self::check_property_names))
+    /// and return the rewritten schema with the mapping back to the originals.
+    ///
+    /// Renamed names stay unique within their object. A property renamed in a
+    /// `$defs` entry is restored at every `$ref` to that definition.
+    ///
+    /// ```
+    /// use rstructor::Schema;
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::new(json!({
+    ///     "type": "object",
+    ///     "properties": { "prénom": { "type": "string" } }
+    /// }));
+    /// let (safe, renames) = schema.with_safe_property_names();
+    /// assert!(safe.to_json()["properties"].get("pr_nom").is_some());
+    ///
+    /// let output = renames.restore(json!({ "pr_nom": "Zoé" }));
+    /// assert_eq!(output, json!({ "prénom": "Zoé" }));
+    /// ```
+    pub fn with_safe_property_names(&self) -> (Schema, PropertyRenames) {
+        let mut schema = self.to_json();
+        let mut walker = Walker::default();
+        walker.walk_root(&mut schema);
+        let renames = PropertyRenames {
+            renames: walker.renames,
+            refs: walker.refs,
+        };
+        (Schema::new(schema), renames)
+    }
+}
+
+#[derive(Default)]
+struct Walker {
+    issues: Vec<PropertyNameIssue>,
+    renames: HashMap<(Location, String), String>,
+    refs: HashMap<Location, Vec<String>>,
+}
+
+impl Walker {
+    fn walk_root(&mut self, root: &mut Value) {
+        self.walk(root, "", &(String::new(), String::new()));
+
+        // Each definition is its own scope, walked once; the `$ref`s recorded
+        // above tie it to the instances it describes.
+        for key in ["$defs", "definitions"] {
+            let names: Vec<String> = match root.get(key) {
+                Some(Value::Object(defs)) => defs.keys().cloned().collect(),
+                _ => continue,
+            };
+            for name in names {
+                let pointer = format!("/{key}/{}", escape(&name));
+                if let Some(definition) = root.pointer_mut(&pointer) {
+                    self.walk(definition, &pointer, &(pointer.clone(), String::new()));
+                }
+            }
+        }
+    }
+
+    fn walk(&mut self, node: &mut Value, pointer: &str, at: &Location) {
+        let Some(obj) = node.as_object_mut() else {
+            return;
+        };
+        let (scope, instance) = at;
+
+        if let Some(target) = obj
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|r| r.strip_prefix('#'))
+        {
+            let targets = self.refs.entry(at.clone()).or_default();
+            if !targets.iter().any(|t| t == target) {
+                targets.push(target.to_string());
+            }
+        }
+
+        if let Some(Value::Object(properties)) = obj.get_mut("properties") {
+            let renamed = self.rename_properties(properties, pointer, at);
+            if !renamed.is_empty() {
+                rename_required(obj, &renamed);
+            }
+        }
+        if let Some(Value::Object(properties)) = obj.get_mut("properties") {
+            for (name, property) in properties.iter_mut() {
+                let original = self
+                    .renames
+                    .get(&(at.clone(), name.clone()))
+                    .unwrap_or(name)
+                    .clone();
+                self.walk(
+                    property,
+                    &format!("{pointer}/properties/{}", escape(name)),
+                    &(scope.clone(), format!("{instance}/{}", escape(&original))),
+                );
+            }
+        }
+
+        if let Some(items) = obj.get_mut("items") {
+            self.walk(
+                items,
+                &format!("{pointer}/items"),
+                &(scope.clone(), format!("{instance}{ANY_ITEM}")),
+            );
+        }
+        for key in ["anyOf", "oneOf", "allOf"] {
+            if let Some(Value::Array(branches)) = obj.get_mut(key) {
+                for (i, branch) in branches.iter_mut().enumerate() {
+                    self.walk(branch, &format!("{pointer}/{key}/{i}"), at);
+                }
+            }
+        }
+    }
+
+    /// Rename the unsafe keys of one `properties` map, returning `(original, safe)` pairs.
+    fn rename_properties(
+        &mut self,
+        properties: &mut Map<String, Value>,
+        pointer: &str,
+        at: &Location,
+    ) -> Vec<(String, String)> {
+        let mut renamed = Vec::new();
+        let unsafe_names: Vec<String> = properties
+            .keys()
+            .filter(|name| !is_safe(name))
+            .cloned()
+            .collect();
+        let mut taken: HashSet<String> = properties.keys().cloned().collect();
+        for name in unsafe_names {
+            let base = safe_name(&name);
+            let mut candidate = base.clone();
+            let mut n = 2;
+            while taken.contains(&candidate) {
+                let suffix = format!("_{n}");
+                candidate = format!("{}{suffix}", truncate(&base, MAX_NAME_LEN - suffix.len()));
+                n += 1;
+            }
+            taken.insert(candidate.clone());
+
+            self.issues.push(PropertyNameIssue {
+                pointer: format!("{pointer}/properties/{}", escape(&name)),
+                name: name.clone(),
+                safe_name: candidate.clone(),
+            });
+            self.renames
+                .insert((at.clone(), candidate.clone()), name.clone());
+            renamed.push((name, candidate));
+        }
+        if !renamed.is_empty() {
+            // Rebuild rather than remove + insert so properties keep their order.
+            *properties = std::mem::take(properties)
+                .into_iter()
+                .map(
+                    |(key, value)| match renamed.iter().find(|(name, _)| *name == key) {
+                        Some((_, safe)) => (safe.clone(), value),
+                        None => (key, value),
+                    },
+                )
+                .collect();
+        }
+        renamed
+    }
+}
+
+fn rename_required(obj: &mut Map<String, Value>, renamed: &[(String, String)]) {
+    if let Some(Value::Array(required)) = obj.get_mut("required") {
+        for entry in required.iter_mut() {
+            if let Some((_, safe)) = renamed
+                .iter()
+                .find(|(original, _)| entry.as_str() == Some(original))
+            {
+                *entry = Value::String(safe.clone());
+            }
+        }
+    }
+}
+
+fn is_safe(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-')
+}
+
+/// Replace every disallowed character with `_`, collapse runs of `_`, and trim.
+fn safe_name(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    for c in name.chars() {
+        let c = if c.is_ascii_alphanumeric() || c == '-' {
+            c
+        } else {
+            '_'
+        };
+        if !(c == '_' && out.ends_with('_')) {
+            out.push(c);
+        }
+    }
+    let out = out.trim_matches('_');
+    let out = if out.is_empty() { "field" } else { out };
+    truncate(out, MAX_NAME_LEN).to_string()
+}
+
+/// `s` cut to at most `max` bytes (all characters are ASCII here).
+fn truncate(s: &str, max: usize) -> &str {
+    &s[..s.len().min(max)]
+}
+
+/// Escape a name for use as a JSON Pointer segment.
+fn escape(name: &str) -> String {
+    name.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Schema {
+        Schema::new(json!({
+            "type": "object",
+            "properties": {
+                "first name": { "type": "string" },
+                "first_name": { "type": "string" },
+                "ok": { "type": "boolean" },
+                "addresses": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": { "straße": { "type": "string" } },
+                        "required": ["straße"]
+                    }
+                }
+            },
+            "required": ["first name", "ok"]
+        }))
+    }
+
+    #[test]
+    fn unsafe_names_are_reported_with_unique_replacements() {
+        let issues = schema().check_property_names();
+        assert_eq!(
+            issues,
+            vec![
+                PropertyNameIssue {
+                    pointer: "/properties/first name".into(),
+                    name: "first name".into(),
+                    safe_name: "first_name_2".into(),
+                },
+                PropertyNameIssue {
+                    pointer: "/properties/addresses/items/properties/straße".into(),
+                    name: "straße".into(),
+                    safe_name: "stra_e".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn renamed_schema_updates_required_and_round_trips() {
+        let (safe, renames) = schema().with_safe_property_names();
+        let safe = safe.to_json();
+        assert_eq!(safe["required"], json!(["first_name_2", "ok"]));
+        assert_eq!(
+            safe["properties"]["addresses"]["items"]["required"],
+            json!(["stra_e"])
+        );
+        assert!(Schema::new(safe).check_property_names().is_empty());
+
+        let output = json!({
+            "first_name_2": "Ada",
+            "first_name": "Grace",
+            "ok": true,
+            "addresses": [{ "stra_e": "Hauptstraße 1" }, { "stra_e": "Ring 2" }]
+        });
+        assert_eq!(
+            renames.restore(output),
+            json!({
+                "first name": "Ada",
+                "first_name": "Grace",
+                "ok": true,
+                "addresses": [{ "straße": "Hauptstraße 1" }, { "straße": "Ring 2" }]
+            })
+        );
+    }
+
+    #[test]
+    fn safe_schema_is_unchanged() {
+        let schema = Schema::new(json!({
+            "type": "object",
+            "properties": { "a-b": { "type": "string" }, "c_1": { "type": "string" } }
+        }));
+        let (safe, renames) = schema.with_safe_property_names();
+        assert!(renames.is_empty());
+        assert_eq!(safe.to_json(), schema.to_json());
+    }
+
+    #[test]
+    fn long_and_symbol_only_names() {
+        assert_eq!(safe_name(&"x".repeat(100)).len(), MAX_NAME_LEN);
+        assert_eq!(safe_name("日本"), "field");
+        assert_eq!(safe_name("  a . b  "), "a_b");
+    }
+
+    #[test]
+    fn root_ref_definition_is_walked() {
+        let schema = Schema::new(json!({
+            "$ref": "#/$defs/Node",
+            "$defs": {
+                "Node": { "type": "object", "properties": { "child node": { "$ref": "#/$defs/Node" } } }
+            }
+        }));
+        let (_, renames) = schema.with_safe_property_names();
+        assert_eq!(
+            renames.restore(json!({ "child_node": null })),
+            json!({ "child node": null })
+        );
+    }
+
+    #[test]
+    fn shared_definitions_are_walked_once_and_restored_at_every_ref() {
+        let schema = Schema::new(json!({
+            "type": "object",
+            "properties": {
+                "home": { "$ref": "#/$defs/Addr", "description": "Home address" },
+                "work": { "$ref": "#/$defs/Addr" },
+                "past": { "type": "array", "items": { "$ref": "#/$defs/Addr" } }
+            },
+            "$defs": {
+                "Addr": {
+                    "type": "object",
+                    "properties": { "first name": { "type": "string" } },
+                    "required": ["first name"]
+                }
+            }
+        }));
+        assert_eq!(
+            schema.check_property_names(),
+            vec![PropertyNameIssue {
+                pointer: "/$defs/Addr/properties/first name".into(),
+                name: "first name".into(),
+                safe_name: "first_name".into(),
+            }]
+        );
+
+        let (safe, renames) = schema.with_safe_property_names();
+        assert_eq!(
+            safe.to_json()["$defs"]["Addr"]["required"],
+            json!(["first_name"])
+        );
+        assert_eq!(
+            renames.restore(json!({
+                "home": { "first_name": "Ada" },
+                "work": { "first_name": "Grace" },
+                "past": [{ "first_name": "Zoé" }]
+            })),
+            json!({
+                "home": { "first name": "Ada" },
+                "work": { "first name": "Grace" },
+                "past": [{ "first name": "Zoé" }]
+            })
+        );
+    }
+}
@@ -0,0 +1,177 @@
+//! Object roots for providers that only accept object schemas.
+//!
+//! `Vec<T>`, `bool`, `String` and the numeric types are valid extraction
+//! targets, but OpenAI, Grok and Azure structured outputs and Anthropic tool
+//! inputs require the schema root to be an object. [`Schema::with_object_root`]
+//! wraps any other root in a single-property object, and the reply is
+//! unwrapped again before it is deserialized, so
+//! `client.materialize::<Vec<Person>>(..)` returns the list directly and
+//! `client.materialize::<bool>(..)` the answer.
+
+use serde_json::{Map, Value, json};
+
+use super::Schema;
+
+/// The property an array root is wrapped in.
+const LIST_FIELD: &str = "items";
+
+/// The property a scalar root (string, number, integer, boolean) is wrapped in.
+const SCALAR_FIELD: &str = "value";
+
+impl Schema {
+    /// This schema with an array or scalar root wrapped as the single
+    /// required property of an object: `items` for arrays, `value` for
+    /// strings, numbers, integers and booleans. Other schemas are returned
+    /// unchanged.
+    ///
+    /// `$defs` stay at the root so `$ref`s into them still resolve.
+    ///
+    /// ```
+    /// use rstructor::{Schema, SchemaType};
+    ///
+    /// let wrapped = Vec::<String>::schema().with_object_root().to_json();
+    /// assert_eq!(wrapped["type"], "object");
+    /// assert_eq!(wrapped["required"], serde_json::json!(["items"]));
+    /// assert_eq!(wrapped["properties"]["items"]["items"]["type"], "string");
+    ///
+    /// let wrapped = bool::schema().with_object_root().to_json();
+    /// assert_eq!(wrapped["properties"]["value"]["type"], "boolean");
+    /// ```
+    #[must_use]
+    pub fn with_object_root(&self) -> Schema {
+        let Some(field) = self.wrapper_field() else {
+            return self.clone();
+        };
+        let mut inner = self.schema.clone();
+        let defs = inner.as_object_mut().and_then(|root| root.remove("$defs"));
+        let mut root = Map::new();
+        root.insert("type".to_string(), json!("object"));
+        root.insert("properties".to_string(), json!({ field: inner }));
+        root.insert("required".to_string(), json!([field]));
+        root.insert("additionalProperties".to_string(), json!(false));
+        if let Some(defs) = defs {
+            root.insert("$defs".to_string(), defs);
+        }
+        super::refs::share_definitions(Schema::new(Value::Object(root)))
+    }
+
+    /// The property [`with_object_root`](Schema::with_object_root) wraps
+    /// this schema in, or `None` if its root is already an object (or has no
+    /// single `type`, like an enum's `oneOf`).
+    fn wrapper_field(&self) -> Option<&'static str> {
+        match self.schema.get("type").and_then(Value::as_str)? {
+            "array" => Some(LIST_FIELD),
+            "string" | "number" | "integer" | "boolean" => Some(SCALAR_FIELD),
+            _ => None,
+        }
+    }
+}
+
+/// The schema of the value [`unwrap_object_root`] takes out of a reply: the
+/// wrapped property of [`Schema::with_object_root`], with the `$defs` the
+/// wrapper gathered at its root so the property's `$ref`s still resolve.
+/// Schemas that are not wrapped are returned unchanged.
+#[cfg(any(feature = "_client", feature = "mock"))]
+pub(crate) fn unwrapped_root_schema(schema: &Schema) -> Schema {
+    let Some(field) = schema.wrapper_field() else {
+        return schema.clone();
+    };
+    let mut wrapped = schema.with_object_root().schema;
+    let mut inner = wrapped["properties"][field].take();
+    if let (Some(inner), Some(defs)) = (inner.as_object_mut(), wrapped.get_mut("$defs")) {
+        inner.insert("$defs".to_string(), defs.take());
+    }
+    Schema::new(inner)
+}
+
+/// The value inside a reply to a schema wrapped by
+/// [`Schema::with_object_root`], or `None` if `schema` is not wrapped or the
+/// reply is not such a wrapper (e.g. the model answered with the bare array
+/// or scalar).
+#[cfg(any(feature = "_client", feature = "mock"))]
+pub(crate) fn unwrap_object_root(schema: &Schema, reply: &str) -> Option<String> {
+    let field = schema.wrapper_field()?;
+    let json = crate::parsing::extract_json_from_markdown(reply);
+    match serde_json::from_str::<Value>(json).ok()? {
+        Value::Object(mut wrapper) if wrapper.len() == 1 => {
+            let inner = wrapper.remove(field)?;
+            (field == SCALAR_FIELD || inner.is_array()).then(|| inner.to_string())
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::SchemaType;
+
+    #[test]
+    fn object_roots_are_unchanged() {
+        let schema = Schema::new(json!({ "type": "object", "properties": {} }));
+        assert_eq!(schema.with_object_root().to_json(), schema.to_json());
+        let one_of = Schema::new(json!({ "oneOf": [{ "const": "a" }] }));
+        assert_eq!(one_of.with_object_root().to_json(), one_of.to_json());
+    }
+
+    #[test]
+    fn scalar_roots_are_wrapped_as_value() {
+        for schema in [
+            String::schema(),
+            i64::schema(),
+            f32::schema(),
+            bool::schema(),
+        ] {
+            let wrapped = schema.with_object_root().to_json();
+            assert_eq!(wrapped["required"], json!(["value"]));
+            assert_eq!(wrapped["properties"]["value"], schema.to_json());
+        }
+    }
+
+    #[test]
+    fn nested_defs_move_to_the_wrapper_root() {
+        let list = Schema::new(json!({
+            "type": "array",
+            "items": {
+                "$defs": { "Node": { "type": "object", "title": "Node", "properties": {} } },
+                "$ref": "#/$defs/Node"
+            }
+        }));
+        let wrapped = list.with_object_root().to_json();
+        assert_eq!(
+            wrapped["properties"]["items"]["items"],
+            json!({ "$ref": "#/$defs/Node" })
+        );
+        assert!(wrapped["$defs"]["Node"].is_object());
+    }
+
+    #[cfg(any(feature = "_client", feature = "mock"))]
+    #[test]
+    fn unwraps_only_wrapper_replies_to_wrapped_schemas() {
+        let list = Vec::<i32>::schema();
+        assert_eq!(
+            unwrap_object_root(&list, "```json\n{\"items\": [1, 2]}\n```").as_deref(),
+            Some("[1,2]")
+        );
+        assert_eq!(unwrap_object_root(&list, "[1, 2]"), None);
+        assert_eq!(
+            unwrap_object_root(&list, r#"{"items": [1], "extra": 2}"#),
+            None
+        );
+        assert_eq!(unwrap_object_root(&list, r#"{"items": 1}"#), None);
+
+        let flag = bool::schema();
+        assert_eq!(
+            unwrap_object_root(&flag, r#"{"value": true}"#).as_deref(),
+            Some("true")
+        );
+        assert_eq!(unwrap_object_root(&flag, "true"), None);
+        assert_eq!(
+            unwrap_object_root(&String::schema(), r#"{"value": "spam"}"#).as_deref(),
+            Some(r#""spam""#)
+        );
+
+        let object = Schema::new(json!({ "type": "object" }));
+        assert_eq!(unwrap_object_root(&object, r#"{"items": [1]}"#), None);
+    }
+}
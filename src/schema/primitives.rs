@@ -35,7 +35,7 @@ impl SchemaType for Value {
 // HashMap<String, V> - Objects with dynamic keys
 // ============================================================================
 
-impl<V: SchemaType> SchemaType for HashMap<String, V> {
+impl<V: SchemaType, S> SchemaType for HashMap<String, V, S> {
     fn schema() -> Schema {
         let value_schema = V::schema().to_json();
         Schema::new(json!({
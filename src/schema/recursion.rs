@@ -0,0 +1,150 @@
+//! Cycle detection while derived schemas are built.
+//!
+//! A derived `schema()` embeds the schema of every field type, so a type that
+//! reaches itself through its fields (`enum Expr { Neg(Box<Expr>) }`, or
+//! `Department` → `Vec<Employee>` → `Option<Box<Department>>`) would recurse
+//! forever. [`build_schema`] keeps the types being built on a per-thread
+//! stack. When a type is reached again while its own schema is still under
+//! construction, the inner occurrence becomes a `$ref` and the outer one
+//! moves its definition into `$defs`, so the result is finite and complete.
+
+use std::cell::RefCell;
+use std::sync::OnceLock;
+
+use serde_json::{Map, Value, json};
+
+use super::Schema;
+
+struct Frame {
+    type_name: &'static str,
+    /// Some schema built inside this one referred back to it.
+    referenced: bool,
+    /// Some schema built inside this one referred to a type further out, so
+    /// the result has a `$ref` that only resolves inside that type's schema.
+    open_reference: bool,
+}
+
+thread_local! {
+    static BUILDING: RefCell<Vec<Frame>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Pops the frame pushed by [`build_schema`], even if `build` panics.
+struct FrameGuard;
+
+impl Drop for FrameGuard {
+    fn drop(&mut self) {
+        BUILDING.with_borrow_mut(|frames| frames.pop());
+    }
+}
+
+/// Build the schema of `T`, named `def_name` in `$defs`, with `build`.
+///
+/// Called by `#[derive(Instructor)]` for every generated `schema()`. The
+/// finished schema is passed through [`share_definitions`] and stored in
+/// `cache`, unless it contains a `$ref` into a type that is still being
+/// built further up the stack: such a schema is only valid embedded there.
+///
+/// [`share_definitions`]: super::refs::share_definitions
+pub fn build_schema<T: ?Sized>(
+    def_name: &str,
+    cache: Option<&OnceLock<Schema>>,
+    build: impl FnOnce() -> Schema,
+) -> Schema {
+    if let Some(schema) = cache.and_then(OnceLock::get) {
+        return schema.clone();
+    }
+    let type_name = std::any::type_name::<T>();
+    let cycle = BUILDING.with_borrow_mut(|frames| {
+        let index = frames.iter().position(|f| f.type_name == type_name)?;
+        frames[index].referenced = true;
+        for frame in &mut frames[index + 1..] {
+            frame.open_reference = true;
+        }
+        Some(())
+    });
+    if cycle.is_some() {
+        return Schema::new(json!({ "$ref": format!("#/$defs/{def_name}") }));
+    }
+
+    BUILDING.with_borrow_mut(|frames| {
+        frames.push(Frame {
+            type_name,
+            referenced: false,
+            open_reference: false,
+        })
+    });
+    let guard = FrameGuard;
+    let built = build();
+    let (referenced, open_reference) = BUILDING.with_borrow(|frames| {
+        frames
+            .last()
+            .map_or((false, false), |f| (f.referenced, f.open_reference))
+    });
+    drop(guard);
+
+    let built = if referenced {
+        into_definition(built, def_name)
+    } else {
+        built
+    };
+    let schema = super::refs::share_definitions(built);
+    if !open_reference && let Some(cache) = cache {
+        let _ = cache.set(schema.clone());
+    }
+    schema
+}
+
+/// `{"$defs": {def_name: schema}, "$ref": "#/$defs/<def_name>"}`, keeping
+/// definitions `schema` already carries at the root.
+fn into_definition(schema: Schema, def_name: &str) -> Schema {
+    let reference = format!("#/$defs/{def_name}");
+    let mut definition = schema.schema;
+    if definition.get("$ref").and_then(Value::as_str) == Some(reference.as_str()) {
+        // Already in this shape (a struct that names itself in a field).
+        return Schema::new(definition);
+    }
+    let mut defs = match definition
+        .as_object_mut()
+        .and_then(|obj| obj.remove("$defs"))
+    {
+        Some(Value::Object(defs)) => defs,
+        _ => Map::new(),
+    };
+    defs.insert(def_name.to_string(), definition);
+    Schema::new(json!({ "$defs": defs, "$ref": reference }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Loop;
+
+    fn loop_schema(cache: Option<&OnceLock<Schema>>) -> Schema {
+        build_schema::<Loop>("Loop", cache, || {
+            Schema::new(json!({
+                "type": "object",
+                "title": "Loop",
+                "properties": { "next": loop_schema(None).to_json() }
+            }))
+        })
+    }
+
+    #[test]
+    fn a_type_reached_again_becomes_a_reference() {
+        let schema = loop_schema(None).to_json();
+        assert_eq!(schema["$ref"], "#/$defs/Loop");
+        assert_eq!(
+            schema["$defs"]["Loop"]["properties"]["next"],
+            json!({ "$ref": "#/$defs/Loop" })
+        );
+    }
+
+    #[test]
+    fn the_stack_is_empty_afterwards_and_the_result_is_cached() {
+        let cache = OnceLock::new();
+        let first = loop_schema(Some(&cache));
+        assert!(BUILDING.with_borrow(Vec::is_empty));
+        assert_eq!(cache.get().map(Schema::to_json), Some(first.to_json()));
+    }
+}
@@ -0,0 +1,583 @@
+//! Shared definitions: `$defs` and `$ref` in derived schemas.
+//!
+//! A derived schema embeds each field type's own schema, so a type used in
+//! several places would be spelled out each time, and a recursive type's
+//! `{"$defs": ..., "$ref": ...}` would land under a property where its
+//! root-relative `$ref` no longer resolves. [`share_definitions`] runs on
+//! every derived schema: it lifts nested `$defs` to the root and moves object
+//! types that appear more than once into `$defs`, leaving `$ref`s behind.
+//! [`Schema::inline`] undoes this for consumers that cannot follow `$ref`s.
+
+use std::collections::HashMap;
+
+use serde_json::{Map, Value};
+
+use super::Schema;
+use super::draft::DATA_KEYWORDS;
+
+/// Keywords whose value maps names to subschemas.
+const NAMED_SUBSCHEMAS: &[&str] = &[
+    "properties",
+    "patternProperties",
+    "dependentSchemas",
+    "$defs",
+    "definitions",
+];
+
+impl Schema {
+    /// This schema with every local `$ref` replaced by the definition it
+    /// points to, for providers and tools that cannot follow references.
+    ///
+    /// Keywords written next to a `$ref` (such as a field's `description`)
+    /// take precedence over the definition's. A recursive type cannot be
+    /// expanded completely: a reference back into a definition that is
+    /// already being expanded stays a `$ref`, and only the definitions such
+    /// references need are kept under `$defs`.
+    ///
+    /// ```
+    /// use rstructor::Schema;
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::new(json!({
+    ///     "type": "object",
+    ///     "properties": {
+    ///         "home": { "$ref": "#/$defs/Address" },
+    ///         "work": { "$ref": "#/$defs/Address", "description": "Office address" }
+    ///     },
+    ///     "$defs": {
+    ///         "Address": { "type": "object", "properties": { "city": { "type": "string" } } }
+    ///     }
+    /// }));
+    /// let inlined = schema.inline().to_json();
+    /// assert_eq!(inlined["properties"]["home"]["properties"]["city"]["type"], "string");
+    /// assert_eq!(inlined["properties"]["work"]["description"], "Office address");
+    /// assert!(inlined.get("$defs").is_none());
+    /// ```
+    pub fn inline(&self) -> Schema {
+        let mut root = self.schema.clone();
+        let Some(obj) = root.as_object_mut() else {
+            return Schema::new(root);
+        };
+        let Some((keyword, defs)) =
+            ["$defs", "definitions"]
+                .into_iter()
+                .find_map(|keyword| match obj.get(keyword) {
+                    Some(Value::Object(_)) => match obj.remove(keyword) {
+                        Some(Value::Object(defs)) => Some((keyword, defs)),
+                        _ => None,
+                    },
+                    _ => None,
+                })
+        else {
+            return Schema::new(root);
+        };
+
+        let mut inliner = Inliner {
+            defs: &defs,
+            prefix: format!("#/{keyword}/"),
+            expanding: Vec::new(),
+            kept: Vec::new(),
+        };
+        inliner.expand(&mut root);
+
+        // Definitions kept for recursive references are expanded too; doing
+        // so can only keep more definitions from the same cycle.
+        let mut retained = Map::new();
+        let mut next = 0;
+        while let Some(name) = inliner.kept.get(next).cloned() {
+            let mut definition = defs[&name].clone();
+            inliner.expanding.push(name.clone());
+            inliner.expand(&mut definition);
+            inliner.expanding.pop();
+            retained.insert(name, definition);
+            next += 1;
+        }
+        if !retained.is_empty()
+            && let Value::Object(obj) = &mut root
+        {
+            obj.insert(keyword.to_string(), Value::Object(retained));
+        }
+        Schema::new(root)
+    }
+}
+
+struct Inliner<'a> {
+    defs: &'a Map<String, Value>,
+    prefix: String,
+    /// Definitions currently being expanded, outermost first.
+    expanding: Vec<String>,
+    /// Definitions that must stay under `$defs` because a cycle points at them.
+    kept: Vec<String>,
+}
+
+impl Inliner<'_> {
+    fn expand(&mut self, schema: &mut Value) {
+        let Value::Object(obj) = schema else {
+            return;
+        };
+        let target = obj
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|reference| reference.strip_prefix(self.prefix.as_str()))
+            .filter(|name| self.defs.contains_key(*name))
+            .map(str::to_string);
+        let Some(name) = target else {
+            for_each_subschema(obj, &mut |child| self.expand(child));
+            return;
+        };
+
+        if self.expanding.contains(&name) {
+            if !self.kept.contains(&name) {
+                self.kept.push(name);
+            }
+            return;
+        }
+        let mut definition = self.defs[&name].clone();
+        self.expanding.push(name);
+        self.expand(&mut definition);
+        self.expanding.pop();
+
+        obj.remove("$ref");
+        match &mut definition {
+            Value::Object(expanded) => {
+                for (key, value) in std::mem::take(obj) {
+                    expanded.insert(key, value);
+                }
+                *schema = definition;
+            }
+            _ if obj.is_empty() => *schema = definition,
+            // A boolean definition cannot carry the sibling keywords.
+            _ => {}
+        }
+    }
+}
+
+/// Lift nested `$defs` to the root and share repeated object types.
+///
+/// Called by `#[derive(Instructor)]` on every generated schema.
+pub fn share_definitions(mut schema: Schema) -> Schema {
+    hoist_definitions(&mut schema.schema);
+    share_repeated_types(&mut schema.schema);
+    schema
+}
+
+/// Move every `$defs` map in `schema` to the root.
+///
+/// Derived `$ref`s are root-relative (`#/$defs/Name`), so a `$defs` map
+/// nested under a property is never what they resolve against. A nested
+/// definition whose name is already taken by a different definition is
+/// renamed, along with the references beside it.
+fn hoist_definitions(schema: &mut Value) {
+    let mut defs = Map::new();
+    collect_definitions(schema, &mut defs);
+    if !defs.is_empty()
+        && let Value::Object(obj) = schema
+    {
+        obj.insert("$defs".to_string(), Value::Object(defs));
+    }
+}
+
+fn collect_definitions(schema: &mut Value, defs: &mut Map<String, Value>) {
+    let Value::Object(obj) = schema else {
+        return;
+    };
+    if matches!(obj.get("$defs"), Some(Value::Object(_)))
+        && let Some(Value::Object(mut local)) = obj.remove("$defs")
+    {
+        for definition in local.values_mut() {
+            collect_definitions(definition, defs);
+        }
+        let renames: Vec<(String, String)> = local
+            .iter()
+            .filter(|(name, definition)| defs.get(*name).is_some_and(|d| d != *definition))
+            .map(|(name, _)| {
+                (
+                    name.clone(),
+                    fresh_name(name, |n| defs.contains_key(n) || local.contains_key(n)),
+                )
+            })
+            .collect();
+        if !renames.is_empty() {
+            rename_refs(obj, &renames);
+            for definition in local.values_mut() {
+                if let Value::Object(definition) = definition {
+                    rename_refs(definition, &renames);
+                }
+            }
+        }
+        for (name, definition) in local {
+            let name = renames
+                .iter()
+                .find(|(old, _)| *old == name)
+                .map_or(name, |(_, new)| new.clone());
+            defs.entry(name).or_insert(definition);
+        }
+    }
+    for_each_subschema(obj, &mut |child| collect_definitions(child, defs));
+}
+
+fn rename_refs(obj: &mut Map<String, Value>, renames: &[(String, String)]) {
+    if let Some(Value::String(reference)) = obj.get_mut("$ref")
+        && let Some(name) = reference.strip_prefix("#/$defs/")
+        && let Some((_, new)) = renames.iter().find(|(old, _)| old == name)
+    {
+        *reference = format!("#/$defs/{new}");
+    }
+    for_each_subschema(obj, &mut |child| {
+        if let Value::Object(child) = child {
+            rename_refs(child, renames);
+        }
+    });
+}
+
+/// `name`, or `name` with the smallest numeric suffix from 2 up that is free.
+fn fresh_name(name: &str, taken: impl Fn(&str) -> bool) -> String {
+    (2..)
+        .map(|n| format!("{name}{n}"))
+        .find(|candidate| !taken(candidate))
+        .expect("an unbounded range always has a free suffix")
+}
+
+/// How often one object type occurs, keyed by its schema minus `description`.
+struct Occurrences {
+    count: usize,
+    schema: Value,
+    /// The description every occurrence shares, if they all agree.
+    description: Option<Value>,
+    descriptions_agree: bool,
+}
+
+/// Move titled object types that occur more than once into root `$defs`,
+/// replacing each occurrence with a `$ref`.
+///
+/// A field's own description stays next to its `$ref` when it differs from
+/// the shared definition's. Types are shared one at a time until nothing
+/// repeats, so a type nested inside another shared type is handled too.
+fn share_repeated_types(schema: &mut Value) {
+    let Value::Object(root) = schema else {
+        return;
+    };
+    loop {
+        let mut order = Vec::new();
+        let mut seen: HashMap<String, Occurrences> = HashMap::new();
+        for_each_shared_scope(root, &mut |scope| count_types(scope, &mut order, &mut seen));
+        let Some((fingerprint, occurrences)) = order
+            .into_iter()
+            .find(|key| seen[key].count > 1)
+            .and_then(|key| seen.remove_entry(&key))
+        else {
+            return;
+        };
+
+        let mut definition = occurrences.schema;
+        if let Value::Object(def) = &mut definition {
+            match occurrences
+                .description
+                .filter(|_| occurrences.descriptions_agree)
+            {
+                Some(description) => def.insert("description".to_string(), description),
+                None => def.remove("description"),
+            };
+        }
+        let title = definition["title"].as_str().unwrap_or_default().to_string();
+        let existing = match root.get("$defs") {
+            Some(Value::Object(defs)) => defs.clone(),
+            _ => Map::new(),
+        };
+        let name = match existing.get(&title) {
+            Some(def) if type_fingerprint(def).as_deref() != Some(fingerprint.as_str()) => {
+                fresh_name(&title, |n| existing.contains_key(n))
+            }
+            _ => title,
+        };
+
+        let reference = format!("#/$defs/{name}");
+        let shared_description = definition.get("description").cloned();
+        for_each_shared_scope(root, &mut |scope| {
+            replace_type(scope, &fingerprint, &reference, shared_description.as_ref())
+        });
+        let defs = root
+            .entry("$defs")
+            .or_insert_with(|| Value::Object(Map::new()));
+        if let Value::Object(defs) = defs {
+            defs.entry(name).or_insert(definition);
+        }
+    }
+}
+
+/// Call `f` on every subschema that may hold a shareable occurrence: the
+/// root's subschemas (but not the root itself) and the inside of each root
+/// definition (but not the definition itself).
+fn for_each_shared_scope(root: &mut Map<String, Value>, f: &mut impl FnMut(&mut Value)) {
+    for (key, child) in root.iter_mut() {
+        if key == "$defs" {
+            if let Value::Object(defs) = child {
+                for definition in defs.values_mut() {
+                    if let Value::Object(def) = definition {
+                        for_each_subschema(def, f);
+                    }
+                }
+            }
+        } else {
+            for_each_child_subschema(key, child, f);
+        }
+    }
+}
+
+fn count_types(
+    schema: &mut Value,
+    order: &mut Vec<String>,
+    seen: &mut HashMap<String, Occurrences>,
+) {
+    if let Some(fingerprint) = type_fingerprint(schema) {
+        let description = schema.get("description").cloned();
+        match seen.get_mut(&fingerprint) {
+            Some(occurrences) => {
+                occurrences.count += 1;
+                occurrences.descriptions_agree &= occurrences.description == description;
+            }
+            None => {
+                order.push(fingerprint.clone());
+                seen.insert(
+                    fingerprint,
+                    Occurrences {
+                        count: 1,
+                        schema: schema.clone(),
+                        description,
+                        descriptions_agree: true,
+                    },
+                );
+            }
+        }
+    }
+    if let Value::Object(obj) = schema {
+        for_each_subschema(obj, &mut |child| count_types(child, order, seen));
+    }
+}
+
+fn replace_type(
+    schema: &mut Value,
+    fingerprint: &str,
+    reference: &str,
+    shared_description: Option<&Value>,
+) {
+    if type_fingerprint(schema).as_deref() == Some(fingerprint) {
+        let mut pointer = Map::new();
+        pointer.insert("$ref".to_string(), Value::from(reference));
+        if let Some(description) = schema.get("description")
+            && Some(description) != shared_description
+        {
+            pointer.insert("description".to_string(), description.clone());
+        }
+        *schema = Value::Object(pointer);
+        return;
+    }
+    if let Value::Object(obj) = schema {
+        for_each_subschema(obj, &mut |child| {
+            replace_type(child, fingerprint, reference, shared_description)
+        });
+    }
+}
+
+/// Identity of a shareable object type: a titled schema with properties or
+/// variants, serialized without its `description` (which fields override).
+fn type_fingerprint(schema: &Value) -> Option<String> {
+    let obj = schema.as_object()?;
+    obj.get("title")?.as_str()?;
+    if obj.contains_key("$ref")
+        || !["properties", "anyOf", "oneOf"]
+            .iter()
+            .any(|keyword| obj.contains_key(*keyword))
+    {
+        return None;
+    }
+    let mut identity = obj.clone();
+    identity.remove("description");
+    serde_json::to_string(&identity).ok()
+}
+
+/// Call `f` on each direct subschema of `obj`, skipping keywords whose
+/// values are instance data (`enum`, `examples`, vendor `x-` keywords, ...).
+fn for_each_subschema(obj: &mut Map<String, Value>, f: &mut impl FnMut(&mut Value)) {
+    for (key, child) in obj.iter_mut() {
+        for_each_child_subschema(key, child, f);
+    }
+}
+
+fn for_each_child_subschema(key: &str, child: &mut Value, f: &mut impl FnMut(&mut Value)) {
+    if DATA_KEYWORDS.contains(&key) || key.starts_with("x-") {
+        return;
+    }
+    match child {
+        Value::Object(named) if NAMED_SUBSCHEMAS.contains(&key) => {
+            named.values_mut().for_each(&mut *f);
+        }
+        Value::Object(_) => f(child),
+        Value::Array(items) => items.iter_mut().for_each(f),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn address(description: Option<&str>) -> Value {
+        let mut address = json!({
+            "type": "object",
+            "title": "Address",
+            "properties": { "city": { "type": "string" } },
+            "required": ["city"]
+        });
+        if let Some(description) = description {
+            address["description"] = json!(description);
+        }
+        address
+    }
+
+    #[test]
+    fn nested_defs_are_hoisted_to_the_root() {
+        let comment = json!({
+            "$defs": {
+                "Comment": {
+                    "type": "object",
+                    "title": "Comment",
+                    "properties": { "replies": { "type": "array", "items": { "$ref": "#/$defs/Comment" } } }
+                }
+            },
+            "$ref": "#/$defs/Comment"
+        });
+        let schema = share_definitions(Schema::new(json!({
+            "type": "object",
+            "title": "Thread",
+            "properties": { "root": comment }
+        })))
+        .to_json();
+
+        assert_eq!(
+            schema["properties"]["root"],
+            json!({ "$ref": "#/$defs/Comment" })
+        );
+        assert_eq!(schema["$defs"]["Comment"]["title"], "Comment");
+    }
+
+    #[test]
+    fn conflicting_nested_defs_are_renamed() {
+        let node = |field: &str| {
+            json!({
+                "$defs": { "Node": { "type": "object", "title": "Node", "properties": { field: { "$ref": "#/$defs/Node" } } } },
+                "$ref": "#/$defs/Node"
+            })
+        };
+        let schema = share_definitions(Schema::new(json!({
+            "type": "object",
+            "title": "Pair",
+            "properties": { "a": node("left"), "b": node("right") }
+        })))
+        .to_json();
+
+        assert_eq!(schema["properties"]["a"]["$ref"], "#/$defs/Node");
+        assert_eq!(schema["properties"]["b"]["$ref"], "#/$defs/Node2");
+        assert_eq!(
+            schema["$defs"]["Node2"]["properties"]["right"]["$ref"],
+            "#/$defs/Node2"
+        );
+    }
+
+    #[test]
+    fn repeated_types_share_one_definition() {
+        let schema = share_definitions(Schema::new(json!({
+            "type": "object",
+            "title": "Person",
+            "properties": {
+                "home": address(Some("An address")),
+                "work": address(Some("Where they work")),
+                "past": { "type": "array", "items": address(Some("An address")) },
+                "examples": { "type": "string", "enum": ["Address"] }
+            }
+        })))
+        .to_json();
+
+        assert_eq!(
+            schema["properties"]["home"],
+            json!({ "$ref": "#/$defs/Address", "description": "An address" })
+        );
+        assert_eq!(
+            schema["properties"]["work"],
+            json!({ "$ref": "#/$defs/Address", "description": "Where they work" })
+        );
+        assert_eq!(
+            schema["properties"]["past"]["items"]["$ref"],
+            "#/$defs/Address"
+        );
+        assert!(schema["$defs"]["Address"].get("description").is_none());
+        assert_eq!(schema["properties"]["examples"]["type"], "string");
+    }
+
+    #[test]
+    fn single_occurrences_stay_inline() {
+        let original = json!({
+            "type": "object",
+            "title": "Person",
+            "properties": { "home": address(None) }
+        });
+        let schema = share_definitions(Schema::new(original.clone())).to_json();
+        assert_eq!(schema, original);
+    }
+
+    #[test]
+    fn inline_round_trips_shared_types() {
+        let original = json!({
+            "type": "object",
+            "title": "Person",
+            "properties": { "home": address(None), "work": address(None) }
+        });
+        let shared = share_definitions(Schema::new(original.clone()));
+        assert!(shared.to_json().get("$defs").is_some());
+        assert_eq!(shared.inline().to_json(), original);
+    }
+
+    #[test]
+    fn inline_keeps_refs_that_close_a_cycle() {
+        let schema = Schema::new(json!({
+            "$defs": {
+                "Node": {
+                    "type": "object",
+                    "title": "Node",
+                    "properties": {
+                        "leaf": { "$ref": "#/$defs/Leaf" },
+                        "children": { "type": "array", "items": { "$ref": "#/$defs/Node" } }
+                    }
+                },
+                "Leaf": { "type": "object", "title": "Leaf", "properties": {} }
+            },
+            "$ref": "#/$defs/Node"
+        }));
+        let inlined = schema.inline().to_json();
+
+        assert_eq!(inlined["title"], "Node");
+        assert_eq!(inlined["properties"]["leaf"]["title"], "Leaf");
+        assert_eq!(
+            inlined["properties"]["children"]["items"]["$ref"],
+            "#/$defs/Node"
+        );
+        assert!(inlined["$defs"].get("Leaf").is_none());
+        assert_eq!(
+            inlined["$defs"]["Node"]["properties"]["leaf"]["title"],
+            "Leaf"
+        );
+    }
+
+    #[test]
+    fn inline_follows_draft07_definitions() {
+        let schema = Schema::new(json!({
+            "type": "object",
+            "properties": { "home": { "$ref": "#/definitions/Address" } },
+            "definitions": { "Address": address(None) }
+        }));
+        let inlined = schema.inline().to_json();
+        assert_eq!(inlined["properties"]["home"], address(None));
+        assert!(inlined.get("definitions").is_none());
+    }
+}
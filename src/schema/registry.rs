@@ -0,0 +1,169 @@
+//! A process-wide, read-only registry of `#[derive(Instructor)]` types, looked
+//! up by name at runtime.
+//!
+//! With the `registry` feature, the derive registers every non-generic type it
+//! is applied to (through `inventory`, so registration happens at link time and
+//! costs nothing at startup). A server can then accept a type name and a prompt
+//! and dispatch without knowing every type at compile time:
+//!
+//! ```no_run
+//! # #[cfg(feature = "openai")]
+//! # async fn run() -> rstructor::Result<()> {
+//! use rstructor::{DynLLMClient, OpenAIClient, SchemaRegistry};
+//!
+//! let client: Box<dyn DynLLMClient> = Box::new(OpenAIClient::from_env()?);
+//! // e.g. from an HTTP request body: {"type": "Invoice", "prompt": "..."}
+//! let entry = SchemaRegistry::get("Invoice")?;
+//! let invoice: serde_json::Value = entry.materialize(&*client, "Acme, $120 due May 1").await?;
+//! # let _ = invoice;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::any::Any;
+#[cfg(feature = "_client")]
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::error::{RStructorError, Result};
+use crate::model::Instructor;
+use crate::schema::Schema;
+
+/// A type in the [`SchemaRegistry`]: its schema, and a deserializer and
+/// validator for JSON values of it.
+pub struct RegisteredSchema {
+    name: &'static str,
+    type_name: fn() -> &'static str,
+    schema: fn() -> Schema,
+    deserialize: fn(Value) -> Result<Box<dyn Any>>,
+}
+
+inventory::collect!(RegisteredSchema);
+
+impl RegisteredSchema {
+    /// The entry for `T`, registered under `name`. Used by the derive's
+    /// registration; not meant to be called directly.
+    #[doc(hidden)]
+    pub const fn of<T: Instructor + 'static>(name: &'static str) -> Self {
+        Self {
+            name,
+            type_name: std::any::type_name::<T>,
+            schema: T::schema,
+            deserialize: deserialize::<T>,
+        }
+    }
+
+    /// The name the type is registered under: its schema name, which for a
+    /// derived type is the type's name.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The type's full path, such as `my_app::invoices::Invoice`.
+    pub fn type_name(&self) -> &'static str {
+        (self.type_name)()
+    }
+
+    /// The type's JSON Schema.
+    pub fn schema(&self) -> Schema {
+        (self.schema)()
+    }
+
+    /// Deserialize `value` into the type and run its
+    /// [`Instructor::validate`]. Downcast the result to the concrete type.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RStructorError::ValidationError`] if `value` doesn't
+    /// deserialize or fails validation.
+    pub fn deserialize(&self, value: Value) -> Result<Box<dyn Any>> {
+        (self.deserialize)(value)
+    }
+
+    /// Check that `value` deserializes into the type and passes its
+    /// [`Instructor::validate`].
+    pub fn validate(&self, value: &Value) -> Result<()> {
+        self.deserialize(value.clone()).map(drop)
+    }
+
+    /// [`validate`](Self::validate) as a [`ValueValidator`](crate::ValueValidator),
+    /// for [`DynLLMClient::materialize_value_validated`](crate::DynLLMClient::materialize_value_validated).
+    #[cfg(feature = "_client")]
+    pub fn validator(&'static self) -> crate::ValueValidator {
+        Arc::new(move |value| self.validate(value))
+    }
+
+    /// Materialize a value of the type through a type-erased client: the
+    /// type's schema is sent, and a reply that doesn't deserialize or
+    /// validate is re-asked as with a typed `materialize`.
+    #[cfg(feature = "_client")]
+    pub async fn materialize(
+        &'static self,
+        client: &dyn crate::DynLLMClient,
+        prompt: &str,
+    ) -> Result<Value> {
+        client
+            .materialize_value_validated(prompt, &self.schema(), self.validator())
+            .await
+    }
+}
+
+impl std::fmt::Debug for RegisteredSchema {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RegisteredSchema")
+            .field("name", &self.name)
+            .field("type_name", &self.type_name())
+            .finish_non_exhaustive()
+    }
+}
+
+fn deserialize<T: Instructor + 'static>(value: Value) -> Result<Box<dyn Any>> {
+    let data = T::deserialize(value).map_err(|e| RStructorError::ValidationError(e.to_string()))?;
+    data.validate()?;
+    Ok(Box::new(data))
+}
+
+/// The types registered by `#[derive(Instructor)]` under the `registry`
+/// feature, looked up by name.
+///
+/// Generic types are not registered, since a name alone doesn't say which
+/// instantiation is meant.
+#[derive(Debug, Clone, Copy)]
+pub struct SchemaRegistry;
+
+impl SchemaRegistry {
+    /// The type registered as `name`: its schema name (`"Invoice"`) or, to
+    /// tell apart types of the same name in different modules, its full path
+    /// (`"my_app::invoices::Invoice"`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RStructorError::SchemaError`] if no type is registered as
+    /// `name`, or if several are and the name doesn't say which.
+    pub fn get(name: &str) -> Result<&'static RegisteredSchema> {
+        let mut matches = Self::iter().filter(|entry| entry.name == name);
+        match (matches.next(), matches.next()) {
+            (Some(entry), None) => Ok(entry),
+            (Some(first), Some(second)) => {
+                let mut paths = vec![first.type_name(), second.type_name()];
+                paths.extend(matches.map(RegisteredSchema::type_name));
+                paths.sort_unstable();
+                Err(RStructorError::SchemaError(format!(
+                    "several registered types are named `{name}`; use one of their paths: {}",
+                    paths.join(", ")
+                )))
+            }
+            (None, _) => Self::iter()
+                .find(|entry| entry.type_name() == name)
+                .ok_or_else(|| {
+                    RStructorError::SchemaError(format!("no registered schema named `{name}`"))
+                }),
+        }
+    }
+
+    /// Every registered type, in no particular order.
+    pub fn iter() -> impl Iterator<Item = &'static RegisteredSchema> {
+        inventory::iter::<RegisteredSchema>.into_iter()
+    }
+}
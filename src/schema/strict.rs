@@ -0,0 +1,661 @@
+//! OpenAI strict-mode schema transformation.
+//!
+//! OpenAI's `json_schema` response format with `strict: true` accepts only a
+//! subset of JSON Schema: every object must list all of its properties in
+//! `required` and set `additionalProperties: false`, unions must use `anyOf`,
+//! and many validation keywords are rejected outright. [`Schema::to_openai_strict`]
+//! rewrites a derived schema into that subset.
+
+use serde_json::{Map, Value};
+
+use super::Schema;
+
+/// Keywords OpenAI strict mode accepts. Everything else is stripped.
+const SUPPORTED_KEYWORDS: &[&str] = &[
+    "type",
+    "description",
+    "title",
+    "properties",
+    "required",
+    "additionalProperties",
+    "items",
+    "enum",
+    "const",
+    "anyOf",
+    "$ref",
+    "$defs",
+    "definitions",
+    "pattern",
+    "format",
+    "minimum",
+    "maximum",
+    "exclusiveMinimum",
+    "exclusiveMaximum",
+    "multipleOf",
+    "minItems",
+    "maxItems",
+];
+
+/// String formats OpenAI strict mode accepts.
+const SUPPORTED_FORMATS: &[&str] = &[
+    "date-time",
+    "time",
+    "date",
+    "duration",
+    "email",
+    "hostname",
+    "ipv4",
+    "ipv6",
+    "uuid",
+];
+
+impl Schema {
+    /// Rewrite this schema for OpenAI's strict structured outputs.
+    ///
+    /// Applied recursively to every subschema (properties, array items, union
+    /// branches and `$defs`):
+    ///
+    /// - objects get `additionalProperties: false` and list every property in
+    ///   `required`; properties that were optional now also admit `null`
+    /// - `oneOf` becomes `anyOf` and `allOf` branches are merged into their parent
+    /// - tuples (`prefixItems` or an array-valued `items`) become an array whose
+    ///   items are `anyOf` the element schemas, with a fixed length
+    /// - unsupported keywords (`default`, `examples`, `minLength`, `uniqueItems`,
+    ///   `patternProperties`, ...) and unsupported string formats are removed
+    ///
+    /// Map types (objects with a schema-valued `additionalProperties`) cannot
+    /// be expressed in strict mode and are closed like any other object.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rstructor::Schema;
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::new(json!({
+    ///     "type": "object",
+    ///     "properties": {
+    ///         "name": { "type": "string", "minLength": 1 },
+    ///         "nickname": { "type": "string" }
+    ///     },
+    ///     "required": ["name"]
+    /// }));
+    ///
+    /// let strict = schema.to_openai_strict();
+    /// assert_eq!(strict["additionalProperties"], false);
+    /// assert_eq!(strict["required"], json!(["name", "nickname"]));
+    /// assert_eq!(strict["properties"]["nickname"]["type"], json!(["string", "null"]));
+    /// assert!(strict["properties"]["name"].get("minLength").is_none());
+    /// ```
+    pub fn to_openai_strict(&self) -> Value {
+        let mut schema = self.to_json();
+        super::locale::strip_translations(&mut schema);
+        make_strict(&mut schema);
+        schema
+    }
+}
+
+fn make_strict(schema: &mut Value) {
+    let Some(obj) = schema.as_object_mut() else {
+        return;
+    };
+
+    if let Some(one_of) = obj.remove("oneOf")
+        && !obj.contains_key("anyOf")
+    {
+        obj.insert("anyOf".to_string(), one_of);
+    }
+    if let Some(Value::Array(branches)) = obj.remove("allOf") {
+        for branch in branches {
+            if let Value::Object(branch) = branch {
+                merge_all_of_branch(obj, branch);
+            }
+        }
+    }
+    convert_tuple(obj);
+
+    obj.retain(|key, _| SUPPORTED_KEYWORDS.contains(&key.as_str()));
+    if obj
+        .get("format")
+        .and_then(Value::as_str)
+        .is_some_and(|format| !SUPPORTED_FORMATS.contains(&format))
+    {
+        obj.remove("format");
+    }
+
+    let is_object_type = match obj.get("type") {
+        Some(Value::String(t)) => t == "object",
+        Some(Value::Array(types)) => types.iter().any(|t| t.as_str() == Some("object")),
+        _ => false,
+    };
+    if is_object_type || obj.contains_key("properties") {
+        close_object(obj);
+    }
+
+    if let Some(items) = obj.get_mut("items") {
+        make_strict(items);
+    }
+    if let Some(Value::Array(branches)) = obj.get_mut("anyOf") {
+        branches.iter_mut().for_each(make_strict);
+    }
+    for key in ["$defs", "definitions"] {
+        if let Some(Value::Object(defs)) = obj.get_mut(key) {
+            defs.values_mut().for_each(make_strict);
+        }
+    }
+}
+
+/// Require every property and forbid extras, making originally optional
+/// properties nullable so the model can still omit a value.
+fn close_object(obj: &mut Map<String, Value>) {
+    let original_required: Vec<Value> = match obj.get("required") {
+        Some(Value::Array(required)) => required.clone(),
+        _ => Vec::new(),
+    };
+
+    let mut required = Vec::new();
+    if let Some(Value::Object(properties)) = obj.get_mut("properties") {
+        for (name, property) in properties.iter_mut() {
+            make_strict(property);
+            if !original_required.iter().any(|r| r.as_str() == Some(name)) {
+                make_schema_nullable(property);
+            }
+            required.push(Value::String(name.clone()));
+        }
+    }
+
+    obj.insert("required".to_string(), Value::Array(required));
+    obj.insert("additionalProperties".to_string(), Value::Bool(false));
+}
+
+/// Fold one `allOf` branch into its parent: properties and `required` are
+/// unioned, other keywords are taken from the branch only when the parent
+/// does not set them.
+fn merge_all_of_branch(obj: &mut Map<String, Value>, branch: Map<String, Value>) {
+    for (key, value) in branch {
+        match (key.as_str(), obj.get_mut(&key), value) {
+            ("properties", Some(Value::Object(existing)), Value::Object(added)) => {
+                for (name, property) in added {
+                    existing.entry(name).or_insert(property);
+                }
+            }
+            ("required", Some(Value::Array(existing)), Value::Array(added)) => {
+                for name in added {
+                    if !existing.contains(&name) {
+                        existing.push(name);
+                    }
+                }
+            }
+            (_, Some(_), _) => {}
+            (_, None, value) => {
+                obj.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Rewrite a tuple (`prefixItems`, or the legacy array form of `items`) as a
+/// fixed-length array of `anyOf` the element schemas.
+fn convert_tuple(obj: &mut Map<String, Value>) {
+    let elements = match obj.remove("prefixItems") {
+        Some(Value::Array(elements)) => elements,
+        _ => match obj.get("items") {
+            Some(Value::Array(elements)) => elements.clone(),
+            _ => return,
+        },
+    };
+
+    let len = Value::from(elements.len());
+    let items = if elements.len() == 1 {
+        elements.into_iter().next().unwrap()
+    } else {
+        serde_json::json!({ "anyOf": elements })
+    };
+    obj.insert("items".to_string(), items);
+    obj.insert("minItems".to_string(), len.clone());
+    obj.insert("maxItems".to_string(), len);
+}
+
+/// Returns true if a schema branch explicitly admits `null` via its `type` keyword.
+fn schema_branch_admits_null(branch: &Value) -> bool {
+    match branch.get("type") {
+        Some(Value::String(t)) => t == "null",
+        Some(Value::Array(types)) => types.iter().any(|t| t.as_str() == Some("null")),
+        _ => false,
+    }
+}
+
+/// Rewrite a property schema so that it also admits `null`.
+///
+/// Used for optional (`Option<T>`) fields under strict mode: strict structured
+/// outputs require every property to be listed in `required`, so optionality is
+/// expressed by allowing `null` instead. Handles the schema shapes this crate
+/// emits:
+///
+/// - `"type": "string"` (scalar) becomes `"type": ["string", "null"]`
+/// - `"type": [...]` (already a union) gets `"null"` appended if absent
+/// - an `"enum"` array gets `null` appended (JSON Schema `enum` constrains
+///   values independently of `type`)
+/// - `anyOf`/`oneOf` unions get a `{"type": "null"}` branch if none exists
+/// - a bare `$ref` (emitted for self-referential structs) is wrapped as
+///   `{"anyOf": [{"$ref": ...}, {"type": "null"}]}`
+///
+/// Schemas without any of these keywords (e.g. `{}`) already admit `null` and
+/// are left untouched.
+pub(crate) fn make_schema_nullable(schema: &mut Value) {
+    let Some(obj) = schema.as_object_mut() else {
+        return;
+    };
+
+    // Bare $ref: type/enum keywords cannot be reliably combined with $ref, so
+    // wrap the reference in an anyOf union with an explicit null branch.
+    if obj.contains_key("$ref") {
+        let original = Value::Object(std::mem::take(obj));
+        *schema = serde_json::json!({
+            "anyOf": [original, { "type": "null" }]
+        });
+        return;
+    }
+
+    // anyOf/oneOf unions: add a {"type": "null"} branch if no branch admits null.
+    for key in ["anyOf", "oneOf"] {
+        if let Some(branches) = obj.get_mut(key).and_then(|v| v.as_array_mut())
+            && !branches.iter().any(schema_branch_admits_null)
+        {
+            branches.push(serde_json::json!({ "type": "null" }));
+        }
+    }
+
+    // type keyword: scalar becomes a [type, "null"] union; existing unions get
+    // "null" appended if absent.
+    if let Some(type_value) = obj.get_mut("type") {
+        match type_value {
+            Value::String(t) if t != "null" => {
+                *type_value = serde_json::json!([t.clone(), "null"]);
+            }
+            Value::Array(types) if !types.iter().any(|t| t.as_str() == Some("null")) => {
+                types.push(serde_json::json!("null"));
+            }
+            _ => {}
+        }
+    }
+
+    // enum constrains allowed values independently of type, so null must also be
+    // listed as an allowed enum value.
+    if let Some(values) = obj.get_mut("enum").and_then(|e| e.as_array_mut())
+        && !values.iter().any(|v| v.is_null())
+    {
+        values.push(Value::Null);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn strict(schema: Value) -> Value {
+        Schema::new(schema).to_openai_strict()
+    }
+
+    #[test]
+    fn required_object_fields_stay_non_nullable() {
+        let out = strict(json!({
+            "type": "object",
+            "properties": { "name": { "type": "string" } },
+            "required": ["name"]
+        }));
+        assert_eq!(out["properties"]["name"]["type"], "string");
+        assert_eq!(out["required"], json!(["name"]));
+        assert_eq!(out["additionalProperties"], false);
+    }
+
+    #[test]
+    fn missing_required_makes_every_property_nullable() {
+        let out = strict(json!({
+            "type": "object",
+            "properties": {
+                "a": { "type": "integer" },
+                "b": { "type": ["string", "number"] }
+            }
+        }));
+        assert_eq!(out["required"], json!(["a", "b"]));
+        assert_eq!(out["properties"]["a"]["type"], json!(["integer", "null"]));
+        assert_eq!(
+            out["properties"]["b"]["type"],
+            json!(["string", "number", "null"])
+        );
+    }
+
+    #[test]
+    fn optional_enum_admits_null_value() {
+        let out = strict(json!({
+            "type": "object",
+            "properties": { "level": { "type": "string", "enum": ["low", "high"] } }
+        }));
+        assert_eq!(
+            out["properties"]["level"]["enum"],
+            json!(["low", "high", null])
+        );
+    }
+
+    #[test]
+    fn optional_ref_is_wrapped_in_any_of() {
+        let out = strict(json!({
+            "type": "object",
+            "properties": { "next": { "$ref": "#" } }
+        }));
+        assert_eq!(
+            out["properties"]["next"],
+            json!({ "anyOf": [{ "$ref": "#" }, { "type": "null" }] })
+        );
+    }
+
+    #[test]
+    fn empty_object_still_gets_required_and_closed() {
+        let out = strict(json!({ "type": "object" }));
+        assert_eq!(out["required"], json!([]));
+        assert_eq!(out["additionalProperties"], false);
+    }
+
+    #[test]
+    fn nested_objects_and_array_items_are_closed() {
+        let out = strict(json!({
+            "type": "object",
+            "properties": {
+                "address": {
+                    "type": "object",
+                    "properties": { "city": { "type": "string" } },
+                    "required": ["city"]
+                },
+                "tags": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": { "label": { "type": "string" } },
+                        "required": ["label"]
+                    }
+                }
+            },
+            "required": ["address", "tags"]
+        }));
+        let address = &out["properties"]["address"];
+        assert_eq!(address["additionalProperties"], false);
+        assert_eq!(address["required"], json!(["city"]));
+        let item = &out["properties"]["tags"]["items"];
+        assert_eq!(item["additionalProperties"], false);
+        assert_eq!(item["required"], json!(["label"]));
+    }
+
+    #[test]
+    fn optional_nested_object_is_closed_and_nullable() {
+        let out = strict(json!({
+            "type": "object",
+            "properties": {
+                "meta": {
+                    "type": "object",
+                    "properties": { "note": { "type": "string" } }
+                }
+            }
+        }));
+        let meta = &out["properties"]["meta"];
+        assert_eq!(meta["type"], json!(["object", "null"]));
+        assert_eq!(meta["additionalProperties"], false);
+        assert_eq!(
+            meta["properties"]["note"]["type"],
+            json!(["string", "null"])
+        );
+    }
+
+    #[test]
+    fn map_schemas_are_closed() {
+        let out = strict(json!({
+            "type": "object",
+            "additionalProperties": { "type": "integer" }
+        }));
+        assert_eq!(out["additionalProperties"], false);
+    }
+
+    #[test]
+    fn one_of_becomes_any_of_with_strict_branches() {
+        let out = strict(json!({
+            "oneOf": [
+                {
+                    "type": "object",
+                    "properties": { "kind": { "const": "circle" }, "radius": { "type": "number" } },
+                    "required": ["kind", "radius"]
+                },
+                { "type": "string", "enum": ["none"] }
+            ]
+        }));
+        assert!(out.get("oneOf").is_none());
+        let branches = out["anyOf"].as_array().unwrap();
+        assert_eq!(branches.len(), 2);
+        assert_eq!(branches[0]["additionalProperties"], false);
+        assert_eq!(branches[0]["properties"]["kind"]["const"], "circle");
+        assert_eq!(branches[1]["enum"], json!(["none"]));
+    }
+
+    #[test]
+    fn existing_any_of_takes_precedence_over_one_of() {
+        let out = strict(json!({
+            "anyOf": [{ "type": "string" }],
+            "oneOf": [{ "type": "integer" }]
+        }));
+        assert_eq!(out, json!({ "anyOf": [{ "type": "string" }] }));
+    }
+
+    #[test]
+    fn optional_union_gets_null_branch() {
+        let out = strict(json!({
+            "type": "object",
+            "properties": {
+                "value": { "oneOf": [{ "type": "string" }, { "type": "integer" }] }
+            }
+        }));
+        assert_eq!(
+            out["properties"]["value"]["anyOf"],
+            json!([{ "type": "string" }, { "type": "integer" }, { "type": "null" }])
+        );
+    }
+
+    #[test]
+    fn all_of_branches_are_merged() {
+        let out = strict(json!({
+            "description": "Employee",
+            "allOf": [
+                {
+                    "type": "object",
+                    "properties": { "name": { "type": "string" } },
+                    "required": ["name"]
+                },
+                {
+                    "properties": { "salary": { "type": "number" } },
+                    "required": ["salary"],
+                    "description": "ignored, parent wins"
+                }
+            ]
+        }));
+        assert!(out.get("allOf").is_none());
+        assert_eq!(out["description"], "Employee");
+        assert_eq!(out["type"], "object");
+        assert_eq!(out["required"], json!(["name", "salary"]));
+        assert_eq!(out["properties"]["salary"]["type"], "number");
+        assert_eq!(out["additionalProperties"], false);
+    }
+
+    #[test]
+    fn unsupported_keywords_are_stripped_everywhere() {
+        let out = strict(json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "type": "object",
+            "title": "Signup",
+            "properties": {
+                "username": {
+                    "type": "string",
+                    "minLength": 3,
+                    "maxLength": 20,
+                    "pattern": "^[a-z]+$",
+                    "default": "anon",
+                    "examples": ["alice"]
+                },
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string", "examples": ["x"] },
+                    "uniqueItems": true,
+                    "minItems": 1
+                },
+                "age": { "type": "integer", "minimum": 13, "not": { "const": 99 } }
+            },
+            "required": ["username", "tags", "age"],
+            "patternProperties": { "^x-": { "type": "string" } },
+            "minProperties": 1,
+            "if": { "properties": { "age": { "const": 13 } } },
+            "then": { "required": ["guardian"] }
+        }));
+        assert_eq!(
+            out,
+            json!({
+                "type": "object",
+                "title": "Signup",
+                "properties": {
+                    "username": { "type": "string", "pattern": "^[a-z]+$" },
+                    "tags": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "minItems": 1
+                    },
+                    "age": { "type": "integer", "minimum": 13 }
+                },
+                "required": ["username", "tags", "age"],
+                "additionalProperties": false
+            })
+        );
+    }
+
+    #[test]
+    fn only_supported_formats_are_kept() {
+        let out = strict(json!({
+            "type": "object",
+            "properties": {
+                "at": { "type": "string", "format": "date-time" },
+                "site": { "type": "string", "format": "uri" }
+            },
+            "required": ["at", "site"]
+        }));
+        assert_eq!(out["properties"]["at"]["format"], "date-time");
+        assert!(out["properties"]["site"].get("format").is_none());
+    }
+
+    #[test]
+    fn prefix_items_tuple_becomes_fixed_length_array() {
+        let out = strict(json!({
+            "type": "array",
+            "prefixItems": [{ "type": "string" }, { "type": "integer" }]
+        }));
+        assert!(out.get("prefixItems").is_none());
+        assert_eq!(
+            out["items"],
+            json!({ "anyOf": [{ "type": "string" }, { "type": "integer" }] })
+        );
+        assert_eq!(out["minItems"], 2);
+        assert_eq!(out["maxItems"], 2);
+    }
+
+    #[test]
+    fn array_form_items_tuple_is_converted() {
+        let out = strict(json!({
+            "type": "array",
+            "items": [{ "type": "object", "properties": { "x": { "type": "number" } } }]
+        }));
+        assert_eq!(out["items"]["additionalProperties"], false);
+        assert_eq!(out["minItems"], 1);
+        assert_eq!(out["maxItems"], 1);
+    }
+
+    #[test]
+    fn definitions_are_made_strict() {
+        let out = strict(json!({
+            "type": "object",
+            "properties": { "child": { "$ref": "#/$defs/Node" } },
+            "required": ["child"],
+            "$defs": {
+                "Node": {
+                    "type": "object",
+                    "properties": { "value": { "type": "integer", "default": 0 } }
+                }
+            }
+        }));
+        let node = &out["$defs"]["Node"];
+        assert_eq!(node["additionalProperties"], false);
+        assert_eq!(node["required"], json!(["value"]));
+        assert_eq!(
+            node["properties"]["value"],
+            json!({ "type": ["integer", "null"] })
+        );
+        assert_eq!(
+            out["properties"]["child"],
+            json!({ "$ref": "#/$defs/Node" })
+        );
+    }
+
+    #[test]
+    fn property_named_like_a_keyword_is_kept() {
+        let out = strict(json!({
+            "type": "object",
+            "properties": {
+                "default": { "type": "string" },
+                "examples": { "type": "array", "items": { "type": "string" } }
+            },
+            "required": ["default", "examples"]
+        }));
+        assert_eq!(out["required"], json!(["default", "examples"]));
+        assert_eq!(out["properties"]["default"]["type"], "string");
+    }
+
+    #[test]
+    fn transformation_is_idempotent() {
+        let once = strict(json!({
+            "type": "object",
+            "properties": {
+                "a": { "type": "string" },
+                "b": { "oneOf": [{ "type": "object", "properties": {} }, { "type": "null" }] }
+            },
+            "required": ["a"]
+        }));
+        let twice = Schema::new(once.clone()).to_openai_strict();
+        assert_eq!(once, twice);
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn derived_schema_is_strict() {
+        use crate::SchemaType;
+        use std::collections::HashMap;
+
+        #[allow(dead_code)]
+        #[derive(crate::Instructor, serde::Serialize, serde::Deserialize)]
+        struct Line {
+            sku: String,
+            qty: Option<u32>,
+        }
+
+        #[allow(dead_code)]
+        #[derive(crate::Instructor, serde::Serialize, serde::Deserialize)]
+        struct Order {
+            lines: Vec<Line>,
+            note: Option<String>,
+            attrs: HashMap<String, String>,
+        }
+
+        let out = Order::schema().to_openai_strict();
+        assert_eq!(out["additionalProperties"], false);
+        assert_eq!(out["required"], json!(["lines", "note", "attrs"]));
+        let line = &out["properties"]["lines"]["items"];
+        assert_eq!(line["required"], json!(["sku", "qty"]));
+        assert_eq!(
+            line["properties"]["qty"]["type"],
+            json!(["integer", "null"])
+        );
+    }
+}
@@ -82,3 +82,44 @@ fn test_schema_builder() {
     assert!(required.iter().any(|v| v == "name"));
     assert!(required.iter().any(|v| v == "age"));
 }
+
+#[test]
+fn test_canonical_string_sorts_nested_keys_and_keeps_arrays() {
+    let schema = Schema::new(json!({
+        "type": "object",
+        "properties": {
+            "zeta": { "type": "string", "description": "z" },
+            "alpha": { "enum": ["b", "a"], "type": "string" }
+        },
+        "required": ["zeta", "alpha"]
+    }));
+    let expected = r#"{
+  "properties": {
+    "alpha": {
+      "enum": [
+        "b",
+        "a"
+      ],
+      "type": "string"
+    },
+    "zeta": {
+      "description": "z",
+      "type": "string"
+    }
+  },
+  "required": [
+    "zeta",
+    "alpha"
+  ],
+  "type": "object"
+}"#;
+    assert_eq!(schema.to_canonical_string(), expected);
+    // Declaration order is untouched in the prompt-facing JSON.
+    let keys: Vec<_> = schema.to_json()["properties"]
+        .as_object()
+        .unwrap()
+        .keys()
+        .cloned()
+        .collect();
+    assert_eq!(keys, ["zeta", "alpha"]);
+}
@@ -0,0 +1,228 @@
+//! Finding properties a model returned that the schema does not declare.
+//!
+//! Serde silently ignores undeclared fields, which hides useful signal while a
+//! schema is still being iterated on: models often volunteer data the schema
+//! did not ask for. [`Schema::unknown_fields`] lists those fields, and clients
+//! configured with `capture_unknown_fields(true)` surface them in
+//! [`MaterializeResult::extra_fields`](crate::MaterializeResult::extra_fields).
+
+use serde_json::{Map, Value};
+
+use super::Schema;
+
+/// Nesting depth beyond which the walk stops (guards recursive `$ref`s).
+const MAX_DEPTH: usize = 64;
+
+impl Schema {
+    /// Properties in `instance` that this schema does not declare, keyed by
+    /// JSON Pointer into `instance` (e.g. `/address/zip` or `/items/1/note`).
+    ///
+    /// `$ref`s are followed, objects matching any branch of an
+    /// `anyOf`/`oneOf`/`allOf` may use the properties of every branch, and
+    /// map-like objects (`additionalProperties` set to a schema) accept any key.
+    ///
+    /// ```
+    /// use rstructor::Schema;
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::new(json!({
+    ///     "type": "object",
+    ///     "properties": { "name": { "type": "string" } }
+    /// }));
+    /// let extra = schema.unknown_fields(&json!({ "name": "Ada", "born": 1815 }));
+    /// assert_eq!(extra["/born"], json!(1815));
+    /// ```
+    pub fn unknown_fields(&self, instance: &Value) -> Map<String, Value> {
+        let mut unknown = Map::new();
+        collect(
+            &self.schema,
+            &self.schema,
+            instance,
+            &mut String::new(),
+            0,
+            &mut unknown,
+        );
+        unknown
+    }
+}
+
+/// [`Schema::unknown_fields`] of the JSON in a raw model reply (which may be
+/// wrapped in a markdown fence). Empty if the reply holds no parseable JSON.
+#[cfg(any(feature = "_client", feature = "mock"))]
+pub(crate) fn unknown_fields_in_reply(schema: &Schema, reply: &str) -> Map<String, Value> {
+    serde_json::from_str(crate::parsing::extract_json_from_markdown(reply))
+        .map(|instance| schema.unknown_fields(&instance))
+        .unwrap_or_default()
+}
+
+fn collect(
+    root: &Value,
+    schema: &Value,
+    instance: &Value,
+    path: &mut String,
+    depth: usize,
+    unknown: &mut Map<String, Value>,
+) {
+    if depth > MAX_DEPTH {
+        return;
+    }
+    let mut branches = Vec::new();
+    flatten_branches(root, schema, 0, &mut branches);
+
+    match instance {
+        Value::Object(object) => {
+            let closed = branches.iter().any(|b| b.get("properties").is_some());
+            let value_schema = branches
+                .iter()
+                .find_map(|b| b.get("additionalProperties").filter(|s| s.is_object()));
+            for (key, value) in object {
+                let declared = branches
+                    .iter()
+                    .find_map(|b| b.get("properties").and_then(|p| p.get(key)))
+                    .or(value_schema);
+                let len = path.len();
+                path.push('/');
+                push_escaped(path, key);
+                match declared {
+                    Some(sub) => collect(root, sub, value, path, depth + 1, unknown),
+                    None if closed => {
+                        unknown.insert(path.clone(), value.clone());
+                    }
+                    None => {}
+                }
+                path.truncate(len);
+            }
+        }
+        Value::Array(items) => {
+            let Some(item_schema) = branches
+                .iter()
+                .find_map(|b| b.get("items").filter(|s| s.is_object()))
+            else {
+                return;
+            };
+            for (index, item) in items.iter().enumerate() {
+                let len = path.len();
+                path.push('/');
+                path.push_str(&index.to_string());
+                collect(root, item_schema, item, path, depth + 1, unknown);
+                path.truncate(len);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// `schema` with `$ref`s resolved, followed by every nested
+/// `anyOf`/`oneOf`/`allOf` branch.
+pub(super) fn flatten_branches<'a>(
+    root: &'a Value,
+    schema: &'a Value,
+    depth: usize,
+    out: &mut Vec<&'a Value>,
+) {
+    if depth > MAX_DEPTH {
+        return;
+    }
+    let schema = match schema.get("$ref").and_then(Value::as_str) {
+        Some(reference) => match reference.strip_prefix('#').and_then(|p| root.pointer(p)) {
+            Some(target) => return flatten_branches(root, target, depth + 1, out),
+            None => return,
+        },
+        None => schema,
+    };
+    out.push(schema);
+    for keyword in ["anyOf", "oneOf", "allOf"] {
+        if let Some(branches) = schema.get(keyword).and_then(Value::as_array) {
+            for branch in branches {
+                flatten_branches(root, branch, depth + 1, out);
+            }
+        }
+    }
+}
+
+/// Append `token` to a JSON Pointer, escaping `~` and `/` per RFC 6901.
+fn push_escaped(path: &mut String, token: &str) {
+    for c in token.chars() {
+        match c {
+            '~' => path.push_str("~0"),
+            '/' => path.push_str("~1"),
+            c => path.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reports_nested_and_array_item_extras() {
+        let schema = Schema::new(json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "address": { "$ref": "#/$defs/Address" },
+                "items": { "type": "array", "items": {
+                    "type": "object",
+                    "properties": { "sku": { "type": "string" } }
+                }}
+            },
+            "$defs": {
+                "Address": { "type": "object", "properties": { "city": { "type": "string" } } }
+            }
+        }));
+        let extra = schema.unknown_fields(&json!({
+            "name": "Ada",
+            "nickname": "Countess",
+            "address": { "city": "London", "zip": "W1" },
+            "items": [{ "sku": "a" }, { "sku": "b", "note": "gift" }]
+        }));
+        assert_eq!(
+            extra,
+            json!({
+                "/nickname": "Countess",
+                "/address/zip": "W1",
+                "/items/1/note": "gift"
+            })
+            .as_object()
+            .unwrap()
+            .clone()
+        );
+    }
+
+    #[test]
+    fn union_branches_and_maps_are_not_reported() {
+        let schema = Schema::new(json!({
+            "type": "object",
+            "properties": {
+                "shape": { "oneOf": [
+                    { "type": "object", "properties": { "radius": { "type": "number" } } },
+                    { "type": "object", "properties": { "width": { "type": "number" } } }
+                ]},
+                "counts": { "type": "object", "additionalProperties": { "type": "integer" } }
+            }
+        }));
+        let extra = schema.unknown_fields(&json!({
+            "shape": { "width": 3, "depth": 1 },
+            "counts": { "chairs": 5 }
+        }));
+        assert_eq!(extra.keys().collect::<Vec<_>>(), ["/shape/depth"]);
+    }
+
+    #[cfg(any(feature = "_client", feature = "mock"))]
+    #[test]
+    fn reply_in_markdown_fence_is_parsed() {
+        let schema = Schema::new(json!({ "type": "object", "properties": { "a": {} } }));
+        let extra = unknown_fields_in_reply(&schema, "```json\n{\"a\": 1, \"b\": 2}\n```");
+        assert_eq!(extra["/b"], json!(2));
+        assert!(unknown_fields_in_reply(&schema, "not json").is_empty());
+    }
+
+    #[test]
+    fn pointer_tokens_are_escaped() {
+        let schema = Schema::new(json!({ "type": "object", "properties": {} }));
+        let extra = schema.unknown_fields(&json!({ "a/b~c": 1 }));
+        assert!(extra.contains_key("/a~1b~0c"));
+    }
+}
@@ -0,0 +1,43 @@
+//! Test doubles for code that calls an LLM.
+//!
+//! [`MockClient`] implements [`LLMClient`](crate::LLMClient) in memory:
+//! script canned JSON replies or a sequence of them (an invalid first attempt
+//! followed by a valid re-ask, say), add simulated latency, and assert on every
+//! prompt and schema it was sent. The same types are also exported at the
+//! crate root.
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! use rstructor::testing::{MockClient, RequestKind};
+//! use rstructor::{Instructor, LLMClient};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Instructor, Serialize, Deserialize)]
+//! struct Movie {
+//!     title: String,
+//!     year: u16,
+//! }
+//!
+//! # #[tokio::main]
+//! # async fn main() -> rstructor::Result<()> {
+//! let client = MockClient::new()
+//!     .with_response("not json")
+//!     .with_response(r#"{"title": "Inception", "year": 2010}"#)
+//!     .with_retries(1)
+//!     .with_latency(Duration::from_millis(5));
+//!
+//! let movie: Movie = client.materialize("Describe Inception").await?;
+//! assert_eq!(movie.year, 2010);
+//!
+//! let request = client.last_request().unwrap();
+//! assert_eq!(request.kind, RequestKind::Materialize);
+//! assert_eq!(request.schema.unwrap()["title"], "Movie");
+//! assert!(client.responses_exhausted());
+//! # Ok(())
+//! # }
+//! ```
+
+pub use crate::backend::mock::{
+    MockClient, MockRequestView, MockResponse, RecordedRequest, RequestKind,
+};
@@ -0,0 +1,72 @@
+//! The adaptive (AIMD) concurrency limit reacting to real responses. Lives in
+//! its own test binary because the limit is global state shared by every
+//! client.
+#![cfg(feature = "openai")]
+
+use rstructor::{
+    AdaptiveConcurrency, ApiErrorKind, LLMClient, OpenAIClient, Provider, clear_concurrency_limit,
+    concurrency_limit, concurrency_metrics, set_adaptive_concurrency_limit,
+};
+use serde_json::json;
+
+#[tokio::test]
+async fn rate_limits_shrink_the_limit_and_successes_grow_it_back() {
+    assert!(
+        set_adaptive_concurrency_limit(Provider::OpenAI, AdaptiveConcurrency::new(0, 4)).is_err()
+    );
+    set_adaptive_concurrency_limit(Provider::OpenAI, AdaptiveConcurrency::new(1, 4)).unwrap();
+    assert_eq!(concurrency_limit(Provider::OpenAI), Some(4));
+
+    let mut server = mockito::Server::new_async().await;
+    let limited = server
+        .mock("POST", "/chat/completions")
+        .with_status(429)
+        .with_header("content-type", "application/json")
+        .with_body(json!({ "error": { "message": "slow down" } }).to_string())
+        .create_async()
+        .await;
+    let client = OpenAIClient::new("test-key")
+        .unwrap()
+        .base_url(server.url())
+        .no_retries();
+
+    for expected in [2, 1, 1] {
+        let err = client.generate("hi").await.unwrap_err();
+        assert!(
+            matches!(err.api_error_kind(), Some(ApiErrorKind::RateLimited { .. })),
+            "{err:?}"
+        );
+        assert_eq!(concurrency_limit(Provider::OpenAI), Some(expected));
+    }
+    let metrics = concurrency_metrics(Provider::OpenAI).unwrap();
+    assert_eq!(
+        (metrics.in_flight, metrics.waiting, metrics.decreases),
+        (0, 0, 3)
+    );
+
+    limited.remove_async().await;
+    server
+        .mock("POST", "/chat/completions")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({
+                "choices": [{
+                    "message": { "role": "assistant", "content": "hello" },
+                    "finish_reason": "stop"
+                }]
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+
+    // One success at limit 1, then two at limit 2.
+    for expected in [2, 2, 3] {
+        client.generate("hi").await.unwrap();
+        assert_eq!(concurrency_limit(Provider::OpenAI), Some(expected));
+    }
+
+    clear_concurrency_limit(Provider::OpenAI);
+    assert_eq!(concurrency_metrics(Provider::OpenAI), None);
+}
@@ -0,0 +1,262 @@
+//! Drive the real `AnthropicClient` over a local mock HTTP server (`mockito`),
+//! covering the request shape and response parsing of each structured-output
+//! strategy. No API key or network needed.
+#![cfg(feature = "anthropic")]
+
+use rstructor::{AnthropicClient, Instructor, LLMClient, OutputStrategy, RStructorError};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Instructor, Serialize, Deserialize, Debug, PartialEq)]
+struct Movie {
+    title: String,
+    year: u16,
+}
+
+fn client(server: &mockito::Server) -> AnthropicClient {
+    AnthropicClient::new("test-key")
+        .unwrap()
+        .base_url(server.url())
+        .no_retries()
+}
+
+#[tokio::test]
+async fn tool_calling_forces_the_tool_and_reads_its_input() {
+    let mut server = mockito::Server::new_async().await;
+    let m = server
+        .mock("POST", "/messages")
+        .match_header("anthropic-beta", mockito::Matcher::Missing)
+        .match_body(mockito::Matcher::PartialJson(json!({
+            "tools": [{ "name": "structured_output" }],
+            "tool_choice": { "type": "tool", "name": "structured_output" }
+        })))
+        .with_status(200)
+        .with_body(
+            json!({
+                "content": [{
+                    "type": "tool_use",
+                    "id": "toolu_1",
+                    "name": "structured_output",
+                    "input": { "title": "Metropolis", "year": 1927 }
+                }],
+                "stop_reason": "tool_use",
+                "usage": { "input_tokens": 10, "output_tokens": 5 }
+            })
+            .to_string(),
+        )
+        .expect(1)
+        .create_async()
+        .await;
+
+    let movie: Movie = client(&server)
+        .output_strategy(OutputStrategy::ToolCalling)
+        .materialize("Metropolis")
+        .await
+        .unwrap();
+    assert_eq!(
+        movie,
+        Movie {
+            title: "Metropolis".into(),
+            year: 1927
+        }
+    );
+    m.assert_async().await;
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug, PartialEq)]
+struct Credit {
+    #[serde(rename = "full name")]
+    full_name: String,
+}
+
+#[tokio::test]
+async fn tool_calling_sends_safe_property_names_and_restores_them() {
+    let mut server = mockito::Server::new_async().await;
+    let m = server
+        .mock("POST", "/messages")
+        .match_body(mockito::Matcher::PartialJson(json!({
+            "tools": [{
+                "name": "structured_output",
+                "input_schema": {
+                    "properties": { "full_name": { "type": "string" } },
+                    "required": ["full_name"]
+                }
+            }]
+        })))
+        .with_status(200)
+        .with_body(
+            json!({
+                "content": [{
+                    "type": "tool_use",
+                    "id": "toolu_1",
+                    "name": "structured_output",
+                    "input": { "full_name": "Fritz Lang" }
+                }],
+                "stop_reason": "tool_use"
+            })
+            .to_string(),
+        )
+        .expect(1)
+        .create_async()
+        .await;
+
+    let credit: Credit = client(&server)
+        .output_strategy(OutputStrategy::ToolCalling)
+        .materialize("Metropolis")
+        .await
+        .unwrap();
+    assert_eq!(credit.full_name, "Fritz Lang");
+    m.assert_async().await;
+}
+
+#[tokio::test]
+async fn json_schema_strategy_uses_native_output_format() {
+    let mut server = mockito::Server::new_async().await;
+    let m = server
+        .mock("POST", "/messages")
+        .match_header("anthropic-beta", "structured-outputs-2025-11-13")
+        .match_body(mockito::Matcher::PartialJson(
+            json!({ "output_format": { "type": "json_schema" } }),
+        ))
+        .with_status(200)
+        .with_body(
+            json!({
+                "content": [{ "type": "text", "text": "{\"title\":\"Alien\",\"year\":1979}" }],
+                "stop_reason": "end_turn"
+            })
+            .to_string(),
+        )
+        .expect(1)
+        .create_async()
+        .await;
+
+    let movie: Movie = client(&server).materialize("Alien").await.unwrap();
+    assert_eq!(movie.year, 1979);
+    m.assert_async().await;
+}
+
+#[tokio::test]
+async fn json_mode_is_unsupported() {
+    let server = mockito::Server::new_async().await;
+    let err = client(&server)
+        .output_strategy(OutputStrategy::JsonMode)
+        .materialize::<Movie>("Alien")
+        .await
+        .unwrap_err();
+    assert!(matches!(err, RStructorError::Unsupported(_)));
+}
+
+#[tokio::test]
+async fn usage_counts_cached_prompt_tokens_as_input() {
+    let mut server = mockito::Server::new_async().await;
+    let m = server
+        .mock("POST", "/messages")
+        .with_status(200)
+        .with_body(
+            json!({
+                "content": [{ "type": "text", "text": "{\"title\":\"Alien\",\"year\":1979}" }],
+                "model": "claude-sonnet-4-6",
+                "stop_reason": "end_turn",
+                "usage": {
+                    "input_tokens": 12,
+                    "cache_creation_input_tokens": 300,
+                    "cache_read_input_tokens": 2000,
+                    "output_tokens": 9
+                }
+            })
+            .to_string(),
+        )
+        .expect(2)
+        .create_async()
+        .await;
+
+    let result = client(&server)
+        .materialize_with_metadata::<Movie>("Alien")
+        .await
+        .unwrap();
+    assert_eq!(result.data.year, 1979);
+    let usage = result.usage.unwrap();
+    assert_eq!(
+        (
+            usage.model.as_str(),
+            usage.input_tokens,
+            usage.output_tokens
+        ),
+        ("claude-sonnet-4-6", 2312, 9)
+    );
+
+    let text = client(&server)
+        .generate_with_metadata("Alien")
+        .await
+        .unwrap();
+    assert_eq!(text.usage.unwrap().total_tokens(), 2321);
+    m.assert_async().await;
+}
+
+#[tokio::test]
+async fn materialize_with_media_sends_a_base64_image_block() {
+    let mut server = mockito::Server::new_async().await;
+    let m = server
+        .mock("POST", "/messages")
+        .match_body(mockito::Matcher::Regex(
+            r#"\{"type":"image","source":\{"type":"base64","media_type":"image/png","data":"YWJj"\}\}"#
+                .into(),
+        ))
+        .with_status(200)
+        .with_body(
+            json!({
+                "content": [{ "type": "text", "text": "{\"title\":\"Alien\",\"year\":1979}" }],
+                "stop_reason": "end_turn"
+            })
+            .to_string(),
+        )
+        .expect(1)
+        .create_async()
+        .await;
+
+    let media = [rstructor::MediaFile::from_bytes(b"abc", "image/png")];
+    let movie: Movie = client(&server)
+        .materialize_with_media("Which movie is this poster for?", &media)
+        .await
+        .unwrap();
+    assert_eq!(movie.year, 1979);
+    m.assert_async().await;
+}
+
+#[tokio::test]
+async fn overloaded_529_is_classified_and_retried() {
+    let mut server = mockito::Server::new_async().await;
+    let overloaded = server
+        .mock("POST", "/messages")
+        .with_status(529)
+        .with_header("retry-after", "0")
+        .with_body(
+            r#"{"type": "error", "error": {"type": "overloaded_error", "message": "Overloaded"}}"#,
+        )
+        .expect(3)
+        .create_async()
+        .await;
+
+    let err = client(&server)
+        .materialize::<Movie>("Alien")
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.api_error_kind(),
+        Some(&rstructor::ApiErrorKind::Overloaded {
+            retry_after: Some(std::time::Duration::ZERO)
+        })
+    );
+    assert!(err.is_retryable());
+
+    let retried = client(&server)
+        .max_retries(1)
+        .materialize::<Movie>("Alien")
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        retried.api_error_kind(),
+        Some(rstructor::ApiErrorKind::Overloaded { .. })
+    ));
+    overloaded.assert_async().await;
+}
@@ -211,5 +211,25 @@ fn anyclient_from_env_detection_precedence_and_per_provider() {
     let gemini: AnyClient = rstructor::GeminiClient::from_env().unwrap().into();
     assert_eq!(gemini.provider(), Provider::Gemini);
 
+    // --- client_from_str: "provider:model" reads the same key variables ---
+
+    set_only(&["OPENAI_API_KEY"]);
+    let client = rstructor::client_from_str("openai:gpt-4o").unwrap();
+    assert_eq!(client.model_id().provider, Provider::OpenAI);
+    assert_eq!(client.model_id().name, "gpt-4o");
+
+    set_only(&["ANTHROPIC_API_KEY"]);
+    let client: AnyClient = "claude:claude-sonnet-4-5".parse().unwrap();
+    assert_eq!(client.provider(), Provider::Anthropic);
+    assert_eq!(client.model_id().name, "claude-sonnet-4-5");
+
+    match rstructor::client_from_str("gemini:gemini-2.5-flash") {
+        Ok(_) => panic!("client_from_str must fail when the provider's key is absent"),
+        Err(err) => assert!(matches!(
+            err.api_error_kind(),
+            Some(ApiErrorKind::AuthenticationFailed)
+        )),
+    }
+
     // `_guard` restores the original environment here.
 }
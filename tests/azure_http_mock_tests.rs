@@ -0,0 +1,158 @@
+//! Drive the real `AzureOpenAIClient` over a local mock HTTP server (`mockito`),
+//! covering deployment routing, the `api-version` query and `api-key` auth.
+//! No Azure resource needed.
+#![cfg(feature = "azure")]
+
+use rstructor::{
+    AzureOpenAIClient, ContextOverflow, DEFAULT_AZURE_API_VERSION, Instructor, LLMClient,
+    OpenAIModel,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Instructor, Serialize, Deserialize, Debug, PartialEq)]
+struct Movie {
+    title: String,
+    year: u16,
+}
+
+fn client(server: &mockito::Server) -> AzureOpenAIClient {
+    AzureOpenAIClient::new(server.url(), "azure-key")
+        .unwrap()
+        .model(OpenAIModel::Gpt41Mini)
+        .no_retries()
+}
+
+fn completion(content: &str) -> String {
+    json!({
+        "model": "gpt-4.1-mini",
+        "choices": [{
+            "index": 0,
+            "message": { "role": "assistant", "content": content },
+            "finish_reason": "stop"
+        }],
+        "usage": { "prompt_tokens": 9, "completion_tokens": 4 }
+    })
+    .to_string()
+}
+
+#[tokio::test]
+async fn materialize_routes_to_the_mapped_deployment() {
+    let mut server = mockito::Server::new_async().await;
+    let m = server
+        .mock(
+            "POST",
+            "/openai/deployments/extraction-prod/chat/completions",
+        )
+        .match_query(mockito::Matcher::UrlEncoded(
+            "api-version".into(),
+            DEFAULT_AZURE_API_VERSION.into(),
+        ))
+        .match_header("api-key", "azure-key")
+        .match_header("authorization", mockito::Matcher::Missing)
+        .match_body(mockito::Matcher::PartialJson(json!({
+            "response_format": { "type": "json_schema" }
+        })))
+        .with_status(200)
+        .with_body(completion("{\"title\":\"Metropolis\",\"year\":1927}"))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let result = client(&server)
+        .deployment_for(OpenAIModel::Gpt41Mini, "extraction-prod")
+        .materialize_with_metadata::<Movie>("Metropolis")
+        .await
+        .unwrap();
+    assert_eq!(result.data.year, 1927);
+    let usage = result.usage.unwrap();
+    assert_eq!((usage.input_tokens, usage.output_tokens), (9, 4));
+    m.assert_async().await;
+}
+
+#[tokio::test]
+async fn unmapped_model_uses_its_name_and_custom_api_version() {
+    let mut server = mockito::Server::new_async().await;
+    let m = server
+        .mock("POST", "/openai/deployments/gpt-4.1-mini/chat/completions")
+        .match_query(mockito::Matcher::UrlEncoded(
+            "api-version".into(),
+            "2025-01-01-preview".into(),
+        ))
+        .with_status(200)
+        .with_body(completion("Hello"))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let text = client(&server)
+        .api_version("2025-01-01-preview")
+        .generate("Say hello")
+        .await
+        .unwrap();
+    assert_eq!(text, "Hello");
+    m.assert_async().await;
+}
+
+#[tokio::test]
+async fn escalation_follows_the_larger_models_deployment() {
+    let mut server = mockito::Server::new_async().await;
+    let small = server
+        .mock("POST", "/openai/deployments/small/chat/completions")
+        .match_query(mockito::Matcher::Any)
+        .with_status(400)
+        .with_body(
+            json!({
+                "error": {
+                    "code": "context_length_exceeded",
+                    "message": "This model's maximum context length is 128000 tokens."
+                }
+            })
+            .to_string(),
+        )
+        .expect(1)
+        .create_async()
+        .await;
+    let big = server
+        .mock("POST", "/openai/deployments/big/chat/completions")
+        .match_query(mockito::Matcher::Any)
+        .match_body(mockito::Matcher::PartialJson(json!({ "model": "gpt-4.1" })))
+        .with_status(200)
+        .with_body(completion("{\"title\":\"Alien\",\"year\":1979}"))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let movie: Movie = client(&server)
+        .deployment_for(OpenAIModel::Gpt41Mini, "small")
+        .deployment_for(OpenAIModel::Gpt41, "big")
+        .on_context_overflow(ContextOverflow::Escalate("gpt-4.1".into()))
+        .materialize("Alien")
+        .await
+        .unwrap();
+    assert_eq!(movie.year, 1979);
+    small.assert_async().await;
+    big.assert_async().await;
+}
+
+#[tokio::test]
+async fn list_models_uses_api_key_header() {
+    let mut server = mockito::Server::new_async().await;
+    let m = server
+        .mock("GET", "/openai/models")
+        .match_query(mockito::Matcher::Any)
+        .match_header("api-key", "azure-key")
+        .with_status(200)
+        .with_body(
+            json!({ "data": [{ "id": "gpt-4.1" }, { "id": "text-embedding-3-large" }] })
+                .to_string(),
+        )
+        .expect(1)
+        .create_async()
+        .await;
+
+    let models = client(&server).list_models().await.unwrap();
+    let ids: Vec<_> = models.iter().map(|m| m.id.as_str()).collect();
+    assert_eq!(ids, ["gpt-4.1"]);
+    m.assert_async().await;
+}
@@ -0,0 +1,221 @@
+//! Drive the real `BedrockClient` over a local mock HTTP server (`mockito`),
+//! covering the Converse and foundation-models endpoints with both SigV4
+//! signing and Bedrock API keys. No AWS account needed.
+#![cfg(feature = "bedrock")]
+
+use rstructor::{
+    AwsCredentials, BedrockClient, BedrockModel, Instructor, LLMClient, OutputStrategy,
+    RStructorError,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Instructor, Serialize, Deserialize, Debug, PartialEq)]
+struct Movie {
+    title: String,
+    year: u16,
+}
+
+/// The default model through the `us` inference profile us-east-1 defaults to.
+const SONNET_PATH: &str = "/model/us.anthropic.claude-sonnet-4-5-20250929-v1%3A0/converse";
+
+fn client(server: &mockito::Server) -> BedrockClient {
+    BedrockClient::new("us-east-1", AwsCredentials::new("AKID", "secret"))
+        .unwrap()
+        .base_url(server.url())
+        .no_retries()
+}
+
+fn converse_reply(content: serde_json::Value) -> String {
+    json!({
+        "output": { "message": { "role": "assistant", "content": [content] } },
+        "stopReason": "end_turn",
+        "usage": {
+            "inputTokens": 20,
+            "outputTokens": 9,
+            "cacheReadInputTokens": 5,
+            "totalTokens": 34
+        }
+    })
+    .to_string()
+}
+
+#[tokio::test]
+async fn claude_materializes_through_a_forced_tool_call() {
+    let mut server = mockito::Server::new_async().await;
+    let m = server
+        .mock("POST", SONNET_PATH)
+        .match_header(
+            "authorization",
+            mockito::Matcher::Regex("^AWS4-HMAC-SHA256 Credential=AKID/\\d{8}/us-east-1/bedrock/aws4_request, SignedHeaders=content-type;host;x-amz-date, Signature=[0-9a-f]{64}$".to_string()),
+        )
+        .match_header("x-amz-date", mockito::Matcher::Regex("^\\d{8}T\\d{6}Z$".to_string()))
+        .match_body(mockito::Matcher::PartialJson(json!({
+            "messages": [{ "role": "user", "content": [{ "text": "Metropolis" }] }],
+            "inferenceConfig": { "temperature": 0.0 },
+            "toolConfig": {
+                "tools": [{ "toolSpec": {
+                    "name": "structured_output",
+                    "inputSchema": { "json": { "type": "object", "required": ["title", "year"] } }
+                }}],
+                "toolChoice": { "tool": { "name": "structured_output" } }
+            }
+        })))
+        .with_status(200)
+        .with_body(converse_reply(json!({
+            "toolUse": {
+                "toolUseId": "tooluse_1",
+                "name": "structured_output",
+                "input": { "title": "Metropolis", "year": 1927 }
+            }
+        })))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let result = client(&server)
+        .materialize_with_metadata::<Movie>("Metropolis")
+        .await
+        .unwrap();
+    assert_eq!(
+        result.data,
+        Movie {
+            title: "Metropolis".into(),
+            year: 1927
+        }
+    );
+    let usage = result.usage.unwrap();
+    assert_eq!((usage.input_tokens, usage.output_tokens), (25, 9));
+    m.assert_async().await;
+}
+
+#[tokio::test]
+async fn llama_gets_the_schema_in_the_system_prompt() {
+    let mut server = mockito::Server::new_async().await;
+    let m = server
+        .mock(
+            "POST",
+            "/model/us.meta.llama3-3-70b-instruct-v1%3A0/converse",
+        )
+        .match_body(mockito::Matcher::AllOf(vec![
+            mockito::Matcher::PartialJson(json!({
+                "system": [{ "text": "You review films." }, {}]
+            })),
+            mockito::Matcher::Regex("matches this JSON Schema".to_string()),
+        ]))
+        .with_status(200)
+        .with_body(converse_reply(
+            json!({ "text": "{\"title\":\"Alien\",\"year\":1979}" }),
+        ))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let movie: Movie = client(&server)
+        .model(BedrockModel::Llama33Instruct70B)
+        .inference_profile("us")
+        .system_prompt("You review films.")
+        .materialize("Alien")
+        .await
+        .unwrap();
+    assert_eq!(movie.year, 1979);
+    m.assert_async().await;
+}
+
+#[tokio::test]
+async fn api_keys_are_sent_as_bearer_tokens() {
+    let mut server = mockito::Server::new_async().await;
+    let m = server
+        .mock("POST", SONNET_PATH)
+        .match_header("authorization", "Bearer bedrock-key")
+        .match_header("x-amz-date", mockito::Matcher::Missing)
+        .match_body(mockito::Matcher::PartialJson(json!({
+            "inferenceConfig": { "maxTokens": 64, "temperature": 0.5 }
+        })))
+        .with_status(200)
+        .with_body(converse_reply(json!({ "text": "Hello there" })))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let text = BedrockClient::with_api_key("us-east-1", "bedrock-key")
+        .unwrap()
+        .base_url(server.url())
+        .temperature(0.5)
+        .max_tokens(64)
+        .generate("Say hello")
+        .await
+        .unwrap();
+    assert_eq!(text, "Hello there");
+    m.assert_async().await;
+}
+
+#[tokio::test]
+async fn list_models_reads_model_summaries() {
+    let mut server = mockito::Server::new_async().await;
+    let m = server
+        .mock("GET", "/foundation-models")
+        .match_query(mockito::Matcher::UrlEncoded(
+            "byOutputModality".into(),
+            "TEXT".into(),
+        ))
+        .match_header(
+            "authorization",
+            mockito::Matcher::Regex("SignedHeaders=host;x-amz-date,".to_string()),
+        )
+        .with_status(200)
+        .with_body(
+            json!({
+                "modelSummaries": [
+                    {
+                        "modelId": "anthropic.claude-haiku-4-5-20251001-v1:0",
+                        "modelName": "Claude Haiku 4.5",
+                        "providerName": "Anthropic"
+                    },
+                    { "modelId": "meta.llama3-1-8b-instruct-v1:0" }
+                ]
+            })
+            .to_string(),
+        )
+        .expect(1)
+        .create_async()
+        .await;
+
+    let models = client(&server).list_models().await.unwrap();
+    let ids: Vec<_> = models.iter().map(|m| m.id.as_str()).collect();
+    assert_eq!(
+        ids,
+        [
+            "anthropic.claude-haiku-4-5-20251001-v1:0",
+            "meta.llama3-1-8b-instruct-v1:0"
+        ]
+    );
+    assert_eq!(models[0].name.as_deref(), Some("Claude Haiku 4.5"));
+    assert_eq!(models[1].description, None);
+    m.assert_async().await;
+}
+
+#[tokio::test]
+async fn json_schema_output_is_unsupported() {
+    let server = mockito::Server::new_async().await;
+    let err = client(&server)
+        .output_strategy(OutputStrategy::JsonSchema)
+        .materialize::<Movie>("Alien")
+        .await
+        .unwrap_err();
+    assert!(matches!(err, RStructorError::Unsupported(_)));
+}
+
+#[test]
+fn credentials_are_required() {
+    let Err(err) = BedrockClient::new("us-east-1", AwsCredentials::new("", "")) else {
+        panic!("empty credentials were accepted");
+    };
+    assert!(
+        matches!(
+            err.api_error_kind(),
+            Some(rstructor::ApiErrorKind::AuthenticationFailed)
+        ),
+        "{err:?}"
+    );
+}
@@ -0,0 +1,90 @@
+//! Tests for the `build()` step that validates a client's configuration.
+
+#![cfg(any(feature = "openai", feature = "anthropic", feature = "gemini"))]
+
+#[cfg(feature = "openai")]
+use rstructor::OpenAIClient;
+use rstructor::RStructorError;
+use std::time::Duration;
+
+fn config_error<T>(result: rstructor::Result<T>) -> String {
+    match result {
+        Err(RStructorError::ConfigError(message)) => message,
+        Err(other) => panic!("expected ConfigError, got {other:?}"),
+        Ok(_) => panic!("expected ConfigError, got a client"),
+    }
+}
+
+#[cfg(feature = "openai")]
+#[test]
+fn valid_configuration_builds() {
+    let client = OpenAIClient::new("key")
+        .unwrap()
+        .temperature(0.7)
+        .max_tokens(1024)
+        .timeout(Duration::from_secs(30))
+        .base_url("http://localhost:11434/v1")
+        .build();
+    assert!(client.is_ok());
+}
+
+#[cfg(feature = "openai")]
+#[test]
+fn out_of_range_temperature_is_rejected() {
+    let message = config_error(OpenAIClient::new("key").unwrap().temperature(20.0).build());
+    assert!(message.contains("temperature"), "{message}");
+    assert!(message.starts_with("OpenAI"), "{message}");
+}
+
+#[cfg(feature = "anthropic")]
+#[test]
+fn temperature_range_is_per_provider() {
+    use rstructor::AnthropicClient;
+    let message = config_error(
+        AnthropicClient::new("key")
+            .unwrap()
+            .temperature(1.5)
+            .build(),
+    );
+    assert!(message.contains("0.0..=1"), "{message}");
+}
+
+#[cfg(feature = "openai")]
+#[test]
+fn zero_timeout_and_schemeless_base_url_are_rejected() {
+    let timeout = config_error(
+        OpenAIClient::new("key")
+            .unwrap()
+            .timeout(Duration::ZERO)
+            .build(),
+    );
+    assert!(timeout.contains("timeout"), "{timeout}");
+
+    let url = config_error(
+        OpenAIClient::new("key")
+            .unwrap()
+            .base_url("localhost:8080/v1")
+            .build(),
+    );
+    assert!(url.contains("base_url"), "{url}");
+}
+
+#[cfg(feature = "anthropic")]
+#[test]
+fn max_tokens_above_model_limit_is_rejected() {
+    use rstructor::{AnthropicClient, AnthropicModel};
+    let client = AnthropicClient::new("key")
+        .unwrap()
+        .model(AnthropicModel::ClaudeHaiku45);
+    let limit = client.capabilities().max_output_tokens.unwrap();
+    let message = config_error(client.max_tokens(limit + 1).build());
+    assert!(message.contains("max_tokens"), "{message}");
+}
+
+#[cfg(feature = "gemini")]
+#[test]
+fn empty_custom_model_name_is_rejected() {
+    use rstructor::GeminiClient;
+    let message = config_error(GeminiClient::new("key").unwrap().model("").build());
+    assert!(message.contains("model"), "{message}");
+}
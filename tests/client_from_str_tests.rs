@@ -0,0 +1,43 @@
+//! Parsing `"provider:model"` strings into clients. Only Ollama is built
+//! here, since it needs no API key; the keyed providers are covered by the
+//! single environment-mutating test in `anyclient_env_tests.rs`.
+#![cfg(all(feature = "openai", feature = "anthropic", feature = "ollama"))]
+
+use rstructor::{AnyClient, Provider, RStructorError};
+
+#[test]
+fn provider_names_and_aliases_parse_case_insensitively() {
+    assert_eq!("openai".parse::<Provider>().unwrap(), Provider::OpenAI);
+    assert_eq!("OpenAI".parse::<Provider>().unwrap(), Provider::OpenAI);
+    assert_eq!("claude".parse::<Provider>().unwrap(), Provider::Anthropic);
+    assert_eq!(" ollama ".parse::<Provider>().unwrap(), Provider::Ollama);
+}
+
+#[test]
+fn unknown_providers_are_config_errors() {
+    let err = "mistral".parse::<Provider>().unwrap_err();
+    assert!(
+        matches!(&err, RStructorError::ConfigError(msg) if msg.contains("mistral") && msg.contains("openai")),
+        "{err:?}"
+    );
+    assert!(matches!(
+        rstructor::client_from_str("mistral:large"),
+        Err(RStructorError::ConfigError(_))
+    ));
+}
+
+#[test]
+fn everything_after_the_first_colon_is_the_model() {
+    let client = rstructor::client_from_str("ollama:llama3.1:8b").unwrap();
+    assert_eq!(client.provider(), Provider::Ollama);
+    assert_eq!(client.model_id().name, "llama3.1:8b");
+}
+
+#[test]
+fn a_missing_model_keeps_the_provider_default() {
+    let default = rstructor::OllamaClient::new().model_id();
+    for spec in ["ollama", "ollama:", "ollama: "] {
+        let client: AnyClient = spec.parse().unwrap();
+        assert_eq!(client.model_id(), default, "{spec}");
+    }
+}
@@ -0,0 +1,84 @@
+//! The process-wide per-provider concurrency limit. Lives in its own test
+//! binary because the limit is global state shared by every client.
+#![cfg(feature = "openai")]
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use rstructor::{
+    Instructor, LLMClient, OpenAIClient, Provider, clear_concurrency_limit, concurrency_limit,
+    set_concurrency_limit,
+};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+struct Answer {
+    value: u32,
+}
+
+/// Serve slow chat completions, recording the peak number of open requests.
+async fn slow_server(peak: Arc<AtomicUsize>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let open = Arc::new(AtomicUsize::new(0));
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let (open, peak) = (Arc::clone(&open), Arc::clone(&peak));
+            tokio::spawn(async move {
+                let mut buf = vec![0u8; 64 * 1024];
+                let _ = socket.read(&mut buf).await;
+                let now = open.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                open.fetch_sub(1, Ordering::SeqCst);
+
+                let body = serde_json::json!({
+                    "choices": [{
+                        "message": { "role": "assistant", "content": "{\"value\":1}" },
+                        "finish_reason": "stop"
+                    }]
+                })
+                .to_string();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            });
+        }
+    });
+    url
+}
+
+#[tokio::test]
+async fn limit_caps_in_flight_requests_across_clients() {
+    assert!(set_concurrency_limit(Provider::OpenAI, 0).is_err());
+    set_concurrency_limit(Provider::OpenAI, 2).unwrap();
+    assert_eq!(concurrency_limit(Provider::OpenAI), Some(2));
+
+    let peak = Arc::new(AtomicUsize::new(0));
+    let url = slow_server(Arc::clone(&peak)).await;
+
+    // A separate client per task: the cap must hold across instances.
+    let tasks: Vec<_> = (0..6)
+        .map(|_| {
+            let client = OpenAIClient::new("test-key")
+                .unwrap()
+                .base_url(url.clone())
+                .no_retries();
+            tokio::spawn(async move { client.materialize::<Answer>("go").await })
+        })
+        .collect();
+    for task in tasks {
+        assert_eq!(task.await.unwrap().unwrap().value, 1);
+    }
+    assert_eq!(peak.load(Ordering::SeqCst), 2);
+
+    clear_concurrency_limit(Provider::OpenAI);
+    assert_eq!(concurrency_limit(Provider::OpenAI), None);
+}
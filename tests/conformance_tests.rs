@@ -0,0 +1,43 @@
+//! Offline tests for the `conformance` suite, scripted with [`MockClient`].
+
+#![cfg(feature = "mock")]
+
+use rstructor::MockClient;
+use rstructor::conformance::{self, Shape};
+
+const GOOD: [&str; 6] = [
+    r#"{"name": "Ada Lovelace", "address": {"city": "London", "country": "United Kingdom"}}"#,
+    r#"{"sentiment": "Positive"}"#,
+    r#"{"shape": {"kind": "Rectangle", "data": {"width": 3, "height": 4}}}"#,
+    r#"{"items": [{"product": "apple", "quantity": 2}, {"product": "pear", "quantity": 3}]}"#,
+    r#"{"name": "Bob", "email": "bob@example.com", "phone": null}"#,
+    r#"{"counts": {"chairs": 5, "tables": 2}}"#,
+];
+
+#[tokio::test]
+async fn correct_answers_pass_every_shape() {
+    let client = MockClient::new().with_responses(GOOD);
+    let report = conformance::run(&client).await;
+    assert_eq!(report.results.len(), Shape::ALL.len());
+    assert!(
+        report.all_passed(),
+        "{:?}",
+        report.failures().collect::<Vec<_>>()
+    );
+    assert_eq!(report.pass_rate(), 1.0);
+}
+
+#[tokio::test]
+async fn wrong_values_and_invalid_json_are_reported_per_case() {
+    let client = MockClient::new().with_responses([
+        r#"{"sentiment": "Negative"}"#,
+        r#"{"counts": "five chairs"}"#,
+    ]);
+    let report = conformance::run_shapes(&client, &[Shape::Enum, Shape::Map]).await;
+    assert_eq!(report.passed(), 0);
+
+    let failures: Vec<_> = report.failures().collect();
+    assert_eq!(failures[0].shape, Shape::Enum);
+    assert!(failures[0].error.as_ref().unwrap().contains("sentiment"));
+    assert_eq!(failures[1].shape, Shape::Map);
+}
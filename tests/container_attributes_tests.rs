@@ -277,4 +277,57 @@ mod container_attributes_tests {
         // Title should default to the enum name
         assert_eq!(schema_json["title"], "EnumWithoutAttributes");
     }
+
+    // Struct with counter-examples, repeated and written as json! expressions
+    #[derive(Instructor, Serialize, Deserialize, Debug)]
+    #[llm(
+        counter_example = ::serde_json::json!({"name": "N/A", "age": 0}),
+        counter_example = ::serde_json::json!({"person": {"name": "John"}})
+    )]
+    struct StructWithCounterExamples {
+        #[llm(example = "Ada")]
+        name: String,
+        #[llm(example = 36)]
+        age: u32,
+    }
+
+    #[derive(Instructor, Serialize, Deserialize, Debug)]
+    #[llm(counter_example = "unknown")]
+    enum EnumWithCounterExample {
+        Cat,
+        Dog,
+    }
+
+    #[test]
+    fn test_counter_examples_in_schema() {
+        let schema_json = StructWithCounterExamples::schema().to_json();
+        let counter_examples = schema_json["x-counter-examples"].as_array().unwrap();
+        assert_eq!(
+            counter_examples[0],
+            ::serde_json::json!({"name": "N/A", "age": 0})
+        );
+        assert_eq!(counter_examples[1]["person"]["name"], "John");
+
+        // A string literal stays a string
+        let enum_json = EnumWithCounterExample::schema().to_json();
+        assert_eq!(
+            enum_json["x-counter-examples"],
+            ::serde_json::json!(["unknown"])
+        );
+    }
+
+    #[test]
+    fn test_counter_examples_rendered_after_example() {
+        let instructions = StructWithCounterExamples::prompt_instructions();
+        let example_at = instructions.find("Example of a valid response").unwrap();
+        let counter_at = instructions.find("Do NOT return output like this").unwrap();
+        assert!(example_at < counter_at);
+        assert_eq!(
+            instructions
+                .matches("Do NOT return output like this")
+                .count(),
+            2
+        );
+        assert!(instructions.contains("\"N/A\""));
+    }
 }
@@ -0,0 +1,237 @@
+//! `Conversation`: multi-turn history sent to each provider, ending in a typed
+//! extraction.
+#![cfg(all(feature = "openai", feature = "anthropic", feature = "gemini"))]
+
+use std::sync::{Arc, Mutex};
+
+use rstructor::{ChatRole, Conversation, Instructor, RStructorError};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+#[derive(Instructor, Serialize, Deserialize, Debug, PartialEq)]
+#[llm(validate = "validate_booking")]
+struct Booking {
+    city: String,
+    nights: u32,
+}
+
+fn validate_booking(booking: &Booking) -> rstructor::Result<()> {
+    if booking.nights == 0 {
+        return Err(RStructorError::ValidationError(
+            "nights must be at least 1".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Serve the next of `replies` for each request on `path`, recording each
+/// request body.
+async fn serve(
+    server: &mut mockito::Server,
+    path: impl Into<mockito::Matcher>,
+    replies: Vec<Value>,
+) -> Arc<Mutex<Vec<Value>>> {
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let sink = requests.clone();
+    server
+        .mock("POST", path)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body_from_request(move |request| {
+            let body = serde_json::from_slice(request.body().unwrap()).unwrap();
+            let mut requests = sink.lock().unwrap();
+            requests.push(body);
+            replies[requests.len() - 1].to_string().into_bytes()
+        })
+        .create_async()
+        .await;
+    requests
+}
+
+fn openai_reply(content: &str) -> Value {
+    json!({
+        "choices": [{
+            "message": { "role": "assistant", "content": content },
+            "finish_reason": "stop"
+        }]
+    })
+}
+
+#[tokio::test]
+async fn openai_sends_the_whole_history_and_keeps_only_the_accepted_reply() {
+    let mut server = mockito::Server::new_async().await;
+    let requests = serve(
+        &mut server,
+        "/chat/completions",
+        vec![
+            openai_reply("How many nights?"),
+            openai_reply(r#"{"city": "Lisbon", "nights": 0}"#),
+            openai_reply(r#"{"city": "Lisbon", "nights": 3}"#),
+        ],
+    )
+    .await;
+    let client = rstructor::OpenAIClient::new("test-key")
+        .unwrap()
+        .base_url(server.url())
+        .model("gpt-4o-mini");
+
+    let mut chat = Conversation::new().with_system("You are a travel agent.");
+    let reply = chat
+        .send(&client, "A hotel in Lisbon, please.")
+        .await
+        .unwrap();
+    assert_eq!(reply, "How many nights?");
+    chat.user("Three.");
+    let result = chat
+        .materialize_with_metadata::<Booking>(&client)
+        .await
+        .unwrap();
+    assert_eq!(result.data.nights, 3);
+    // The re-ask is in the result's conversation but not in the chat history.
+    assert_eq!(result.conversation.len(), 7);
+
+    let roles: Vec<_> = chat.messages().iter().map(|m| m.role).collect();
+    assert_eq!(
+        roles,
+        [
+            ChatRole::System,
+            ChatRole::User,
+            ChatRole::Assistant,
+            ChatRole::User,
+            ChatRole::Assistant
+        ]
+    );
+    assert_eq!(
+        chat.last_reply(),
+        Some(r#"{"city": "Lisbon", "nights": 3}"#)
+    );
+
+    let requests = requests.lock().unwrap();
+    let sent: Vec<_> = requests[1]["messages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| (m["role"].as_str().unwrap(), m["content"].as_str().unwrap()))
+        .collect();
+    assert_eq!(
+        sent,
+        [
+            ("system", "You are a travel agent."),
+            ("user", "A hotel in Lisbon, please."),
+            ("assistant", "How many nights?"),
+            ("user", "Three."),
+        ]
+    );
+    assert_eq!(requests[2]["messages"].as_array().unwrap().len(), 6);
+}
+
+#[tokio::test]
+async fn anthropic_moves_system_messages_into_the_system_field() {
+    let mut server = mockito::Server::new_async().await;
+    let requests = serve(
+        &mut server,
+        "/messages",
+        vec![json!({
+            "content": [{ "type": "text", "text": r#"{"city": "Oslo", "nights": 2}"# }],
+            "stop_reason": "end_turn"
+        })],
+    )
+    .await;
+    let client = rstructor::AnthropicClient::new("test-key")
+        .unwrap()
+        .base_url(server.url())
+        .system_prompt("Be brief.");
+
+    let mut chat = Conversation::new().with_system("You are a travel agent.");
+    chat.user("Oslo")
+        .assistant("For how long?")
+        .user("Two nights.");
+    let booking: Booking = chat.materialize(&client).await.unwrap();
+    assert_eq!(booking.city, "Oslo");
+
+    let body = &requests.lock().unwrap()[0];
+    assert_eq!(body["system"], "Be brief.\n\nYou are a travel agent.");
+    let roles: Vec<_> = body["messages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| m["role"].as_str().unwrap())
+        .collect();
+    assert_eq!(roles, ["user", "assistant", "user"]);
+}
+
+#[tokio::test]
+async fn gemini_sends_assistant_turns_as_model_and_system_as_instruction() {
+    let mut server = mockito::Server::new_async().await;
+    let requests = serve(
+        &mut server,
+        mockito::Matcher::Regex(r"^/models/.+:generateContent".to_string()),
+        vec![json!({
+            "candidates": [{
+                "content": { "parts": [{ "text": "Enjoy Rome!" }] },
+                "finishReason": "STOP"
+            }]
+        })],
+    )
+    .await;
+    let client = rstructor::GeminiClient::new("test-key")
+        .unwrap()
+        .base_url(server.url());
+
+    let mut chat = Conversation::from(vec![
+        rstructor::ChatMessage::system("You are a travel agent."),
+        rstructor::ChatMessage::user("Rome"),
+        rstructor::ChatMessage::assistant("Booked."),
+    ]);
+    let reply = chat.send(&client, "Thanks!").await.unwrap();
+    assert_eq!(reply, "Enjoy Rome!");
+    assert_eq!(chat.len(), 5);
+
+    let body = &requests.lock().unwrap()[0];
+    assert_eq!(
+        body["systemInstruction"],
+        json!({ "parts": [{ "text": "You are a travel agent." }] })
+    );
+    let roles: Vec<_> = body["contents"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["role"].as_str().unwrap())
+        .collect();
+    assert_eq!(roles, ["user", "model", "user"]);
+}
+
+#[tokio::test]
+async fn materialize_needs_a_trailing_user_turn() {
+    let client = rstructor::OpenAIClient::new("test-key")
+        .unwrap()
+        .base_url("http://127.0.0.1:9");
+    let mut chat = Conversation::new();
+    chat.user("Paris").assistant("How many nights?");
+
+    let err = chat.materialize::<Booking>(&client).await.unwrap_err();
+    assert!(matches!(err, RStructorError::ConfigError(_)), "{err:?}");
+    assert_eq!(chat.len(), 2);
+}
+
+#[tokio::test]
+async fn a_failed_send_leaves_the_history_unchanged() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("POST", "/chat/completions")
+        .with_status(400)
+        .with_header("content-type", "application/json")
+        .with_body(json!({ "error": { "message": "bad request" } }).to_string())
+        .create_async()
+        .await;
+    let client = rstructor::OpenAIClient::new("test-key")
+        .unwrap()
+        .base_url(server.url())
+        .no_retries();
+
+    let mut chat = Conversation::new();
+    chat.user("Paris").assistant("How many nights?");
+    assert!(chat.send(&client, "Two").await.is_err());
+    assert_eq!(chat.len(), 2);
+    assert_eq!(chat.last_reply(), Some("How many nights?"));
+}
@@ -0,0 +1,125 @@
+//! Offline tests for `materialize_corpus`: items extracted from several
+//! documents come back attributed to the document they were found in.
+#![cfg(all(feature = "mock", feature = "openai"))]
+
+use rstructor::{Document, Instructor, LLMClient, MockClient, RStructorError, SchemaType};
+use serde::{Deserialize, Serialize};
+
+#[derive(Instructor, Serialize, Deserialize, Debug, PartialEq)]
+#[llm(validate = "validate_deadline")]
+struct Deadline {
+    task: String,
+    day: String,
+}
+
+fn validate_deadline(deadline: &Deadline) -> rstructor::Result<()> {
+    if deadline.day.is_empty() {
+        return Err(RStructorError::ValidationError("day is empty".into()));
+    }
+    Ok(())
+}
+
+fn emails() -> Vec<Document> {
+    vec![
+        Document::new("email-1", "Please send the report by Friday."),
+        Document::new("email-2", "Invoices are due on Monday."),
+    ]
+}
+
+fn deadline(task: &str, day: &str) -> Deadline {
+    Deadline {
+        task: task.into(),
+        day: day.into(),
+    }
+}
+
+#[tokio::test]
+async fn items_are_attributed_to_their_documents() {
+    let client = MockClient::new().with_response(
+        r#"{"items": [
+            {"document_id": "email-2", "item": {"task": "invoices", "day": "Monday"}},
+            {"document_id": "email-1", "item": {"task": "report", "day": "Friday"}}
+        ]}"#,
+    );
+
+    let deadlines: Vec<(String, Deadline)> = client
+        .materialize_corpus(&emails(), "List every deadline.")
+        .await
+        .unwrap();
+    assert_eq!(
+        deadlines,
+        vec![
+            ("email-2".to_string(), deadline("invoices", "Monday")),
+            ("email-1".to_string(), deadline("report", "Friday")),
+        ]
+    );
+
+    let request = client.last_request().unwrap();
+    assert!(request.prompt.starts_with("List every deadline."));
+    assert!(
+        request
+            .prompt
+            .contains("<document id=\"email-1\">\nPlease send the report by Friday.\n</document>")
+    );
+    let schema = request.schema.unwrap();
+    let entry = &schema["properties"]["items"]["items"];
+    assert_eq!(
+        entry["properties"]["document_id"]["enum"],
+        serde_json::json!(["email-1", "email-2"])
+    );
+    assert_eq!(
+        entry["properties"]["item"]["title"],
+        Deadline::schema().to_json()["title"]
+    );
+}
+
+#[tokio::test]
+async fn unknown_ids_and_invalid_items_are_re_asked() {
+    let client = MockClient::new()
+        .with_retries(2)
+        .with_response(
+            r#"{"items": [{"document_id": "email-3", "item": {"task": "report", "day": "Friday"}}]}"#,
+        )
+        .with_response(
+            r#"{"items": [{"document_id": "email-1", "item": {"task": "report", "day": ""}}]}"#,
+        )
+        .with_response(
+            r#"{"items": [{"document_id": "email-1", "item": {"task": "report", "day": "Friday"}}]}"#,
+        );
+
+    let deadlines: Vec<(String, Deadline)> = client
+        .materialize_corpus(&emails(), "List every deadline.")
+        .await
+        .unwrap();
+    assert_eq!(
+        deadlines,
+        vec![("email-1".to_string(), deadline("report", "Friday"))]
+    );
+    assert!(client.responses_exhausted());
+}
+
+#[tokio::test]
+async fn an_empty_corpus_makes_no_call() {
+    let client = MockClient::new();
+    let deadlines: Vec<(String, Deadline)> = client
+        .materialize_corpus(&[], "List every deadline.")
+        .await
+        .unwrap();
+    assert!(deadlines.is_empty());
+    assert_eq!(client.request_count(), 0);
+}
+
+#[tokio::test]
+async fn duplicate_ids_are_rejected_before_the_call() {
+    let client = MockClient::new();
+    let docs = [Document::new("a", "one"), Document::new("a", "two")];
+    let err = client
+        .materialize_corpus::<Deadline>(&docs, "List every deadline.")
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&err, RStructorError::ValidationError(msg) if msg.contains("`a`")),
+        "{err:?}"
+    );
+    assert_eq!(client.request_count(), 0);
+}
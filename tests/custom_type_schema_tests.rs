@@ -112,3 +112,159 @@ fn test_struct_with_custom_date() {
     assert!(required.contains(&json!("start_date")));
     assert!(!required.contains(&json!("end_date")));
 }
+
+// A foreign type with no SchemaType impl: `schema_with` supplies its schema,
+// and serde's `with` adapts how it is (de)serialized.
+mod duration_secs {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(value: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(value.as_secs())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        u64::deserialize(deserializer).map(Duration::from_secs)
+    }
+
+    pub fn schema() -> rstructor::Schema {
+        rstructor::Schema::new(serde_json::json!({ "type": "integer", "minimum": 0 }))
+    }
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+struct Timer {
+    label: String,
+    #[llm(
+        description = "Length in seconds",
+        schema_with = "duration_secs::schema"
+    )]
+    #[serde(with = "duration_secs")]
+    length: std::time::Duration,
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+enum Schedule {
+    Every {
+        #[llm(schema_with = "duration_secs::schema")]
+        #[serde(with = "duration_secs")]
+        interval: std::time::Duration,
+    },
+    Never,
+}
+
+#[test]
+fn schema_with_supplies_the_field_schema() {
+    let schema = Timer::schema().to_json();
+    assert_eq!(
+        schema["properties"]["length"],
+        json!({ "type": "integer", "minimum": 0, "description": "Length in seconds" })
+    );
+    assert_eq!(schema["required"], json!(["label", "length"]));
+
+    let timer: Timer = serde_json::from_value(json!({ "label": "tea", "length": 180 })).unwrap();
+    assert_eq!(timer.length.as_secs(), 180);
+    assert!(timer.validate().is_ok());
+}
+
+#[test]
+fn schema_with_applies_to_enum_variant_fields() {
+    let rendered = Schedule::schema().to_json().to_string();
+    assert!(
+        rendered.contains(r#""interval":{"type":"integer","minimum":0"#),
+        "{rendered}"
+    );
+}
+
+// Serde helpers that change the wire format: recognized ones are detected,
+// others are named with `serialized_as`.
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+struct Booking {
+    #[serde(with = "chrono::serde::ts_seconds")]
+    created: chrono::DateTime<chrono::Utc>,
+    #[llm(description = "When the guest checks out")]
+    #[serde(with = "chrono::serde::ts_milliseconds_option", default)]
+    checkout: Option<chrono::DateTime<chrono::Utc>>,
+    #[llm(serialized_as = "integer", minimum = 60)]
+    #[serde(serialize_with = "duration_secs::serialize")]
+    #[serde(deserialize_with = "duration_secs::deserialize")]
+    stay: std::time::Duration,
+    // Without a recognized helper the type-based schema stays
+    #[serde(with = "string_passthrough")]
+    note: String,
+}
+
+mod string_passthrough {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &str, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(value)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+        String::deserialize(deserializer)
+    }
+}
+
+#[test]
+fn serde_helpers_describe_the_wire_format() {
+    let schema = Booking::schema().to_json();
+    let props = &schema["properties"];
+    assert_eq!(
+        props["created"],
+        json!({
+            "type": "integer",
+            "format": "unix-timestamp",
+            "description": "Unix timestamp in seconds"
+        })
+    );
+    assert_eq!(
+        props["checkout"],
+        json!({ "type": "integer", "description": "When the guest checks out" })
+    );
+    assert_eq!(props["stay"], json!({ "type": "integer", "minimum": 60 }));
+    assert_eq!(props["note"], json!({ "type": "string" }));
+    assert_eq!(schema["required"], json!(["created", "stay", "note"]));
+
+    // The schema matches what serde reads; the minimum stays schema-only
+    let booking: Booking = serde_json::from_value(json!({
+        "created": 1_700_000_000,
+        "checkout": 1_700_086_400_000i64,
+        "stay": 30,
+        "note": "late arrival"
+    }))
+    .unwrap();
+    assert_eq!(booking.created.timestamp(), 1_700_000_000);
+    assert!(booking.validate().is_ok());
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+enum Reminder {
+    At {
+        #[serde(with = "chrono::serde::ts_seconds")]
+        when: chrono::DateTime<chrono::Utc>,
+    },
+    After {
+        #[llm(serialized_as = "integer", description = "Delay in seconds")]
+        #[serde(with = "duration_secs")]
+        delay: std::time::Duration,
+    },
+}
+
+#[test]
+fn serde_helpers_apply_to_enum_variant_fields() {
+    let schema = Reminder::schema().to_json();
+    let branches = schema["anyOf"].as_array().unwrap();
+    assert_eq!(
+        branches[0]["properties"]["At"]["properties"]["when"],
+        json!({
+            "type": "integer",
+            "format": "unix-timestamp",
+            "description": "Unix timestamp in seconds"
+        })
+    );
+    assert_eq!(
+        branches[1]["properties"]["After"]["properties"]["delay"],
+        json!({ "type": "integer", "description": "Delay in seconds" })
+    );
+}
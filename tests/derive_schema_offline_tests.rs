@@ -6,9 +6,12 @@
 //!
 //! - Tagged enum schema shapes (internally-tagged, adjacently-tagged, untagged)
 //! - Externally-tagged tuple/struct/unit + mixed variants
-//! - Map field schemas (`additionalProperties` chain, `x-enum-keys`, "Keys: [..]" hint)
+//! - Tagged enums with serde renames: a sample of every branch deserializes
+//! - Map field schemas (`additionalProperties` chain, `x-enum-keys`, "Keys: [..]" hint,
+//!   `BTreeMap`/`HashMap` values holding derived structs)
 //! - Tuple field schemas (`prefixItems`/`minItems`/`maxItems`)
 //! - `Box<T>` fields
+//! - Arbitrary nesting of `Option`/`Vec`/maps/`Box` (`Vec<Option<Vec<T>>>`, ...)
 //! - Self-referential `$defs`/`$ref`
 //! - `rename_all` styles applied to *struct* fields
 //! - `example`/`examples` string-coercion + empty-array edges
@@ -367,6 +370,118 @@ fn externally_tagged_mixed_round_trips() {
     );
 }
 
+// ============================================================================
+// Tagged enums with serde renames: every branch deserializes
+// ============================================================================
+
+/// A minimal instance of `schema`: the first allowed value of a tag, every
+/// required property, one value per tuple position.
+fn sample_instance(schema: &serde_json::Value) -> serde_json::Value {
+    use serde_json::{Value, json};
+    if let Some(first) = schema["enum"].get(0) {
+        return first.clone();
+    }
+    match schema["type"].as_str() {
+        Some("object") => {
+            let mut object = serde_json::Map::new();
+            for key in schema["required"].as_array().into_iter().flatten() {
+                let key = key.as_str().unwrap();
+                object.insert(key.to_string(), sample_instance(&schema["properties"][key]));
+            }
+            Value::Object(object)
+        }
+        Some("array") => match &schema["items"] {
+            Value::Array(positions) => positions.iter().map(sample_instance).collect(),
+            _ => json!([]),
+        },
+        Some("string") => json!("text"),
+        Some("integer") => json!(1),
+        Some("number") => json!(1.5),
+        Some("boolean") => json!(true),
+        other => panic!("no sample for {other:?} in {schema}"),
+    }
+}
+
+fn assert_every_branch_deserializes<T: SchemaType + serde::de::DeserializeOwned>() {
+    let schema = T::schema().to_json();
+    let branches = schema["anyOf"].as_array().expect("anyOf branches");
+    for branch in branches {
+        let instance = sample_instance(branch);
+        if let Err(err) = serde_json::from_value::<T>(instance.clone()) {
+            panic!("{instance} from branch {branch} does not deserialize: {err}");
+        }
+    }
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct EventPayload {
+    item_count: u32,
+    #[serde(rename = "LABEL")]
+    label: String,
+    note: Option<String>,
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum RenamedInternal {
+    SignedUp {
+        user_id: u64,
+        #[serde(rename = "mail")]
+        email: String,
+    },
+    #[serde(rename = "custom")]
+    Wrapped(EventPayload),
+    LoggedOut,
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+#[serde(tag = "t", content = "c", rename_all = "kebab-case")]
+enum RenamedAdjacent {
+    DoThing(EventPayload),
+    Pair(u8, String),
+    NoData,
+    Named {
+        optional_value: Option<u8>,
+        #[serde(rename = "req")]
+        required_value: u8,
+    },
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+#[serde(untagged)]
+enum RenamedUntagged {
+    Point {
+        #[serde(rename = "X")]
+        x: u8,
+    },
+    Payload(EventPayload),
+    Text(String),
+}
+
+#[test]
+fn internally_tagged_branches_deserialize_with_renames() {
+    let schema = RenamedInternal::schema().to_json();
+    let tags: Vec<&serde_json::Value> = schema["anyOf"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|branch| &branch["properties"]["type"]["enum"][0])
+        .collect();
+    assert_eq!(tags, ["signed_up", "custom", "logged_out"]);
+    assert_every_branch_deserializes::<RenamedInternal>();
+}
+
+#[test]
+fn adjacently_tagged_branches_deserialize_with_renames() {
+    assert_every_branch_deserializes::<RenamedAdjacent>();
+}
+
+#[test]
+fn untagged_branches_deserialize_with_renames() {
+    assert_every_branch_deserializes::<RenamedUntagged>();
+}
+
 // ============================================================================
 // Map field schemas
 // ============================================================================
@@ -419,6 +534,58 @@ fn map_field_enum_keys_x_enum_keys_and_keys_hint() {
     assert_eq!(f["additionalProperties"]["type"], "integer");
 }
 
+// ============================================================================
+// Unit enums with variant descriptions: oneOf of const values
+// ============================================================================
+
+#[derive(Instructor, Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
+#[serde(rename_all = "snake_case")]
+enum Triage {
+    #[llm(description = "Customer cannot work at all")]
+    Blocker,
+    #[llm(description = "Degraded but usable")]
+    Degraded,
+    Question,
+}
+
+#[test]
+fn described_unit_enum_emits_one_of_consts() {
+    let schema = Triage::schema().to_json();
+    assert_eq!(schema["type"], "string");
+    assert!(schema.get("enum").is_none());
+    assert_eq!(
+        schema["oneOf"],
+        serde_json::json!([
+            { "const": "blocker", "description": "Customer cannot work at all" },
+            { "const": "degraded", "description": "Degraded but usable" },
+            { "const": "question" }
+        ])
+    );
+    let parsed: Triage = serde_json::from_str("\"degraded\"").unwrap();
+    assert_eq!(parsed, Triage::Degraded);
+}
+
+#[test]
+fn undescribed_unit_enum_keeps_plain_enum() {
+    let schema = Level::schema().to_json();
+    assert_eq!(schema["enum"], serde_json::json!(["A", "B"]));
+    assert!(schema.get("oneOf").is_none());
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+struct TriageMap {
+    counts: HashMap<Triage, u32>,
+}
+
+#[test]
+fn map_keyed_by_described_enum_lists_const_keys() {
+    let schema = TriageMap::schema().to_json();
+    assert_eq!(
+        schema["properties"]["counts"]["x-enum-keys"],
+        serde_json::json!(["blocker", "degraded", "question"])
+    );
+}
+
 #[derive(Instructor, Serialize, Deserialize, Debug)]
 struct EnumKeyMapWithDesc {
     #[llm(description = "counts per level")]
@@ -435,6 +602,106 @@ fn map_field_user_description_merges_with_keys_hint() {
     assert_eq!(desc, "counts per level. Keys: [A, B]");
 }
 
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+struct Price {
+    amount: f64,
+    currency: String,
+}
+
+type PriceBook = std::collections::BTreeMap<String, Price>;
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+struct Catalog {
+    #[llm(description = "price per SKU")]
+    prices: std::collections::BTreeMap<String, Price>,
+    regional: HashMap<String, HashMap<String, Price>>,
+    aliased: PriceBook,
+    history: Vec<std::collections::BTreeMap<String, f64>>,
+    overrides: Option<HashMap<String, Price>>,
+}
+
+#[test]
+fn map_values_embed_instructor_struct_schema() {
+    let schema = Catalog::schema().inline().to_json();
+    let props = &schema["properties"];
+
+    let prices = &props["prices"];
+    assert_eq!(prices["type"], "object");
+    assert_eq!(prices["description"], "price per SKU");
+    assert_eq!(
+        prices["additionalProperties"]["properties"]["amount"]["type"],
+        "number"
+    );
+    assert_eq!(
+        prices["additionalProperties"]["required"],
+        serde_json::json!(["amount", "currency"])
+    );
+
+    let regional = &props["regional"]["additionalProperties"];
+    assert_eq!(regional["type"], "object");
+    assert_eq!(
+        regional["additionalProperties"]["properties"]["currency"]["type"],
+        "string"
+    );
+
+    // An alias is opaque to the macro; the runtime BTreeMap impl fills in
+    // the same shape.
+    assert_eq!(
+        props["aliased"]["additionalProperties"]["properties"]["amount"]["type"],
+        "number"
+    );
+
+    assert_eq!(props["history"]["type"], "array");
+    assert_eq!(
+        props["history"]["items"]["additionalProperties"]["type"],
+        "number"
+    );
+
+    assert_eq!(props["overrides"]["type"], "object");
+    assert_eq!(
+        props["overrides"]["additionalProperties"]["properties"]["amount"]["type"],
+        "number"
+    );
+    let required: Vec<&str> = schema["required"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| v.as_str().unwrap())
+        .collect();
+    assert!(!required.contains(&"overrides"));
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+enum PriceChange {
+    Bulk(std::collections::BTreeMap<String, Price>),
+    Tagged { labels: HashMap<String, String> },
+}
+
+#[test]
+fn map_payloads_in_enum_variants() {
+    let schema = PriceChange::schema().to_json();
+    let variants = schema["anyOf"].as_array().unwrap();
+    let bulk = &variants[0]["properties"]["Bulk"];
+    assert_eq!(bulk["type"], "object");
+    assert_eq!(
+        bulk["additionalProperties"]["properties"]["currency"]["type"],
+        "string"
+    );
+    let labels = &variants[1]["properties"]["Tagged"]["properties"]["labels"];
+    assert_eq!(labels["additionalProperties"]["type"], "string");
+}
+
+#[test]
+fn custom_hasher_maps_have_runtime_schema() {
+    type FastMap<V> = HashMap<String, V, std::hash::BuildHasherDefault<std::hash::DefaultHasher>>;
+    let schema = <FastMap<Price>>::schema().to_json();
+    assert_eq!(schema["type"], "object");
+    assert_eq!(
+        schema["additionalProperties"]["properties"]["amount"]["type"],
+        "number"
+    );
+}
+
 // ============================================================================
 // Tuple field schemas
 // ============================================================================
@@ -490,7 +757,7 @@ struct BoxHolder {
 
 #[test]
 fn box_field_of_struct_inlines_inner_schema() {
-    let schema = BoxHolder::schema().to_json();
+    let schema = BoxHolder::schema().inline().to_json();
     let boxed = &schema["properties"]["boxed"];
     // Box<Address> is invisible: the field carries Address's object schema.
     assert_eq!(boxed["type"], "object");
@@ -912,7 +1179,7 @@ struct Appointment {
 
 #[test]
 fn user_defined_date_struct_keeps_its_object_schema() {
-    let schema = Appointment::schema().to_json();
+    let schema = Appointment::schema().inline().to_json();
     let date = &schema["properties"]["date"];
     assert_eq!(
         date["type"], "object",
@@ -938,7 +1205,7 @@ fn user_defined_datetime_struct_keeps_its_object_schema() {
 
 #[test]
 fn optional_user_defined_date_struct_keeps_its_object_schema() {
-    let schema = Appointment::schema().to_json();
+    let schema = Appointment::schema().inline().to_json();
     let ends_at = &schema["properties"]["ends_at"];
     assert_eq!(ends_at["type"], "object");
     assert_eq!(ends_at["properties"]["day"]["type"], "integer");
@@ -953,7 +1220,7 @@ fn optional_user_defined_date_struct_keeps_its_object_schema() {
 
 #[test]
 fn vec_of_user_defined_date_struct_embeds_object_items() {
-    let schema = Appointment::schema().to_json();
+    let schema = Appointment::schema().inline().to_json();
     let opts = &schema["properties"]["reschedule_options"];
     assert_eq!(opts["type"], "array");
     assert_eq!(opts["items"]["type"], "object");
@@ -1197,3 +1464,350 @@ fn recursive_struct_named_date_terminates_with_ref() {
     );
     assert_eq!(def["properties"]["label"]["type"], "string");
 }
+
+// ===========================================================================
+// Property order
+// ===========================================================================
+
+fn property_keys(schema: &serde_json::Value) -> Vec<String> {
+    schema["properties"]
+        .as_object()
+        .unwrap()
+        .keys()
+        .cloned()
+        .collect()
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+struct DeclarationOrder {
+    zeta: String,
+    alpha: String,
+    mid: Option<String>,
+}
+
+#[test]
+fn properties_and_required_follow_declaration_order() {
+    let schema = DeclarationOrder::schema().to_json();
+    assert_eq!(property_keys(&schema), ["zeta", "alpha", "mid"]);
+    assert_eq!(schema["required"], serde_json::json!(["zeta", "alpha"]));
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+struct ExplicitOrder {
+    answer: String,
+    confidence: f32,
+    #[llm(order = 1)]
+    evidence: Vec<String>,
+    #[llm(order = 0, description = "Think first")]
+    reasoning: String,
+}
+
+#[test]
+fn llm_order_moves_fields_to_the_front() {
+    let schema = ExplicitOrder::schema().to_json();
+    assert_eq!(
+        property_keys(&schema),
+        ["reasoning", "evidence", "answer", "confidence"]
+    );
+    assert_eq!(
+        schema["properties"]["reasoning"]["description"],
+        "Think first"
+    );
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+#[serde(tag = "kind")]
+enum OrderedVariant {
+    Verdict {
+        label: String,
+        #[llm(order = 0)]
+        rationale: String,
+    },
+}
+
+#[test]
+fn llm_order_applies_to_enum_variant_fields() {
+    let schema = OrderedVariant::schema().to_json();
+    assert_eq!(
+        property_keys(&schema["anyOf"][0]),
+        ["kind", "rationale", "label"]
+    );
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+struct Bounded {
+    #[llm(minimum = 0, maximum = 150)]
+    age: u8,
+    #[llm(exclusive_minimum = -1.5, exclusive_maximum = 2.5, multiple_of = 0.25)]
+    score: Option<f32>,
+}
+
+#[test]
+fn numeric_constraints_become_schema_keywords() {
+    let schema = Bounded::schema().to_json();
+    assert_eq!(
+        schema["properties"]["age"],
+        serde_json::json!({ "type": "integer", "minimum": 0, "maximum": 150 })
+    );
+    let score = &schema["properties"]["score"];
+    assert_eq!(score["exclusiveMinimum"], serde_json::json!(-1.5));
+    assert_eq!(score["exclusiveMaximum"], serde_json::json!(2.5));
+    assert_eq!(score["multipleOf"], serde_json::json!(0.25));
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+struct Constrained {
+    #[llm(description = "Display name", min_length = 1, max_length = 200)]
+    title: String,
+    #[llm(pattern = "^[A-Z]", format = "email")]
+    contact: Option<String>,
+}
+
+#[test]
+fn string_constraints_become_schema_keywords() {
+    let schema = Constrained::schema().to_json();
+    assert_eq!(
+        schema["properties"]["title"],
+        serde_json::json!({
+            "type": "string",
+            "description": "Display name",
+            "minLength": 1,
+            "maxLength": 200
+        })
+    );
+    let contact = &schema["properties"]["contact"];
+    assert_eq!(contact["pattern"], "^[A-Z]");
+    assert_eq!(contact["format"], "email");
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+struct BoundedLists {
+    #[llm(min_items = 3, max_items = 5, unique_items)]
+    tags: Vec<String>,
+    #[llm(max_items = 2)]
+    scores: Option<Vec<u8>>,
+}
+
+#[test]
+fn array_constraints_become_schema_keywords() {
+    let schema = BoundedLists::schema().to_json();
+    assert_eq!(
+        schema["properties"]["tags"],
+        serde_json::json!({
+            "type": "array",
+            "items": { "type": "string" },
+            "minItems": 3,
+            "maxItems": 5,
+            "uniqueItems": true
+        })
+    );
+    assert_eq!(schema["properties"]["scores"]["maxItems"], 2);
+    assert!(schema["properties"]["scores"].get("minItems").is_none());
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+struct SkippedFields {
+    name: String,
+    #[llm(skip)]
+    #[serde(default)]
+    internal_id: u64,
+    #[llm(skip)]
+    cached_score: Option<f32>,
+}
+
+#[test]
+fn llm_skip_omits_fields_from_properties_and_required() {
+    let schema = SkippedFields::schema().to_json();
+    assert_eq!(property_keys(&schema), ["name"]);
+    assert_eq!(schema["required"], serde_json::json!(["name"]));
+
+    // The model's reply lacks the skipped fields; serde fills them in.
+    let parsed: SkippedFields = serde_json::from_str(r#"{"name":"Ada"}"#).unwrap();
+    assert_eq!((parsed.internal_id, parsed.cached_score), (0, None));
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+struct SerdeSkippedFields {
+    name: String,
+    #[serde(skip)]
+    cache_key: String,
+    #[serde(skip_deserializing)]
+    fetched: bool,
+    #[serde(skip_serializing)]
+    note: Option<String>,
+}
+
+#[test]
+fn serde_skipped_fields_are_never_requested() {
+    let schema = SerdeSkippedFields::schema().to_json();
+    // `skip_serializing` fields are still read from the reply, so they stay.
+    assert_eq!(property_keys(&schema), ["name", "note"]);
+    assert_eq!(schema["required"], serde_json::json!(["name"]));
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+#[serde(tag = "kind")]
+enum SkippedVariantField {
+    Note {
+        text: String,
+        #[llm(skip)]
+        #[serde(default)]
+        revision: u32,
+    },
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+#[serde(tag = "kind")]
+enum SerdeSkippedVariantField {
+    Note {
+        text: String,
+        #[serde(skip)]
+        revision: u32,
+    },
+}
+
+#[test]
+fn llm_skip_applies_to_enum_variant_fields() {
+    let schema = SkippedVariantField::schema().to_json();
+    assert_eq!(property_keys(&schema["anyOf"][0]), ["kind", "text"]);
+
+    let schema = SerdeSkippedVariantField::schema().to_json();
+    assert_eq!(property_keys(&schema["anyOf"][0]), ["kind", "text"]);
+}
+
+// ===========================================================================
+// Markdown documentation
+// ===========================================================================
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+#[llm(description = "Where a customer receives mail")]
+struct MailingAddress {
+    #[llm(description = "City name", example = "London")]
+    city: String,
+    postcode: Option<String>,
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+enum AccountStatus {
+    Active,
+    Suspended,
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+#[llm(description = "A customer record")]
+struct DocumentedCustomer {
+    #[llm(description = "Full legal name", examples = ["Ada Lovelace"])]
+    name: String,
+    status: AccountStatus,
+    addresses: Vec<MailingAddress>,
+}
+
+#[test]
+fn derived_schema_renders_as_markdown_tables() {
+    let markdown = DocumentedCustomer::schema().to_markdown();
+    assert!(markdown.starts_with("# DocumentedCustomer\n\nA customer record\n"));
+    assert!(
+        markdown.contains("| `name` | string | yes | Full legal name | `\"Ada Lovelace\"` |  |")
+    );
+    assert!(
+        markdown
+            .contains("| `status` | string | yes |  |  | one of `\"Active\"`, `\"Suspended\"` |")
+    );
+    assert!(
+        markdown.contains("| `addresses` | array of [MailingAddress](#mailingaddress) | yes |")
+    );
+    assert!(markdown.contains("## MailingAddress\n\nWhere a customer receives mail\n"));
+    assert!(markdown.contains("| `city` | string | yes | City name | `\"London\"` |  |"));
+    assert!(markdown.contains("| `postcode` | string | no |"));
+}
+
+// ===========================================================================
+// Nested generics: arbitrary nesting of Option/Vec/Map/Box
+// ===========================================================================
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+struct NestedGenerics {
+    matrix: Vec<Option<Vec<i32>>>,
+    tags: Option<Option<Vec<String>>>,
+    groups: Option<Vec<Option<Vec<Inner>>>>,
+    by_level: Option<HashMap<Level, Vec<Option<Inner>>>>,
+    boxed_list: Box<Option<Vec<u8>>>,
+    sets: Vec<std::collections::BTreeSet<String>>,
+}
+
+#[test]
+fn nested_generics_keep_every_level() {
+    let schema = NestedGenerics::schema().inline().to_json();
+    let props = &schema["properties"];
+
+    // `Option` below field level has no `required` list to express it, so
+    // the item itself admits `null`.
+    assert_eq!(
+        props["matrix"],
+        serde_json::json!({
+            "type": "array",
+            "items": { "type": ["array", "null"], "items": { "type": "integer" } }
+        })
+    );
+    assert_eq!(
+        props["tags"],
+        serde_json::json!({ "type": "array", "items": { "type": "string" } })
+    );
+    assert_eq!(
+        props["groups"]["items"]["type"],
+        serde_json::json!(["array", "null"])
+    );
+    let inner = &props["groups"]["items"]["items"];
+    assert_eq!(inner["type"], "object");
+    assert_eq!(inner["properties"]["b"]["type"], "string");
+
+    let by_level = &props["by_level"];
+    assert_eq!(by_level["type"], "object");
+    assert_eq!(by_level["x-enum-keys"], serde_json::json!(["A", "B"]));
+    let level_item = &by_level["additionalProperties"]["items"];
+    assert_eq!(level_item["type"], serde_json::json!(["object", "null"]));
+    assert_eq!(level_item["properties"]["b"]["type"], "string");
+
+    assert_eq!(props["boxed_list"]["items"]["type"], "integer");
+    assert_eq!(props["sets"]["items"]["uniqueItems"], true);
+
+    let required = schema["required"].as_array().unwrap();
+    assert_eq!(required, &["matrix", "boxed_list", "sets"]);
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+enum NestedPayload {
+    Grid { rows: Vec<Vec<Option<f64>>> },
+    Lookup(HashMap<String, Vec<Vec<String>>>),
+}
+
+#[test]
+fn nested_generics_in_enum_variants_keep_every_level() {
+    let schema = NestedPayload::schema().to_json();
+    let rows = &schema["anyOf"][0]["properties"]["Grid"]["properties"]["rows"];
+    assert_eq!(
+        rows["items"]["items"]["type"],
+        serde_json::json!(["number", "null"])
+    );
+    let lookup = &schema["anyOf"][1]["properties"]["Lookup"];
+    assert_eq!(
+        lookup["additionalProperties"]["items"]["items"]["type"],
+        "string"
+    );
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+struct Category {
+    name: String,
+    children_by_name: Option<HashMap<String, Vec<Option<Box<Category>>>>>,
+}
+
+#[test]
+fn self_reference_inside_map_values_uses_ref() {
+    let schema = Category::schema().to_json();
+    let def = &schema["$defs"]["Category"];
+    assert_eq!(
+        def["properties"]["children_by_name"]["additionalProperties"]["items"],
+        serde_json::json!({ "anyOf": [{ "$ref": "#/$defs/Category" }, { "type": "null" }] })
+    );
+}
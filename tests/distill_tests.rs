@@ -0,0 +1,90 @@
+//! Offline tests for [`DistillClient`], using [`MockClient`] for both the
+//! primary and the shadow model.
+
+#![cfg(feature = "mock")]
+
+use rstructor::{DistillClient, Instructor, LLMClient, MockClient, RStructorError};
+use serde::{Deserialize, Serialize};
+
+#[derive(Instructor, Serialize, Deserialize, Debug, PartialEq)]
+struct Movie {
+    title: String,
+    year: u16,
+}
+
+const INCEPTION: &str = r#"{"title": "Inception", "year": 2010}"#;
+
+#[tokio::test]
+async fn primary_answers_and_shadow_agreement_is_tracked() {
+    let primary = MockClient::new().with_default_response(INCEPTION);
+    let shadow = MockClient::new()
+        .with_response(r#"{"year": 2010, "title": "Inception"}"#)
+        .with_response(r#"{"title": "Interstellar", "year": 2014}"#);
+    let client = DistillClient::new(primary, shadow).sample_every(1);
+
+    for _ in 0..2 {
+        let movie: Movie = client.materialize("Describe Inception").await.unwrap();
+        assert_eq!(movie.title, "Inception");
+    }
+
+    let stats = client.stats_for::<Movie>();
+    assert_eq!(stats.compared, 2);
+    assert_eq!(stats.agreed, 1);
+    assert_eq!(stats.agreement_rate(), Some(0.5));
+}
+
+#[tokio::test]
+async fn shadow_runs_only_on_sampled_calls() {
+    let primary = MockClient::new().with_default_response(INCEPTION);
+    let shadow = MockClient::new().with_default_response(INCEPTION);
+    let client = DistillClient::new(primary, shadow).sample_every(3);
+
+    for _ in 0..7 {
+        let _: Movie = client.materialize("p").await.unwrap();
+    }
+
+    assert_eq!(client.primary().request_count(), 7);
+    // Calls 1, 4, and 7 are sampled.
+    assert_eq!(client.shadow().request_count(), 3);
+    assert_eq!(client.total_stats().shadow_runs(), 3);
+}
+
+#[tokio::test]
+async fn shadow_failure_counts_as_disagreement_and_is_hidden() {
+    let primary = MockClient::new().with_default_response(INCEPTION);
+    let shadow = MockClient::new().with_response(r#"{"title": "Inception"}"#);
+    let client = DistillClient::new(primary, shadow).sample_every(1);
+
+    let movie: Movie = client.materialize("p").await.unwrap();
+    assert_eq!(movie.year, 2010);
+
+    let stats = client.stats_for::<Movie>();
+    assert_eq!(stats.shadow_failed, 1);
+    assert_eq!(stats.agreement_rate(), Some(0.0));
+}
+
+#[tokio::test]
+async fn primary_error_is_returned_without_shadowing() {
+    let primary = MockClient::new().with_error(RStructorError::Timeout);
+    let shadow = MockClient::new().with_default_response(INCEPTION);
+    let client = DistillClient::new(primary, shadow).sample_every(1);
+
+    let err = client.materialize::<Movie>("p").await.unwrap_err();
+    assert_eq!(err, RStructorError::Timeout);
+    assert_eq!(client.shadow().request_count(), 0);
+    assert_eq!(client.stats_for::<Movie>().agreement_rate(), None);
+}
+
+#[tokio::test]
+async fn clones_share_stats_and_reset_clears_them() {
+    let primary = MockClient::new().with_default_response(INCEPTION);
+    let shadow = MockClient::new().with_default_response(INCEPTION);
+    let client = DistillClient::new(primary, shadow).sample_every(1);
+    let clone = client.clone();
+
+    let _: Movie = clone.materialize("p").await.unwrap();
+    assert_eq!(client.stats_for::<Movie>().agreed, 1);
+
+    client.reset_stats();
+    assert!(clone.stats().is_empty());
+}
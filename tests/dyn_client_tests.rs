@@ -0,0 +1,134 @@
+//! Type-erased clients: a `Box<dyn DynLLMClient>` holding either a
+//! `MockClient` or a real provider client (against a local mock server)
+//! materializes raw JSON for a runtime schema, or typed values through
+//! `DynMaterializeExt`.
+#![cfg(all(feature = "mock", feature = "openai"))]
+
+use std::sync::Arc;
+
+use rstructor::{
+    DynLLMClient, DynMaterializeExt, Instructor, MockClient, RStructorError, Schema, SchemaType,
+    ValueValidator,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Instructor, Serialize, Deserialize, Debug, PartialEq)]
+#[llm(validate = "validate_city")]
+struct City {
+    name: String,
+    population: u64,
+}
+
+fn validate_city(city: &City) -> rstructor::Result<()> {
+    if city.population == 0 {
+        return Err(RStructorError::ValidationError(
+            "population must be positive".into(),
+        ));
+    }
+    Ok(())
+}
+
+const PARIS: &str = r#"{"name": "Paris", "population": 2100000}"#;
+
+fn paris() -> City {
+    City {
+        name: "Paris".into(),
+        population: 2_100_000,
+    }
+}
+
+#[tokio::test]
+async fn raw_values_follow_the_runtime_schema() {
+    let mock = Arc::new(MockClient::new().with_response(r#"{"answer": 42}"#));
+    let client: Arc<dyn DynLLMClient> = mock.clone();
+    let schema = Schema::new(json!({
+        "type": "object",
+        "title": "Answer",
+        "properties": { "answer": { "type": "integer" } },
+        "required": ["answer"]
+    }));
+
+    let value = client
+        .materialize_value("The answer?", &schema)
+        .await
+        .unwrap();
+    assert_eq!(value, json!({ "answer": 42 }));
+    assert_eq!(mock.last_request().unwrap().schema, Some(schema.to_json()));
+}
+
+#[tokio::test]
+async fn typed_materialize_sends_the_type_schema_and_re_asks_on_invalid_values() {
+    let mock = Arc::new(
+        MockClient::new()
+            .with_retries(1)
+            .with_response(r#"{"name": "Atlantis", "population": 0}"#)
+            .with_response(PARIS),
+    );
+    let client: Arc<dyn DynLLMClient> = mock.clone();
+
+    let city: City = client.materialize("Capital of France?").await.unwrap();
+    assert_eq!(city, paris());
+    assert!(mock.responses_exhausted());
+    assert_eq!(
+        mock.last_request().unwrap().schema,
+        Some(City::schema().to_json())
+    );
+}
+
+#[tokio::test]
+async fn custom_validators_reject_values() {
+    let client: Box<dyn DynLLMClient> =
+        Box::new(MockClient::new().with_response(r#"{"answer": -1}"#));
+    let validator: ValueValidator = Arc::new(|value| {
+        if value["answer"].as_i64().is_some_and(|n| n < 0) {
+            return Err(RStructorError::ValidationError("negative".into()));
+        }
+        Ok(())
+    });
+    let schema = Schema::new(json!({ "type": "object" }));
+    let err = client
+        .materialize_value_validated("The answer?", &schema, validator)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, RStructorError::ValidationError(_)), "{err:?}");
+}
+
+#[tokio::test]
+async fn heterogeneous_clients_share_one_collection() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("POST", "/chat/completions")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({
+                "choices": [{
+                    "message": { "role": "assistant", "content": PARIS },
+                    "finish_reason": "stop"
+                }]
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+    let openai = rstructor::OpenAIClient::new("test-key")
+        .unwrap()
+        .base_url(server.url())
+        .model("gpt-4o-mini");
+
+    let clients: Vec<Box<dyn DynLLMClient>> = vec![
+        Box::new(MockClient::new().with_response(PARIS)),
+        Box::new(openai),
+    ];
+    for client in &clients {
+        let city: City = client.materialize("Capital of France?").await.unwrap();
+        assert_eq!(city, paris());
+    }
+}
+
+#[tokio::test]
+async fn generate_text_passes_through() {
+    let client: Box<dyn DynLLMClient> = Box::new(MockClient::new().with_response("Bonjour"));
+    assert_eq!(client.generate_text("Greet me").await.unwrap(), "Bonjour");
+}
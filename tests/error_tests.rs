@@ -58,6 +58,7 @@ mod error_tests {
             .is_retryable()
         );
         assert!(ApiErrorKind::ServiceUnavailable.is_retryable());
+        assert!(ApiErrorKind::Overloaded { retry_after: None }.is_retryable());
         assert!(ApiErrorKind::GatewayError { code: 520 }.is_retryable());
         assert!(ApiErrorKind::GatewayError { code: 521 }.is_retryable());
         assert!(ApiErrorKind::GatewayError { code: 522 }.is_retryable());
@@ -72,7 +73,8 @@ mod error_tests {
         assert!(!ApiErrorKind::RequestTooLarge.is_retryable());
         assert!(
             !ApiErrorKind::BadRequest {
-                details: "test".into()
+                details: "test".into(),
+                provider_error: None,
             }
             .is_retryable()
         );
@@ -86,7 +88,8 @@ mod error_tests {
         assert!(
             !ApiErrorKind::Other {
                 code: 418,
-                message: "teapot".into()
+                message: "teapot".into(),
+                provider_error: None,
             }
             .is_retryable()
         );
@@ -114,6 +117,13 @@ mod error_tests {
             Some(Duration::from_secs(42))
         );
         assert!(ApiErrorKind::ServiceUnavailable.retry_delay().is_some());
+        assert_eq!(
+            ApiErrorKind::Overloaded {
+                retry_after: Some(Duration::from_secs(9))
+            }
+            .retry_delay(),
+            Some(Duration::from_secs(9))
+        );
         assert!(
             ApiErrorKind::GatewayError { code: 520 }
                 .retry_delay()
@@ -131,7 +141,8 @@ mod error_tests {
         assert!(ApiErrorKind::RequestTooLarge.retry_delay().is_none());
         assert!(
             ApiErrorKind::BadRequest {
-                details: "test".into()
+                details: "test".into(),
+                provider_error: None,
             }
             .retry_delay()
             .is_none()
@@ -181,6 +192,7 @@ mod error_tests {
                 "new",
             ),
             (ApiErrorKind::ServiceUnavailable, "temporarily unavailable"),
+            (ApiErrorKind::Overloaded { retry_after: None }, "overloaded"),
             (ApiErrorKind::GatewayError { code: 520 }, "520"),
             (ApiErrorKind::AuthenticationFailed, "API_KEY"),
             (ApiErrorKind::PermissionDenied, "Permission denied"),
@@ -188,6 +200,7 @@ mod error_tests {
             (
                 ApiErrorKind::BadRequest {
                     details: "invalid param".into(),
+                    provider_error: None,
                 },
                 "invalid param",
             ),
@@ -196,6 +209,7 @@ mod error_tests {
                 ApiErrorKind::Other {
                     code: 418,
                     message: "I'm a teapot".into(),
+                    provider_error: None,
                 },
                 "teapot",
             ),
@@ -236,17 +250,20 @@ mod error_tests {
                 suggestion: Some("alt".into()),
             },
             ApiErrorKind::ServiceUnavailable,
+            ApiErrorKind::Overloaded { retry_after: None },
             ApiErrorKind::GatewayError { code: 520 },
             ApiErrorKind::AuthenticationFailed,
             ApiErrorKind::PermissionDenied,
             ApiErrorKind::RequestTooLarge,
             ApiErrorKind::BadRequest {
                 details: "test".into(),
+                provider_error: None,
             },
             ApiErrorKind::ServerError { code: 500 },
             ApiErrorKind::Other {
                 code: 999,
                 message: "custom".into(),
+                provider_error: None,
             },
             ApiErrorKind::UnexpectedResponse {
                 details: "test".into(),
@@ -189,31 +189,17 @@ mod example_validation_tests {
         let entities_prop = &schema_json["properties"]["entities"];
         assert_eq!(entities_prop["type"], "array");
 
-        // Verify entities items are objects
+        // Verify entities items embed TestEntity's own schema
         let entities_items = &entities_prop["items"];
-        // Note: The schema enhancement logic adds properties even if type might be initially wrong
-        // So we check for properties existence as the real indicator
-        assert!(
-            entities_items["type"] == "object" || entities_items["properties"].is_object(),
-            "Entities items should be objects or have properties. Got: {:?}",
-            entities_items
-        );
-
-        // Verify entities items have schema structure (either from enhancement or embedding)
-        assert!(
-            entities_items["properties"].is_object(),
-            "Entities items should have properties. Got: {:?}",
-            entities_items
-        );
+        assert_eq!(entities_items["type"], "object");
         let entity_props = entities_items["properties"].as_object().unwrap();
-        // The enhancement logic adds generic properties (name, entity_type, relevance)
-        // so at least one of these should exist
-        assert!(
-            entity_props.contains_key("name")
-                || entity_props.contains_key("entity_type")
-                || entity_props.contains_key("relevance"),
-            "Entity properties should include name, entity_type, or relevance. Got: {:?}",
-            entity_props
+        assert_eq!(
+            entity_props.keys().collect::<Vec<_>>(),
+            ["name", "entity_type", "relevance"]
+        );
+        assert_eq!(
+            entities_items["required"],
+            serde_json::json!(["name", "entity_type", "relevance"])
         );
     }
 
@@ -296,4 +282,39 @@ mod example_validation_tests {
         assert_eq!(analysis.entities[0].name, "Microsoft");
         assert_eq!(analysis.entities[0].relevance, 8);
     }
+
+    #[test]
+    fn test_example_value_assembles_nested_instance() {
+        let example = TestArticleAnalysis::example_value().expect("every field has an example");
+        assert_eq!(example["title"], "Tech Stocks Tumble");
+        assert_eq!(example["entities"][0]["name"], "Microsoft");
+        assert_eq!(example["entities"][0]["relevance"], 8);
+
+        let analysis: TestArticleAnalysis = serde_json::from_value(example).unwrap();
+        assert_eq!(analysis.entities.len(), 1);
+    }
+
+    #[test]
+    fn test_example_value_requires_every_required_field() {
+        // `ingredients` and `nutrition` carry no field-level example, but their
+        // item/nested types do, so the recipe can still be assembled.
+        let recipe = TestRecipe::example_value().unwrap();
+        assert_eq!(recipe["nutrition"]["calories"], 350);
+        assert_eq!(recipe["ingredients"][0]["unit"], "cups");
+
+        #[derive(Instructor, Serialize, Deserialize, Debug)]
+        struct NoExamples {
+            name: String,
+        }
+        assert_eq!(NoExamples::example_value(), None);
+        assert!(!NoExamples::prompt_instructions().contains("Example of a valid response"));
+    }
+
+    #[test]
+    fn test_prompt_instructions_include_example() {
+        let instructions = TestEntity::prompt_instructions();
+        assert!(instructions.contains("JSON Schema"));
+        assert!(instructions.contains("Example of a valid response"));
+        assert!(instructions.contains("\"entity_type\": \"organization\""));
+    }
 }
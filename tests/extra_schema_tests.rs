@@ -0,0 +1,65 @@
+//! `#[llm(extra_schema = "..")]`: raw keywords merged into the generated
+//! schema on containers and fields.
+
+use rstructor::{Instructor, SchemaType};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+#[llm(
+    description = "A person",
+    extra_schema = r#"{"x-order": ["name", "age"], "description": "Overridden"}"#
+)]
+struct Person {
+    #[llm(description = "Full name", extra_schema = r#"{"x-pii": true}"#)]
+    name: String,
+    #[llm(extra_schema = r#"{"type": "number", "x-unit": "years"}"#)]
+    age: u32,
+    nickname: Option<String>,
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+#[llm(extra_schema = r#"{"x-kind": "shape"}"#)]
+enum Shape {
+    Circle {
+        #[llm(extra_schema = r#"{"x-unit": "cm"}"#)]
+        radius: f64,
+    },
+    Square {
+        side: f64,
+    },
+}
+
+#[test]
+fn container_keywords_are_merged_into_the_root() {
+    let schema = Person::schema().to_json();
+    assert_eq!(schema["x-order"], json!(["name", "age"]));
+    assert_eq!(schema["type"], "object");
+}
+
+#[test]
+fn field_keywords_are_merged_into_the_property() {
+    let schema = Person::schema().to_json();
+    let name = &schema["properties"]["name"];
+    assert_eq!(name["x-pii"], true);
+    assert_eq!(name["description"], "Full name");
+    assert_eq!(name["type"], "string");
+    assert!(schema["properties"]["nickname"].get("x-pii").is_none());
+}
+
+#[test]
+fn extra_keywords_replace_generated_ones() {
+    let schema = Person::schema().to_json();
+    assert_eq!(schema["description"], "Overridden");
+    assert_eq!(schema["properties"]["age"]["type"], "number");
+    assert_eq!(schema["properties"]["age"]["x-unit"], "years");
+}
+
+#[test]
+fn enums_take_container_and_variant_field_keywords() {
+    let schema = Shape::schema().to_json();
+    assert_eq!(schema["x-kind"], "shape");
+    let text = schema.to_string();
+    assert!(text.contains(r#""x-unit":"cm""#), "{text}");
+    assert_eq!(text.matches("x-unit").count(), 1, "{text}");
+}
@@ -0,0 +1,172 @@
+//! Offline tests for [`FaultInjectingClient`] wrapped around a [`MockClient`].
+
+#![cfg(all(feature = "test-util", feature = "mock"))]
+
+use std::time::{Duration, Instant};
+
+use rstructor::{
+    ApiErrorKind, FaultInjectingClient, InjectedFaults, Instructor, LLMClient, MockClient,
+    RStructorError,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Instructor, Serialize, Deserialize, Debug, PartialEq)]
+struct Movie {
+    title: String,
+    year: u16,
+}
+
+const MOVIE: &str = r#"{"title":"Heat","year":1995}"#;
+
+#[tokio::test]
+async fn passes_calls_through_when_no_fault_fires() {
+    let mock = MockClient::new().with_default_response(MOVIE);
+    let client = FaultInjectingClient::new(mock.clone())
+        .api_error(0.0, ApiErrorKind::ServiceUnavailable)
+        .malformed_json(0.0);
+
+    let movie: Movie = client.materialize("Describe Heat").await.unwrap();
+    assert_eq!(movie.year, 1995);
+    assert_eq!(mock.requests().len(), 1);
+    assert_eq!(client.injected(), InjectedFaults::default());
+}
+
+#[tokio::test]
+async fn injected_api_errors_never_reach_the_wrapped_client() {
+    let mock = MockClient::new().with_default_response(MOVIE);
+    let client = FaultInjectingClient::new(mock.clone())
+        .api_error(1.0, ApiErrorKind::ServiceUnavailable)
+        .api_error(1.0, ApiErrorKind::AuthenticationFailed);
+
+    let err = client
+        .materialize::<Movie>("Describe Heat")
+        .await
+        .unwrap_err();
+    // The first kind added wins when several fire.
+    assert_eq!(
+        err.api_error_kind(),
+        Some(&ApiErrorKind::ServiceUnavailable)
+    );
+    assert!(err.is_retryable());
+    assert!(mock.requests().is_empty());
+    assert_eq!(client.injected().api_errors, 1);
+}
+
+#[tokio::test]
+async fn timeouts_wait_before_failing() {
+    let client = FaultInjectingClient::new(MockClient::new().with_default_response("hi"))
+        .timeout(1.0, Duration::from_millis(50));
+
+    let started = Instant::now();
+    let err = client.generate("Say hi").await.unwrap_err();
+    assert!(matches!(err, RStructorError::Timeout), "{err:?}");
+    assert!(started.elapsed() >= Duration::from_millis(50));
+}
+
+#[tokio::test]
+async fn malformed_json_reaches_the_wrapped_clients_parser() {
+    let mock = MockClient::new().with_default_response(MOVIE);
+    let client = FaultInjectingClient::new(mock.clone()).malformed_json(1.0);
+
+    let err = client
+        .materialize::<Movie>("Describe Heat")
+        .await
+        .unwrap_err();
+    // The cut-off reply is reported the way a live provider reports it
+    assert!(err.to_string().contains("truncated output"), "{err}");
+    let text = client.generate("Describe Heat").await.unwrap();
+    assert_eq!(text, &MOVIE[..MOVIE.len() / 2]);
+    // A malformed reply is still a request the wrapped client served.
+    assert_eq!(mock.requests().len(), 2);
+    assert_eq!(client.injected().malformed, 2);
+}
+
+#[tokio::test]
+async fn malformed_json_is_repaired_by_the_wrapped_clients_re_ask() {
+    let mock = MockClient::new()
+        .with_response(MOVIE)
+        .with_response(r#"{"title":"Ronin","year":1998}"#)
+        .with_retries(1);
+    let client = FaultInjectingClient::new(mock.clone()).malformed_json(1.0);
+
+    let result = client
+        .materialize_with_metadata::<Movie>("Describe a heist film")
+        .await
+        .unwrap();
+    // Only the first reply is cut off; the re-ask gets the real second one.
+    assert_eq!(result.data.title, "Ronin");
+    let contents: Vec<_> = result
+        .conversation
+        .iter()
+        .map(|message| message.content.as_str())
+        .collect();
+    assert_eq!(contents[1], &MOVIE[..MOVIE.len() / 2]);
+    assert!(contents[2].contains("truncated"), "{}", contents[2]);
+    assert_eq!(contents.len(), 4);
+    assert_eq!(client.injected().malformed, 1);
+}
+
+#[tokio::test]
+async fn list_models_is_never_counted_as_malformed() {
+    let client = FaultInjectingClient::new(MockClient::new()).malformed_json(1.0);
+    let _ = client.list_models().await;
+    assert_eq!(client.injected(), InjectedFaults::default());
+}
+
+#[tokio::test]
+async fn seeded_runs_inject_the_same_faults() {
+    async fn outcomes(seed: u64) -> (Vec<bool>, InjectedFaults) {
+        let client = FaultInjectingClient::new(MockClient::new().with_default_response("ok"))
+            .seed(seed)
+            .delay(0.5, Duration::from_millis(1))
+            .api_error(0.3, ApiErrorKind::RequestTimeout);
+        let mut ok = Vec::new();
+        for _ in 0..40 {
+            ok.push(client.generate("ping").await.is_ok());
+        }
+        (ok, client.injected())
+    }
+
+    let (first, injected) = outcomes(42).await;
+    assert_eq!(outcomes(42).await, (first.clone(), injected));
+    let failures = first.iter().filter(|ok| !**ok).count() as u64;
+    assert_eq!(failures, injected.api_errors);
+    assert!(failures > 0 && failures < 40, "{failures} of 40 failed");
+    assert!(injected.delays > 0 && injected.delays < 40, "{injected:?}");
+}
+
+#[cfg(feature = "ollama")]
+#[tokio::test]
+async fn malformed_json_goes_through_a_providers_truncation_check() {
+    let mut server = mockito::Server::new_async().await;
+    let reply = serde_json::json!({
+        "model": "llama3.2",
+        "message": { "role": "assistant", "content": MOVIE },
+        "done": true
+    });
+    let m = server
+        .mock("POST", "/api/chat")
+        .with_status(200)
+        .with_body(reply.to_string())
+        .expect(1)
+        .create_async()
+        .await;
+    let ollama = rstructor::OllamaClient::new()
+        .base_url(server.url())
+        .no_retries();
+    let client = FaultInjectingClient::new(ollama).malformed_json(1.0);
+
+    let err = client
+        .materialize::<Movie>("Describe Heat")
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(
+            err.api_error_kind(),
+            Some(ApiErrorKind::UnexpectedResponse { details }) if details == "truncated output"
+        ),
+        "{err:?}"
+    );
+    assert_eq!(client.injected().malformed, 1);
+    m.assert_async().await;
+}
@@ -0,0 +1,120 @@
+//! Fixture recording writes anonymized request/response pairs and leaves the
+//! call itself unaffected. Recording is process-wide, so this file holds a
+//! single test.
+#![cfg(all(feature = "test-util", feature = "openai", feature = "gemini"))]
+
+use rstructor::{Fixture, Instructor, LLMClient};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+struct Movie {
+    title: String,
+    year: u16,
+}
+
+const SECRET: &str = "sk-fixture-secret";
+
+async fn serve(server: &mut mockito::Server, path: mockito::Matcher, body: serde_json::Value) {
+    server
+        .mock("POST", path)
+        .match_query(mockito::Matcher::Any)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(body.to_string())
+        .create_async()
+        .await;
+}
+
+#[tokio::test]
+async fn recording_writes_anonymized_fixtures_and_returns_the_response() {
+    let dir = std::env::temp_dir().join(format!("rstructor-recording-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    rstructor::record_fixtures(&dir);
+    assert_eq!(rstructor::fixture_recording_dir(), Some(dir.clone()));
+
+    let mut openai = mockito::Server::new_async().await;
+    serve(
+        &mut openai,
+        "/chat/completions".into(),
+        json!({
+            "id": "chatcmpl-123",
+            "created": 1_752_000_000,
+            "model": "gpt-4o-mini",
+            "choices": [{
+                "message": { "role": "assistant", "content": r#"{"title":"Inception","year":2010}"# },
+                "finish_reason": "stop"
+            }],
+            "usage": { "prompt_tokens": 10, "completion_tokens": 5, "total_tokens": 15 }
+        }),
+    )
+    .await;
+    let client = rstructor::OpenAIClient::new(SECRET)
+        .unwrap()
+        .base_url(openai.url())
+        .model("gpt-4o-mini");
+    let movie: Movie = client.materialize("Tell me about Inception").await.unwrap();
+    assert_eq!(movie.year, 2010);
+
+    let mut gemini = mockito::Server::new_async().await;
+    serve(
+        &mut gemini,
+        mockito::Matcher::Regex(r"^/models/.+:generateContent$".to_string()),
+        json!({
+            "candidates": [{ "content": { "parts": [{ "text": "Christopher Nolan" }] }, "finishReason": "STOP" }],
+            "usageMetadata": { "promptTokenCount": 4, "candidatesTokenCount": 3 },
+            "responseId": "resp-1"
+        }),
+    )
+    .await;
+    let client = rstructor::GeminiClient::new(SECRET)
+        .unwrap()
+        .base_url(gemini.url());
+    let text = client.generate("Who directed Inception?").await.unwrap();
+    assert_eq!(text, "Christopher Nolan");
+
+    rstructor::stop_recording_fixtures();
+    assert_eq!(rstructor::fixture_recording_dir(), None);
+    let client = rstructor::OpenAIClient::new(SECRET)
+        .unwrap()
+        .base_url(openai.url())
+        .model("gpt-4o-mini");
+    let _: Movie = client.materialize("Not recorded").await.unwrap();
+
+    let fixtures = Fixture::load_dir(&dir).unwrap();
+    let names: Vec<_> = fixtures
+        .iter()
+        .map(|(path, _)| path.strip_prefix(&dir).unwrap().to_path_buf())
+        .collect();
+    assert_eq!(
+        names,
+        [
+            std::path::Path::new("gemini/001.json"),
+            std::path::Path::new("openai/001.json")
+        ]
+    );
+
+    let gemini_fixture = &fixtures[0].1;
+    assert_eq!(gemini_fixture.provider, "Gemini");
+    assert!(gemini_fixture.request.path.ends_with(":generateContent"));
+    assert_eq!(gemini_fixture.response.body["responseId"], "<responseId>");
+
+    let openai_fixture = &fixtures[1].1;
+    assert_eq!(openai_fixture.request.method, "POST");
+    assert_eq!(openai_fixture.request.path, "/chat/completions");
+    assert_eq!(openai_fixture.request.body["model"], "gpt-4o-mini");
+    assert_eq!(openai_fixture.response.status, 200);
+    assert_eq!(openai_fixture.response.body["id"], "<id>");
+    assert_eq!(openai_fixture.response.body["created"], 0);
+    assert_eq!(openai_fixture.response.body["usage"]["prompt_tokens"], 10);
+
+    for (path, _) in &fixtures {
+        let text = std::fs::read_to_string(path).unwrap();
+        assert!(
+            !text.contains(SECRET),
+            "{} leaks the API key",
+            path.display()
+        );
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
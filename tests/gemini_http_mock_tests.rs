@@ -0,0 +1,140 @@
+//! Drive the real `GeminiClient` over a local mock HTTP server (`mockito`),
+//! covering response parsing. No API key or network needed.
+#![cfg(feature = "gemini")]
+
+use rstructor::{GeminiClient, Instructor, LLMClient};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Instructor, Serialize, Deserialize, Debug, PartialEq)]
+struct Movie {
+    title: String,
+    year: u16,
+}
+
+fn client(server: &mockito::Server) -> GeminiClient {
+    GeminiClient::new("test-key")
+        .unwrap()
+        .base_url(server.url())
+        .no_retries()
+}
+
+#[tokio::test]
+async fn usage_counts_thinking_tokens_as_output() {
+    let mut server = mockito::Server::new_async().await;
+    let m = server
+        .mock(
+            "POST",
+            mockito::Matcher::Regex(r"^/models/.+:generateContent".to_string()),
+        )
+        .with_status(200)
+        .with_body(
+            json!({
+                "candidates": [{
+                    "content": { "parts": [{ "text": "{\"title\":\"Alien\",\"year\":1979}" }] },
+                    "finishReason": "STOP"
+                }],
+                "usageMetadata": {
+                    "promptTokenCount": 40,
+                    "candidatesTokenCount": 11,
+                    "thoughtsTokenCount": 250,
+                    "totalTokenCount": 301
+                },
+                "modelVersion": "gemini-3.5-flash"
+            })
+            .to_string(),
+        )
+        .expect(2)
+        .create_async()
+        .await;
+
+    let result = client(&server)
+        .materialize_with_metadata::<Movie>("Alien")
+        .await
+        .unwrap();
+    assert_eq!(result.data.year, 1979);
+    let usage = result.usage.unwrap();
+    assert_eq!(
+        (
+            usage.model.as_str(),
+            usage.input_tokens,
+            usage.output_tokens
+        ),
+        ("gemini-3.5-flash", 40, 261)
+    );
+
+    let text = client(&server)
+        .generate_with_metadata("Alien")
+        .await
+        .unwrap();
+    assert_eq!(text.usage.unwrap().total_tokens(), 301);
+    m.assert_async().await;
+}
+
+#[tokio::test]
+async fn materialize_with_media_sends_inline_data() {
+    let mut server = mockito::Server::new_async().await;
+    let m = server
+        .mock(
+            "POST",
+            mockito::Matcher::Regex(r"^/models/.+:generateContent".to_string()),
+        )
+        .match_body(mockito::Matcher::Regex(
+            r#""inlineData":\{"mimeType":"image/jpeg","data":"YWJj"\}"#.into(),
+        ))
+        .with_status(200)
+        .with_body(
+            json!({
+                "candidates": [{
+                    "content": { "parts": [{ "text": "{\"title\":\"Alien\",\"year\":1979}" }] },
+                    "finishReason": "STOP"
+                }]
+            })
+            .to_string(),
+        )
+        .expect(1)
+        .create_async()
+        .await;
+
+    let media = [rstructor::MediaFile::from_bytes(b"abc", "image/jpeg")];
+    let movie: Movie = client(&server)
+        .materialize_with_media("Which movie is this poster for?", &media)
+        .await
+        .unwrap();
+    assert_eq!(movie.year, 1979);
+    m.assert_async().await;
+}
+
+#[tokio::test]
+async fn materialize_with_media_sends_audio_as_inline_data() {
+    let mut server = mockito::Server::new_async().await;
+    let m = server
+        .mock(
+            "POST",
+            mockito::Matcher::Regex(r"^/models/.+:generateContent".to_string()),
+        )
+        .match_body(mockito::Matcher::Regex(
+            r#""inlineData":\{"mimeType":"audio/ogg","data":"T2dnUw=="\}"#.into(),
+        ))
+        .with_status(200)
+        .with_body(
+            json!({
+                "candidates": [{
+                    "content": { "parts": [{ "text": "{\"title\":\"Alien\",\"year\":1979}" }] },
+                    "finishReason": "STOP"
+                }]
+            })
+            .to_string(),
+        )
+        .expect(1)
+        .create_async()
+        .await;
+
+    let media = [rstructor::MediaFile::from_audio(b"OggS").unwrap()];
+    let movie: Movie = client(&server)
+        .materialize_with_media("Which movie is this trailer for?", &media)
+        .await
+        .unwrap();
+    assert_eq!(movie.year, 1979);
+    m.assert_async().await;
+}
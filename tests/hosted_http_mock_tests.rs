@@ -0,0 +1,146 @@
+//! Drive `GroqClient` and `TogetherClient` over a local mock HTTP server
+//! (`mockito`), covering JSON mode with the schema in the prompt, error
+//! attribution and the model catalogs. No Groq or Together account needed.
+#![cfg(any(feature = "groq", feature = "together"))]
+
+use rstructor::{Instructor, LLMClient};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Instructor, Serialize, Deserialize, Debug, PartialEq)]
+struct Movie {
+    title: String,
+    year: u16,
+}
+
+fn completion(content: &str) -> String {
+    json!({
+        "model": "llama-3.3-70b-versatile",
+        "choices": [{
+            "index": 0,
+            "message": { "role": "assistant", "content": content },
+            "finish_reason": "stop"
+        }],
+        "usage": { "prompt_tokens": 9, "completion_tokens": 4 }
+    })
+    .to_string()
+}
+
+#[cfg(feature = "groq")]
+#[tokio::test]
+async fn groq_materializes_in_json_mode_with_the_schema_in_the_prompt() {
+    use rstructor::{GroqClient, Provider};
+
+    let mut server = mockito::Server::new_async().await;
+    let m = server
+        .mock("POST", "/chat/completions")
+        .match_header("authorization", "Bearer groq-key")
+        .match_body(mockito::Matcher::PartialJson(json!({
+            "model": "llama-3.3-70b-versatile",
+            "response_format": { "type": "json_object" }
+        })))
+        .match_body(mockito::Matcher::Regex(
+            r#""role":"system","content":"Respond with a single JSON object"#.to_string(),
+        ))
+        .with_status(200)
+        .with_body(completion(r#"{"title":"Metropolis","year":1927}"#))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = GroqClient::new("groq-key")
+        .unwrap()
+        .base_url(server.url())
+        .no_retries();
+    assert_eq!(client.model_id().provider, Provider::Groq);
+    let movie: Movie = client.materialize("Describe Metropolis").await.unwrap();
+    assert_eq!(
+        movie,
+        Movie {
+            title: "Metropolis".to_string(),
+            year: 1927
+        }
+    );
+    m.assert_async().await;
+}
+
+#[cfg(feature = "groq")]
+#[tokio::test]
+async fn groq_errors_name_groq() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("POST", "/chat/completions")
+        .with_status(401)
+        .with_body(r#"{"error":{"message":"Invalid API Key","type":"invalid_request_error"}}"#)
+        .create_async()
+        .await;
+
+    let client = rstructor::GroqClient::new("bad-key")
+        .unwrap()
+        .base_url(server.url())
+        .no_retries();
+    let err = client
+        .materialize::<Movie>("Describe Metropolis")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("GROQ_API_KEY"), "{err}");
+}
+
+#[cfg(feature = "together")]
+#[tokio::test]
+async fn together_lists_only_chat_models() {
+    let mut server = mockito::Server::new_async().await;
+    // Together returns a bare array rather than OpenAI's `{"data": [...]}`.
+    server
+        .mock("GET", "/models")
+        .match_header("authorization", "Bearer together-key")
+        .with_status(200)
+        .with_body(
+            json!([
+                {
+                    "id": "meta-llama/Llama-3.3-70B-Instruct-Turbo",
+                    "type": "chat",
+                    "display_name": "Meta Llama 3.3 70B Instruct Turbo",
+                    "organization": "Meta"
+                },
+                {
+                    "id": "black-forest-labs/FLUX.1-schnell",
+                    "type": "image",
+                    "display_name": "FLUX.1 Schnell"
+                },
+                {
+                    "id": "BAAI/bge-large-en-v1.5",
+                    "type": "embedding"
+                }
+            ])
+            .to_string(),
+        )
+        .create_async()
+        .await;
+
+    let client = rstructor::TogetherClient::new("together-key")
+        .unwrap()
+        .base_url(server.url());
+    let models = client.list_models().await.unwrap();
+    assert_eq!(models.len(), 1, "{models:?}");
+    assert_eq!(models[0].id, "meta-llama/Llama-3.3-70B-Instruct-Turbo");
+    assert_eq!(
+        models[0].name.as_deref(),
+        Some("Meta Llama 3.3 70B Instruct Turbo")
+    );
+}
+
+#[cfg(feature = "together")]
+#[test]
+fn together_is_selectable_by_name() {
+    let provider: rstructor::Provider = "together-ai".parse().unwrap();
+    assert_eq!(provider, rstructor::Provider::Together);
+
+    let err = rstructor::TogetherClient::new("together-key")
+        .unwrap()
+        .temperature(5.0)
+        .build()
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("Together: temperature"), "{err}");
+}
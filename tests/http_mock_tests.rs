@@ -7,9 +7,13 @@
 //! shaping and the retry loop it drives are shared by the OpenAI-compatible path.
 #![cfg(feature = "openai")]
 
-use rstructor::{ApiErrorKind, Instructor, LLMClient, OpenAIClient, RStructorError};
+use rstructor::{
+    ApiErrorKind, ChatRole, ContextOverflow, Instructor, LLMClient, OpenAIClient, Provider,
+    RStructorError, RequestExt, RetryBudget, UsageSnapshot, UsageTracker, scoped_api_key,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
+use std::time::Duration;
 
 #[derive(Instructor, Serialize, Deserialize, Debug, PartialEq)]
 #[llm(validate = "validate_movie")]
@@ -72,30 +76,691 @@ async fn materialize_parses_a_real_response() {
     m.assert_async().await;
 }
 
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+struct Screening {
+    movie: Movie,
+    cinema: Option<String>,
+}
+
+/// Structured calls use native Structured Outputs: a strict `json_schema`
+/// `response_format` with every object closed and every property required
+/// (optional ones made nullable), never the legacy `functions` parameters.
+#[tokio::test]
+async fn materialize_requests_strict_json_schema_output() {
+    let mut server = mockito::Server::new_async().await;
+    let m = server
+        .mock("POST", "/chat/completions")
+        .match_body(mockito::Matcher::PartialJson(json!({
+            "response_format": {
+                "type": "json_schema",
+                "json_schema": {
+                    "name": "Screening",
+                    "strict": true,
+                    "schema": {
+                        "additionalProperties": false,
+                        "required": ["movie", "cinema"],
+                        "properties": {
+                            "movie": {
+                                "additionalProperties": false,
+                                "required": ["title", "year"]
+                            },
+                            "cinema": { "type": ["string", "null"] }
+                        }
+                    }
+                }
+            }
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body_from_request(|request| {
+            let body: Value = serde_json::from_slice(request.body().unwrap()).unwrap();
+            assert!(body.get("functions").is_none(), "{body}");
+            assert!(body.get("function_call").is_none(), "{body}");
+            chat_completion(r#"{"movie":{"title":"Alien","year":1979},"cinema":null}"#).into_bytes()
+        })
+        .expect(1)
+        .create_async()
+        .await;
+
+    let screening: Screening = client(&server).materialize("Alien tonight").await.unwrap();
+    assert_eq!(screening.movie.year, 1979);
+    assert_eq!(screening.cinema, None);
+    m.assert_async().await;
+}
+
+#[tokio::test]
+async fn unknown_fields_are_captured_when_enabled() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("POST", "/chat/completions")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(chat_completion(
+            r#"{"title":"Inception","year":2010,"director":"Nolan"}"#,
+        ))
+        .expect(2)
+        .create_async()
+        .await;
+
+    let captured = client(&server)
+        .capture_unknown_fields(true)
+        .materialize_with_metadata::<Movie>("Describe Inception")
+        .await
+        .unwrap();
+    assert_eq!(captured.extra_fields["/director"], json!("Nolan"));
+
+    let dropped = client(&server)
+        .materialize_with_metadata::<Movie>("Describe Inception")
+        .await
+        .unwrap();
+    assert!(dropped.extra_fields.is_empty());
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct StrictMovie {
+    title: String,
+}
+
+#[tokio::test]
+async fn explanation_is_requested_and_kept_off_the_type() {
+    let mut server = mockito::Server::new_async().await;
+    let m = server
+        .mock("POST", "/chat/completions")
+        .match_body(mockito::Matcher::PartialJson(json!({
+            "response_format": { "json_schema": { "schema": {
+                "required": ["_reasoning", "title"]
+            }}}
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(chat_completion(
+            r#"{"_reasoning":"Named in the prompt","title":"Inception"}"#,
+        ))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let result = client(&server)
+        .explain(true)
+        .capture_unknown_fields(true)
+        .materialize_with_metadata::<StrictMovie>("Describe Inception")
+        .await
+        .unwrap();
+    assert_eq!(result.data.title, "Inception");
+    assert_eq!(result.explanation.as_deref(), Some("Named in the prompt"));
+    assert!(result.extra_fields.is_empty());
+    m.assert_async().await;
+}
+
+#[tokio::test]
+async fn reask_loop_recovers_from_validation_failure() {
+    let mut server = mockito::Server::new_async().await;
+    // First response fails the validator; the real re-ask loop must retry with the
+    // error fed back into the conversation, then accept the corrected response.
+    let bad = server
+        .mock("POST", "/chat/completions")
+        .with_status(200)
+        .with_body(chat_completion(r#"{"title":"Old","year":1700}"#))
+        .expect(1)
+        .create_async()
+        .await;
+    let good = server
+        .mock("POST", "/chat/completions")
+        .with_status(200)
+        .with_body(chat_completion(r#"{"title":"Metropolis","year":1927}"#))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let movie: Movie = client(&server).materialize("a film").await.unwrap();
+    assert_eq!(movie.year, 1927);
+    bad.assert_async().await;
+    good.assert_async().await;
+}
+
+/// A mock that answers after `delay`, blocking the mock server meanwhile.
+async fn slow_reply(server: &mut mockito::Server, delay: Duration, content: &str) -> mockito::Mock {
+    let body = chat_completion(content);
+    server
+        .mock("POST", "/chat/completions")
+        .with_status(200)
+        .with_body_from_request(move |_| {
+            std::thread::sleep(delay);
+            body.clone().into_bytes()
+        })
+        .create_async()
+        .await
+}
+
+#[tokio::test]
+async fn materialize_within_returns_a_reply_that_validates_in_time() {
+    let mut server = mockito::Server::new_async().await;
+    slow_reply(
+        &mut server,
+        Duration::ZERO,
+        r#"{"title":"Metropolis","year":1927}"#,
+    )
+    .await;
+
+    let result = client(&server)
+        .materialize_within::<Movie>("a film", Duration::from_secs(5))
+        .await
+        .unwrap();
+    assert!(result.is_valid());
+    assert_eq!(result.into_inner().year, 1927);
+}
+
+#[tokio::test]
+async fn materialize_within_falls_back_to_the_invalid_reply_when_a_reask_runs_late() {
+    let mut server = mockito::Server::new_async().await;
+    let bad = server
+        .mock("POST", "/chat/completions")
+        .with_status(200)
+        .with_body(chat_completion(r#"{"title":"Old","year":1700}"#))
+        .expect(1)
+        .create_async()
+        .await;
+    slow_reply(
+        &mut server,
+        Duration::from_secs(3),
+        r#"{"title":"Metropolis","year":1927}"#,
+    )
+    .await;
+
+    let started = std::time::Instant::now();
+    let result = client(&server)
+        .materialize_within::<Movie>("a film", Duration::from_secs(1))
+        .await
+        .unwrap();
+    assert!(started.elapsed() < Duration::from_secs(2));
+    assert_eq!(
+        result,
+        rstructor::BestEffort::Unvalidated {
+            value: Movie {
+                title: "Old".into(),
+                year: 1700
+            },
+            error: "Validation error: year predates cinema".into()
+        }
+    );
+    bad.assert_async().await;
+}
+
+#[tokio::test]
+async fn materialize_within_times_out_without_any_reply() {
+    let mut server = mockito::Server::new_async().await;
+    slow_reply(
+        &mut server,
+        Duration::from_secs(2),
+        r#"{"title":"Metropolis","year":1927}"#,
+    )
+    .await;
+
+    let err = client(&server)
+        .materialize_within::<Movie>("a film", Duration::from_millis(300))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, RStructorError::Timeout), "{err:?}");
+}
+
+#[tokio::test]
+async fn idempotency_key_is_reused_for_transient_retries_only() {
+    use std::sync::{Arc, Mutex};
+
+    let keys = Arc::new(Mutex::new(Vec::new()));
+    let record = |keys: &Arc<Mutex<Vec<String>>>| {
+        let keys = Arc::clone(keys);
+        move |request: &mockito::Request| {
+            let key = request.header("idempotency-key")[0].to_str().unwrap();
+            keys.lock().unwrap().push(key.to_string());
+        }
+    };
+    let mut server = mockito::Server::new_async().await;
+    let mut responses = Vec::new();
+    for (status, body) in [
+        (520, String::new()), // transient gateway error: same request is resent
+        (200, chat_completion(r#"{"title":"Old","year":1700}"#)), // re-ask: new request
+        (
+            200,
+            chat_completion(r#"{"title":"Metropolis","year":1927}"#),
+        ),
+    ] {
+        let record = record(&keys);
+        responses.push(
+            server
+                .mock("POST", "/chat/completions")
+                .with_status(status)
+                .with_body_from_request(move |request| {
+                    record(request);
+                    body.clone().into()
+                })
+                .expect(1)
+                .create_async()
+                .await,
+        );
+    }
+
+    let movie: Movie = client(&server).materialize("a film").await.unwrap();
+    assert_eq!(movie.year, 1927);
+    let keys = keys.lock().unwrap();
+    assert_eq!(keys.len(), 3);
+    assert!(keys[0].starts_with("rstructor-"));
+    assert_eq!(keys[0], keys[1]);
+    assert_ne!(keys[1], keys[2]);
+}
+
+const CONTEXT_LENGTH_EXCEEDED: &str = r#"{"error": {
+    "message": "This model's maximum context length is 128000 tokens.",
+    "type": "invalid_request_error", "param": "messages", "code": "context_length_exceeded"}}"#;
+
+#[tokio::test]
+async fn context_overflow_is_not_retried_by_default() {
+    let mut server = mockito::Server::new_async().await;
+    let overflow = server
+        .mock("POST", "/chat/completions")
+        .with_status(400)
+        .with_body(CONTEXT_LENGTH_EXCEEDED)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let err = client(&server)
+        .materialize::<Movie>("a very long film")
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.api_error_kind(),
+        Some(ApiErrorKind::ContextLengthExceeded { .. })
+    ));
+    assert!(err.provider_error().unwrap().is_context_length_exceeded());
+    overflow.assert_async().await;
+}
+
+#[tokio::test]
+async fn context_overflow_escalates_to_the_configured_model() {
+    let mut server = mockito::Server::new_async().await;
+    let small = server
+        .mock("POST", "/chat/completions")
+        .match_body(mockito::Matcher::PartialJson(
+            json!({ "model": "gpt-4o-mini" }),
+        ))
+        .with_status(400)
+        .with_body(CONTEXT_LENGTH_EXCEEDED)
+        .expect(1)
+        .create_async()
+        .await;
+    let large = server
+        .mock("POST", "/chat/completions")
+        .match_body(mockito::Matcher::PartialJson(json!({ "model": "gpt-4.1" })))
+        .with_status(200)
+        .with_body(chat_completion(r#"{"title":"Metropolis","year":1927}"#))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let movie: Movie = client(&server)
+        .on_context_overflow(ContextOverflow::Escalate("gpt-4.1".into()))
+        .materialize("a very long film")
+        .await
+        .unwrap();
+    assert_eq!(movie.year, 1927);
+    small.assert_async().await;
+    large.assert_async().await;
+}
+
+#[tokio::test]
+async fn retry_budget_caps_retries_across_calls() {
+    let mut server = mockito::Server::new_async().await;
+    // Every reply fails validation, so each call would retry up to max_retries.
+    let m = server
+        .mock("POST", "/chat/completions")
+        .with_status(200)
+        .with_body(chat_completion(r#"{"title":"Too early","year":1700}"#))
+        .expect(3)
+        .create_async()
+        .await;
+
+    let client = client(&server).max_retries(3);
+    let budget = RetryBudget::new(1);
+    let (first, second) = budget
+        .scope(async {
+            (
+                client.materialize::<Movie>("first step").await,
+                client.materialize::<Movie>("second step").await,
+            )
+        })
+        .await;
+
+    // The first call spends the single retry; the second gets none.
+    assert!(first.is_err() && second.is_err());
+    assert!(budget.is_exhausted());
+    m.assert_async().await;
+}
+
+#[tokio::test]
+async fn per_call_api_key_covers_every_retry() {
+    let mut server = mockito::Server::new_async().await;
+    let bad = server
+        .mock("POST", "/chat/completions")
+        .match_header("authorization", "Bearer tenant-a")
+        .with_status(200)
+        .with_body(chat_completion(r#"{"title":"Old","year":1700}"#))
+        .expect(1)
+        .create_async()
+        .await;
+    let good = server
+        .mock("POST", "/chat/completions")
+        .match_header("authorization", "Bearer tenant-a")
+        .with_status(200)
+        .with_body(chat_completion(r#"{"title":"Metropolis","year":1927}"#))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = client(&server);
+    let movie: Movie = client
+        .with_api_key_for_call(Provider::OpenAI, "tenant-a")
+        .materialize("a film")
+        .await
+        .unwrap();
+    assert_eq!(movie.year, 1927);
+    bad.assert_async().await;
+    good.assert_async().await;
+}
+
+#[tokio::test]
+async fn scoped_api_key_applies_only_inside_the_scope() {
+    let mut server = mockito::Server::new_async().await;
+    let tenant = server
+        .mock("POST", "/chat/completions")
+        .match_header("authorization", "Bearer tenant-b")
+        .with_status(200)
+        .with_body(chat_completion(r#"{"title":"Alien","year":1979}"#))
+        .expect(2)
+        .create_async()
+        .await;
+    let shared = server
+        .mock("POST", "/chat/completions")
+        .match_header("authorization", "Bearer test-key")
+        .with_status(200)
+        .with_body(chat_completion(r#"{"title":"Alien","year":1979}"#))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = client(&server);
+    let (first, second) = scoped_api_key(Provider::OpenAI, "tenant-b", async {
+        (
+            client.materialize::<Movie>("one").await,
+            client.generate("two").await,
+        )
+    })
+    .await;
+    assert!(first.is_ok() && second.is_ok());
+    client.materialize::<Movie>("three").await.unwrap();
+    tenant.assert_async().await;
+    shared.assert_async().await;
+}
+
+#[cfg(feature = "groq")]
+#[tokio::test]
+async fn scoped_api_key_leaves_other_providers_keys_alone() {
+    let mut server = mockito::Server::new_async().await;
+    let tenant = server
+        .mock("POST", "/chat/completions")
+        .match_header("authorization", "Bearer tenant-b")
+        .with_status(200)
+        .with_body(chat_completion("hi"))
+        .expect(1)
+        .create_async()
+        .await;
+    let groq = server
+        .mock("POST", "/chat/completions")
+        .match_header("authorization", "Bearer groq-key")
+        .with_status(200)
+        .with_body(chat_completion("hi"))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let openai = client(&server);
+    // Groq serves through an inner `OpenAIClient`, but under its own provider.
+    let groq_client = rstructor::GroqClient::new("groq-key")
+        .unwrap()
+        .base_url(server.url());
+    let (first, second) = scoped_api_key(Provider::OpenAI, "tenant-b", async {
+        (
+            openai.generate("one").await,
+            groq_client.generate("two").await,
+        )
+    })
+    .await;
+    assert!(first.is_ok() && second.is_ok(), "{first:?} {second:?}");
+    tenant.assert_async().await;
+    groq.assert_async().await;
+}
+
+#[tokio::test]
+async fn metadata_exposes_final_conversation_with_reask_turns() {
+    let mut server = mockito::Server::new_async().await;
+    let _bad = server
+        .mock("POST", "/chat/completions")
+        .with_status(200)
+        .with_body(chat_completion(r#"{"title":"Old","year":1700}"#))
+        .expect(1)
+        .create_async()
+        .await;
+    let _good = server
+        .mock("POST", "/chat/completions")
+        .with_status(200)
+        .with_body(chat_completion(r#"{"title":"Metropolis","year":1927}"#))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let result = client(&server)
+        .materialize_with_metadata::<Movie>("a film")
+        .await
+        .unwrap();
+    assert_eq!(result.data.year, 1927);
+
+    // prompt, failed reply, re-ask feedback, accepted reply
+    let conversation = &result.conversation;
+    assert_eq!(conversation.len(), 4);
+    assert_eq!(conversation[0].role, ChatRole::User);
+    assert_eq!(conversation[0].content, "a film");
+    assert_eq!(conversation[1].role, ChatRole::Assistant);
+    assert!(conversation[1].content.contains("1700"));
+    assert_eq!(conversation[2].role, ChatRole::User);
+    assert!(conversation[2].content.contains("year predates cinema"));
+    assert_eq!(conversation[3].role, ChatRole::Assistant);
+    assert!(conversation[3].content.contains("Metropolis"));
+    assert_eq!(result.attempts(), 2);
+}
+
+#[tokio::test]
+async fn total_usage_counts_rejected_replies() {
+    let with_usage = |content: &str, prompt_tokens: u64| {
+        json!({
+            "model": "gpt-4o-mini",
+            "choices": [{
+                "message": { "role": "assistant", "content": content },
+                "finish_reason": "stop",
+            }],
+            "usage": { "prompt_tokens": prompt_tokens, "completion_tokens": 10, "total_tokens": prompt_tokens + 10 },
+        })
+        .to_string()
+    };
+    let mut server = mockito::Server::new_async().await;
+    let _bad = server
+        .mock("POST", "/chat/completions")
+        .with_status(200)
+        .with_body(with_usage(r#"{"title":"Old","year":1700}"#, 100))
+        .expect(1)
+        .create_async()
+        .await;
+    let _good = server
+        .mock("POST", "/chat/completions")
+        .with_status(200)
+        .with_body(with_usage(r#"{"title":"Metropolis","year":1927}"#, 150))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = client(&server);
+    let per_call = UsageTracker::new();
+    let result = per_call
+        .scope(client.materialize_with_metadata::<Movie>("a film"))
+        .await
+        .unwrap();
+
+    assert_eq!(result.usage.unwrap().input_tokens, 150);
+    let total = result.total_usage.unwrap();
+    assert_eq!((total.input_tokens, total.output_tokens), (250, 20));
+    let expected = UsageSnapshot {
+        attempts: 2,
+        input_tokens: 250,
+        output_tokens: 20,
+    };
+    assert_eq!(client.usage_snapshot(), expected);
+    assert_eq!(client.clone().usage_snapshot(), expected);
+    assert_eq!(per_call.snapshot(), expected);
+}
+
+#[tokio::test]
+async fn usage_tracker_is_shared_between_clients() {
+    let mut server = mockito::Server::new_async().await;
+    let m = server
+        .mock("POST", "/chat/completions")
+        .with_status(200)
+        .with_body(chat_completion(r#"{"title":"Alien","year":1979}"#))
+        .expect(2)
+        .create_async()
+        .await;
+    let failing = server
+        .mock("POST", "/chat/completions")
+        .match_header("authorization", "Bearer revoked")
+        .with_status(401)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let tracker = UsageTracker::new();
+    let first = client(&server).usage_tracker(tracker.clone());
+    let second = client(&server).usage_tracker(tracker.clone());
+    first.materialize::<Movie>("one").await.unwrap();
+    second.materialize::<Movie>("two").await.unwrap();
+    let revoked = second
+        .with_api_key_for_call(Provider::OpenAI, "revoked")
+        .materialize::<Movie>("three")
+        .await;
+    assert!(revoked.is_err());
+
+    // Requests without usage in the reply, or without a reply, still count.
+    assert_eq!(tracker.snapshot().attempts, 3);
+    assert_eq!(tracker.snapshot().total_tokens(), 0);
+    assert_eq!(first.usage_snapshot(), tracker.snapshot());
+    m.assert_async().await;
+    failing.assert_async().await;
+}
+
 #[tokio::test]
-async fn reask_loop_recovers_from_validation_failure() {
+async fn fenced_json_output_is_unwrapped() {
     let mut server = mockito::Server::new_async().await;
-    // First response fails the validator; the real re-ask loop must retry with the
-    // error fed back into the conversation, then accept the corrected response.
-    let bad = server
+    let m = server
         .mock("POST", "/chat/completions")
         .with_status(200)
-        .with_body(chat_completion(r#"{"title":"Old","year":1700}"#))
+        .with_body(chat_completion(
+            "```json\n{\"title\":\"Inception\",\"year\":2010}\n```",
+        ))
         .expect(1)
         .create_async()
         .await;
-    let good = server
+
+    let movie: Movie = client(&server).materialize("a film").await.unwrap();
+    assert_eq!(movie.title, "Inception");
+    m.assert_async().await;
+}
+
+#[tokio::test]
+async fn type_mismatches_are_coerced_without_a_reask() {
+    let mut server = mockito::Server::new_async().await;
+    let m = server
         .mock("POST", "/chat/completions")
         .with_status(200)
-        .with_body(chat_completion(r#"{"title":"Metropolis","year":1927}"#))
+        .with_body(chat_completion(r#"{"title":1984,"year":"1984"}"#))
         .expect(1)
         .create_async()
         .await;
 
     let movie: Movie = client(&server).materialize("a film").await.unwrap();
-    assert_eq!(movie.year, 1927);
-    bad.assert_async().await;
-    good.assert_async().await;
+    assert_eq!(
+        movie,
+        Movie {
+            title: "1984".into(),
+            year: 1984
+        }
+    );
+    m.assert_async().await;
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug, PartialEq)]
+struct Credit {
+    name: String,
+    born: u16,
+}
+
+/// Uses `Credit` twice, so it is shared through `$defs`.
+#[derive(Instructor, Serialize, Deserialize, Debug, PartialEq)]
+struct Film {
+    director: Credit,
+    writer: Credit,
+}
+
+#[tokio::test]
+async fn list_root_type_mismatches_are_coerced_without_a_reask() {
+    let mut server = mockito::Server::new_async().await;
+    let m = server
+        .mock("POST", "/chat/completions")
+        .with_status(200)
+        .with_body(chat_completion(
+            r#"{"items":[{"director":{"name":"Nolan","born":"1970"},"writer":{"name":"Nolan","born":"1970"}}]}"#,
+        ))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let films: Vec<Film> = client(&server).materialize("a film").await.unwrap();
+    assert_eq!(films[0].writer.born, 1970);
+    m.assert_async().await;
+}
+
+#[tokio::test]
+async fn truncated_output_is_reasked_then_classified() {
+    let mut server = mockito::Server::new_async().await;
+    // A response cut off mid-document triggers a re-ask; if every attempt is
+    // truncated, the error is an UnexpectedResponse rather than a serde error.
+    let truncated = server
+        .mock("POST", "/chat/completions")
+        .with_status(200)
+        .with_body(chat_completion(r#"{"title":"Incep"#))
+        .expect(2)
+        .create_async()
+        .await;
+
+    let err = client(&server)
+        .max_retries(1)
+        .materialize::<Movie>("a film")
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.api_error_kind(),
+        Some(&ApiErrorKind::UnexpectedResponse {
+            details: "truncated output".to_string()
+        })
+    );
+    truncated.assert_async().await;
 }
 
 #[tokio::test]
@@ -691,6 +1356,179 @@ async fn tool_loop_full_round_trip() {
     );
 }
 
+/// Malformed tool calls observed from providers: one call's arguments split
+/// across two `tool_calls` entries, with a duplicated key and trailing garbage.
+/// The fragments are merged into a single call (last duplicate wins), the tool
+/// runs once, and exactly one `role: tool` message answers the merged call.
+#[cfg(feature = "tools")]
+#[tokio::test]
+async fn tool_loop_merges_fragmented_tool_calls() {
+    use rstructor::{RequestExt, Toolbox};
+    use std::sync::{Arc, Mutex};
+
+    let mut server = mockito::Server::new_async().await;
+    let captured: Arc<Mutex<Vec<Value>>> = Arc::new(Mutex::new(Vec::new()));
+    let fragmented = json!({
+        "choices": [{
+            "message": {
+                "role": "assistant",
+                "content": null,
+                "tool_calls": [
+                    {
+                        "id": "c1",
+                        "type": "function",
+                        "function": { "name": "add", "arguments": r#"{"a":1,"b":3,"# }
+                    },
+                    {
+                        "id": "c1",
+                        "type": "function",
+                        "function": { "name": "add", "arguments": r#""a":2}}"# }
+                    }
+                ],
+            },
+            "finish_reason": "tool_calls",
+        }]
+    })
+    .to_string();
+
+    server
+        .mock("POST", "/chat/completions")
+        .match_request(|req| {
+            let v: Value = serde_json::from_str(&req.utf8_lossy_body().unwrap()).unwrap();
+            !messages_contain_tool_role(&v)
+        })
+        .with_status(200)
+        .with_body(fragmented)
+        .expect(1)
+        .create_async()
+        .await;
+    let sink = captured.clone();
+    server
+        .mock("POST", "/chat/completions")
+        .match_request(move |req| {
+            let v: Value = serde_json::from_str(&req.utf8_lossy_body().unwrap()).unwrap();
+            let answers = messages_contain_tool_role(&v);
+            if answers {
+                sink.lock().unwrap().push(v);
+            }
+            answers
+        })
+        .with_status(200)
+        .with_body(chat_completion("the sum is 5"))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let sums = Arc::new(Mutex::new(Vec::new()));
+    let seen = sums.clone();
+    let toolbox = Toolbox::new().with(rstructor::FnTool::new(
+        "add",
+        "Add two integers",
+        move |args: AddArgs| {
+            seen.lock().unwrap().push(args.a + args.b);
+            std::future::ready(Ok(json!({ "sum": args.a + args.b })))
+        },
+    ));
+
+    let answer = client(&server)
+        .with_tools(&toolbox)
+        .run("add 2 and 3")
+        .await
+        .unwrap();
+    assert_eq!(answer, "the sum is 5");
+    assert_eq!(*sums.lock().unwrap(), vec![5]);
+
+    let bodies = captured.lock().unwrap();
+    let messages = bodies[0]["messages"].as_array().unwrap();
+    let calls = messages
+        .iter()
+        .find_map(|m| m["tool_calls"].as_array())
+        .expect("the assistant tool-call message is echoed back");
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0]["function"]["arguments"], r#"{"a":2,"b":3}"#);
+    let answers: Vec<_> = messages.iter().filter(|m| m["role"] == "tool").collect();
+    assert_eq!(answers.len(), 1);
+    assert_eq!(answers[0]["tool_call_id"], "c1");
+}
+
+/// `ToolMode::LegacyFunctions` declares tools as `functions`, reads the single
+/// `function_call` off the assistant message, and answers it with a
+/// `role: function` message instead of `role: tool`.
+#[cfg(feature = "tools")]
+#[tokio::test]
+async fn legacy_function_mode_round_trip() {
+    use rstructor::{RequestExt, ToolMode, Toolbox};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    fn answers_function(body: &Value) -> bool {
+        body["messages"]
+            .as_array()
+            .is_some_and(|msgs| msgs.iter().any(|m| m["role"] == "function"))
+    }
+
+    let mut server = mockito::Server::new_async().await;
+    let first = server
+        .mock("POST", "/chat/completions")
+        .match_request(|req| {
+            let v: Value = serde_json::from_str(&req.utf8_lossy_body().unwrap()).unwrap();
+            v.get("tools").is_none()
+                && v["functions"][0]["name"] == "add"
+                && v["function_call"] == "auto"
+                && !answers_function(&v)
+        })
+        .with_status(200)
+        .with_body(
+            json!({
+                "choices": [{
+                    "message": {
+                        "role": "assistant",
+                        "content": null,
+                        "function_call": { "name": "add", "arguments": r#"{"a":2,"b":3}"# },
+                    },
+                    "finish_reason": "function_call",
+                }]
+            })
+            .to_string(),
+        )
+        .expect(1)
+        .create_async()
+        .await;
+    let second = server
+        .mock("POST", "/chat/completions")
+        .match_request(|req| {
+            let v: Value = serde_json::from_str(&req.utf8_lossy_body().unwrap()).unwrap();
+            v["messages"].as_array().is_some_and(|msgs| {
+                msgs.iter().any(|m| {
+                    m["role"] == "function"
+                        && m["name"] == "add"
+                        && m["content"]
+                            .as_str()
+                            .is_some_and(|c| c.contains("\"sum\":5"))
+                })
+            })
+        })
+        .with_status(200)
+        .with_body(chat_completion("the sum is 5"))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let invoked = Arc::new(AtomicBool::new(false));
+    let toolbox = Toolbox::new().with(recording_add_tool(invoked.clone()));
+    let answer = client(&server)
+        .tool_mode(ToolMode::LegacyFunctions)
+        .with_tools(&toolbox)
+        .run("add 2 and 3")
+        .await
+        .unwrap();
+
+    assert_eq!(answer, "the sum is 5");
+    assert!(invoked.load(Ordering::SeqCst));
+    first.assert_async().await;
+    second.assert_async().await;
+}
+
 /// Helper: does the request body contain a message with `role: "tool"`?
 #[cfg(feature = "tools")]
 fn messages_contain_tool_role(body: &Value) -> bool {
@@ -943,3 +1781,220 @@ async fn tool_run_request_body_carries_attached_media() {
     assert_eq!(answer, "a red square");
     m.assert_async().await;
 }
+
+/// `materialize_with_media` sends an image read with `MediaFile::from_path`
+/// as an `image_url` data URL next to the prompt.
+#[tokio::test]
+async fn materialize_with_media_sends_the_image_from_disk() {
+    use rstructor::MediaFile;
+
+    let path = std::env::temp_dir().join(format!("rstructor-poster-{}.png", std::process::id()));
+    std::fs::write(&path, b"abc").unwrap();
+    let poster = MediaFile::from_path(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(poster.mime_type, "image/png");
+
+    let mut server = mockito::Server::new_async().await;
+    let m = server
+        .mock("POST", "/chat/completions")
+        .match_body(mockito::Matcher::AllOf(vec![
+            mockito::Matcher::Regex("Which movie is this poster for".into()),
+            mockito::Matcher::Regex(r#""url":"data:image/png;base64,YWJj""#.into()),
+        ]))
+        .with_status(200)
+        .with_body(chat_completion(r#"{"title":"Alien","year":1979}"#))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let movie: Movie = client(&server)
+        .materialize_with_media("Which movie is this poster for?", &[poster])
+        .await
+        .unwrap();
+    assert_eq!(movie.title, "Alien");
+    m.assert_async().await;
+}
+
+#[test]
+fn media_from_path_needs_a_known_extension() {
+    let err = rstructor::MediaFile::from_path("notes.txt").unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(err.to_string().contains("notes.txt"), "{err}");
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+struct CallSummary {
+    caller: String,
+    callback_requested: bool,
+}
+
+/// A voicemail goes straight into a typed summary: the recording is sent as
+/// an `input_audio` part in the same request as the schema.
+#[tokio::test]
+async fn materialize_with_media_sends_audio_as_input_audio() {
+    use rstructor::MediaFile;
+
+    let mut server = mockito::Server::new_async().await;
+    let m = server
+        .mock("POST", "/chat/completions")
+        .match_body(mockito::Matcher::Regex(
+            r#"\{"type":"input_audio","input_audio":\{"data":"SUQzBA==","format":"mp3"\}\}"#.into(),
+        ))
+        .with_status(200)
+        .with_body(chat_completion(
+            r#"{"caller":"Dana","callback_requested":true}"#,
+        ))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let voicemail = MediaFile::from_audio(b"ID3\x04").unwrap();
+    let summary: CallSummary = client(&server)
+        .model("gpt-4o-audio-preview")
+        .materialize_with_media("Summarize this voicemail.", &[voicemail])
+        .await
+        .unwrap();
+    assert_eq!(summary.caller, "Dana");
+    assert!(summary.callback_requested);
+    m.assert_async().await;
+}
+
+#[test]
+fn media_from_audio_detects_the_format() {
+    use rstructor::MediaFile;
+
+    let mime = |bytes: &[u8]| MediaFile::from_audio(bytes).map(|m| m.mime_type);
+    assert_eq!(mime(b"ID3\x04\x00").unwrap(), "audio/mpeg");
+    assert_eq!(mime(&[0xFF, 0xFB, 0x90]).unwrap(), "audio/mpeg");
+    assert_eq!(mime(&[0xFF, 0xF1, 0x50]).unwrap(), "audio/aac");
+    assert_eq!(mime(b"fLaC\x00").unwrap(), "audio/flac");
+    assert_eq!(mime(b"OggS\x00").unwrap(), "audio/ogg");
+    assert_eq!(
+        mime(b"").unwrap_err().kind(),
+        std::io::ErrorKind::InvalidInput
+    );
+}
+
+/// An endpoint that rejects `json_schema` (as vLLM and Together.ai
+/// deployments do) is retried at once in `json_object` mode with the schema in
+/// a system message, and later calls to it skip straight to JSON mode.
+#[tokio::test]
+async fn rejected_json_schema_falls_back_to_json_object_mode() {
+    let mut server = mockito::Server::new_async().await;
+    let rejected = server
+        .mock("POST", "/chat/completions")
+        .match_body(mockito::Matcher::PartialJson(
+            json!({ "response_format": { "type": "json_schema" } }),
+        ))
+        .with_status(400)
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({
+                "object": "error",
+                "message": "response_format json_schema is not supported",
+                "type": "BadRequestError",
+                "code": 400
+            })
+            .to_string(),
+        )
+        .expect(1)
+        .create_async()
+        .await;
+    let json_mode = server
+        .mock("POST", "/chat/completions")
+        .match_body(mockito::Matcher::PartialJson(
+            json!({ "response_format": { "type": "json_object" } }),
+        ))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body_from_request(|request| {
+            let body: Value = serde_json::from_slice(request.body().unwrap()).unwrap();
+            let instruction = body["messages"][0]["content"].as_str().unwrap();
+            assert_eq!(body["messages"][0]["role"], "system");
+            assert!(instruction.contains("JSON Schema"), "{body}");
+            assert!(instruction.contains("\"year\""), "{body}");
+            chat_completion(r#"{"title":"Inception","year":2010}"#).into_bytes()
+        })
+        .expect(2)
+        .create_async()
+        .await;
+
+    let client = client(&server).no_retries();
+    let first: Movie = client.materialize("Describe Inception").await.unwrap();
+    let second: Movie = client
+        .clone()
+        .materialize("Describe Inception")
+        .await
+        .unwrap();
+    assert_eq!(first, second);
+    rejected.assert_async().await;
+    json_mode.assert_async().await;
+}
+
+/// An error about the schema itself names `response_format` too, but the
+/// endpoint does support `json_schema`: the error is returned and the client
+/// keeps sending `json_schema`.
+#[tokio::test]
+async fn invalid_schema_errors_do_not_switch_to_json_object_mode() {
+    let mut server = mockito::Server::new_async().await;
+    let invalid = server
+        .mock("POST", "/chat/completions")
+        .match_body(mockito::Matcher::PartialJson(
+            json!({ "response_format": { "type": "json_schema" } }),
+        ))
+        .with_status(400)
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({ "error": {
+                "message": "Invalid 'response_format.json_schema.name': string does not match pattern. Expected a string that matches the pattern '^[a-zA-Z0-9_-]+$'.",
+                "type": "invalid_request_error",
+                "param": "response_format.json_schema.name",
+                "code": "invalid_value"
+            }})
+            .to_string(),
+        )
+        .expect(2)
+        .create_async()
+        .await;
+
+    let client = client(&server).no_retries();
+    for _ in 0..2 {
+        let err = client
+            .materialize::<Movie>("Describe Inception")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("json_schema.name"), "{err}");
+    }
+    invalid.assert_async().await;
+}
+
+#[tokio::test]
+async fn pinned_output_strategies_skip_the_fallback() {
+    let mut server = mockito::Server::new_async().await;
+    let m = server
+        .mock("POST", "/chat/completions")
+        .match_body(mockito::Matcher::PartialJson(
+            json!({ "response_format": { "type": "json_object" } }),
+        ))
+        .with_status(200)
+        .with_body(chat_completion(r#"{"title":"Alien","year":1979}"#))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let movie: Movie = client(&server)
+        .output_strategy(rstructor::OutputStrategy::JsonMode)
+        .materialize("Describe Alien")
+        .await
+        .unwrap();
+    assert_eq!(movie.year, 1979);
+    m.assert_async().await;
+
+    let err = client(&server)
+        .output_strategy(rstructor::OutputStrategy::ToolCalling)
+        .no_retries()
+        .materialize::<Movie>("Describe Alien")
+        .await
+        .unwrap_err();
+    assert!(matches!(err, RStructorError::Unsupported(_)), "{err:?}");
+}
@@ -0,0 +1,140 @@
+//! Offline tests for [`Labeler`], using [`MockClient`] as the labeling model
+//! and closures as the reviewer.
+
+#![cfg(feature = "mock")]
+
+use std::sync::Mutex;
+
+use rstructor::{
+    ApiErrorKind, Instructor, LabelOutcome, Labeler, MockClient, RStructorError, ReviewItem,
+    ReviewReason,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Instructor, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[llm(validate = "validate_topic")]
+struct Topic {
+    name: String,
+}
+
+fn validate_topic(topic: &Topic) -> rstructor::Result<()> {
+    if topic.name.is_empty() {
+        return Err(RStructorError::ValidationError("name is empty".into()));
+    }
+    Ok(())
+}
+
+fn topic(name: &str) -> Topic {
+    Topic { name: name.into() }
+}
+
+#[tokio::test]
+async fn uncertain_labels_are_reviewed_and_corrections_become_examples() {
+    let client = MockClient::new()
+        .with_response(r#"{"score": 0.9, "value": {"name": "sports"}}"#)
+        .with_response(r#"{"score": 0.3, "value": {"name": "sports"}}"#)
+        .with_response(r#"{"score": 0.8, "value": {"name": "finance"}}"#);
+    let reviewed = Mutex::new(Vec::new());
+    let reviewer = |item: ReviewItem<Topic>| {
+        reviewed.lock().unwrap().push(item.clone());
+        Some(topic("chess"))
+    };
+    let mut labeler = Labeler::new(client, reviewer).instructions("Name the topic.");
+
+    let outcomes = labeler
+        .label_all(["Cup final tonight", "Carlsen resigns", "Rates rise again"])
+        .await
+        .unwrap();
+
+    assert_eq!(
+        outcomes,
+        vec![
+            LabelOutcome::Accepted {
+                value: topic("sports"),
+                confidence: 0.9
+            },
+            LabelOutcome::Corrected {
+                value: topic("chess"),
+                reason: ReviewReason::LowConfidence { confidence: 0.3 }
+            },
+            LabelOutcome::Accepted {
+                value: topic("finance"),
+                confidence: 0.8
+            },
+        ]
+    );
+    assert_eq!(
+        labeler.examples(),
+        vec![("Carlsen resigns", r#"{"name":"chess"}"#)]
+    );
+    let requests = labeler.client().requests();
+    assert!(requests[0].prompt.starts_with("Name the topic."));
+    assert!(!requests[1].prompt.contains("Carlsen resigns\nLabel:"));
+    assert!(
+        requests[2]
+            .prompt
+            .contains("Input:\nCarlsen resigns\nLabel:\n{\"name\":\"chess\"}")
+    );
+    assert!(requests[2].prompt.ends_with("Input:\nRates rise again"));
+
+    drop(labeler);
+    let reviewed = reviewed.into_inner().unwrap();
+    assert_eq!(reviewed.len(), 1);
+    assert_eq!(reviewed[0].input, "Carlsen resigns");
+    assert_eq!(reviewed[0].proposed, Some(topic("sports")));
+}
+
+#[tokio::test]
+async fn invalid_replies_are_reviewed_without_a_proposal() {
+    let client = MockClient::new().with_response(r#"{"score": 0.99, "value": {"name": ""}}"#);
+    let reviewer = |item: ReviewItem<Topic>| {
+        assert!(item.proposed.is_none());
+        None
+    };
+    let mut labeler = Labeler::new(client, reviewer);
+
+    let outcome = labeler.label("???").await.unwrap();
+    let LabelOutcome::Skipped {
+        reason: ReviewReason::Invalid { error },
+    } = &outcome
+    else {
+        panic!("expected an invalid, skipped item: {outcome:?}");
+    };
+    assert!(error.contains("name is empty"), "{error}");
+    assert_eq!(outcome.value(), None);
+    assert!(labeler.examples().is_empty());
+}
+
+#[tokio::test]
+async fn request_errors_are_returned_instead_of_reviewed() {
+    let client = MockClient::new().with_error(RStructorError::api_error(
+        "Mock",
+        ApiErrorKind::AuthenticationFailed,
+    ));
+    let reviewer = |_: ReviewItem<Topic>| -> Option<Topic> { panic!("nothing to review") };
+    let mut labeler = Labeler::new(client, reviewer);
+
+    let err = labeler.label("anything").await.unwrap_err();
+    assert!(matches!(
+        err.api_error_kind(),
+        Some(ApiErrorKind::AuthenticationFailed)
+    ));
+}
+
+#[tokio::test]
+async fn only_the_latest_examples_are_kept() {
+    let client =
+        MockClient::new().with_default_response(r#"{"score": 0.1, "value": {"name": "x"}}"#);
+    let reviewer = |item: ReviewItem<Topic>| Some(topic(&item.input.to_uppercase()));
+    let mut labeler = Labeler::new(client, reviewer)
+        .example("seed", &topic("seeded"))
+        .unwrap()
+        .max_examples(2)
+        .review_threshold(0.5);
+
+    labeler.label_all(["a", "b", "c"]).await.unwrap();
+    assert_eq!(
+        labeler.examples(),
+        vec![("b", r#"{"name":"B"}"#), ("c", r#"{"name":"C"}"#)]
+    );
+}
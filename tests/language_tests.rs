@@ -0,0 +1,117 @@
+//! `#[llm(language = "..")]`: free text in the wrong language fails
+//! validation and is re-asked. Only compiled with `--features language`.
+#![cfg(feature = "language")]
+
+use rstructor::{Instructor, RStructorError};
+use serde::{Deserialize, Serialize};
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+struct Clause {
+    #[llm(pattern = "^§ ?[0-9]+$")]
+    section: String,
+    #[llm(language = "German")]
+    summary: String,
+    #[llm(language = "fra")]
+    translation: Option<String>,
+}
+
+const GERMAN: &str = "Der Mieter muss die Miete am ersten Tag jedes Monats bezahlen.";
+const ENGLISH: &str = "The tenant must pay the rent on the first day of every month.";
+const FRENCH: &str = "Le locataire doit payer le loyer le premier jour de chaque mois.";
+
+fn clause(summary: &str, translation: Option<&str>) -> Clause {
+    Clause {
+        section: "§ 556".into(),
+        summary: summary.into(),
+        translation: translation.map(Into::into),
+    }
+}
+
+#[test]
+fn text_in_the_declared_language_passes() {
+    assert!(clause(GERMAN, Some(FRENCH)).validate().is_ok());
+    assert!(clause(GERMAN, None).validate().is_ok());
+}
+
+#[test]
+fn text_in_another_language_is_rejected() {
+    let err = clause(ENGLISH, None).validate().unwrap_err();
+    assert_eq!(
+        err,
+        RStructorError::ValidationError(
+            "`summary` must be written in German, but it reads as English".into()
+        )
+    );
+    let err = clause(GERMAN, Some(ENGLISH)).validate().unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("`translation` must be written in French")
+    );
+}
+
+#[test]
+fn text_too_short_to_tell_passes() {
+    assert!(clause("OK", Some("Miete")).validate().is_ok());
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+struct Misdeclared {
+    #[llm(language = "Klingon")]
+    text: String,
+}
+
+#[test]
+fn an_unknown_language_is_a_schema_error() {
+    let err = Misdeclared {
+        text: GERMAN.into(),
+    }
+    .validate()
+    .unwrap_err();
+    assert!(matches!(err, RStructorError::SchemaError(_)), "{err:?}");
+}
+
+#[cfg(feature = "openai")]
+#[tokio::test]
+async fn an_answer_in_the_wrong_language_is_re_asked() {
+    use rstructor::{LLMClient, OpenAIClient};
+    use serde_json::json;
+
+    fn reply(summary: &str) -> String {
+        let content = json!({ "section": "§ 556", "summary": summary, "translation": null });
+        json!({
+            "choices": [{
+                "message": { "role": "assistant", "content": content.to_string() },
+                "finish_reason": "stop"
+            }]
+        })
+        .to_string()
+    }
+
+    let mut server = mockito::Server::new_async().await;
+    let english = server
+        .mock("POST", "/chat/completions")
+        .with_status(200)
+        .with_body(reply(ENGLISH))
+        .expect(1)
+        .create_async()
+        .await;
+    let german = server
+        .mock("POST", "/chat/completions")
+        .match_body(mockito::Matcher::Regex("must be written in German".into()))
+        .with_status(200)
+        .with_body(reply(GERMAN))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let clause: Clause = OpenAIClient::new("test-key")
+        .unwrap()
+        .base_url(server.url())
+        .model("gpt-4o-mini")
+        .materialize("Summarize § 556 BGB")
+        .await
+        .unwrap();
+    assert_eq!(clause.summary, GERMAN);
+    english.assert_async().await;
+    german.assert_async().await;
+}
@@ -0,0 +1,170 @@
+//! Offline tests for the type-directed [`MaterializeExt`] entry points.
+
+#![cfg(feature = "mock")]
+
+use rstructor::{Instructor, MaterializeExt, MockClient, RStructorError};
+use serde::{Deserialize, Serialize};
+
+#[derive(Instructor, Serialize, Deserialize, Debug, PartialEq)]
+#[llm(validate = "validate_person")]
+struct Person {
+    name: String,
+    age: u8,
+}
+
+fn validate_person(p: &Person) -> rstructor::Result<()> {
+    if p.name.is_empty() {
+        return Err(RStructorError::ValidationError("name is empty".into()));
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn materialize_is_callable_on_the_type() {
+    let client = MockClient::new().with_response(r#"{"name": "Ada", "age": 36}"#);
+    let person = Person::materialize(&client, "Describe Ada").await.unwrap();
+    assert_eq!(
+        person,
+        Person {
+            name: "Ada".into(),
+            age: 36
+        }
+    );
+    assert_eq!(client.last_request().unwrap().prompt, "Describe Ada");
+}
+
+#[tokio::test]
+async fn materialize_many_requests_an_items_wrapper() {
+    let client = MockClient::new()
+        .with_response(r#"{"items": [{"name": "Ada", "age": 36}, {"name": "Alan", "age": 41}]}"#);
+    let people = Person::materialize_many(&client, "Name two pioneers")
+        .await
+        .unwrap();
+    assert_eq!(people.len(), 2);
+    assert_eq!(people[1].name, "Alan");
+
+    let request = client.last_request().unwrap();
+    let schema = request.schema.unwrap();
+    assert_eq!(schema["type"], "object");
+    assert_eq!(schema["properties"]["items"]["type"], "array");
+    assert_eq!(request.schema_name.as_deref(), Some("PersonList"));
+}
+
+#[tokio::test]
+async fn materialize_many_validates_each_item() {
+    let client = MockClient::new()
+        .with_retries(1)
+        .with_response(r#"{"items": [{"name": "", "age": 1}]}"#)
+        .with_response(r#"{"items": [{"name": "Grace", "age": 85}]}"#);
+    let people = Person::materialize_many(&client, "Name a pioneer")
+        .await
+        .unwrap();
+    assert_eq!(
+        people,
+        vec![Person {
+            name: "Grace".into(),
+            age: 85
+        }]
+    );
+}
+
+#[tokio::test]
+async fn materialize_ranked_sorts_by_score_and_keeps_at_most_k() {
+    let client = MockClient::new().with_response(
+        r#"{"candidates": [
+            {"score": 0.4, "value": {"name": "Alan", "age": 41}},
+            {"score": 0.9, "value": {"name": "Ada", "age": 36}},
+            {"score": 0.4, "value": {"name": "Grace", "age": 85}},
+            {"score": 0.1, "value": {"name": "Edsger", "age": 72}}
+        ]}"#,
+    );
+    let ranked = Person::materialize_ranked(&client, "Who founded computing?", 3)
+        .await
+        .unwrap();
+    let names: Vec<&str> = ranked.iter().map(|c| c.value.name.as_str()).collect();
+    assert_eq!(names, ["Ada", "Alan", "Grace"]);
+    assert_eq!(ranked[0].score, 0.9);
+
+    let request = client.last_request().unwrap();
+    assert!(request.prompt.starts_with("Who founded computing?"));
+    assert!(request.prompt.contains("Give 3 distinct candidate answers"));
+    assert_eq!(request.schema_name.as_deref(), Some("RankedPerson"));
+    let candidate = &request.schema.unwrap()["properties"]["candidates"]["items"];
+    assert_eq!(candidate["required"], serde_json::json!(["score", "value"]));
+    assert_eq!(candidate["properties"]["value"]["title"], "Person");
+}
+
+#[tokio::test]
+async fn materialize_ranked_re_asks_out_of_range_scores_and_invalid_values() {
+    let client = MockClient::new()
+        .with_retries(2)
+        .with_response(r#"{"candidates": [{"score": 7, "value": {"name": "Ada", "age": 36}}]}"#)
+        .with_response(r#"{"candidates": [{"score": 0.5, "value": {"name": "", "age": 1}}]}"#)
+        .with_response(r#"{"candidates": [{"score": 0.5, "value": {"name": "Ada", "age": 36}}]}"#);
+    let ranked = Person::materialize_ranked(&client, "Name a pioneer", 1)
+        .await
+        .unwrap();
+    assert_eq!(ranked.len(), 1);
+    assert_eq!(ranked[0].value.name, "Ada");
+    assert!(client.responses_exhausted());
+}
+
+#[tokio::test]
+async fn materialize_ranked_rejects_zero_candidates() {
+    let client = MockClient::new();
+    let err = Person::materialize_ranked(&client, "p", 0)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, RStructorError::ConfigError(_)));
+    assert_eq!(client.request_count(), 0);
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug, PartialEq)]
+struct Comment {
+    text: String,
+    replies: Vec<Comment>,
+}
+
+#[tokio::test]
+async fn materialize_many_hoists_a_recursive_items_definitions_to_the_root() {
+    let client = MockClient::new().with_response(
+        r#"{"items": [{"text": "hi", "replies": [{"text": "hey", "replies": []}]}]}"#,
+    );
+    let comments = Comment::materialize_many(&client, "List the comments")
+        .await
+        .unwrap();
+    assert_eq!(comments[0].replies[0].text, "hey");
+
+    let schema = client.last_request().unwrap().schema.unwrap();
+    let items = &schema["properties"]["items"]["items"];
+    assert_eq!(items["$ref"], "#/$defs/Comment");
+    assert!(items.get("$defs").is_none());
+    assert_eq!(schema["$defs"]["Comment"]["type"], "object");
+}
+
+#[tokio::test]
+async fn materialize_ranked_hoists_a_recursive_values_definitions_to_the_root() {
+    let client = MockClient::new().with_response(
+        r#"{"candidates": [{"score": 0.8, "value": {"text": "hi", "replies": []}}]}"#,
+    );
+    let ranked = Comment::materialize_ranked(&client, "Suggest a comment", 1)
+        .await
+        .unwrap();
+    assert_eq!(ranked[0].value.text, "hi");
+
+    let schema = client.last_request().unwrap().schema.unwrap();
+    let value = &schema["properties"]["candidates"]["items"]["properties"]["value"];
+    assert_eq!(value["$ref"], "#/$defs/Comment");
+    assert!(value.get("$defs").is_none());
+    assert_eq!(schema["$defs"]["Comment"]["type"], "object");
+}
+
+#[test]
+fn scored_hoists_a_recursive_values_definitions_to_the_root() {
+    use rstructor::{SchemaType, Scored};
+
+    let schema = Scored::<Comment>::schema().to_json();
+    assert_eq!(schema["properties"]["value"]["$ref"], "#/$defs/Comment");
+    assert!(schema["properties"]["value"].get("$defs").is_none());
+    assert_eq!(schema["$defs"]["Comment"]["type"], "object");
+}
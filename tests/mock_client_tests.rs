@@ -59,6 +59,33 @@ async fn metadata_usage_can_be_configured() {
     assert_eq!(usage.total_tokens(), 33);
 }
 
+#[tokio::test]
+async fn unknown_field_capture_reports_undeclared_fields() {
+    let client = MockClient::new()
+        .with_response(r#"{"title":"A","year":2000,"rating":"PG"}"#)
+        .with_unknown_field_capture();
+    let result = client
+        .materialize_with_metadata::<Movie>("p")
+        .await
+        .unwrap();
+    assert_eq!(result.extra_fields.len(), 1);
+    assert_eq!(result.extra_fields["/rating"], "PG");
+}
+
+#[tokio::test]
+async fn explanation_is_split_from_the_reply() {
+    let client = MockClient::new()
+        .with_response(r#"{"_reasoning":"Released in 2000","title":"A","year":2000}"#)
+        .with_unknown_field_capture();
+    let result = client
+        .materialize_with_metadata::<Movie>("p")
+        .await
+        .unwrap();
+    assert_eq!(result.data.year, 2000);
+    assert_eq!(result.explanation.as_deref(), Some("Released in 2000"));
+    assert!(result.extra_fields.is_empty());
+}
+
 #[tokio::test]
 async fn queue_is_fifo() {
     let client = MockClient::new()
@@ -162,6 +189,99 @@ async fn nested_validation_recurses_through_the_mock() {
     assert!(matches!(err, RStructorError::ValidationError(_)));
 }
 
+#[tokio::test]
+async fn list_targets_accept_bare_and_wrapped_arrays() {
+    let client = MockClient::new()
+        .with_response(r#"[{"title":"A","year":2000}]"#)
+        .with_response(r#"{"items": [{"title":"B","year":2001}]}"#);
+    let bare: Vec<Movie> = client.materialize("p").await.unwrap();
+    let wrapped: Vec<Movie> = client.materialize("p").await.unwrap();
+    assert_eq!(bare[0].title, "A");
+    assert_eq!(wrapped[0].title, "B");
+}
+
+#[tokio::test]
+async fn scalar_targets_need_no_wrapper_struct() {
+    let client = MockClient::new()
+        .with_response("true")
+        .with_response(r#"{"value": 3}"#);
+    assert!(client.materialize::<bool>("Is this spam?").await.unwrap());
+    assert_eq!(client.materialize::<u8>("How many?").await.unwrap(), 3);
+    assert_eq!(
+        client.requests()[0].schema,
+        Some(serde_json::json!({ "type": "boolean" }))
+    );
+}
+
+#[tokio::test]
+async fn conversations_record_the_final_turn_as_the_prompt() {
+    let client = MockClient::new()
+        .with_response("Which year?")
+        .with_response(r#"{"title":"Alien","year":1979}"#);
+    let mut chat = rstructor::Conversation::new();
+    chat.send(&client, "Alien").await.unwrap();
+    chat.user("The original.");
+    let movie: Movie = chat.materialize(&client).await.unwrap();
+    assert_eq!(movie.year, 1979);
+
+    let reqs = client.requests();
+    assert_eq!(reqs[0].kind, RequestKind::GenerateConversation);
+    assert!(reqs[0].history.is_empty());
+    assert_eq!(reqs[1].kind, RequestKind::MaterializeConversation);
+    assert_eq!(reqs[1].prompt, "The original.");
+    assert_eq!(reqs[1].history.len(), 2);
+    assert_eq!(chat.last_reply(), Some(r#"{"title":"Alien","year":1979}"#));
+}
+
+// ---------------------------------------------------------------------------
+// Latency and the `testing` module path
+// ---------------------------------------------------------------------------
+
+mod latency {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[tokio::test]
+    async fn every_attempt_waits_for_the_latency() {
+        let client = rstructor::testing::MockClient::new()
+            .with_response(r#"{"title":"X","year":1700}"#)
+            .with_response(r#"{"title":"Inception","year":2010}"#)
+            .with_retries(1)
+            .with_latency(Duration::from_millis(40));
+        let started = Instant::now();
+        let movie: Movie = client.materialize("p").await.unwrap();
+        assert_eq!(movie.year, 2010);
+        assert!(started.elapsed() >= Duration::from_millis(80));
+    }
+
+    #[tokio::test]
+    async fn slow_calls_can_time_out_and_are_still_recorded() {
+        let client = MockClient::new()
+            .with_response("late")
+            .with_latency(Duration::from_secs(5));
+        let result = tokio::time::timeout(Duration::from_millis(20), client.generate("p")).await;
+        assert!(result.is_err());
+        assert_eq!(client.request_count(), 1);
+        // The reply was never picked, so it is still queued.
+        assert!(!client.responses_exhausted());
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn waiting_does_not_block_the_executor() {
+        let client = MockClient::new()
+            .with_default_response("ok")
+            .with_latency(Duration::from_millis(200));
+        let started = Instant::now();
+        let (a, b, c) = tokio::join!(
+            client.generate("a"),
+            client.generate("b"),
+            client.generate("c")
+        );
+        assert_eq!([a.unwrap(), b.unwrap(), c.unwrap()], ["ok"; 3]);
+        assert!(started.elapsed() < Duration::from_millis(600));
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Streaming (requires `streaming`, which implies `_client`)
 // ---------------------------------------------------------------------------
@@ -130,31 +130,13 @@ mod nested_struct_tests {
         let tags_prop = &schema_json["properties"]["tags"];
         assert_eq!(tags_prop["type"], "array");
 
-        // Verify items are objects or have properties (schema enhancement adds properties)
+        // Items embed Tag's own schema, with its real properties and required list
         let items = &tags_prop["items"];
-        let items_type = items
-            .get("type")
-            .and_then(|v| v.as_str())
-            .unwrap_or("unknown");
-        let has_properties = items
-            .get("properties")
-            .and_then(|v| v.as_object())
-            .is_some();
-
-        // CRITICAL: Items MUST be objects for nested structs to work
-        // Schema enhancement should ensure this, but verify anyway
-        assert!(
-            items_type == "object" || has_properties,
-            "Tags items should be type 'object' or have properties. Type: {:?}, Has properties: {:?}, Full: {:?}",
-            items_type,
-            has_properties,
-            serde_json::to_string_pretty(items).unwrap_or_default()
-        );
-
-        // If properties exist, the enhancement worked
-        if has_properties {
-            assert!(items["properties"].is_object());
-        }
+        assert_eq!(items["type"], "object");
+        assert_eq!(items["title"], "Tag");
+        let item_props = items["properties"].as_object().unwrap();
+        assert_eq!(item_props.keys().collect::<Vec<_>>(), ["name", "category"]);
+        assert_eq!(items["required"], serde_json::json!(["name", "category"]));
     }
 
     #[test]
@@ -371,7 +353,7 @@ mod nested_struct_tests {
     #[test]
     fn test_complex_nested_structure_schema() {
         let schema = BlogPost::schema();
-        let schema_json = schema.to_json();
+        let schema_json = schema.inline().to_json();
 
         // Verify all fields exist
         assert!(schema_json["properties"]["title"].is_object());
@@ -753,6 +735,32 @@ mod nested_struct_tests {
         assert_eq!(team_prop["type"], "object");
     }
 
+    // ====== Boxed and tuple-wrapped collections of nested structs ======
+
+    #[derive(Instructor, Serialize, Deserialize, Debug, PartialEq)]
+    struct Catalog {
+        #[llm(description = "Tags, boxed")]
+        #[allow(clippy::box_collection)]
+        boxed_tags: Box<Vec<Tag>>,
+
+        #[llm(description = "A label and its tags")]
+        labelled: (String, Vec<Tag>),
+    }
+
+    #[test]
+    fn test_boxed_and_tuple_collections_embed_item_schemas() {
+        let schema_json = Catalog::schema().inline().to_json();
+
+        let boxed = &schema_json["properties"]["boxed_tags"];
+        assert_eq!(boxed["type"], "array");
+        assert!(boxed["items"]["properties"]["category"].is_object());
+
+        let labelled = &schema_json["properties"]["labelled"]["prefixItems"];
+        assert_eq!(labelled[0]["type"], "string");
+        assert_eq!(labelled[1]["type"], "array");
+        assert!(labelled[1]["items"]["properties"]["name"].is_object());
+    }
+
     #[test]
     fn test_enum_in_deeply_nested_struct_deserialization() {
         let json = serde_json::json!({
@@ -0,0 +1,239 @@
+//! Drive the real `OllamaClient` over a local mock HTTP server (`mockito`),
+//! covering the native `/api/chat` and `/api/tags` endpoints. No Ollama
+//! install needed.
+#![cfg(feature = "ollama")]
+
+use rstructor::{Instructor, LLMClient, OllamaClient, OutputStrategy, RStructorError};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Instructor, Serialize, Deserialize, Debug, PartialEq)]
+struct Movie {
+    title: String,
+    year: u16,
+}
+
+fn client(server: &mockito::Server) -> OllamaClient {
+    OllamaClient::new().base_url(server.url()).no_retries()
+}
+
+fn chat_reply(content: &str) -> String {
+    json!({
+        "model": "llama3.2",
+        "message": { "role": "assistant", "content": content },
+        "done": true,
+        "done_reason": "stop",
+        "prompt_eval_count": 12,
+        "eval_count": 7
+    })
+    .to_string()
+}
+
+#[tokio::test]
+async fn materialize_sends_schema_as_format_without_auth() {
+    let mut server = mockito::Server::new_async().await;
+    let m = server
+        .mock("POST", "/api/chat")
+        .match_header("authorization", mockito::Matcher::Missing)
+        .match_body(mockito::Matcher::PartialJson(json!({
+            "model": "llama3.2",
+            "stream": false,
+            "format": { "type": "object", "required": ["title", "year"] }
+        })))
+        .with_status(200)
+        .with_body(chat_reply("{\"title\":\"Metropolis\",\"year\":1927}"))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let result = client(&server)
+        .materialize_with_metadata::<Movie>("Metropolis")
+        .await
+        .unwrap();
+    assert_eq!(
+        result.data,
+        Movie {
+            title: "Metropolis".into(),
+            year: 1927
+        }
+    );
+    let usage = result.usage.unwrap();
+    assert_eq!((usage.input_tokens, usage.output_tokens), (12, 7));
+    m.assert_async().await;
+}
+
+#[tokio::test]
+async fn json_mode_sends_format_json_and_schema_in_prompt() {
+    let mut server = mockito::Server::new_async().await;
+    let m = server
+        .mock("POST", "/api/chat")
+        .match_body(mockito::Matcher::AllOf(vec![
+            mockito::Matcher::PartialJson(json!({ "format": "json" })),
+            mockito::Matcher::Regex("JSON Schema".to_string()),
+        ]))
+        .with_status(200)
+        .with_body(chat_reply("{\"title\":\"Alien\",\"year\":1979}"))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let movie: Movie = client(&server)
+        .output_strategy(OutputStrategy::JsonMode)
+        .materialize("Alien")
+        .await
+        .unwrap();
+    assert_eq!(movie.year, 1979);
+    m.assert_async().await;
+}
+
+#[tokio::test]
+async fn json_mode_prompt_includes_an_example_instance() {
+    #[derive(Instructor, Serialize, Deserialize, Debug)]
+    struct City {
+        #[llm(example = "Lisbon")]
+        name: String,
+        #[llm(example = 545000)]
+        population: u32,
+    }
+
+    let mut server = mockito::Server::new_async().await;
+    let m = server
+        .mock("POST", "/api/chat")
+        .match_body(mockito::Matcher::Regex(
+            r#"Example of a valid response:\\n\{\\n  \\"name\\": \\"Lisbon\\",\\n  \\"population\\": 545000\\n\}"#
+                .to_string(),
+        ))
+        .with_status(200)
+        .with_body(chat_reply("{\"name\":\"Porto\",\"population\":232000}"))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let city: City = client(&server)
+        .output_strategy(OutputStrategy::JsonMode)
+        .materialize("Portugal's second city")
+        .await
+        .unwrap();
+    assert_eq!(city.name, "Porto");
+    m.assert_async().await;
+}
+
+#[tokio::test]
+async fn json_mode_prompt_warns_against_counter_examples() {
+    #[derive(Instructor, Serialize, Deserialize, Debug)]
+    #[llm(counter_example = ::serde_json::json!({ "name": "N/A" }))]
+    struct Person {
+        name: String,
+    }
+
+    let mut server = mockito::Server::new_async().await;
+    let m = server
+        .mock("POST", "/api/chat")
+        .match_body(mockito::Matcher::Regex(
+            r#"Do NOT return output like this:\\n\{\\n  \\"name\\": \\"N/A\\"\\n\}"#.to_string(),
+        ))
+        .with_status(200)
+        .with_body(chat_reply("{\"name\":\"Ada Lovelace\"}"))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let person: Person = client(&server)
+        .output_strategy(OutputStrategy::JsonMode)
+        .materialize("Who wrote the first program?")
+        .await
+        .unwrap();
+    assert_eq!(person.name, "Ada Lovelace");
+    m.assert_async().await;
+}
+
+#[tokio::test]
+async fn generate_omits_format_and_sends_api_key_when_set() {
+    let mut server = mockito::Server::new_async().await;
+    let m = server
+        .mock("POST", "/api/chat")
+        .match_header("authorization", "Bearer proxy-token")
+        .match_body(mockito::Matcher::PartialJson(json!({
+            "options": { "temperature": 0.5, "num_predict": 64 }
+        })))
+        .with_status(200)
+        .with_body(chat_reply("Hello there"))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let text = client(&server)
+        .api_key("proxy-token")
+        .temperature(0.5)
+        .max_tokens(64)
+        .generate("Say hello")
+        .await
+        .unwrap();
+    assert_eq!(text, "Hello there");
+    m.assert_async().await;
+}
+
+#[tokio::test]
+async fn list_models_reads_tags() {
+    let mut server = mockito::Server::new_async().await;
+    let m = server
+        .mock("GET", "/api/tags")
+        .with_status(200)
+        .with_body(
+            json!({
+                "models": [
+                    {
+                        "name": "qwen3:8b",
+                        "details": { "family": "qwen3", "parameter_size": "8.2B" }
+                    },
+                    { "name": "llama3.2:latest" }
+                ]
+            })
+            .to_string(),
+        )
+        .expect(1)
+        .create_async()
+        .await;
+
+    let models = client(&server).list_models().await.unwrap();
+    let ids: Vec<_> = models.iter().map(|m| m.id.as_str()).collect();
+    assert_eq!(ids, ["qwen3:8b", "llama3.2:latest"]);
+    assert_eq!(models[0].description.as_deref(), Some("qwen3, 8.2B"));
+    assert_eq!(models[1].description, None);
+    m.assert_async().await;
+}
+
+#[tokio::test]
+async fn missing_model_is_reported() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("POST", "/api/chat")
+        .with_status(404)
+        .with_body(json!({ "error": "model \"nope\" not found, try pulling it first" }).to_string())
+        .create_async()
+        .await;
+
+    let err = client(&server)
+        .model("nope")
+        .generate("hi")
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(
+            err.api_error_kind(),
+            Some(rstructor::ApiErrorKind::InvalidModel { .. })
+        ),
+        "{err:?}"
+    );
+}
+
+#[tokio::test]
+async fn tool_calling_is_unsupported() {
+    let server = mockito::Server::new_async().await;
+    let err = client(&server)
+        .output_strategy(OutputStrategy::ToolCalling)
+        .materialize::<Movie>("Alien")
+        .await
+        .unwrap_err();
+    assert!(matches!(err, RStructorError::Unsupported(_)));
+}
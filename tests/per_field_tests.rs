@@ -0,0 +1,159 @@
+//! Offline tests for `materialize_per_field`, with [`MockClient`] standing in
+//! for a text-only model.
+
+#![cfg(feature = "mock")]
+
+use rstructor::{Instructor, LLMClient, MockClient, RStructorError, RequestKind};
+use serde::{Deserialize, Serialize};
+
+#[derive(Instructor, Serialize, Deserialize, Debug, PartialEq)]
+enum Genre {
+    Drama,
+    SciFi,
+    Horror,
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug, PartialEq)]
+#[llm(validate = "validate_movie")]
+struct Movie {
+    #[llm(description = "The movie's title")]
+    title: String,
+    year: u16,
+    genre: Genre,
+    sequel: bool,
+    rating: Option<f32>,
+}
+
+fn validate_movie(movie: &Movie) -> rstructor::Result<()> {
+    if movie.year < 1888 {
+        return Err(RStructorError::ValidationError(
+            "year predates cinema".into(),
+        ));
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn each_field_is_asked_for_and_assembled() {
+    let client = MockClient::new()
+        .with_response("Title: \"Alien\"")
+        .with_response("It was released in 1979.")
+        .with_response("I'd say Horror, though it's also SciFi.")
+        .with_response("No.")
+        .with_response("none");
+
+    let movie: Movie = client
+        .materialize_per_field("Alien (1979) is Ridley Scott's horror classic.")
+        .await
+        .unwrap();
+    assert_eq!(
+        movie,
+        Movie {
+            title: "Alien".into(),
+            year: 1979,
+            genre: Genre::Horror,
+            sequel: false,
+            rating: None,
+        }
+    );
+
+    let requests = client.requests();
+    assert_eq!(requests.len(), 5);
+    assert!(requests.iter().all(|r| r.kind == RequestKind::Generate));
+    assert!(requests[0].prompt.contains("`title` (The movie's title)"));
+    assert!(requests[2].prompt.contains("one of: Drama, SciFi, Horror"));
+    assert!(requests[4].prompt.contains("reply none"));
+    assert!(!requests[1].prompt.contains("reply none"));
+}
+
+#[tokio::test]
+async fn a_reply_without_a_value_is_re_asked_once() {
+    let client = MockClient::new()
+        .with_response("Alien")
+        .with_response("Hard to say.")
+        .with_response("1979")
+        .with_response("Horror")
+        .with_response("false")
+        .with_response("8.5 out of 10");
+
+    let movie: Movie = client.materialize_per_field("Alien").await.unwrap();
+    assert_eq!(movie.year, 1979);
+    assert_eq!(movie.rating, Some(8.5));
+    let requests = client.requests();
+    assert_eq!(requests.len(), 6);
+    assert!(
+        requests[2]
+            .prompt
+            .contains("Your previous reply was:\nHard to say.")
+    );
+}
+
+#[tokio::test]
+async fn a_missing_required_value_is_an_error() {
+    let client = MockClient::new()
+        .with_response("Alien")
+        .with_response("Hard to say.")
+        .with_response("Still hard to say.");
+
+    let err = client
+        .materialize_per_field::<Movie>("Alien")
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&err, RStructorError::ValidationError(msg) if msg.contains("`year`")),
+        "{err:?}"
+    );
+}
+
+#[tokio::test]
+async fn the_assembled_value_is_validated() {
+    let client = MockClient::new()
+        .with_response("The Horse in Motion")
+        .with_response("It was filmed in 1878.")
+        .with_response("Drama")
+        .with_response("no")
+        .with_response("none");
+
+    let err = client
+        .materialize_per_field::<Movie>("The Horse in Motion")
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        RStructorError::ValidationError("year predates cinema".into())
+    );
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug, PartialEq)]
+enum Mood {
+    #[llm(description = "Serious and character-driven")]
+    Somber,
+    Upbeat,
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug, PartialEq)]
+struct Review {
+    mood: Mood,
+}
+
+#[tokio::test]
+async fn an_enum_with_variant_descriptions_is_asked_for_as_an_enum() {
+    let client = MockClient::new()
+        .with_response("up-beat")
+        .with_response("Upbeat");
+
+    let review: Review = client
+        .materialize_per_field("A joyful film.")
+        .await
+        .unwrap();
+    assert_eq!(review.mood, Mood::Upbeat);
+
+    let requests = client.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[0].prompt.contains("one of: Somber, Upbeat"));
+    assert!(
+        requests[1]
+            .prompt
+            .contains("Your previous reply was:\nup-beat")
+    );
+}
@@ -0,0 +1,27 @@
+//! `use rstructor::prelude::*` alone is enough for a typical extraction.
+#![cfg(feature = "mock")]
+
+use rstructor::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Instructor, Serialize, Deserialize, Debug, PartialEq)]
+struct Movie {
+    title: String,
+    year: u16,
+}
+
+#[tokio::test]
+async fn prelude_covers_a_typical_extraction() {
+    let client = MockClient::new().with_response(r#"{"title":"Alien","year":1979}"#);
+    let movie: Movie = client.materialize("Alien").await.unwrap();
+    assert_eq!(movie.year, 1979);
+
+    let schema: Schema = Movie::schema();
+    assert_eq!(
+        schema.to_json()["required"],
+        serde_json::json!(["title", "year"])
+    );
+
+    let err: RStructorError = client.materialize::<Movie>("again").await.unwrap_err();
+    assert!(err.api_error_kind().is_none());
+}
@@ -0,0 +1,94 @@
+//! Cost estimates from `TokenUsage`, with the built-in price table and with
+//! prices loaded from LiteLLM's pricing file.
+
+use rstructor::{CostEstimate, ModelPricing, TokenUsage, load_litellm_pricing};
+use serde_json::json;
+
+#[test]
+fn known_models_are_priced_per_million_tokens() {
+    let cost = TokenUsage::new("claude-sonnet-4-5-20250929", 1_000_000, 100_000)
+        .estimate_cost()
+        .unwrap();
+    assert_eq!(
+        cost,
+        CostEstimate {
+            input_cost: 3.0,
+            output_cost: 1.5,
+            total_cost: 4.5
+        }
+    );
+}
+
+#[test]
+fn unknown_models_have_no_estimate() {
+    assert!(TokenUsage::new("qwen3", 10, 10).estimate_cost().is_none());
+}
+
+#[test]
+fn litellm_prices_override_the_table() {
+    let file = json!({
+        "sample_spec": { "input_cost_per_token": "see docs" },
+        "pricing-test-model": {
+            "input_cost_per_token": 1e-6,
+            "output_cost_per_token": 4e-6,
+            "litellm_provider": "openai"
+        },
+        "azure/pricing-test-model": {
+            "input_cost_per_token": 9e-6,
+            "output_cost_per_token": 9e-6
+        },
+        "vertex_ai/pricing-test-gemini": {
+            "input_cost_per_token": 2e-7,
+            "output_cost_per_token": 8e-7
+        },
+        "pricing-test-embedding": { "input_cost_per_token": 1e-8 }
+    });
+    assert_eq!(load_litellm_pricing(&file), 2);
+    assert_eq!(
+        ModelPricing::for_model("pricing-test-model"),
+        Some(ModelPricing {
+            input_per_mtok: 1.0,
+            output_per_mtok: 4.0
+        })
+    );
+    let cost = TokenUsage::new("pricing-test-gemini-001", 1_000_000, 0)
+        .estimate_cost()
+        .unwrap();
+    assert!((cost.total_cost - 0.2).abs() < 1e-9);
+    assert!(ModelPricing::for_model("pricing-test-embedding").is_none());
+}
+
+#[cfg(feature = "_client")]
+#[tokio::test]
+async fn pricing_refreshes_from_a_url() {
+    let mut server = mockito::Server::new_async().await;
+    let m = server
+        .mock("GET", "/prices.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({
+                "pricing-refresh-model": {
+                    "input_cost_per_token": 5e-7,
+                    "output_cost_per_token": 1.5e-6
+                }
+            })
+            .to_string(),
+        )
+        .expect(1)
+        .create_async()
+        .await;
+
+    let loaded = rstructor::refresh_pricing_from(&format!("{}/prices.json", server.url()))
+        .await
+        .unwrap();
+    assert_eq!(loaded, 1);
+    let cost = TokenUsage::new("pricing-refresh-model", 2_000_000, 1_000_000)
+        .estimate_cost()
+        .unwrap();
+    assert!((cost.total_cost - 2.5).abs() < 1e-9);
+    m.assert_async().await;
+
+    let missing = rstructor::refresh_pricing_from(&format!("{}/missing.json", server.url())).await;
+    assert!(missing.is_err());
+}
@@ -245,7 +245,9 @@ fn anthropic_model_roundtrip_all_variants() {
     }
 }
 
+// The Gemini 2.0 variants are deprecated but still round-trip.
 #[test]
+#[allow(deprecated)]
 fn gemini_model_roundtrip_all_variants() {
     use GeminiModel::*;
     let table: &[(GeminiModel, &str)] = &[
@@ -0,0 +1,121 @@
+//! Client rate limiters: request and token budgets, sharing one limiter
+//! between clients, and a `retry-after` holding back every client on it.
+#![cfg(feature = "openai")]
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use rstructor::{
+    ApiErrorKind, LLMClient, OpenAIClient, RStructorError, RateLimiter, Requests, Tokens,
+};
+use serde_json::json;
+
+async fn ok_server() -> mockito::ServerGuard {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("POST", "/chat/completions")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({
+                "choices": [{
+                    "message": { "role": "assistant", "content": "hello" },
+                    "finish_reason": "stop"
+                }]
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+    server
+}
+
+fn client(server: &mockito::ServerGuard) -> OpenAIClient {
+    OpenAIClient::new("test-key")
+        .unwrap()
+        .base_url(server.url())
+        .model("gpt-4o-mini")
+}
+
+#[tokio::test]
+async fn clients_sharing_a_limiter_share_its_request_budget() {
+    let server = ok_server().await;
+    let limiter =
+        Arc::new(RateLimiter::new().requests(Requests::new(2, Duration::from_millis(600))));
+    let first = client(&server).rate_limiter(limiter.clone());
+    let second = client(&server).rate_limiter(limiter);
+
+    let start = Instant::now();
+    first.generate("one").await.unwrap();
+    second.generate("two").await.unwrap();
+    assert!(start.elapsed() < Duration::from_millis(250));
+
+    // The budget is spent, so a third request waits for a refill.
+    first.generate("three").await.unwrap();
+    assert!(
+        start.elapsed() >= Duration::from_millis(250),
+        "{:?}",
+        start.elapsed()
+    );
+}
+
+#[tokio::test]
+async fn output_allowances_count_against_the_token_budget() {
+    let server = ok_server().await;
+    let client = client(&server).max_tokens(900).rate_limit(
+        Requests::per_second(100),
+        Tokens::new(1_000, Duration::from_millis(500)),
+    );
+
+    client.generate("one").await.unwrap();
+    let start = Instant::now();
+    client.generate("two").await.unwrap();
+    assert!(
+        start.elapsed() >= Duration::from_millis(400),
+        "{:?}",
+        start.elapsed()
+    );
+}
+
+#[tokio::test]
+async fn retry_after_holds_back_every_client_on_the_limiter() {
+    let mut limited = mockito::Server::new_async().await;
+    limited
+        .mock("POST", "/chat/completions")
+        .with_status(429)
+        .with_header("content-type", "application/json")
+        .with_header("retry-after", "1")
+        .with_body(json!({ "error": { "message": "slow down" } }).to_string())
+        .create_async()
+        .await;
+    let healthy = ok_server().await;
+    let limiter = Arc::new(RateLimiter::new());
+    let refused = client(&limited).no_retries().rate_limiter(limiter.clone());
+    let other = client(&healthy).rate_limiter(limiter.clone());
+
+    let err = refused.generate("hi").await.unwrap_err();
+    assert!(
+        matches!(err.api_error_kind(), Some(ApiErrorKind::RateLimited { .. })),
+        "{err:?}"
+    );
+    assert!(limiter.paused_for().is_some());
+
+    let start = Instant::now();
+    other.generate("hi").await.unwrap();
+    assert!(
+        start.elapsed() >= Duration::from_millis(800),
+        "{:?}",
+        start.elapsed()
+    );
+}
+
+#[test]
+fn build_rejects_an_empty_budget() {
+    let err = OpenAIClient::new("test-key")
+        .unwrap()
+        .rate_limit(Requests::per_minute(0), Tokens::per_minute(90_000))
+        .build()
+        .err()
+        .unwrap();
+    assert!(matches!(err, RStructorError::ConfigError(_)), "{err:?}");
+}
@@ -0,0 +1,138 @@
+//! Offline tests for types that reach themselves through their fields: a
+//! recursive enum, and two structs that refer to each other. Building their
+//! schemas must terminate and leave a `$ref` at the point of recursion, with
+//! every `$ref` resolving against the root `$defs`.
+
+use rstructor::{Instructor, SchemaType};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+#[derive(Instructor, Serialize, Deserialize, Debug, PartialEq)]
+#[llm(description = "An arithmetic expression")]
+enum Expr {
+    Literal(i64),
+    Negate(Box<Expr>),
+    Sum { left: Box<Expr>, right: Box<Expr> },
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug, PartialEq)]
+struct Department {
+    name: String,
+    staff: Vec<Employee>,
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug, PartialEq)]
+struct Employee {
+    name: String,
+    #[llm(description = "A department this employee manages, if any")]
+    manages: Option<Box<Department>>,
+}
+
+/// Every `$ref` in `schema`.
+fn refs(schema: &Value) -> Vec<String> {
+    let mut found = Vec::new();
+    let mut stack = vec![schema];
+    while let Some(value) = stack.pop() {
+        match value {
+            Value::Object(obj) => {
+                if let Some(Value::String(target)) = obj.get("$ref") {
+                    found.push(target.clone());
+                }
+                stack.extend(obj.values());
+            }
+            Value::Array(items) => stack.extend(items),
+            _ => {}
+        }
+    }
+    found
+}
+
+/// Every `$ref` points at a root definition.
+fn assert_refs_resolve(schema: &Value) {
+    for target in refs(schema) {
+        let name = target.strip_prefix("#/$defs/").unwrap();
+        assert!(
+            schema["$defs"][name].is_object(),
+            "{target} does not resolve in {schema:#}"
+        );
+    }
+}
+
+#[test]
+fn recursive_enum_refers_to_its_own_definition() {
+    let schema = Expr::schema().to_json();
+    assert_eq!(schema["$ref"], "#/$defs/Expr");
+    let definition = &schema["$defs"]["Expr"];
+    assert_eq!(definition["title"], "Expr");
+    assert_eq!(definition["description"], "An arithmetic expression");
+    assert!(refs(definition).len() >= 3, "{definition:#}");
+    assert_refs_resolve(&schema);
+}
+
+#[test]
+fn mutually_recursive_structs_terminate_from_either_end() {
+    let department = Department::schema().to_json();
+    assert_eq!(department["$ref"], "#/$defs/Department");
+    let staff = &department["$defs"]["Department"]["properties"]["staff"]["items"];
+    assert_eq!(staff["title"], "Employee");
+    assert_eq!(staff["properties"]["manages"]["$ref"], "#/$defs/Department");
+    assert_eq!(
+        staff["properties"]["manages"]["description"],
+        "A department this employee manages, if any"
+    );
+    assert_refs_resolve(&department);
+
+    // Employee was built inside Department's schema above, where its `$ref`
+    // only resolved against Department's $defs; that copy must not be reused.
+    let employee = Employee::schema().to_json();
+    assert_eq!(employee["title"], "Employee");
+    assert_eq!(
+        employee["properties"]["manages"]["$ref"],
+        "#/$defs/Department"
+    );
+    assert_refs_resolve(&employee);
+}
+
+#[test]
+fn schemas_are_stable_across_calls_and_threads() {
+    let first = Department::schema().to_json();
+    let from_thread = std::thread::spawn(|| Department::schema().to_json())
+        .join()
+        .unwrap();
+    assert_eq!(first, from_thread);
+    assert_eq!(first, Department::schema().to_json());
+}
+
+#[test]
+fn inlining_keeps_a_reference_only_at_the_cycle() {
+    let inlined = Department::schema().inline().to_json();
+    let manages = &inlined["properties"]["staff"]["items"]["properties"]["manages"];
+    assert_eq!(manages["$ref"], "#/$defs/Department");
+    assert_refs_resolve(&inlined);
+}
+
+#[test]
+fn recursive_values_round_trip() {
+    let expr = Expr::Sum {
+        left: Box::new(Expr::Literal(2)),
+        right: Box::new(Expr::Negate(Box::new(Expr::Literal(3)))),
+    };
+    let json = serde_json::to_value(&expr).unwrap();
+    assert_eq!(
+        json,
+        json!({ "Sum": { "left": { "Literal": 2 }, "right": { "Negate": { "Literal": 3 } } } })
+    );
+    assert_eq!(serde_json::from_value::<Expr>(json).unwrap(), expr);
+
+    let department = Department {
+        name: "Research".into(),
+        staff: vec![Employee {
+            name: "Grace".into(),
+            manages: Some(Box::new(Department {
+                name: "Compilers".into(),
+                staff: vec![],
+            })),
+        }],
+    };
+    assert!(department.validate().is_ok());
+}
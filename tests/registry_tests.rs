@@ -0,0 +1,120 @@
+//! Tests for the `registry` feature: derived types are found by name at
+//! runtime, with their schema and a validating deserializer.
+#![cfg(feature = "registry")]
+
+use rstructor::{Instructor, RStructorError, SchemaRegistry, SchemaType};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Instructor, Serialize, Deserialize, Debug, PartialEq)]
+#[llm(validate = "validate_invoice")]
+struct Invoice {
+    customer: String,
+    total: f64,
+}
+
+fn validate_invoice(invoice: &Invoice) -> rstructor::Result<()> {
+    if invoice.total < 0.0 {
+        return Err(RStructorError::ValidationError(
+            "total is negative".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Instructor, Serialize, Deserialize)]
+struct Wrapper<T> {
+    inner: T,
+}
+
+mod billing {
+    use super::*;
+
+    #[derive(Instructor, Serialize, Deserialize)]
+    pub struct Receipt {
+        pub amount: f64,
+    }
+}
+
+mod shipping {
+    use super::*;
+
+    #[derive(Instructor, Serialize, Deserialize)]
+    pub struct Receipt {
+        pub tracking: String,
+    }
+}
+
+#[test]
+fn derived_types_are_found_by_name() {
+    let entry = SchemaRegistry::get("Invoice").unwrap();
+    assert_eq!(entry.name(), "Invoice");
+    assert_eq!(entry.type_name(), std::any::type_name::<Invoice>());
+    assert_eq!(entry.schema().to_json(), Invoice::schema().to_json());
+
+    let by_path = SchemaRegistry::get(std::any::type_name::<Invoice>()).unwrap();
+    assert!(std::ptr::eq(entry, by_path));
+}
+
+#[test]
+fn values_are_deserialized_and_validated() {
+    let entry = SchemaRegistry::get("Invoice").unwrap();
+    let invoice = entry
+        .deserialize(json!({ "customer": "Acme", "total": 120.0 }))
+        .unwrap();
+    assert_eq!(
+        invoice.downcast_ref::<Invoice>(),
+        Some(&Invoice {
+            customer: "Acme".into(),
+            total: 120.0
+        })
+    );
+
+    let err = entry
+        .validate(&json!({ "customer": "Acme", "total": -1.0 }))
+        .unwrap_err();
+    assert!(err.to_string().contains("total is negative"), "{err}");
+    assert!(entry.validate(&json!({ "customer": "Acme" })).is_err());
+}
+
+#[test]
+fn ambiguous_and_unknown_names_are_errors() {
+    let err = SchemaRegistry::get("Receipt").unwrap_err();
+    assert!(
+        matches!(&err, RStructorError::SchemaError(msg)
+            if msg.contains("billing::Receipt") && msg.contains("shipping::Receipt")),
+        "{err:?}"
+    );
+    let receipt = SchemaRegistry::get(std::any::type_name::<shipping::Receipt>()).unwrap();
+    assert!(receipt.schema().to_json()["properties"]["tracking"].is_object());
+
+    assert!(matches!(
+        SchemaRegistry::get("Nope"),
+        Err(RStructorError::SchemaError(_))
+    ));
+}
+
+#[test]
+fn generic_types_are_not_registered() {
+    assert!(SchemaRegistry::iter().all(|entry| entry.name() != "Wrapper"));
+    let _ = Wrapper { inner: 1u8 };
+}
+
+#[cfg(feature = "mock")]
+#[tokio::test]
+async fn registered_types_materialize_through_a_dyn_client() {
+    use rstructor::{DynLLMClient, MockClient};
+
+    let client: Box<dyn DynLLMClient> = Box::new(
+        MockClient::new()
+            .with_retries(1)
+            .with_response(r#"{"customer": "Acme", "total": -5}"#)
+            .with_response(r#"{"customer": "Acme", "total": 5}"#),
+    );
+    let value = SchemaRegistry::get("Invoice")
+        .unwrap()
+        .materialize(&*client, "Acme owes five dollars")
+        .await
+        .unwrap();
+    assert_eq!(value, json!({ "customer": "Acme", "total": 5 }));
+}
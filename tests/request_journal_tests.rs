@@ -0,0 +1,80 @@
+//! The request journal records each request before it is sent and finalizes
+//! it with the reported usage. The journal is process-wide, so this file holds
+//! a single test.
+#![cfg(feature = "openai")]
+
+use rstructor::{Instructor, LLMClient, OpenAIClient, RequestJournal};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+struct Movie {
+    title: String,
+    year: u16,
+}
+
+fn chat_completion(content: &str, prompt_tokens: u64) -> String {
+    json!({
+        "model": "gpt-4o-mini-2024-07-18",
+        "choices": [{
+            "message": { "role": "assistant", "content": content },
+            "finish_reason": "stop",
+        }],
+        "usage": { "prompt_tokens": prompt_tokens, "completion_tokens": 7, "total_tokens": prompt_tokens + 7 }
+    })
+    .to_string()
+}
+
+#[tokio::test]
+async fn each_attempt_is_journaled_before_sending_and_finalized_with_usage() {
+    let path = std::env::temp_dir().join(format!("rstructor-journal-{}.jsonl", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    rstructor::set_request_journal(RequestJournal::open(&path).unwrap());
+
+    let mut server = mockito::Server::new_async().await;
+    // The first reply fails to parse, so the client re-asks once.
+    server
+        .mock("POST", "/chat/completions")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(chat_completion(r#"{"title":"Inception"}"#, 40))
+        .expect(1)
+        .create_async()
+        .await;
+    server
+        .mock("POST", "/chat/completions")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(chat_completion(r#"{"title":"Inception","year":2010}"#, 60))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = OpenAIClient::new("test-key")
+        .unwrap()
+        .base_url(server.url())
+        .model("gpt-4o-mini")
+        .max_retries(1);
+    let movie: Movie = client.materialize("Describe Inception").await.unwrap();
+    assert_eq!(movie.year, 2010);
+
+    rstructor::clear_request_journal();
+    assert!(rstructor::request_journal().is_none());
+
+    let entries = RequestJournal::read(&path).unwrap();
+    assert_eq!(entries.len(), 2, "{entries:?}");
+    assert_ne!(entries[0].id, entries[1].id);
+    for entry in &entries {
+        assert_eq!(entry.provider, "OpenAI");
+        assert_eq!(entry.model.as_deref(), Some("gpt-4o-mini"));
+        assert!(entry.estimated_tokens > 0);
+        assert!(entry.is_finished());
+    }
+    let usage: Vec<_> = entries
+        .iter()
+        .map(|e| e.usage.as_ref().unwrap().input_tokens)
+        .collect();
+    assert_eq!(usage, [40, 60]);
+    assert_eq!(entries[1].billable_tokens(), 67);
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,106 @@
+//! `max_request_bytes`: oversized requests fail locally with
+//! `RequestTooLarge`, naming their largest part, and are never sent.
+#![cfg(feature = "openai")]
+
+use rstructor::{
+    Instructor, LLMClient, MediaFile, OpenAIClient, PayloadComponent, RStructorError, RequestExt,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+struct Caption {
+    text: String,
+}
+
+/// A server expecting `hits` requests.
+async fn server(hits: usize) -> (mockito::ServerGuard, mockito::Mock) {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("POST", "/chat/completions")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({
+                "choices": [{
+                    "message": { "role": "assistant", "content": r#"{"text":"a cat"}"# },
+                    "finish_reason": "stop"
+                }]
+            })
+            .to_string(),
+        )
+        .expect(hits)
+        .create_async()
+        .await;
+    (server, mock)
+}
+
+fn client(server: &mockito::ServerGuard, limit: usize) -> OpenAIClient {
+    OpenAIClient::new("test-key")
+        .unwrap()
+        .base_url(server.url())
+        .model("gpt-4o-mini")
+        .max_request_bytes(limit)
+}
+
+fn component(err: RStructorError) -> PayloadComponent {
+    match err {
+        RStructorError::RequestTooLarge {
+            component,
+            bytes,
+            limit,
+            ..
+        } => {
+            assert!(bytes > limit);
+            component
+        }
+        other => panic!("expected RequestTooLarge, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn an_oversized_image_is_refused_before_upload() {
+    let (server, mock) = server(0).await;
+    let image = [MediaFile::from_bytes([7u8; 30_000], "image/png")];
+    let err = client(&server, 20_000)
+        .with_media(&image)
+        .materialize::<Caption>("Caption this")
+        .await
+        .unwrap_err();
+    assert_eq!(component(err), PayloadComponent::Media);
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn an_oversized_prompt_is_named_as_the_culprit() {
+    let (server, mock) = server(0).await;
+    let err = client(&server, 5_000)
+        .generate(&"lorem ipsum ".repeat(1_000))
+        .await
+        .unwrap_err();
+    assert_eq!(component(err), PayloadComponent::Prompt);
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn requests_within_the_limit_are_sent() {
+    let (server, mock) = server(1).await;
+    let caption: Caption = client(&server, 20_000)
+        .materialize("Caption this")
+        .await
+        .unwrap();
+    assert_eq!(caption.text, "a cat");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn the_limit_can_be_turned_off() {
+    let (server, mock) = server(1).await;
+    let caption: Caption = client(&server, 5_000)
+        .without_request_size_limit()
+        .materialize(&"lorem ipsum ".repeat(1_000))
+        .await
+        .unwrap();
+    assert_eq!(caption.text, "a cat");
+    mock.assert_async().await;
+}
@@ -0,0 +1,205 @@
+//! `Vec<T>` as the extraction target: the schema is sent wrapped in an object
+//! (which OpenAI-style structured outputs and Anthropic tools require) and the
+//! list is unwrapped from the reply.
+#![cfg(all(feature = "openai", feature = "anthropic", feature = "ollama"))]
+
+use std::sync::{Arc, Mutex};
+
+use rstructor::{Instructor, LLMClient, RStructorError};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+#[derive(Instructor, Serialize, Deserialize, Debug, PartialEq)]
+#[llm(validate = "validate_person")]
+struct Person {
+    name: String,
+    age: u8,
+}
+
+fn validate_person(person: &Person) -> rstructor::Result<()> {
+    if person.name.is_empty() {
+        return Err(RStructorError::ValidationError(
+            "name must not be empty".into(),
+        ));
+    }
+    Ok(())
+}
+
+fn people() -> Vec<Person> {
+    vec![
+        Person {
+            name: "Ada".into(),
+            age: 36,
+        },
+        Person {
+            name: "Alan".into(),
+            age: 41,
+        },
+    ]
+}
+
+const WRAPPED: &str = r#"{"items": [{"name": "Ada", "age": 36}, {"name": "Alan", "age": 41}]}"#;
+
+/// Serve `bodies` in order on `path`, recording each request body.
+async fn serve(
+    server: &mut mockito::Server,
+    path: &str,
+    bodies: Vec<Value>,
+) -> Arc<Mutex<Vec<Value>>> {
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let sink = requests.clone();
+    let replies = Mutex::new(bodies.into_iter());
+    server
+        .mock("POST", path)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body_from_request(move |request| {
+            let body = serde_json::from_slice(request.body().unwrap()).unwrap();
+            sink.lock().unwrap().push(body);
+            let reply = replies.lock().unwrap().next().expect("unexpected request");
+            reply.to_string().into_bytes()
+        })
+        .create_async()
+        .await;
+    requests
+}
+
+fn chat_completion(content: &str) -> Value {
+    json!({
+        "choices": [{
+            "message": { "role": "assistant", "content": content },
+            "finish_reason": "stop"
+        }]
+    })
+}
+
+#[tokio::test]
+async fn openai_wraps_the_array_schema_and_unwraps_the_reply() {
+    let mut server = mockito::Server::new_async().await;
+    let requests = serve(
+        &mut server,
+        "/chat/completions",
+        vec![chat_completion(WRAPPED)],
+    )
+    .await;
+    let client = rstructor::OpenAIClient::new("test-key")
+        .unwrap()
+        .base_url(server.url())
+        .model("gpt-4o-mini");
+
+    let found: Vec<Person> = client.materialize("Who invented computing?").await.unwrap();
+    assert_eq!(found, people());
+
+    let request = &requests.lock().unwrap()[0];
+    let format = &request["response_format"]["json_schema"];
+    assert_eq!(format["name"], "Vec_Person");
+    assert_eq!(format["schema"]["type"], "object");
+    assert_eq!(format["schema"]["required"], json!(["items"]));
+    assert_eq!(format["schema"]["properties"]["items"]["type"], "array");
+    assert_eq!(
+        format["schema"]["properties"]["items"]["items"]["title"],
+        "Person"
+    );
+}
+
+#[tokio::test]
+async fn element_validation_failures_are_re_asked() {
+    let mut server = mockito::Server::new_async().await;
+    let invalid = r#"{"items": [{"name": "", "age": 1}]}"#;
+    let requests = serve(
+        &mut server,
+        "/chat/completions",
+        vec![chat_completion(invalid), chat_completion(WRAPPED)],
+    )
+    .await;
+    let client = rstructor::OpenAIClient::new("test-key")
+        .unwrap()
+        .base_url(server.url())
+        .model("gpt-4o-mini")
+        .max_retries(1);
+
+    let found: Vec<Person> = client.materialize("Who invented computing?").await.unwrap();
+    assert_eq!(found, people());
+    assert_eq!(requests.lock().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn anthropic_tool_input_schema_has_an_object_root() {
+    let mut server = mockito::Server::new_async().await;
+    let requests = serve(
+        &mut server,
+        "/messages",
+        vec![json!({
+            "content": [{
+                "type": "tool_use",
+                "id": "toolu_1",
+                "name": "structured_output",
+                "input": serde_json::from_str::<Value>(WRAPPED).unwrap()
+            }],
+            "stop_reason": "tool_use"
+        })],
+    )
+    .await;
+    let client = rstructor::AnthropicClient::new("test-key")
+        .unwrap()
+        .base_url(server.url())
+        .output_strategy(rstructor::OutputStrategy::ToolCalling);
+
+    let found: Vec<Person> = client.materialize("Who invented computing?").await.unwrap();
+    assert_eq!(found, people());
+
+    let request = &requests.lock().unwrap()[0];
+    let input_schema = &request["tools"][0]["input_schema"];
+    assert_eq!(input_schema["type"], "object");
+    assert_eq!(input_schema["properties"]["items"]["type"], "array");
+}
+
+#[tokio::test]
+async fn bare_array_replies_are_accepted_too() {
+    let mut server = mockito::Server::new_async().await;
+    serve(
+        &mut server,
+        "/api/chat",
+        vec![json!({
+            "model": "llama3.2",
+            "message": {
+                "role": "assistant",
+                "content": r#"[{"name": "Ada", "age": 36}, {"name": "Alan", "age": 41}]"#
+            },
+            "done": true
+        })],
+    )
+    .await;
+    let client = rstructor::OllamaClient::new().base_url(server.url());
+
+    let found: Vec<Person> = client.materialize("Who invented computing?").await.unwrap();
+    assert_eq!(found, people());
+}
+
+#[tokio::test]
+async fn explanations_work_with_array_roots() {
+    let mut server = mockito::Server::new_async().await;
+    let reply = r#"{"_reasoning": "Two pioneers", "items": [{"name": "Ada", "age": 36}, {"name": "Alan", "age": 41}]}"#;
+    let requests = serve(
+        &mut server,
+        "/chat/completions",
+        vec![chat_completion(reply)],
+    )
+    .await;
+    let client = rstructor::OpenAIClient::new("test-key")
+        .unwrap()
+        .base_url(server.url())
+        .model("gpt-4o-mini")
+        .explain(true);
+
+    let result = client
+        .materialize_with_metadata::<Vec<Person>>("Who invented computing?")
+        .await
+        .unwrap();
+    assert_eq!(result.data, people());
+    assert_eq!(result.explanation.as_deref(), Some("Two pioneers"));
+
+    let request = &requests.lock().unwrap()[0];
+    let schema = &request["response_format"]["json_schema"]["schema"];
+    assert_eq!(schema["required"], json!(["_reasoning", "items"]));
+}
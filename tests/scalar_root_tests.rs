@@ -0,0 +1,152 @@
+//! Scalars as the extraction target: `materialize::<bool>` for a yes/no
+//! question, numbers and strings likewise. The schema is sent wrapped in an
+//! object with a single `value` property and the answer is unwrapped again.
+#![cfg(all(feature = "openai", feature = "anthropic", feature = "ollama"))]
+
+use std::sync::{Arc, Mutex};
+
+use rstructor::LLMClient;
+use serde_json::{Value, json};
+
+/// Serve `reply` on `path`, recording each request body.
+async fn serve(server: &mut mockito::Server, path: &str, reply: Value) -> Arc<Mutex<Vec<Value>>> {
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let sink = requests.clone();
+    server
+        .mock("POST", path)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body_from_request(move |request| {
+            let body = serde_json::from_slice(request.body().unwrap()).unwrap();
+            sink.lock().unwrap().push(body);
+            reply.to_string().into_bytes()
+        })
+        .create_async()
+        .await;
+    requests
+}
+
+fn chat_completion(content: &str) -> Value {
+    json!({
+        "choices": [{
+            "message": { "role": "assistant", "content": content },
+            "finish_reason": "stop"
+        }]
+    })
+}
+
+async fn openai(
+    content: &str,
+) -> (
+    rstructor::OpenAIClient,
+    Arc<Mutex<Vec<Value>>>,
+    mockito::ServerGuard,
+) {
+    let mut server = mockito::Server::new_async().await;
+    let requests = serve(&mut server, "/chat/completions", chat_completion(content)).await;
+    let client = rstructor::OpenAIClient::new("test-key")
+        .unwrap()
+        .base_url(server.url())
+        .model("gpt-4o-mini");
+    (client, requests, server)
+}
+
+#[tokio::test]
+async fn yes_no_questions_materialize_as_bool() {
+    let (client, requests, _server) = openai(r#"{"value": true}"#).await;
+    let spam: bool = client
+        .materialize("Is this email spam? 'You won a free cruise!'")
+        .await
+        .unwrap();
+    assert!(spam);
+
+    let request = &requests.lock().unwrap()[0];
+    let format = &request["response_format"]["json_schema"];
+    assert_eq!(format["name"], "bool");
+    assert_eq!(format["schema"]["type"], "object");
+    assert_eq!(format["schema"]["required"], json!(["value"]));
+    assert_eq!(format["schema"]["properties"]["value"]["type"], "boolean");
+}
+
+#[tokio::test]
+async fn numbers_and_strings_are_unwrapped() {
+    let (client, _requests, _server) = openai(r#"{"value": 42}"#).await;
+    assert_eq!(client.materialize::<u32>("How many?").await.unwrap(), 42);
+
+    let (client, _requests, _server) = openai(r#"{"value": 0.25}"#).await;
+    assert_eq!(
+        client.materialize::<f64>("What fraction?").await.unwrap(),
+        0.25
+    );
+
+    let (client, _requests, _server) = openai(r#"{"value": "Paris"}"#).await;
+    assert_eq!(
+        client
+            .materialize::<String>("Capital of France?")
+            .await
+            .unwrap(),
+        "Paris"
+    );
+
+    let (client, _requests, _server) = openai(r#"{"items": ["red", "green"]}"#).await;
+    assert_eq!(
+        client
+            .materialize::<Vec<String>>("Two colours")
+            .await
+            .unwrap(),
+        ["red", "green"]
+    );
+}
+
+#[tokio::test]
+async fn out_of_range_answers_are_rejected() {
+    let (client, _requests, _server) = openai(r#"{"value": -1}"#).await;
+    let client = client.no_retries();
+    assert!(client.materialize::<u8>("How many?").await.is_err());
+}
+
+#[tokio::test]
+async fn anthropic_tool_input_wraps_the_scalar() {
+    let mut server = mockito::Server::new_async().await;
+    let requests = serve(
+        &mut server,
+        "/messages",
+        json!({
+            "content": [{
+                "type": "tool_use",
+                "id": "toolu_1",
+                "name": "structured_output",
+                "input": { "value": 7 }
+            }],
+            "stop_reason": "tool_use"
+        }),
+    )
+    .await;
+    let client = rstructor::AnthropicClient::new("test-key")
+        .unwrap()
+        .base_url(server.url())
+        .output_strategy(rstructor::OutputStrategy::ToolCalling);
+
+    assert_eq!(client.materialize::<i64>("Pick a number").await.unwrap(), 7);
+    let request = &requests.lock().unwrap()[0];
+    let input_schema = &request["tools"][0]["input_schema"];
+    assert_eq!(input_schema["type"], "object");
+    assert_eq!(input_schema["properties"]["value"]["type"], "integer");
+}
+
+#[tokio::test]
+async fn bare_scalar_replies_are_accepted_too() {
+    let mut server = mockito::Server::new_async().await;
+    serve(
+        &mut server,
+        "/api/chat",
+        json!({
+            "model": "llama3.2",
+            "message": { "role": "assistant", "content": "false" },
+            "done": true
+        }),
+    )
+    .await;
+    let client = rstructor::OllamaClient::new().base_url(server.url());
+    assert!(!client.materialize::<bool>("Is water dry?").await.unwrap());
+}
@@ -0,0 +1,77 @@
+//! Derived `schema()` caching: built once per non-generic type, rebuilt on
+//! every call for generic types and `#[llm(no_schema_cache)]`.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rstructor::{Instructor, Schema, SchemaType};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+static CACHED_BUILDS: AtomicUsize = AtomicUsize::new(0);
+static UNCACHED_BUILDS: AtomicUsize = AtomicUsize::new(0);
+static GENERIC_BUILDS: AtomicUsize = AtomicUsize::new(0);
+
+fn counted(counter: &AtomicUsize) -> Schema {
+    counter.fetch_add(1, Ordering::SeqCst);
+    Schema::new(json!({ "type": "string" }))
+}
+
+fn cached_field() -> Schema {
+    counted(&CACHED_BUILDS)
+}
+
+fn uncached_field() -> Schema {
+    counted(&UNCACHED_BUILDS)
+}
+
+fn generic_field() -> Schema {
+    counted(&GENERIC_BUILDS)
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+struct Cached {
+    #[llm(schema_with = "cached_field")]
+    label: String,
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+#[llm(no_schema_cache)]
+enum Uncached {
+    Label {
+        #[llm(schema_with = "uncached_field")]
+        label: String,
+    },
+    Empty,
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+struct Page<T> {
+    #[llm(schema_with = "generic_field")]
+    cursor: String,
+    items: Vec<T>,
+}
+
+#[test]
+fn derived_schema_is_built_once() {
+    let first = Cached::schema().to_json();
+    let second = Cached::schema().to_json();
+    assert_eq!(first, second);
+    assert_eq!(first["properties"]["label"]["type"], "string");
+    assert_eq!(CACHED_BUILDS.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn no_schema_cache_rebuilds_every_call() {
+    Uncached::schema();
+    Uncached::schema();
+    assert_eq!(UNCACHED_BUILDS.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn generic_types_keep_a_schema_per_instantiation() {
+    let numbers = <Page<u32>>::schema().to_json();
+    let words = <Page<String>>::schema().to_json();
+    assert_eq!(numbers["properties"]["items"]["items"]["type"], "integer");
+    assert_eq!(words["properties"]["items"]["items"]["type"], "string");
+    assert_eq!(GENERIC_BUILDS.load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,227 @@
+//! Per-locale descriptions from `#[llm(description(en = "..", de = ".."))]`
+//! and `DescriptionCatalog`, selected with `Schema::localized` or a client's
+//! `.schema_locale(..)`.
+
+use rstructor::{DescriptionCatalog, Instructor, SchemaType};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Instructor, Serialize, Deserialize, Debug, PartialEq)]
+#[llm(description(en = "A line item", de = "Eine Rechnungsposition"))]
+struct LineItem {
+    #[llm(description(
+        en = "What was sold",
+        de = "Was verkauft wurde",
+        fr = "Ce qui a été vendu"
+    ))]
+    product: String,
+    #[llm(description = "Units sold")]
+    quantity: u32,
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug, PartialEq)]
+enum Status {
+    #[llm(description(en = "Paid in full", de = "Vollständig bezahlt"))]
+    Paid,
+    Open,
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug, PartialEq)]
+#[llm(description(en = "An invoice", de = "Eine Rechnung"))]
+struct Invoice {
+    #[llm(description(en = "Total amount due", de = "Fälliger Gesamtbetrag"))]
+    total: f64,
+    status: Status,
+    items: Vec<LineItem>,
+}
+
+fn contains_key(value: &Value, key: &str) -> bool {
+    match value {
+        Value::Object(map) => map.contains_key(key) || map.values().any(|v| contains_key(v, key)),
+        Value::Array(items) => items.iter().any(|v| contains_key(v, key)),
+        _ => false,
+    }
+}
+
+#[test]
+fn the_first_locale_is_the_default_description() {
+    let schema = Invoice::schema().to_json();
+    assert_eq!(schema["description"], "An invoice");
+    assert_eq!(
+        schema["properties"]["total"]["description"],
+        "Total amount due"
+    );
+}
+
+#[test]
+fn localized_translates_nested_types_and_variants() {
+    let german = Invoice::schema().localized("de").to_json();
+    assert_eq!(german["description"], "Eine Rechnung");
+    assert_eq!(
+        german["properties"]["total"]["description"],
+        "Fälliger Gesamtbetrag"
+    );
+
+    let item = &german["properties"]["items"]["items"];
+    assert_eq!(item["description"], "Eine Rechnungsposition");
+    assert_eq!(
+        item["properties"]["product"]["description"],
+        "Was verkauft wurde"
+    );
+    // Without a translation the default text is kept.
+    assert_eq!(item["properties"]["quantity"]["description"], "Units sold");
+
+    let variants = german["properties"]["status"]["oneOf"].as_array().unwrap();
+    assert_eq!(variants[0]["description"], "Vollständig bezahlt");
+    assert!(!contains_key(&german, "x-descriptions"));
+}
+
+#[test]
+fn regional_locales_fall_back_to_their_language() {
+    let swiss = LineItem::schema().localized("de_CH").to_json();
+    assert_eq!(
+        swiss["properties"]["product"]["description"],
+        "Was verkauft wurde"
+    );
+
+    let french = LineItem::schema().localized("fr").to_json();
+    assert_eq!(french["description"], "A line item");
+    assert_eq!(
+        french["properties"]["product"]["description"],
+        "Ce qui a été vendu"
+    );
+}
+
+#[test]
+fn catalog_entries_take_precedence_over_derived_ones() {
+    let catalog = DescriptionCatalog::new()
+        .translation("de", "Total amount due", "Zu zahlender Betrag")
+        .translation("de", "Units sold", "Verkaufte Menge");
+    let german = Invoice::schema().localized_with("de", &catalog).to_json();
+    assert_eq!(
+        german["properties"]["total"]["description"],
+        "Zu zahlender Betrag"
+    );
+    assert_eq!(
+        german["properties"]["items"]["items"]["properties"]["quantity"]["description"],
+        "Verkaufte Menge"
+    );
+    assert_eq!(german["description"], "Eine Rechnung");
+}
+
+#[test]
+fn translations_lists_what_the_derive_recorded() {
+    let translations = Invoice::schema().translations();
+    let mut locales: Vec<_> = translations.locales().collect();
+    locales.sort_unstable();
+    assert_eq!(locales, ["de", "fr"]);
+    assert_eq!(
+        translations.get("de", "Paid in full"),
+        Some("Vollständig bezahlt")
+    );
+}
+
+#[test]
+fn catalogs_load_from_json() {
+    let catalog = DescriptionCatalog::from_json(&serde_json::json!({
+        "es": { "An invoice": "Una factura" }
+    }))
+    .unwrap();
+    assert_eq!(catalog.get("es-MX", "An invoice"), Some("Una factura"));
+
+    let err = DescriptionCatalog::from_json(&serde_json::json!({ "es": "Una factura" }));
+    assert!(matches!(
+        err,
+        Err(rstructor::RStructorError::SchemaError(_))
+    ));
+}
+
+#[test]
+fn strict_schemas_never_carry_the_translations() {
+    let strict = Invoice::schema().to_openai_strict();
+    assert!(!contains_key(&strict, "x-descriptions"));
+    assert_eq!(strict["description"], "An invoice");
+}
+
+#[cfg(feature = "openai")]
+mod client {
+    use std::sync::{Arc, Mutex};
+
+    use rstructor::LLMClient;
+    use serde_json::{Value, json};
+
+    use super::{Invoice, contains_key};
+
+    async fn sent_schema(
+        configure: impl FnOnce(rstructor::OpenAIClient) -> rstructor::OpenAIClient,
+    ) -> Value {
+        let mut server = mockito::Server::new_async().await;
+        let requests = Arc::new(Mutex::new(Vec::<Value>::new()));
+        let sink = requests.clone();
+        let reply = json!({
+            "choices": [{
+                "message": {
+                    "role": "assistant",
+                    "content": r#"{"total": 12.5, "status": "Paid", "items": []}"#
+                },
+                "finish_reason": "stop"
+            }]
+        });
+        server
+            .mock("POST", "/chat/completions")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body_from_request(move |request| {
+                sink.lock()
+                    .unwrap()
+                    .push(serde_json::from_slice(request.body().unwrap()).unwrap());
+                reply.to_string().into_bytes()
+            })
+            .create_async()
+            .await;
+        let client = configure(
+            rstructor::OpenAIClient::new("test-key")
+                .unwrap()
+                .base_url(server.url())
+                .model("gpt-4o-mini"),
+        );
+        let invoice: Invoice = client.materialize("Rechnung Nr. 7").await.unwrap();
+        assert_eq!(invoice.total, 12.5);
+        let body = requests.lock().unwrap().remove(0);
+        body["response_format"]["json_schema"]["schema"].clone()
+    }
+
+    #[tokio::test]
+    async fn schema_locale_sends_translated_descriptions() {
+        let schema = sent_schema(|client| client.schema_locale("de")).await;
+        assert_eq!(schema["description"], "Eine Rechnung");
+        assert_eq!(
+            schema["properties"]["total"]["description"],
+            "Fälliger Gesamtbetrag"
+        );
+        assert!(!contains_key(&schema, "x-descriptions"));
+    }
+
+    #[tokio::test]
+    async fn without_a_locale_the_defaults_are_sent_without_translations() {
+        let schema = sent_schema(|client| client).await;
+        assert_eq!(schema["description"], "An invoice");
+        assert!(!contains_key(&schema, "x-descriptions"));
+    }
+
+    #[tokio::test]
+    async fn a_client_catalog_overrides_derived_translations() {
+        let catalog = rstructor::DescriptionCatalog::new().translation(
+            "de",
+            "An invoice",
+            "Eine Kundenrechnung",
+        );
+        let schema =
+            sent_schema(|client| client.description_catalog(catalog).schema_locale("de-DE")).await;
+        assert_eq!(schema["description"], "Eine Kundenrechnung");
+        assert_eq!(
+            schema["properties"]["total"]["description"],
+            "Fälliger Gesamtbetrag"
+        );
+    }
+}
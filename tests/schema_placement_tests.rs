@@ -0,0 +1,227 @@
+//! Every structured-output request carries the schema in exactly one place —
+//! the provider's native schema slot (response format, tool parameters,
+//! Ollama `format`) or, for JSON mode, the prompt — never both. A schema sent
+//! twice roughly doubles input tokens on every call and every re-ask.
+//!
+//! Each test captures the real request bodies over a local mock server, with
+//! a validation failure first so the re-ask request is checked too.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use rstructor::{Instructor, LLMClient, RStructorError};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+/// Appears once per copy of the schema in a request body.
+const MARKER: &str = "SCHEMA-PLACEMENT-MARKER";
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+#[llm(validate = "validate_review")]
+struct Review {
+    #[llm(description = "SCHEMA-PLACEMENT-MARKER one-line verdict")]
+    verdict: String,
+    stars: u8,
+}
+
+fn validate_review(review: &Review) -> rstructor::Result<()> {
+    if !(1..=5).contains(&review.stars) {
+        return Err(RStructorError::ValidationError(format!(
+            "stars must be between 1 and 5, got {}",
+            review.stars
+        )));
+    }
+    Ok(())
+}
+
+const INVALID: &str = r#"{"verdict": "great", "stars": 9}"#;
+const VALID: &str = r#"{"verdict": "great", "stars": 4}"#;
+
+/// Serve `reply(INVALID)` then `reply(VALID)` on `path`, recording each
+/// request body.
+async fn serve(
+    server: &mut mockito::Server,
+    path: impl Into<mockito::Matcher>,
+    reply: fn(&str) -> Value,
+) -> (mockito::Mock, Arc<Mutex<Vec<String>>>) {
+    let bodies = Arc::new(Mutex::new(Vec::new()));
+    let sink = bodies.clone();
+    let calls = AtomicUsize::new(0);
+    let mock = server
+        .mock("POST", path)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body_from_request(move |request| {
+            sink.lock()
+                .unwrap()
+                .push(request.utf8_lossy_body().unwrap().into_owned());
+            let content = match calls.fetch_add(1, Ordering::SeqCst) {
+                0 => INVALID,
+                _ => VALID,
+            };
+            reply(content).to_string().into_bytes()
+        })
+        .expect(2)
+        .create_async()
+        .await;
+    (mock, bodies)
+}
+
+fn assert_schema_sent_once(bodies: &Mutex<Vec<String>>) {
+    let bodies = bodies.lock().unwrap();
+    assert_eq!(bodies.len(), 2, "first attempt and one re-ask");
+    for (attempt, body) in bodies.iter().enumerate() {
+        assert_eq!(
+            body.matches(MARKER).count(),
+            1,
+            "attempt {attempt} should carry the schema exactly once: {body}"
+        );
+    }
+}
+
+#[cfg(any(feature = "openai", feature = "grok"))]
+fn chat_completion(content: &str) -> Value {
+    json!({
+        "choices": [{
+            "message": { "role": "assistant", "content": content },
+            "finish_reason": "stop",
+        }]
+    })
+}
+
+#[cfg(feature = "openai")]
+#[tokio::test]
+async fn openai_sends_the_schema_only_as_response_format() {
+    let mut server = mockito::Server::new_async().await;
+    let (mock, bodies) = serve(&mut server, "/chat/completions", chat_completion).await;
+
+    let client = rstructor::OpenAIClient::new("test-key")
+        .unwrap()
+        .base_url(server.url())
+        .model("gpt-4o-mini")
+        .max_retries(1);
+    let review: Review = client.materialize("Review the film").await.unwrap();
+    assert_eq!(review.stars, 4);
+
+    mock.assert_async().await;
+    assert_schema_sent_once(&bodies);
+}
+
+#[cfg(feature = "grok")]
+#[tokio::test]
+async fn grok_sends_the_schema_only_as_response_format() {
+    let mut server = mockito::Server::new_async().await;
+    let (mock, bodies) = serve(&mut server, "/chat/completions", chat_completion).await;
+
+    let client = rstructor::GrokClient::new("test-key")
+        .unwrap()
+        .base_url(server.url())
+        .max_retries(1);
+    let review: Review = client.materialize("Review the film").await.unwrap();
+    assert_eq!(review.stars, 4);
+
+    mock.assert_async().await;
+    assert_schema_sent_once(&bodies);
+    let first: Value = serde_json::from_str(&bodies.lock().unwrap()[0]).unwrap();
+    assert!(first.get("tools").is_none());
+}
+
+#[cfg(feature = "gemini")]
+#[tokio::test]
+async fn gemini_sends_the_schema_only_as_response_schema() {
+    fn reply(content: &str) -> Value {
+        json!({
+            "candidates": [{
+                "content": { "parts": [{ "text": content }] },
+                "finish_reason": "STOP"
+            }]
+        })
+    }
+    let mut server = mockito::Server::new_async().await;
+    let path = mockito::Matcher::Regex(r"^/models/.+:generateContent".to_string());
+    let (mock, bodies) = serve(&mut server, path, reply).await;
+
+    let client = rstructor::GeminiClient::new("test-key")
+        .unwrap()
+        .base_url(server.url())
+        .max_retries(1);
+    let review: Review = client.materialize("Review the film").await.unwrap();
+    assert_eq!(review.stars, 4);
+
+    mock.assert_async().await;
+    assert_schema_sent_once(&bodies);
+}
+
+#[cfg(feature = "anthropic")]
+#[tokio::test]
+async fn anthropic_sends_the_schema_once_with_either_strategy() {
+    fn text_reply(content: &str) -> Value {
+        json!({
+            "content": [{ "type": "text", "text": content }],
+            "stop_reason": "end_turn"
+        })
+    }
+    fn tool_reply(content: &str) -> Value {
+        json!({
+            "content": [{
+                "type": "tool_use",
+                "id": "toolu_1",
+                "name": "structured_output",
+                "input": serde_json::from_str::<Value>(content).unwrap()
+            }],
+            "stop_reason": "tool_use"
+        })
+    }
+
+    for (strategy, reply) in [
+        (
+            rstructor::OutputStrategy::JsonSchema,
+            text_reply as fn(&str) -> Value,
+        ),
+        (rstructor::OutputStrategy::ToolCalling, tool_reply),
+    ] {
+        let mut server = mockito::Server::new_async().await;
+        let (mock, bodies) = serve(&mut server, "/messages", reply).await;
+
+        let client = rstructor::AnthropicClient::new("test-key")
+            .unwrap()
+            .base_url(server.url())
+            .output_strategy(strategy)
+            .max_retries(1);
+        let review: Review = client.materialize("Review the film").await.unwrap();
+        assert_eq!(review.stars, 4, "{strategy:?}");
+
+        mock.assert_async().await;
+        assert_schema_sent_once(&bodies);
+    }
+}
+
+#[cfg(feature = "ollama")]
+#[tokio::test]
+async fn ollama_sends_the_schema_once_with_either_strategy() {
+    fn reply(content: &str) -> Value {
+        json!({
+            "model": "llama3.2",
+            "message": { "role": "assistant", "content": content },
+            "done": true
+        })
+    }
+
+    for strategy in [
+        rstructor::OutputStrategy::JsonSchema,
+        rstructor::OutputStrategy::JsonMode,
+    ] {
+        let mut server = mockito::Server::new_async().await;
+        let (mock, bodies) = serve(&mut server, "/api/chat", reply).await;
+
+        let client = rstructor::OllamaClient::new()
+            .base_url(server.url())
+            .output_strategy(strategy)
+            .max_retries(1);
+        let review: Review = client.materialize("Review the film").await.unwrap();
+        assert_eq!(review.stars, 4, "{strategy:?}");
+
+        mock.assert_async().await;
+        assert_schema_sent_once(&bodies);
+    }
+}
@@ -187,6 +187,74 @@ mod serde_rename_tests {
         );
     }
 
+    /// Renames listed after other serde options in the same attribute
+    #[derive(Instructor, Serialize, Deserialize, Debug)]
+    #[serde(deny_unknown_fields, rename_all = "camelCase")]
+    struct MixedSerdeOptions {
+        #[serde(default, skip_serializing_if = "Option::is_none", rename = "fullName")]
+        name: Option<String>,
+
+        #[serde(with = "serde_bytes_as_list", rename = "raw")]
+        payload: Vec<u8>,
+
+        /// Only the deserialize side matters for parsing the model's reply
+        #[serde(rename(serialize = "emailOut", deserialize = "emailIn"))]
+        email: String,
+
+        #[serde(rename(serialize = "ignored"))]
+        zip_code: String,
+    }
+
+    mod serde_bytes_as_list {
+        use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+        pub fn serialize<S: Serializer>(bytes: &[u8], s: S) -> Result<S::Ok, S::Error> {
+            bytes.serialize(s)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<u8>, D::Error> {
+            Vec::deserialize(d)
+        }
+    }
+
+    #[test]
+    fn test_field_rename_after_other_serde_options() {
+        let schema_json = MixedSerdeOptions::schema().to_json();
+        let props = schema_json["properties"].as_object().unwrap();
+        let mut keys: Vec<&str> = props.keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        assert_eq!(keys, vec!["emailIn", "fullName", "raw", "zipCode"]);
+
+        // Every schema key is one serde accepts
+        let reply = serde_json::json!({
+            "fullName": "Ada",
+            "raw": [1, 2],
+            "emailIn": "ada@example.com",
+            "zipCode": "12345"
+        });
+        let parsed: MixedSerdeOptions = serde_json::from_value(reply).unwrap();
+        assert_eq!(parsed.name.as_deref(), Some("Ada"));
+        assert_eq!(parsed.payload, vec![1, 2]);
+    }
+
+    #[derive(Instructor, Serialize, Deserialize, Debug)]
+    enum Shape {
+        #[serde(alias = "circle", rename = "CIRCLE")]
+        Circle { radius: f64 },
+        Square {
+            #[serde(alias = "len", rename = "side_length")]
+            side: f64,
+        },
+    }
+
+    #[test]
+    fn test_variant_and_variant_field_rename_after_alias() {
+        let schema = Shape::schema().to_json().to_string();
+        assert!(schema.contains("\"CIRCLE\""), "{schema}");
+        assert!(schema.contains("\"side_length\""), "{schema}");
+        assert!(!schema.contains("\"side\""), "{schema}");
+    }
+
     // =====================================================================
     // Simple enum rename tests
     // =====================================================================
@@ -0,0 +1,130 @@
+//! Derived schemas put recursive and repeated nested types under a root
+//! `$defs` and point at them with `$ref`; `Schema::inline` expands them again.
+
+use rstructor::{Instructor, SchemaType};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+#[llm(description = "A comment and its replies")]
+struct Comment {
+    text: String,
+    replies: Vec<Comment>,
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+struct Address {
+    street: String,
+    city: String,
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+struct Thread {
+    root: Comment,
+    pinned: Option<Comment>,
+    home: Address,
+    #[llm(description = "Where the author works")]
+    work: Address,
+    past: Vec<Address>,
+    title: String,
+}
+
+/// Every `$ref` in `value`.
+fn refs(value: &Value) -> Vec<String> {
+    match value {
+        Value::Object(obj) => obj
+            .iter()
+            .flat_map(|(key, child)| match (key.as_str(), child) {
+                ("$ref", Value::String(reference)) => vec![reference.clone()],
+                _ => refs(child),
+            })
+            .collect(),
+        Value::Array(items) => items.iter().flat_map(refs).collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[test]
+fn recursive_types_embedded_in_another_type_keep_resolvable_refs() {
+    let schema = Thread::schema().to_json();
+    let props = &schema["properties"];
+
+    assert_eq!(props["root"]["$ref"], "#/$defs/Comment");
+    assert_eq!(props["pinned"]["$ref"], "#/$defs/Comment");
+    assert!(props["root"].get("$defs").is_none());
+    assert_eq!(
+        schema["$defs"]["Comment"]["properties"]["replies"]["items"]["$ref"],
+        "#/$defs/Comment"
+    );
+    for reference in refs(&schema) {
+        let name = reference.strip_prefix("#/$defs/").unwrap();
+        assert!(
+            schema["$defs"].get(name).is_some(),
+            "{reference} does not resolve"
+        );
+    }
+}
+
+#[test]
+fn repeated_types_are_defined_once() {
+    let schema = Thread::schema().to_json();
+    let props = &schema["properties"];
+
+    assert_eq!(props["home"], json!({ "$ref": "#/$defs/Address" }));
+    assert_eq!(
+        props["work"],
+        json!({ "$ref": "#/$defs/Address", "description": "Where the author works" })
+    );
+    assert_eq!(props["past"]["items"]["$ref"], "#/$defs/Address");
+    assert_eq!(
+        schema["$defs"]["Address"]["properties"]["city"]["type"],
+        "string"
+    );
+    assert_eq!(props["title"]["type"], "string");
+    assert_eq!(
+        schema.to_string().matches("\"street\":").count(),
+        1,
+        "Address should be spelled out once"
+    );
+}
+
+#[test]
+fn types_used_once_stay_inline() {
+    let schema = Address::schema().to_json();
+    assert!(schema.get("$defs").is_none());
+    assert_eq!(schema["properties"]["street"]["type"], "string");
+}
+
+#[test]
+fn inline_expands_everything_but_cycles() {
+    let inlined = Thread::schema().inline().to_json();
+    let props = &inlined["properties"];
+
+    assert_eq!(props["home"]["properties"]["city"]["type"], "string");
+    assert_eq!(props["work"]["description"], "Where the author works");
+    assert_eq!(props["past"]["items"]["title"], "Address");
+    assert_eq!(props["root"]["title"], "Comment");
+    assert_eq!(
+        props["root"]["properties"]["replies"]["items"]["$ref"],
+        "#/$defs/Comment"
+    );
+
+    let defs: Vec<&String> = inlined["$defs"].as_object().unwrap().keys().collect();
+    assert_eq!(
+        defs,
+        ["Comment"],
+        "only the recursive type keeps a definition"
+    );
+}
+
+#[test]
+fn inline_of_a_recursive_root_keeps_its_own_definition() {
+    let inlined = Comment::schema().inline().to_json();
+    assert_eq!(inlined["title"], "Comment");
+    assert!(inlined.get("$ref").is_none());
+    assert_eq!(
+        inlined["properties"]["replies"]["items"]["$ref"],
+        "#/$defs/Comment"
+    );
+    assert!(inlined["$defs"]["Comment"].is_object());
+}
@@ -0,0 +1,182 @@
+//! Parameter sweeps over real clients against a local mock server: each
+//! combination is configured on the client, sampled with retries disabled,
+//! and scored by validity and cost.
+#![cfg(all(feature = "openai", feature = "anthropic"))]
+
+use std::sync::{Arc, Mutex};
+
+use rstructor::{
+    AnthropicClient, AnyClient, Instructor, OpenAIClient, OutputStrategy, RStructorError,
+    SweepGrid, ThinkingLevel,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+#[llm(validate = "validate_invoice")]
+struct Invoice {
+    total: f64,
+}
+
+fn validate_invoice(invoice: &Invoice) -> rstructor::Result<()> {
+    if invoice.total < 0.0 {
+        return Err(RStructorError::ValidationError(
+            "total must not be negative".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Serve `reply(request body)` on `path`, recording each request body.
+async fn serve(
+    server: &mut mockito::Server,
+    path: &str,
+    reply: impl Fn(&Value) -> Value + Send + Sync + 'static,
+) -> Arc<Mutex<Vec<Value>>> {
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let sink = requests.clone();
+    server
+        .mock("POST", path)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body_from_request(move |request| {
+            let body: Value = serde_json::from_slice(request.body().unwrap()).unwrap();
+            let response = reply(&body);
+            sink.lock().unwrap().push(body);
+            response.to_string().into_bytes()
+        })
+        .create_async()
+        .await;
+    requests
+}
+
+#[tokio::test]
+async fn openai_temperatures_are_scored_by_validity_and_cost() {
+    let mut server = mockito::Server::new_async().await;
+    // Valid at temperature 0, invalid (negative total) above it.
+    let requests = serve(&mut server, "/chat/completions", |body| {
+        let total = if body["temperature"].as_f64() == Some(0.0) {
+            12.5
+        } else {
+            -1.0
+        };
+        json!({
+            "model": "gpt-4o-mini",
+            "choices": [{
+                "message": { "role": "assistant", "content": json!({ "total": total }).to_string() },
+                "finish_reason": "stop"
+            }],
+            "usage": { "prompt_tokens": 100, "completion_tokens": 10, "total_tokens": 110 }
+        })
+    })
+    .await;
+    let client: AnyClient = OpenAIClient::new("test-key")
+        .unwrap()
+        .base_url(server.url())
+        .model("gpt-4o-mini")
+        .into();
+    let grid = SweepGrid::new()
+        .temperatures([0.0, 1.0])
+        .samples(2)
+        .pricing(1.0, 10.0);
+
+    let report = rstructor::sweep::<Invoice>(&client, "Extract the invoice", &grid)
+        .await
+        .unwrap();
+
+    assert_eq!(report.results.len(), 2);
+    let cold = &report.results[0];
+    assert_eq!(cold.settings.temperature, Some(0.0));
+    assert_eq!((cold.samples, cold.valid), (2, 2));
+    assert_eq!(cold.validity_rate(), 1.0);
+    let usage = cold.usage.as_ref().unwrap();
+    assert_eq!((usage.input_tokens, usage.output_tokens), (200, 20));
+    // 200 input tokens at $1/M plus 20 output tokens at $10/M.
+    assert!((cold.cost_usd.unwrap() - 0.0004).abs() < 1e-12);
+    assert!((cold.cost_per_valid_usd().unwrap() - 0.0002).abs() < 1e-12);
+
+    let hot = &report.results[1];
+    assert_eq!((hot.samples, hot.valid), (2, 0));
+    assert!(hot.usage.is_none() && hot.cost_usd.is_none());
+    assert!(hot.last_error.as_deref().unwrap().contains("negative"));
+
+    assert_eq!(report.best().unwrap().settings.temperature, Some(0.0));
+    // Retries are disabled, so every sample is exactly one request.
+    assert_eq!(requests.lock().unwrap().len(), 4);
+}
+
+#[tokio::test]
+async fn anthropic_output_strategies_and_thinking_levels_are_combined() {
+    let mut server = mockito::Server::new_async().await;
+    let requests = serve(&mut server, "/messages", |body| {
+        let content = if body.get("tools").is_some() {
+            json!([{
+                "type": "tool_use",
+                "id": "toolu_1",
+                "name": "structured_output",
+                "input": { "total": 3.0 }
+            }])
+        } else {
+            json!([{ "type": "text", "text": r#"{"total": 3.0}"# }])
+        };
+        json!({
+            "content": content,
+            "stop_reason": "end_turn",
+            "usage": { "input_tokens": 50, "output_tokens": 5 }
+        })
+    })
+    .await;
+    let client: AnyClient = AnthropicClient::new("test-key")
+        .unwrap()
+        .base_url(server.url())
+        .into();
+    let grid = SweepGrid::new()
+        .thinking_levels([ThinkingLevel::Off])
+        .output_strategies([
+            OutputStrategy::JsonSchema,
+            OutputStrategy::ToolCalling,
+            OutputStrategy::JsonMode,
+        ])
+        .samples(1);
+
+    let report = rstructor::sweep::<Invoice>(&client, "Extract the invoice", &grid)
+        .await
+        .unwrap();
+
+    let strategies: Vec<_> = report
+        .results
+        .iter()
+        .map(|r| (r.settings.output_strategy, r.valid, r.skipped.is_some()))
+        .collect();
+    assert_eq!(
+        strategies,
+        vec![
+            (Some(OutputStrategy::JsonSchema), 1, false),
+            (Some(OutputStrategy::ToolCalling), 1, false),
+            (Some(OutputStrategy::JsonMode), 0, true),
+        ]
+    );
+    assert_eq!(report.results[2].samples, 0);
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[0].get("tools").is_none());
+    assert_eq!(requests[1]["tools"][0]["name"], "structured_output");
+}
+
+#[tokio::test]
+async fn unsupported_settings_are_skipped_and_zero_samples_rejected() {
+    let client: AnyClient = OpenAIClient::new("test-key").unwrap().into();
+    let grid = SweepGrid::new().output_strategies([OutputStrategy::ToolCalling]);
+    let report = rstructor::sweep::<Invoice>(&client, "p", &grid)
+        .await
+        .unwrap();
+    assert_eq!(report.results.len(), 1);
+    assert!(report.results[0].skipped.is_some());
+    assert!(report.best().is_none());
+
+    let err = rstructor::sweep::<Invoice>(&client, "p", &SweepGrid::new().samples(0))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, RStructorError::ConfigError(_)), "{err:?}");
+}
@@ -0,0 +1,183 @@
+//! `.system_prompt(..)` is sent in each provider's system role, separate from
+//! the user prompt, for structured and text requests alike.
+#![cfg(all(
+    feature = "openai",
+    feature = "anthropic",
+    feature = "gemini",
+    feature = "ollama"
+))]
+
+use std::sync::{Arc, Mutex};
+
+use rstructor::{Instructor, LLMClient};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+#[derive(Instructor, Serialize, Deserialize, Debug, PartialEq)]
+struct Code {
+    icd10: String,
+}
+
+const SYSTEM: &str = "You are a medical coder. Use ICD-10 codes.";
+const PROMPT: &str = "Patient presents with type 2 diabetes.";
+const REPLY: &str = r#"{"icd10": "E11.9"}"#;
+
+/// Serve `reply` for every request on `path`, recording each request body.
+async fn serve(
+    server: &mut mockito::Server,
+    path: impl Into<mockito::Matcher>,
+    reply: Value,
+) -> Arc<Mutex<Vec<Value>>> {
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let sink = requests.clone();
+    server
+        .mock("POST", path)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body_from_request(move |request| {
+            let body = serde_json::from_slice(request.body().unwrap()).unwrap();
+            sink.lock().unwrap().push(body);
+            reply.to_string().into_bytes()
+        })
+        .create_async()
+        .await;
+    requests
+}
+
+#[tokio::test]
+async fn openai_sends_a_leading_system_message() {
+    let mut server = mockito::Server::new_async().await;
+    let reply = json!({
+        "choices": [{
+            "message": { "role": "assistant", "content": REPLY },
+            "finish_reason": "stop"
+        }]
+    });
+    let requests = serve(&mut server, "/chat/completions", reply).await;
+    let client = rstructor::OpenAIClient::new("test-key")
+        .unwrap()
+        .base_url(server.url())
+        .model("gpt-4o-mini")
+        .system_prompt(SYSTEM);
+
+    let code: Code = client.materialize(PROMPT).await.unwrap();
+    assert_eq!(code.icd10, "E11.9");
+    client.generate(PROMPT).await.unwrap();
+
+    for body in requests.lock().unwrap().iter() {
+        assert_eq!(
+            body["messages"][0],
+            json!({ "role": "system", "content": SYSTEM })
+        );
+        assert_eq!(body["messages"][1]["role"], "user");
+        assert_eq!(body["messages"][1]["content"], PROMPT);
+    }
+}
+
+#[tokio::test]
+async fn no_system_message_is_sent_by_default() {
+    let mut server = mockito::Server::new_async().await;
+    let reply = json!({
+        "choices": [{
+            "message": { "role": "assistant", "content": REPLY },
+            "finish_reason": "stop"
+        }]
+    });
+    let requests = serve(&mut server, "/chat/completions", reply).await;
+    let client = rstructor::OpenAIClient::new("test-key")
+        .unwrap()
+        .base_url(server.url())
+        .model("gpt-4o-mini");
+
+    let _: Code = client.materialize(PROMPT).await.unwrap();
+    let body = &requests.lock().unwrap()[0];
+    assert_eq!(body["messages"].as_array().unwrap().len(), 1);
+    assert_eq!(body["messages"][0]["role"], "user");
+}
+
+#[tokio::test]
+async fn anthropic_sends_the_top_level_system_field() {
+    let mut server = mockito::Server::new_async().await;
+    let reply = json!({
+        "content": [{ "type": "text", "text": REPLY }],
+        "stop_reason": "end_turn"
+    });
+    let requests = serve(&mut server, "/messages", reply).await;
+    let client = rstructor::AnthropicClient::new("test-key")
+        .unwrap()
+        .base_url(server.url())
+        .system_prompt(SYSTEM);
+
+    let _: Code = client.materialize(PROMPT).await.unwrap();
+    client.generate(PROMPT).await.unwrap();
+
+    for body in requests.lock().unwrap().iter() {
+        assert_eq!(body["system"], SYSTEM);
+        assert_eq!(body["messages"].as_array().unwrap().len(), 1);
+        assert_eq!(body["messages"][0]["role"], "user");
+    }
+}
+
+#[tokio::test]
+async fn gemini_sends_a_system_instruction() {
+    let mut server = mockito::Server::new_async().await;
+    let reply = json!({
+        "candidates": [{
+            "content": { "parts": [{ "text": REPLY }] },
+            "finishReason": "STOP"
+        }]
+    });
+    let requests = serve(
+        &mut server,
+        mockito::Matcher::Regex(r"^/models/.+:generateContent".to_string()),
+        reply,
+    )
+    .await;
+    let client = rstructor::GeminiClient::new("test-key")
+        .unwrap()
+        .base_url(server.url())
+        .system_prompt(SYSTEM);
+
+    let _: Code = client.materialize(PROMPT).await.unwrap();
+    client.generate(PROMPT).await.unwrap();
+
+    for body in requests.lock().unwrap().iter() {
+        assert_eq!(
+            body["systemInstruction"],
+            json!({ "parts": [{ "text": SYSTEM }] })
+        );
+        assert_eq!(body["contents"].as_array().unwrap().len(), 1);
+    }
+}
+
+#[tokio::test]
+async fn ollama_json_mode_keeps_the_system_prompt_first() {
+    let mut server = mockito::Server::new_async().await;
+    let reply = json!({
+        "model": "llama3.2",
+        "message": { "role": "assistant", "content": REPLY },
+        "done": true
+    });
+    let requests = serve(&mut server, "/api/chat", reply).await;
+    let client = rstructor::OllamaClient::new()
+        .base_url(server.url())
+        .output_strategy(rstructor::OutputStrategy::JsonMode)
+        .system_prompt(SYSTEM);
+
+    let _: Code = client.materialize(PROMPT).await.unwrap();
+
+    let body = &requests.lock().unwrap()[0];
+    let messages = body["messages"].as_array().unwrap();
+    let roles: Vec<_> = messages
+        .iter()
+        .map(|m| m["role"].as_str().unwrap())
+        .collect();
+    assert_eq!(roles, ["system", "system", "user"]);
+    assert_eq!(messages[0]["content"], SYSTEM);
+    assert!(
+        messages[1]["content"]
+            .as_str()
+            .unwrap()
+            .contains("JSON Schema")
+    );
+}
@@ -0,0 +1,133 @@
+//! Per-schema telemetry recorded by the real retry loop, driven over a local
+//! mock HTTP server. Telemetry is process-wide, so each test uses its own
+//! schema type.
+#![cfg(feature = "openai")]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use rstructor::{Instructor, LLMClient, OpenAIClient, RStructorError, schema_telemetry};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+#[llm(validate = "validate_invoice")]
+struct Invoice {
+    total: f64,
+}
+
+fn validate_invoice(invoice: &Invoice) -> rstructor::Result<()> {
+    if invoice.total < 0.0 {
+        return Err(RStructorError::ValidationError(
+            "total must not be negative".into(),
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+struct Receipt {
+    merchant: String,
+}
+
+fn chat_completion(content: &str) -> String {
+    json!({
+        "choices": [{
+            "message": { "role": "assistant", "content": content },
+            "finish_reason": "stop",
+        }]
+    })
+    .to_string()
+}
+
+fn client(server: &mockito::Server) -> OpenAIClient {
+    OpenAIClient::new("test-key")
+        .unwrap()
+        .base_url(server.url())
+        .model("gpt-4o-mini")
+}
+
+#[tokio::test]
+async fn records_retries_and_validation_failures_per_schema() {
+    let mut server = mockito::Server::new_async().await;
+    let calls = AtomicUsize::new(0);
+    let _m = server
+        .mock("POST", "/chat/completions")
+        .with_status(200)
+        .with_body_from_request(move |_| {
+            let reply = match calls.fetch_add(1, Ordering::SeqCst) {
+                0 => r#"{"total": -5}"#,
+                _ => r#"{"total": 12.5}"#,
+            };
+            chat_completion(reply).into_bytes()
+        })
+        .create_async()
+        .await;
+
+    let records = Arc::new(Mutex::new(Vec::new()));
+    let sink = records.clone();
+    rstructor::set_telemetry_hook(move |record| {
+        if record.schema_name == "Invoice" {
+            sink.lock().unwrap().push(record.clone());
+        }
+    });
+
+    let invoice: Invoice = client(&server)
+        .max_retries(2)
+        .materialize("invoice")
+        .await
+        .unwrap();
+    assert_eq!(invoice.total, 12.5);
+    rstructor::clear_telemetry_hook();
+
+    let totals = schema_telemetry()
+        .into_iter()
+        .find(|t| t.schema_name == "Invoice")
+        .unwrap();
+    assert_eq!(totals.calls, 1);
+    assert_eq!(totals.validation_failures, 1);
+    assert_eq!(totals.retries, 1);
+    assert_eq!(totals.failures, 0);
+    assert_eq!(totals.fingerprint.len(), 16);
+
+    let records = records.lock().unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].attempts, 2);
+    assert!(records[0].succeeded && !records[0].coerced);
+}
+
+#[tokio::test]
+async fn coerced_replies_and_errors_are_counted() {
+    let mut server = mockito::Server::new_async().await;
+    let calls = AtomicUsize::new(0);
+    let _m = server
+        .mock("POST", "/chat/completions")
+        .with_status(200)
+        .with_body_from_request(move |_| {
+            let reply = match calls.fetch_add(1, Ordering::SeqCst) {
+                // Extracting from a fence is not a coercion
+                0 => "```json\n{\"merchant\": \"Cafe\"}\n```",
+                1 => r#"{"merchant": 7}"#,
+                _ => "not json at all",
+            };
+            chat_completion(reply).into_bytes()
+        })
+        .create_async()
+        .await;
+
+    let client = client(&server).no_retries();
+    client.materialize::<Receipt>("receipt").await.unwrap();
+    let receipt = client.materialize::<Receipt>("receipt").await.unwrap();
+    assert_eq!(receipt.merchant, "7");
+    client.materialize::<Receipt>("receipt").await.unwrap_err();
+
+    let totals = schema_telemetry()
+        .into_iter()
+        .find(|t| t.schema_name == "Receipt")
+        .unwrap();
+    assert_eq!(totals.calls, 3);
+    assert_eq!(totals.coercions, 1);
+    assert_eq!(totals.failures, 1);
+    assert_eq!(totals.validation_failures, 1);
+    assert_eq!(totals.retries, 0);
+}
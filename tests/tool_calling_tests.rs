@@ -48,6 +48,65 @@ async fn unknown_args_error_is_surfaced() {
     assert!(err.is_err());
 }
 
+#[derive(Instructor, Serialize, Deserialize)]
+struct GreetArgs {
+    #[serde(rename = "nom complet")]
+    full_name: String,
+}
+
+#[tokio::test]
+async fn unsafe_arg_names_are_renamed_and_restored() {
+    let tool = FnTool::new("greet", "Greet someone", |args: GreetArgs| async move {
+        Ok(json!(format!("Bonjour {}", args.full_name)))
+    });
+    let schema = tool.parameters_schema();
+    assert!(schema["properties"].get("nom complet").is_none());
+    assert_eq!(schema["required"], json!(["nom_complet"]));
+    assert_eq!(
+        tool.parameters_schema_gemini().unwrap()["properties"]["nom_complet"]["type"],
+        "string"
+    );
+
+    let result = tool
+        .invoke_json(json!({ "nom_complet": "Zoé" }))
+        .await
+        .unwrap();
+    assert_eq!(result, json!("Bonjour Zoé"));
+}
+
+#[derive(Instructor, Serialize, Deserialize)]
+struct Contact {
+    #[serde(rename = "nom complet")]
+    full_name: String,
+}
+
+#[derive(Instructor, Serialize, Deserialize)]
+struct ContactsArgs {
+    home: Contact,
+    work: Contact,
+}
+
+#[tokio::test]
+async fn unsafe_names_in_a_repeated_nested_type_are_restored_everywhere() {
+    use rstructor::SchemaType;
+    assert!(!ContactsArgs::schema().check_property_names().is_empty());
+
+    let tool = FnTool::new("pair", "Pair contacts", |args: ContactsArgs| async move {
+        Ok(json!([args.home.full_name, args.work.full_name]))
+    });
+    let schema = tool.parameters_schema().to_string();
+    assert!(!schema.contains("nom complet"));
+
+    let result = tool
+        .invoke_json(json!({
+            "home": { "nom_complet": "Zoé" },
+            "work": { "nom_complet": "Ada" }
+        }))
+        .await
+        .unwrap();
+    assert_eq!(result, json!(["Zoé", "Ada"]));
+}
+
 // ---- Live agentic-loop tests (one per provider) ----
 
 #[derive(Instructor, Serialize, Deserialize)]
@@ -1,21 +1,22 @@
 //! Recursion coverage for the public `Instructor::validate()` API.
 //!
 //! These tests exercise the container `Instructor` impls (`Box<T>`,
-//! `Vec<T>`, `Option<T>`, `HashMap<String, V>`) and the derive-generated
+//! `Vec<T>`, `Option<T>`, `HashMap<String, V>`, `BTreeMap<String, V>`,
+//! `HashSet<T>`, `BTreeSet<T>`) and the derive-generated
 //! children-then-parent validation order. They complement
 //! `nested_validation_tests.rs` (direct/Option/Vec recursion) by covering
-//! `Box`, string-keyed maps, combination containers, ordering, error-message
-//! identity, first-failure short-circuit, `None` deep short-circuit, and the
+//! `Box`, string-keyed maps, combination containers, ordering, error paths,
+//! first-failure short-circuit, `None` deep short-circuit, and the
 //! primitive `Probe` fallback path.
 //!
 //! Maps the "validation" rows of the coverage-gap report to real assertions
 //! grounded in `src/model/instructor.rs` (the container impls run values in
-//! `self.values()` order, which is non-deterministic, so map tests assert
-//! err/ok only — never which value failed) and `rstructor_derive/src/lib.rs`
+//! map order, which is non-deterministic, so map tests with several bad values
+//! assert err/ok only — never which value failed) and `rstructor_derive/src/lib.rs`
 //! (struct field probes run in declaration order, before the container's own
 //! `#[llm(validate)]` function).
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
 use rstructor::{Instructor, RStructorError, Result};
 use serde::{Deserialize, Serialize};
@@ -65,7 +66,10 @@ fn box_field_recurses_into_child() {
         boxed: Box::new(child(-1)),
     };
     let err = bad.validate().expect_err("boxed child -1 must fail");
-    assert_eq!(validation_message(err), "child.value must be >= 0, got -1");
+    assert_eq!(
+        validation_message(err),
+        "`boxed`: child.value must be >= 0, got -1"
+    );
 
     let good = BoxParent {
         boxed: Box::new(child(1)),
@@ -84,9 +88,7 @@ struct MapParent {
 
 #[test]
 fn hashmap_value_recursion_one_bad_errors() {
-    // One bad value among several -> error. Iteration order over the map is
-    // non-deterministic, so we only assert that it is the child message; we do
-    // not assert which key surfaced.
+    // One bad value among several -> error, reported under its key.
     let mut map = HashMap::new();
     map.insert("a".to_string(), child(0));
     map.insert("b".to_string(), child(-3));
@@ -94,7 +96,10 @@ fn hashmap_value_recursion_one_bad_errors() {
     let err = MapParent { map }
         .validate()
         .expect_err("map with a -3 value must fail");
-    assert_eq!(validation_message(err), "child.value must be >= 0, got -3");
+    assert_eq!(
+        validation_message(err),
+        r#"`map["b"]`: child.value must be >= 0, got -3"#
+    );
 }
 
 #[test]
@@ -139,7 +144,10 @@ fn vec_of_option_reaches_innermost_validator() {
     let err = p
         .validate()
         .expect_err("Vec<Option<Child>> with -2 must fail");
-    assert_eq!(validation_message(err), "child.value must be >= 0, got -2");
+    assert_eq!(
+        validation_message(err),
+        "`vec_opt[2]`: child.value must be >= 0, got -2"
+    );
 
     // All-good (with a None hole) -> ok.
     let mut ok = empty_combo();
@@ -208,7 +216,10 @@ fn child_failure_surfaces_before_parent_failure() {
         name: "bad".to_string(),
     };
     let err = p.validate().expect_err("invalid child must fail");
-    assert_eq!(validation_message(err), "child.value must be >= 0, got -1");
+    assert_eq!(
+        validation_message(err),
+        "`child`: child.value must be >= 0, got -1"
+    );
 }
 
 #[test]
@@ -232,8 +243,8 @@ fn order_parent_all_valid_ok() {
 }
 
 // ---------------------------------------------------------------------------
-// Nested error-message identity through recursion: the message that surfaces is
-// the exact, unwrapped child message (no enclosing context is prepended).
+// Nested error paths: the child's message surfaces prefixed with the path to
+// the failing value, and bounds errors have their field path extended.
 // ---------------------------------------------------------------------------
 
 #[derive(Instructor, Serialize, Deserialize, Debug)]
@@ -244,14 +255,77 @@ struct IdentityParent {
 }
 
 #[test]
-fn nested_error_message_is_unmodified_child_message() {
+fn nested_error_message_names_the_path_to_the_child() {
     let p = IdentityParent {
         direct: child(0),
         maybe: None,
         list: vec![child(1), child(2), child(-9)],
     };
     let err = p.validate().expect_err("a -9 in the list must fail");
-    assert_eq!(validation_message(err), "child.value must be >= 0, got -9");
+    assert_eq!(
+        validation_message(err),
+        "`list[2]`: child.value must be >= 0, got -9"
+    );
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Reading {
+    #[llm(minimum = 0)]
+    level_pct: i32,
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+struct Sensor {
+    readings: Vec<Reading>,
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+enum Probe {
+    Pair(Child, Child),
+    Single(Sensor),
+    Named { sensor: Box<Sensor> },
+}
+
+fn sensor(levels: &[i32]) -> Sensor {
+    Sensor {
+        readings: levels
+            .iter()
+            .map(|&level_pct| Reading { level_pct })
+            .collect(),
+    }
+}
+
+#[test]
+fn bounds_errors_extend_their_field_path() {
+    let probe = Probe::Named {
+        sensor: Box::new(sensor(&[1, -4])),
+    };
+    let err = probe.validate().expect_err("a -4 reading must fail");
+    assert_eq!(
+        validation_message(err),
+        "`sensor.readings[1].levelPct` must be at least 0, got -4"
+    );
+
+    // A newtype variant adds no segment of its own.
+    let err = Probe::Single(sensor(&[-2]))
+        .validate()
+        .expect_err("a -2 reading must fail");
+    assert_eq!(
+        validation_message(err),
+        "`readings[0].levelPct` must be at least 0, got -2"
+    );
+}
+
+#[test]
+fn positional_fields_are_named_by_index() {
+    let err = Probe::Pair(child(0), child(-1))
+        .validate()
+        .expect_err("second child must fail");
+    assert_eq!(
+        validation_message(err),
+        "`[1]`: child.value must be >= 0, got -1"
+    );
 }
 
 // ---------------------------------------------------------------------------
@@ -329,3 +403,75 @@ fn primitive_probe_fallback_is_noop() {
     };
     assert!(p.validate().is_ok());
 }
+
+// ---------------------------------------------------------------------------
+// Wrappers validated directly, ordered maps, and sets
+// ---------------------------------------------------------------------------
+
+#[test]
+fn wrappers_validate_their_contents_when_called_directly() {
+    assert!(vec![child(1), child(-1)].validate().is_err());
+    assert!(Some(child(-1)).validate().is_err());
+    assert!(None::<Child>.validate().is_ok());
+    assert!(Box::new(child(-1)).validate().is_err());
+    assert!(Some(vec![Box::new(child(2))]).validate().is_ok());
+}
+
+#[test]
+fn btreemap_values_are_validated() {
+    let map = BTreeMap::from([("a".to_string(), child(0)), ("b".to_string(), child(-2))]);
+    let err = map.validate().expect_err("a -2 value must fail");
+    assert_eq!(
+        validation_message(err),
+        r#"`["b"]`: child.value must be >= 0, got -2"#
+    );
+}
+
+/// Set element with a validator: the code must not be empty.
+#[derive(Instructor, Serialize, Deserialize, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[llm(validate = "validate_code")]
+struct Code {
+    code: String,
+}
+
+fn validate_code(c: &Code) -> Result<()> {
+    if c.code.is_empty() {
+        return Err(RStructorError::ValidationError(
+            "code must not be empty".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Instructor, Serialize, Deserialize, Debug)]
+struct SetParent {
+    ordered: BTreeSet<Code>,
+    unordered: HashSet<Code>,
+}
+
+fn code(c: &str) -> Code {
+    Code {
+        code: c.to_string(),
+    }
+}
+
+#[test]
+fn set_elements_are_validated() {
+    let bad_ordered = SetParent {
+        ordered: BTreeSet::from([code("a"), code("")]),
+        unordered: HashSet::new(),
+    };
+    assert!(bad_ordered.validate().is_err());
+
+    let bad_unordered = SetParent {
+        ordered: BTreeSet::new(),
+        unordered: HashSet::from([code("")]),
+    };
+    assert!(bad_unordered.validate().is_err());
+
+    let good = SetParent {
+        ordered: BTreeSet::from([code("a")]),
+        unordered: HashSet::from([code("b")]),
+    };
+    assert!(good.validate().is_ok());
+}
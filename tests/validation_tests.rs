@@ -115,4 +115,209 @@ mod validation_tests {
             panic!("Expected ValidationError, got {:?}", err);
         }
     }
+
+    #[derive(Instructor, Serialize, Deserialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    struct Reading {
+        #[llm(minimum = -40, maximum = 85)]
+        temperature_c: i32,
+        #[llm(exclusive_minimum = 0, exclusive_maximum = 1)]
+        humidity_ratio: f64,
+        #[llm(multiple_of = 0.5)]
+        step: Option<f64>,
+    }
+
+    fn reading() -> Reading {
+        Reading {
+            temperature_c: 20,
+            humidity_ratio: 0.4,
+            step: Some(1.5),
+        }
+    }
+
+    fn numeric_error(reading: Reading) -> String {
+        match rstructor::Instructor::validate(&reading).unwrap_err() {
+            RStructorError::ValidationError(msg) => msg,
+            err => panic!("Expected ValidationError, got {:?}", err),
+        }
+    }
+
+    #[test]
+    fn numeric_constraints_accept_values_in_range() {
+        assert!(rstructor::Instructor::validate(&reading()).is_ok());
+        let unset = Reading {
+            step: None,
+            ..reading()
+        };
+        assert!(rstructor::Instructor::validate(&unset).is_ok());
+    }
+
+    #[test]
+    fn numeric_constraints_report_the_schema_field_name() {
+        let cold = Reading {
+            temperature_c: -41,
+            ..reading()
+        };
+        assert_eq!(
+            numeric_error(cold),
+            "`temperatureC` must be at least -40, got -41"
+        );
+
+        let saturated = Reading {
+            humidity_ratio: 1.0,
+            ..reading()
+        };
+        assert_eq!(
+            numeric_error(saturated),
+            "`humidityRatio` must be less than 1, got 1"
+        );
+
+        let off_step = Reading {
+            step: Some(0.75),
+            ..reading()
+        };
+        assert_eq!(
+            numeric_error(off_step),
+            "`step` must be a multiple of 0.5, got 0.75"
+        );
+    }
+
+    #[derive(Instructor, Serialize, Deserialize, Debug)]
+    struct Contact {
+        #[llm(min_length = 1, max_length = 5)]
+        name: String,
+        #[llm(pattern = "^[A-Z]{2}$")]
+        country: String,
+        #[llm(format = "email")]
+        email: Option<String>,
+        #[llm(format = "date")]
+        joined: String,
+    }
+
+    fn contact() -> Contact {
+        Contact {
+            name: "Zoë".into(),
+            country: "GB".into(),
+            email: Some("ada@example.com".into()),
+            joined: "2024-02-29".into(),
+        }
+    }
+
+    fn string_error(contact: Contact) -> String {
+        match rstructor::Instructor::validate(&contact).unwrap_err() {
+            RStructorError::ValidationError(msg) => msg,
+            err => panic!("Expected ValidationError, got {:?}", err),
+        }
+    }
+
+    #[test]
+    fn string_constraints_accept_conforming_values() {
+        assert!(rstructor::Instructor::validate(&contact()).is_ok());
+        let unset = Contact {
+            email: None,
+            ..contact()
+        };
+        assert!(rstructor::Instructor::validate(&unset).is_ok());
+    }
+
+    #[test]
+    fn string_constraints_report_each_violation() {
+        let empty = Contact {
+            name: String::new(),
+            ..contact()
+        };
+        assert_eq!(
+            string_error(empty),
+            "`name` must be at least 1 characters long, got \"\""
+        );
+
+        // Lengths count characters, not bytes
+        let long = Contact {
+            name: "Zoëëëë".into(),
+            ..contact()
+        };
+        assert_eq!(
+            string_error(long),
+            "`name` must be at most 5 characters long, got \"Zoëëëë\""
+        );
+
+        let lowercase = Contact {
+            country: "gb".into(),
+            ..contact()
+        };
+        assert_eq!(
+            string_error(lowercase),
+            "`country` must be a match for the pattern `^[A-Z]{2}$`, got \"gb\""
+        );
+
+        let not_email = Contact {
+            email: Some("ada at example".into()),
+            ..contact()
+        };
+        assert_eq!(
+            string_error(not_email),
+            "`email` must be a valid email, got \"ada at example\""
+        );
+
+        let not_date = Contact {
+            joined: "2024-13-01".into(),
+            ..contact()
+        };
+        assert_eq!(
+            string_error(not_date),
+            "`joined` must be a valid date, got \"2024-13-01\""
+        );
+    }
+
+    #[derive(Instructor, Serialize, Deserialize, Debug)]
+    struct Tagged {
+        #[llm(min_items = 3, max_items = 5, unique_items)]
+        tags: Vec<String>,
+        #[llm(max_items = 1)]
+        aliases: Option<Vec<String>>,
+    }
+
+    fn tagged(tags: &[&str]) -> Tagged {
+        Tagged {
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            aliases: None,
+        }
+    }
+
+    fn array_error(value: Tagged) -> String {
+        match rstructor::Instructor::validate(&value).unwrap_err() {
+            RStructorError::ValidationError(msg) => msg,
+            err => panic!("Expected ValidationError, got {:?}", err),
+        }
+    }
+
+    #[test]
+    fn array_constraints_accept_bounded_unique_lists() {
+        assert!(rstructor::Instructor::validate(&tagged(&["a", "b", "c"])).is_ok());
+    }
+
+    #[test]
+    fn array_constraints_report_each_violation() {
+        assert_eq!(
+            array_error(tagged(&["a", "b"])),
+            "`tags` must have at least 3 items, got 2"
+        );
+        assert_eq!(
+            array_error(tagged(&["a", "b", "c", "d", "e", "f"])),
+            "`tags` must have at most 5 items, got 6"
+        );
+        assert_eq!(
+            array_error(tagged(&["a", "b", "a"])),
+            "`tags` must have unique items, but item 2 repeats an earlier one"
+        );
+
+        let aliased = Tagged {
+            aliases: Some(vec!["x".into(), "y".into()]),
+            ..tagged(&["a", "b", "c"])
+        };
+        assert_eq!(
+            array_error(aliased),
+            "`aliases` must have at most 1 items, got 2"
+        );
+    }
 }
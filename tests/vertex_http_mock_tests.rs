@@ -0,0 +1,201 @@
+//! Drive the real `VertexAIClient` over a local mock HTTP server (`mockito`),
+//! covering the project/location endpoint and bearer-token auth. No Google
+//! Cloud account needed.
+#![cfg(feature = "vertex")]
+
+use rstructor::{
+    AnyClient, GeminiModel, Instructor, LLMClient, Provider, RStructorError, VertexAIClient,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Instructor, Serialize, Deserialize, Debug, PartialEq)]
+struct Movie {
+    title: String,
+    year: u16,
+}
+
+fn client(server: &mockito::Server) -> VertexAIClient {
+    VertexAIClient::with_access_token("my-project", "us-central1", "ya29.test")
+        .unwrap()
+        .base_url(server.url())
+        .no_retries()
+}
+
+fn reply(text: &str) -> String {
+    json!({
+        "candidates": [{
+            "content": { "role": "model", "parts": [{ "text": text }] },
+            "finishReason": "STOP"
+        }],
+        "usageMetadata": {
+            "promptTokenCount": 30,
+            "candidatesTokenCount": 8,
+            "totalTokenCount": 38
+        },
+        "modelVersion": "gemini-2.5-pro"
+    })
+    .to_string()
+}
+
+#[tokio::test]
+async fn materialize_calls_the_project_endpoint_with_a_bearer_token() {
+    let mut server = mockito::Server::new_async().await;
+    let m = server
+        .mock(
+            "POST",
+            "/v1/projects/my-project/locations/us-central1/publishers/google/models/gemini-2.5-pro:generateContent",
+        )
+        .match_header("authorization", "Bearer ya29.test")
+        .match_query(mockito::Matcher::Missing)
+        .match_body(mockito::Matcher::PartialJson(json!({
+            "systemInstruction": { "parts": [{ "text": "You review films." }] },
+            "contents": [{ "role": "user", "parts": [{ "text": "Alien" }] }],
+            "generation_config": {
+                "response_mime_type": "application/json",
+                "response_schema": { "type": "object", "required": ["title", "year"] }
+            }
+        })))
+        .with_status(200)
+        .with_body(reply(r#"{"title":"Alien","year":1979}"#))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = client(&server)
+        .model(GeminiModel::Gemini25Pro)
+        .system_prompt("You review films.");
+    let result = client
+        .materialize_with_metadata::<Movie>("Alien")
+        .await
+        .unwrap();
+    assert_eq!(
+        result.data,
+        Movie {
+            title: "Alien".into(),
+            year: 1979
+        }
+    );
+    let usage = result.usage.unwrap();
+    assert_eq!((usage.input_tokens, usage.output_tokens), (30, 8));
+    // Vertex AI serves the Gemini models
+    assert_eq!(client.model_id().provider, Provider::Gemini);
+    m.assert_async().await;
+}
+
+#[tokio::test]
+async fn generate_returns_text() {
+    let mut server = mockito::Server::new_async().await;
+    let m = server
+        .mock(
+            "POST",
+            mockito::Matcher::Regex(
+                r"^/v1/projects/my-project/locations/us-central1/publishers/google/models/.+:generateContent$"
+                    .to_string(),
+            ),
+        )
+        .match_header("authorization", "Bearer ya29.rotated")
+        .with_status(200)
+        .with_body(reply("Hello there"))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let text = client(&server)
+        .access_token("ya29.rotated")
+        .generate("Say hello")
+        .await
+        .unwrap();
+    assert_eq!(text, "Hello there");
+    m.assert_async().await;
+}
+
+#[tokio::test]
+async fn list_models_reads_publisher_models() {
+    let mut server = mockito::Server::new_async().await;
+    let m = server
+        .mock("GET", "/v1beta1/publishers/google/models")
+        .match_header("authorization", "Bearer ya29.test")
+        .match_header("x-goog-user-project", "my-project")
+        .with_status(200)
+        .with_body(
+            json!({
+                "publisherModels": [
+                    { "name": "publishers/google/models/gemini-2.5-flash" },
+                    { "name": "publishers/google/models/imagen-4.0-generate-001" }
+                ]
+            })
+            .to_string(),
+        )
+        .expect(1)
+        .create_async()
+        .await;
+
+    let models = client(&server).list_models().await.unwrap();
+    let ids: Vec<_> = models.iter().map(|m| m.id.as_str()).collect();
+    assert_eq!(ids, ["gemini-2.5-flash"]);
+    m.assert_async().await;
+}
+
+#[tokio::test]
+async fn expired_tokens_surface_as_authentication_errors() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock(
+            "POST",
+            mockito::Matcher::Regex(r":generateContent$".to_string()),
+        )
+        .with_status(401)
+        .with_body(
+            json!({
+                "error": {
+                    "code": 401,
+                    "message": "Request had invalid authentication credentials.",
+                    "status": "UNAUTHENTICATED"
+                }
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+
+    let err = client(&server)
+        .materialize::<Movie>("Alien")
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(
+            err.api_error_kind(),
+            Some(rstructor::ApiErrorKind::AuthenticationFailed)
+        ),
+        "{err:?}"
+    );
+}
+
+#[test]
+fn access_tokens_and_projects_are_required() {
+    let Err(err) = VertexAIClient::with_access_token("my-project", "global", "") else {
+        panic!("an empty token was accepted");
+    };
+    assert!(
+        matches!(
+            err.api_error_kind(),
+            Some(rstructor::ApiErrorKind::AuthenticationFailed)
+        ),
+        "{err:?}"
+    );
+    assert!(matches!(
+        VertexAIClient::with_access_token("", "global", "ya29.test"),
+        Err(RStructorError::ConfigError(_))
+    ));
+}
+
+#[test]
+fn vertex_is_selected_by_name() {
+    assert_eq!("vertex-ai".parse::<Provider>().unwrap(), Provider::VertexAI);
+    let client: AnyClient = VertexAIClient::with_access_token("my-project", "global", "ya29.test")
+        .unwrap()
+        .into();
+    assert_eq!(client.provider(), Provider::VertexAI);
+    assert!(client.capabilities().vision);
+}
@@ -0,0 +1,84 @@
+//! Offline tests for `WebhookClient`: a `MockClient` underneath, a mockito
+//! server as the webhook endpoint.
+
+#![cfg(all(feature = "webhook", feature = "mock"))]
+
+use rstructor::{LLMClient, MockClient, TokenUsage, WebhookClient};
+
+#[tokio::test]
+async fn successful_calls_are_batched_with_usage_and_cost() {
+    let mut server = mockito::Server::new_async().await;
+    let hook = server
+        .mock("POST", "/usage")
+        .match_body(mockito::Matcher::AllOf(vec![
+            mockito::Matcher::Regex(r#""operation":"generate""#.into()),
+            mockito::Matcher::Regex(r#""service":"billing""#.into()),
+            mockito::Matcher::Regex(r#""model":"mock-model""#.into()),
+            mockito::Matcher::Regex(r#""input_tokens":1000"#.into()),
+            mockito::Matcher::Regex(r#""cost_usd":0.003"#.into()),
+        ]))
+        .with_status(200)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let inner = MockClient::new()
+        .with_default_response("hi")
+        .with_usage(TokenUsage::new("mock-model", 1000, 200));
+    let client = WebhookClient::new(inner, format!("{}/usage", server.url()))
+        .service("billing")
+        .pricing(1.0, 10.0);
+
+    assert_eq!(client.generate("a").await.unwrap(), "hi");
+    assert_eq!(client.generate("b").await.unwrap(), "hi");
+    client.flush().await;
+    hook.assert_async().await;
+}
+
+#[tokio::test]
+async fn errors_are_reported_and_returned() {
+    let mut server = mockito::Server::new_async().await;
+    let hook = server
+        .mock("POST", "/usage")
+        .match_body(mockito::Matcher::Regex(r#""error":"[^"]+""#.into()))
+        .with_status(200)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let inner = MockClient::new().with_error(rstructor::RStructorError::Timeout);
+    let client = WebhookClient::new(inner, format!("{}/usage", server.url()));
+    let _ = client.generate("a").await;
+    client.flush().await;
+    hook.assert_async().await;
+}
+
+#[tokio::test]
+async fn failed_deliveries_are_retried() {
+    let mut server = mockito::Server::new_async().await;
+    let rejected = server
+        .mock("POST", "/usage")
+        .with_status(503)
+        .expect(2)
+        .create_async()
+        .await;
+
+    let client = WebhookClient::new(
+        MockClient::new().with_default_response("hi"),
+        format!("{}/usage", server.url()),
+    )
+    .max_retries(1);
+    client.generate("a").await.unwrap();
+    client.flush().await;
+    rejected.assert_async().await;
+}
+
+#[tokio::test]
+async fn without_a_url_the_wrapper_is_a_pass_through() {
+    // SAFETY: no other test in this binary reads this variable.
+    unsafe { std::env::remove_var("RSTRUCTOR_USAGE_WEBHOOK_URL") };
+    let client = WebhookClient::from_env_url(MockClient::new().with_default_response("hi"));
+    assert_eq!(client.generate("a").await.unwrap(), "hi");
+    client.flush().await;
+    assert_eq!(client.inner().request_count(), 1);
+}